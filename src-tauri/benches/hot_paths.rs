@@ -0,0 +1,135 @@
+use std::{
+    hint::black_box,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use tauri_app_lib::bench_support::{
+    detect_silence_hallucination, normalize_transcript_text, pcm16_to_wav_bytes, AudioInputChunk,
+    AudioInputChunkCallback, DictationProgress, InputFrameBench, RedactionAction, RedactionEngine,
+    RedactionEntity, RedactionRule, SpeechActivity, WordCountStrategy,
+};
+
+const SAMPLE_RATE_HZ: u32 = 48_000;
+// CoreAudio delivers 512-frame buffers by default.
+const CALLBACK_FRAMES: usize = 512;
+
+const TRANSCRIPT: &str =
+    "  So the plan for   tomorrow is to send the draft to jane.doe@example.com\n\n\
+    before noon, then  follow up with the finance team about card 4111 1111 1111 1111 \
+    and the invoice.   Thanks for watching   ";
+
+fn sine_frames(frames: usize, channels: usize) -> Vec<f32> {
+    (0..frames)
+        .flat_map(|frame| {
+            let sample =
+                (frame as f32 * 440.0 * std::f32::consts::TAU / SAMPLE_RATE_HZ as f32).sin() * 0.5;
+            std::iter::repeat_n(sample, channels)
+        })
+        .collect()
+}
+
+fn bench_process_input_frames(c: &mut Criterion) {
+    let mut group = c.benchmark_group("process_input_frames");
+    group.throughput(Throughput::Elements(CALLBACK_FRAMES as u64));
+    let stereo = sine_frames(CALLBACK_FRAMES, 2);
+    let on_chunk: AudioInputChunkCallback = Arc::new(|chunk: AudioInputChunk| {
+        black_box(chunk);
+    });
+
+    let mut plain = InputFrameBench::new(2, SAMPLE_RATE_HZ, false);
+    group.bench_function("stereo_downmix", |b| {
+        b.iter(|| plain.process(black_box(&stereo), None))
+    });
+    let mut streaming = InputFrameBench::new(2, SAMPLE_RATE_HZ, false);
+    group.bench_function("stereo_downmix_with_chunks", |b| {
+        b.iter(|| streaming.process(black_box(&stereo), Some(&on_chunk)))
+    });
+    let mut suppressed = InputFrameBench::new(2, SAMPLE_RATE_HZ, true);
+    group.bench_function("stereo_noise_suppression", |b| {
+        b.iter(|| suppressed.process(black_box(&stereo), None))
+    });
+    group.finish();
+}
+
+fn bench_wav_encoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wav_encoding");
+    let samples: Vec<i16> = sine_frames(SAMPLE_RATE_HZ as usize * 60, 1)
+        .into_iter()
+        .map(|sample| (sample * i16::MAX as f32) as i16)
+        .collect();
+    group.throughput(Throughput::Bytes((samples.len() * 2) as u64));
+    group.bench_function("one_minute_mono", |b| {
+        b.iter(|| pcm16_to_wav_bytes(black_box(&samples), SAMPLE_RATE_HZ))
+    });
+    group.finish();
+}
+
+fn bench_delta_coalescing(c: &mut Criterion) {
+    let deltas: Vec<String> = TRANSCRIPT
+        .split_inclusive(' ')
+        .cycle()
+        .take(500)
+        .map(str::to_string)
+        .collect();
+    c.bench_function("delta_coalescing/500_deltas", |b| {
+        b.iter_batched(
+            || {
+                let started_at = Instant::now();
+                (
+                    DictationProgress::new(started_at, WordCountStrategy::Segmented),
+                    started_at,
+                )
+            },
+            |(mut progress, started_at)| {
+                for (index, delta) in deltas.iter().enumerate() {
+                    let now = started_at + Duration::from_millis(index as u64 * 40);
+                    black_box(progress.push_delta(delta, now));
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_post_processing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("post_processing");
+    let rule = |name: &str, entity| RedactionRule {
+        name: name.to_string(),
+        entity,
+        pattern: None,
+        action: RedactionAction::Mask,
+        enabled: true,
+    };
+    let redaction = RedactionEngine::new(&[
+        rule("emails", RedactionEntity::Email),
+        rule("cards", RedactionEntity::CreditCard),
+        rule("ssns", RedactionEntity::Ssn),
+    ])
+    .expect("benchmark rules should compile");
+    let activity = SpeechActivity {
+        speech_ms: 9_000,
+        total_ms: 10_000,
+    };
+
+    group.bench_function("normalize_whitespace", |b| {
+        b.iter(|| normalize_transcript_text(black_box(TRANSCRIPT)))
+    });
+    group.bench_function("redaction", |b| {
+        b.iter(|| redaction.apply(black_box(TRANSCRIPT)))
+    });
+    group.bench_function("silence_hallucination_check", |b| {
+        b.iter(|| detect_silence_hallucination(black_box(TRANSCRIPT), activity))
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_process_input_frames,
+    bench_wav_encoding,
+    bench_delta_coalescing,
+    bench_post_processing
+);
+criterion_main!(benches);
@@ -6,12 +6,21 @@ use std::{
     sync::{Arc, Mutex},
     time::{SystemTime, UNIX_EPOCH},
 };
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-use crate::settings_store::DEFAULT_TRANSCRIPTION_PROVIDER;
+use crate::app_error::{AppError, ErrorCode};
+use crate::keychain;
+use crate::settings_store::{DEFAULT_TRANSCRIPTION_PROVIDER, GEMINI_TRANSCRIPTION_PROVIDER};
 
 const API_KEY_STORE_NAMESPACE: &str = "voice.transcription.api-keys";
 const API_KEYS_FILE_NAME: &str = "api_keys.json";
+pub const ANTHROPIC_PROVIDER: &str = "anthropic";
+// Each provider's key is stored under its own account name.
+const SUPPORTED_PROVIDERS: &[&str] = &[
+    DEFAULT_TRANSCRIPTION_PROVIDER,
+    GEMINI_TRANSCRIPTION_PROVIDER,
+    ANTHROPIC_PROVIDER,
+];
 
 #[derive(Debug, Clone)]
 pub struct ApiKeyStore {
@@ -24,7 +33,7 @@ impl ApiKeyStore {
         let file_path = app_data_dir.join(API_KEYS_FILE_NAME);
         debug!(path = %file_path.display(), "api key store initialized");
         Self {
-            backend: Arc::new(FileBackend::new(file_path)),
+            backend: select_backend(file_path),
             cache: Arc::new(Mutex::new(HashMap::new())),
         }
     }
@@ -37,7 +46,7 @@ impl ApiKeyStore {
         }
     }
 
-    pub fn get_api_key(&self, provider: &str) -> Result<Option<String>, String> {
+    pub fn get_api_key(&self, provider: &str) -> Result<Option<String>, AppError> {
         let account = normalize_provider(provider)?;
         if let Some(cached) = self.get_cached_api_key(account.as_str())? {
             debug!(provider = %account, "api key served from in-memory cache");
@@ -52,11 +61,11 @@ impl ApiKeyStore {
         Ok(key)
     }
 
-    pub fn has_api_key(&self, provider: &str) -> Result<bool, String> {
+    pub fn has_api_key(&self, provider: &str) -> Result<bool, AppError> {
         Ok(self.get_api_key(provider)?.is_some())
     }
 
-    pub fn set_api_key(&self, provider: &str, key: &str) -> Result<(), String> {
+    pub fn set_api_key(&self, provider: &str, key: &str) -> Result<(), AppError> {
         let account = normalize_provider(provider)?;
         let normalized_key = normalize_api_key(key)?;
         info!(provider = %account, "writing api key to store");
@@ -65,15 +74,15 @@ impl ApiKeyStore {
             account.as_str(),
             normalized_key.as_str(),
         )?;
-        self.set_cached_api_key(account.as_str(), Some(normalized_key))
+        Ok(self.set_cached_api_key(account.as_str(), Some(normalized_key))?)
     }
 
-    pub fn delete_api_key(&self, provider: &str) -> Result<(), String> {
+    pub fn delete_api_key(&self, provider: &str) -> Result<(), AppError> {
         let account = normalize_provider(provider)?;
         info!(provider = %account, "deleting api key from store");
         self.backend
             .delete(API_KEY_STORE_NAMESPACE, account.as_str())?;
-        self.clear_cached_api_key(account.as_str())
+        Ok(self.clear_cached_api_key(account.as_str())?)
     }
 
     fn get_cached_api_key(&self, provider: &str) -> Result<Option<Option<String>>, String> {
@@ -109,6 +118,72 @@ trait ApiKeyBackend: Send + Sync + std::fmt::Debug {
     fn delete(&self, service: &str, account: &str) -> Result<(), String>;
 }
 
+// Keys live in the keychain where there is one; an existing plaintext file is
+// moved over on first run. Tests stay on the file so they never touch the
+// user's keychain.
+fn select_backend(file_path: PathBuf) -> Arc<dyn ApiKeyBackend> {
+    let file_backend = FileBackend::new(file_path);
+    if cfg!(test) || !keychain::is_available() {
+        return Arc::new(file_backend);
+    }
+
+    let keychain_backend = KeychainBackend;
+    match migrate_plaintext_keys(&file_backend, &keychain_backend) {
+        Ok(0) => {}
+        Ok(migrated) => info!(migrated, "moved plaintext api keys into the keychain"),
+        Err(error) => {
+            warn!(%error, "failed to migrate api keys into the keychain; keeping file storage");
+            return Arc::new(file_backend);
+        }
+    }
+    Arc::new(keychain_backend)
+}
+
+// The file is only removed once every key has been copied.
+fn migrate_plaintext_keys(
+    file_backend: &FileBackend,
+    target: &dyn ApiKeyBackend,
+) -> Result<usize, String> {
+    if !file_backend.file_path.exists() {
+        return Ok(0);
+    }
+
+    let keys = file_backend.read_keys()?;
+    for (account, key) in &keys {
+        if let Some(key) = normalize_optional_string(Some(key.clone())) {
+            target.set(API_KEY_STORE_NAMESPACE, account, &key)?;
+        }
+    }
+    fs::remove_file(&file_backend.file_path).map_err(|error| {
+        format!(
+            "Failed to remove migrated API key file `{}`: {error}",
+            file_backend.file_path.display()
+        )
+    })?;
+    Ok(keys.len())
+}
+
+#[derive(Debug)]
+struct KeychainBackend;
+
+impl ApiKeyBackend for KeychainBackend {
+    fn get(&self, service: &str, account: &str) -> Result<Option<String>, String> {
+        let key = keychain::get(service, account)?
+            .map(String::from_utf8)
+            .transpose()
+            .map_err(|_| format!("Keychain item `{service}/{account}` is not valid UTF-8"))?;
+        Ok(normalize_optional_string(key))
+    }
+
+    fn set(&self, service: &str, account: &str, key: &str) -> Result<(), String> {
+        keychain::set(service, account, key.as_bytes())
+    }
+
+    fn delete(&self, service: &str, account: &str) -> Result<(), String> {
+        keychain::delete(service, account)
+    }
+}
+
 #[derive(Debug)]
 struct FileBackend {
     file_path: PathBuf,
@@ -258,15 +333,22 @@ fn temp_file_path_for(file_path: &Path) -> PathBuf {
     file_path.with_file_name(format!(".{file_name}.{pid}.{timestamp}.tmp"))
 }
 
-fn normalize_provider(provider: &str) -> Result<String, String> {
+fn normalize_provider(provider: &str) -> Result<String, AppError> {
     let trimmed = provider.trim().to_lowercase();
     if trimmed.is_empty() {
-        return Err("`provider` cannot be empty".to_string());
+        return Err(AppError::new(
+            ErrorCode::InvalidInput,
+            "`provider` cannot be empty",
+        ));
     }
 
     if !is_supported_provider(trimmed.as_str()) {
-        return Err(format!(
-            "Unsupported provider `{trimmed}`. Expected `{DEFAULT_TRANSCRIPTION_PROVIDER}`"
+        return Err(AppError::new(
+            ErrorCode::InvalidInput,
+            format!(
+                "Unsupported provider `{trimmed}`. Expected one of: {}",
+                SUPPORTED_PROVIDERS.join(", ")
+            ),
         ));
     }
 
@@ -274,7 +356,7 @@ fn normalize_provider(provider: &str) -> Result<String, String> {
 }
 
 fn is_supported_provider(provider: &str) -> bool {
-    if provider == DEFAULT_TRANSCRIPTION_PROVIDER {
+    if SUPPORTED_PROVIDERS.contains(&provider) {
         return true;
     }
 
@@ -288,10 +370,13 @@ fn is_supported_provider(provider: &str) -> bool {
     false
 }
 
-fn normalize_api_key(key: &str) -> Result<String, String> {
+fn normalize_api_key(key: &str) -> Result<String, AppError> {
     let trimmed = key.trim();
     if trimmed.is_empty() {
-        return Err("`key` cannot be empty".to_string());
+        return Err(AppError::new(
+            ErrorCode::InvalidInput,
+            "`key` cannot be empty",
+        ));
     }
 
     Ok(trimmed.to_string())
@@ -483,12 +568,37 @@ mod tests {
     fn rejects_unsupported_provider() {
         let store = ApiKeyStore::with_backend(Arc::new(InMemoryBackend::default()));
 
-        assert!(store.get_api_key("anthropic").is_err());
-        assert!(store.has_api_key("gemini").is_err());
+        assert!(store.get_api_key("deepgram").is_err());
+        assert!(store.has_api_key("google-speech").is_err());
         assert!(store.set_api_key("azure-openai", "sk-test").is_err());
         assert!(store.delete_api_key("custom").is_err());
     }
 
+    #[test]
+    fn keys_are_scoped_per_provider() {
+        let store = ApiKeyStore::with_backend(Arc::new(InMemoryBackend::default()));
+
+        store
+            .set_api_key("gemini", "gm-key")
+            .expect("gemini key should persist");
+        store
+            .set_api_key("anthropic", "sk-ant-key")
+            .expect("anthropic key should persist");
+
+        assert!(!store.has_api_key("openai").expect("has should succeed"));
+        assert_eq!(
+            store
+                .get_api_key("gemini")
+                .expect("get should succeed")
+                .as_deref(),
+            Some("gm-key")
+        );
+        store
+            .delete_api_key("gemini")
+            .expect("delete should succeed");
+        assert!(store.has_api_key("anthropic").expect("has should succeed"));
+    }
+
     #[test]
     fn caches_backend_get_results_per_provider() {
         let backend = Arc::new(CountingBackend::default());
@@ -639,4 +749,33 @@ mod tests {
 
         cleanup_api_key_file(&file_path);
     }
+
+    #[test]
+    fn migrates_plaintext_keys_and_removes_the_file() {
+        let file_path = unique_api_key_file_path("migrate");
+        let file_backend = FileBackend::new(file_path.clone());
+        file_backend
+            .set(API_KEY_STORE_NAMESPACE, "openai", "sk-plaintext")
+            .expect("seed should succeed");
+        let target = InMemoryBackend::default();
+
+        let migrated =
+            migrate_plaintext_keys(&file_backend, &target).expect("migration should succeed");
+
+        assert_eq!(migrated, 1);
+        assert_eq!(
+            target
+                .get(API_KEY_STORE_NAMESPACE, "openai")
+                .expect("get should succeed")
+                .as_deref(),
+            Some("sk-plaintext")
+        );
+        assert!(!file_path.exists(), "expected plaintext file to be removed");
+        assert_eq!(
+            migrate_plaintext_keys(&file_backend, &target).expect("rerun should succeed"),
+            0
+        );
+
+        cleanup_api_key_file(&file_path);
+    }
 }
@@ -0,0 +1,8270 @@
+pub mod schema_export;
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
+    tray::{MouseButton, MouseButtonState, TrayIconEvent},
+    AppHandle, Emitter, EventTarget, Listener, LogicalPosition, Manager, Monitor, PhysicalPosition,
+    PhysicalSize, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+};
+use tauri_plugin_autostart::{MacosLauncher, ManagerExt as AutostartManagerExt};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+use crate::api_key_store::ApiKeyStore;
+use crate::app_data_sync::{AppDataConflictStrategy, AppDataImportReport};
+use crate::app_error::{AppError, ErrorCode};
+use crate::audio_capture_service::{
+    AudioCaptureService, AudioInputChunk, AudioInputChunkCallback, AudioInputStreamErrorEvent,
+    AudioSpectrumEvent, CaptureSource, CaptureSourceInfo, InputProcessingConfig, MicrophoneInfo,
+    RecordedAudio, RecordedWav, RecoveredRecording, AUDIO_INPUT_STREAM_ERROR_EVENT,
+    AUDIO_LEVEL_EVENT, AUDIO_SPECTRUM_EVENT,
+};
+use crate::audio_encoding::AudioEncoding;
+use crate::auth_store::{AuthMethod, AuthStore};
+use crate::automation::{AutomationCommand, AutomationHandler};
+use crate::browser_bridge::{BrowserBridge, BrowserBridgeConfig};
+use crate::command_guard::{check_revision, CommandGuard, CommandResource};
+use crate::compatibility_probe::{CompatibilityProbe, CompatibilityReport};
+use crate::control_server::{ControlHandler, ControlRequest, ControlResponse, ControlServer};
+use crate::crash_reporter::CrashReport;
+use crate::deep_link::{DeepLinkAction, DeepLinkNavigation};
+use crate::diagnostics::{DiagnosticCheck, DiagnosticCheckId, DiagnosticsReport};
+use crate::focused_app_watcher::{FocusedApp, FocusedAppWatcher};
+use crate::history_store::{
+    encryption::HistoryCipher,
+    import::{HistoryImportFormat, HistoryImportProgressEvent, HistoryImportReport},
+    render_transcript_export,
+    reprocess::{self, HistoryReprocessProgressEvent, HistoryReprocessReport},
+    subtitles::{render_subtitles, SubtitleFormat},
+    vocabulary::{self, VocabularyRange, VocabularyReport},
+    HistoryEntry, HistorySource, HistoryStore,
+};
+use crate::hotkey_service::{
+    ActiveHotkeyBinding, HotkeyConfig, HotkeyService, HotkeyStateSnapshot, HotkeyTrigger,
+    RecordingMode, RecordingTransition, StopProcessingDecision,
+};
+use crate::input_triggers::{InputTriggerDevice, InputTriggerService, TriggerHandler};
+use crate::insertion_audit::{InsertionAuditEntry, InsertionAuditLog};
+use crate::instance_conflicts::{ConflictingInstanceReport, EVENT_CONFLICTING_INSTANCE};
+use crate::logging::{LogEntry, LogLevel, LoggingState};
+use crate::overlay_keys::{OverlayKeyAction, OverlayKeyService};
+use crate::pending_transcriptions::{
+    is_reachable, reachability_url, PendingTranscription, PendingTranscriptionCompletedEvent,
+    PendingTranscriptionStore, MAX_AUTOMATIC_ATTEMPTS, PENDING_RETRY_INTERVAL,
+};
+use crate::permission_service::{
+    PermissionService, PermissionSnapshot, PermissionState, PermissionType,
+};
+use crate::private_shortcut::PrivateShortcutService;
+use crate::recent_insertions::RecentInsertions;
+use crate::redaction::{RedactionEngine, RedactionRule};
+use crate::release_notes::ReleaseNotesReport;
+use crate::retro_shortcut::RetroShortcutService;
+use crate::selftest::{SelfTestReport, SELFTEST_LEAD_IN_MS, SELFTEST_PHRASE, SELFTEST_TAIL_MS};
+use crate::session_trace::{
+    scrub_transcript_text, trace_timeline, SessionTraceEvent, SessionTraceRecorder, StageFinished,
+    STAGE_FINISHED_TRACE_EVENT,
+};
+use crate::settings_store::{
+    OverlayOffset, SettingsSnapshot, SettingsStore, VoiceSettings, VoiceSettingsUpdate,
+    WatchFolder, DEFAULT_TRANSCRIPTION_PROVIDER, GEMINI_TRANSCRIPTION_PROVIDER,
+    HALLUCINATION_FILTER_DISCARD, HALLUCINATION_FILTER_OFF, OVERLAY_PLACEMENT_BOTTOM_CENTER,
+    OVERLAY_PLACEMENT_FIXED_DISPLAY, OVERLAY_PLACEMENT_NEAR_CARET, OVERLAY_PLACEMENT_NEAR_CURSOR,
+    RECORDING_MODE_HOLD_TO_TALK, RECORDING_MODE_TOGGLE, TRANSCRIPTION_STYLE_CASUAL,
+    TRANSCRIPTION_STYLE_CLEAN, TRANSCRIPTION_STYLE_CUSTOM, TRANSCRIPTION_STYLE_VERBATIM,
+    TRANSLATION_INSERT_TRANSLATION,
+};
+use crate::silence_trim::SpeechActivity;
+use crate::snippets_store::{Snippet, SnippetsStore};
+use crate::sound_feedback_service::{SoundCue, SoundFeedbackService};
+use crate::stats_store::{
+    AppUsage, DailyUsage, StatsStore, TranscriptionRacePath, UsageStatsReport,
+};
+use crate::status_notifier::{AppStatus, StatusNotifier};
+use crate::telemetry::{TelemetryEvent, TelemetryEventKind, TelemetryQueue};
+use crate::text_formatting::format_transcript;
+use crate::text_insertion_service::{
+    focused_caret_position, AutoInsertMode, InsertionDecision, InsertionOptions, InsertionSpacing,
+    InsertionStrategy, TextInsertionService,
+};
+use crate::transcription::chatgpt::{ChatGptTranscriptionConfig, ChatGptTranscriptionProvider};
+use crate::transcription::chunked::ChunkedTranscriptionConfig;
+use crate::transcription::decoding::DecodingOptions;
+use crate::transcription::endpoint::{ProviderConfig, SharedProviderConfig};
+use crate::transcription::gemini::{GeminiTranscriptionConfig, GeminiTranscriptionProvider};
+use crate::transcription::language::{
+    language_hint, uncertain_alternatives, LanguageUncertainEvent,
+};
+use crate::transcription::models::{ApiKeyValidation, TranscriptionModel};
+use crate::transcription::openai::{OpenAiTranscriptionConfig, OpenAiTranscriptionProvider};
+use crate::transcription::prompt::{
+    resolve_transcription_prompt, transcription_context_hint, transcription_profile,
+    transcription_prompt,
+};
+use crate::transcription::realtime::{
+    OpenAiRealtimeTranscriptionClient, OpenAiRealtimeTranscriptionConfig, RealtimeAppendOutcome,
+    RealtimeTranscriptionSession,
+};
+use crate::transcription::retry::{TranscriptionRetryCallback, TranscriptionRetryEvent};
+use crate::transcription::translation::{
+    self, OpenAiTranslationClient, OpenAiTranslationConfig, DEFAULT_TRANSLATION_TARGET_LANGUAGE,
+};
+use crate::transcription::{
+    TranscriptionOptions, TranscriptionOrchestrator, TranscriptionProvider, TranscriptionSegment,
+    TranscriptionWord,
+};
+use crate::tray_menu::{
+    QuickSetting, TrayMenuState, MENU_CANCEL_RECORDING, MENU_MICROPHONES, MENU_PROFILES,
+    MENU_RECORDING_MODES, MENU_RECORDING_TIMER, TRAY_ID,
+};
+use crate::voice_pipeline::error_router::{route_error, ErrorAction};
+use crate::voice_pipeline::hallucination::{detect_silence_hallucination, HallucinationReason};
+use crate::voice_pipeline::plausibility::{
+    check_transcript_plausibility, ImplausibleTranscriptReason,
+};
+use crate::voice_pipeline::progress::DictationProgress;
+use crate::voice_pipeline::queue::{QueueTicket, SessionQueue};
+use crate::voice_pipeline::rest::{self, count_words};
+use crate::voice_pipeline::{
+    PipelineError, PipelineTranscript, PipelineTranslation, VoicePipeline, VoicePipelineDelegate,
+};
+use crate::watch_folder_service::{WatchFolderHandler, WatchFolderService};
+use crate::word_count::WordCountStrategy;
+use crate::{
+    app_data_sync, audio_capture_service, audio_decoding, automation, control_server,
+    crash_reporter, deep_link, diagnostics, history_store, hotkey_service, http_client,
+    input_triggers, instance_conflicts, logging, oauth, proxy, release_notes, selftest,
+    silence_trim, sound_feedback_service, support_bundle, telemetry, transcription, voice_pipeline,
+    watch_folder_service,
+};
+
+const EVENT_STATUS_CHANGED: &str = "voice://status-changed";
+const EVENT_TRANSCRIPT_READY: &str = "voice://transcript-ready";
+const EVENT_TRANSCRIPTION_DELTA: &str = "voice://transcription-delta";
+const EVENT_TRANSCRIPTION_RETRY: &str = "voice://transcription-retry";
+const EVENT_DICTATION_PROGRESS: &str = "voice://dictation-progress";
+const EVENT_PIPELINE_ERROR: &str = "voice://pipeline-error";
+const EVENT_RECOVERED_RECORDINGS_FOUND: &str = "voice://recovered-recordings-found";
+const EVENT_OVERLAY_AUDIO_LEVEL: &str = "voice://overlay-audio-level";
+const EVENT_OVERLAY_AUDIO_SPECTRUM: &str = "voice://overlay-audio-spectrum";
+const EVENT_INSERT_CONFIRMATION_REQUESTED: &str = "voice://insert-confirmation-requested";
+const EVENT_TRANSCRIPT_FLAGGED: &str = "voice://transcript-flagged";
+const EVENT_TRANSCRIPT_SUPPRESSED: &str = "voice://transcript-suppressed";
+const EVENT_LANGUAGE_UNCERTAIN: &str = "voice://language-uncertain";
+const EVENT_SETTINGS_CHANGED: &str = "voice://settings-changed";
+const EVENT_REDACTION_WARNING: &str = "voice://redaction-warning";
+const EVENT_HISTORY_IMPORT_PROGRESS: &str = "voice://history-import-progress";
+const EVENT_HISTORY_REPROCESS_PROGRESS: &str = "voice://history-reprocess-progress";
+const EVENT_INSERTION_COPIED_INSTEAD: &str = "voice://insertion-copied-instead";
+const EVENT_DEEP_LINK_NAVIGATE: &str = "voice://deep-link-navigate";
+const EVENT_FILE_TRANSCRIPTION: &str = "voice://file-transcription";
+const EVENT_RECORDING_LIMIT_REACHED: &str = "voice://recording-limit-reached";
+const EVENT_PENDING_TRANSCRIPTIONS_CHANGED: &str = "voice://pending-transcriptions-changed";
+const EVENT_PENDING_TRANSCRIPTION_COMPLETED: &str = "voice://pending-transcription-completed";
+const AUDIO_STREAM_ERROR_RESET_DELAY_MS: u64 = 1_500;
+// Dictations that may be recording or transcribing at the same time.
+const MAX_QUEUED_SESSIONS: usize = 3;
+const DEFAULT_HISTORY_PAGE_SIZE: usize = 50;
+const DEFAULT_INSERTION_AUDIT_PAGE_SIZE: usize = 100;
+const SETTINGS_FILE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const DEFAULT_RETRO_TRANSCRIBE_SECS: u32 = 30;
+const OVERLAY_WINDOW_LABEL: &str = "recording-overlay";
+// Keep these values aligned with src/Overlay.css so the overlay shadow remains inside the window.
+const OVERLAY_PILL_WIDTH: f64 = 300.0;
+const OVERLAY_PILL_HEIGHT: f64 = 56.0;
+const OVERLAY_SHADOW_SAFE_TOP: f64 = 24.0;
+const OVERLAY_SHADOW_SAFE_SIDE: f64 = 36.0;
+const OVERLAY_SHADOW_SAFE_BOTTOM: f64 = 52.0;
+const OVERLAY_WINDOW_WIDTH: f64 = OVERLAY_PILL_WIDTH + (OVERLAY_SHADOW_SAFE_SIDE * 2.0);
+const OVERLAY_WINDOW_HEIGHT: f64 =
+    OVERLAY_PILL_HEIGHT + OVERLAY_SHADOW_SAFE_TOP + OVERLAY_SHADOW_SAFE_BOTTOM;
+const OVERLAY_WINDOW_TOP_MARGIN: f64 = 12.0;
+const OVERLAY_ANCHOR_GAP: f64 = 8.0;
+const INSERT_REVIEW_WINDOW_LABEL: &str = "insert-review";
+const INSERT_REVIEW_WINDOW_WIDTH: f64 = 460.0;
+const INSERT_REVIEW_WINDOW_HEIGHT: f64 = 240.0;
+// Time for the app the user dictated into to become frontmost again once the
+// review window is dismissed, before keystrokes are sent to it.
+const INSERT_REVIEW_FOCUS_RETURN_DELAY: Duration = Duration::from_millis(150);
+const LEGACY_APP_IDENTIFIER: &str = "com.sawyerhood.voice";
+
+fn is_usable_transcript(result: &Result<PipelineTranscript, AppError>) -> bool {
+    result
+        .as_ref()
+        .is_ok_and(|transcript| !transcript.text.trim().is_empty())
+}
+
+fn sanitize_recording_duration_secs(duration_secs: f64) -> f64 {
+    if duration_secs.is_finite() && duration_secs > 0.0 {
+        duration_secs
+    } else {
+        0.0
+    }
+}
+
+fn should_discard_recording(duration_ms: u64, min_duration_ms: u64) -> bool {
+    duration_ms < min_duration_ms
+}
+
+fn max_recording_duration(settings: &VoiceSettings) -> Option<Duration> {
+    (settings.max_recording_duration_mins > 0)
+        .then(|| Duration::from_secs(u64::from(settings.max_recording_duration_mins) * 60))
+}
+
+fn recording_mode_from_settings_value(value: &str) -> Result<RecordingMode, String> {
+    match value.trim().to_lowercase().as_str() {
+        RECORDING_MODE_HOLD_TO_TALK => Ok(RecordingMode::HoldToTalk),
+        RECORDING_MODE_TOGGLE => Ok(RecordingMode::Toggle),
+        normalized => Err(format!(
+            "Unsupported recording mode `{normalized}`. Expected `{RECORDING_MODE_HOLD_TO_TALK}` or `{RECORDING_MODE_TOGGLE}`"
+        )),
+    }
+}
+
+fn recording_mode_to_settings_value(mode: RecordingMode) -> &'static str {
+    match mode {
+        RecordingMode::HoldToTalk => RECORDING_MODE_HOLD_TO_TALK,
+        RecordingMode::Toggle => RECORDING_MODE_TOGGLE,
+    }
+}
+
+// Runs the profile's formatting passes, if it has any.
+fn format_for_profile(settings: &VoiceSettings, text: &str, language: Option<&str>) -> String {
+    match settings
+        .formatting
+        .get(transcription_profile(settings, language))
+    {
+        Some(options) => format_transcript(text, *options, language),
+        None => text.to_string(),
+    }
+}
+
+// Profiles are the transcription styles; verbatim has nothing to apply to a
+// finished transcript.
+fn transform_profile_instructions(profile: &str, custom_prompt: &str) -> Result<String, String> {
+    match profile.trim().to_lowercase().as_str() {
+        TRANSCRIPTION_STYLE_VERBATIM => {
+            Err("The verbatim profile leaves transcripts unchanged".to_string())
+        }
+        style @ (TRANSCRIPTION_STYLE_CLEAN
+        | TRANSCRIPTION_STYLE_CASUAL
+        | TRANSCRIPTION_STYLE_CUSTOM) => resolve_transcription_prompt(style, custom_prompt)
+            .ok_or_else(|| "The custom profile has no prompt yet".to_string()),
+        _ => Err(format!("Unknown transform profile: {profile}")),
+    }
+}
+
+fn resolve_hotkey_config_for_settings(
+    update: &VoiceSettingsUpdate,
+    fallback_hotkey: &HotkeyConfig,
+) -> Result<HotkeyConfig, String> {
+    let mode = match update.recording_mode.as_deref() {
+        Some(mode) => recording_mode_from_settings_value(mode)?,
+        None => fallback_hotkey.mode,
+    };
+
+    Ok(HotkeyConfig {
+        shortcut: update
+            .hotkey_shortcut
+            .clone()
+            .unwrap_or_else(|| fallback_hotkey.shortcut.clone()),
+        mode,
+        name: update
+            .hotkey_name
+            .clone()
+            .unwrap_or_else(|| fallback_hotkey.name.clone()),
+    })
+}
+
+fn apply_hotkey_from_settings_with_fallback<FApplyConfig, FApplyDefault>(
+    settings: &VoiceSettings,
+    mut apply_config: FApplyConfig,
+    mut apply_default: FApplyDefault,
+) -> Result<(), String>
+where
+    FApplyConfig: FnMut(HotkeyConfig) -> Result<(), String>,
+    FApplyDefault: FnMut() -> Result<(), String>,
+{
+    let config = match recording_mode_from_settings_value(settings.recording_mode.as_str()) {
+        Ok(mode) => HotkeyConfig {
+            shortcut: settings.hotkey_shortcut.clone(),
+            mode,
+            name: settings.hotkey_name.clone(),
+        },
+        Err(error) => {
+            warn!(
+                %error,
+                "invalid persisted recording mode; falling back to default hotkey configuration"
+            );
+            apply_default()?;
+            return Ok(());
+        }
+    };
+
+    if let Err(error) = apply_config(config) {
+        warn!(
+            %error,
+            "failed to apply persisted hotkey configuration; falling back to defaults"
+        );
+        apply_default()?;
+    }
+
+    Ok(())
+}
+
+fn apply_settings_transaction_with_hooks<
+    FApplyHotkey,
+    FApplyLaunchAtLogin,
+    FPersistSettings,
+    FRollbackLaunchAtLogin,
+    FRollbackHotkey,
+>(
+    update: VoiceSettingsUpdate,
+    previous_hotkey: HotkeyConfig,
+    requested_hotkey: HotkeyConfig,
+    previous_launch_at_login: bool,
+    requested_launch_at_login: bool,
+    mut apply_hotkey: FApplyHotkey,
+    mut apply_launch_at_login: FApplyLaunchAtLogin,
+    mut persist_settings: FPersistSettings,
+    mut rollback_launch_at_login: FRollbackLaunchAtLogin,
+    mut rollback_hotkey: FRollbackHotkey,
+) -> Result<VoiceSettings, AppError>
+where
+    FApplyHotkey: FnMut(HotkeyConfig) -> Result<HotkeyConfig, String>,
+    FApplyLaunchAtLogin: FnMut(bool) -> Result<(), String>,
+    FPersistSettings: FnMut(VoiceSettingsUpdate) -> Result<VoiceSettings, AppError>,
+    FRollbackLaunchAtLogin: FnMut(bool) -> Result<(), String>,
+    FRollbackHotkey: FnMut(HotkeyConfig) -> Result<HotkeyConfig, String>,
+{
+    let applied_hotkey = apply_hotkey(requested_hotkey)?;
+    if let Err(launch_error) = apply_launch_at_login(requested_launch_at_login) {
+        return match rollback_hotkey(previous_hotkey.clone()) {
+            Ok(_) => Err(AppError::from(format!(
+                "Failed to apply launch-at-login setting: {launch_error}"
+            ))),
+            Err(rollback_error) => Err(AppError::from(format!(
+                "Failed to apply launch-at-login setting: {launch_error}. Failed to roll back hotkey config: {rollback_error}"
+            ))),
+        };
+    }
+
+    let mut update_with_applied_hotkey = update;
+    update_with_applied_hotkey.hotkey_shortcut = Some(applied_hotkey.shortcut.clone());
+    update_with_applied_hotkey.recording_mode =
+        Some(recording_mode_to_settings_value(applied_hotkey.mode).to_string());
+    update_with_applied_hotkey.hotkey_name = Some(applied_hotkey.name.clone());
+    if update_with_applied_hotkey.launch_at_login.is_some() {
+        update_with_applied_hotkey.launch_at_login = Some(requested_launch_at_login);
+    }
+
+    match persist_settings(update_with_applied_hotkey) {
+        Ok(settings) => Ok(settings),
+        Err(persist_error) => {
+            let mut rollback_failures = Vec::new();
+
+            if let Err(rollback_error) = rollback_launch_at_login(previous_launch_at_login) {
+                rollback_failures.push(format!(
+                    "Failed to roll back launch-at-login state: {rollback_error}"
+                ));
+            }
+
+            if let Err(rollback_error) = rollback_hotkey(previous_hotkey) {
+                rollback_failures.push(format!(
+                    "Failed to roll back hotkey config: {rollback_error}"
+                ));
+            }
+
+            // The persist failure decides the code, so a stale revision
+            // still reads as a conflict.
+            if rollback_failures.is_empty() {
+                Err(AppError::new(
+                    persist_error.code,
+                    format!("Failed to persist settings: {persist_error}"),
+                ))
+            } else {
+                Err(AppError::new(
+                    persist_error.code,
+                    format!(
+                        "Failed to persist settings: {persist_error}. {}",
+                        rollback_failures.join(". ")
+                    ),
+                ))
+            }
+        }
+    }
+}
+
+fn load_startup_settings_with_fallback<FLoadSettings>(
+    mut load_settings: FLoadSettings,
+) -> VoiceSettings
+where
+    FLoadSettings: FnMut() -> Result<VoiceSettings, AppError>,
+{
+    match load_settings() {
+        Ok(settings) => {
+            info!("persisted settings loaded");
+            settings
+        }
+        Err(error) => {
+            warn!(%error, "failed to load persisted settings");
+            VoiceSettings::default()
+        }
+    }
+}
+
+fn copy_directory_contents(source_dir: &Path, destination_dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(destination_dir)?;
+
+    for entry in fs::read_dir(source_dir)? {
+        let entry = entry?;
+        let source_path = entry.path();
+        let destination_path = destination_dir.join(entry.file_name());
+        let entry_type = entry.file_type()?;
+
+        if entry_type.is_dir() {
+            copy_directory_contents(&source_path, &destination_path)?;
+        } else if entry_type.is_file() {
+            fs::copy(&source_path, &destination_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn migrate_legacy_app_data_dir(new_app_data_dir: &Path) {
+    let Some(parent_dir) = new_app_data_dir.parent() else {
+        warn!(
+            path = %new_app_data_dir.display(),
+            "app data directory is missing parent path; skipping legacy migration"
+        );
+        return;
+    };
+
+    let legacy_app_data_dir = parent_dir.join(LEGACY_APP_IDENTIFIER);
+    if !legacy_app_data_dir.is_dir() {
+        return;
+    }
+
+    if new_app_data_dir.exists() {
+        info!(
+            path = %new_app_data_dir.display(),
+            "new app data directory already exists; skipping legacy migration"
+        );
+        return;
+    }
+
+    match copy_directory_contents(&legacy_app_data_dir, new_app_data_dir) {
+        Ok(()) => info!(
+            from = %legacy_app_data_dir.display(),
+            to = %new_app_data_dir.display(),
+            "copied legacy app data directory contents"
+        ),
+        Err(error) => warn!(
+            %error,
+            from = %legacy_app_data_dir.display(),
+            to = %new_app_data_dir.display(),
+            "failed to migrate legacy app data directory contents"
+        ),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct TranscriptReadyEvent {
+    text: String,
+    confidence: Option<f32>,
+    // Scored so the UI can highlight what the provider was unsure of. Empty
+    // when `text` is a translation, which they do not line up with.
+    segments: Vec<TranscriptionSegment>,
+    words: Vec<TranscriptionWord>,
+}
+
+// A transcript held back by the `ask` auto-insert mode until the user picks
+// what to do with it.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct PendingInsert {
+    text: String,
+    origin_app: Option<FocusedApp>,
+}
+
+// A transcript whose length does not fit the recording, held back from
+// insertion until the user retries or dismisses it.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct FlaggedTranscript {
+    text: String,
+    reason: ImplausibleTranscriptReason,
+    word_count: u64,
+    recording_secs: f64,
+    provider: String,
+}
+
+#[derive(Debug, Clone)]
+struct FlaggedRecording {
+    reason: ImplausibleTranscriptReason,
+    recording_secs: f64,
+    wav: RecordedWav,
+}
+
+// Why the current session's transcript is kept out of the focused app.
+#[derive(Debug, Clone)]
+enum HeldTranscript {
+    Implausible(FlaggedRecording),
+    SilenceHallucination(SuspectedHallucination),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SuspectedHallucination {
+    activity: SpeechActivity,
+    reason: HallucinationReason,
+    // Set by the `discard` filter; otherwise the transcript waits for review.
+    discard: bool,
+}
+
+// A transcript of essentially silent audio, kept out of the focused app. When
+// it was not discarded it is waiting in the review window.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct TranscriptSuppressedEvent {
+    text: String,
+    reason: HallucinationReason,
+    discarded: bool,
+    speech_ms: u64,
+    recording_ms: u64,
+}
+
+// Names the `warn` redaction rules that matched; the text is never included.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct RedactionWarningEvent {
+    session_id: Option<u64>,
+    rules: Vec<String>,
+}
+
+// A recording ran into `max_recording_duration_mins` and was stopped and sent
+// for transcription without the user asking.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct RecordingLimitReachedEvent {
+    session_id: u64,
+    max_duration_mins: u32,
+}
+
+// The transcript could not be confirmed in the focused field and was left on
+// the clipboard instead, so the UI can prompt the user to paste it.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct InsertionCopiedInsteadEvent {
+    session_id: Option<u64>,
+    app_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+enum FileTranscriptionState {
+    Started,
+    Completed,
+    Failed,
+}
+
+// Progress of a file transcription, whether it came from a command, the
+// control socket or a file dropped on the main window.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct FileTranscriptionEvent {
+    path: String,
+    state: FileTranscriptionState,
+    entry_id: Option<String>,
+    text: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum PendingInsertAction {
+    Insert,
+    Copy,
+    Discard,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct PipelineErrorEvent {
+    stage: String,
+    code: ErrorCode,
+    message: String,
+    recoverable: bool,
+    hint: Option<String>,
+    action: Option<ErrorAction>,
+    help_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct ChatGptAuthStatus {
+    account_id: String,
+    expires_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct StatusDetails {
+    status: AppStatus,
+    active_binding: Option<ActiveHotkeyBinding>,
+}
+
+#[derive(Debug)]
+struct AppServices {
+    audio_capture_service: AudioCaptureService,
+    transcription_orchestrator: TranscriptionOrchestrator,
+    gemini_transcription_orchestrator: TranscriptionOrchestrator,
+    chatgpt_transcription_provider: ChatGptTranscriptionProvider,
+    realtime_transcription_client: OpenAiRealtimeTranscriptionClient,
+    translation_client: OpenAiTranslationClient,
+    text_insertion_service: TextInsertionService,
+    insertion_audit: InsertionAuditLog,
+    snippets_store: SnippetsStore,
+    sound_feedback_service: SoundFeedbackService,
+    browser_bridge: BrowserBridge,
+    settings_store: SettingsStore,
+    api_key_store: ApiKeyStore,
+    auth_store: AuthStore,
+    permission_service: PermissionService,
+    provider_config: SharedProviderConfig,
+    gemini_provider_config: SharedProviderConfig,
+    recent_insertions: RecentInsertions,
+    session_trace: SessionTraceRecorder,
+}
+
+impl AppServices {
+    fn new(app_data_dir: PathBuf) -> Self {
+        let api_key_store = ApiKeyStore::new(app_data_dir.clone());
+        let auth_store = AuthStore::new(app_data_dir.clone());
+        let provider_config = SharedProviderConfig::default();
+        let mut openai_config = OpenAiTranscriptionConfig::from_env();
+        openai_config.api_key_store_app_data_dir = Some(app_data_dir.clone());
+        openai_config.overrides = provider_config.clone();
+        let provider = OpenAiTranscriptionProvider::new(openai_config.clone());
+        let transcription_orchestrator = TranscriptionOrchestrator::new(Arc::new(provider));
+        let gemini_provider_config = SharedProviderConfig::default();
+        let mut gemini_config = GeminiTranscriptionConfig::from_env();
+        gemini_config.api_key_store_app_data_dir = Some(app_data_dir.clone());
+        gemini_config.overrides = gemini_provider_config.clone();
+        let gemini_transcription_orchestrator = TranscriptionOrchestrator::new(Arc::new(
+            GeminiTranscriptionProvider::new(gemini_config),
+        ));
+        let chatgpt_transcription_provider = ChatGptTranscriptionProvider::new(
+            ChatGptTranscriptionConfig::from_env(),
+            auth_store.clone(),
+        );
+        let mut realtime_config = OpenAiRealtimeTranscriptionConfig::from_env();
+        if realtime_config.transcription_model.trim().is_empty() {
+            realtime_config.transcription_model = openai_config.model.clone();
+        }
+        realtime_config.api_key = openai_config.api_key.clone();
+        realtime_config.api_key_store_app_data_dir = Some(app_data_dir.clone());
+        realtime_config.overrides = provider_config.clone();
+        let realtime_transcription_client = OpenAiRealtimeTranscriptionClient::new(realtime_config);
+        let mut translation_config = OpenAiTranslationConfig::from_env();
+        translation_config.api_key = openai_config.api_key.clone();
+        translation_config.api_key_store_app_data_dir = Some(app_data_dir.clone());
+        let translation_client = OpenAiTranslationClient::new(translation_config);
+        info!("initializing app services");
+
+        Self {
+            audio_capture_service: AudioCaptureService::new()
+                .with_recovery_dir(audio_capture_service::recovery_dir(&app_data_dir)),
+            transcription_orchestrator,
+            gemini_transcription_orchestrator,
+            chatgpt_transcription_provider,
+            realtime_transcription_client,
+            translation_client,
+            text_insertion_service: TextInsertionService::new(),
+            insertion_audit: InsertionAuditLog::new_in_dir(&app_data_dir),
+            snippets_store: SnippetsStore::new_in_dir(&app_data_dir),
+            sound_feedback_service: SoundFeedbackService::new()
+                .with_custom_sounds_dir(sound_feedback_service::sounds_dir(&app_data_dir)),
+            browser_bridge: BrowserBridge::new(BrowserBridgeConfig::from_env())
+                .with_pairing_token_dir(&app_data_dir),
+            settings_store: SettingsStore::new(),
+            api_key_store,
+            auth_store,
+            permission_service: PermissionService::new(),
+            provider_config,
+            gemini_provider_config,
+            recent_insertions: RecentInsertions::new(),
+            session_trace: SessionTraceRecorder::new_in_dir(&app_data_dir),
+        }
+    }
+
+    fn current_auth_method(&self) -> Result<AuthMethod, AppError> {
+        self.auth_store.effective_auth_method(&self.api_key_store)
+    }
+
+    // API key transcription goes to whichever provider the settings select.
+    fn api_key_orchestrator(&self, settings: &VoiceSettings) -> TranscriptionOrchestrator {
+        if settings.transcription_provider == GEMINI_TRANSCRIPTION_PROVIDER {
+            self.gemini_transcription_orchestrator.clone()
+        } else {
+            self.transcription_orchestrator.clone()
+        }
+    }
+
+    // The orchestrator whose provider a stored key belongs to, for checks
+    // that are about a specific key rather than the selected provider.
+    fn provider_orchestrator(&self, provider: &str) -> Result<TranscriptionOrchestrator, AppError> {
+        match provider.trim().to_lowercase().as_str() {
+            DEFAULT_TRANSCRIPTION_PROVIDER => Ok(self.transcription_orchestrator.clone()),
+            GEMINI_TRANSCRIPTION_PROVIDER => Ok(self.gemini_transcription_orchestrator.clone()),
+            other => Err(AppError::new(
+                ErrorCode::InvalidInput,
+                format!("Unsupported transcription provider `{other}`"),
+            )),
+        }
+    }
+
+    // Settings the services read on every use rather than at construction.
+    fn apply_live_settings(&self, settings: &VoiceSettings) {
+        self.browser_bridge
+            .set_enabled(settings.browser_bridge_enabled);
+        self.provider_config
+            .set(settings.provider_config_for(DEFAULT_TRANSCRIPTION_PROVIDER));
+        self.gemini_provider_config
+            .set(settings.provider_config_for(GEMINI_TRANSCRIPTION_PROVIDER));
+        if !settings.recent_text_context {
+            self.recent_insertions.clear();
+        }
+        self.session_trace
+            .set_enabled(settings.session_trace_enabled);
+        proxy::set_configured(settings.proxy.clone());
+        http_client::set_connect_timeout_secs(u64::from(
+            settings.transcription_connect_timeout_secs,
+        ));
+        if let Err(error) = self.audio_capture_service.configure_retro_buffer(
+            settings.microphone_id.as_deref(),
+            InputProcessingConfig::from_settings(settings),
+            settings.retro_buffer_secs,
+        ) {
+            warn!(%error, "failed to configure retro buffer");
+        }
+        if let Err(error) = self.audio_capture_service.configure_warm_microphone(
+            settings.microphone_id.as_deref(),
+            InputProcessingConfig::from_settings(settings),
+            settings.microphone_warm_up,
+        ) {
+            warn!(%error, "failed to configure warm microphone");
+        }
+    }
+}
+
+#[derive(Debug)]
+struct AppState {
+    status_notifier: Mutex<StatusNotifier>,
+    services: AppServices,
+    command_guard: CommandGuard,
+}
+
+impl AppState {
+    fn new(app_data_dir: PathBuf) -> Self {
+        Self {
+            status_notifier: Mutex::new(StatusNotifier::default()),
+            services: AppServices::new(app_data_dir),
+            command_guard: CommandGuard::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PipelineRuntimeState {
+    execution_lock: Arc<tokio::sync::Mutex<()>>,
+    next_session_id: Arc<AtomicU64>,
+    active_session_id: Arc<AtomicU64>,
+    // Sessions below this id were cancelled; a newer session only supersedes
+    // older ones, which keep transcribing and inserting in order.
+    cancelled_before_session_id: Arc<AtomicU64>,
+    realtime_session: Arc<Mutex<Option<RealtimeTranscriptionSession>>>,
+    session_queue: SessionQueue,
+    reserved_queue_ticket: Arc<Mutex<Option<(u64, QueueTicket)>>>,
+    // Several sessions can be queued at once, so each keeps its own mark
+    // until it finishes.
+    copy_only_sessions: Arc<Mutex<HashSet<u64>>>,
+    private_sessions: Arc<Mutex<HashSet<u64>>>,
+    private_requested: Arc<AtomicBool>,
+    origin_apps: Arc<Mutex<HashMap<u64, FocusedApp>>>,
+    requested_style: Arc<Mutex<Option<String>>>,
+    session_styles: Arc<Mutex<HashMap<u64, String>>>,
+    pending_insert: Arc<Mutex<Option<PendingInsert>>>,
+    flagged_transcript: Arc<Mutex<Option<(FlaggedTranscript, RecordedWav)>>>,
+    uncertain_language: Arc<Mutex<Option<(LanguageUncertainEvent, RecordedWav)>>>,
+    // Shared by every session started since the last cancel, so cancelling
+    // aborts their in-flight requests at once.
+    cancellation: Arc<Mutex<CancellationToken>>,
+}
+
+impl Default for PipelineRuntimeState {
+    fn default() -> Self {
+        Self {
+            execution_lock: Arc::new(tokio::sync::Mutex::new(())),
+            next_session_id: Arc::new(AtomicU64::new(0)),
+            active_session_id: Arc::new(AtomicU64::new(0)),
+            cancelled_before_session_id: Arc::new(AtomicU64::new(0)),
+            realtime_session: Arc::new(Mutex::new(None)),
+            session_queue: SessionQueue::new(MAX_QUEUED_SESSIONS),
+            reserved_queue_ticket: Arc::new(Mutex::new(None)),
+            copy_only_sessions: Arc::new(Mutex::new(HashSet::new())),
+            private_sessions: Arc::new(Mutex::new(HashSet::new())),
+            private_requested: Arc::new(AtomicBool::new(false)),
+            origin_apps: Arc::new(Mutex::new(HashMap::new())),
+            requested_style: Arc::new(Mutex::new(None)),
+            session_styles: Arc::new(Mutex::new(HashMap::new())),
+            pending_insert: Arc::new(Mutex::new(None)),
+            flagged_transcript: Arc::new(Mutex::new(None)),
+            uncertain_language: Arc::new(Mutex::new(None)),
+            cancellation: Arc::new(Mutex::new(CancellationToken::new())),
+        }
+    }
+}
+
+impl PipelineRuntimeState {
+    fn begin_session(&self) -> u64 {
+        let session_id = self.next_session_id.fetch_add(1, Ordering::Relaxed) + 1;
+        self.active_session_id.store(session_id, Ordering::Relaxed);
+        self.clear_realtime_session();
+        self.release_queue_ticket();
+        debug!(session_id, "pipeline session started");
+        session_id
+    }
+
+    fn cancel_sessions(&self) -> u64 {
+        let session_id = self.begin_session();
+        self.cancelled_before_session_id
+            .fetch_max(session_id, Ordering::Relaxed);
+        if let Ok(mut cancellation) = self.cancellation.lock() {
+            std::mem::take(&mut *cancellation).cancel();
+        }
+        info!(session_id, "cancelled in-flight pipeline sessions");
+        session_id
+    }
+
+    fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation
+            .lock()
+            .map(|cancellation| cancellation.clone())
+            .unwrap_or_default()
+    }
+
+    fn active_session_id(&self) -> Option<u64> {
+        let session_id = self.active_session_id.load(Ordering::Relaxed);
+        (session_id > 0).then_some(session_id)
+    }
+
+    fn is_session_active(&self, session_id: u64) -> bool {
+        self.active_session_id.load(Ordering::Relaxed) == session_id
+    }
+
+    fn is_session_cancelled(&self, session_id: u64) -> bool {
+        session_id < self.cancelled_before_session_id.load(Ordering::Relaxed)
+    }
+
+    // Copy-only sessions put their transcript on the clipboard instead of
+    // inserting it, regardless of the auto-insert setting.
+    fn mark_copy_only(&self, session_id: u64) {
+        if let Ok(mut copy_only_sessions) = self.copy_only_sessions.lock() {
+            copy_only_sessions.insert(session_id);
+        }
+    }
+
+    fn is_copy_only(&self, session_id: u64) -> bool {
+        self.copy_only_sessions
+            .lock()
+            .is_ok_and(|copy_only_sessions| copy_only_sessions.contains(&session_id))
+    }
+
+    fn mark_private(&self, session_id: u64) {
+        if let Ok(mut private_sessions) = self.private_sessions.lock() {
+            private_sessions.insert(session_id);
+        }
+    }
+
+    // An unreadable set counts as private, so a poisoned lock can only ever
+    // keep a transcript out of history.
+    fn is_private(&self, session_id: u64) -> bool {
+        self.private_sessions
+            .lock()
+            .map_or(true, |private_sessions| {
+                private_sessions.contains(&session_id)
+            })
+    }
+
+    // Set by the private dictation shortcut just before it starts recording;
+    // the session that starts next takes it.
+    fn request_private_for_next_session(&self, private: bool) {
+        self.private_requested.store(private, Ordering::Relaxed);
+    }
+
+    fn adopt_requested_private(&self, session_id: u64) {
+        if self.private_requested.swap(false, Ordering::Relaxed) {
+            self.mark_private(session_id);
+        }
+    }
+
+    fn finish_session(&self, session_id: u64) {
+        if let Ok(mut copy_only_sessions) = self.copy_only_sessions.lock() {
+            copy_only_sessions.remove(&session_id);
+        }
+        if let Ok(mut private_sessions) = self.private_sessions.lock() {
+            private_sessions.remove(&session_id);
+        }
+    }
+
+    // The app that was frontmost when a session started recording, for the
+    // `if_focused` auto-insert mode. Entries for sessions that never reach
+    // insertion are pruned once they fall out of the queue window.
+    fn remember_origin_app(&self, session_id: u64, origin_app: Option<FocusedApp>) {
+        let Ok(mut origin_apps) = self.origin_apps.lock() else {
+            warn!("origin app lock poisoned; skipping focus tracking");
+            return;
+        };
+        origin_apps.retain(|id, _| id + MAX_QUEUED_SESSIONS as u64 >= session_id);
+        if let Some(origin_app) = origin_app {
+            origin_apps.insert(session_id, origin_app);
+        }
+    }
+
+    fn take_origin_app(&self, session_id: u64) -> Option<FocusedApp> {
+        self.origin_apps
+            .lock()
+            .ok()
+            .and_then(|mut origin_apps| origin_apps.remove(&session_id))
+    }
+
+    // A transcription style asked for by a deep link, used by the next session
+    // to start instead of the one in settings.
+    fn request_style_for_next_session(&self, style: Option<String>) {
+        if let Ok(mut requested_style) = self.requested_style.lock() {
+            *requested_style = style;
+        }
+    }
+
+    fn adopt_requested_style(&self, session_id: u64) {
+        let Some(style) = self
+            .requested_style
+            .lock()
+            .ok()
+            .and_then(|mut requested_style| requested_style.take())
+        else {
+            return;
+        };
+        if let Ok(mut session_styles) = self.session_styles.lock() {
+            session_styles.retain(|id, _| id + MAX_QUEUED_SESSIONS as u64 >= session_id);
+            session_styles.insert(session_id, style);
+        }
+    }
+
+    fn session_style(&self, session_id: u64) -> Option<String> {
+        self.session_styles
+            .lock()
+            .ok()
+            .and_then(|session_styles| session_styles.get(&session_id).cloned())
+    }
+
+    fn set_pending_insert(&self, pending_insert: PendingInsert) {
+        if let Ok(mut guard) = self.pending_insert.lock() {
+            if guard.is_some() {
+                warn!("replacing unresolved pending insert with a newer transcript");
+            }
+            *guard = Some(pending_insert);
+        }
+    }
+
+    fn pending_insert(&self) -> Option<PendingInsert> {
+        self.pending_insert
+            .lock()
+            .ok()
+            .and_then(|guard| guard.clone())
+    }
+
+    fn take_pending_insert(&self) -> Option<PendingInsert> {
+        self.pending_insert
+            .lock()
+            .ok()
+            .and_then(|mut guard| guard.take())
+    }
+
+    // Only the latest flagged transcript keeps its audio around for a retry.
+    fn set_flagged_transcript(&self, flagged: FlaggedTranscript, wav: RecordedWav) {
+        if let Ok(mut guard) = self.flagged_transcript.lock() {
+            *guard = Some((flagged, wav));
+        }
+    }
+
+    fn flagged_transcript(&self) -> Option<FlaggedTranscript> {
+        self.flagged_transcript
+            .lock()
+            .ok()
+            .and_then(|guard| guard.as_ref().map(|(flagged, _)| flagged.clone()))
+    }
+
+    fn take_flagged_transcript(&self) -> Option<(FlaggedTranscript, RecordedWav)> {
+        self.flagged_transcript
+            .lock()
+            .ok()
+            .and_then(|mut guard| guard.take())
+    }
+
+    // Likewise only the latest dictation with an uncertain language.
+    fn set_uncertain_language(&self, event: LanguageUncertainEvent, wav: RecordedWav) {
+        if let Ok(mut guard) = self.uncertain_language.lock() {
+            *guard = Some((event, wav));
+        }
+    }
+
+    fn uncertain_language_recording(&self) -> Option<RecordedWav> {
+        self.uncertain_language
+            .lock()
+            .ok()
+            .and_then(|guard| guard.as_ref().map(|(_, wav)| wav.clone()))
+    }
+
+    fn take_uncertain_language(&self) -> Option<(LanguageUncertainEvent, RecordedWav)> {
+        self.uncertain_language
+            .lock()
+            .ok()
+            .and_then(|mut guard| guard.take())
+    }
+
+    fn reserve_queue_slot(&self, session_id: u64) -> Result<(), String> {
+        let ticket = self.session_queue.try_reserve()?;
+        let mut guard = self
+            .reserved_queue_ticket
+            .lock()
+            .map_err(|_| "Pipeline session queue is unavailable".to_string())?;
+        *guard = Some((session_id, ticket));
+        Ok(())
+    }
+
+    fn take_queue_ticket(&self, session_id: u64) -> Option<QueueTicket> {
+        let mut guard = match self.reserved_queue_ticket.lock() {
+            Ok(guard) => guard,
+            Err(_) => {
+                error!("failed to take queue ticket because runtime lock was poisoned");
+                return None;
+            }
+        };
+        match guard.take() {
+            Some((reserved_for, ticket)) if reserved_for == session_id => Some(ticket),
+            other => {
+                *guard = other;
+                None
+            }
+        }
+    }
+
+    fn release_queue_ticket(&self) {
+        match self.reserved_queue_ticket.lock() {
+            Ok(mut guard) => {
+                guard.take();
+            }
+            Err(_) => {
+                error!("failed to release queue ticket because runtime lock was poisoned");
+            }
+        }
+    }
+
+    fn clear_realtime_session(&self) {
+        match self.realtime_session.lock() {
+            Ok(mut guard) => {
+                if let Some(session) = guard.take() {
+                    session.close();
+                }
+            }
+            Err(_) => {
+                error!("failed to clear realtime session because runtime lock was poisoned");
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct AppPipelineDelegate {
+    app: AppHandle,
+    session_id: Option<u64>,
+    realtime_session: Arc<Mutex<Option<RealtimeTranscriptionSession>>>,
+    recording_duration_secs: Arc<Mutex<Option<f64>>>,
+    trimmed_silence_ms: Arc<AtomicU64>,
+    held_transcript: Arc<Mutex<Option<HeldTranscript>>>,
+    // Read back by `insert_text` to route unsure transcripts to review.
+    transcript_confidence: Arc<Mutex<Option<f32>>>,
+    cancellation: CancellationToken,
+}
+
+impl AppPipelineDelegate {
+    fn new(app: AppHandle) -> Self {
+        let (realtime_session, cancellation) = {
+            let runtime_state = app.state::<PipelineRuntimeState>();
+            (
+                Arc::clone(&runtime_state.realtime_session),
+                runtime_state.cancellation_token(),
+            )
+        };
+        Self {
+            app,
+            session_id: None,
+            realtime_session,
+            recording_duration_secs: Arc::new(Mutex::new(None)),
+            trimmed_silence_ms: Arc::new(AtomicU64::new(0)),
+            held_transcript: Arc::new(Mutex::new(None)),
+            transcript_confidence: Arc::new(Mutex::new(None)),
+            cancellation,
+        }
+    }
+
+    fn for_session(app: AppHandle, session_id: u64) -> Self {
+        let (realtime_session, cancellation) = {
+            let runtime_state = app.state::<PipelineRuntimeState>();
+            (
+                Arc::clone(&runtime_state.realtime_session),
+                runtime_state.cancellation_token(),
+            )
+        };
+        Self {
+            app,
+            session_id: Some(session_id),
+            realtime_session,
+            recording_duration_secs: Arc::new(Mutex::new(None)),
+            trimmed_silence_ms: Arc::new(AtomicU64::new(0)),
+            held_transcript: Arc::new(Mutex::new(None)),
+            transcript_confidence: Arc::new(Mutex::new(None)),
+            cancellation,
+        }
+    }
+
+    fn is_session_active(&self) -> bool {
+        match self.session_id {
+            Some(session_id) => self
+                .app
+                .state::<PipelineRuntimeState>()
+                .is_session_active(session_id),
+            None => true,
+        }
+    }
+
+    // Superseded sessions still deliver their transcript; only cancelled ones
+    // are dropped.
+    fn is_session_live(&self) -> bool {
+        match self.session_id {
+            Some(session_id) => !self
+                .app
+                .state::<PipelineRuntimeState>()
+                .is_session_cancelled(session_id),
+            None => true,
+        }
+    }
+
+    // Moves this session's realtime connection out of the shared slot so the
+    // next session can open its own while this one is still transcribing.
+    fn detach_realtime_session(mut self) -> Self {
+        let session = self.take_realtime_session();
+        self.realtime_session = Arc::new(Mutex::new(session));
+        self
+    }
+
+    fn current_settings(&self) -> VoiceSettings {
+        let state = self.app.state::<AppState>();
+        let mut settings = state.services.settings_store.current();
+        if let Some(style) = self.session_id.and_then(|session_id| {
+            self.app
+                .state::<PipelineRuntimeState>()
+                .session_style(session_id)
+        }) {
+            settings.transcription_style = style;
+            // A style picked for this dictation beats the language's own.
+            for profile in settings.language_profiles.values_mut() {
+                profile.transcription_style = None;
+            }
+        }
+        settings
+    }
+
+    // The frontmost app is where the transcript will go.
+    fn recent_text(&self, settings: &VoiceSettings) -> Option<String> {
+        let state = self.app.state::<AppState>();
+        let target_app = self.app.state::<FocusedAppWatcher>().current();
+        recent_text_context(&state, settings, target_app.as_ref())
+    }
+
+    fn redaction_engine(&self) -> RedactionEngine {
+        RedactionEngine::new(&self.current_settings().redaction_rules).unwrap_or_else(|error| {
+            warn!(session_id = ?self.session_id, %error, "ignoring invalid redaction rules");
+            RedactionEngine::default()
+        })
+    }
+
+    fn report_redaction_warnings(&self, rules: Vec<String>) {
+        if rules.is_empty() {
+            return;
+        }
+        warn!(session_id = ?self.session_id, ?rules, "transcript matched redaction warning rules");
+        let event = RedactionWarningEvent {
+            session_id: self.session_id,
+            rules,
+        };
+        if let Err(error) = self.app.emit(EVENT_REDACTION_WARNING, event) {
+            warn!(session_id = ?self.session_id, %error, "failed to emit redaction warning event");
+        }
+    }
+
+    fn build_delta_callback(&self) -> transcription::TranscriptionDeltaCallback {
+        let app_for_delta = self.app.clone();
+        let session_id_for_delta = self.session_id;
+        Arc::new(move |delta| {
+            if let Some(session_id) = session_id_for_delta {
+                let runtime_state = app_for_delta.state::<PipelineRuntimeState>();
+                if !runtime_state.is_session_active(session_id) {
+                    return;
+                }
+            }
+            trace_session_event(
+                &app_for_delta,
+                session_id_for_delta,
+                EVENT_TRANSCRIPTION_DELTA,
+                &delta,
+            );
+            emit_transcription_delta_event(&app_for_delta, &delta);
+        })
+    }
+
+    // Realtime deltas also drive the live word count and pace shown in the
+    // overlay while the user is still speaking.
+    fn build_realtime_delta_callback(&self) -> transcription::TranscriptionDeltaCallback {
+        let forward_delta = self.build_delta_callback();
+        let app_for_progress = self.app.clone();
+        let session_id_for_progress = self.session_id;
+        let settings = self.current_settings();
+        let progress = Mutex::new(DictationProgress::new(
+            Instant::now(),
+            WordCountStrategy::from_settings(
+                &settings.word_count_mode,
+                settings.language.as_deref(),
+            ),
+        ));
+        Arc::new(move |delta: String| {
+            let event = progress
+                .lock()
+                .ok()
+                .and_then(|mut progress| progress.push_delta(&delta, Instant::now()));
+            forward_delta(delta);
+            let Some(event) = event else {
+                return;
+            };
+            if let Some(session_id) = session_id_for_progress {
+                let runtime_state = app_for_progress.state::<PipelineRuntimeState>();
+                if !runtime_state.is_session_active(session_id) {
+                    return;
+                }
+            }
+            if let Err(error) = app_for_progress.emit(EVENT_DICTATION_PROGRESS, event) {
+                warn!(%error, "failed to emit dictation progress event");
+            }
+        })
+    }
+
+    fn build_retry_callback(&self) -> TranscriptionRetryCallback {
+        let app_for_retry = self.app.clone();
+        let session_id_for_retry = self.session_id;
+        Arc::new(move |event| {
+            if let Some(session_id) = session_id_for_retry {
+                let runtime_state = app_for_retry.state::<PipelineRuntimeState>();
+                if !runtime_state.is_session_active(session_id) {
+                    return;
+                }
+            }
+            trace_session_event(
+                &app_for_retry,
+                session_id_for_retry,
+                EVENT_TRANSCRIPTION_RETRY,
+                &event,
+            );
+            emit_transcription_retry_event(&app_for_retry, event);
+        })
+    }
+
+    fn store_realtime_session(&self, session: Option<RealtimeTranscriptionSession>) {
+        if !self.is_session_live() {
+            if let Some(stale_session) = session {
+                stale_session.close();
+            }
+            debug!(
+                session_id = ?self.session_id,
+                "ignoring realtime session store for cancelled session"
+            );
+            return;
+        }
+
+        if let Ok(mut guard) = self.realtime_session.lock() {
+            *guard = session;
+        } else {
+            warn!(
+                session_id = ?self.session_id,
+                "failed to store realtime session because session lock was poisoned"
+            );
+        }
+    }
+
+    fn take_realtime_session(&self) -> Option<RealtimeTranscriptionSession> {
+        if !self.is_session_live() {
+            debug!(
+                session_id = ?self.session_id,
+                "ignoring realtime session access for cancelled session"
+            );
+            return None;
+        }
+
+        match self.realtime_session.lock() {
+            Ok(mut guard) => guard.take(),
+            Err(_) => {
+                warn!(
+                    session_id = ?self.session_id,
+                    "failed to access realtime session because session lock was poisoned"
+                );
+                None
+            }
+        }
+    }
+
+    fn clear_realtime_session(&self) {
+        if let Some(session) = self.take_realtime_session() {
+            session.close();
+        }
+    }
+
+    fn store_recording_duration_secs(&self, duration_secs: Option<f64>) {
+        match self.recording_duration_secs.lock() {
+            Ok(mut guard) => {
+                *guard = duration_secs.map(sanitize_recording_duration_secs);
+            }
+            Err(_) => {
+                warn!(
+                    session_id = ?self.session_id,
+                    "failed to store recording duration because lock was poisoned"
+                );
+            }
+        }
+    }
+
+    fn take_recording_duration_secs(&self) -> Option<f64> {
+        match self.recording_duration_secs.lock() {
+            Ok(mut guard) => guard.take(),
+            Err(_) => {
+                warn!(
+                    session_id = ?self.session_id,
+                    "failed to read recording duration because lock was poisoned"
+                );
+                None
+            }
+        }
+    }
+
+    fn clear_recording_duration_secs(&self) {
+        self.store_recording_duration_secs(None);
+    }
+
+    fn store_held_transcript(&self, held_transcript: Option<HeldTranscript>) {
+        if let Ok(mut guard) = self.held_transcript.lock() {
+            *guard = held_transcript;
+        }
+    }
+
+    fn take_held_transcript(&self) -> Option<HeldTranscript> {
+        self.held_transcript
+            .lock()
+            .ok()
+            .and_then(|mut guard| guard.take())
+    }
+
+    fn store_transcript_confidence(&self, confidence: Option<f32>) {
+        if let Ok(mut guard) = self.transcript_confidence.lock() {
+            *guard = confidence;
+        }
+    }
+
+    fn take_transcript_confidence(&self) -> Option<f32> {
+        self.transcript_confidence
+            .lock()
+            .ok()
+            .and_then(|mut guard| guard.take())
+    }
+
+    fn is_hallucination_discarded(&self) -> bool {
+        self.held_transcript.lock().is_ok_and(|guard| {
+            matches!(
+                *guard,
+                Some(HeldTranscript::SilenceHallucination(
+                    SuspectedHallucination { discard: true, .. }
+                ))
+            )
+        })
+    }
+
+    fn record_usage_stats_for_transcript(&self, transcript: &str, app: Option<&FocusedApp>) {
+        if self.is_private() {
+            self.take_recording_duration_secs();
+            self.trimmed_silence_ms.store(0, Ordering::Relaxed);
+            debug!(session_id = ?self.session_id, "skipping usage stats for private session");
+            return;
+        }
+        let settings = self.current_settings();
+        let recording_duration_secs = self.take_recording_duration_secs().unwrap_or(0.0);
+        let trimmed_silence_ms = self.trimmed_silence_ms.swap(0, Ordering::Relaxed);
+        let stats_store = self.app.state::<StatsStore>();
+
+        if let Err(error) = rest::record_usage_stats(
+            &stats_store,
+            &settings,
+            transcript,
+            None,
+            recording_duration_secs,
+            trimmed_silence_ms,
+            app,
+        ) {
+            warn!(
+                session_id = ?self.session_id,
+                recording_duration_secs,
+                %error,
+                "failed to persist usage stats"
+            );
+        }
+
+        if settings.telemetry_enabled {
+            let provider = self
+                .app
+                .state::<AppState>()
+                .services
+                .current_auth_method()
+                .map(|auth_method| {
+                    rest::rest_provider_name(auth_method, &settings.transcription_provider)
+                        .to_string()
+                })
+                .unwrap_or_else(|_| "none".to_string());
+            record_telemetry(
+                &self.app,
+                TelemetryEventKind::DictationCompleted {
+                    recording_mode: settings.recording_mode.clone(),
+                    provider,
+                },
+            );
+        }
+    }
+
+    async fn transcribe_recording(&self, wav: RecordedWav) -> Result<PipelineTranscript, AppError> {
+        let settings = self.current_settings();
+        let language = language_hint(&settings.preferred_languages);
+        let options = TranscriptionOptions {
+            on_delta: Some(self.build_delta_callback()),
+            on_retry: Some(self.build_retry_callback()),
+            cancellation: Some(self.cancellation.clone()),
+            ..rest::transcription_options(&settings, language, self.recent_text(&settings))
+        };
+        let state = self.app.state::<AppState>();
+        let auth_method = state.services.current_auth_method()?;
+        let provider_name =
+            rest::rest_provider_name(auth_method, &settings.transcription_provider).to_string();
+        let meeting_mode_duration_secs = rest::meeting_mode_duration_secs(&settings, &wav);
+
+        // Long meeting recordings go through chunked REST uploads so each request
+        // stays small and the stitched transcript carries segment timestamps.
+        if meeting_mode_duration_secs.is_some() {
+            self.clear_realtime_session();
+        }
+
+        if auth_method == AuthMethod::ApiKey && meeting_mode_duration_secs.is_none() {
+            if let Some(realtime_session) = self.take_realtime_session() {
+                if settings.transcription_race_mode {
+                    return self
+                        .race_realtime_against_rest(
+                            realtime_session,
+                            &settings,
+                            wav,
+                            provider_name,
+                            options,
+                        )
+                        .await;
+                }
+
+                match self.commit_realtime_session(realtime_session).await {
+                    Ok(transcript) => return Ok(transcript),
+                    Err(error) => {
+                        warn!(
+                            session_id = ?self.session_id,
+                            error = %error,
+                            provider = "openai-realtime",
+                            "realtime transcription failed; falling back to REST upload"
+                        );
+                    }
+                }
+            }
+        } else if auth_method != AuthMethod::ApiKey {
+            self.clear_realtime_session();
+        }
+
+        self.transcribe_with_rest(
+            &settings,
+            wav,
+            auth_method,
+            provider_name,
+            options,
+            meeting_mode_duration_secs,
+        )
+        .await
+    }
+
+    async fn commit_realtime_session(
+        &self,
+        realtime_session: RealtimeTranscriptionSession,
+    ) -> Result<PipelineTranscript, AppError> {
+        info!(
+            session_id = ?self.session_id,
+            provider = "openai-realtime",
+            "awaiting realtime transcription completion"
+        );
+        let transcription = realtime_session
+            .commit_and_wait(Some(&self.cancellation))
+            .await?;
+        let transcript =
+            PipelineTranscript::from_transcription(transcription, "openai-realtime".to_string());
+        info!(
+            session_id = ?self.session_id,
+            provider = %transcript.provider,
+            transcript_chars = transcript.text.chars().count(),
+            "realtime transcription completed"
+        );
+        Ok(transcript)
+    }
+
+    // Experimental latency mode: the realtime commit and a REST upload of the
+    // same audio run side by side and the first usable transcript wins. The
+    // slower path is dropped, which cancels its request.
+    async fn race_realtime_against_rest(
+        &self,
+        realtime_session: RealtimeTranscriptionSession,
+        settings: &VoiceSettings,
+        wav: RecordedWav,
+        provider_name: String,
+        options: TranscriptionOptions,
+    ) -> Result<PipelineTranscript, AppError> {
+        info!(
+            session_id = ?self.session_id,
+            "racing realtime commit against REST upload"
+        );
+        let started_at = Instant::now();
+        let realtime_sender = realtime_session.audio_sender();
+        let realtime = self.commit_realtime_session(realtime_session);
+        let rest = self.transcribe_with_rest(
+            settings,
+            wav,
+            AuthMethod::ApiKey,
+            provider_name,
+            options,
+            None,
+        );
+        tokio::pin!(realtime, rest);
+
+        let (first_path, first) = tokio::select! {
+            result = &mut realtime => (TranscriptionRacePath::Realtime, result),
+            result = &mut rest => (TranscriptionRacePath::Rest, result),
+        };
+        let (winner, result) = if is_usable_transcript(&first) {
+            (first_path, first)
+        } else {
+            warn!(
+                session_id = ?self.session_id,
+                path = first_path.as_str(),
+                error = ?first.as_ref().err(),
+                "race path finished without a usable transcript; waiting for the other"
+            );
+            let second_path = first_path.other();
+            let second = match second_path {
+                TranscriptionRacePath::Realtime => realtime.await,
+                TranscriptionRacePath::Rest => rest.await,
+            };
+            match (first, second) {
+                (first @ Ok(_), Err(_)) => (first_path, first),
+                (_, second) => (second_path, second),
+            }
+        };
+
+        if winner == TranscriptionRacePath::Rest {
+            realtime_sender.close();
+        }
+        info!(
+            session_id = ?self.session_id,
+            winner = winner.as_str(),
+            elapsed_ms = started_at.elapsed().as_millis() as u64,
+            succeeded = result.is_ok(),
+            "transcription race finished"
+        );
+        if result.is_ok() {
+            if let Err(error) = self
+                .app
+                .state::<StatsStore>()
+                .record_transcription_race_win(winner)
+            {
+                warn!(session_id = ?self.session_id, %error, "failed to record race winner");
+            }
+        }
+        result
+    }
+
+    // A recording that failed only because the provider was out of reach is
+    // kept for later instead of being discarded with the error.
+    fn queue_when_offline(&self, error: AppError, wav: RecordedWav) -> AppError {
+        if error.code != ErrorCode::Network || self.is_private() {
+            return error;
+        }
+        let Some(store) = self.app.try_state::<PendingTranscriptionStore>() else {
+            return error;
+        };
+        let duration_secs = wav.duration_secs();
+        let cipher = self
+            .app
+            .try_state::<HistoryStore>()
+            .and_then(|history_store| history_store.current_cipher());
+        let queued = wav
+            .into_bytes()
+            .map_err(AppError::from)
+            .and_then(|wav_bytes| {
+                store.enqueue(&wav_bytes, duration_secs, &error.message, cipher.as_ref())
+            });
+        match queued {
+            Ok(_) => {
+                emit_pending_transcriptions_changed(&self.app);
+                AppError::new(
+                    error.code,
+                    format!("{error}. The recording was saved and will be transcribed once you are back online"),
+                )
+            }
+            Err(queue_error) => {
+                warn!(session_id = ?self.session_id, error = %queue_error, "failed to queue recording for later");
+                error
+            }
+        }
+    }
+
+    // Keeps the audio of a dictation whose language looks wrong so the user
+    // can re-transcribe it in one of their other preferred languages.
+    fn offer_language_alternatives(&self, transcript: &PipelineTranscript, wav: &RecordedWav) {
+        if transcript.text.trim().is_empty() || self.is_private() {
+            return;
+        }
+        let settings = self.current_settings();
+        let Some(alternatives) = uncertain_alternatives(
+            transcript.language.as_deref(),
+            transcript.language_confidence,
+            &settings.preferred_languages,
+        )
+        .filter(|alternatives| !alternatives.is_empty()) else {
+            return;
+        };
+        info!(
+            session_id = ?self.session_id,
+            detected_language = ?transcript.language,
+            confidence = ?transcript.language_confidence,
+            "transcript language is uncertain"
+        );
+        let event = LanguageUncertainEvent {
+            session_id: self.session_id,
+            text: transcript.text.clone(),
+            detected_language: transcript.language.clone(),
+            confidence: transcript.language_confidence,
+            alternatives,
+        };
+        self.app
+            .state::<PipelineRuntimeState>()
+            .set_uncertain_language(event.clone(), wav.clone());
+        if let Err(error) = self.app.emit(EVENT_LANGUAGE_UNCERTAIN, event) {
+            warn!(session_id = ?self.session_id, %error, "failed to emit uncertain language event");
+        }
+    }
+
+    async fn retranscribe_with_rest(
+        &self,
+        wav: RecordedWav,
+        language: Option<String>,
+    ) -> Result<PipelineTranscript, AppError> {
+        let settings = self.current_settings();
+        let language = language.or_else(|| language_hint(&settings.preferred_languages));
+        let options = TranscriptionOptions {
+            on_retry: Some(self.build_retry_callback()),
+            cancellation: Some(self.cancellation.clone()),
+            ..rest::transcription_options(&settings, language, self.recent_text(&settings))
+        };
+        let auth_method = self
+            .app
+            .state::<AppState>()
+            .services
+            .current_auth_method()?;
+        let meeting_mode_duration_secs = rest::meeting_mode_duration_secs(&settings, &wav);
+        self.transcribe_with_rest(
+            &settings,
+            wav,
+            auth_method,
+            rest::rest_provider_name(auth_method, &settings.transcription_provider).to_string(),
+            options,
+            meeting_mode_duration_secs,
+        )
+        .await
+    }
+
+    async fn transcribe_with_rest(
+        &self,
+        settings: &VoiceSettings,
+        wav: RecordedWav,
+        auth_method: AuthMethod,
+        provider_name: String,
+        options: TranscriptionOptions,
+        meeting_mode_duration_secs: Option<f64>,
+    ) -> Result<PipelineTranscript, AppError> {
+        let state = self.app.state::<AppState>();
+        let orchestrator = state.services.api_key_orchestrator(settings);
+        let providers = rest::RestProviders {
+            api_key: &orchestrator,
+            chatgpt: &state.services.chatgpt_transcription_provider,
+        };
+        match rest::transcribe_with_rest(
+            settings,
+            wav,
+            auth_method,
+            providers,
+            options,
+            meeting_mode_duration_secs,
+        )
+        .await
+        {
+            Ok(rest_transcription) => {
+                self.trimmed_silence_ms
+                    .store(rest_transcription.trimmed_silence_ms, Ordering::Relaxed);
+                let transcript = PipelineTranscript::from_transcription(
+                    rest_transcription.transcription,
+                    provider_name,
+                );
+                info!(
+                    session_id = ?self.session_id,
+                    provider = %transcript.provider,
+                    transcript_chars = transcript.text.chars().count(),
+                    "transcription request completed"
+                );
+                Ok(transcript)
+            }
+            Err(error) => {
+                error!(
+                    session_id = ?self.session_id,
+                    provider = %provider_name,
+                    error = %error,
+                    "transcription request failed"
+                );
+                Err(error)
+            }
+        }
+    }
+
+    // Best-effort: the original transcript is still inserted when translating
+    // fails or is unavailable for the active auth method.
+    async fn translate_transcript(
+        &self,
+        transcript: &PipelineTranscript,
+    ) -> Option<PipelineTranslation> {
+        let settings = self.current_settings();
+        if !settings.translation_enabled || transcript.text.trim().is_empty() {
+            return None;
+        }
+
+        let target_language = DEFAULT_TRANSLATION_TARGET_LANGUAGE;
+        let spoken_language = transcript
+            .language
+            .as_deref()
+            .or(settings.language.as_deref());
+        if translation::is_language(spoken_language, target_language) {
+            debug!(
+                session_id = ?self.session_id,
+                target_language,
+                "transcript is already in the translation language"
+            );
+            return None;
+        }
+
+        let state = self.app.state::<AppState>();
+        if !matches!(state.services.current_auth_method(), Ok(AuthMethod::ApiKey)) {
+            warn!(
+                session_id = ?self.session_id,
+                "translation requires an OpenAI API key; skipping"
+            );
+            return None;
+        }
+
+        match state
+            .services
+            .translation_client
+            .translate(&transcript.text, target_language)
+            .await
+        {
+            Ok(text) => {
+                info!(
+                    session_id = ?self.session_id,
+                    target_language,
+                    translation_chars = text.chars().count(),
+                    "transcript translation completed"
+                );
+                Some(PipelineTranslation {
+                    language: target_language.to_string(),
+                    text,
+                    insert: settings.translation_insert == TRANSLATION_INSERT_TRANSLATION,
+                })
+            }
+            Err(error) => {
+                warn!(
+                    session_id = ?self.session_id,
+                    %error,
+                    "transcript translation failed; keeping original text only"
+                );
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl VoicePipelineDelegate for AppPipelineDelegate {
+    fn set_status(&self, status: AppStatus) {
+        if self.is_session_active() {
+            debug!(?status, session_id = ?self.session_id, "updating app status");
+            trace_session_event(&self.app, self.session_id, EVENT_STATUS_CHANGED, &status);
+            set_status_for_app(&self.app, status);
+        } else {
+            debug!(
+                ?status,
+                session_id = ?self.session_id,
+                "ignoring status update for inactive session"
+            );
+        }
+    }
+
+    fn emit_transcript(&self, transcript: &PipelineTranscript) {
+        if self.is_hallucination_discarded() {
+            debug!(
+                session_id = ?self.session_id,
+                "not emitting suppressed transcript"
+            );
+        } else if self.is_session_live() {
+            info!(
+                session_id = ?self.session_id,
+                transcript_chars = transcript.text_to_insert().chars().count(),
+                confidence = ?transcript.confidence,
+                "pipeline transcript ready"
+            );
+            emit_transcript_event(&self.app, self.session_id, transcript);
+            play_sound_cue(&self.app, SoundCue::TranscriptionCompleted);
+        } else {
+            debug!(
+                session_id = ?self.session_id,
+                "ignoring transcript for cancelled session"
+            );
+        }
+    }
+
+    fn emit_error(&self, error: &PipelineError) {
+        if self.is_session_live() {
+            error!(
+                session_id = ?self.session_id,
+                stage = error.stage.as_str(),
+                message = %error.message,
+                "pipeline error emitted"
+            );
+            emit_pipeline_error_event(&self.app, self.session_id, error);
+            play_sound_cue(&self.app, SoundCue::Error);
+        } else {
+            debug!(
+                session_id = ?self.session_id,
+                stage = error.stage.as_str(),
+                "ignoring pipeline error for cancelled session"
+            );
+        }
+    }
+
+    fn on_recording_started(&self, success: bool) {
+        debug!(session_id = ?self.session_id, success, "recording start acknowledged");
+        if success {
+            play_sound_cue(&self.app, SoundCue::RecordingStarted);
+        }
+        let hotkey_service = self.app.state::<HotkeyService>();
+        hotkey_service.acknowledge_transition(RecordingTransition::Started, success);
+    }
+
+    fn on_recording_stopped(&self, success: bool) {
+        debug!(session_id = ?self.session_id, success, "recording stop acknowledged");
+        if success {
+            play_sound_cue(&self.app, SoundCue::RecordingStopped);
+        } else {
+            self.clear_realtime_session();
+            self.clear_recording_duration_secs();
+        }
+        let hotkey_service = self.app.state::<HotkeyService>();
+        hotkey_service.acknowledge_transition(RecordingTransition::Stopped, success);
+    }
+
+    fn on_stage_finished(
+        &self,
+        stage: voice_pipeline::PipelineErrorStage,
+        elapsed: Duration,
+        success: bool,
+    ) {
+        trace_session_event(
+            &self.app,
+            self.session_id,
+            STAGE_FINISHED_TRACE_EVENT,
+            &StageFinished {
+                stage: stage.as_str().to_string(),
+                duration_ms: elapsed.as_millis() as u64,
+                success,
+            },
+        );
+    }
+
+    fn start_recording(&self) -> Result<(), AppError> {
+        let settings = self.current_settings();
+        let capture_source = CaptureSource::from_settings_value(&settings.capture_source);
+        info!(
+            session_id = ?self.session_id,
+            microphone_id = ?settings.microphone_id.as_deref(),
+            capture_source = capture_source.as_str(),
+            "pipeline requested recording start"
+        );
+        if let Some(session_id) = self.session_id {
+            self.app
+                .state::<PipelineRuntimeState>()
+                .reserve_queue_slot(session_id)?;
+            self.app
+                .state::<PipelineRuntimeState>()
+                .remember_origin_app(session_id, self.app.state::<FocusedAppWatcher>().current());
+        }
+        let state = self.app.state::<AppState>();
+        ensure_microphone_permission_for_recording(&state)?;
+
+        self.clear_realtime_session();
+        self.clear_recording_duration_secs();
+
+        let auth_method = state.services.current_auth_method()?;
+
+        // Live chunks only carry the primary device, so mixed capture relies on the
+        // REST upload of the fully mixed recording.
+        let realtime_session = if capture_source == CaptureSource::Mixed {
+            debug!(
+                session_id = ?self.session_id,
+                "mixed capture source does not support realtime transcription; using REST upload fallback"
+            );
+            None
+        } else if auth_method == AuthMethod::ApiKey
+            && settings.transcription_provider == DEFAULT_TRANSCRIPTION_PROVIDER
+            && state
+                .services
+                .realtime_transcription_client
+                .model_supports_realtime()
+        {
+            let language = language_hint(&settings.preferred_languages);
+            let transcription_prompt = transcription_prompt(&settings, language.as_deref());
+            let context_hint = transcription_context_hint(
+                &settings,
+                language.as_deref(),
+                self.recent_text(&settings),
+            );
+            let options = TranscriptionOptions {
+                language,
+                prompt: transcription_prompt,
+                context_hint,
+                on_delta: Some(self.build_realtime_delta_callback()),
+                ..TranscriptionOptions::default()
+            };
+            match state
+                .services
+                .realtime_transcription_client
+                .begin_session(options, settings.pin_realtime_language)
+            {
+                Ok(session) => {
+                    info!(
+                        session_id = ?self.session_id,
+                        model = %state.services.realtime_transcription_client.model(),
+                        "realtime transcription session prepared"
+                    );
+                    Some(session)
+                }
+                Err(error) => {
+                    warn!(
+                        session_id = ?self.session_id,
+                        error = %error,
+                        "unable to start realtime transcription session; will fall back to REST upload"
+                    );
+                    None
+                }
+            }
+        } else if auth_method != AuthMethod::ApiKey {
+            debug!(
+                session_id = ?self.session_id,
+                auth_method = auth_method.as_str(),
+                "active auth method does not support realtime transcription; using REST upload fallback"
+            );
+            None
+        } else {
+            debug!(
+                session_id = ?self.session_id,
+                model = %state.services.realtime_transcription_client.model(),
+                "configured model does not support realtime transcription; using REST upload fallback"
+            );
+            None
+        };
+
+        let chunk_callback: Option<AudioInputChunkCallback> =
+            realtime_session.as_ref().map(|session| {
+                let audio_sender = session.audio_sender();
+                let append_error_logged = Arc::new(AtomicBool::new(false));
+                let append_error_logged_for_callback = Arc::clone(&append_error_logged);
+                let chunk_drop_logged = Arc::new(AtomicBool::new(false));
+                let chunk_drop_logged_for_callback = Arc::clone(&chunk_drop_logged);
+                let session_id = self.session_id;
+                Arc::new(move |chunk: AudioInputChunk| {
+                    match audio_sender
+                        .append_pcm16_mono(chunk.pcm16_mono_samples, chunk.sample_rate_hz)
+                    {
+                        Ok(RealtimeAppendOutcome::Queued) => {
+                            chunk_drop_logged_for_callback.store(false, Ordering::Relaxed);
+                        }
+                        Ok(RealtimeAppendOutcome::DroppedBackpressure) => {
+                            if !chunk_drop_logged_for_callback.swap(true, Ordering::Relaxed) {
+                                warn!(
+                                    session_id = ?session_id,
+                                    "realtime command buffer is full; dropping audio chunk"
+                                );
+                            }
+                        }
+                        Err(error) => {
+                            if !append_error_logged_for_callback.swap(true, Ordering::Relaxed) {
+                                warn!(
+                                    session_id = ?session_id,
+                                    error = %error,
+                                    "failed to forward audio chunk to realtime transcription session"
+                                );
+                            }
+                        }
+                    }
+                }) as AudioInputChunkCallback
+            });
+
+        let start_result = state.services.audio_capture_service.start_recording(
+            self.app.clone(),
+            settings.microphone_id.as_deref(),
+            capture_source,
+            InputProcessingConfig::from_settings(&settings),
+            chunk_callback,
+        );
+
+        if start_result.is_ok() {
+            self.store_realtime_session(realtime_session);
+            if let (Some(session_id), Some(limit)) =
+                (self.session_id, max_recording_duration(&settings))
+            {
+                spawn_recording_limit_watchdog(self.app.clone(), session_id, limit);
+            }
+            start_result
+        } else {
+            if let Some(session) = realtime_session {
+                session.close();
+            }
+            start_result
+        }
+    }
+
+    fn stop_recording(&self) -> Result<RecordedWav, AppError> {
+        info!(session_id = ?self.session_id, "pipeline requested recording stop");
+        let min_duration_ms = self.current_settings().min_recording_duration_ms;
+        let state = self.app.state::<AppState>();
+        let result = state
+            .services
+            .audio_capture_service
+            .stop_recording(self.app.clone())
+            .map(|mut recorded| {
+                if should_discard_recording(recorded.duration_ms, min_duration_ms) {
+                    debug!(
+                        session_id = ?self.session_id,
+                        duration_ms = recorded.duration_ms,
+                        min_duration_ms,
+                        "recording too short, discarding"
+                    );
+                    self.clear_realtime_session();
+                    self.clear_recording_duration_secs();
+                    return RecordedWav::Memory(Vec::new());
+                }
+                let duration_secs = recorded.duration_ms as f64 / 1000.0;
+                self.store_recording_duration_secs(Some(duration_secs));
+                recorded.take_wav()
+            });
+        if result.is_err() {
+            self.clear_realtime_session();
+            self.clear_recording_duration_secs();
+        }
+        result
+    }
+
+    async fn transcribe(&self, wav: RecordedWav) -> Result<PipelineTranscript, AppError> {
+        let recording_secs = wav.duration_secs();
+        // Spooled recordings run for minutes; reading them back just to look
+        // for silence is not worth it, and they are rarely silent throughout.
+        let speech_activity = match &wav {
+            RecordedWav::Memory(wav_bytes) => silence_trim::measure_speech_activity(
+                wav_bytes,
+                self.current_settings().silence_trim_threshold_dbfs,
+            ),
+            RecordedWav::Spooled(_) => None,
+        };
+        // Kept so a flagged transcript can be retried; spooled recordings only
+        // share the file on disk.
+        let retry_wav = wav.clone();
+        let mut transcript = match self.transcribe_recording(wav).await {
+            Ok(transcript) => transcript,
+            Err(error) => return Err(self.queue_when_offline(error, retry_wav)),
+        };
+        self.store_transcript_confidence(transcript.confidence);
+        transcript.detect_missing_language();
+        transcript.text = format_for_profile(
+            &self.current_settings(),
+            &transcript.text,
+            transcript.language.as_deref(),
+        );
+        self.offer_language_alternatives(&transcript, &retry_wav);
+        transcript.translation = self.translate_transcript(&transcript).await;
+
+        let hallucination_filter = self.current_settings().hallucination_filter;
+        let suspected_hallucination = speech_activity
+            .filter(|_| hallucination_filter != HALLUCINATION_FILTER_OFF)
+            .and_then(|activity| {
+                detect_silence_hallucination(&transcript.text, activity).map(|reason| {
+                    SuspectedHallucination {
+                        activity,
+                        reason,
+                        discard: hallucination_filter == HALLUCINATION_FILTER_DISCARD,
+                    }
+                })
+            });
+        if let Some(suspected) = suspected_hallucination {
+            warn!(
+                session_id = ?self.session_id,
+                speech_ms = suspected.activity.speech_ms,
+                recording_ms = suspected.activity.total_ms,
+                reason = ?suspected.reason,
+                discard = suspected.discard,
+                provider = %transcript.provider,
+                "transcript of essentially silent audio looks hallucinated"
+            );
+            self.store_held_transcript(Some(HeldTranscript::SilenceHallucination(suspected)));
+        } else if let Some(recording_secs) = recording_secs {
+            let word_count = count_words(
+                &self.current_settings(),
+                &transcript.text,
+                transcript.language.as_deref(),
+            );
+            if let Some(reason) = check_transcript_plausibility(word_count, recording_secs) {
+                warn!(
+                    session_id = ?self.session_id,
+                    ?reason,
+                    word_count,
+                    recording_secs,
+                    provider = %transcript.provider,
+                    "transcript length is implausible for recording duration"
+                );
+                self.store_held_transcript(Some(HeldTranscript::Implausible(FlaggedRecording {
+                    reason,
+                    recording_secs,
+                    wav: retry_wav,
+                })));
+            }
+        }
+        Ok(transcript)
+    }
+
+    async fn insert_text(&self, transcript: &str) -> Result<(), AppError> {
+        if !self.is_session_live() {
+            warn!(
+                session_id = ?self.session_id,
+                "skipping text insertion for cancelled session"
+            );
+            return Ok(());
+        }
+        let state = self.app.state::<AppState>();
+        let expanded = state.services.snippets_store.expand(transcript);
+        let transcript = expanded.as_deref().unwrap_or(transcript);
+        let redacted = self
+            .current_settings()
+            .redact_before_insertion
+            .then(|| self.redaction_engine().apply(transcript));
+        let transcript = match redacted {
+            Some(redacted) => {
+                self.report_redaction_warnings(redacted.warnings);
+                if redacted.changed && redacted.text.is_empty() {
+                    info!(session_id = ?self.session_id, "transcript was fully redacted");
+                    return Ok(());
+                }
+                Cow::Owned(redacted.text)
+            }
+            None => Cow::Borrowed(transcript),
+        };
+        let transcript = transcript.as_ref();
+
+        info!(
+            session_id = ?self.session_id,
+            transcript_chars = transcript.chars().count(),
+            "inserting transcript text"
+        );
+        let runtime = self.app.state::<PipelineRuntimeState>();
+        let mode = AutoInsertMode::for_transcript(
+            &state.services.settings_store.current(),
+            self.take_transcript_confidence(),
+        );
+        let copy_only = self
+            .session_id
+            .is_some_and(|session_id| runtime.is_copy_only(session_id));
+        let origin_app = self
+            .session_id
+            .and_then(|session_id| runtime.take_origin_app(session_id));
+        let focused_app = self.app.state::<FocusedAppWatcher>().current();
+        let origin_still_focused = origin_app
+            .as_ref()
+            .is_none_or(|origin_app| focused_app.as_ref() == Some(origin_app));
+
+        let decision = mode.decide(copy_only, origin_still_focused);
+        debug!(
+            session_id = ?self.session_id,
+            ?mode,
+            ?decision,
+            "resolved transcript insertion"
+        );
+        let insertion_result = match decision {
+            InsertionDecision::Insert => {
+                insert_text_for_state(&self.app, transcript, focused_app.as_ref(), self.session_id)
+                    .await
+            }
+            InsertionDecision::CopyToClipboard => state
+                .services
+                .text_insertion_service
+                .copy_to_clipboard(transcript)
+                .map(|strategy| {
+                    record_insertion(
+                        &state,
+                        self.session_id,
+                        focused_app.as_ref(),
+                        strategy,
+                        transcript,
+                    )
+                })
+                .map_err(AppError::from),
+            InsertionDecision::AwaitConfirmation => {
+                let pending_insert = PendingInsert {
+                    text: transcript.to_string(),
+                    origin_app: origin_app.clone(),
+                };
+                runtime.set_pending_insert(pending_insert.clone());
+                show_insert_review_window(&self.app);
+                self.app
+                    .emit(EVENT_INSERT_CONFIRMATION_REQUESTED, pending_insert)
+                    .map_err(|error| {
+                        AppError::from(format!("Failed to request insert confirmation: {error}"))
+                    })
+            }
+        };
+
+        if insertion_result.is_ok() {
+            self.record_usage_stats_for_transcript(transcript, origin_app.as_ref());
+        }
+
+        insertion_result
+    }
+
+    fn should_hold_insertion(&self, transcript: &PipelineTranscript) -> bool {
+        let Some(held_transcript) = self.take_held_transcript() else {
+            return false;
+        };
+        if !self.is_session_live() {
+            return false;
+        }
+
+        let runtime = self.app.state::<PipelineRuntimeState>();
+        let origin_app = self
+            .session_id
+            .and_then(|session_id| runtime.take_origin_app(session_id));
+        let flagged_recording = match held_transcript {
+            HeldTranscript::Implausible(flagged_recording) => flagged_recording,
+            HeldTranscript::SilenceHallucination(suspected) => {
+                let text = transcript.text_to_insert().to_string();
+                if !suspected.discard {
+                    let pending_insert = PendingInsert {
+                        text: text.clone(),
+                        origin_app,
+                    };
+                    runtime.set_pending_insert(pending_insert.clone());
+                    show_insert_review_window(&self.app);
+                    if let Err(error) = self
+                        .app
+                        .emit(EVENT_INSERT_CONFIRMATION_REQUESTED, pending_insert)
+                    {
+                        warn!(
+                            session_id = ?self.session_id,
+                            %error,
+                            "failed to request review of suspected hallucination"
+                        );
+                    }
+                }
+                let event = TranscriptSuppressedEvent {
+                    text,
+                    reason: suspected.reason,
+                    discarded: suspected.discard,
+                    speech_ms: suspected.activity.speech_ms,
+                    recording_ms: suspected.activity.total_ms,
+                };
+                trace_session_event(
+                    &self.app,
+                    self.session_id,
+                    EVENT_TRANSCRIPT_SUPPRESSED,
+                    &event,
+                );
+                if let Err(error) = self.app.emit(EVENT_TRANSCRIPT_SUPPRESSED, event) {
+                    warn!(
+                        session_id = ?self.session_id,
+                        %error,
+                        "failed to emit suppressed transcript event"
+                    );
+                }
+                return true;
+            }
+        };
+        let flagged = FlaggedTranscript {
+            text: transcript.text_to_insert().to_string(),
+            reason: flagged_recording.reason,
+            word_count: count_words(
+                &self.current_settings(),
+                &transcript.text,
+                transcript.language.as_deref(),
+            ),
+            recording_secs: flagged_recording.recording_secs,
+            provider: transcript.provider.clone(),
+        };
+        runtime.set_flagged_transcript(flagged.clone(), flagged_recording.wav);
+        trace_session_event(
+            &self.app,
+            self.session_id,
+            EVENT_TRANSCRIPT_FLAGGED,
+            &flagged,
+        );
+        if let Err(error) = self.app.emit(EVENT_TRANSCRIPT_FLAGGED, flagged) {
+            warn!(session_id = ?self.session_id, %error, "failed to emit flagged transcript event");
+        }
+        true
+    }
+
+    fn is_private(&self) -> bool {
+        self.current_settings().private_dictation
+            || self.session_id.is_some_and(|session_id| {
+                self.app
+                    .state::<PipelineRuntimeState>()
+                    .is_private(session_id)
+            })
+    }
+
+    fn transcription_timeout(&self) -> Option<Duration> {
+        Some(Duration::from_secs(u64::from(
+            self.current_settings().transcription_timeout_secs,
+        )))
+    }
+
+    fn save_history_entry(&self, transcript: &PipelineTranscript) -> Result<(), AppError> {
+        if !self.is_session_live() {
+            warn!(
+                session_id = ?self.session_id,
+                "skipping history persistence for cancelled session"
+            );
+            return Ok(());
+        }
+        if self.is_hallucination_discarded() {
+            debug!(
+                session_id = ?self.session_id,
+                "skipping history persistence for suppressed transcript"
+            );
+            return Ok(());
+        }
+
+        let history_store = self.app.state::<HistoryStore>();
+        let (entry, warnings) = rest::history_entry(transcript, &self.redaction_engine());
+        // Insertion already reported these when it redacts too.
+        if !self.current_settings().redact_before_insertion {
+            self.report_redaction_warnings(warnings);
+        }
+        debug!(
+            session_id = ?self.session_id,
+            provider = %entry.provider,
+            transcript_chars = entry.text.chars().count(),
+            "persisting transcript history entry"
+        );
+
+        history_store.add_entry(entry)
+    }
+}
+
+fn get_status_from_state(state: &AppState) -> AppStatus {
+    state
+        .status_notifier
+        .lock()
+        .map(|notifier| notifier.current())
+        .unwrap_or_else(|_| {
+            error!("status notifier lock poisoned while reading status");
+            AppStatus::Error
+        })
+}
+
+fn set_status_for_state(app: &AppHandle, state: &AppState, status: AppStatus) {
+    crash_reporter::record_pipeline_stage(status.as_str());
+    let listening_since = if let Ok(mut notifier) = state.status_notifier.lock() {
+        notifier.set(status);
+        notifier.listening_since()
+    } else {
+        error!("status notifier lock poisoned while setting status");
+        None
+    };
+
+    if status == AppStatus::Idle {
+        app.state::<HotkeyService>().clear_session_binding();
+    }
+
+    set_overlay_visible_for_status(app, status);
+
+    if let Some(tray_menu) = app.try_state::<TrayMenuState>() {
+        match listening_since {
+            Some(listening_since) => tray_menu.show_recording(app, listening_since),
+            None => tray_menu.show_idle(app),
+        }
+    }
+    sync_overlay_keys(app);
+
+    if let Err(error) = app.emit(EVENT_STATUS_CHANGED, status) {
+        warn!(?status, %error, "failed to emit status changed event");
+    }
+}
+
+// Runs off the calling thread because status changes can originate inside a
+// global shortcut handler; see `OverlayKeyService::set_active`.
+fn sync_overlay_keys(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let settings = state.services.settings_store.current();
+        app.state::<OverlayKeyService>().set_active(
+            &app,
+            || get_status_from_state(&state) == AppStatus::Listening,
+            &settings,
+            handle_overlay_key_action,
+        );
+    });
+}
+
+fn sync_retro_shortcut(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let settings = app.state::<AppState>().services.settings_store.current();
+        app.state::<RetroShortcutService>()
+            .apply(&app, &settings, |app| {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(error) = transcribe_retro_buffer(&app, None).await {
+                        warn!(%error, "retro shortcut transcription failed");
+                    }
+                });
+            });
+    });
+}
+
+fn sync_private_shortcut(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let settings = app.state::<AppState>().services.settings_store.current();
+        app.state::<PrivateShortcutService>()
+            .apply(&app, &settings, handle_private_shortcut);
+    });
+}
+
+// Drives recording like the main hotkey in its configured mode, marking the
+// session it starts as private.
+fn handle_private_shortcut(app: &AppHandle, trigger: HotkeyTrigger) {
+    let hotkey_service = app.state::<HotkeyService>();
+    let runtime_state = app.state::<PipelineRuntimeState>();
+    match trigger {
+        HotkeyTrigger::Pressed => {
+            runtime_state.request_private_for_next_session(true);
+            if !hotkey_service.request_recording(app, true) {
+                runtime_state.request_private_for_next_session(false);
+                if hotkey_service.current_config().mode == RecordingMode::Toggle {
+                    hotkey_service.request_recording(app, false);
+                }
+            }
+        }
+        HotkeyTrigger::Released => {
+            if hotkey_service.current_config().mode == RecordingMode::HoldToTalk {
+                hotkey_service.request_recording(app, false);
+            }
+        }
+    }
+}
+
+// Clicking the overlay pill stops and transcribes, like the stop key.
+#[tauri::command]
+fn stop_recording_from_overlay(app: AppHandle, state: tauri::State<'_, AppState>) {
+    let status = get_status_from_state(&state);
+    if status != AppStatus::Listening {
+        debug!(
+            ?status,
+            "ignoring overlay stop because app is not listening"
+        );
+        return;
+    }
+    handle_overlay_key_action(&app, OverlayKeyAction::StopAndInsert);
+}
+
+#[tauri::command]
+fn cancel_recording_from_overlay(app: AppHandle, state: tauri::State<'_, AppState>) {
+    let status = get_status_from_state(&state);
+    if !should_show_overlay_for_status(status) {
+        debug!(
+            ?status,
+            "ignoring overlay cancel because no session is active"
+        );
+        return;
+    }
+    handle_overlay_key_action(&app, OverlayKeyAction::Cancel);
+}
+
+// Called by the overlay once a drag ends. The offset is stored for the display
+// the overlay was dropped on, relative to where its placement put it there.
+#[tauri::command]
+fn save_overlay_offset(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<OverlayOffset, AppError> {
+    let window = app
+        .get_webview_window(OVERLAY_WINDOW_LABEL)
+        .ok_or_else(|| "Recording overlay window is not available".to_string())?;
+    let monitor = window
+        .current_monitor()
+        .map_err(|error| format!("Failed to read overlay display: {error}"))?
+        .ok_or_else(|| "Recording overlay is not on a display".to_string())?;
+    let display_name = monitor
+        .name()
+        .cloned()
+        .ok_or_else(|| "Recording overlay display has no name".to_string())?;
+    let dragged = window
+        .outer_position()
+        .map_err(|error| format!("Failed to read overlay position: {error}"))?
+        .to_logical::<f64>(monitor.scale_factor());
+
+    let settings = state.services.settings_store.current();
+    let stored_base = app
+        .state::<OverlayPositionState>()
+        .base
+        .lock()
+        .ok()
+        .and_then(|base| base.clone());
+    let base = match stored_base {
+        Some((Some(name), base)) if name == display_name => base,
+        _ => overlay_position_from_work_area(
+            monitor.work_area().position,
+            monitor.work_area().size,
+            monitor.scale_factor(),
+            OverlayPlacement::from_settings_value(&settings.overlay_placement),
+            None,
+            OverlayOffset::default(),
+        ),
+    };
+    let offset = overlay_offset_from_drag(base, dragged);
+
+    state
+        .command_guard
+        .run(CommandResource::Settings, "save_overlay_offset", || {
+            let mut overlay_offsets = state.services.settings_store.current().overlay_offsets;
+            overlay_offsets.insert(display_name.clone(), offset);
+            let settings = state.services.settings_store.update(
+                &app,
+                VoiceSettingsUpdate {
+                    overlay_offsets: Some(overlay_offsets),
+                    ..VoiceSettingsUpdate::default()
+                },
+            )?;
+            let saved = settings
+                .overlay_offsets
+                .get(&display_name)
+                .copied()
+                .unwrap_or_default();
+            info!(%display_name, x = saved.x, y = saved.y, "saved overlay offset");
+            Ok(saved)
+        })
+}
+
+fn sync_control_server(app: &AppHandle) {
+    let Some(control_server) = app.try_state::<ControlServer>() else {
+        return;
+    };
+    let settings = app.state::<AppState>().services.settings_store.current();
+    let enabled = settings.control_server_enabled || settings.background_only;
+    let handler_app = app.clone();
+    let handler: ControlHandler = Arc::new(move |request| {
+        let app = handler_app.clone();
+        Box::pin(async move { handle_control_request(&app, request).await })
+    });
+    control_server.set_enabled(enabled, handler);
+}
+
+// Start and stop go through the hotkey service so a scripted session is
+// handled exactly like one started from the keyboard.
+async fn handle_control_request(app: &AppHandle, request: ControlRequest) -> ControlResponse {
+    info!(?request, "control request received");
+    let state = app.state::<AppState>();
+    let result = match request {
+        ControlRequest::Start => {
+            if app.state::<HotkeyService>().request_recording(app, true) {
+                Ok(())
+            } else {
+                Err(AppError::new(
+                    ErrorCode::Conflict,
+                    "Recording is already in progress",
+                ))
+            }
+        }
+        ControlRequest::Stop => {
+            if app.state::<HotkeyService>().request_recording(app, false) {
+                Ok(())
+            } else {
+                complete_recording(app.clone(), app.state::<AppState>()).await
+            }
+        }
+        ControlRequest::Cancel => cancel_recording(app.clone(), app.state::<AppState>()),
+        ControlRequest::Status => Ok(()),
+        ControlRequest::TranscribeFile { path } => {
+            return match transcribe_audio_file(app, path, None, true).await {
+                Ok(entry) => ControlResponse::text(entry.text),
+                Err(error) => ControlResponse::error(error.message),
+            };
+        }
+    };
+    match result {
+        Ok(()) => ControlResponse::status(get_status_from_state(&state)),
+        Err(error) => {
+            warn!(%error, "control request failed");
+            ControlResponse::error(error.message)
+        }
+    }
+}
+
+fn register_automation_handler(app: &AppHandle) {
+    let handler_app = app.clone();
+    let handler: AutomationHandler = Arc::new(move |command| {
+        let app = handler_app.clone();
+        Box::pin(async move { handle_automation_command(&app, command).await })
+    });
+    automation::install(app, handler);
+}
+
+// Like control requests, start and stop go through the hotkey service.
+async fn handle_automation_command(
+    app: &AppHandle,
+    command: AutomationCommand,
+) -> Result<Option<String>, String> {
+    match command {
+        AutomationCommand::StartDictation => {
+            if app.state::<HotkeyService>().request_recording(app, true) {
+                Ok(None)
+            } else {
+                Err("Recording is already in progress".to_string())
+            }
+        }
+        AutomationCommand::StopDictation => stop_dictation_for_automation(app).await.map(Some),
+        AutomationCommand::TranscribeFile { path } => transcribe_audio_file(app, path, None, true)
+            .await
+            .map(|entry| Some(entry.text))
+            .map_err(|error| error.message),
+        AutomationCommand::LastTranscript => Ok(app
+            .state::<HistoryStore>()
+            .list_entries(1, 0)
+            .map_err(|error| error.message)?
+            .into_iter()
+            .next()
+            .map(|entry| entry.text)),
+    }
+}
+
+// Listens for the stopped session's outcome before stopping it, so a fast
+// transcript cannot slip past.
+async fn stop_dictation_for_automation(app: &AppHandle) -> Result<String, String> {
+    let (outcome_tx, outcome_rx) = tokio::sync::oneshot::channel::<Result<String, String>>();
+    let outcome_tx = Arc::new(Mutex::new(Some(outcome_tx)));
+    let send_outcome = move |outcome: Result<String, String>| {
+        if let Some(outcome_tx) = outcome_tx.lock().ok().and_then(|mut sender| sender.take()) {
+            let _ = outcome_tx.send(outcome);
+        }
+    };
+    let payload_field = |payload: &str, field: &str| {
+        serde_json::from_str::<serde_json::Value>(payload)
+            .ok()
+            .and_then(|payload| payload.get(field)?.as_str().map(str::to_string))
+            .unwrap_or_default()
+    };
+    let on_transcript = send_outcome.clone();
+    let transcript_listener = app.listen(EVENT_TRANSCRIPT_READY, move |event| {
+        on_transcript(Ok(payload_field(event.payload(), "text")));
+    });
+    let error_listener = app.listen(EVENT_PIPELINE_ERROR, move |event| {
+        send_outcome(Err(payload_field(event.payload(), "message")));
+    });
+
+    let stopped = if app.state::<HotkeyService>().request_recording(app, false) {
+        Ok(())
+    } else {
+        complete_recording(app.clone(), app.state::<AppState>())
+            .await
+            .map_err(|error| error.message)
+    };
+    let timeout_secs = app
+        .state::<AppState>()
+        .services
+        .settings_store
+        .current()
+        .transcription_timeout_secs;
+    let outcome = match stopped {
+        Ok(()) => {
+            match tokio::time::timeout(Duration::from_secs(u64::from(timeout_secs)), outcome_rx)
+                .await
+            {
+                Ok(Ok(outcome)) => outcome,
+                _ => Err("Timed out waiting for the transcript".to_string()),
+            }
+        }
+        Err(error) => Err(error),
+    };
+    app.unlisten(transcript_listener);
+    app.unlisten(error_listener);
+    outcome
+}
+
+fn handle_deep_link(app: &AppHandle, url: &tauri::Url) {
+    let action = match deep_link::parse_deep_link(url) {
+        Ok(action) => action,
+        Err(error) => {
+            warn!(%url, %error, "ignoring deep link");
+            return;
+        }
+    };
+    info!(%url, ?action, "handling deep link");
+
+    match action {
+        DeepLinkAction::Record { profile } => {
+            let runtime_state = app.state::<PipelineRuntimeState>();
+            runtime_state.request_style_for_next_session(profile);
+            if !app.state::<HotkeyService>().request_recording(app, true) {
+                runtime_state.request_style_for_next_session(None);
+                warn!("deep link asked to record while already recording");
+            }
+        }
+        DeepLinkAction::Stop => {
+            if !app.state::<HotkeyService>().request_recording(app, false) {
+                debug!("deep link asked to stop while not recording");
+            }
+        }
+        DeepLinkAction::Open(navigation) => navigate_main_window(app, &navigation),
+    }
+}
+
+fn navigate_main_window(app: &AppHandle, navigation: &DeepLinkNavigation) {
+    show_main_window(app);
+    if let Err(error) = app.emit_to("main", EVENT_DEEP_LINK_NAVIGATE, navigation) {
+        warn!(%error, "failed to emit deep link navigation");
+    }
+}
+
+fn register_deep_link_handler(app: &AppHandle) {
+    let handler_app = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle_deep_link(&handler_app, &url);
+        }
+    });
+}
+
+fn handle_overlay_key_action(app: &AppHandle, action: OverlayKeyAction) {
+    if action == OverlayKeyAction::Cancel {
+        if let Err(error) = cancel_recording(app.clone(), app.state::<AppState>()) {
+            warn!(%error, "failed to cancel recording from overlay key");
+        }
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let runtime_state = app.state::<PipelineRuntimeState>();
+        if let Some(session_id) = runtime_state.active_session_id() {
+            match action {
+                OverlayKeyAction::CopyOnly => runtime_state.mark_copy_only(session_id),
+                OverlayKeyAction::Private => runtime_state.mark_private(session_id),
+                OverlayKeyAction::Cancel | OverlayKeyAction::StopAndInsert => {}
+            }
+        }
+        app.state::<HotkeyService>().force_stop_recording(&app);
+        if let Err(error) = complete_recording(app.clone(), app.state::<AppState>()).await {
+            warn!(%error, "failed to complete recording from overlay key");
+        }
+    });
+}
+
+fn set_status_for_app(app: &AppHandle, status: AppStatus) {
+    let state = app.state::<AppState>();
+    set_status_for_state(app, &state, status);
+}
+
+fn emit_transcript_event(
+    app: &AppHandle,
+    session_id: Option<u64>,
+    transcript: &PipelineTranscript,
+) {
+    let translated = transcript
+        .translation
+        .as_ref()
+        .is_some_and(|translation| translation.insert);
+    let (segments, words) = if translated {
+        (Vec::new(), Vec::new())
+    } else {
+        (transcript.segments.clone(), transcript.words.clone())
+    };
+    let payload = TranscriptReadyEvent {
+        text: transcript.text_to_insert().to_string(),
+        confidence: transcript.confidence,
+        segments,
+        words,
+    };
+    trace_session_event(app, session_id, EVENT_TRANSCRIPT_READY, &payload);
+    if let Err(error) = app.emit(EVENT_TRANSCRIPT_READY, payload) {
+        warn!(%error, "failed to emit transcript ready event");
+    }
+}
+
+fn play_sound_cue(app: &AppHandle, cue: SoundCue) {
+    let state = app.state::<AppState>();
+    let settings = state.services.settings_store.current();
+    if settings.sound_feedback_enabled {
+        state
+            .services
+            .sound_feedback_service
+            .play(cue, settings.sound_feedback_volume_percent);
+    }
+}
+
+fn emit_transcription_delta_event(app: &AppHandle, delta: &str) {
+    if let Err(error) = app.emit(EVENT_TRANSCRIPTION_DELTA, delta.to_string()) {
+        warn!(%error, "failed to emit transcription delta event");
+    }
+}
+
+fn emit_transcription_retry_event(app: &AppHandle, event: TranscriptionRetryEvent) {
+    if let Err(error) = app.emit(EVENT_TRANSCRIPTION_RETRY, event) {
+        warn!(%error, "failed to emit transcription retry event");
+    }
+}
+
+// Traces can carry transcript text, so private sessions are skipped along
+// with anything outside a pipeline session, and text is redacted first or
+// dropped entirely when history is encrypted.
+fn trace_session_event<T: Serialize>(
+    app: &AppHandle,
+    session_id: Option<u64>,
+    event: &str,
+    payload: &T,
+) {
+    let Some(session_id) = session_id else {
+        return;
+    };
+    let state = app.state::<AppState>();
+    let settings = state.services.settings_store.current();
+    if !state.services.session_trace.is_enabled()
+        || settings.private_dictation
+        || app.state::<PipelineRuntimeState>().is_private(session_id)
+    {
+        return;
+    }
+    let mut payload = match serde_json::to_value(payload) {
+        Ok(payload) => payload,
+        Err(error) => {
+            warn!(session_id, event, %error, "failed to serialize session trace payload");
+            return;
+        }
+    };
+    if event == EVENT_TRANSCRIPTION_DELTA {
+        // A redaction match can straddle two deltas, so only their length is
+        // kept.
+        let chars = payload.as_str().map_or(0, |delta| delta.chars().count());
+        payload = serde_json::json!({ "chars": chars });
+    } else if settings.history_encryption {
+        // Encrypted history means transcripts never touch disk in plaintext.
+        scrub_transcript_text(&mut payload, &|_| None);
+    } else {
+        let redaction = RedactionEngine::new(&settings.redaction_rules).unwrap_or_else(|error| {
+            warn!(session_id, %error, "ignoring invalid redaction rules");
+            RedactionEngine::default()
+        });
+        scrub_transcript_text(&mut payload, &|text| Some(redaction.apply(text).text));
+    }
+    if let Err(error) = state
+        .services
+        .session_trace
+        .record(session_id, event, &payload)
+    {
+        warn!(session_id, event, %error, "failed to record session trace event");
+    }
+}
+
+// Nothing is recording at launch, so any checkpoint left on disk was orphaned
+// by a crash.
+fn announce_recovered_recordings(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let Some(recovery_dir) = state.services.audio_capture_service.recovery_dir() else {
+        return;
+    };
+    let recordings = audio_capture_service::list_recovered_recordings(recovery_dir);
+    if recordings.is_empty() {
+        return;
+    }
+
+    warn!(
+        count = recordings.len(),
+        "found recordings orphaned by a previous crash"
+    );
+    if let Err(error) = app.emit(EVENT_RECOVERED_RECORDINGS_FOUND, recordings) {
+        warn!(%error, "failed to emit recovered recordings event");
+    }
+}
+
+// Another copy of the app fights over the hotkey and microphone, so tell the
+// user which one and how to get rid of it.
+fn announce_conflicting_instances(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let Some(report) = instance_conflicts::detect() else {
+            return;
+        };
+        warn!(
+            count = report.instances.len(),
+            legacy = report.instances.iter().any(|instance| instance.legacy),
+            "found conflicting app instances"
+        );
+        if let Err(error) = app.emit(EVENT_CONFLICTING_INSTANCE, report) {
+            warn!(%error, "failed to emit conflicting instance event");
+        }
+    });
+}
+
+fn emit_pipeline_error_event(app: &AppHandle, session_id: Option<u64>, error: &PipelineError) {
+    crash_reporter::record_pipeline_stage(error.stage.as_str());
+    record_telemetry(
+        app,
+        TelemetryEventKind::DictationFailed {
+            stage: error.stage.as_str().to_string(),
+            error_code: error.code,
+        },
+    );
+    let auth_method = app
+        .state::<AppState>()
+        .services
+        .current_auth_method()
+        .unwrap_or(AuthMethod::None);
+    let action = route_error(&error.stage, error.code, auth_method);
+    let payload = PipelineErrorEvent {
+        stage: error.stage.as_str().to_string(),
+        code: error.code,
+        message: error.message.clone(),
+        recoverable: error.code.recoverable(),
+        hint: error.code.hint().map(str::to_string),
+        action,
+        help_url: action.and_then(ErrorAction::help_url).map(str::to_string),
+    };
+    trace_session_event(app, session_id, EVENT_PIPELINE_ERROR, &payload);
+
+    if let Err(emit_error) = app.emit(EVENT_PIPELINE_ERROR, payload) {
+        warn!(
+            stage = error.stage.as_str(),
+            event_error = %emit_error,
+            "failed to emit pipeline error event"
+        );
+    }
+}
+
+fn should_show_overlay_for_status(status: AppStatus) -> bool {
+    matches!(status, AppStatus::Listening | AppStatus::Transcribing)
+}
+
+// Where the recording overlay sits, from the `overlay_placement` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OverlayPlacement {
+    TopCenter,
+    BottomCenter,
+    NearCursor,
+    NearCaret,
+    FixedDisplay,
+}
+
+impl OverlayPlacement {
+    fn from_settings_value(value: &str) -> Self {
+        match value {
+            OVERLAY_PLACEMENT_BOTTOM_CENTER => Self::BottomCenter,
+            OVERLAY_PLACEMENT_NEAR_CURSOR => Self::NearCursor,
+            OVERLAY_PLACEMENT_NEAR_CARET => Self::NearCaret,
+            OVERLAY_PLACEMENT_FIXED_DISPLAY => Self::FixedDisplay,
+            _ => Self::TopCenter,
+        }
+    }
+}
+
+// Anchored placements sit just below the anchor, or above it when there is no
+// room underneath. The per-display offset is applied last and the result is
+// kept inside the work area.
+fn overlay_position_from_work_area(
+    work_area_position: PhysicalPosition<i32>,
+    work_area_size: PhysicalSize<u32>,
+    scale_factor: f64,
+    placement: OverlayPlacement,
+    anchor: Option<LogicalPosition<f64>>,
+    offset: OverlayOffset,
+) -> LogicalPosition<f64> {
+    let work_area_x = f64::from(work_area_position.x) / scale_factor;
+    let work_area_y = f64::from(work_area_position.y) / scale_factor;
+    let work_area_width = f64::from(work_area_size.width) / scale_factor;
+    let work_area_height = f64::from(work_area_size.height) / scale_factor;
+    let work_area_bottom = work_area_y + work_area_height;
+    let centered_x = work_area_x + ((work_area_width - OVERLAY_WINDOW_WIDTH) / 2.0).max(0.0);
+
+    let (x, y) = match (placement, anchor) {
+        (OverlayPlacement::BottomCenter, _) => (
+            centered_x,
+            work_area_bottom - OVERLAY_WINDOW_HEIGHT - OVERLAY_WINDOW_TOP_MARGIN,
+        ),
+        (OverlayPlacement::NearCursor | OverlayPlacement::NearCaret, Some(anchor)) => {
+            let below = anchor.y + OVERLAY_ANCHOR_GAP;
+            let y = if below + OVERLAY_WINDOW_HEIGHT > work_area_bottom {
+                anchor.y - OVERLAY_ANCHOR_GAP - OVERLAY_WINDOW_HEIGHT
+            } else {
+                below
+            };
+            (anchor.x - OVERLAY_WINDOW_WIDTH / 2.0, y)
+        }
+        _ => (centered_x, work_area_y + OVERLAY_WINDOW_TOP_MARGIN),
+    };
+
+    let max_x = (work_area_x + work_area_width - OVERLAY_WINDOW_WIDTH).max(work_area_x);
+    let max_y = (work_area_bottom - OVERLAY_WINDOW_HEIGHT).max(work_area_y);
+    LogicalPosition::new(
+        (x + f64::from(offset.x)).clamp(work_area_x, max_x),
+        (y + f64::from(offset.y)).clamp(work_area_y, max_y),
+    )
+}
+
+fn monitor_containing_logical_point(
+    app: &AppHandle,
+    point: LogicalPosition<f64>,
+) -> Option<Monitor> {
+    app.available_monitors().ok()?.into_iter().find(|monitor| {
+        let scale_factor = monitor.scale_factor();
+        let x = f64::from(monitor.position().x) / scale_factor;
+        let y = f64::from(monitor.position().y) / scale_factor;
+        let width = f64::from(monitor.size().width) / scale_factor;
+        let height = f64::from(monitor.size().height) / scale_factor;
+        (x..x + width).contains(&point.x) && (y..y + height).contains(&point.y)
+    })
+}
+
+// Picks the display for the overlay and, for anchored placements, the point
+// to sit next to. A caret the focused app does not expose falls back to the
+// cursor, and a fixed display that is not connected falls back to the
+// cursor's display.
+fn resolve_overlay_target(
+    app: &AppHandle,
+    placement: OverlayPlacement,
+    settings: &VoiceSettings,
+) -> Option<(Monitor, Option<LogicalPosition<f64>>)> {
+    if placement == OverlayPlacement::NearCaret {
+        if let Some((x, y)) = focused_caret_position() {
+            let caret = LogicalPosition::new(x, y);
+            if let Some(monitor) = monitor_containing_logical_point(app, caret) {
+                return Some((monitor, Some(caret)));
+            }
+        }
+    }
+    if placement == OverlayPlacement::FixedDisplay {
+        let fixed_monitor = settings.overlay_display.as_ref().and_then(|display| {
+            app.available_monitors()
+                .ok()?
+                .into_iter()
+                .find(|monitor| monitor.name() == Some(display))
+        });
+        if let Some(monitor) = fixed_monitor {
+            return Some((monitor, None));
+        }
+    }
+
+    if let Ok(cursor) = app.cursor_position() {
+        if let Ok(Some(cursor_monitor)) = app.monitor_from_point(cursor.x, cursor.y) {
+            let scale_factor = cursor_monitor.scale_factor();
+            let anchor = LogicalPosition::new(cursor.x / scale_factor, cursor.y / scale_factor);
+            return Some((cursor_monitor, Some(anchor)));
+        }
+    }
+
+    if let Some(main_window) = app.get_webview_window("main") {
+        if let Ok(Some(main_monitor)) = main_window.current_monitor() {
+            return Some((main_monitor, None));
+        }
+    }
+
+    app.primary_monitor()
+        .ok()
+        .flatten()
+        .map(|monitor| (monitor, None))
+}
+
+// The offset that moves the overlay from where its placement put it to where
+// it was dragged.
+fn overlay_offset_from_drag(
+    base: LogicalPosition<f64>,
+    dragged: LogicalPosition<f64>,
+) -> OverlayOffset {
+    OverlayOffset {
+        x: (dragged.x - base.x).round() as i32,
+        y: (dragged.y - base.y).round() as i32,
+    }
+}
+
+// Where the overlay was last placed before its per-display offset, so a drag
+// can be stored relative to it.
+#[derive(Debug, Default)]
+struct OverlayPositionState {
+    base: Mutex<Option<(Option<String>, LogicalPosition<f64>)>>,
+}
+
+fn position_overlay_window(window: &WebviewWindow, app: &AppHandle) {
+    let settings = app
+        .try_state::<AppState>()
+        .map(|state| state.services.settings_store.current())
+        .unwrap_or_default();
+    let placement = OverlayPlacement::from_settings_value(&settings.overlay_placement);
+    if let Some((monitor, anchor)) = resolve_overlay_target(app, placement, &settings) {
+        let offset = monitor
+            .name()
+            .and_then(|name| settings.overlay_offsets.get(name))
+            .copied()
+            .unwrap_or_default();
+        let place = |offset| {
+            overlay_position_from_work_area(
+                monitor.work_area().position,
+                monitor.work_area().size,
+                monitor.scale_factor(),
+                placement,
+                anchor,
+                offset,
+            )
+        };
+        if let Some(position_state) = app.try_state::<OverlayPositionState>() {
+            if let Ok(mut base) = position_state.base.lock() {
+                *base = Some((monitor.name().cloned(), place(OverlayOffset::default())));
+            }
+        }
+        let position = place(offset);
+        debug!(?placement, ?position, "positioning recording overlay");
+        if let Err(error) = window.set_position(position) {
+            warn!(%error, "failed to position recording overlay");
+        }
+    }
+}
+
+fn create_recording_overlay_window(app: &AppHandle) -> Result<WebviewWindow, String> {
+    let window = WebviewWindowBuilder::new(
+        app,
+        OVERLAY_WINDOW_LABEL,
+        WebviewUrl::App("overlay.html".into()),
+    )
+    .title("Voice Recording Overlay")
+    .inner_size(OVERLAY_WINDOW_WIDTH, OVERLAY_WINDOW_HEIGHT)
+    .min_inner_size(OVERLAY_WINDOW_WIDTH, OVERLAY_WINDOW_HEIGHT)
+    .max_inner_size(OVERLAY_WINDOW_WIDTH, OVERLAY_WINDOW_HEIGHT)
+    .resizable(false)
+    .decorations(false)
+    .shadow(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .visible_on_all_workspaces(true)
+    .focusable(false)
+    .focused(false)
+    .visible(false)
+    .transparent(true)
+    .build()
+    .map_err(|error| format!("failed to create recording overlay window: {error}"))?;
+
+    if let Err(error) = window.set_focusable(false) {
+        warn!(%error, "failed to set recording overlay as non-focusable");
+    }
+    if let Err(error) = window.set_ignore_cursor_events(false) {
+        warn!(%error, "failed to enable recording overlay cursor events");
+    }
+
+    position_overlay_window(&window, app);
+    Ok(window)
+}
+
+fn setup_recording_overlay_window(app: &AppHandle) {
+    if app.get_webview_window(OVERLAY_WINDOW_LABEL).is_some() {
+        return;
+    }
+
+    match create_recording_overlay_window(app) {
+        Ok(_) => info!("recording overlay window initialized"),
+        Err(error) => warn!(%error, "recording overlay window initialization failed"),
+    }
+}
+
+fn set_overlay_visible_for_status(app: &AppHandle, status: AppStatus) {
+    let Some(window) = app.get_webview_window(OVERLAY_WINDOW_LABEL) else {
+        return;
+    };
+
+    if should_show_overlay_for_status(status) {
+        position_overlay_window(&window, app);
+        if let Err(error) = window.show() {
+            warn!(%error, "failed to show recording overlay window");
+        }
+    } else if let Err(error) = window.hide() {
+        warn!(%error, "failed to hide recording overlay window");
+    }
+}
+
+// The review window takes focus so the transcript can be edited before it is
+// inserted; it is created hidden at startup like the overlay.
+fn create_insert_review_window(app: &AppHandle) -> Result<WebviewWindow, String> {
+    WebviewWindowBuilder::new(
+        app,
+        INSERT_REVIEW_WINDOW_LABEL,
+        WebviewUrl::App("insert-review.html".into()),
+    )
+    .title("Review Transcript")
+    .inner_size(INSERT_REVIEW_WINDOW_WIDTH, INSERT_REVIEW_WINDOW_HEIGHT)
+    .min_inner_size(INSERT_REVIEW_WINDOW_WIDTH, INSERT_REVIEW_WINDOW_HEIGHT)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .visible_on_all_workspaces(true)
+    .visible(false)
+    .build()
+    .map_err(|error| format!("failed to create insert review window: {error}"))
+}
+
+fn setup_insert_review_window(app: &AppHandle) {
+    if app.get_webview_window(INSERT_REVIEW_WINDOW_LABEL).is_some() {
+        return;
+    }
+
+    match create_insert_review_window(app) {
+        Ok(_) => info!("insert review window initialized"),
+        Err(error) => warn!(%error, "insert review window initialization failed"),
+    }
+}
+
+fn show_insert_review_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(INSERT_REVIEW_WINDOW_LABEL) else {
+        warn!("insert review window is unavailable");
+        return;
+    };
+
+    if let Err(error) = window.center() {
+        warn!(%error, "failed to center insert review window");
+    }
+    if let Err(error) = window.show() {
+        warn!(%error, "failed to show insert review window");
+    }
+    if let Err(error) = window.set_focus() {
+        warn!(%error, "failed to focus insert review window");
+    }
+}
+
+// Hiding the whole app hands focus back to whatever was frontmost before the
+// review window appeared, unless the main window is in use.
+fn hide_insert_review_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(INSERT_REVIEW_WINDOW_LABEL) {
+        if let Err(error) = window.hide() {
+            warn!(%error, "failed to hide insert review window");
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let main_visible = app
+            .get_webview_window("main")
+            .and_then(|window| window.is_visible().ok())
+            .unwrap_or(false);
+        if !main_visible {
+            if let Err(error) = app.hide() {
+                warn!(%error, "failed to return focus after insert review");
+            }
+        }
+    }
+}
+
+fn register_overlay_audio_forwarder(app: &AppHandle) {
+    let overlay_app = app.clone();
+    app.listen(AUDIO_LEVEL_EVENT, move |event| {
+        let level = serde_json::from_str::<f32>(event.payload()).unwrap_or_else(|error| {
+            warn!(%error, payload = event.payload(), "invalid audio-level payload");
+            0.0
+        });
+        if let Err(error) = overlay_app.emit_to(
+            EventTarget::webview_window(OVERLAY_WINDOW_LABEL),
+            EVENT_OVERLAY_AUDIO_LEVEL,
+            level,
+        ) {
+            warn!(%error, "failed to forward audio level to recording overlay");
+        }
+    });
+
+    let overlay_app = app.clone();
+    app.listen(AUDIO_SPECTRUM_EVENT, move |event| {
+        let spectrum =
+            serde_json::from_str::<AudioSpectrumEvent>(event.payload()).unwrap_or_else(|error| {
+                warn!(%error, "invalid audio-spectrum payload");
+                AudioSpectrumEvent::silent()
+            });
+        if let Err(error) = overlay_app.emit_to(
+            EventTarget::webview_window(OVERLAY_WINDOW_LABEL),
+            EVENT_OVERLAY_AUDIO_SPECTRUM,
+            spectrum,
+        ) {
+            warn!(%error, "failed to forward audio spectrum to recording overlay");
+        }
+    });
+}
+
+fn parse_audio_stream_error_message(payload: &str) -> String {
+    serde_json::from_str::<AudioInputStreamErrorEvent>(payload)
+        .ok()
+        .map(|event| event.message.trim().to_string())
+        .filter(|message| !message.is_empty())
+        .unwrap_or_else(|| {
+            warn!("audio stream error payload was invalid; using fallback message");
+            "Microphone stream failed unexpectedly".to_string()
+        })
+}
+
+fn get_launch_at_login_state(app: &AppHandle) -> Result<bool, String> {
+    app.autolaunch()
+        .is_enabled()
+        .map_err(|error| format!("Failed to get launch-at-login state: {error}"))
+}
+
+fn set_launch_at_login_state(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    let result = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+
+    result.map_err(|error| format!("Failed to set launch-at-login state: {error}"))
+}
+
+fn ensure_microphone_permission_for_recording(state: &AppState) -> Result<(), AppError> {
+    ensure_permission_for_action(
+        state.services.permission_service.microphone_permission(),
+        PermissionType::Microphone,
+        "start recording",
+    )
+}
+
+fn ensure_accessibility_permission_for_insertion(state: &AppState) -> Result<(), AppError> {
+    ensure_permission_for_action(
+        state.services.permission_service.accessibility_permission(),
+        PermissionType::Accessibility,
+        "insert text",
+    )
+}
+
+fn ensure_permission_for_action(
+    permission_state: PermissionState,
+    permission_type: PermissionType,
+    action: &str,
+) -> Result<(), AppError> {
+    if permission_state == PermissionState::Granted {
+        return Ok(());
+    }
+
+    Err(AppError::new(
+        ErrorCode::PermissionDenied,
+        permission_preflight_error_message(permission_type, permission_state, action),
+    ))
+}
+
+fn permission_preflight_error_message(
+    permission_type: PermissionType,
+    permission_state: PermissionState,
+    action: &str,
+) -> String {
+    match permission_type {
+        PermissionType::Microphone => match permission_state {
+            PermissionState::NotDetermined => format!(
+                "Microphone access has not been granted yet, so Voice cannot {action}. Click Grant Access in the app and allow Voice in System Settings > Privacy & Security > Microphone."
+            ),
+            PermissionState::Denied => format!(
+                "Microphone access is denied, so Voice cannot {action}. Enable Voice in System Settings > Privacy & Security > Microphone, then try again."
+            ),
+            PermissionState::Granted => String::new(),
+        },
+        PermissionType::Accessibility => match permission_state {
+            PermissionState::NotDetermined | PermissionState::Denied => format!(
+                "Accessibility access is required to {action}. Enable Voice in System Settings > Privacy & Security > Accessibility, then try again."
+            ),
+            PermissionState::Granted => String::new(),
+        },
+    }
+}
+
+fn cancel_recording_with_hooks<CancelSessions, ForceStopRecording, AbortRecording, SetStatus>(
+    mut cancel_sessions: CancelSessions,
+    mut force_stop_recording: ForceStopRecording,
+    mut abort_recording: AbortRecording,
+    mut set_status: SetStatus,
+) -> Result<bool, String>
+where
+    CancelSessions: FnMut(),
+    ForceStopRecording: FnMut(),
+    AbortRecording: FnMut() -> Result<bool, String>,
+    SetStatus: FnMut(AppStatus),
+{
+    cancel_sessions();
+    force_stop_recording();
+    let abort_result = abort_recording();
+    set_status(AppStatus::Idle);
+    abort_result
+}
+
+fn handle_audio_input_stream_error_with_hooks<
+    BeginSession,
+    ForceStopRecording,
+    AbortRecording,
+    EmitPipelineError,
+    SetStatus,
+    ScheduleReset,
+>(
+    message: String,
+    mut begin_session: BeginSession,
+    mut force_stop_recording: ForceStopRecording,
+    mut abort_recording: AbortRecording,
+    mut emit_pipeline_error: EmitPipelineError,
+    mut set_status: SetStatus,
+    schedule_reset: ScheduleReset,
+) where
+    BeginSession: FnMut(),
+    ForceStopRecording: FnMut(),
+    AbortRecording: FnMut() -> Result<(), String>,
+    EmitPipelineError: FnMut(&PipelineError),
+    SetStatus: FnMut(AppStatus),
+    ScheduleReset: FnOnce(),
+{
+    error!(message = %message, "handling runtime audio stream error");
+    begin_session();
+    force_stop_recording();
+
+    if let Err(error) = abort_recording() {
+        error!(%error, "failed to abort recording after stream error");
+    }
+
+    let pipeline_error = PipelineError::new(
+        voice_pipeline::PipelineErrorStage::RecordingRuntime,
+        AppError::from(message),
+    );
+    emit_pipeline_error(&pipeline_error);
+    set_status(AppStatus::Error);
+    schedule_reset();
+}
+
+fn handle_audio_input_stream_error(app: &AppHandle, message: String) {
+    error!(message = %message, "audio stream error received by app");
+    let reset_app = app.clone();
+    handle_audio_input_stream_error_with_hooks(
+        message,
+        || {
+            let runtime_state = app.state::<PipelineRuntimeState>();
+            runtime_state.begin_session();
+        },
+        || {
+            let hotkey_service = app.state::<HotkeyService>();
+            hotkey_service.force_stop_recording(app);
+        },
+        || {
+            let state = app.state::<AppState>();
+            state
+                .services
+                .audio_capture_service
+                .abort_recording(app.clone())
+                .map(|_| ())
+        },
+        |error| emit_pipeline_error_event(app, None, error),
+        |status| {
+            let state = app.state::<AppState>();
+            set_status_for_state(app, &state, status);
+        },
+        move || {
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(AUDIO_STREAM_ERROR_RESET_DELAY_MS)).await;
+                let state = reset_app.state::<AppState>();
+                if get_status_from_state(&state) == AppStatus::Error {
+                    info!(
+                        delay_ms = AUDIO_STREAM_ERROR_RESET_DELAY_MS,
+                        "resetting status to idle after stream error"
+                    );
+                    set_status_for_state(&reset_app, &state, AppStatus::Idle);
+                }
+            });
+        },
+    );
+}
+
+fn spawn_pipeline_stage_error_reset<D>(
+    pipeline: VoicePipeline,
+    delegate: D,
+    stage: voice_pipeline::PipelineErrorStage,
+    error: AppError,
+) -> tauri::async_runtime::JoinHandle<()>
+where
+    D: VoicePipelineDelegate + Send + Sync + 'static,
+{
+    error!(stage = stage.as_str(), %error, "scheduling stage error reset");
+    tauri::async_runtime::spawn(async move {
+        pipeline.handle_stage_error(&delegate, stage, error).await;
+    })
+}
+
+fn active_pipeline_session_id(runtime_state: &PipelineRuntimeState) -> Option<u64> {
+    runtime_state.active_session_id()
+}
+
+fn resolve_or_begin_pipeline_session(runtime_state: &PipelineRuntimeState) -> u64 {
+    runtime_state
+        .active_session_id()
+        .unwrap_or_else(|| runtime_state.begin_session())
+}
+
+fn register_pipeline_handlers(app: &AppHandle) {
+    info!("registering pipeline event handlers");
+    let start_app = app.clone();
+    app.listen(hotkey_service::EVENT_RECORDING_STARTED, move |event| {
+        debug!(
+            event_id = event.id(),
+            "received recording started hotkey event"
+        );
+        let app = start_app.clone();
+        let runtime_state = app.state::<PipelineRuntimeState>().inner().clone();
+        tauri::async_runtime::spawn(async move {
+            let guard = runtime_state.execution_lock.lock().await;
+            let session_id = runtime_state.begin_session();
+            runtime_state.adopt_requested_style(session_id);
+            runtime_state.adopt_requested_private(session_id);
+            let delegate = AppPipelineDelegate::for_session(app.clone(), session_id);
+            VoicePipeline::default()
+                .handle_hotkey_started(&delegate)
+                .await;
+
+            handle_pending_stop_transition(&app, &runtime_state, delegate, guard).await;
+        });
+    });
+
+    let stop_app = app.clone();
+    app.listen(hotkey_service::EVENT_RECORDING_STOPPED, move |event| {
+        debug!(
+            event_id = event.id(),
+            "received recording stopped hotkey event"
+        );
+        let app = stop_app.clone();
+        let runtime_state = app.state::<PipelineRuntimeState>().inner().clone();
+        tauri::async_runtime::spawn(async move {
+            let guard = runtime_state.execution_lock.lock().await;
+            let hotkey_service = app.state::<HotkeyService>();
+            let stop_decision = hotkey_service.stop_processing_decision();
+            drop(hotkey_service);
+
+            match stop_decision {
+                StopProcessingDecision::Process => {
+                    let Some(session_id) = active_pipeline_session_id(&runtime_state) else {
+                        warn!(
+                            "received stop event with no active pipeline session; acknowledging stop as failed"
+                        );
+                        let hotkey_service = app.state::<HotkeyService>();
+                        hotkey_service
+                            .acknowledge_transition(RecordingTransition::Stopped, false);
+                        return;
+                    };
+                    let delegate = AppPipelineDelegate::for_session(app.clone(), session_id);
+                    process_stopped_session(&runtime_state, delegate, guard).await;
+                }
+                StopProcessingDecision::AcknowledgeOnly => {
+                    warn!("received stop event while hotkey service was not recording");
+                    let hotkey_service = app.state::<HotkeyService>();
+                    hotkey_service.acknowledge_transition(RecordingTransition::Stopped, false);
+                }
+                StopProcessingDecision::DeferUntilStarted => {
+                    debug!("deferring stop processing until start transition is acknowledged");
+                }
+                StopProcessingDecision::Ignore => {
+                    debug!("ignoring redundant stop event");
+                }
+            }
+        });
+    });
+
+    let stream_error_app = app.clone();
+    app.listen(AUDIO_INPUT_STREAM_ERROR_EVENT, move |event| {
+        let message = parse_audio_stream_error_message(event.payload());
+        error!(
+            event_id = event.id(),
+            message = %message,
+            "received audio input stream error event"
+        );
+        handle_audio_input_stream_error(&stream_error_app, message);
+    });
+}
+
+async fn handle_pending_stop_transition(
+    app: &AppHandle,
+    runtime_state: &PipelineRuntimeState,
+    delegate: AppPipelineDelegate,
+    execution_guard: tokio::sync::MutexGuard<'_, ()>,
+) {
+    let stop_decision = {
+        let hotkey_service = app.state::<HotkeyService>();
+        hotkey_service.stop_processing_decision()
+    };
+
+    match stop_decision {
+        StopProcessingDecision::Process => {
+            process_stopped_session(runtime_state, delegate, execution_guard).await;
+        }
+        StopProcessingDecision::AcknowledgeOnly => {
+            let hotkey_service = app.state::<HotkeyService>();
+            hotkey_service.acknowledge_transition(RecordingTransition::Stopped, false);
+        }
+        StopProcessingDecision::DeferUntilStarted | StopProcessingDecision::Ignore => {}
+    }
+}
+
+// Stops the recording while the execution lock is held, then releases the lock
+// before transcribing so the next dictation can start during the upload.
+async fn process_stopped_session(
+    runtime_state: &PipelineRuntimeState,
+    delegate: AppPipelineDelegate,
+    execution_guard: tokio::sync::MutexGuard<'_, ()>,
+) {
+    let pipeline = VoicePipeline::default();
+    let session_id = delegate.session_id;
+    let ticket = session_id.and_then(|session_id| runtime_state.take_queue_ticket(session_id));
+    if let Some(wav) = pipeline.stop_recording_for_transcription(&delegate).await {
+        let delegate = delegate.detach_realtime_session();
+        drop(execution_guard);
+        debug!(
+            session_id = ?delegate.session_id,
+            queued_sessions = runtime_state.session_queue.in_flight(),
+            "transcribing stopped session outside the execution lock"
+        );
+        pipeline.transcribe_and_insert(&delegate, wav, ticket).await;
+    }
+    if let Some(session_id) = session_id {
+        runtime_state.finish_session(session_id);
+    }
+}
+
+#[tauri::command]
+fn get_status(state: tauri::State<'_, AppState>) -> AppStatus {
+    let status = get_status_from_state(&state);
+    debug!(?status, "status requested");
+    status
+}
+
+#[tauri::command]
+fn set_status(app: AppHandle, status: AppStatus, state: tauri::State<'_, AppState>) {
+    info!(?status, "status set requested");
+    set_status_for_state(&app, &state, status);
+}
+
+#[tauri::command]
+fn get_status_details(
+    state: tauri::State<'_, AppState>,
+    hotkey_service: tauri::State<'_, HotkeyService>,
+) -> StatusDetails {
+    let status = get_status_from_state(&state);
+    let active_binding = match status {
+        AppStatus::Idle => None,
+        _ => hotkey_service.session_binding(),
+    };
+    debug!(?status, ?active_binding, "status details requested");
+    StatusDetails {
+        status,
+        active_binding,
+    }
+}
+
+#[tauri::command]
+fn get_settings(state: tauri::State<'_, AppState>) -> VoiceSettings {
+    let settings = state.services.settings_store.current();
+    debug!("settings requested");
+    settings
+}
+
+#[tauri::command]
+fn get_onboarding_status(state: tauri::State<'_, AppState>) -> bool {
+    state.services.settings_store.current().onboarding_completed
+}
+
+#[tauri::command]
+fn complete_onboarding(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<bool, AppError> {
+    state
+        .command_guard
+        .run(CommandResource::Settings, "complete_onboarding", || {
+            state.services.settings_store.update(
+                &app,
+                VoiceSettingsUpdate {
+                    onboarding_completed: Some(true),
+                    ..VoiceSettingsUpdate::default()
+                },
+            )?;
+            Ok(true)
+        })
+}
+
+// Read under the settings guard so the revision always belongs to the
+// settings returned with it.
+#[tauri::command]
+fn get_settings_snapshot(state: tauri::State<'_, AppState>) -> Result<SettingsSnapshot, AppError> {
+    state
+        .command_guard
+        .run(CommandResource::Settings, "get_settings_snapshot", || {
+            state.services.settings_store.snapshot()
+        })
+}
+
+// `expected_revision` comes from `get_settings_snapshot`; when set, the update
+// is refused with a conflict if settings changed since the caller read them.
+#[tauri::command]
+fn update_settings(
+    app: AppHandle,
+    update: VoiceSettingsUpdate,
+    expected_revision: Option<u64>,
+    state: tauri::State<'_, AppState>,
+) -> Result<VoiceSettings, AppError> {
+    info!(?expected_revision, "settings update requested");
+    state
+        .command_guard
+        .run(CommandResource::Settings, "update_settings", || {
+            let updated =
+                state
+                    .services
+                    .settings_store
+                    .update_checked(&app, update, expected_revision);
+            match &updated {
+                Ok(settings) => {
+                    state.services.apply_live_settings(settings);
+                    info!(
+                        recording_mode = %settings.recording_mode,
+                        auto_insert = %settings.auto_insert,
+                        "settings updated"
+                    );
+                }
+                Err(error) => {
+                    error!(%error, "settings update failed");
+                }
+            }
+            let settings = updated?;
+            apply_history_encryption(&app, settings.history_encryption)?;
+            sync_retro_shortcut(&app);
+            sync_private_shortcut(&app);
+            sync_control_server(&app);
+            sync_watch_folders(&app);
+            sync_input_triggers(&app);
+            sync_telemetry(&app);
+            Ok(settings)
+        })
+}
+
+#[tauri::command]
+fn list_redaction_rules(state: tauri::State<'_, AppState>) -> Vec<RedactionRule> {
+    state.services.settings_store.current().redaction_rules
+}
+
+#[tauri::command]
+fn set_redaction_rules(
+    app: AppHandle,
+    rules: Vec<RedactionRule>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<RedactionRule>, AppError> {
+    state
+        .command_guard
+        .run(CommandResource::Settings, "set_redaction_rules", || {
+            let update = VoiceSettingsUpdate {
+                redaction_rules: Some(rules),
+                ..VoiceSettingsUpdate::default()
+            };
+            let settings = state.services.settings_store.update(&app, update)?;
+            info!(
+                rule_count = settings.redaction_rules.len(),
+                "redaction rules updated"
+            );
+            Ok(settings.redaction_rules)
+        })
+}
+
+#[tauri::command]
+fn list_snippets(state: tauri::State<'_, AppState>) -> Vec<Snippet> {
+    state.services.snippets_store.list()
+}
+
+#[tauri::command]
+fn create_snippet(
+    trigger: String,
+    template: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Snippet, AppError> {
+    state.services.snippets_store.create(&trigger, &template)
+}
+
+#[tauri::command]
+fn update_snippet(
+    id: String,
+    trigger: String,
+    template: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Snippet, AppError> {
+    state
+        .services
+        .snippets_store
+        .update(&id, &trigger, &template)
+}
+
+#[tauri::command]
+fn delete_snippet(id: String, state: tauri::State<'_, AppState>) -> Result<bool, AppError> {
+    state.services.snippets_store.delete(&id)
+}
+
+#[tauri::command]
+fn update_provider_config(
+    app: AppHandle,
+    config: ProviderConfig,
+    state: tauri::State<'_, AppState>,
+) -> Result<ProviderConfig, AppError> {
+    state
+        .command_guard
+        .run(CommandResource::Settings, "update_provider_config", || {
+            let update = VoiceSettingsUpdate {
+                provider_config: Some(config),
+                ..VoiceSettingsUpdate::default()
+            };
+            let settings = state.services.settings_store.update(&app, update)?;
+            state.services.apply_live_settings(&settings);
+            info!(
+                base_url = ?settings.provider_config.base_url,
+                model = ?settings.provider_config.model,
+                realtime_model = ?settings.provider_config.realtime_model,
+                extra_header_count = settings.provider_config.extra_headers.len(),
+                "provider config updated"
+            );
+            Ok(settings.provider_config)
+        })
+}
+
+#[tauri::command]
+fn apply_settings(
+    app: AppHandle,
+    update: VoiceSettingsUpdate,
+    expected_revision: Option<u64>,
+) -> Result<VoiceSettings, AppError> {
+    apply_settings_update(&app, update, expected_revision)
+}
+
+// Shared by the settings window and the tray menu's quick settings, so both
+// go through the same hotkey and launch-at-login rollback.
+fn apply_settings_update(
+    app: &AppHandle,
+    update: VoiceSettingsUpdate,
+    expected_revision: Option<u64>,
+) -> Result<VoiceSettings, AppError> {
+    let state = app.state::<AppState>();
+    let hotkey_service = app.state::<HotkeyService>();
+    state
+        .command_guard
+        .run(CommandResource::Settings, "apply_settings", || {
+            // Checked up front so a stale caller never touches the hotkey or
+            // launch-at-login state; the persist step checks again.
+            check_revision(expected_revision, state.services.settings_store.revision())?;
+            let previous_hotkey = hotkey_service.current_config();
+            let requested_hotkey = resolve_hotkey_config_for_settings(&update, &previous_hotkey)
+                .map_err(|message| AppError::new(ErrorCode::InvalidInput, message))?;
+            let previous_launch_at_login = get_launch_at_login_state(app)?;
+            let requested_launch_at_login =
+                update.launch_at_login.unwrap_or(previous_launch_at_login);
+
+            apply_settings_transaction_with_hooks(
+                update,
+                previous_hotkey,
+                requested_hotkey,
+                previous_launch_at_login,
+                requested_launch_at_login,
+                |config| hotkey_service.apply_config(app, config),
+                |enabled| set_launch_at_login_state(app, enabled),
+                |persist_update| {
+                    state.services.settings_store.update_checked(
+                        app,
+                        persist_update,
+                        expected_revision,
+                    )
+                },
+                |enabled| set_launch_at_login_state(app, enabled),
+                |config| hotkey_service.apply_config(app, config),
+            )
+            .inspect(|settings| state.services.apply_live_settings(settings))
+            .and_then(|settings| {
+                apply_history_encryption(app, settings.history_encryption)?;
+                sync_retro_shortcut(app);
+                sync_private_shortcut(app);
+                sync_control_server(app);
+                sync_watch_folders(app);
+                sync_input_triggers(app);
+                sync_telemetry(app);
+                sync_tray_quick_settings(app);
+                Ok(settings)
+            })
+        })
+}
+
+// Only touches the history file when the setting actually changes, since
+// switching re-encrypts or decrypts every entry and every queued recording.
+fn apply_history_encryption(app: &AppHandle, enabled: bool) -> Result<(), AppError> {
+    let Some(history_store) = app.try_state::<HistoryStore>() else {
+        return Ok(());
+    };
+    if history_store.is_encrypted() == enabled {
+        return Ok(());
+    }
+    let cipher = enabled.then(HistoryCipher::from_keychain).transpose()?;
+    let read_cipher = history_store.current_cipher().or_else(|| cipher.clone());
+    history_store
+        .set_encryption(cipher.clone())
+        .and_then(|()| match app.try_state::<PendingTranscriptionStore>() {
+            Some(pending_store) => {
+                pending_store.set_encryption(read_cipher.as_ref(), cipher.as_ref())
+            }
+            None => Ok(()),
+        })
+        .inspect_err(|error| {
+            error!(%error, enabled, "failed to apply history encryption");
+        })
+}
+
+// Polls `settings.json` so hand edits take effect without a restart.
+fn start_settings_file_watcher(app: &AppHandle) {
+    let app = app.clone();
+    let spawn_result = std::thread::Builder::new()
+        .name("settings-file-watcher".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(SETTINGS_FILE_POLL_INTERVAL);
+            match app
+                .state::<AppState>()
+                .services
+                .settings_store
+                .reload_if_changed(&app)
+            {
+                Ok(Some(settings)) => apply_externally_edited_settings(&app, &settings),
+                Ok(None) => {}
+                Err(error) => warn!(%error, "ignoring invalid hand-edited settings file"),
+            }
+        });
+
+    match spawn_result {
+        Ok(_) => info!("settings file watcher started"),
+        Err(error) => warn!(%error, "failed to start settings file watcher"),
+    }
+}
+
+fn start_chatgpt_token_renewal(app: &AppHandle) {
+    let auth_store = app.state::<AppState>().services.auth_store.clone();
+    let app = app.clone();
+    tauri::async_runtime::spawn(oauth::run_token_renewal(auth_store, move |event| {
+        if let Err(error) = app.emit(oauth::EVENT_AUTH_EXPIRED, event) {
+            warn!(%error, "failed to emit auth expired event");
+        }
+    }));
+    info!("ChatGPT token renewal started");
+}
+
+// Mirrors what startup applies from settings, then tells open windows to
+// refresh.
+fn apply_externally_edited_settings(app: &AppHandle, settings: &VoiceSettings) {
+    let state = app.state::<AppState>();
+    let hotkey_service = app.state::<HotkeyService>();
+    let current_hotkey = hotkey_service.current_config();
+    if let Err(error) = apply_hotkey_from_settings_with_fallback(
+        settings,
+        |config| {
+            if config == current_hotkey {
+                return Ok(());
+            }
+            hotkey_service.apply_config(app, config).map(|_| ())
+        },
+        || hotkey_service.register_default_shortcut(app),
+    ) {
+        warn!(%error, "failed to apply hotkey from edited settings");
+    }
+
+    state.services.apply_live_settings(settings);
+    if let Err(error) = apply_history_encryption(app, settings.history_encryption) {
+        warn!(%error, "failed to apply history encryption from edited settings");
+    }
+    if get_launch_at_login_state(app).ok() != Some(settings.launch_at_login) {
+        if let Err(error) = set_launch_at_login_state(app, settings.launch_at_login) {
+            warn!(%error, "failed to apply launch-at-login from edited settings");
+        }
+    }
+    sync_overlay_keys(app);
+    sync_retro_shortcut(app);
+    sync_private_shortcut(app);
+    sync_control_server(app);
+    sync_watch_folders(app);
+    sync_input_triggers(app);
+    sync_telemetry(app);
+    sync_tray_quick_settings(app);
+
+    if let Err(error) = app.emit(EVENT_SETTINGS_CHANGED, settings) {
+        warn!(%error, "failed to emit settings changed event");
+    }
+}
+
+#[tauri::command]
+fn get_launch_at_login(app: AppHandle) -> Result<bool, AppError> {
+    get_launch_at_login_state(&app).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn set_launch_at_login(
+    app: AppHandle,
+    enabled: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<bool, AppError> {
+    state
+        .command_guard
+        .run(CommandResource::Settings, "set_launch_at_login", || {
+            let previous = get_launch_at_login_state(&app)?;
+            set_launch_at_login_state(&app, enabled)?;
+
+            if let Err(error) = state.services.settings_store.update(
+                &app,
+                VoiceSettingsUpdate {
+                    launch_at_login: Some(enabled),
+                    ..VoiceSettingsUpdate::default()
+                },
+            ) {
+                if let Err(rollback_error) = set_launch_at_login_state(&app, previous) {
+                    return Err(format!(
+                        "Failed to persist launch-at-login setting: {error}. Failed to roll back launch-at-login state: {rollback_error}"
+                    ));
+                }
+
+                return Err(format!(
+                    "Failed to persist launch-at-login setting: {error}"
+                ));
+            }
+
+            Ok(enabled)
+        })
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+fn has_api_key(provider: String, state: tauri::State<'_, AppState>) -> Result<bool, AppError> {
+    debug!(provider = %provider, "api key presence lookup requested");
+    let result = state.services.api_key_store.has_api_key(provider.as_str());
+    match &result {
+        Ok(true) => debug!(provider = %provider, "api key is present"),
+        Ok(false) => debug!(provider = %provider, "api key is absent"),
+        Err(error) => error!(provider = %provider, %error, "api key lookup failed"),
+    }
+    result
+}
+
+#[tauri::command]
+fn get_auth_method(state: tauri::State<'_, AppState>) -> Result<String, AppError> {
+    let method = state.services.current_auth_method()?;
+    Ok(method.as_str().to_string())
+}
+
+#[tauri::command]
+fn set_auth_method(method: String, state: tauri::State<'_, AppState>) -> Result<String, AppError> {
+    let parsed = AuthMethod::parse(&method)?;
+    state.services.auth_store.set_auth_method(parsed)?;
+    Ok(parsed.as_str().to_string())
+}
+
+#[tauri::command]
+fn get_chatgpt_auth_status(
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<ChatGptAuthStatus>, AppError> {
+    Ok(state
+        .services
+        .auth_store
+        .chatgpt_credentials()?
+        .map(|credentials| ChatGptAuthStatus {
+            account_id: credentials.account_id,
+            expires_at: credentials.expires_at,
+        }))
+}
+
+#[tauri::command]
+fn get_auth_status(
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<ChatGptAuthStatus>, AppError> {
+    get_chatgpt_auth_status(state)
+}
+
+#[tauri::command]
+async fn start_chatgpt_login(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<ChatGptAuthStatus, AppError> {
+    info!("ChatGPT OAuth login requested");
+    let login = oauth::start_chatgpt_login(&app).await?;
+    state.services.auth_store.save_chatgpt_login(
+        &login.access_token,
+        &login.refresh_token,
+        login.expires_at,
+        &login.account_id,
+    )?;
+
+    Ok(ChatGptAuthStatus {
+        account_id: login.account_id,
+        expires_at: login.expires_at,
+    })
+}
+
+#[tauri::command]
+async fn start_oauth_login(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<ChatGptAuthStatus, AppError> {
+    start_chatgpt_login(app, state).await
+}
+
+#[tauri::command]
+fn logout_chatgpt(state: tauri::State<'_, AppState>) -> Result<(), AppError> {
+    info!("ChatGPT OAuth logout requested");
+    state.services.auth_store.logout_chatgpt()?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn save_api_key(
+    provider: String,
+    key: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), AppError> {
+    set_api_key(provider, key, None, state).await.map(|_| ())
+}
+
+// With `validate`, the key is also checked against the provider once saved.
+// It is kept either way, since the check fails while offline too.
+#[tauri::command]
+async fn set_api_key(
+    provider: String,
+    key: String,
+    validate: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<ApiKeyValidation>, AppError> {
+    state
+        .command_guard
+        .run(CommandResource::Credentials, "set_api_key", || {
+            info!(provider = %provider, "api key set requested");
+            let result = state
+                .services
+                .api_key_store
+                .set_api_key(provider.as_str(), key.as_str());
+            if let Err(error) = &result {
+                error!(provider = %provider, %error, "api key set failed");
+                return result;
+            }
+
+            if provider.trim().eq_ignore_ascii_case("openai") {
+                if let Err(error) = state.services.auth_store.set_api_key(key.as_str()) {
+                    error!(provider = %provider, %error, "failed to update auth store after setting API key");
+                    return Err(error);
+                }
+            }
+
+            result
+        })?;
+
+    if !validate.unwrap_or(false) {
+        return Ok(None);
+    }
+    validate_provider_api_key(&state, &provider, Some(&key))
+        .await
+        .map(Some)
+}
+
+// Checks the stored key, so users find out it is wrong before a dictation
+// fails on it.
+#[tauri::command]
+async fn validate_api_key(
+    provider: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ApiKeyValidation, AppError> {
+    validate_provider_api_key(&state, &provider, None).await
+}
+
+async fn validate_provider_api_key(
+    state: &AppState,
+    provider: &str,
+    api_key: Option<&str>,
+) -> Result<ApiKeyValidation, AppError> {
+    let orchestrator = state.services.provider_orchestrator(provider)?;
+    let listing = orchestrator.list_models(api_key).await;
+    let validation = ApiKeyValidation::from_listing(provider.trim(), listing);
+    info!(
+        provider = %validation.provider,
+        valid = ?validation.valid,
+        allowed_model_count = validation.allowed_models.len(),
+        "api key validated"
+    );
+    Ok(validation)
+}
+
+// Feeds the model picker in settings; the pick is saved per provider in
+// `transcription_models`.
+#[tauri::command]
+async fn list_transcription_models(
+    provider: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<TranscriptionModel>, AppError> {
+    let orchestrator = state.services.provider_orchestrator(&provider)?;
+    let listing = orchestrator.list_models(None).await?;
+    info!(
+        provider = %provider,
+        model_count = listing.models.len(),
+        realtime_model_count = listing.models.iter().filter(|model| model.realtime).count(),
+        "transcription models listed"
+    );
+    Ok(listing.models)
+}
+
+#[tauri::command]
+fn delete_api_key(provider: String, state: tauri::State<'_, AppState>) -> Result<(), AppError> {
+    state
+        .command_guard
+        .run(CommandResource::Credentials, "delete_api_key", || {
+            info!(provider = %provider, "api key delete requested");
+            let result = state
+                .services
+                .api_key_store
+                .delete_api_key(provider.as_str());
+            if let Err(error) = &result {
+                error!(provider = %provider, %error, "api key delete failed");
+                return result;
+            }
+
+            if provider.trim().eq_ignore_ascii_case("openai") {
+                if let Err(error) = state.services.auth_store.clear_api_key() {
+                    error!(provider = %provider, %error, "failed to update auth store after deleting API key");
+                    return Err(error);
+                }
+            }
+
+            result
+        })
+}
+
+#[tauri::command]
+fn list_microphones(state: tauri::State<'_, AppState>) -> Result<Vec<MicrophoneInfo>, AppError> {
+    let result = state.services.audio_capture_service.list_microphones();
+    match &result {
+        Ok(microphones) => debug!(count = microphones.len(), "listed microphones"),
+        Err(error) => error!(%error, "failed to list microphones"),
+    }
+    result.map_err(AppError::from)
+}
+
+// Foot pedals and MIDI controllers that can be bound to start and stop
+// recording; bindings themselves are saved through `apply_settings`.
+#[tauri::command]
+fn list_input_triggers(state: tauri::State<'_, AppState>) -> Vec<InputTriggerDevice> {
+    let bindings = state.services.settings_store.current().input_triggers;
+    let devices = input_triggers::list_devices(&bindings);
+    debug!(count = devices.len(), "listed input triggers");
+    devices
+}
+
+#[tauri::command]
+fn list_capture_sources(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<CaptureSourceInfo>, AppError> {
+    let result = state.services.audio_capture_service.list_capture_sources();
+    match &result {
+        Ok(sources) => debug!(count = sources.len(), "listed capture sources"),
+        Err(error) => error!(%error, "failed to list capture sources"),
+    }
+    result.map_err(AppError::from)
+}
+
+#[tauri::command]
+fn check_permissions(state: tauri::State<'_, AppState>) -> PermissionSnapshot {
+    state.services.permission_service.check_permissions()
+}
+
+#[tauri::command]
+fn request_permission(
+    r#type: PermissionType,
+    state: tauri::State<'_, AppState>,
+) -> Result<PermissionSnapshot, AppError> {
+    state
+        .services
+        .permission_service
+        .request_permission(r#type)
+        .map_err(AppError::from)
+}
+
+// `timeout_ms` is capped by the service; the result is `granted` unless the
+// wait ran out first.
+#[tauri::command]
+async fn wait_for_permission(
+    app: AppHandle,
+    r#type: PermissionType,
+    timeout_ms: u64,
+) -> PermissionState {
+    app.state::<AppState>()
+        .services
+        .permission_service
+        .wait_for_permission(r#type, Duration::from_millis(timeout_ms))
+        .await
+}
+
+#[tauri::command]
+fn request_mic_permission(
+    state: tauri::State<'_, AppState>,
+) -> Result<PermissionSnapshot, AppError> {
+    state
+        .services
+        .permission_service
+        .request_microphone_permission()
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+fn open_accessibility_settings(state: tauri::State<'_, AppState>) -> Result<(), AppError> {
+    state
+        .services
+        .permission_service
+        .open_accessibility_settings()
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+fn start_recording(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    microphone_id: Option<String>,
+) -> Result<(), AppError> {
+    info!(
+        microphone_id = ?microphone_id.as_deref(),
+        "manual recording start requested"
+    );
+    ensure_microphone_permission_for_recording(&state)?;
+
+    let settings = state.services.settings_store.current();
+    let capture_source = CaptureSource::from_settings_value(&settings.capture_source);
+    let result = state.services.audio_capture_service.start_recording(
+        app.clone(),
+        microphone_id.as_deref(),
+        capture_source,
+        InputProcessingConfig::from_settings(&settings),
+        None,
+    );
+
+    if result.is_ok() {
+        set_status_for_state(&app, &state, AppStatus::Listening);
+        info!("manual recording started");
+    } else if let Err(error) = &result {
+        error!(%error, "manual recording start failed");
+    }
+
+    result
+}
+
+#[tauri::command]
+fn stop_recording(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<RecordedAudio, AppError> {
+    info!("manual recording stop requested");
+    let mut recorded = state
+        .services
+        .audio_capture_service
+        .stop_recording(app.clone())
+        .map_err(|error| {
+            error!(%error, "manual recording stop failed");
+            error
+        })?;
+    // The frontend receives the audio inline, so spooled recordings are loaded
+    // back into memory here.
+    recorded.wav_bytes = recorded.take_wav().into_bytes()?;
+
+    set_status_for_state(&app, &state, AppStatus::Idle);
+    info!(
+        duration_ms = recorded.duration_ms,
+        sample_rate_hz = recorded.sample_rate_hz,
+        channels = recorded.channels,
+        "manual recording stopped"
+    );
+    Ok(recorded)
+}
+
+// Stops a recording that is still running once the limit passes, so a
+// forgotten toggle does not record for hours and then fail to upload.
+fn spawn_recording_limit_watchdog(app: AppHandle, session_id: u64, limit: Duration) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(limit).await;
+        let still_recording = app
+            .state::<PipelineRuntimeState>()
+            .is_session_active(session_id)
+            && get_status_from_state(&app.state::<AppState>()) == AppStatus::Listening;
+        if !still_recording {
+            return;
+        }
+
+        let max_duration_mins = (limit.as_secs() / 60) as u32;
+        warn!(
+            session_id,
+            max_duration_mins, "recording hit the maximum duration; stopping"
+        );
+        let event = RecordingLimitReachedEvent {
+            session_id,
+            max_duration_mins,
+        };
+        if let Err(error) = app.emit(EVENT_RECORDING_LIMIT_REACHED, event) {
+            warn!(%error, "failed to emit recording limit event");
+        }
+        if !app.state::<HotkeyService>().request_recording(&app, false) {
+            if let Err(error) = complete_recording(app.clone(), app.state::<AppState>()).await {
+                warn!(%error, "failed to stop recording at the maximum duration");
+            }
+        }
+    });
+}
+
+#[tauri::command]
+async fn complete_recording(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), AppError> {
+    info!("recording completion requested");
+    let runtime_state = app.state::<PipelineRuntimeState>().inner().clone();
+    let guard = runtime_state.execution_lock.lock().await;
+
+    let status = get_status_from_state(&state);
+    if status != AppStatus::Listening {
+        debug!(
+            ?status,
+            "ignoring recording completion request because app is not listening"
+        );
+        return Ok(());
+    }
+
+    let session_id = resolve_or_begin_pipeline_session(&runtime_state);
+    let delegate = AppPipelineDelegate::for_session(app, session_id);
+    process_stopped_session(&runtime_state, delegate, guard).await;
+    info!(session_id, "recording completion pipeline finished");
+    Ok(())
+}
+
+#[tauri::command]
+fn cancel_recording(app: AppHandle, state: tauri::State<'_, AppState>) -> Result<(), AppError> {
+    info!("recording/transcription cancel requested");
+    let cancel_result = cancel_recording_with_hooks(
+        || {
+            let runtime_state = app.state::<PipelineRuntimeState>();
+            runtime_state.cancel_sessions();
+        },
+        || {
+            let hotkey_service = app.state::<HotkeyService>();
+            hotkey_service.force_stop_recording(&app);
+        },
+        || {
+            state
+                .services
+                .audio_capture_service
+                .abort_recording(app.clone())
+        },
+        |status| set_status_for_state(&app, &state, status),
+    );
+
+    match cancel_result {
+        Ok(recording_aborted) => {
+            info!(
+                recording_aborted,
+                "recording/transcription cancel completed"
+            );
+            Ok(())
+        }
+        Err(error) => {
+            error!(%error, "recording/transcription cancel failed");
+            Err(AppError::from(error))
+        }
+    }
+}
+
+// Escape hatch for a hotkey that believes it is still recording: forgets all
+// runtime state and registers the shortcut from settings again.
+#[tauri::command]
+fn reset_hotkey_state(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    hotkey_service: tauri::State<'_, HotkeyService>,
+) -> Result<HotkeyStateSnapshot, AppError> {
+    let previous = hotkey_service.snapshot()?;
+    warn!(?previous, "hotkey state reset requested");
+
+    hotkey_service.force_stop_recording(&app);
+    hotkey_service.reset_runtime_state(&app)?;
+    let settings = state.services.settings_store.current();
+    apply_hotkey_from_settings_with_fallback(
+        &settings,
+        |config| hotkey_service.apply_config(&app, config).map(|_| ()),
+        || hotkey_service.register_default_shortcut(&app),
+    )?;
+
+    hotkey_service.snapshot().map_err(AppError::from)
+}
+
+#[tauri::command]
+fn pin_realtime_language(
+    language: String,
+    runtime_state: tauri::State<'_, PipelineRuntimeState>,
+) -> Result<(), AppError> {
+    let guard = runtime_state
+        .realtime_session
+        .lock()
+        .map_err(|_| "Realtime session state is unavailable".to_string())?;
+    let Some(session) = guard.as_ref() else {
+        return Err(AppError::new(
+            ErrorCode::NotFound,
+            "No realtime transcription session is active",
+        ));
+    };
+
+    info!(language = %language.trim(), "realtime language pin requested");
+    session
+        .audio_sender()
+        .pin_language(&language)
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+fn get_audio_level(state: tauri::State<'_, AppState>) -> f32 {
+    state.services.audio_capture_service.get_audio_level()
+}
+
+#[tauri::command]
+async fn insert_text(
+    app: AppHandle,
+    text: String,
+    focused_app_watcher: tauri::State<'_, FocusedAppWatcher>,
+) -> Result<(), AppError> {
+    info!(
+        chars = text.chars().count(),
+        "manual text insertion requested"
+    );
+    insert_text_for_state(&app, &text, focused_app_watcher.current().as_ref(), None).await
+}
+
+// Browser fields are filled through the companion extension when one reports
+// focus; everything else goes through synthetic keystrokes or paste, paced
+// for the target app.
+async fn insert_text_for_state(
+    app: &AppHandle,
+    text: &str,
+    target_app: Option<&FocusedApp>,
+    session_id: Option<u64>,
+) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+    let settings = state.services.settings_store.current();
+    let spaced = state.services.text_insertion_service.space_text(
+        text,
+        &InsertionSpacing::for_app(&settings, target_app),
+        target_app,
+    );
+    let strategy = if state.services.browser_bridge.try_insert(&spaced).await {
+        Some(InsertionStrategy::BrowserExtension)
+    } else {
+        ensure_accessibility_permission_for_insertion(&state)?;
+        state
+            .services
+            .text_insertion_service
+            .insert_text(&spaced, InsertionOptions::for_app(&settings, target_app))?
+    };
+    if strategy == Some(InsertionStrategy::ClipboardFallback) {
+        let event = InsertionCopiedInsteadEvent {
+            session_id,
+            app_name: target_app.map(|app| app.name.clone()),
+        };
+        if let Err(error) = app.emit(EVENT_INSERTION_COPIED_INSTEAD, event) {
+            warn!(%error, "failed to emit insertion copied-instead event");
+        }
+    }
+    record_insertion(&state, session_id, target_app, strategy, text);
+    remember_recent_text(app, &state, session_id, target_app, text);
+    Ok(())
+}
+
+// Only kept while recent text context is on, and never for private
+// dictations.
+fn remember_recent_text(
+    app: &AppHandle,
+    state: &AppState,
+    session_id: Option<u64>,
+    target_app: Option<&FocusedApp>,
+    text: &str,
+) {
+    let Some(target_app) = target_app else {
+        return;
+    };
+    let settings = state.services.settings_store.current();
+    let is_private = settings.private_dictation
+        || session_id
+            .is_some_and(|session_id| app.state::<PipelineRuntimeState>().is_private(session_id));
+    if settings.recent_text_context && !is_private {
+        state.services.recent_insertions.record(target_app, text);
+    }
+}
+
+fn recent_text_context(
+    state: &AppState,
+    settings: &VoiceSettings,
+    target_app: Option<&FocusedApp>,
+) -> Option<String> {
+    if !settings.recent_text_context {
+        return None;
+    }
+    state
+        .services
+        .recent_insertions
+        .recent_text(target_app?, settings.recent_text_context_words as usize)
+}
+
+// Audit failures never block delivery; the transcript already reached the app.
+fn record_insertion(
+    state: &AppState,
+    session_id: Option<u64>,
+    target_app: Option<&FocusedApp>,
+    strategy: Option<InsertionStrategy>,
+    text: &str,
+) {
+    let Some(strategy) = strategy else {
+        return;
+    };
+    let entry = InsertionAuditEntry::new(session_id, target_app, strategy, text);
+    if let Err(error) = state.services.insertion_audit.append(&entry) {
+        warn!(%error, "failed to record insertion audit entry");
+    }
+}
+
+#[tauri::command]
+fn get_insertion_audit_log(
+    limit: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<InsertionAuditEntry>, AppError> {
+    state
+        .services
+        .insertion_audit
+        .recent(limit.unwrap_or(DEFAULT_INSERTION_AUDIT_PAGE_SIZE))
+        .map_err(AppError::from)
+}
+
+// The browser extension asks for this once and sends it with every `hello`.
+#[tauri::command]
+fn get_browser_bridge_pairing_token(state: tauri::State<'_, AppState>) -> String {
+    state.services.browser_bridge.pairing_token().to_string()
+}
+
+#[tauri::command]
+fn get_last_session_trace(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<SessionTraceEvent>, AppError> {
+    state
+        .services
+        .session_trace
+        .last_trace()
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+fn get_pending_insert(runtime: tauri::State<'_, PipelineRuntimeState>) -> Option<PendingInsert> {
+    runtime.pending_insert()
+}
+
+// Returns false when there was nothing waiting, e.g. the prompt was already
+// answered from another window. `text` carries the user's edits from the
+// review window; without it the transcript goes out as it was held.
+#[tauri::command]
+async fn resolve_pending_insert(
+    app: AppHandle,
+    action: PendingInsertAction,
+    text: Option<String>,
+    state: tauri::State<'_, AppState>,
+    runtime: tauri::State<'_, PipelineRuntimeState>,
+    focused_app_watcher: tauri::State<'_, FocusedAppWatcher>,
+) -> Result<bool, AppError> {
+    let Some(pending_insert) = runtime.take_pending_insert() else {
+        return Ok(false);
+    };
+    info!(
+        ?action,
+        edited = text
+            .as_ref()
+            .is_some_and(|text| *text != pending_insert.text),
+        "resolving pending insert"
+    );
+    hide_insert_review_window(&app);
+    let text = text.unwrap_or(pending_insert.text);
+    match action {
+        PendingInsertAction::Insert => {
+            tokio::time::sleep(INSERT_REVIEW_FOCUS_RETURN_DELAY).await;
+            insert_text_for_state(&app, &text, focused_app_watcher.current().as_ref(), None).await?
+        }
+        PendingInsertAction::Copy => {
+            let strategy = state
+                .services
+                .text_insertion_service
+                .copy_to_clipboard(&text)?;
+            record_insertion(
+                &state,
+                None,
+                pending_insert.origin_app.as_ref(),
+                strategy,
+                &text,
+            );
+        }
+        PendingInsertAction::Discard => {}
+    }
+    Ok(true)
+}
+
+#[tauri::command]
+fn get_flagged_transcript(
+    runtime: tauri::State<'_, PipelineRuntimeState>,
+) -> Option<FlaggedTranscript> {
+    runtime.flagged_transcript()
+}
+
+// Re-transcribes the flagged recording over REST and inserts the result into
+// the focused app. A failed retry leaves the flag in place to try again.
+#[tauri::command]
+async fn retry_flagged_transcript(
+    app: AppHandle,
+    runtime: tauri::State<'_, PipelineRuntimeState>,
+    focused_app_watcher: tauri::State<'_, FocusedAppWatcher>,
+) -> Result<String, AppError> {
+    let Some((flagged, wav)) = runtime.take_flagged_transcript() else {
+        return Err(AppError::new(
+            ErrorCode::NotFound,
+            "There is no flagged transcript to retry",
+        ));
+    };
+    info!(
+        reason = ?flagged.reason,
+        provider = %flagged.provider,
+        "retrying flagged transcript"
+    );
+
+    let delegate = AppPipelineDelegate::new(app.clone());
+    let transcript = match delegate.retranscribe_with_rest(wav.clone(), None).await {
+        Ok(transcript) => transcript,
+        Err(error) => {
+            error!(%error, "flagged transcript retry failed");
+            runtime.set_flagged_transcript(flagged, wav);
+            return Err(error);
+        }
+    };
+    let word_count = count_words(
+        &delegate.current_settings(),
+        &transcript.text,
+        transcript.language.as_deref(),
+    );
+    if let Some(reason) = check_transcript_plausibility(word_count, flagged.recording_secs) {
+        warn!(
+            ?reason,
+            "retried transcript is still implausible; inserting anyway"
+        );
+    }
+
+    if let Err(error) = delegate.save_history_entry(&transcript) {
+        warn!(%error, "failed to persist retried transcript history entry");
+    }
+    insert_text_for_state(
+        &app,
+        &transcript.text,
+        focused_app_watcher.current().as_ref(),
+        None,
+    )
+    .await?;
+    Ok(transcript.text)
+}
+
+#[tauri::command]
+fn dismiss_flagged_transcript(runtime: tauri::State<'_, PipelineRuntimeState>) -> bool {
+    runtime.take_flagged_transcript().is_some()
+}
+
+// Re-transcribes the latest dictation whose language looked wrong, pinned to
+// the chosen language. The first transcript is already in the focused app, so
+// this one goes to history and back to the UI. The audio is kept until
+// dismissed so another language can be tried.
+#[tauri::command]
+async fn retranscribe_in_language(
+    app: AppHandle,
+    language: String,
+    runtime: tauri::State<'_, PipelineRuntimeState>,
+) -> Result<String, AppError> {
+    let language = language.trim().to_string();
+    if language.is_empty() {
+        return Err(AppError::new(
+            ErrorCode::InvalidInput,
+            "Choose a language to re-transcribe in",
+        ));
+    }
+    let Some(wav) = runtime.uncertain_language_recording() else {
+        return Err(AppError::new(
+            ErrorCode::NotFound,
+            "There is no dictation to re-transcribe",
+        ));
+    };
+    info!(%language, "re-transcribing dictation in a chosen language");
+
+    let delegate = AppPipelineDelegate::new(app.clone());
+    let transcript = delegate
+        .retranscribe_with_rest(wav, Some(language))
+        .await
+        .map_err(|error| {
+            error!(%error, "re-transcribing in a chosen language failed");
+            error
+        })?;
+    if let Err(error) = delegate.save_history_entry(&transcript) {
+        warn!(%error, "failed to persist re-transcribed history entry");
+    }
+    Ok(transcript.text)
+}
+
+#[tauri::command]
+fn dismiss_language_suggestion(runtime: tauri::State<'_, PipelineRuntimeState>) -> bool {
+    runtime.take_uncertain_language().is_some()
+}
+
+#[tauri::command]
+fn copy_to_clipboard(text: String, state: tauri::State<'_, AppState>) -> Result<(), AppError> {
+    info!(
+        chars = text.chars().count(),
+        "manual clipboard copy requested"
+    );
+    state
+        .services
+        .text_insertion_service
+        .copy_to_clipboard(&text)
+        .map(|_| ())
+        .map_err(AppError::from)
+}
+
+// Runs the tail of the retro buffer through the normal pipeline as if it had
+// just been recorded, including insertion into the focused app.
+#[tauri::command]
+async fn transcribe_last_seconds(app: AppHandle, secs: Option<u32>) -> Result<(), AppError> {
+    transcribe_retro_buffer(&app, secs).await
+}
+
+async fn transcribe_retro_buffer(app: &AppHandle, secs: Option<u32>) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+    let runtime_state = app.state::<PipelineRuntimeState>();
+    let execution_guard = runtime_state.execution_lock.lock().await;
+    if get_status_from_state(&state) != AppStatus::Idle {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            "Finish the current dictation before transcribing the retro buffer",
+        ));
+    }
+    let buffer_secs = state.services.settings_store.current().retro_buffer_secs;
+    let secs = secs
+        .unwrap_or(DEFAULT_RETRO_TRANSCRIBE_SECS)
+        .clamp(1, buffer_secs.max(1));
+    let wav_bytes = state.services.audio_capture_service.retro_wav(secs)?;
+    info!(
+        secs,
+        audio_bytes = wav_bytes.len(),
+        "retro buffer transcription requested"
+    );
+
+    let session_id = runtime_state.begin_session();
+    let delegate =
+        AppPipelineDelegate::for_session(app.clone(), session_id).detach_realtime_session();
+    set_status_for_state(app, &state, AppStatus::Transcribing);
+    drop(execution_guard);
+    VoicePipeline::default()
+        .transcribe_and_insert(&delegate, RecordedWav::Memory(wav_bytes), None)
+        .await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn transcribe_audio(
+    app: AppHandle,
+    audio_bytes: Vec<u8>,
+    options: Option<TranscriptionOptions>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, AppError> {
+    info!(
+        audio_bytes = audio_bytes.len(),
+        "command transcription requested"
+    );
+    set_status_for_state(&app, &state, AppStatus::Transcribing);
+    let app_for_delta = app.clone();
+    let mut request_options = options.unwrap_or_default();
+    let has_explicit_prompt = request_options
+        .prompt
+        .as_deref()
+        .map(|prompt| !prompt.trim().is_empty())
+        .unwrap_or(false);
+    if !has_explicit_prompt {
+        let settings = state.services.settings_store.current();
+        request_options.prompt =
+            transcription_prompt(&settings, request_options.language.as_deref());
+    }
+    if request_options.context_hint.is_none() {
+        let settings = state.services.settings_store.current();
+        request_options.context_hint =
+            transcription_context_hint(&settings, request_options.language.as_deref(), None);
+    }
+    if request_options.decoding == DecodingOptions::default() {
+        request_options.decoding = state.services.settings_store.current().decoding;
+    }
+    request_options.on_delta = Some(Arc::new(move |delta| {
+        emit_transcription_delta_event(&app_for_delta, &delta);
+    }));
+    let auth_method = state.services.current_auth_method()?;
+    let orchestrator = state
+        .services
+        .api_key_orchestrator(&state.services.settings_store.current());
+    let chatgpt_provider = state.services.chatgpt_transcription_provider.clone();
+
+    let result = match auth_method {
+        AuthMethod::ApiKey => orchestrator.transcribe(audio_bytes, request_options).await,
+        AuthMethod::ChatgptOauth => {
+            chatgpt_provider
+                .transcribe(audio_bytes, request_options)
+                .await
+        }
+        AuthMethod::None => Err(transcription::TranscriptionError::Authentication(
+            "No authentication configured. Add an OpenAI API key or login with ChatGPT."
+                .to_string(),
+        )),
+    };
+
+    match result {
+        Ok(transcription) => {
+            set_status_for_state(&app, &state, AppStatus::Idle);
+            info!(
+                transcript_chars = transcription.text.chars().count(),
+                language = ?transcription.language,
+                "command transcription completed"
+            );
+
+            Ok(transcription.text)
+        }
+        Err(error) => {
+            let error = AppError::from(error);
+            error!(%error, "command transcription failed");
+            let delegate = AppPipelineDelegate::new(app.clone());
+            let _ = spawn_pipeline_stage_error_reset(
+                VoicePipeline::default(),
+                delegate,
+                voice_pipeline::PipelineErrorStage::Transcription,
+                error.clone(),
+            );
+
+            Err(error)
+        }
+    }
+}
+
+fn emit_pending_transcriptions_changed(app: &AppHandle) {
+    let pending = app.state::<PendingTranscriptionStore>().list();
+    if let Err(error) = app.emit(EVENT_PENDING_TRANSCRIPTIONS_CHANGED, pending) {
+        warn!(%error, "failed to emit pending transcriptions event");
+    }
+}
+
+fn spawn_pending_transcription_worker(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(PENDING_RETRY_INTERVAL).await;
+            if let Err(error) = process_pending_transcriptions(&app, false).await {
+                warn!(%error, "failed to process pending transcriptions");
+            }
+        }
+    });
+}
+
+// Transcribes queued recordings oldest first into history and onto the
+// clipboard; the app they were meant for has long lost focus. The pass stops
+// at the first network failure, since the rest would fail the same way.
+async fn process_pending_transcriptions(app: &AppHandle, manual: bool) -> Result<usize, AppError> {
+    let store = app.state::<PendingTranscriptionStore>();
+    let Some(_processing) = store.try_begin_processing() else {
+        return Ok(0);
+    };
+    let pending = store
+        .list()
+        .into_iter()
+        .filter(|pending| manual || pending.attempts < MAX_AUTOMATIC_ATTEMPTS)
+        .collect::<Vec<_>>();
+    if pending.is_empty() {
+        return Ok(0);
+    }
+    let state = app.state::<AppState>();
+    let settings = state.services.settings_store.current();
+    let url = reachability_url(
+        settings.provider_config.base_url.as_deref(),
+        &settings.transcription_provider,
+    );
+    if !is_reachable(&url).await {
+        debug!(
+            pending = pending.len(),
+            "provider still unreachable; keeping queued recordings"
+        );
+        return Ok(0);
+    }
+
+    info!(pending = pending.len(), "transcribing queued recordings");
+    let cipher = app.state::<HistoryStore>().current_cipher();
+    let mut transcribed = 0;
+    for pending in pending {
+        let result = match store.load_wav(&pending.id, cipher.as_ref()) {
+            Ok(wav_bytes) => {
+                transcribe_wav_into_history(app, &state, wav_bytes, None, HistorySource::Dictation)
+                    .await
+            }
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok(entry) => {
+                store.remove(&pending.id)?;
+                if let Err(error) = state
+                    .services
+                    .text_insertion_service
+                    .copy_to_clipboard(&entry.text)
+                {
+                    warn!(id = %pending.id, %error, "failed to copy queued transcript");
+                }
+                let event = PendingTranscriptionCompletedEvent {
+                    id: pending.id,
+                    text: entry.text,
+                };
+                if let Err(error) = app.emit(EVENT_PENDING_TRANSCRIPTION_COMPLETED, event) {
+                    warn!(%error, "failed to emit pending transcription event");
+                }
+                transcribed += 1;
+            }
+            Err(error) => {
+                warn!(id = %pending.id, %error, "queued recording transcription failed");
+                store.record_failure(&pending.id, &error.message)?;
+                if error.code == ErrorCode::Network {
+                    break;
+                }
+            }
+        }
+    }
+    emit_pending_transcriptions_changed(app);
+    Ok(transcribed)
+}
+
+#[tauri::command]
+fn list_pending_transcriptions(
+    store: tauri::State<'_, PendingTranscriptionStore>,
+) -> Vec<PendingTranscription> {
+    store.list()
+}
+
+// Also picks up recordings that gave up after repeated failures.
+#[tauri::command]
+async fn retry_pending_transcriptions(app: AppHandle) -> Result<usize, AppError> {
+    process_pending_transcriptions(&app, true).await
+}
+
+#[tauri::command]
+fn discard_pending_transcription(
+    app: AppHandle,
+    id: String,
+    store: tauri::State<'_, PendingTranscriptionStore>,
+) -> Result<bool, AppError> {
+    let removed = store.remove(&id)?;
+    info!(id = %id, removed, "pending transcription discarded");
+    emit_pending_transcriptions_changed(&app);
+    Ok(removed)
+}
+
+fn recovery_dir_for_state(state: &AppState) -> Result<PathBuf, String> {
+    state
+        .services
+        .audio_capture_service
+        .recovery_dir()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| "Recording recovery is not configured".to_string())
+}
+
+#[tauri::command]
+fn list_recovered_recordings(state: tauri::State<'_, AppState>) -> Vec<RecoveredRecording> {
+    state
+        .services
+        .audio_capture_service
+        .recovery_dir()
+        .map(audio_capture_service::list_recovered_recordings)
+        .unwrap_or_default()
+}
+
+// Transcribes an orphaned recording into history without inserting it, since
+// the app it was meant for is long gone. The checkpoint is only deleted once
+// the transcript is saved.
+#[tauri::command]
+async fn transcribe_recovered_recording(
+    app: AppHandle,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, AppError> {
+    let recovery_dir = recovery_dir_for_state(&state)?;
+    let wav_bytes = audio_capture_service::load_recovered_recording_wav(&recovery_dir, &id)?;
+    info!(
+        id = %id,
+        audio_bytes = wav_bytes.len(),
+        "recovered recording transcription requested"
+    );
+
+    let text = transcribe_wav_into_history(&app, &state, wav_bytes, None, HistorySource::Dictation)
+        .await
+        .inspect_err(|error| error!(id = %id, %error, "recovered recording transcription failed"))?
+        .text;
+    audio_capture_service::discard_recovered_recording(&recovery_dir, &id)?;
+    info!(
+        id = %id,
+        transcript_chars = text.chars().count(),
+        "recovered recording transcribed into history"
+    );
+    Ok(text)
+}
+
+#[tauri::command]
+async fn transcribe_file(
+    app: AppHandle,
+    path: String,
+    options: Option<TranscriptionOptions>,
+) -> Result<HistoryEntry, AppError> {
+    transcribe_audio_file(&app, PathBuf::from(path), options, true).await
+}
+
+// Decoding is CPU-bound, so it runs off the async runtime; the path is read
+// as-is, so callers decide what is allowed.
+async fn transcribe_audio_file(
+    app: &AppHandle,
+    path: PathBuf,
+    options: Option<TranscriptionOptions>,
+    save_to_history: bool,
+) -> Result<HistoryEntry, AppError> {
+    info!(path = %path.display(), "file transcription requested");
+    emit_file_transcription(app, &path, FileTranscriptionState::Started, None);
+    let decode_path = path.clone();
+    let result = match tauri::async_runtime::spawn_blocking(move || {
+        audio_decoding::decode_audio_file_to_wav(&decode_path)
+    })
+    .await
+    {
+        Ok(Ok(wav_bytes)) if save_to_history => {
+            let state = app.state::<AppState>();
+            transcribe_wav_into_history(app, &state, wav_bytes, options, HistorySource::File).await
+        }
+        Ok(Ok(wav_bytes)) => {
+            let state = app.state::<AppState>();
+            transcribe_wav_to_entry(&state, wav_bytes, options, HistorySource::File).await
+        }
+        Ok(Err(error)) => Err(AppError::new(ErrorCode::InvalidInput, error)),
+        Err(error) => Err(AppError::from(format!(
+            "Audio decoding task failed: {error}"
+        ))),
+    };
+
+    match &result {
+        Ok(entry) => {
+            info!(
+                path = %path.display(),
+                entry_id = %entry.id,
+                transcript_chars = entry.text.chars().count(),
+                save_to_history,
+                "file transcribed"
+            );
+            emit_file_transcription(
+                app,
+                &path,
+                FileTranscriptionState::Completed,
+                Some(Ok(entry)),
+            );
+        }
+        Err(error) => {
+            error!(path = %path.display(), %error, "file transcription failed");
+            emit_file_transcription(app, &path, FileTranscriptionState::Failed, Some(Err(error)));
+        }
+    }
+    result
+}
+
+fn sync_watch_folders(app: &AppHandle) {
+    let Some(watch_folder_service) = app.try_state::<WatchFolderService>() else {
+        return;
+    };
+    let folders = app
+        .state::<AppState>()
+        .services
+        .settings_store
+        .current()
+        .watch_folders;
+    let handler_app = app.clone();
+    let handler: WatchFolderHandler = Arc::new(move |path, folder| {
+        let app = handler_app.clone();
+        Box::pin(async move { transcribe_watched_file(&app, path, folder).await })
+    });
+    watch_folder_service.sync(&folders, handler);
+}
+
+// Device presses go through the hotkey service, the same as the shortcut.
+fn sync_input_triggers(app: &AppHandle) {
+    let Some(input_trigger_service) = app.try_state::<InputTriggerService>() else {
+        return;
+    };
+    let bindings = app
+        .state::<AppState>()
+        .services
+        .settings_store
+        .current()
+        .input_triggers;
+    let handler_app = app.clone();
+    let handler: TriggerHandler = Arc::new(move |trigger| {
+        handler_app
+            .state::<HotkeyService>()
+            .handle_trigger(&handler_app, trigger);
+    });
+    input_trigger_service.sync(&bindings, handler);
+}
+
+// Reconnects bound devices that were unplugged or not there at launch.
+fn start_input_trigger_poller(app: &AppHandle) {
+    let app = app.clone();
+    let spawn_result = std::thread::Builder::new()
+        .name("input-trigger-poller".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(input_triggers::DEVICE_POLL_INTERVAL);
+            sync_input_triggers(&app);
+        });
+
+    if let Err(error) = spawn_result {
+        warn!(%error, "failed to start input trigger poller");
+    }
+}
+
+async fn transcribe_watched_file(app: &AppHandle, path: PathBuf, folder: WatchFolder) {
+    let Ok(entry) = transcribe_audio_file(app, path.clone(), None, folder.save_to_history).await
+    else {
+        return;
+    };
+    if folder.write_text_file {
+        let transcript_path = watch_folder_service::transcript_path_for(&path);
+        if let Err(error) = tokio::fs::write(&transcript_path, format!("{}\n", entry.text)).await {
+            warn!(
+                path = %transcript_path.display(),
+                %error,
+                "failed to write transcript next to watched file"
+            );
+        }
+    }
+}
+
+fn emit_file_transcription(
+    app: &AppHandle,
+    path: &Path,
+    state: FileTranscriptionState,
+    outcome: Option<Result<&HistoryEntry, &AppError>>,
+) {
+    let (entry, error) = match outcome {
+        Some(Ok(entry)) => (Some(entry), None),
+        Some(Err(error)) => (None, Some(error.message.clone())),
+        None => (None, None),
+    };
+    let event = FileTranscriptionEvent {
+        path: path.display().to_string(),
+        state,
+        entry_id: entry.map(|entry| entry.id.clone()),
+        text: entry.map(|entry| entry.text.clone()),
+        error,
+    };
+    if let Err(error) = app.emit(EVENT_FILE_TRANSCRIPTION, event) {
+        warn!(%error, "failed to emit file transcription event");
+    }
+}
+
+fn handle_dropped_files(app: &AppHandle, paths: &[PathBuf]) {
+    for path in paths {
+        if !audio_decoding::is_supported_audio_file(path) {
+            debug!(path = %path.display(), "ignoring dropped non-audio file");
+            continue;
+        }
+        let app = app.clone();
+        let path = path.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = transcribe_audio_file(&app, path, None, true).await;
+        });
+    }
+}
+
+// Transcribes audio that has no target app into history, without inserting.
+async fn transcribe_wav_into_history(
+    app: &AppHandle,
+    state: &AppState,
+    wav_bytes: Vec<u8>,
+    options: Option<TranscriptionOptions>,
+    source: HistorySource,
+) -> Result<HistoryEntry, AppError> {
+    let entry = transcribe_wav_to_entry(state, wav_bytes, options, source).await?;
+    app.state::<HistoryStore>().add_entry(entry.clone())?;
+    Ok(entry)
+}
+
+// Anything longer than one chunk is split the way meeting mode splits it. The
+// entry comes back redacted but not yet stored.
+async fn transcribe_wav_to_entry(
+    state: &AppState,
+    wav_bytes: Vec<u8>,
+    options: Option<TranscriptionOptions>,
+    source: HistorySource,
+) -> Result<HistoryEntry, AppError> {
+    let settings = state.services.settings_store.current();
+    let mut options = options.unwrap_or_else(|| TranscriptionOptions {
+        language: language_hint(&settings.preferred_languages),
+        ..TranscriptionOptions::default()
+    });
+    if options
+        .prompt
+        .as_deref()
+        .is_none_or(|prompt| prompt.trim().is_empty())
+    {
+        options.prompt = transcription_prompt(&settings, options.language.as_deref());
+    }
+    if options.context_hint.is_none() {
+        options.context_hint =
+            transcription_context_hint(&settings, options.language.as_deref(), None);
+    }
+    if options.decoding == DecodingOptions::default() {
+        options.decoding = settings.decoding.clone();
+    }
+    let chunked_config = ChunkedTranscriptionConfig {
+        audio_encoding: AudioEncoding::from_settings_value(&settings.audio_encoding),
+        ..ChunkedTranscriptionConfig::from_env()
+    };
+    let chunked = transcription::chunked::wav_duration_secs(&wav_bytes)
+        .is_some_and(|duration_secs| duration_secs > f64::from(chunked_config.chunk_secs));
+    let auth_method = state.services.current_auth_method()?;
+    let orchestrator = state.services.api_key_orchestrator(&settings);
+    let chatgpt_provider = state.services.chatgpt_transcription_provider.clone();
+    let (provider, result) = match auth_method {
+        AuthMethod::ApiKey if chunked => (
+            settings.transcription_provider.as_str(),
+            orchestrator
+                .transcribe_chunked(&wav_bytes, options, &chunked_config)
+                .await,
+        ),
+        AuthMethod::ApiKey => (
+            settings.transcription_provider.as_str(),
+            orchestrator.transcribe(wav_bytes, options).await,
+        ),
+        AuthMethod::ChatgptOauth if chunked => (
+            "chatgpt-oauth",
+            transcription::chunked::transcribe_chunked(
+                &chatgpt_provider,
+                &wav_bytes,
+                options,
+                &chunked_config,
+            )
+            .await,
+        ),
+        AuthMethod::ChatgptOauth => (
+            "chatgpt-oauth",
+            chatgpt_provider.transcribe(wav_bytes, options).await,
+        ),
+        AuthMethod::None => {
+            return Err(AppError::new(
+                ErrorCode::NoAuth,
+                "No authentication configured. Add an OpenAI API key or login with ChatGPT.",
+            ))
+        }
+    };
+    let transcription = result?;
+
+    let entry = HistoryEntry::new(
+        transcription.text.clone(),
+        transcription.duration_secs,
+        transcription.language,
+        provider.to_string(),
+    )
+    .with_segments(transcription.segments)
+    .with_words(transcription.words)
+    .with_confidence(transcription.confidence)
+    .with_source(source);
+    let (entry, _) = RedactionEngine::new(&settings.redaction_rules)?.redact_history_entry(entry);
+    Ok(entry)
+}
+
+#[tauri::command]
+async fn get_conflicting_instances() -> Result<Option<ConflictingInstanceReport>, AppError> {
+    tauri::async_runtime::spawn_blocking(instance_conflicts::detect)
+        .await
+        .map_err(|error| AppError::from(format!("Conflicting instance check failed: {error}")))
+}
+
+#[tauri::command]
+async fn quit_conflicting_instance(pid: u32) -> Result<(), AppError> {
+    tauri::async_runtime::spawn_blocking(move || instance_conflicts::request_quit(pid))
+        .await
+        .map_err(|error| format!("Failed to quit conflicting instance: {error}"))?
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+fn discard_recovered_recording(
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<bool, AppError> {
+    let recovery_dir = recovery_dir_for_state(&state)?;
+    audio_capture_service::discard_recovered_recording(&recovery_dir, &id).map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn run_e2e_selftest(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<SelfTestReport, AppError> {
+    if get_status_from_state(&state) != AppStatus::Idle {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            "Finish the current dictation before running the self-test",
+        ));
+    }
+    ensure_microphone_permission_for_recording(&state)?;
+    let auth_method = state.services.current_auth_method()?;
+    if auth_method == AuthMethod::None {
+        return Err(AppError::new(
+            ErrorCode::NoAuth,
+            "No authentication configured. Add an OpenAI API key or login with ChatGPT.",
+        ));
+    }
+
+    let settings = state.services.settings_store.current();
+    info!(
+        microphone_id = ?settings.microphone_id.as_deref(),
+        "end-to-end self-test started"
+    );
+    let audio_capture_service = &state.services.audio_capture_service;
+    audio_capture_service.start_recording(
+        app.clone(),
+        settings.microphone_id.as_deref(),
+        CaptureSource::Microphone,
+        InputProcessingConfig::from_settings(&settings),
+        None,
+    )?;
+
+    tokio::time::sleep(Duration::from_millis(SELFTEST_LEAD_IN_MS)).await;
+    let spoken = tauri::async_runtime::spawn_blocking(|| selftest::speak_phrase(SELFTEST_PHRASE))
+        .await
+        .map_err(|error| format!("Self-test playback task failed: {error}"))
+        .and_then(|result| result);
+    if let Err(error) = spoken {
+        if let Err(abort_error) = audio_capture_service.abort_recording(app.clone()) {
+            warn!(error = %abort_error, "failed to abort self-test recording");
+        }
+        return Err(AppError::from(error));
+    }
+    tokio::time::sleep(Duration::from_millis(SELFTEST_TAIL_MS)).await;
+    let mut recorded = audio_capture_service.stop_recording(app.clone())?;
+    let wav_bytes = recorded.take_wav().into_bytes()?;
+
+    // The phrase is English, so language detection is skipped.
+    let options = TranscriptionOptions {
+        language: Some("en".to_string()),
+        ..TranscriptionOptions::default()
+    };
+    let transcription = match auth_method {
+        AuthMethod::ApiKey => {
+            state
+                .services
+                .api_key_orchestrator(&settings)
+                .transcribe(wav_bytes, options)
+                .await
+        }
+        AuthMethod::ChatgptOauth => {
+            state
+                .services
+                .chatgpt_transcription_provider
+                .transcribe(wav_bytes, options)
+                .await
+        }
+        AuthMethod::None => unreachable!("auth method none is handled above"),
+    }
+    .map_err(|error| {
+        error!(%error, "self-test transcription failed");
+        error.to_string()
+    })?;
+
+    Ok(selftest::evaluate_selftest(
+        SELFTEST_PHRASE,
+        &transcription.text,
+        &recorded,
+    ))
+}
+
+// Unlike the self-test this needs no speaker, so support can ask anyone to
+// run it; each check reports on its own instead of stopping the run.
+#[tauri::command]
+async fn run_diagnostics(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<DiagnosticsReport, AppError> {
+    if get_status_from_state(&state) != AppStatus::Idle {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            "Finish the current dictation before running diagnostics",
+        ));
+    }
+    info!("diagnostics started");
+    let settings = state.services.settings_store.current();
+    let permission_service = &state.services.permission_service;
+    let mut checks = Vec::new();
+
+    let microphone_permission = permission_service.microphone_permission();
+    checks.push(diagnostics::permission_check(
+        DiagnosticCheckId::MicrophonePermission,
+        microphone_permission,
+    ));
+    checks.push(if microphone_permission == PermissionState::Granted {
+        run_capture_stream_check(&app, &state, &settings).await
+    } else {
+        DiagnosticCheck::skipped(DiagnosticCheckId::CaptureStream, "Needs microphone access")
+    });
+
+    let auth_method = state.services.current_auth_method()?;
+    if auth_method == AuthMethod::None {
+        checks.push(DiagnosticCheck::failed(
+            DiagnosticCheckId::Credentials,
+            AppError::new(
+                ErrorCode::NoAuth,
+                "No authentication configured. Add an OpenAI API key or login with ChatGPT.",
+            ),
+            None,
+        ));
+        checks.push(DiagnosticCheck::skipped(
+            DiagnosticCheckId::ProviderLatency,
+            "Needs working credentials",
+        ));
+    } else {
+        let wav_bytes = diagnostics::sample_wav()?;
+        let options = TranscriptionOptions {
+            language: Some("en".to_string()),
+            ..TranscriptionOptions::default()
+        };
+        let started_at = Instant::now();
+        let result = if auth_method == AuthMethod::ChatgptOauth {
+            state
+                .services
+                .chatgpt_transcription_provider
+                .transcribe(wav_bytes, options)
+                .await
+        } else {
+            state
+                .services
+                .api_key_orchestrator(&settings)
+                .transcribe(wav_bytes, options)
+                .await
+        };
+        checks.extend(diagnostics::provider_checks(result, started_at.elapsed()));
+    }
+
+    checks.push(diagnostics::permission_check(
+        DiagnosticCheckId::AccessibilityPermission,
+        permission_service.accessibility_permission(),
+    ));
+    Ok(DiagnosticsReport::new(
+        &app.package_info().version.to_string(),
+        checks,
+    ))
+}
+
+async fn run_capture_stream_check(
+    app: &AppHandle,
+    state: &AppState,
+    settings: &VoiceSettings,
+) -> DiagnosticCheck {
+    let started_at = Instant::now();
+    let audio_capture_service = &state.services.audio_capture_service;
+    if let Err(error) = audio_capture_service.start_recording(
+        app.clone(),
+        settings.microphone_id.as_deref(),
+        CaptureSource::Microphone,
+        InputProcessingConfig::from_settings(settings),
+        None,
+    ) {
+        return DiagnosticCheck::failed(
+            DiagnosticCheckId::CaptureStream,
+            error,
+            Some(started_at.elapsed()),
+        );
+    }
+    tokio::time::sleep(diagnostics::CAPTURE_CHECK_HOLD).await;
+    match audio_capture_service.abort_recording(app.clone()) {
+        Ok(_) => DiagnosticCheck::passed(
+            DiagnosticCheckId::CaptureStream,
+            "Opened and closed the input stream",
+            started_at.elapsed(),
+        ),
+        Err(error) => DiagnosticCheck::failed(
+            DiagnosticCheckId::CaptureStream,
+            AppError::from(error),
+            Some(started_at.elapsed()),
+        ),
+    }
+}
+
+#[tauri::command]
+fn list_history(
+    history_store: tauri::State<'_, HistoryStore>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    include_text: Option<bool>,
+) -> Result<Vec<HistoryEntry>, AppError> {
+    let page_limit = limit.unwrap_or(DEFAULT_HISTORY_PAGE_SIZE);
+    let page_offset = offset.unwrap_or(0);
+    let include_text = include_text.unwrap_or(true);
+    debug!(
+        limit = page_limit,
+        offset = page_offset,
+        include_text,
+        "history list requested"
+    );
+    Ok(history_store
+        .list_entries(page_limit, page_offset)?
+        .into_iter()
+        .map(|entry| entry.into_list_item(include_text))
+        .collect())
+}
+
+#[tauri::command]
+fn get_history_entry(
+    history_store: tauri::State<'_, HistoryStore>,
+    id: String,
+) -> Result<Option<HistoryEntry>, AppError> {
+    debug!(id = %id, "history lookup requested");
+    history_store.get_entry(&id)
+}
+
+#[tauri::command]
+fn export_history_entry(
+    history_store: tauri::State<'_, HistoryStore>,
+    id: String,
+) -> Result<String, AppError> {
+    info!(id = %id, "history entry export requested");
+    let entry = history_store.get_entry(&id)?.ok_or_else(|| {
+        AppError::new(
+            ErrorCode::NotFound,
+            format!("History entry `{id}` was not found"),
+        )
+    })?;
+    Ok(render_transcript_export(&entry))
+}
+
+#[tauri::command]
+fn export_history_entry_subtitles(
+    history_store: tauri::State<'_, HistoryStore>,
+    id: String,
+    format: SubtitleFormat,
+) -> Result<String, AppError> {
+    info!(id = %id, ?format, "history subtitle export requested");
+    let entry = history_store.get_entry(&id)?.ok_or_else(|| {
+        AppError::new(
+            ErrorCode::NotFound,
+            format!("History entry `{id}` was not found"),
+        )
+    })?;
+    Ok(render_subtitles(&entry, format))
+}
+
+#[tauri::command]
+async fn import_history(
+    app: AppHandle,
+    path: String,
+    format: HistoryImportFormat,
+) -> Result<HistoryImportReport, AppError> {
+    info!(?format, "history import requested");
+    tauri::async_runtime::spawn_blocking(move || {
+        import_history_file(&app, Path::new(&path), format)
+    })
+    .await
+    .map_err(|error| AppError::from(format!("History import task failed: {error}")))?
+}
+
+fn import_history_file(
+    app: &AppHandle,
+    path: &Path,
+    format: HistoryImportFormat,
+) -> Result<HistoryImportReport, AppError> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read history import file: {error}"))?;
+    let parsed = history_store::import::parse_import(&contents, format)
+        .map_err(|message| AppError::new(ErrorCode::InvalidInput, message))?;
+    let unreadable = parsed.total - parsed.entries.len();
+
+    // Imported transcripts go through the same redaction rules as new ones.
+    let settings = app.state::<AppState>().services.settings_store.current();
+    let redaction = RedactionEngine::new(&settings.redaction_rules)?;
+    let entries = parsed
+        .entries
+        .into_iter()
+        .map(|entry| redaction.redact_history_entry(entry).0)
+        .collect();
+
+    let mut report = app
+        .state::<HistoryStore>()
+        .import_entries(entries, |processed, total| {
+            let event = HistoryImportProgressEvent { processed, total };
+            if let Err(error) = app.emit(EVENT_HISTORY_IMPORT_PROGRESS, event) {
+                warn!(%error, "failed to emit history import progress event");
+            }
+        })?;
+    report.total = parsed.total;
+    report.skipped += unreadable;
+    Ok(report)
+}
+
+// Rewrites stored transcripts with a profile and keeps each result as a
+// revision next to the original. Stops early when the provider rejects the
+// key or quota, since every remaining entry would fail the same way.
+#[tauri::command]
+async fn reprocess_history(
+    app: AppHandle,
+    entry_ids: Vec<String>,
+    transform_profile: String,
+) -> Result<HistoryReprocessReport, AppError> {
+    let state = app.state::<AppState>();
+    let settings = state.services.settings_store.current();
+    let instructions =
+        transform_profile_instructions(&transform_profile, &settings.custom_transcription_prompt)
+            .map_err(|message| AppError::new(ErrorCode::InvalidInput, message))?;
+    if !matches!(state.services.current_auth_method(), Ok(AuthMethod::ApiKey)) {
+        return Err(AppError::new(
+            ErrorCode::NoAuth,
+            "Reprocessing history requires an OpenAI API key",
+        ));
+    }
+
+    let entry_ids = reprocess::unique_entry_ids(entry_ids);
+    let profile = transform_profile.trim().to_lowercase();
+    let total = entry_ids.len();
+    info!(total, profile = %profile, "history reprocessing requested");
+    let history_store = app.state::<HistoryStore>();
+    let mut report = HistoryReprocessReport {
+        total,
+        ..HistoryReprocessReport::default()
+    };
+    for (index, entry_id) in entry_ids.into_iter().enumerate() {
+        let mut error = None;
+        match history_store.get_entry(&entry_id)? {
+            None => report.missing += 1,
+            Some(entry) => match state
+                .services
+                .translation_client
+                .rewrite(&entry.text, &instructions)
+                .await
+            {
+                Ok(text) => {
+                    if history_store
+                        .add_revision(&entry_id, reprocess::revision(text, &profile))?
+                        .is_some()
+                    {
+                        report.reprocessed += 1;
+                    } else {
+                        report.missing += 1;
+                    }
+                }
+                Err(rewrite_error) => {
+                    let rewrite_error = AppError::from(rewrite_error);
+                    warn!(entry_id = %entry_id, error = %rewrite_error, "history entry rewrite failed");
+                    if matches!(rewrite_error.code, ErrorCode::NoAuth | ErrorCode::Quota) {
+                        return Err(rewrite_error);
+                    }
+                    report.failed += 1;
+                    error = Some(rewrite_error.message);
+                }
+            },
+        }
+
+        let event = HistoryReprocessProgressEvent {
+            processed: index + 1,
+            total,
+            entry_id,
+            error,
+        };
+        if let Err(error) = app.emit(EVENT_HISTORY_REPROCESS_PROGRESS, event) {
+            warn!(%error, "failed to emit history reprocess progress event");
+        }
+    }
+    info!(
+        reprocessed = report.reprocessed,
+        missing = report.missing,
+        failed = report.failed,
+        "history reprocessing finished"
+    );
+    Ok(report)
+}
+
+// Computed from local history only; nothing is sent to a provider.
+#[tauri::command]
+fn get_vocabulary_report(
+    history_store: tauri::State<'_, HistoryStore>,
+    range: Option<VocabularyRange>,
+) -> Result<VocabularyReport, AppError> {
+    let range = range.unwrap_or_default();
+    debug!(?range, "vocabulary report requested");
+    let entries = history_store.all_entries()?;
+    Ok(vocabulary::build_vocabulary_report(
+        &entries,
+        range,
+        chrono::Utc::now(),
+    ))
+}
+
+#[tauri::command]
+fn delete_history_entry(
+    history_store: tauri::State<'_, HistoryStore>,
+    id: String,
+) -> Result<bool, AppError> {
+    info!(id = %id, "history delete requested");
+    history_store.delete_entry(&id)
+}
+
+#[tauri::command]
+fn clear_history(history_store: tauri::State<'_, HistoryStore>) -> Result<(), AppError> {
+    info!("history clear requested");
+    history_store.clear_history()
+}
+
+#[tauri::command]
+fn get_usage_stats(
+    state: tauri::State<'_, AppState>,
+    stats_store: tauri::State<'_, StatsStore>,
+) -> Result<UsageStatsReport, AppError> {
+    debug!("usage stats requested");
+    let privacy_mode = state.services.settings_store.current().stats_privacy_mode;
+    stats_store.get_usage_stats(privacy_mode)
+}
+
+#[tauri::command]
+fn get_usage_stats_range(
+    from: String,
+    to: String,
+    state: tauri::State<'_, AppState>,
+    stats_store: tauri::State<'_, StatsStore>,
+) -> Result<Vec<DailyUsage>, AppError> {
+    debug!(%from, %to, "usage stats range requested");
+    let privacy_mode = state.services.settings_store.current().stats_privacy_mode;
+    stats_store.get_usage_stats_range(&from, &to, privacy_mode)
+}
+
+#[tauri::command]
+fn get_usage_stats_by_app(
+    state: tauri::State<'_, AppState>,
+    stats_store: tauri::State<'_, StatsStore>,
+) -> Result<Vec<AppUsage>, AppError> {
+    debug!("usage stats by app requested");
+    let privacy_mode = state.services.settings_store.current().stats_privacy_mode;
+    stats_store.get_usage_stats_by_app(privacy_mode)
+}
+
+#[tauri::command]
+fn reset_usage_stats(stats_store: tauri::State<'_, StatsStore>) -> Result<(), AppError> {
+    info!("usage stats reset requested");
+    stats_store.reset_usage_stats()
+}
+
+#[tauri::command]
+fn list_crash_reports(app: AppHandle) -> Result<Vec<CrashReport>, AppError> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|error| format!("Failed to resolve app data directory: {error}"))?;
+    crash_reporter::list_crash_reports(&crash_reporter::crash_reports_dir(&app_data_dir))
+        .map_err(AppError::from)
+}
+
+// Runs once per launch, which is when reports from the previous run's crash
+// are first available.
+fn upload_crash_reports(app_data_dir: &Path, settings: &VoiceSettings) {
+    let Some(url) = settings
+        .crash_report_upload_url
+        .clone()
+        .filter(|_| settings.crash_report_upload_enabled)
+    else {
+        return;
+    };
+    let dir = crash_reporter::crash_reports_dir(app_data_dir);
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = crash_reporter::upload_pending(&dir, &url).await {
+            warn!(%error, "failed to upload crash reports");
+        }
+    });
+}
+
+#[tauri::command]
+fn list_telemetry_events(
+    telemetry_queue: tauri::State<'_, TelemetryQueue>,
+) -> Result<Vec<TelemetryEvent>, AppError> {
+    Ok(telemetry_queue.pending())
+}
+
+// Only records when the user opted in; a full batch is sent right away when
+// an endpoint is set.
+fn record_telemetry(app: &AppHandle, kind: TelemetryEventKind) {
+    let settings = app.state::<AppState>().services.settings_store.current();
+    if !settings.telemetry_enabled {
+        return;
+    }
+    let Some(telemetry_queue) = app.try_state::<TelemetryQueue>() else {
+        return;
+    };
+    match telemetry_queue.record(TelemetryEvent::new(kind)) {
+        Ok(queued) if queued >= telemetry::TELEMETRY_BATCH_SIZE => flush_telemetry(app, &settings),
+        Ok(_) => {}
+        Err(error) => warn!(%error, "failed to queue telemetry event"),
+    }
+}
+
+fn flush_telemetry(app: &AppHandle, settings: &VoiceSettings) {
+    let Some(endpoint) = settings.telemetry_endpoint.clone() else {
+        return;
+    };
+    let app = app.clone();
+    let app_version = app.package_info().version.to_string();
+    tauri::async_runtime::spawn(async move {
+        let telemetry_queue = app.state::<TelemetryQueue>();
+        if let Err(error) = telemetry_queue.flush(&endpoint, &app_version).await {
+            warn!(%error, "failed to send telemetry");
+        }
+    });
+}
+
+// The kill switch: switching telemetry off drops whatever is still queued.
+fn sync_telemetry(app: &AppHandle) {
+    let Some(telemetry_queue) = app.try_state::<TelemetryQueue>() else {
+        return;
+    };
+    let settings = app.state::<AppState>().services.settings_store.current();
+    if settings.telemetry_enabled {
+        flush_telemetry(app, &settings);
+    } else if let Err(error) = telemetry_queue.clear() {
+        warn!(%error, "failed to clear telemetry queue");
+    }
+}
+
+// Settings and snippets only; API keys, proxy passwords and machine-specific
+// settings stay on this machine.
+#[tauri::command]
+fn export_app_data(
+    app: AppHandle,
+    path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), AppError> {
+    info!(path = %path, "app data export requested");
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|error| format!("Failed to resolve app data directory: {error}"))?;
+    let services = &state.services;
+    let archive = app_data_sync::build_archive(
+        &services.settings_store.current(),
+        services.snippets_store.list(),
+    )?;
+    app_data_sync::write_archive(Path::new(&path), &archive)?;
+    app_data_sync::save_sync_base(&app_data_dir, &archive).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn import_app_data(
+    app: AppHandle,
+    path: String,
+    strategy: Option<AppDataConflictStrategy>,
+    state: tauri::State<'_, AppState>,
+) -> Result<AppDataImportReport, AppError> {
+    let strategy = strategy.unwrap_or_default();
+    info!(path = %path, ?strategy, "app data import requested");
+    state
+        .command_guard
+        .run(CommandResource::Settings, "import_app_data", || {
+            let app_data_dir = app
+                .path()
+                .app_data_dir()
+                .map_err(|error| format!("Failed to resolve app data directory: {error}"))?;
+            let imported = app_data_sync::read_archive(Path::new(&path))?;
+            let services = &state.services;
+            let merge = app_data_sync::merge_archive(
+                &services.settings_store.current(),
+                &services.snippets_store.list(),
+                &imported,
+                app_data_sync::load_sync_base(&app_data_dir).as_ref(),
+                strategy,
+            )?;
+
+            let settings = services.settings_store.replace(&app, merge.settings)?;
+            services.snippets_store.replace_all(merge.snippets)?;
+            apply_externally_edited_settings(&app, &settings);
+            app_data_sync::save_sync_base(&app_data_dir, &imported)?;
+            info!(
+                settings_updated = merge.report.settings_updated.len(),
+                snippets_added = merge.report.snippets_added,
+                snippets_updated = merge.report.snippets_updated,
+                snippets_removed = merge.report.snippets_removed,
+                conflicts = merge.report.conflicts.len(),
+                "imported app data"
+            );
+            Ok(merge.report)
+        })
+}
+
+#[tauri::command]
+fn export_logs(log_state: tauri::State<'_, LoggingState>) -> Result<String, AppError> {
+    info!(
+        log_file = %log_state.log_file_path().display(),
+        "diagnostic log export requested"
+    );
+    logging::export_log_contents(&log_state).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn query_logs(
+    level: Option<LogLevel>,
+    since: Option<String>,
+    limit: Option<usize>,
+    log_state: tauri::State<'_, LoggingState>,
+) -> Result<Vec<LogEntry>, AppError> {
+    logging::query_logs(
+        &log_state,
+        level,
+        since.as_deref(),
+        limit.unwrap_or(logging::DEFAULT_LOG_QUERY_LIMIT),
+    )
+    .map_err(AppError::from)
+}
+
+#[tauri::command]
+fn set_log_level(
+    level: LogLevel,
+    log_state: tauri::State<'_, LoggingState>,
+) -> Result<LogLevel, AppError> {
+    log_state.set_level(level)?;
+    Ok(level)
+}
+
+#[tauri::command]
+fn export_support_bundle(
+    app: AppHandle,
+    destination_path: Option<String>,
+    state: tauri::State<'_, AppState>,
+    stats_store: tauri::State<'_, StatsStore>,
+    log_state: tauri::State<'_, LoggingState>,
+) -> Result<String, AppError> {
+    let bundle_path = match destination_path
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let app_data_dir = app
+                .path()
+                .app_data_dir()
+                .map_err(|error| format!("Failed to resolve app data directory: {error}"))?;
+            support_bundle::default_bundle_path(&app_data_dir)
+        }
+    };
+    info!(path = %bundle_path.display(), "support bundle export requested");
+
+    let services = &state.services;
+    let logs = logging::export_log_contents(&log_state).unwrap_or_else(|error| {
+        warn!(%error, "failed to read logs for support bundle");
+        format!("<unavailable: {error}>")
+    });
+    let devices = match services.audio_capture_service.list_microphones() {
+        Ok(microphones) => to_bundle_value(&microphones),
+        Err(error) => serde_json::json!({ "error": error }),
+    };
+    let privacy_mode = services.settings_store.current().stats_privacy_mode;
+    let session_metrics = match stats_store.get_usage_stats(privacy_mode) {
+        Ok(report) => to_bundle_value(&report),
+        Err(error) => serde_json::json!({ "error": error }),
+    };
+    let auth_method = services
+        .current_auth_method()
+        .map(|method| method.as_str().to_string())
+        .unwrap_or_else(|error| format!("unknown ({error})"));
+    let last_session_trace = match services.session_trace.last_trace() {
+        Ok(trace) => to_bundle_value(&trace_timeline(trace)),
+        Err(error) => serde_json::json!({ "error": error }),
+    };
+    let diagnostics = serde_json::json!({
+        "appVersion": app.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "status": get_status_from_state(&state),
+        "authMethod": auth_method,
+    });
+
+    let manifest = support_bundle::write_support_bundle(
+        &bundle_path,
+        support_bundle::SupportBundleContents {
+            app_version: app.package_info().version.to_string(),
+            logs,
+            diagnostics,
+            settings: to_bundle_value(&services.settings_store.current()),
+            devices,
+            permissions: to_bundle_value(&services.permission_service.check_permissions()),
+            session_metrics,
+            last_session_trace,
+        },
+    )?;
+    info!(
+        path = %bundle_path.display(),
+        file_count = manifest.files.len(),
+        "support bundle exported"
+    );
+
+    Ok(bundle_path.display().to_string())
+}
+
+// Lets the frontend show what changed after an update, given the version it
+// last saw.
+#[tauri::command]
+fn get_release_notes(
+    app: AppHandle,
+    since_version: Option<String>,
+) -> Result<ReleaseNotesReport, AppError> {
+    let current_version = app.package_info().version.to_string();
+    info!(
+        since_version = ?since_version.as_deref(),
+        current_version = %current_version,
+        "release notes requested"
+    );
+    release_notes::release_notes_since(since_version.as_deref(), &current_version)
+        .map_err(AppError::from)
+}
+
+// Latest value of the focused-app stream, for windows that open after the
+// last change event.
+#[tauri::command]
+fn get_focused_app(watcher: tauri::State<'_, FocusedAppWatcher>) -> Option<FocusedApp> {
+    watcher.current()
+}
+
+// Returns the report persisted on first launch; `refresh` re-runs the probe,
+// e.g. after the user plugs in a microphone or grants Input Monitoring.
+#[tauri::command]
+async fn get_compatibility_report(
+    app: AppHandle,
+    refresh: Option<bool>,
+) -> Result<CompatibilityReport, AppError> {
+    let probe = app.state::<CompatibilityProbe>();
+    if !refresh.unwrap_or(false) {
+        if let Some(report) = probe.report() {
+            return Ok(report);
+        }
+    }
+    run_compatibility_probe(&app).await.map_err(AppError::from)
+}
+
+async fn run_compatibility_probe(app: &AppHandle) -> Result<CompatibilityReport, String> {
+    let microphone_available = app
+        .state::<AppState>()
+        .services
+        .audio_capture_service
+        .list_microphones()
+        .map(|microphones| !microphones.is_empty())
+        .unwrap_or_else(|error| {
+            warn!(%error, "failed to list microphones for compatibility probe");
+            false
+        });
+    let app_version = app.package_info().version.to_string();
+    app.state::<CompatibilityProbe>()
+        .run(&app_version, microphone_available)
+        .await
+}
+
+fn run_first_launch_compatibility_probe(app: &AppHandle) {
+    if app.state::<CompatibilityProbe>().report().is_some() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = run_compatibility_probe(&app).await {
+            warn!(%error, "first-launch compatibility probe failed");
+        }
+    });
+}
+
+fn to_bundle_value<T: Serialize>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value)
+        .unwrap_or_else(|error| serde_json::json!({ "error": error.to_string() }))
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        info!("showing main window");
+        if let Err(error) = window.show() {
+            warn!(%error, "failed to show main window");
+        }
+        if let Err(error) = window.set_focus() {
+            warn!(%error, "failed to focus main window");
+        }
+    } else {
+        warn!("main window was not found while attempting to show");
+    }
+}
+
+fn hide_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        info!("hiding main window");
+        if let Err(error) = window.hide() {
+            warn!(%error, "failed to hide main window");
+        }
+    } else {
+        warn!("main window was not found while attempting to hide");
+    }
+}
+
+fn setup_tray_icon(app: &tauri::App) -> tauri::Result<()> {
+    let show_item = MenuItem::with_id(app, "show_window", "Open Voice", true, None::<&str>)?;
+    let hide_item = MenuItem::with_id(app, "hide_window", "Hide Voice", true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit Voice", true, None::<&str>)?;
+    // Filled in by `sync_tray_quick_settings` once the tray is managed.
+    let microphone_menu = Submenu::with_id(app, MENU_MICROPHONES, "Microphone", true)?;
+    let recording_mode_menu = Submenu::with_id(app, MENU_RECORDING_MODES, "Recording Mode", true)?;
+    let profile_menu = Submenu::with_id(app, MENU_PROFILES, "Profile", true)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let tray_menu = Menu::with_items(
+        app,
+        &[
+            &microphone_menu,
+            &recording_mode_menu,
+            &profile_menu,
+            &separator,
+            &show_item,
+            &hide_item,
+            &quit_item,
+        ],
+    )?;
+    let recording_timer_item =
+        MenuItem::with_id(app, MENU_RECORDING_TIMER, "Recording", false, None::<&str>)?;
+    let cancel_recording_item = MenuItem::with_id(
+        app,
+        MENU_CANCEL_RECORDING,
+        "Cancel Recording",
+        true,
+        None::<&str>,
+    )?;
+
+    let tray_icon_image =
+        tauri::image::Image::from_bytes(include_bytes!("../../icons/tray-icon.png"))
+            .expect("failed to decode tray icon PNG");
+    let tray_menu_state = TrayMenuState::new(
+        tray_menu,
+        microphone_menu,
+        recording_mode_menu,
+        profile_menu,
+        recording_timer_item,
+        cancel_recording_item,
+        tray_icon_image,
+    );
+
+    tauri::tray::TrayIconBuilder::with_id(TRAY_ID)
+        .icon(tray_menu_state.idle_icon())
+        .icon_as_template(true)
+        .menu(tray_menu_state.menu())
+        .show_menu_on_left_click(false)
+        .on_tray_icon_event(|tray, event| match event {
+            TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } => toggle_main_window(tray.app_handle()),
+            // Before the menu can open, so a microphone plugged in since the
+            // last settings change is already listed.
+            TrayIconEvent::Enter { .. } => sync_tray_quick_settings(tray.app_handle()),
+            _ => {}
+        })
+        .on_menu_event(|app, event| {
+            handle_tray_menu_event(app, event.id().as_ref());
+        })
+        .build(app)?;
+    app.manage(tray_menu_state);
+    sync_tray_quick_settings(app.handle());
+    info!("tray icon initialized");
+    Ok(())
+}
+
+fn should_hide_main_window_on_startup(settings: &VoiceSettings) -> bool {
+    settings.onboarding_completed
+}
+
+fn toggle_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        match window.is_visible() {
+            Ok(true) => {
+                info!("toggling main window to hidden");
+                if let Err(error) = window.hide() {
+                    warn!(%error, "failed to hide main window while toggling");
+                }
+            }
+            _ => {
+                info!("toggling main window to visible");
+                if let Err(error) = window.show() {
+                    warn!(%error, "failed to show main window while toggling");
+                }
+                if let Err(error) = window.set_focus() {
+                    warn!(%error, "failed to focus main window while toggling");
+                }
+            }
+        }
+    } else {
+        warn!("main window was not found while toggling visibility");
+    }
+}
+
+fn handle_tray_menu_event(app: &AppHandle, menu_id: &str) {
+    info!(menu_id, "tray menu event received");
+    match menu_id {
+        "show_window" => show_main_window(app),
+        "hide_window" => hide_main_window(app),
+        MENU_CANCEL_RECORDING => {
+            if let Err(error) = cancel_recording(app.clone(), app.state::<AppState>()) {
+                warn!(%error, "failed to cancel recording from tray menu");
+            }
+        }
+        "quit" => {
+            info!("quitting app from tray menu");
+            app.exit(0);
+        }
+        _ => match QuickSetting::from_menu_id(menu_id) {
+            Some(setting) => apply_quick_setting(app, setting),
+            None => warn!(menu_id, "unknown tray menu event"),
+        },
+    }
+}
+
+fn apply_quick_setting(app: &AppHandle, setting: QuickSetting) {
+    info!(?setting, "applying quick setting from tray menu");
+    match apply_settings_update(app, setting.into_update(), None) {
+        Ok(settings) => {
+            if let Err(error) = app.emit(EVENT_SETTINGS_CHANGED, &settings) {
+                warn!(%error, "failed to emit settings changed event");
+            }
+        }
+        Err(error) => {
+            warn!(%error, "failed to apply quick setting from tray menu");
+            // Puts the check marks back on the settings that are in effect.
+            sync_tray_quick_settings(app);
+        }
+    }
+}
+
+fn sync_tray_quick_settings(app: &AppHandle) {
+    let Some(tray_menu) = app.try_state::<TrayMenuState>() else {
+        return;
+    };
+    let state = app.state::<AppState>();
+    let settings = state.services.settings_store.current();
+    let microphones = state
+        .services
+        .audio_capture_service
+        .list_microphones()
+        .unwrap_or_else(|error| {
+            warn!(%error, "failed to list microphones for tray menu");
+            Vec::new()
+        });
+    tray_menu.refresh_quick_settings(app, &settings, &microphones);
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    info!("starting tauri app builder");
+    tauri::Builder::default()
+        .plugin(tauri_plugin_autostart::init(
+            MacosLauncher::LaunchAgent,
+            None::<Vec<&str>>,
+        ))
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .manage(HotkeyService::new())
+        .manage(PipelineRuntimeState::default())
+        .manage(FocusedAppWatcher::new())
+        .manage(OverlayKeyService::new())
+        .manage(OverlayPositionState::default())
+        .manage(RetroShortcutService::new())
+        .manage(PrivateShortcutService::new())
+        .setup(|app| {
+            let logging_state = logging::initialize(app.handle()).map_err(std::io::Error::other)?;
+            app.manage(logging_state);
+
+            #[cfg(target_os = "macos")]
+            app.set_activation_policy(tauri::ActivationPolicy::Accessory);
+            info!("setup started");
+
+            let app_data_dir = app
+                .path()
+                .app_data_dir()
+                .map_err(std::io::Error::other)?;
+            migrate_legacy_app_data_dir(&app_data_dir);
+            crash_reporter::install(&app_data_dir, &app.package_info().version.to_string());
+            app.manage(AppState::new(app_data_dir.clone()));
+            info!(path = %app_data_dir.display(), "app state initialized");
+
+            let history_store = HistoryStore::new(app.handle()).map_err(std::io::Error::other)?;
+            app.manage(history_store);
+            info!("history store initialized");
+
+            let stats_store = StatsStore::new(app.handle()).map_err(std::io::Error::other)?;
+            app.manage(stats_store);
+            info!("usage stats store initialized");
+
+            app.manage(CompatibilityProbe::new(&app_data_dir));
+            app.manage(ControlServer::new(control_server::socket_path(&app_data_dir)));
+            app.manage(WatchFolderService::new());
+            app.manage(InputTriggerService::new());
+            app.manage(TelemetryQueue::new_in_dir(&app_data_dir));
+            app.manage(PendingTranscriptionStore::new_in_dir(&app_data_dir));
+            spawn_pending_transcription_worker(app.handle().clone());
+
+            app.handle()
+                .plugin(tauri_plugin_global_shortcut::Builder::new().build())?;
+            info!("global shortcut plugin initialized");
+
+            let hotkey_service = app.state::<HotkeyService>();
+            let app_state = app.state::<AppState>();
+
+            let permission_state = app_state
+                .services
+                .permission_service
+                .microphone_permission();
+            info!(?permission_state, "microphone permission check completed");
+
+            let settings = load_startup_settings_with_fallback(|| {
+                app_state.services.settings_store.load(app.handle())
+            });
+            let launch_at_login = settings.launch_at_login;
+            app_state.services.apply_live_settings(&settings);
+            if let Err(error) = apply_history_encryption(app.handle(), settings.history_encryption)
+            {
+                warn!(%error, "history encryption could not be applied at startup");
+            }
+            if let Err(error) = app_state
+                .services
+                .insertion_audit
+                .prune(settings.insertion_audit_retention_days, chrono::Utc::now())
+            {
+                warn!(%error, "failed to prune insertion audit log");
+            }
+
+            apply_hotkey_from_settings_with_fallback(
+                &settings,
+                |config| {
+                    hotkey_service
+                        .apply_config(app.handle(), config)
+                        .map(|_| ())
+                },
+                || hotkey_service.register_default_shortcut(app.handle()),
+            )
+            .map_err(std::io::Error::other)?;
+            info!("hotkey configuration applied");
+            sync_retro_shortcut(app.handle());
+            sync_private_shortcut(app.handle());
+            sync_control_server(app.handle());
+            sync_watch_folders(app.handle());
+            sync_input_triggers(app.handle());
+            sync_telemetry(app.handle());
+            upload_crash_reports(&app_data_dir, &settings);
+
+            if let Err(error) = set_launch_at_login_state(app.handle(), launch_at_login) {
+                warn!(%error, "failed to apply launch-at-login preference");
+            }
+
+            setup_recording_overlay_window(app.handle());
+            setup_insert_review_window(app.handle());
+            register_overlay_audio_forwarder(app.handle());
+            register_pipeline_handlers(app.handle());
+            register_deep_link_handler(app.handle());
+            register_automation_handler(app.handle());
+            announce_recovered_recordings(app.handle());
+            announce_conflicting_instances(app.handle());
+            app.state::<FocusedAppWatcher>().start(app.handle().clone());
+            app_state
+                .services
+                .permission_service
+                .start_watching(app.handle().clone());
+            start_settings_file_watcher(app.handle());
+            start_input_trigger_poller(app.handle());
+            start_chatgpt_token_renewal(app.handle());
+            run_first_launch_compatibility_probe(app.handle());
+            set_status_for_app(app.handle(), AppStatus::Idle);
+            info!("overlay, pipeline handlers, and initial status configured");
+
+            if settings.background_only {
+                info!("background-only mode; skipping menu bar icon");
+            } else {
+                setup_tray_icon(app)?;
+            }
+
+            if should_hide_main_window_on_startup(&settings) {
+                hide_main_window(app.handle());
+                info!("setup complete in tray-only mode");
+            } else {
+                show_main_window(app.handle());
+                info!("setup complete with onboarding window visible");
+            }
+
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                if window.label() == "main" {
+                    handle_dropped_files(window.app_handle(), paths);
+                }
+            }
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                api.prevent_close();
+                info!(window = %window.label(), "window close requested; hiding instead");
+                if let Err(error) = window.hide() {
+                    warn!(%error, window = %window.label(), "failed to hide window on close request");
+                }
+            }
+        })
+        .invoke_handler(tauri::generate_handler![
+            get_status,
+            set_status,
+            get_status_details,
+            get_settings,
+            get_settings_snapshot,
+            get_onboarding_status,
+            complete_onboarding,
+            update_settings,
+            apply_settings,
+            list_redaction_rules,
+            set_redaction_rules,
+            list_snippets,
+            create_snippet,
+            update_snippet,
+            delete_snippet,
+            update_provider_config,
+            get_launch_at_login,
+            set_launch_at_login,
+            has_api_key,
+            get_auth_method,
+            set_auth_method,
+            get_chatgpt_auth_status,
+            get_auth_status,
+            start_chatgpt_login,
+            start_oauth_login,
+            logout_chatgpt,
+            save_api_key,
+            set_api_key,
+            validate_api_key,
+            list_transcription_models,
+            delete_api_key,
+            list_microphones,
+            list_capture_sources,
+            list_input_triggers,
+            check_permissions,
+            request_permission,
+            wait_for_permission,
+            request_mic_permission,
+            open_accessibility_settings,
+            start_recording,
+            stop_recording,
+            complete_recording,
+            cancel_recording,
+            stop_recording_from_overlay,
+            cancel_recording_from_overlay,
+            save_overlay_offset,
+            pin_realtime_language,
+            get_audio_level,
+            insert_text,
+            copy_to_clipboard,
+            get_pending_insert,
+            resolve_pending_insert,
+            get_flagged_transcript,
+            retry_flagged_transcript,
+            dismiss_flagged_transcript,
+            retranscribe_in_language,
+            dismiss_language_suggestion,
+            get_insertion_audit_log,
+            get_browser_bridge_pairing_token,
+            get_last_session_trace,
+            transcribe_audio,
+            transcribe_file,
+            transcribe_last_seconds,
+            get_conflicting_instances,
+            quit_conflicting_instance,
+            list_recovered_recordings,
+            transcribe_recovered_recording,
+            list_pending_transcriptions,
+            retry_pending_transcriptions,
+            discard_pending_transcription,
+            discard_recovered_recording,
+            run_e2e_selftest,
+            run_diagnostics,
+            list_history,
+            get_history_entry,
+            export_history_entry,
+            export_history_entry_subtitles,
+            import_history,
+            reprocess_history,
+            get_vocabulary_report,
+            delete_history_entry,
+            clear_history,
+            get_usage_stats,
+            get_usage_stats_range,
+            get_usage_stats_by_app,
+            reset_usage_stats,
+            export_logs,
+            list_crash_reports,
+            list_telemetry_events,
+            query_logs,
+            set_log_level,
+            export_support_bundle,
+            export_app_data,
+            import_app_data,
+            get_release_notes,
+            get_focused_app,
+            get_compatibility_report,
+            hotkey_service::get_hotkey_config,
+            hotkey_service::get_hotkey_recording_state,
+            hotkey_service::dump_hotkey_state,
+            reset_hotkey_state,
+            hotkey_service::set_hotkey_config
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        path::{Path, PathBuf},
+        sync::{atomic::Ordering, Arc, Mutex},
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+    use tauri::{LogicalPosition, PhysicalPosition, PhysicalSize};
+    use tokio::sync::{oneshot, Notify};
+    use uuid::Uuid;
+
+    use crate::{
+        app_error::{AppError, ErrorCode},
+        audio_capture_service::RecordedWav,
+        hotkey_service::{HotkeyConfig, RecordingMode},
+        settings_store::{
+            OverlayOffset, VoiceSettings, VoiceSettingsUpdate, RECORDING_MODE_TOGGLE,
+        },
+        status_notifier::AppStatus,
+        transcription::prompt::CASUAL_TRANSCRIPTION_PROMPT,
+        voice_pipeline::{
+            PipelineError, PipelineErrorStage, PipelineTranscript, VoicePipeline,
+            VoicePipelineDelegate,
+        },
+    };
+
+    use super::{
+        active_pipeline_session_id, apply_hotkey_from_settings_with_fallback,
+        apply_settings_transaction_with_hooks, cancel_recording_with_hooks,
+        copy_directory_contents, handle_audio_input_stream_error_with_hooks, has_api_key,
+        load_startup_settings_with_fallback, migrate_legacy_app_data_dir, overlay_offset_from_drag,
+        overlay_position_from_work_area, permission_preflight_error_message,
+        should_hide_main_window_on_startup, should_show_overlay_for_status,
+        spawn_pipeline_stage_error_reset, transform_profile_instructions, AppState,
+        OverlayPlacement, PipelineRuntimeState, OVERLAY_ANCHOR_GAP, OVERLAY_WINDOW_HEIGHT,
+        OVERLAY_WINDOW_TOP_MARGIN, OVERLAY_WINDOW_WIDTH,
+    };
+    use crate::permission_service::{PermissionState, PermissionType};
+
+    #[derive(Debug)]
+    struct TempDirGuard {
+        path: PathBuf,
+    }
+
+    impl TempDirGuard {
+        fn new(prefix: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("{prefix}-{}", Uuid::new_v4()));
+            std::fs::create_dir_all(&path).expect("temporary directory should be created");
+            Self { path }
+        }
+
+        fn path(&self) -> &Path {
+            &self.path
+        }
+    }
+
+    impl Drop for TempDirGuard {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct SessionEventLog {
+        statuses: Mutex<Vec<(u64, AppStatus)>>,
+        transcripts: Mutex<Vec<(u64, String)>>,
+        insertions: Mutex<Vec<(u64, String)>>,
+        errors: Mutex<Vec<(u64, PipelineError)>>,
+    }
+
+    impl SessionEventLog {
+        fn statuses_for(&self, session_id: u64) -> Vec<AppStatus> {
+            self.statuses
+                .lock()
+                .expect("status lock should not be poisoned")
+                .iter()
+                .filter_map(|(id, status)| (*id == session_id).then_some(*status))
+                .collect()
+        }
+
+        fn transcripts(&self) -> Vec<(u64, String)> {
+            self.transcripts
+                .lock()
+                .expect("transcript lock should not be poisoned")
+                .clone()
+        }
+
+        fn insertions(&self) -> Vec<(u64, String)> {
+            self.insertions
+                .lock()
+                .expect("insertion lock should not be poisoned")
+                .clone()
+        }
+
+        fn errors(&self) -> Vec<(u64, PipelineError)> {
+            self.errors
+                .lock()
+                .expect("error lock should not be poisoned")
+                .clone()
+        }
+    }
+
+    #[derive(Debug)]
+    struct SessionAwareDelegate {
+        runtime: PipelineRuntimeState,
+        session_id: u64,
+        event_log: Arc<SessionEventLog>,
+        transcript: String,
+        transcribe_started_tx: Mutex<Option<oneshot::Sender<()>>>,
+        transcribe_blocker: Option<Arc<Notify>>,
+    }
+
+    impl SessionAwareDelegate {
+        fn new(
+            runtime: PipelineRuntimeState,
+            session_id: u64,
+            event_log: Arc<SessionEventLog>,
+            transcript: &str,
+        ) -> Self {
+            Self {
+                runtime,
+                session_id,
+                event_log,
+                transcript: transcript.to_string(),
+                transcribe_started_tx: Mutex::new(None),
+                transcribe_blocker: None,
+            }
+        }
+
+        fn with_transcription_gate(
+            mut self,
+            started_tx: oneshot::Sender<()>,
+            blocker: Arc<Notify>,
+        ) -> Self {
+            self.transcribe_started_tx = Mutex::new(Some(started_tx));
+            self.transcribe_blocker = Some(blocker);
+            self
+        }
+
+        fn is_active(&self) -> bool {
+            self.runtime.is_session_active(self.session_id)
+        }
+    }
+
+    #[async_trait]
+    impl VoicePipelineDelegate for SessionAwareDelegate {
+        fn set_status(&self, status: AppStatus) {
+            if self.is_active() {
+                self.event_log
+                    .statuses
+                    .lock()
+                    .expect("status lock should not be poisoned")
+                    .push((self.session_id, status));
+            }
+        }
+
+        fn emit_transcript(&self, transcript: &PipelineTranscript) {
+            if self.is_active() {
+                self.event_log
+                    .transcripts
+                    .lock()
+                    .expect("transcript lock should not be poisoned")
+                    .push((self.session_id, transcript.text_to_insert().to_string()));
+            }
+        }
+
+        fn emit_error(&self, error: &PipelineError) {
+            if self.is_active() {
+                self.event_log
+                    .errors
+                    .lock()
+                    .expect("error lock should not be poisoned")
+                    .push((self.session_id, error.clone()));
+            }
+        }
+
+        fn start_recording(&self) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        fn stop_recording(&self) -> Result<RecordedWav, AppError> {
+            Ok(RecordedWav::Memory(vec![1, 2, 3]))
+        }
+
+        async fn transcribe(&self, _wav: RecordedWav) -> Result<PipelineTranscript, AppError> {
+            if let Some(started_tx) = self
+                .transcribe_started_tx
+                .lock()
+                .expect("transcription gate lock should not be poisoned")
+                .take()
+            {
+                let _ = started_tx.send(());
+            }
+
+            if let Some(blocker) = &self.transcribe_blocker {
+                blocker.notified().await;
+            }
+
+            Ok(PipelineTranscript {
+                text: self.transcript.clone(),
+                duration_secs: None,
+                language: None,
+                language_confidence: None,
+                confidence: None,
+                provider: "test".to_string(),
+                segments: Vec::new(),
+                words: Vec::new(),
+                translation: None,
+            })
+        }
+
+        async fn insert_text(&self, transcript: &str) -> Result<(), AppError> {
+            if self.is_active() {
+                self.event_log
+                    .insertions
+                    .lock()
+                    .expect("insertion lock should not be poisoned")
+                    .push((self.session_id, transcript.to_string()));
+            }
+
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct TranscriptionFailureDelegate {
+        statuses: Mutex<Vec<AppStatus>>,
+        errors: Mutex<Vec<PipelineError>>,
+        transcripts: Mutex<Vec<String>>,
+        insertions: Mutex<Vec<String>>,
+    }
+
+    impl TranscriptionFailureDelegate {
+        fn statuses(&self) -> Vec<AppStatus> {
+            self.statuses
+                .lock()
+                .expect("status lock should not be poisoned")
+                .clone()
+        }
+
+        fn errors(&self) -> Vec<PipelineError> {
+            self.errors
+                .lock()
+                .expect("error lock should not be poisoned")
+                .clone()
+        }
+
+        fn transcripts(&self) -> Vec<String> {
+            self.transcripts
+                .lock()
+                .expect("transcript lock should not be poisoned")
+                .clone()
+        }
+
+        fn insertions(&self) -> Vec<String> {
+            self.insertions
+                .lock()
+                .expect("insertion lock should not be poisoned")
+                .clone()
+        }
+    }
+
+    #[async_trait]
+    impl VoicePipelineDelegate for TranscriptionFailureDelegate {
+        fn set_status(&self, status: AppStatus) {
+            self.statuses
+                .lock()
+                .expect("status lock should not be poisoned")
+                .push(status);
+        }
+
+        fn emit_transcript(&self, transcript: &PipelineTranscript) {
+            self.transcripts
+                .lock()
+                .expect("transcript lock should not be poisoned")
+                .push(transcript.text_to_insert().to_string());
+        }
+
+        fn emit_error(&self, error: &PipelineError) {
+            self.errors
+                .lock()
+                .expect("error lock should not be poisoned")
+                .push(error.clone());
+        }
+
+        fn start_recording(&self) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        fn stop_recording(&self) -> Result<RecordedWav, AppError> {
+            Ok(RecordedWav::Memory(vec![4, 5, 6]))
+        }
+
+        async fn transcribe(&self, _wav: RecordedWav) -> Result<PipelineTranscript, AppError> {
+            Err(AppError::new(ErrorCode::Network, "provider unavailable"))
+        }
+
+        async fn insert_text(&self, transcript: &str) -> Result<(), AppError> {
+            self.insertions
+                .lock()
+                .expect("insertion lock should not be poisoned")
+                .push(transcript.to_string());
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct InsertionFailureDelegate {
+        statuses: Mutex<Vec<AppStatus>>,
+        errors: Mutex<Vec<PipelineError>>,
+        transcripts: Mutex<Vec<String>>,
+        saved_history: Mutex<Vec<PipelineTranscript>>,
+    }
+
+    impl InsertionFailureDelegate {
+        fn statuses(&self) -> Vec<AppStatus> {
+            self.statuses
+                .lock()
+                .expect("status lock should not be poisoned")
+                .clone()
+        }
+
+        fn errors(&self) -> Vec<PipelineError> {
+            self.errors
+                .lock()
+                .expect("error lock should not be poisoned")
+                .clone()
+        }
+
+        fn transcripts(&self) -> Vec<String> {
+            self.transcripts
+                .lock()
+                .expect("transcript lock should not be poisoned")
+                .clone()
+        }
+
+        fn saved_history(&self) -> Vec<PipelineTranscript> {
+            self.saved_history
+                .lock()
+                .expect("saved-history lock should not be poisoned")
+                .clone()
+        }
+    }
+
+    #[async_trait]
+    impl VoicePipelineDelegate for InsertionFailureDelegate {
+        fn set_status(&self, status: AppStatus) {
+            self.statuses
+                .lock()
+                .expect("status lock should not be poisoned")
+                .push(status);
+        }
+
+        fn emit_transcript(&self, transcript: &PipelineTranscript) {
+            self.transcripts
+                .lock()
+                .expect("transcript lock should not be poisoned")
+                .push(transcript.text_to_insert().to_string());
+        }
+
+        fn emit_error(&self, error: &PipelineError) {
+            self.errors
+                .lock()
+                .expect("error lock should not be poisoned")
+                .push(error.clone());
+        }
+
+        fn start_recording(&self) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        fn stop_recording(&self) -> Result<RecordedWav, AppError> {
+            Ok(RecordedWav::Memory(vec![7, 8, 9]))
+        }
+
+        async fn transcribe(&self, _wav: RecordedWav) -> Result<PipelineTranscript, AppError> {
+            Ok(PipelineTranscript {
+                text: "hello world".to_string(),
+                duration_secs: Some(2.4),
+                language: Some("en".to_string()),
+                language_confidence: None,
+                confidence: None,
+                provider: "test".to_string(),
+                segments: Vec::new(),
+                words: Vec::new(),
+                translation: None,
+            })
+        }
+
+        async fn insert_text(&self, _transcript: &str) -> Result<(), AppError> {
+            Err(AppError::new(
+                ErrorCode::PermissionDenied,
+                "accessibility denied",
+            ))
+        }
+
+        fn save_history_entry(&self, transcript: &PipelineTranscript) -> Result<(), AppError> {
+            self.saved_history
+                .lock()
+                .expect("saved-history lock should not be poisoned")
+                .push(transcript.clone());
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct SharedStageErrorDelegate {
+        statuses: Arc<Mutex<Vec<AppStatus>>>,
+        errors: Arc<Mutex<Vec<PipelineError>>>,
+    }
+
+    impl SharedStageErrorDelegate {
+        fn statuses(&self) -> Vec<AppStatus> {
+            self.statuses
+                .lock()
+                .expect("status lock should not be poisoned")
+                .clone()
+        }
+
+        fn errors(&self) -> Vec<PipelineError> {
+            self.errors
+                .lock()
+                .expect("error lock should not be poisoned")
+                .clone()
+        }
+    }
+
+    #[async_trait]
+    impl VoicePipelineDelegate for SharedStageErrorDelegate {
+        fn set_status(&self, status: AppStatus) {
+            self.statuses
+                .lock()
+                .expect("status lock should not be poisoned")
+                .push(status);
+        }
+
+        fn emit_transcript(&self, _transcript: &PipelineTranscript) {}
+
+        fn emit_error(&self, error: &PipelineError) {
+            self.errors
+                .lock()
+                .expect("error lock should not be poisoned")
+                .push(error.clone());
+        }
+
+        fn start_recording(&self) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        fn stop_recording(&self) -> Result<RecordedWav, AppError> {
+            Ok(RecordedWav::Memory(Vec::new()))
+        }
+
+        async fn transcribe(&self, _wav: RecordedWav) -> Result<PipelineTranscript, AppError> {
+            Ok(PipelineTranscript {
+                text: String::new(),
+                duration_secs: None,
+                language: None,
+                language_confidence: None,
+                confidence: None,
+                provider: "test".to_string(),
+                segments: Vec::new(),
+                words: Vec::new(),
+                translation: None,
+            })
+        }
+
+        async fn insert_text(&self, _transcript: &str) -> Result<(), AppError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn later_session_invalidates_previous_session() {
+        let runtime = PipelineRuntimeState::default();
+
+        let first = runtime.begin_session();
+        let second = runtime.begin_session();
+
+        assert!(!runtime.is_session_active(first));
+        assert!(runtime.is_session_active(second));
+    }
+
+    #[test]
+    fn cancelling_drops_earlier_sessions_but_superseding_keeps_them() {
+        let runtime = PipelineRuntimeState::default();
+
+        let first = runtime.begin_session();
+        runtime
+            .reserve_queue_slot(first)
+            .expect("queue should have room");
+        let second = runtime.begin_session();
+        assert!(!runtime.is_session_cancelled(first));
+        assert_eq!(runtime.session_queue.in_flight(), 0);
+
+        runtime
+            .reserve_queue_slot(second)
+            .expect("queue should have room");
+        assert!(runtime.take_queue_ticket(first).is_none());
+        let ticket = runtime.take_queue_ticket(second);
+        assert!(ticket.is_some());
+        assert_eq!(runtime.session_queue.in_flight(), 1);
+
+        let after_cancel = runtime.cancel_sessions();
+        assert!(runtime.is_session_cancelled(first));
+        assert!(runtime.is_session_cancelled(second));
+        assert!(!runtime.is_session_cancelled(after_cancel));
+    }
+
+    #[test]
+    fn private_and_copy_only_marks_are_kept_per_session_until_it_finishes() {
+        let runtime = PipelineRuntimeState::default();
+        let first = runtime.begin_session();
+        runtime.mark_private(first);
+        runtime.mark_copy_only(first);
+        let second = runtime.begin_session();
+        runtime.mark_private(second);
+        assert!(runtime.is_private(first));
+        assert!(runtime.is_copy_only(first));
+        assert!(!runtime.is_copy_only(second));
+
+        runtime.finish_session(first);
+        assert!(!runtime.is_private(first));
+        assert!(!runtime.is_copy_only(first));
+        assert!(runtime.is_private(second));
+
+        runtime.request_private_for_next_session(true);
+        let third = runtime.begin_session();
+        runtime.adopt_requested_private(third);
+        let fourth = runtime.begin_session();
+        runtime.adopt_requested_private(fourth);
+        assert!(runtime.is_private(third));
+        assert!(!runtime.is_private(fourth));
+    }
+
+    #[test]
+    fn active_pipeline_session_id_returns_current_session_without_mutating_counter() {
+        let runtime = PipelineRuntimeState::default();
+        let session_id = runtime.begin_session();
+        let next_before = runtime.next_session_id.load(Ordering::Relaxed);
+
+        assert_eq!(active_pipeline_session_id(&runtime), Some(session_id));
+        assert_eq!(runtime.next_session_id.load(Ordering::Relaxed), next_before);
+    }
+
+    #[test]
+    fn active_pipeline_session_id_is_none_before_any_session_starts() {
+        let runtime = PipelineRuntimeState::default();
+        assert_eq!(active_pipeline_session_id(&runtime), None);
+    }
+
+    #[test]
+    fn resolve_or_begin_pipeline_session_uses_existing_active_session() {
+        let runtime = PipelineRuntimeState::default();
+        let existing_session = runtime.begin_session();
+        let next_before = runtime.next_session_id.load(Ordering::Relaxed);
+
+        assert_eq!(
+            super::resolve_or_begin_pipeline_session(&runtime),
+            existing_session
+        );
+        assert_eq!(runtime.next_session_id.load(Ordering::Relaxed), next_before);
+    }
+
+    #[test]
+    fn resolve_or_begin_pipeline_session_starts_new_session_when_missing() {
+        let runtime = PipelineRuntimeState::default();
+
+        assert_eq!(super::resolve_or_begin_pipeline_session(&runtime), 1);
+        assert_eq!(runtime.active_session_id(), Some(1));
+    }
+
+    #[test]
+    fn short_recordings_are_discarded_before_transcription() {
+        assert!(super::should_discard_recording(199, 200));
+        assert!(!super::should_discard_recording(200, 200));
+        assert!(!super::should_discard_recording(201, 200));
+        assert!(!super::should_discard_recording(0, 0));
+    }
+
+    #[test]
+    fn max_recording_duration_is_off_at_zero() {
+        let mut settings = VoiceSettings::default();
+        assert_eq!(
+            super::max_recording_duration(&settings),
+            Some(Duration::from_secs(3_600))
+        );
+
+        settings.max_recording_duration_mins = 0;
+        assert_eq!(super::max_recording_duration(&settings), None);
+    }
+
+    #[tokio::test]
+    async fn overlapping_pipeline_sessions_ignore_stale_mutations() {
+        let runtime = PipelineRuntimeState::default();
+        let pipeline = VoicePipeline::new(Duration::ZERO);
+        let event_log = Arc::new(SessionEventLog::default());
+
+        let first_session_id = runtime.begin_session();
+        let (first_started_tx, first_started_rx) = oneshot::channel();
+        let first_blocker = Arc::new(Notify::new());
+        let first_delegate = SessionAwareDelegate::new(
+            runtime.clone(),
+            first_session_id,
+            Arc::clone(&event_log),
+            "first transcript",
+        )
+        .with_transcription_gate(first_started_tx, Arc::clone(&first_blocker));
+
+        let first_task = {
+            let pipeline = pipeline.clone();
+            tokio::spawn(async move {
+                pipeline.handle_hotkey_stopped(&first_delegate).await;
+            })
+        };
+
+        first_started_rx
+            .await
+            .expect("first pipeline should reach transcription");
+
+        let second_session_id = runtime.begin_session();
+        let second_delegate = SessionAwareDelegate::new(
+            runtime.clone(),
+            second_session_id,
+            Arc::clone(&event_log),
+            "second transcript",
+        );
+
+        pipeline.handle_hotkey_stopped(&second_delegate).await;
+        first_blocker.notify_waiters();
+        first_task
+            .await
+            .expect("first pipeline task should finish cleanly");
+
+        assert_eq!(
+            event_log.statuses_for(first_session_id),
+            vec![AppStatus::Transcribing]
+        );
+        assert_eq!(
+            event_log.statuses_for(second_session_id),
+            vec![AppStatus::Transcribing, AppStatus::Idle]
+        );
+        assert_eq!(
+            event_log.transcripts(),
+            vec![(second_session_id, "second transcript".to_string())]
+        );
+        assert_eq!(
+            event_log.insertions(),
+            vec![(second_session_id, "second transcript".to_string())]
+        );
+        assert!(event_log.errors().is_empty());
+    }
+
+    #[tokio::test]
+    async fn transcription_failure_emits_error_resets_idle_and_skips_insertion() {
+        let pipeline = VoicePipeline::new(Duration::ZERO);
+        let delegate = TranscriptionFailureDelegate::default();
+
+        pipeline.handle_hotkey_stopped(&delegate).await;
+
+        assert_eq!(
+            delegate.statuses(),
+            vec![AppStatus::Transcribing, AppStatus::Error, AppStatus::Idle]
+        );
+        assert_eq!(
+            delegate.errors(),
+            vec![PipelineError::new(
+                PipelineErrorStage::Transcription,
+                AppError::new(ErrorCode::Network, "provider unavailable")
+            )]
+        );
+        assert!(delegate.transcripts().is_empty());
+        assert!(delegate.insertions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn insertion_failure_still_persists_history_entry() {
+        let pipeline = VoicePipeline::new(Duration::ZERO);
+        let delegate = InsertionFailureDelegate::default();
+
+        pipeline.handle_hotkey_stopped(&delegate).await;
+
+        assert_eq!(
+            delegate.statuses(),
+            vec![AppStatus::Transcribing, AppStatus::Error, AppStatus::Idle]
+        );
+        assert_eq!(
+            delegate.errors(),
+            vec![PipelineError::new(
+                PipelineErrorStage::TextInsertion,
+                AppError::new(ErrorCode::PermissionDenied, "accessibility denied")
+            )]
+        );
+        assert_eq!(delegate.transcripts(), vec!["hello world".to_string()]);
+        assert_eq!(
+            delegate.saved_history(),
+            vec![PipelineTranscript {
+                text: "hello world".to_string(),
+                duration_secs: Some(2.4),
+                language: Some("en".to_string()),
+                language_confidence: None,
+                confidence: None,
+                provider: "test".to_string(),
+                segments: Vec::new(),
+                words: Vec::new(),
+                translation: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn stream_error_propagation_aborts_recording_emits_error_and_resets_status() {
+        let call_order: Arc<Mutex<Vec<&'static str>>> = Arc::new(Mutex::new(Vec::new()));
+        let statuses: Arc<Mutex<Vec<AppStatus>>> = Arc::new(Mutex::new(Vec::new()));
+        let errors: Arc<Mutex<Vec<PipelineError>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let begin_call_order = Arc::clone(&call_order);
+        let stop_call_order = Arc::clone(&call_order);
+        let abort_call_order = Arc::clone(&call_order);
+        let emit_call_order = Arc::clone(&call_order);
+        let status_call_order = Arc::clone(&call_order);
+        let reset_call_order = Arc::clone(&call_order);
+        let status_log = Arc::clone(&statuses);
+        let reset_status_log = Arc::clone(&statuses);
+        let error_log = Arc::clone(&errors);
+
+        handle_audio_input_stream_error_with_hooks(
+            "stream disconnected".to_string(),
+            move || {
+                begin_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("begin_session");
+            },
+            move || {
+                stop_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("force_stop_recording");
+            },
+            move || {
+                abort_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("abort_recording");
+                Ok(())
+            },
+            move |error| {
+                emit_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("emit_pipeline_error");
+                error_log
+                    .lock()
+                    .expect("error lock should not be poisoned")
+                    .push(error.clone());
+            },
+            move |status| {
+                status_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("set_status");
+                status_log
+                    .lock()
+                    .expect("status lock should not be poisoned")
+                    .push(status);
+            },
+            move || {
+                reset_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("schedule_reset");
+                reset_status_log
+                    .lock()
+                    .expect("status lock should not be poisoned")
+                    .push(AppStatus::Idle);
+            },
+        );
+
+        assert_eq!(
+            call_order
+                .lock()
+                .expect("call-order lock should not be poisoned")
+                .clone(),
+            vec![
+                "begin_session",
+                "force_stop_recording",
+                "abort_recording",
+                "emit_pipeline_error",
+                "set_status",
+                "schedule_reset"
+            ]
+        );
+        assert_eq!(
+            statuses
+                .lock()
+                .expect("status lock should not be poisoned")
+                .clone(),
+            vec![AppStatus::Error, AppStatus::Idle]
+        );
+        assert_eq!(
+            errors
+                .lock()
+                .expect("error lock should not be poisoned")
+                .clone(),
+            vec![PipelineError::new(
+                PipelineErrorStage::RecordingRuntime,
+                AppError::from("stream disconnected".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn cancel_recording_resets_to_idle_after_abort_attempt() {
+        let call_order: Arc<Mutex<Vec<&'static str>>> = Arc::new(Mutex::new(Vec::new()));
+        let statuses: Arc<Mutex<Vec<AppStatus>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let begin_call_order = Arc::clone(&call_order);
+        let stop_call_order = Arc::clone(&call_order);
+        let abort_call_order = Arc::clone(&call_order);
+        let status_call_order = Arc::clone(&call_order);
+        let status_log = Arc::clone(&statuses);
+
+        let result = cancel_recording_with_hooks(
+            move || {
+                begin_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("begin_session");
+            },
+            move || {
+                stop_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("force_stop_recording");
+            },
+            move || {
+                abort_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("abort_recording");
+                Ok(true)
+            },
+            move |status| {
+                status_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("set_status");
+                status_log
+                    .lock()
+                    .expect("status lock should not be poisoned")
+                    .push(status);
+            },
+        );
+
+        assert_eq!(result, Ok(true));
+        assert_eq!(
+            call_order
+                .lock()
+                .expect("call-order lock should not be poisoned")
+                .clone(),
+            vec![
+                "begin_session",
+                "force_stop_recording",
+                "abort_recording",
+                "set_status"
+            ]
+        );
+        assert_eq!(
+            statuses
+                .lock()
+                .expect("status lock should not be poisoned")
+                .clone(),
+            vec![AppStatus::Idle]
+        );
+    }
+
+    #[test]
+    fn cancel_recording_still_resets_to_idle_when_abort_fails() {
+        let call_order: Arc<Mutex<Vec<&'static str>>> = Arc::new(Mutex::new(Vec::new()));
+        let statuses: Arc<Mutex<Vec<AppStatus>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let begin_call_order = Arc::clone(&call_order);
+        let stop_call_order = Arc::clone(&call_order);
+        let abort_call_order = Arc::clone(&call_order);
+        let status_call_order = Arc::clone(&call_order);
+        let status_log = Arc::clone(&statuses);
+
+        let result = cancel_recording_with_hooks(
+            move || {
+                begin_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("begin_session");
+            },
+            move || {
+                stop_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("force_stop_recording");
+            },
+            move || {
+                abort_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("abort_recording");
+                Err("abort failure".to_string())
+            },
+            move |status| {
+                status_call_order
+                    .lock()
+                    .expect("call-order lock should not be poisoned")
+                    .push("set_status");
+                status_log
+                    .lock()
+                    .expect("status lock should not be poisoned")
+                    .push(status);
+            },
+        );
+
+        assert_eq!(result, Err("abort failure".to_string()));
+        assert_eq!(
+            call_order
+                .lock()
+                .expect("call-order lock should not be poisoned")
+                .clone(),
+            vec![
+                "begin_session",
+                "force_stop_recording",
+                "abort_recording",
+                "set_status"
+            ]
+        );
+        assert_eq!(
+            statuses
+                .lock()
+                .expect("status lock should not be poisoned")
+                .clone(),
+            vec![AppStatus::Idle]
+        );
+    }
+
+    #[tokio::test]
+    async fn command_path_stage_error_recovery_resets_status_to_idle() {
+        let delegate = SharedStageErrorDelegate::default();
+        let observer = delegate.clone();
+
+        let task = spawn_pipeline_stage_error_reset(
+            VoicePipeline::new(Duration::ZERO),
+            delegate,
+            PipelineErrorStage::Transcription,
+            AppError::new(ErrorCode::Network, "command transcription failed"),
+        );
+        task.await.expect("stage-error task should complete");
+
+        assert_eq!(observer.statuses(), vec![AppStatus::Error, AppStatus::Idle]);
+        assert_eq!(
+            observer.errors(),
+            vec![PipelineError::new(
+                PipelineErrorStage::Transcription,
+                AppError::new(ErrorCode::Network, "command transcription failed")
+            )]
+        );
+    }
+
+    #[test]
+    fn microphone_not_determined_preflight_error_mentions_system_settings_path() {
+        let message = permission_preflight_error_message(
+            PermissionType::Microphone,
+            PermissionState::NotDetermined,
+            "start recording",
+        );
+
+        assert!(message.contains("cannot start recording"));
+        assert!(message.contains("Privacy & Security > Microphone"));
+    }
+
+    #[test]
+    fn accessibility_preflight_error_mentions_accessibility_settings_path() {
+        let message = permission_preflight_error_message(
+            PermissionType::Accessibility,
+            PermissionState::Denied,
+            "insert text",
+        );
+
+        assert!(message.contains("Accessibility access is required"));
+        assert!(message.contains("Privacy & Security > Accessibility"));
+    }
+
+    #[test]
+    fn transform_profiles_resolve_to_style_instructions() {
+        assert_eq!(
+            transform_profile_instructions(" Casual ", ""),
+            Ok(CASUAL_TRANSCRIPTION_PROMPT.to_string())
+        );
+        assert_eq!(
+            transform_profile_instructions("custom", "Bullet points."),
+            Ok("Bullet points.".to_string())
+        );
+        assert!(transform_profile_instructions("custom", " ").is_err());
+        assert!(transform_profile_instructions("verbatim", "").is_err());
+        assert!(transform_profile_instructions("shouty", "").is_err());
+    }
+
+    #[test]
+    fn startup_restore_applies_persisted_hotkey_configuration() {
+        let settings = VoiceSettings {
+            hotkey_shortcut: "Cmd+Shift+Space".to_string(),
+            recording_mode: RECORDING_MODE_TOGGLE.to_string(),
+            ..VoiceSettings::default()
+        };
+        let mut applied = Vec::new();
+        let mut default_fallback_calls = 0usize;
+
+        apply_hotkey_from_settings_with_fallback(
+            &settings,
+            |config| {
+                applied.push(config);
+                Ok(())
+            },
+            || {
+                default_fallback_calls += 1;
+                Ok(())
+            },
+        )
+        .expect("startup hotkey restoration should succeed");
+
+        assert_eq!(
+            applied,
+            vec![HotkeyConfig {
+                shortcut: "Cmd+Shift+Space".to_string(),
+                mode: RecordingMode::Toggle,
+                name: None,
+            }]
+        );
+        assert_eq!(default_fallback_calls, 0);
+    }
+
+    #[test]
+    fn startup_restore_falls_back_to_default_when_persisted_mode_is_invalid() {
+        let settings = VoiceSettings {
+            hotkey_shortcut: "Cmd+Shift+Space".to_string(),
+            recording_mode: "invalid_mode".to_string(),
+            ..VoiceSettings::default()
+        };
+        let mut apply_attempts = 0usize;
+        let mut default_fallback_calls = 0usize;
+
+        apply_hotkey_from_settings_with_fallback(
+            &settings,
+            |_config| {
+                apply_attempts += 1;
+                Ok(())
+            },
+            || {
+                default_fallback_calls += 1;
+                Ok(())
+            },
+        )
+        .expect("startup fallback should recover from invalid mode");
+
+        assert_eq!(apply_attempts, 0);
+        assert_eq!(default_fallback_calls, 1);
+    }
+
+    #[test]
+    fn startup_restore_falls_back_to_default_when_persisted_shortcut_apply_fails() {
+        let settings = VoiceSettings {
+            hotkey_shortcut: "Cmd+Shift+Space".to_string(),
+            recording_mode: RECORDING_MODE_TOGGLE.to_string(),
+            ..VoiceSettings::default()
+        };
+        let mut apply_attempts = 0usize;
+        let mut default_fallback_calls = 0usize;
+
+        apply_hotkey_from_settings_with_fallback(
+            &settings,
+            |_config| {
+                apply_attempts += 1;
+                Err("shortcut already registered".to_string())
+            },
+            || {
+                default_fallback_calls += 1;
+                Ok(())
+            },
+        )
+        .expect("startup fallback should recover from persisted hotkey apply failure");
+
+        assert_eq!(apply_attempts, 1);
+        assert_eq!(default_fallback_calls, 1);
+    }
+
+    #[test]
+    fn startup_settings_load_falls_back_to_defaults_when_load_errors() {
+        let mut load_attempts = 0usize;
+        let settings = load_startup_settings_with_fallback(|| {
+            load_attempts += 1;
+            Err(AppError::from(
+                "Failed to parse settings file `/tmp/settings.json`: malformed".to_string(),
+            ))
+        });
+
+        assert_eq!(load_attempts, 1);
+        assert_eq!(settings, VoiceSettings::default());
+    }
+
+    #[test]
+    fn apply_settings_rolls_back_hotkey_when_launch_at_login_apply_fails() {
+        let previous_hotkey = HotkeyConfig {
+            shortcut: "Alt+Space".to_string(),
+            mode: RecordingMode::HoldToTalk,
+            name: None,
+        };
+        let requested_hotkey = HotkeyConfig {
+            shortcut: "Ctrl+Space".to_string(),
+            mode: RecordingMode::Toggle,
+            name: None,
+        };
+        let update = VoiceSettingsUpdate {
+            microphone_id: Some(Some("mic-42".to_string())),
+            launch_at_login: Some(true),
+            ..VoiceSettingsUpdate::default()
+        };
+        let mut applied_hotkeys = Vec::new();
+        let mut rollback_hotkeys = Vec::new();
+        let mut launch_apply_attempts = Vec::new();
+
+        let error = apply_settings_transaction_with_hooks(
+            update,
+            previous_hotkey.clone(),
+            requested_hotkey.clone(),
+            false,
+            true,
+            |config| {
+                applied_hotkeys.push(config.clone());
+                Ok(config)
+            },
+            |enabled| {
+                launch_apply_attempts.push(enabled);
+                Err("launchctl denied".to_string())
+            },
+            |_update| panic!("settings should not be persisted when launch-at-login apply fails"),
+            |_enabled| panic!("launch-at-login rollback should not run before a successful apply"),
+            |config| {
+                rollback_hotkeys.push(config.clone());
+                Ok(config)
+            },
+        )
+        .expect_err("launch-at-login apply failure should return an error");
+
+        assert_eq!(applied_hotkeys, vec![requested_hotkey]);
+        assert_eq!(launch_apply_attempts, vec![true]);
+        assert_eq!(rollback_hotkeys, vec![previous_hotkey]);
+        assert!(error
+            .message
+            .contains("Failed to apply launch-at-login setting: launchctl denied"));
+    }
+
+    #[test]
+    fn apply_settings_persists_effective_hotkey_configuration_from_apply_result() {
+        let previous_hotkey = HotkeyConfig {
+            shortcut: "Alt+Space".to_string(),
+            mode: RecordingMode::HoldToTalk,
+            name: None,
+        };
+        let requested_hotkey = HotkeyConfig {
+            shortcut: " Ctrl+Space ".to_string(),
+            mode: RecordingMode::HoldToTalk,
+            name: None,
+        };
+        let update = VoiceSettingsUpdate {
+            microphone_id: Some(Some("mic-42".to_string())),
+            auto_insert: Some("clipboard_only".to_string()),
+            ..VoiceSettingsUpdate::default()
+        };
+        let applied_hotkey = HotkeyConfig {
+            shortcut: "Ctrl+Space".to_string(),
+            mode: RecordingMode::Toggle,
+            name: None,
+        };
+        let mut persisted_updates = Vec::new();
+        let mut rollback_launch_attempts = 0usize;
+        let mut rollback_hotkey_attempts = 0usize;
+
+        let persisted = apply_settings_transaction_with_hooks(
+            update,
+            previous_hotkey,
+            requested_hotkey,
+            false,
+            false,
+            |_config| Ok(applied_hotkey.clone()),
+            |_enabled| Ok(()),
+            |persist_update| {
+                persisted_updates.push(persist_update.clone());
+                Ok(VoiceSettings {
+                    hotkey_shortcut: persist_update
+                        .hotkey_shortcut
+                        .expect("effective shortcut should be persisted"),
+                    recording_mode: persist_update
+                        .recording_mode
+                        .expect("effective recording mode should be persisted"),
+                    microphone_id: persist_update.microphone_id.unwrap_or(None),
+                    auto_insert: persist_update.auto_insert.unwrap_or_default(),
+                    launch_at_login: persist_update.launch_at_login.unwrap_or(false),
+                    ..VoiceSettings::default()
+                })
+            },
+            |_enabled| {
+                rollback_launch_attempts += 1;
+                Err("launch rollback should not run for successful transaction".to_string())
+            },
+            |_config| {
+                rollback_hotkey_attempts += 1;
+                Err("hotkey rollback should not run for successful transaction".to_string())
+            },
+        )
+        .expect("settings transaction should persist applied hotkey config");
+
+        assert_eq!(persisted_updates.len(), 1);
+        let persisted_update = persisted_updates
+            .pop()
+            .expect("persist update should be captured");
+        assert_eq!(
+            persisted_update.hotkey_shortcut.as_deref(),
+            Some("Ctrl+Space")
+        );
+        assert_eq!(
+            persisted_update.recording_mode.as_deref(),
+            Some(RECORDING_MODE_TOGGLE)
+        );
+        assert_eq!(
+            persisted_update.microphone_id,
+            Some(Some("mic-42".to_string()))
+        );
+        assert_eq!(
+            persisted_update.auto_insert.as_deref(),
+            Some("clipboard_only")
+        );
+        assert_eq!(persisted_update.launch_at_login, None);
+        assert_eq!(persisted.hotkey_shortcut, "Ctrl+Space");
+        assert_eq!(persisted.recording_mode, RECORDING_MODE_TOGGLE);
+        assert_eq!(persisted.microphone_id.as_deref(), Some("mic-42"));
+        assert_eq!(persisted.auto_insert, "clipboard_only");
+        assert_eq!(rollback_launch_attempts, 0);
+        assert_eq!(rollback_hotkey_attempts, 0);
+    }
+
+    #[test]
+    fn apply_settings_rolls_back_launch_at_login_and_hotkey_when_settings_persist_fails() {
+        let previous_hotkey = HotkeyConfig {
+            shortcut: "Alt+Space".to_string(),
+            mode: RecordingMode::HoldToTalk,
+            name: None,
+        };
+        let requested_hotkey = HotkeyConfig {
+            shortcut: "Ctrl+Space".to_string(),
+            mode: RecordingMode::Toggle,
+            name: None,
+        };
+        let update = VoiceSettingsUpdate {
+            launch_at_login: Some(true),
+            ..VoiceSettingsUpdate::default()
+        };
+        let mut rollback_launch_states = Vec::new();
+        let mut rollback_hotkeys = Vec::new();
+
+        let error = apply_settings_transaction_with_hooks(
+            update,
+            previous_hotkey.clone(),
+            requested_hotkey.clone(),
+            false,
+            true,
+            Ok,
+            |_enabled| Ok(()),
+            |_update| Err(AppError::from("disk full".to_string())),
+            |enabled| {
+                rollback_launch_states.push(enabled);
+                Ok(())
+            },
+            |config| {
+                rollback_hotkeys.push(config.clone());
+                Ok(config)
+            },
+        )
+        .expect_err("persist failure should trigger both launch-at-login and hotkey rollbacks");
+
+        assert!(error
+            .message
+            .contains("Failed to persist settings: disk full"));
+        assert_eq!(rollback_launch_states, vec![false]);
+        assert_eq!(rollback_hotkeys, vec![previous_hotkey]);
+    }
+
+    #[test]
+    fn apply_settings_keeps_the_persist_error_code() {
+        let hotkey = HotkeyConfig {
+            shortcut: "Alt+Space".to_string(),
+            mode: RecordingMode::HoldToTalk,
+            name: None,
+        };
+
+        let error = apply_settings_transaction_with_hooks(
+            VoiceSettingsUpdate::default(),
+            hotkey.clone(),
+            hotkey,
+            false,
+            false,
+            Ok,
+            |_enabled| Ok(()),
+            |_update| Err(AppError::new(ErrorCode::Conflict, "settings changed")),
+            |_enabled| Ok(()),
+            Ok,
+        )
+        .expect_err("a stale revision should fail the transaction");
+
+        assert_eq!(error.code, ErrorCode::Conflict);
+    }
+
+    #[test]
+    fn apply_settings_reports_rollback_failures_after_persist_error() {
+        let previous_hotkey = HotkeyConfig {
+            shortcut: "Alt+Space".to_string(),
+            mode: RecordingMode::HoldToTalk,
+            name: None,
+        };
+        let requested_hotkey = HotkeyConfig {
+            shortcut: "Ctrl+Space".to_string(),
+            mode: RecordingMode::Toggle,
+            name: None,
+        };
+        let update = VoiceSettingsUpdate {
+            launch_at_login: Some(true),
+            ..VoiceSettingsUpdate::default()
+        };
+
+        let error = apply_settings_transaction_with_hooks(
+            update,
+            previous_hotkey,
+            requested_hotkey,
+            false,
+            true,
+            Ok,
+            |_enabled| Ok(()),
+            |_update| Err(AppError::from("disk full".to_string())),
+            |_enabled| Err("launch rollback failed".to_string()),
+            |_config| Err("rollback shortcut registration failed".to_string()),
+        )
+        .expect_err("persist failure with rollback failures should return combined error");
+
+        assert!(error
+            .message
+            .contains("Failed to persist settings: disk full"));
+        assert!(error
+            .message
+            .contains("Failed to roll back launch-at-login state"));
+        assert!(error.message.contains("Failed to roll back hotkey config"));
+    }
+
+    #[test]
+    fn copy_directory_contents_copies_nested_files() {
+        let temp_dir = TempDirGuard::new("voice-copy-directory-contents");
+        let source_dir = temp_dir.path().join("source");
+        let destination_dir = temp_dir.path().join("destination");
+        let nested_source_dir = source_dir.join("nested");
+        std::fs::create_dir_all(&nested_source_dir).expect("nested source directory should exist");
+        std::fs::write(source_dir.join("settings.json"), b"{\"autoInsert\":true}")
+            .expect("settings should be written");
+        std::fs::write(nested_source_dir.join("history.json"), b"[]")
+            .expect("history should be written");
+
+        copy_directory_contents(&source_dir, &destination_dir)
+            .expect("copy helper should copy source directory contents");
+
+        assert_eq!(
+            std::fs::read(destination_dir.join("settings.json"))
+                .expect("destination settings should exist"),
+            b"{\"autoInsert\":true}"
+        );
+        assert_eq!(
+            std::fs::read(destination_dir.join("nested").join("history.json"))
+                .expect("destination history should exist"),
+            b"[]"
+        );
+    }
+
+    #[test]
+    fn migrate_legacy_app_data_dir_copies_when_legacy_exists_and_new_is_missing() {
+        let temp_dir = TempDirGuard::new("voice-legacy-app-data-migration");
+        let app_support_dir = temp_dir.path().join("Application Support");
+        let legacy_app_data_dir = app_support_dir.join("com.sawyerhood.voice");
+        let new_app_data_dir = app_support_dir.join("com.sawyerhood.buzz");
+        std::fs::create_dir_all(legacy_app_data_dir.join("nested"))
+            .expect("legacy app data directory should exist");
+        std::fs::write(legacy_app_data_dir.join("api-key.json"), b"secret")
+            .expect("legacy api key should be written");
+        std::fs::write(
+            legacy_app_data_dir.join("nested").join("stats.json"),
+            b"{\"words\":42}",
+        )
+        .expect("legacy stats should be written");
+
+        migrate_legacy_app_data_dir(&new_app_data_dir);
+
+        assert_eq!(
+            std::fs::read(new_app_data_dir.join("api-key.json"))
+                .expect("new app data should include copied api key"),
+            b"secret"
+        );
+        assert_eq!(
+            std::fs::read(new_app_data_dir.join("nested").join("stats.json"))
+                .expect("new app data should include copied nested stats"),
+            b"{\"words\":42}"
+        );
+        assert!(legacy_app_data_dir.exists());
+    }
+
+    #[test]
+    fn migrate_legacy_app_data_dir_skips_when_new_directory_already_exists() {
+        let temp_dir = TempDirGuard::new("voice-legacy-app-data-skip");
+        let app_support_dir = temp_dir.path().join("Application Support");
+        let legacy_app_data_dir = app_support_dir.join("com.sawyerhood.voice");
+        let new_app_data_dir = app_support_dir.join("com.sawyerhood.buzz");
+        std::fs::create_dir_all(&legacy_app_data_dir)
+            .expect("legacy app data directory should exist");
+        std::fs::write(legacy_app_data_dir.join("history.json"), b"[\"legacy\"]")
+            .expect("legacy history should be written");
+        std::fs::create_dir_all(&new_app_data_dir).expect("new app data directory should exist");
+        std::fs::write(new_app_data_dir.join("history.json"), b"[\"new\"]")
+            .expect("new history should be written");
+
+        migrate_legacy_app_data_dir(&new_app_data_dir);
+
+        assert_eq!(
+            std::fs::read(new_app_data_dir.join("history.json"))
+                .expect("new history should remain unchanged"),
+            b"[\"new\"]"
+        );
+    }
+
+    #[test]
+    fn startup_window_visibility_hides_when_onboarding_completed() {
+        let settings = VoiceSettings {
+            onboarding_completed: true,
+            ..VoiceSettings::default()
+        };
+
+        assert!(should_hide_main_window_on_startup(&settings));
+    }
+
+    #[test]
+    fn startup_window_visibility_shows_when_onboarding_not_completed() {
+        let settings = VoiceSettings {
+            onboarding_completed: false,
+            ..VoiceSettings::default()
+        };
+
+        assert!(!should_hide_main_window_on_startup(&settings));
+    }
+
+    #[test]
+    fn overlay_is_visible_while_listening_or_transcribing() {
+        assert!(should_show_overlay_for_status(AppStatus::Listening));
+        assert!(should_show_overlay_for_status(AppStatus::Transcribing));
+        assert!(!should_show_overlay_for_status(AppStatus::Idle));
+        assert!(!should_show_overlay_for_status(AppStatus::Error));
+    }
+
+    #[test]
+    fn overlay_position_is_top_centered_in_work_area() {
+        let position = overlay_position_from_work_area(
+            PhysicalPosition::new(100, 32),
+            PhysicalSize::new(1600, 1000),
+            2.0,
+            OverlayPlacement::TopCenter,
+            None,
+            OverlayOffset::default(),
+        );
+
+        let expected_x = (100.0 / 2.0) + ((1600.0 / 2.0 - OVERLAY_WINDOW_WIDTH) / 2.0);
+        let expected_y = (32.0 / 2.0) + OVERLAY_WINDOW_TOP_MARGIN;
+
+        assert!((position.x - expected_x).abs() < f64::EPSILON);
+        assert!((position.y - expected_y).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn overlay_placements_follow_anchor_and_stay_inside_work_area() {
+        let work_area = (PhysicalPosition::new(0, 0), PhysicalSize::new(1000, 800));
+        let place = |placement, anchor, offset| {
+            overlay_position_from_work_area(
+                work_area.0,
+                work_area.1,
+                1.0,
+                placement,
+                anchor,
+                offset,
+            )
+        };
+
+        let bottom = place(
+            OverlayPlacement::BottomCenter,
+            None,
+            OverlayOffset::default(),
+        );
+        assert_eq!(
+            bottom.y,
+            800.0 - OVERLAY_WINDOW_HEIGHT - OVERLAY_WINDOW_TOP_MARGIN
+        );
+
+        let below_caret = place(
+            OverlayPlacement::NearCaret,
+            Some(LogicalPosition::new(500.0, 300.0)),
+            OverlayOffset::default(),
+        );
+        assert_eq!(below_caret.x, 500.0 - OVERLAY_WINDOW_WIDTH / 2.0);
+        assert_eq!(below_caret.y, 300.0 + OVERLAY_ANCHOR_GAP);
+
+        let above_cursor = place(
+            OverlayPlacement::NearCursor,
+            Some(LogicalPosition::new(5.0, 790.0)),
+            OverlayOffset::default(),
+        );
+        assert_eq!(above_cursor.x, 0.0);
+        assert_eq!(
+            above_cursor.y,
+            790.0 - OVERLAY_ANCHOR_GAP - OVERLAY_WINDOW_HEIGHT
+        );
+
+        let nudged = place(
+            OverlayPlacement::TopCenter,
+            None,
+            OverlayOffset { x: 40, y: -500 },
+        );
+        assert_eq!(nudged.x, (1000.0 - OVERLAY_WINDOW_WIDTH) / 2.0 + 40.0);
+        assert_eq!(nudged.y, 0.0);
+    }
+
+    #[test]
+    fn overlay_drag_offset_is_relative_to_placed_position() {
+        let offset = overlay_offset_from_drag(
+            LogicalPosition::new(350.0, 12.0),
+            LogicalPosition::new(412.4, 600.6),
+        );
+        assert_eq!(offset, OverlayOffset { x: 62, y: 589 });
+        assert_eq!(
+            overlay_offset_from_drag(
+                LogicalPosition::new(350.0, 12.0),
+                LogicalPosition::new(300.0, 12.0)
+            ),
+            OverlayOffset { x: -50, y: 0 }
+        );
+    }
+
+    #[test]
+    fn has_api_key_command_contract_returns_boolean_presence_only() {
+        let _: for<'a> fn(String, tauri::State<'a, AppState>) -> Result<bool, AppError> =
+            has_api_key;
+    }
+}
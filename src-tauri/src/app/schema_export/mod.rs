@@ -0,0 +1,772 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use schemars::{gen::SchemaSettings, schema::RootSchema, JsonSchema};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    app_data_sync::{AppDataConflictStrategy, AppDataImportReport},
+    app_error::{AppError, ErrorCode},
+    audio_capture_service::{
+        AudioInputStreamErrorEvent, AudioSpectrumEvent, CaptureSourceInfo, MicrophoneInfo,
+        RecordedAudio, RecoveredRecording, AUDIO_INPUT_STREAM_ERROR_EVENT, AUDIO_LEVEL_EVENT,
+        AUDIO_SPECTRUM_EVENT,
+    },
+    compatibility_probe::CompatibilityReport,
+    crash_reporter::CrashReport,
+    deep_link::DeepLinkNavigation,
+    diagnostics::DiagnosticsReport,
+    focused_app_watcher::{FocusedApp, EVENT_FOCUSED_APP_CHANGED},
+    history_store::{
+        import::{HistoryImportFormat, HistoryImportProgressEvent, HistoryImportReport},
+        reprocess::{HistoryReprocessProgressEvent, HistoryReprocessReport},
+        subtitles::SubtitleFormat,
+        vocabulary::{VocabularyRange, VocabularyReport},
+        HistoryEntry,
+    },
+    hotkey_service::{
+        HotkeyConfig, HotkeyStateSnapshot, RecordingStateChangedEvent, EVENT_HOTKEY_CONFIG_CHANGED,
+        EVENT_RECORDING_STARTED, EVENT_RECORDING_STATE_CHANGED, EVENT_RECORDING_STOPPED,
+    },
+    input_triggers::InputTriggerDevice,
+    insertion_audit::InsertionAuditEntry,
+    instance_conflicts::{ConflictingInstanceReport, EVENT_CONFLICTING_INSTANCE},
+    logging::{LogEntry, LogLevel},
+    oauth::{AuthExpiredEvent, EVENT_AUTH_EXPIRED},
+    pending_transcriptions::{PendingTranscription, PendingTranscriptionCompletedEvent},
+    permission_service::{
+        PermissionChangedEvent, PermissionSnapshot, PermissionState, PermissionType,
+        EVENT_PERMISSIONS_CHANGED, EVENT_PERMISSION_CHANGED,
+    },
+    redaction::RedactionRule,
+    release_notes::ReleaseNotesReport,
+    selftest::SelfTestReport,
+    session_trace::SessionTraceEvent,
+    settings_store::{OverlayOffset, SettingsSnapshot, VoiceSettings, VoiceSettingsUpdate},
+    snippets_store::Snippet,
+    stats_store::{AppUsage, DailyUsage, UsageStatsReport},
+    status_notifier::AppStatus,
+    telemetry::TelemetryEvent,
+    transcription::{
+        endpoint::ProviderConfig,
+        language::LanguageUncertainEvent,
+        models::{ApiKeyValidation, TranscriptionModel},
+        retry::TranscriptionRetryEvent,
+        TranscriptionOptions,
+    },
+    voice_pipeline::progress::DictationProgressEvent,
+};
+
+use super::{
+    ChatGptAuthStatus, FileTranscriptionEvent, FlaggedTranscript, InsertionCopiedInsteadEvent,
+    PendingInsert, PendingInsertAction, PipelineErrorEvent, RecordingLimitReachedEvent,
+    RedactionWarningEvent, StatusDetails, TranscriptReadyEvent, TranscriptSuppressedEvent,
+    EVENT_DEEP_LINK_NAVIGATE, EVENT_DICTATION_PROGRESS, EVENT_FILE_TRANSCRIPTION,
+    EVENT_HISTORY_IMPORT_PROGRESS, EVENT_HISTORY_REPROCESS_PROGRESS,
+    EVENT_INSERTION_COPIED_INSTEAD, EVENT_INSERT_CONFIRMATION_REQUESTED, EVENT_LANGUAGE_UNCERTAIN,
+    EVENT_OVERLAY_AUDIO_LEVEL, EVENT_OVERLAY_AUDIO_SPECTRUM, EVENT_PENDING_TRANSCRIPTIONS_CHANGED,
+    EVENT_PENDING_TRANSCRIPTION_COMPLETED, EVENT_PIPELINE_ERROR, EVENT_RECORDING_LIMIT_REACHED,
+    EVENT_RECOVERED_RECORDINGS_FOUND, EVENT_REDACTION_WARNING, EVENT_SETTINGS_CHANGED,
+    EVENT_STATUS_CHANGED, EVENT_TRANSCRIPTION_DELTA, EVENT_TRANSCRIPTION_RETRY,
+    EVENT_TRANSCRIPT_FLAGGED, EVENT_TRANSCRIPT_READY, EVENT_TRANSCRIPT_SUPPRESSED,
+};
+
+pub const SCHEMA_INDEX_FILE_NAME: &str = "index.json";
+
+// Type references below are either a primitive, a schema name, or a schema name
+// suffixed with `[]` (array) or ` | null` (optional).
+struct CommandContract {
+    name: &'static str,
+    args: &'static [(&'static str, &'static str)],
+    output: &'static str,
+}
+
+const fn command(
+    name: &'static str,
+    args: &'static [(&'static str, &'static str)],
+    output: &'static str,
+) -> CommandContract {
+    CommandContract { name, args, output }
+}
+
+const COMMAND_CONTRACTS: &[CommandContract] = &[
+    command("get_status", &[], "AppStatus"),
+    command("set_status", &[("status", "AppStatus")], "null"),
+    command("get_status_details", &[], "StatusDetails"),
+    command("get_settings", &[], "VoiceSettings"),
+    command("get_settings_snapshot", &[], "SettingsSnapshot"),
+    command("get_onboarding_status", &[], "boolean"),
+    command("complete_onboarding", &[], "boolean"),
+    command(
+        "update_settings",
+        &[
+            ("update", "VoiceSettingsUpdate"),
+            ("expectedRevision", "number | null"),
+        ],
+        "VoiceSettings",
+    ),
+    command(
+        "apply_settings",
+        &[
+            ("update", "VoiceSettingsUpdate"),
+            ("expectedRevision", "number | null"),
+        ],
+        "VoiceSettings",
+    ),
+    command(
+        "update_provider_config",
+        &[("config", "ProviderConfig")],
+        "ProviderConfig",
+    ),
+    command("list_redaction_rules", &[], "RedactionRule[]"),
+    command(
+        "set_redaction_rules",
+        &[("rules", "RedactionRule[]")],
+        "RedactionRule[]",
+    ),
+    command("list_snippets", &[], "Snippet[]"),
+    command(
+        "create_snippet",
+        &[("trigger", "string"), ("template", "string")],
+        "Snippet",
+    ),
+    command(
+        "update_snippet",
+        &[
+            ("id", "string"),
+            ("trigger", "string"),
+            ("template", "string"),
+        ],
+        "Snippet",
+    ),
+    command("delete_snippet", &[("id", "string")], "boolean"),
+    command("get_launch_at_login", &[], "boolean"),
+    command("set_launch_at_login", &[("enabled", "boolean")], "boolean"),
+    command("has_api_key", &[("provider", "string")], "boolean"),
+    command("get_auth_method", &[], "string"),
+    command("set_auth_method", &[("method", "string")], "string"),
+    command("get_chatgpt_auth_status", &[], "ChatGptAuthStatus | null"),
+    command("get_auth_status", &[], "ChatGptAuthStatus | null"),
+    command("start_chatgpt_login", &[], "ChatGptAuthStatus"),
+    command("start_oauth_login", &[], "ChatGptAuthStatus"),
+    command("logout_chatgpt", &[], "null"),
+    command(
+        "save_api_key",
+        &[("provider", "string"), ("key", "string")],
+        "null",
+    ),
+    command(
+        "set_api_key",
+        &[
+            ("provider", "string"),
+            ("key", "string"),
+            ("validate", "boolean | null"),
+        ],
+        "ApiKeyValidation | null",
+    ),
+    command(
+        "validate_api_key",
+        &[("provider", "string")],
+        "ApiKeyValidation",
+    ),
+    command(
+        "list_transcription_models",
+        &[("provider", "string")],
+        "TranscriptionModel[]",
+    ),
+    command("delete_api_key", &[("provider", "string")], "null"),
+    command("list_microphones", &[], "MicrophoneInfo[]"),
+    command("list_capture_sources", &[], "CaptureSourceInfo[]"),
+    command("list_input_triggers", &[], "InputTriggerDevice[]"),
+    command("check_permissions", &[], "PermissionSnapshot"),
+    command(
+        "request_permission",
+        &[("type", "PermissionType")],
+        "PermissionSnapshot",
+    ),
+    command(
+        "wait_for_permission",
+        &[("type", "PermissionType"), ("timeoutMs", "number")],
+        "PermissionState",
+    ),
+    command("request_mic_permission", &[], "PermissionSnapshot"),
+    command("open_accessibility_settings", &[], "null"),
+    command(
+        "start_recording",
+        &[("microphoneId", "string | null")],
+        "null",
+    ),
+    command("stop_recording", &[], "RecordedAudio"),
+    command("complete_recording", &[], "null"),
+    command("cancel_recording", &[], "null"),
+    command("stop_recording_from_overlay", &[], "null"),
+    command("cancel_recording_from_overlay", &[], "null"),
+    command("save_overlay_offset", &[], "OverlayOffset"),
+    command("pin_realtime_language", &[("language", "string")], "null"),
+    command("get_audio_level", &[], "number"),
+    command("insert_text", &[("text", "string")], "null"),
+    command("copy_to_clipboard", &[("text", "string")], "null"),
+    command("get_pending_insert", &[], "PendingInsert | null"),
+    command(
+        "resolve_pending_insert",
+        &[("action", "PendingInsertAction"), ("text", "string | null")],
+        "boolean",
+    ),
+    command("get_flagged_transcript", &[], "FlaggedTranscript | null"),
+    command("retry_flagged_transcript", &[], "string"),
+    command("dismiss_flagged_transcript", &[], "boolean"),
+    command(
+        "retranscribe_in_language",
+        &[("language", "string")],
+        "string",
+    ),
+    command("dismiss_language_suggestion", &[], "boolean"),
+    command(
+        "get_insertion_audit_log",
+        &[("limit", "number | null")],
+        "InsertionAuditEntry[]",
+    ),
+    command("get_last_session_trace", &[], "SessionTraceEvent[]"),
+    command("get_browser_bridge_pairing_token", &[], "string"),
+    command(
+        "transcribe_audio",
+        &[
+            ("audioBytes", "number[]"),
+            ("options", "TranscriptionOptions | null"),
+        ],
+        "string",
+    ),
+    command(
+        "transcribe_file",
+        &[
+            ("path", "string"),
+            ("options", "TranscriptionOptions | null"),
+        ],
+        "HistoryEntry",
+    ),
+    command(
+        "transcribe_last_seconds",
+        &[("secs", "number | null")],
+        "null",
+    ),
+    command(
+        "get_conflicting_instances",
+        &[],
+        "ConflictingInstanceReport | null",
+    ),
+    command("quit_conflicting_instance", &[("pid", "number")], "null"),
+    command("list_recovered_recordings", &[], "RecoveredRecording[]"),
+    command(
+        "transcribe_recovered_recording",
+        &[("id", "string")],
+        "string",
+    ),
+    command(
+        "discard_recovered_recording",
+        &[("id", "string")],
+        "boolean",
+    ),
+    command("list_pending_transcriptions", &[], "PendingTranscription[]"),
+    command("retry_pending_transcriptions", &[], "number"),
+    command(
+        "discard_pending_transcription",
+        &[("id", "string")],
+        "boolean",
+    ),
+    command("run_e2e_selftest", &[], "SelfTestReport"),
+    command("run_diagnostics", &[], "DiagnosticsReport"),
+    command(
+        "list_history",
+        &[
+            ("limit", "number | null"),
+            ("offset", "number | null"),
+            ("includeText", "boolean | null"),
+        ],
+        "HistoryEntry[]",
+    ),
+    command(
+        "get_history_entry",
+        &[("id", "string")],
+        "HistoryEntry | null",
+    ),
+    command("export_history_entry", &[("id", "string")], "string"),
+    command(
+        "export_history_entry_subtitles",
+        &[("id", "string"), ("format", "SubtitleFormat")],
+        "string",
+    ),
+    command(
+        "import_history",
+        &[("path", "string"), ("format", "HistoryImportFormat")],
+        "HistoryImportReport",
+    ),
+    command(
+        "reprocess_history",
+        &[("entryIds", "string[]"), ("transformProfile", "string")],
+        "HistoryReprocessReport",
+    ),
+    command(
+        "get_vocabulary_report",
+        &[("range", "VocabularyRange | null")],
+        "VocabularyReport",
+    ),
+    command("delete_history_entry", &[("id", "string")], "boolean"),
+    command("clear_history", &[], "null"),
+    command("get_usage_stats", &[], "UsageStatsReport"),
+    command(
+        "get_usage_stats_range",
+        &[("from", "string"), ("to", "string")],
+        "DailyUsage[]",
+    ),
+    command("get_usage_stats_by_app", &[], "AppUsage[]"),
+    command("reset_usage_stats", &[], "null"),
+    command("export_logs", &[], "string"),
+    command("list_crash_reports", &[], "CrashReport[]"),
+    command("list_telemetry_events", &[], "TelemetryEvent[]"),
+    command(
+        "query_logs",
+        &[
+            ("level", "LogLevel | null"),
+            ("since", "string | null"),
+            ("limit", "number | null"),
+        ],
+        "LogEntry[]",
+    ),
+    command("set_log_level", &[("level", "LogLevel")], "LogLevel"),
+    command(
+        "export_support_bundle",
+        &[("destinationPath", "string | null")],
+        "string",
+    ),
+    command("export_app_data", &[("path", "string")], "null"),
+    command(
+        "import_app_data",
+        &[
+            ("path", "string"),
+            ("strategy", "AppDataConflictStrategy | null"),
+        ],
+        "AppDataImportReport",
+    ),
+    command(
+        "get_release_notes",
+        &[("sinceVersion", "string | null")],
+        "ReleaseNotesReport",
+    ),
+    command("get_focused_app", &[], "FocusedApp | null"),
+    command(
+        "get_compatibility_report",
+        &[("refresh", "boolean | null")],
+        "CompatibilityReport",
+    ),
+    command("get_hotkey_config", &[], "HotkeyConfig"),
+    command("get_hotkey_recording_state", &[], "boolean"),
+    command("dump_hotkey_state", &[], "HotkeyStateSnapshot"),
+    command("reset_hotkey_state", &[], "HotkeyStateSnapshot"),
+    command(
+        "set_hotkey_config",
+        &[("config", "HotkeyConfig")],
+        "HotkeyConfig",
+    ),
+];
+
+const EVENT_CONTRACTS: &[(&str, &str)] = &[
+    (EVENT_STATUS_CHANGED, "AppStatus"),
+    (EVENT_TRANSCRIPT_READY, "TranscriptReadyEvent"),
+    (EVENT_TRANSCRIPTION_DELTA, "string"),
+    (EVENT_TRANSCRIPTION_RETRY, "TranscriptionRetryEvent"),
+    (EVENT_DICTATION_PROGRESS, "DictationProgressEvent"),
+    (EVENT_PIPELINE_ERROR, "PipelineErrorEvent"),
+    (EVENT_INSERT_CONFIRMATION_REQUESTED, "PendingInsert"),
+    (
+        EVENT_INSERTION_COPIED_INSTEAD,
+        "InsertionCopiedInsteadEvent",
+    ),
+    (EVENT_TRANSCRIPT_FLAGGED, "FlaggedTranscript"),
+    (EVENT_TRANSCRIPT_SUPPRESSED, "TranscriptSuppressedEvent"),
+    (EVENT_LANGUAGE_UNCERTAIN, "LanguageUncertainEvent"),
+    (EVENT_SETTINGS_CHANGED, "VoiceSettings"),
+    (EVENT_REDACTION_WARNING, "RedactionWarningEvent"),
+    (EVENT_HISTORY_IMPORT_PROGRESS, "HistoryImportProgressEvent"),
+    (
+        EVENT_HISTORY_REPROCESS_PROGRESS,
+        "HistoryReprocessProgressEvent",
+    ),
+    (EVENT_RECOVERED_RECORDINGS_FOUND, "RecoveredRecording[]"),
+    (EVENT_RECORDING_LIMIT_REACHED, "RecordingLimitReachedEvent"),
+    (
+        EVENT_PENDING_TRANSCRIPTIONS_CHANGED,
+        "PendingTranscription[]",
+    ),
+    (
+        EVENT_PENDING_TRANSCRIPTION_COMPLETED,
+        "PendingTranscriptionCompletedEvent",
+    ),
+    (EVENT_OVERLAY_AUDIO_LEVEL, "number"),
+    (EVENT_OVERLAY_AUDIO_SPECTRUM, "AudioSpectrumEvent"),
+    (EVENT_DEEP_LINK_NAVIGATE, "DeepLinkNavigation"),
+    (EVENT_FILE_TRANSCRIPTION, "FileTranscriptionEvent"),
+    (AUDIO_LEVEL_EVENT, "number"),
+    (AUDIO_SPECTRUM_EVENT, "AudioSpectrumEvent"),
+    (AUDIO_INPUT_STREAM_ERROR_EVENT, "AudioInputStreamErrorEvent"),
+    (EVENT_RECORDING_STATE_CHANGED, "RecordingStateChangedEvent"),
+    (EVENT_RECORDING_STARTED, "RecordingStateChangedEvent"),
+    (EVENT_RECORDING_STOPPED, "RecordingStateChangedEvent"),
+    (EVENT_HOTKEY_CONFIG_CHANGED, "HotkeyConfig"),
+    (EVENT_FOCUSED_APP_CHANGED, "FocusedApp"),
+    (EVENT_CONFLICTING_INSTANCE, "ConflictingInstanceReport"),
+    (EVENT_AUTH_EXPIRED, "AuthExpiredEvent"),
+    (EVENT_PERMISSIONS_CHANGED, "PermissionSnapshot"),
+    (EVENT_PERMISSION_CHANGED, "PermissionChangedEvent"),
+];
+
+fn schema_for<T: JsonSchema>() -> RootSchema {
+    SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<T>()
+}
+
+pub fn contract_schemas() -> Vec<(&'static str, RootSchema)> {
+    vec![
+        ("ApiKeyValidation", schema_for::<ApiKeyValidation>()),
+        (
+            "AppDataConflictStrategy",
+            schema_for::<AppDataConflictStrategy>(),
+        ),
+        ("AppDataImportReport", schema_for::<AppDataImportReport>()),
+        ("AppError", schema_for::<AppError>()),
+        ("AppStatus", schema_for::<AppStatus>()),
+        ("AppUsage", schema_for::<AppUsage>()),
+        (
+            "AudioInputStreamErrorEvent",
+            schema_for::<AudioInputStreamErrorEvent>(),
+        ),
+        ("AudioSpectrumEvent", schema_for::<AudioSpectrumEvent>()),
+        ("AuthExpiredEvent", schema_for::<AuthExpiredEvent>()),
+        ("CaptureSourceInfo", schema_for::<CaptureSourceInfo>()),
+        ("ChatGptAuthStatus", schema_for::<ChatGptAuthStatus>()),
+        ("CompatibilityReport", schema_for::<CompatibilityReport>()),
+        (
+            "ConflictingInstanceReport",
+            schema_for::<ConflictingInstanceReport>(),
+        ),
+        ("CrashReport", schema_for::<CrashReport>()),
+        ("DailyUsage", schema_for::<DailyUsage>()),
+        ("DeepLinkNavigation", schema_for::<DeepLinkNavigation>()),
+        ("DiagnosticsReport", schema_for::<DiagnosticsReport>()),
+        ("ErrorCode", schema_for::<ErrorCode>()),
+        (
+            "DictationProgressEvent",
+            schema_for::<DictationProgressEvent>(),
+        ),
+        (
+            "FileTranscriptionEvent",
+            schema_for::<FileTranscriptionEvent>(),
+        ),
+        ("FlaggedTranscript", schema_for::<FlaggedTranscript>()),
+        ("FocusedApp", schema_for::<FocusedApp>()),
+        ("HistoryEntry", schema_for::<HistoryEntry>()),
+        ("HistoryImportFormat", schema_for::<HistoryImportFormat>()),
+        (
+            "HistoryImportProgressEvent",
+            schema_for::<HistoryImportProgressEvent>(),
+        ),
+        ("HistoryImportReport", schema_for::<HistoryImportReport>()),
+        (
+            "HistoryReprocessProgressEvent",
+            schema_for::<HistoryReprocessProgressEvent>(),
+        ),
+        (
+            "HistoryReprocessReport",
+            schema_for::<HistoryReprocessReport>(),
+        ),
+        ("HotkeyConfig", schema_for::<HotkeyConfig>()),
+        ("HotkeyStateSnapshot", schema_for::<HotkeyStateSnapshot>()),
+        ("InputTriggerDevice", schema_for::<InputTriggerDevice>()),
+        ("InsertionAuditEntry", schema_for::<InsertionAuditEntry>()),
+        (
+            "InsertionCopiedInsteadEvent",
+            schema_for::<InsertionCopiedInsteadEvent>(),
+        ),
+        (
+            "LanguageUncertainEvent",
+            schema_for::<LanguageUncertainEvent>(),
+        ),
+        ("LogEntry", schema_for::<LogEntry>()),
+        ("LogLevel", schema_for::<LogLevel>()),
+        ("MicrophoneInfo", schema_for::<MicrophoneInfo>()),
+        ("OverlayOffset", schema_for::<OverlayOffset>()),
+        ("PendingInsert", schema_for::<PendingInsert>()),
+        ("PendingInsertAction", schema_for::<PendingInsertAction>()),
+        ("PendingTranscription", schema_for::<PendingTranscription>()),
+        (
+            "PendingTranscriptionCompletedEvent",
+            schema_for::<PendingTranscriptionCompletedEvent>(),
+        ),
+        (
+            "PermissionChangedEvent",
+            schema_for::<PermissionChangedEvent>(),
+        ),
+        ("PermissionSnapshot", schema_for::<PermissionSnapshot>()),
+        ("PermissionState", schema_for::<PermissionState>()),
+        ("PermissionType", schema_for::<PermissionType>()),
+        ("PipelineErrorEvent", schema_for::<PipelineErrorEvent>()),
+        ("ProviderConfig", schema_for::<ProviderConfig>()),
+        ("RecordedAudio", schema_for::<RecordedAudio>()),
+        ("RecoveredRecording", schema_for::<RecoveredRecording>()),
+        ("RedactionRule", schema_for::<RedactionRule>()),
+        (
+            "RedactionWarningEvent",
+            schema_for::<RedactionWarningEvent>(),
+        ),
+        ("ReleaseNotesReport", schema_for::<ReleaseNotesReport>()),
+        (
+            "RecordingLimitReachedEvent",
+            schema_for::<RecordingLimitReachedEvent>(),
+        ),
+        (
+            "RecordingStateChangedEvent",
+            schema_for::<RecordingStateChangedEvent>(),
+        ),
+        ("SelfTestReport", schema_for::<SelfTestReport>()),
+        ("SessionTraceEvent", schema_for::<SessionTraceEvent>()),
+        ("SettingsSnapshot", schema_for::<SettingsSnapshot>()),
+        ("Snippet", schema_for::<Snippet>()),
+        ("StatusDetails", schema_for::<StatusDetails>()),
+        ("SubtitleFormat", schema_for::<SubtitleFormat>()),
+        ("TelemetryEvent", schema_for::<TelemetryEvent>()),
+        ("TranscriptReadyEvent", schema_for::<TranscriptReadyEvent>()),
+        (
+            "TranscriptSuppressedEvent",
+            schema_for::<TranscriptSuppressedEvent>(),
+        ),
+        ("TranscriptionModel", schema_for::<TranscriptionModel>()),
+        ("TranscriptionOptions", schema_for::<TranscriptionOptions>()),
+        (
+            "TranscriptionRetryEvent",
+            schema_for::<TranscriptionRetryEvent>(),
+        ),
+        ("UsageStatsReport", schema_for::<UsageStatsReport>()),
+        ("VocabularyRange", schema_for::<VocabularyRange>()),
+        ("VocabularyReport", schema_for::<VocabularyReport>()),
+        ("VoiceSettings", schema_for::<VoiceSettings>()),
+        ("VoiceSettingsUpdate", schema_for::<VoiceSettingsUpdate>()),
+    ]
+}
+
+pub fn contract_index() -> Value {
+    let commands = COMMAND_CONTRACTS
+        .iter()
+        .map(|contract| {
+            let args = contract
+                .args
+                .iter()
+                .map(|(name, type_ref)| (name.to_string(), Value::String(type_ref.to_string())))
+                .collect::<Map<_, _>>();
+            (
+                contract.name.to_string(),
+                json!({ "args": args, "output": contract.output }),
+            )
+        })
+        .collect::<Map<_, _>>();
+    let events = EVENT_CONTRACTS
+        .iter()
+        .map(|(name, payload)| (name.to_string(), Value::String(payload.to_string())))
+        .collect::<Map<_, _>>();
+    let schemas = contract_schemas()
+        .into_iter()
+        .map(|(name, _)| Value::String(schema_file_name(name)))
+        .collect::<Vec<_>>();
+
+    // Every command rejects with the same error shape.
+    json!({
+        "commands": commands,
+        "error": "AppError",
+        "events": events,
+        "schemas": schemas,
+    })
+}
+
+// Returns `(file name, contents)` pairs so callers can either write them or
+// compare them against the checked-in copies.
+pub fn render_schema_files() -> Result<Vec<(String, String)>, String> {
+    let mut files = contract_schemas()
+        .into_iter()
+        .map(|(name, schema)| {
+            to_pretty_json(&schema).map(|contents| (schema_file_name(name), contents))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    files.push((
+        SCHEMA_INDEX_FILE_NAME.to_string(),
+        to_pretty_json(&contract_index())?,
+    ));
+    Ok(files)
+}
+
+pub fn write_schemas(output_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    fs::create_dir_all(output_dir).map_err(|error| {
+        format!(
+            "Failed to create schema directory `{}`: {error}",
+            output_dir.display()
+        )
+    })?;
+
+    render_schema_files()?
+        .into_iter()
+        .map(|(file_name, contents)| {
+            let path = output_dir.join(file_name);
+            fs::write(&path, contents)
+                .map_err(|error| format!("Failed to write `{}`: {error}", path.display()))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+fn schema_file_name(name: &str) -> String {
+    format!("{name}.schema.json")
+}
+
+fn to_pretty_json<T: serde::Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value)
+        .map(|contents| format!("{contents}\n"))
+        .map_err(|error| format!("Failed to serialize schema: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hotkey_service::{HotkeyTrigger, RecordingMode, RecordingTransition},
+        transcription::TranscriptionSegment,
+        voice_pipeline::error_router::ErrorAction,
+    };
+
+    const PRIMITIVE_TYPES: [&str; 4] = ["boolean", "null", "number", "string"];
+
+    fn committed_schema_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("schemas")
+    }
+
+    fn schema_named(name: &str) -> Value {
+        let (_, schema) = contract_schemas()
+            .into_iter()
+            .find(|(candidate, _)| *candidate == name)
+            .unwrap_or_else(|| panic!("schema `{name}` should exist"));
+        serde_json::to_value(schema).expect("schema should serialize")
+    }
+
+    fn assert_matches_schema<T: serde::Serialize>(name: &str, value: &T) {
+        let schema = schema_named(name);
+        let serialized = serde_json::to_value(value).expect("payload should serialize");
+        let object = serialized
+            .as_object()
+            .unwrap_or_else(|| panic!("`{name}` should serialize to an object"));
+        let properties = schema["properties"]
+            .as_object()
+            .unwrap_or_else(|| panic!("`{name}` schema should declare properties"));
+
+        for key in object.keys() {
+            assert!(
+                properties.contains_key(key),
+                "`{name}` serialized undeclared field `{key}`"
+            );
+        }
+        for required in schema["required"].as_array().into_iter().flatten() {
+            let required = required.as_str().expect("required entries are strings");
+            assert!(
+                object.contains_key(required),
+                "`{name}` is missing required field `{required}`"
+            );
+        }
+    }
+
+    #[test]
+    fn committed_schemas_match_generated_contracts() {
+        let schema_dir = committed_schema_dir();
+        for (file_name, expected) in render_schema_files().expect("schemas should render") {
+            let committed = fs::read_to_string(schema_dir.join(&file_name)).unwrap_or_default();
+            assert!(
+                committed == expected,
+                "`schemas/{file_name}` is out of date; run `cargo run --bin schema_export`"
+            );
+        }
+    }
+
+    #[test]
+    fn index_only_references_known_schemas() {
+        let known = contract_schemas()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        let type_refs = COMMAND_CONTRACTS
+            .iter()
+            .flat_map(|contract| {
+                contract
+                    .args
+                    .iter()
+                    .map(|(_, type_ref)| *type_ref)
+                    .chain(std::iter::once(contract.output))
+            })
+            .chain(EVENT_CONTRACTS.iter().map(|(_, payload)| *payload));
+
+        for type_ref in type_refs {
+            let base = type_ref.trim_end_matches(" | null").trim_end_matches("[]");
+            assert!(
+                PRIMITIVE_TYPES.contains(&base) || known.contains(&base),
+                "unknown contract type `{type_ref}`"
+            );
+        }
+    }
+
+    #[test]
+    fn serialized_payloads_match_their_schemas() {
+        assert_matches_schema("VoiceSettings", &VoiceSettings::default());
+        assert_matches_schema(
+            "AppError",
+            &AppError::new(ErrorCode::Network, "Network error: offline"),
+        );
+        assert_matches_schema("HotkeyConfig", &HotkeyConfig::default());
+        assert_matches_schema(
+            "HistoryEntry",
+            &HistoryEntry::new(
+                "hello".to_string(),
+                Some(1.0),
+                Some("en".to_string()),
+                "openai".to_string(),
+            )
+            .with_segments(vec![TranscriptionSegment {
+                start_secs: 0.0,
+                end_secs: 1.0,
+                text: "hello".to_string(),
+                speaker: Some("Speaker 1".to_string()),
+                confidence: None,
+            }]),
+        );
+        assert_matches_schema(
+            "RecordingStateChangedEvent",
+            &RecordingStateChangedEvent {
+                is_recording: true,
+                binding_name: "Alt+Space".to_string(),
+                mode: RecordingMode::Toggle,
+                shortcut: "Alt+Space".to_string(),
+                transition: RecordingTransition::Started,
+                trigger: HotkeyTrigger::Pressed,
+            },
+        );
+        assert_matches_schema(
+            "PipelineErrorEvent",
+            &PipelineErrorEvent {
+                stage: "transcription".to_string(),
+                code: ErrorCode::NoAuth,
+                message: "failed".to_string(),
+                recoverable: false,
+                hint: ErrorCode::NoAuth.hint().map(str::to_string),
+                action: Some(ErrorAction::OpenApiKeySettings),
+                help_url: ErrorAction::OpenApiKeySettings
+                    .help_url()
+                    .map(str::to_string),
+            },
+        );
+    }
+}
@@ -0,0 +1,478 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+
+use chrono::{SecondsFormat, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tracing::warn;
+
+use crate::settings_store::VoiceSettings;
+use crate::snippets_store::{same_trigger, Snippet};
+
+const ARCHIVE_FORMAT: &str = "voice-app-data";
+const ARCHIVE_VERSION: u32 = 1;
+// The archive this machine last exported or imported; it is the common
+// ancestor that tells a local edit apart from one made on the other machine.
+const SYNC_BASE_FILE_NAME: &str = "sync_base.json";
+// Settings that describe this machine rather than the user's preferences.
+const MACHINE_SETTINGS: &[&str] = &[
+    "input_triggers",
+    "microphone_id",
+    "microphone_channels",
+    "overlay_display",
+    "overlay_offsets",
+    "watch_folders",
+    "launch_at_login",
+    "onboarding_completed",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AppDataArchive {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub settings: Map<String, Value>,
+    pub snippets: Vec<Snippet>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum AppDataConflictStrategy {
+    #[default]
+    KeepLocal,
+    UseImported,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum AppDataItemKind {
+    Setting,
+    Snippet,
+}
+
+// Something changed on both machines since they last synced.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppDataConflict {
+    pub kind: AppDataItemKind,
+    // The setting name, or the snippet's trigger.
+    pub key: String,
+    pub resolution: AppDataConflictStrategy,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppDataImportReport {
+    pub settings_updated: Vec<String>,
+    pub snippets_added: usize,
+    pub snippets_updated: usize,
+    pub snippets_removed: usize,
+    pub conflicts: Vec<AppDataConflict>,
+}
+
+#[derive(Debug)]
+pub struct AppDataMerge {
+    pub settings: VoiceSettings,
+    pub snippets: Vec<Snippet>,
+    pub report: AppDataImportReport,
+}
+
+pub fn build_archive(
+    settings: &VoiceSettings,
+    snippets: Vec<Snippet>,
+) -> Result<AppDataArchive, String> {
+    Ok(AppDataArchive {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        exported_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        settings: shared_settings(settings)?,
+        snippets,
+    })
+}
+
+pub fn write_archive(path: &Path, archive: &AppDataArchive) -> Result<(), String> {
+    if let Some(parent_dir) = path.parent() {
+        fs::create_dir_all(parent_dir)
+            .map_err(|error| format!("Failed to create export directory: {error}"))?;
+    }
+    let contents = serde_json::to_vec_pretty(archive)
+        .map_err(|error| format!("Failed to serialize app data: {error}"))?;
+    fs::write(path, contents).map_err(|error| format!("Failed to write app data archive: {error}"))
+}
+
+pub fn read_archive(path: &Path) -> Result<AppDataArchive, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read app data archive: {error}"))?;
+    let archive = serde_json::from_str::<AppDataArchive>(&contents)
+        .map_err(|error| format!("Not a Voice app data archive: {error}"))?;
+    if archive.format != ARCHIVE_FORMAT {
+        return Err(format!("Not a Voice app data archive: {}", archive.format));
+    }
+    if archive.version > ARCHIVE_VERSION {
+        return Err(format!(
+            "This archive was written by a newer version of Voice (format {})",
+            archive.version
+        ));
+    }
+    Ok(archive)
+}
+
+pub fn load_sync_base(app_data_dir: &Path) -> Option<AppDataArchive> {
+    let path = app_data_dir.join(SYNC_BASE_FILE_NAME);
+    if !path.exists() {
+        return None;
+    }
+    read_archive(&path)
+        .inspect_err(|error| warn!(%error, "ignoring unreadable sync base"))
+        .ok()
+}
+
+pub fn save_sync_base(app_data_dir: &Path, archive: &AppDataArchive) -> Result<(), String> {
+    write_archive(&app_data_dir.join(SYNC_BASE_FILE_NAME), archive)
+}
+
+// Three-way merge against the last synced archive: a side that still matches
+// the base takes the other side's edit, and only items edited on both sides
+// are conflicts. With no base yet, additions merge and every difference is a
+// conflict.
+pub fn merge_archive(
+    local_settings: &VoiceSettings,
+    local_snippets: &[Snippet],
+    imported: &AppDataArchive,
+    base: Option<&AppDataArchive>,
+    strategy: AppDataConflictStrategy,
+) -> Result<AppDataMerge, String> {
+    let mut report = AppDataImportReport::default();
+
+    let local_shared = shared_settings(local_settings)?;
+    // Keys a newer build added are ignored rather than written into a
+    // settings file that would drop them anyway.
+    let imported_shared = imported
+        .settings
+        .iter()
+        .filter(|(key, _)| local_shared.contains_key(*key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect::<Map<_, _>>();
+    let mut merged_settings = serde_json::to_value(local_settings)
+        .map_err(|error| format!("Failed to serialize settings: {error}"))?;
+    for (key, pick) in merge_keyed(
+        &to_keyed(&local_shared),
+        &to_keyed(&imported_shared),
+        base.map(|base| to_keyed(&base.settings)).as_ref(),
+        strategy,
+    ) {
+        if pick.conflict {
+            report.conflicts.push(AppDataConflict {
+                kind: AppDataItemKind::Setting,
+                key: key.clone(),
+                resolution: strategy,
+            });
+        }
+        if let (Some(value), true) = (pick.value, pick.from_imported) {
+            merged_settings[&key] = value;
+            report.settings_updated.push(key);
+        }
+    }
+    let mut settings = serde_json::from_value::<VoiceSettings>(merged_settings)
+        .map_err(|error| format!("Imported settings are invalid: {error}"))?;
+    settings.proxy.password = local_settings.proxy.password.clone();
+    settings.provider_config.extra_headers = local_settings.provider_config.extra_headers.clone();
+
+    let snippets = merge_snippets(
+        local_snippets,
+        &imported.snippets,
+        base.map(|base| base.snippets.as_slice()),
+        strategy,
+        &mut report,
+    )?;
+
+    Ok(AppDataMerge {
+        settings,
+        snippets,
+        report,
+    })
+}
+
+// Proxy passwords and provider headers usually carry credentials, so like
+// API keys they never leave this machine.
+fn shared_settings(settings: &VoiceSettings) -> Result<Map<String, Value>, String> {
+    let Value::Object(mut shared) = serde_json::to_value(settings)
+        .map_err(|error| format!("Failed to serialize settings: {error}"))?
+    else {
+        return Err("Settings did not serialize to an object".to_string());
+    };
+    shared.retain(|key, _| !MACHINE_SETTINGS.contains(&key.as_str()));
+    if let Some(proxy) = shared.get_mut("proxy").and_then(Value::as_object_mut) {
+        proxy.remove("password");
+    }
+    if let Some(config) = shared
+        .get_mut("provider_config")
+        .and_then(Value::as_object_mut)
+    {
+        config.remove("extra_headers");
+    }
+    Ok(shared)
+}
+
+fn merge_snippets(
+    local: &[Snippet],
+    imported: &[Snippet],
+    base: Option<&[Snippet]>,
+    strategy: AppDataConflictStrategy,
+    report: &mut AppDataImportReport,
+) -> Result<Vec<Snippet>, String> {
+    let by_id = |snippets: &[Snippet]| -> Result<BTreeMap<String, Value>, String> {
+        snippets
+            .iter()
+            .map(|snippet| {
+                serde_json::to_value(snippet)
+                    .map(|value| (snippet.id.clone(), value))
+                    .map_err(|error| format!("Failed to serialize snippet: {error}"))
+            })
+            .collect()
+    };
+    let local_by_id = by_id(local)?;
+    let base_by_id = base.map(by_id).transpose()?;
+
+    let mut merged = Vec::new();
+    for (id, pick) in merge_keyed(
+        &local_by_id,
+        &by_id(imported)?,
+        base_by_id.as_ref(),
+        strategy,
+    ) {
+        let snippet = pick
+            .value
+            .map(serde_json::from_value::<Snippet>)
+            .transpose()
+            .map_err(|error| format!("Imported snippet is invalid: {error}"))?;
+        if pick.conflict {
+            let trigger = snippet
+                .as_ref()
+                .or_else(|| local.iter().find(|local| local.id == id))
+                .map(|snippet| snippet.trigger.clone())
+                .unwrap_or_else(|| id.clone());
+            report.conflicts.push(AppDataConflict {
+                kind: AppDataItemKind::Snippet,
+                key: trigger,
+                resolution: strategy,
+            });
+        }
+        if pick.from_imported {
+            match (&snippet, local_by_id.contains_key(&id)) {
+                (Some(_), true) => report.snippets_updated += 1,
+                (Some(_), false) => report.snippets_added += 1,
+                (None, _) => report.snippets_removed += 1,
+            }
+        }
+        if let Some(snippet) = snippet {
+            merged.push((snippet, pick.from_imported));
+        }
+    }
+
+    // Both machines may have created the same trigger under different ids;
+    // only one can stay.
+    let prefer_imported = strategy == AppDataConflictStrategy::UseImported;
+    merged.sort_by_key(|(_, from_imported)| *from_imported != prefer_imported);
+    let mut kept: Vec<Snippet> = Vec::new();
+    for (snippet, from_imported) in merged {
+        if kept
+            .iter()
+            .any(|other| same_trigger(&other.trigger, &snippet.trigger))
+        {
+            report.conflicts.push(AppDataConflict {
+                kind: AppDataItemKind::Snippet,
+                key: snippet.trigger.clone(),
+                resolution: strategy,
+            });
+            if from_imported {
+                report.snippets_added = report.snippets_added.saturating_sub(1);
+            }
+            continue;
+        }
+        kept.push(snippet);
+    }
+    // Keep the local list order so the settings UI doesn't reshuffle.
+    let position = |id: &str| {
+        local
+            .iter()
+            .position(|snippet| snippet.id == id)
+            .unwrap_or(usize::MAX)
+    };
+    kept.sort_by_key(|snippet| position(&snippet.id));
+    Ok(kept)
+}
+
+struct MergePick {
+    value: Option<Value>,
+    from_imported: bool,
+    conflict: bool,
+}
+
+fn to_keyed(map: &Map<String, Value>) -> BTreeMap<String, Value> {
+    map.iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+fn merge_keyed(
+    local: &BTreeMap<String, Value>,
+    imported: &BTreeMap<String, Value>,
+    base: Option<&BTreeMap<String, Value>>,
+    strategy: AppDataConflictStrategy,
+) -> Vec<(String, MergePick)> {
+    let keys = local.keys().chain(imported.keys()).collect::<BTreeSet<_>>();
+    keys.into_iter()
+        .map(|key| {
+            let local_value = local.get(key);
+            let imported_value = imported.get(key);
+            let base_value = base.and_then(|base| base.get(key));
+            let (from_imported, conflict) = if local_value == imported_value {
+                (false, false)
+            } else if local_value == base_value {
+                (true, false)
+            } else if imported_value == base_value {
+                (false, false)
+            } else {
+                (strategy == AppDataConflictStrategy::UseImported, true)
+            };
+            let value = if from_imported {
+                imported_value
+            } else {
+                local_value
+            };
+            (
+                key.clone(),
+                MergePick {
+                    value: value.cloned(),
+                    from_imported,
+                    conflict,
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_archive, merge_archive, AppDataConflictStrategy, AppDataItemKind};
+    use crate::settings_store::VoiceSettings;
+    use crate::snippets_store::Snippet;
+
+    fn snippet(id: &str, trigger: &str, template: &str) -> Snippet {
+        Snippet {
+            id: id.to_string(),
+            trigger: trigger.to_string(),
+            template: template.to_string(),
+        }
+    }
+
+    #[test]
+    fn archives_leave_out_secrets_and_machine_settings() {
+        let mut settings = VoiceSettings::default();
+        settings.proxy.password = Some("hunter2".to_string());
+        settings.microphone_id = Some("usb-mic".to_string());
+        settings
+            .provider_config
+            .extra_headers
+            .insert("Authorization".to_string(), "Bearer secret".to_string());
+
+        let archive = build_archive(&settings, Vec::new()).expect("archive should build");
+        let json = serde_json::to_string(&archive).expect("archive should serialize");
+        assert!(!json.contains("hunter2"));
+        assert!(!json.contains("Bearer secret"));
+        assert!(!archive.settings.contains_key("microphone_id"));
+        assert!(archive.settings.contains_key("transcription_style"));
+
+        // Importing on the same machine keeps the local secrets.
+        let merge = merge_archive(
+            &settings,
+            &[],
+            &archive,
+            None,
+            AppDataConflictStrategy::UseImported,
+        )
+        .expect("merge should succeed");
+        assert_eq!(merge.settings, settings);
+        assert!(merge.report.conflicts.is_empty());
+    }
+
+    #[test]
+    fn three_way_merge_takes_remote_edits_and_flags_conflicts() {
+        let base_settings = VoiceSettings {
+            transcription_style: "clean".to_string(),
+            language: Some("en".to_string()),
+            ..VoiceSettings::default()
+        };
+        let base = build_archive(
+            &base_settings,
+            vec![
+                snippet("a", "sign off", "Thanks"),
+                snippet("b", "standup", "Yesterday"),
+            ],
+        )
+        .expect("base should build");
+
+        let mut remote_settings = base_settings.clone();
+        remote_settings.transcription_style = "casual".to_string();
+        remote_settings.language = Some("fr".to_string());
+        let remote = build_archive(
+            &remote_settings,
+            vec![
+                snippet("a", "sign off", "Cheers"),
+                snippet("c", "address", "1 Main St"),
+            ],
+        )
+        .expect("remote should build");
+
+        let mut local_settings = base_settings.clone();
+        local_settings.language = Some("de".to_string());
+        let local_snippets = vec![
+            snippet("a", "sign off", "Thanks"),
+            snippet("b", "standup", "Yesterday"),
+            snippet("e", "Address", "2 Side St"),
+        ];
+
+        let merge = merge_archive(
+            &local_settings,
+            &local_snippets,
+            &remote,
+            Some(&base),
+            AppDataConflictStrategy::KeepLocal,
+        )
+        .expect("merge should succeed");
+
+        assert_eq!(merge.settings.transcription_style, "casual");
+        assert_eq!(merge.settings.language.as_deref(), Some("de"));
+        assert_eq!(merge.report.settings_updated, vec!["transcription_style"]);
+        assert_eq!(
+            merge
+                .snippets
+                .iter()
+                .map(|snippet| (snippet.id.as_str(), snippet.template.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("a", "Cheers"), ("e", "2 Side St")]
+        );
+        assert_eq!(merge.report.snippets_updated, 1);
+        // "c" was created on the other machine with a trigger "e" already uses.
+        assert_eq!(merge.report.snippets_added, 0);
+        assert_eq!(merge.report.snippets_removed, 1);
+        assert_eq!(
+            merge
+                .report
+                .conflicts
+                .iter()
+                .map(|conflict| (conflict.kind, conflict.key.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (AppDataItemKind::Setting, "language"),
+                (AppDataItemKind::Snippet, "address")
+            ]
+        );
+    }
+}
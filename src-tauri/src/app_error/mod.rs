@@ -0,0 +1,163 @@
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::transcription::TranscriptionError;
+
+// What went wrong, coarse enough for the frontend to pick a screen or a
+// retry button without reading the message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    NoAuth,
+    PermissionDenied,
+    Network,
+    Quota,
+    RateLimited,
+    Timeout,
+    NotFound,
+    InvalidInput,
+    Conflict,
+    Cancelled,
+    Provider,
+    Internal,
+}
+
+impl ErrorCode {
+    // Whether trying the same thing again, unchanged, can succeed.
+    pub fn recoverable(self) -> bool {
+        matches!(
+            self,
+            Self::Network
+                | Self::RateLimited
+                | Self::Timeout
+                | Self::Conflict
+                | Self::Cancelled
+                | Self::Provider
+        )
+    }
+
+    pub fn hint(self) -> Option<&'static str> {
+        match self {
+            Self::NoAuth => Some("Add an API key or sign in with ChatGPT in Settings."),
+            Self::PermissionDenied => Some("Grant the permission in System Settings, then retry."),
+            Self::Network => Some("Check your internet connection or proxy settings."),
+            Self::Quota => Some("Add credits or raise the usage limit with your provider."),
+            Self::RateLimited => Some("Wait a moment before trying again."),
+            Self::Timeout => Some("Try again, or switch to a faster model."),
+            Self::Conflict => Some("Settings changed elsewhere; reload them and retry."),
+            Self::NotFound
+            | Self::InvalidInput
+            | Self::Cancelled
+            | Self::Provider
+            | Self::Internal => None,
+        }
+    }
+}
+
+// The error every command returns to the frontend.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub recoverable: bool,
+    pub hint: Option<String>,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            recoverable: code.recoverable(),
+            hint: code.hint().map(str::to_string),
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+// A bare message says nothing about what went wrong, so it is reported as
+// internal; failures the frontend can act on are built with their code.
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+}
+
+impl From<TranscriptionError> for AppError {
+    fn from(error: TranscriptionError) -> Self {
+        let code = match &error {
+            TranscriptionError::MissingApiKey | TranscriptionError::Authentication(_) => {
+                ErrorCode::NoAuth
+            }
+            TranscriptionError::RateLimited(_) => ErrorCode::RateLimited,
+            TranscriptionError::QuotaExceeded(_) => ErrorCode::Quota,
+            TranscriptionError::Network(_) => ErrorCode::Network,
+            TranscriptionError::InvalidResponse(_) | TranscriptionError::Provider(_) => {
+                ErrorCode::Provider
+            }
+            TranscriptionError::Cancelled => ErrorCode::Cancelled,
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppError, ErrorCode};
+    use crate::transcription::TranscriptionError;
+
+    #[test]
+    fn bare_messages_are_internal() {
+        // Wording alone never picks a code, however it reads.
+        for message in [
+            "Network error: connection reset",
+            "Permission denied (os error 13)",
+            "HTTP 401",
+        ] {
+            let error = AppError::from(message.to_string());
+            assert_eq!(error.code, ErrorCode::Internal);
+            assert!(!error.recoverable);
+            assert_eq!(error.hint, None);
+        }
+
+        let error = AppError::new(ErrorCode::Network, "offline");
+        assert!(error.recoverable);
+        assert!(error.hint.is_some());
+        assert_eq!(
+            serde_json::to_value(&error).expect("error should serialize")["code"],
+            "network"
+        );
+    }
+
+    #[test]
+    fn transcription_errors_keep_their_kind() {
+        let code = |error| AppError::from(error).code;
+
+        assert_eq!(code(TranscriptionError::MissingApiKey), ErrorCode::NoAuth);
+        assert_eq!(
+            code(TranscriptionError::QuotaExceeded("slow down".to_string())),
+            ErrorCode::Quota
+        );
+        assert_eq!(
+            code(TranscriptionError::RateLimited(
+                "You exceeded your current quota".to_string()
+            )),
+            ErrorCode::RateLimited
+        );
+        assert_eq!(
+            code(TranscriptionError::Network("timed out".to_string())),
+            ErrorCode::Network
+        );
+        assert!(!AppError::from(TranscriptionError::MissingApiKey).recoverable);
+    }
+}
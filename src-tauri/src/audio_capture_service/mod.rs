@@ -1,6 +1,14 @@
+mod noise_suppression;
+mod recovery;
+mod retro;
+mod spectrum;
+mod spool;
+mod warm;
+
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{BTreeMap, HashMap, VecDeque},
     fmt,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicU32, Ordering},
         mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError},
@@ -14,16 +22,151 @@ use cpal::{
     traits::{DeviceTrait, HostTrait, StreamTrait},
     Device, SampleFormat, Stream, StreamConfig, StreamError,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
 use tracing::{debug, error, info, warn};
 
+use crate::app_error::{AppError, ErrorCode};
+use crate::crash_reporter;
+use crate::settings_store::{
+    VoiceSettings, MICROPHONE_CHANNEL_LEFT, MICROPHONE_CHANNEL_NUMBERED_PREFIX,
+    MICROPHONE_CHANNEL_RIGHT,
+};
+use noise_suppression::NoiseSuppressor;
+use recovery::RecoveryCheckpoint;
+pub use recovery::{
+    discard_recovered_recording, list_recovered_recordings, load_recovered_recording_wav,
+    recovery_dir, RecoveredRecording,
+};
+use retro::RetroCapture;
+pub use spectrum::AudioSpectrumEvent;
+use spectrum::{LevelHistory, SpectrumTap};
+use spool::SampleSpool;
+pub use spool::{AudioSpoolConfig, SpooledWav};
+use warm::{WarmActivation, WarmCapture};
+
 pub const AUDIO_LEVEL_EVENT: &str = "audio-level";
+pub const AUDIO_SPECTRUM_EVENT: &str = "audio-spectrum";
 pub const AUDIO_INPUT_STREAM_ERROR_EVENT: &str = "voice://audio-input-stream-error";
 const LEVEL_EVENT_INTERVAL: Duration = Duration::from_millis(50);
 const WORKER_STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+const WAV_HEADER_LEN: usize = 44;
+// There is no native system audio capture (no ScreenCaptureKit or process
+// taps): the `loopback` source records a virtual input device from a
+// third-party driver (BlackHole, Loopback, Soundflower, ...) that the user
+// routes the macOS output to, found by its name. Without such a driver the
+// source reports itself unavailable.
+const LOOPBACK_DEVICE_NAME_HINTS: [&str; 6] = [
+    "blackhole",
+    "loopback",
+    "soundflower",
+    "ishowu",
+    "background music",
+    "system audio",
+];
+// How far system audio may run ahead of the microphone in the realtime mix
+// before the oldest of it is dropped.
+const MAX_PENDING_SYSTEM_MIX_MS: usize = 1_000;
+
+// Which input channel(s) feed the mono recording. Interfaces often carry the
+// microphone on one channel and something unrelated on the others.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChannelSelection {
+    #[default]
+    Downmix,
+    // Zero-based; `left` and `right` are channels 0 and 1.
+    Channel(usize),
+}
+
+impl ChannelSelection {
+    // Accepts `downmix`, `left`, `right` or a one-based `channel_<n>`; anything
+    // else downmixes.
+    pub fn from_settings_value(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            MICROPHONE_CHANNEL_LEFT => Self::Channel(0),
+            MICROPHONE_CHANNEL_RIGHT => Self::Channel(1),
+            other => other
+                .strip_prefix(MICROPHONE_CHANNEL_NUMBERED_PREFIX)
+                .and_then(|number| number.parse::<usize>().ok())
+                .filter(|number| *number > 0)
+                .map_or(Self::Downmix, |number| Self::Channel(number - 1)),
+        }
+    }
+}
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputProcessingConfig {
+    pub noise_suppression: bool,
+    pub channel_selections: BTreeMap<String, ChannelSelection>,
+}
+
+impl InputProcessingConfig {
+    pub fn from_settings(settings: &VoiceSettings) -> Self {
+        Self {
+            noise_suppression: settings.noise_suppression,
+            channel_selections: settings
+                .microphone_channels
+                .iter()
+                .map(|(device_id, value)| {
+                    (
+                        device_id.clone(),
+                        ChannelSelection::from_settings_value(value),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    fn channel_selection_for(&self, device_id: &str) -> ChannelSelection {
+        self.channel_selections
+            .get(device_id)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureSource {
+    #[default]
+    Microphone,
+    // A loopback driver's input device; see `LOOPBACK_DEVICE_NAME_HINTS`.
+    Loopback,
+    Mixed,
+}
+
+impl CaptureSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Microphone => "microphone",
+            Self::Loopback => "loopback",
+            Self::Mixed => "mixed",
+        }
+    }
+
+    pub fn from_settings_value(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            // `system` is what the source was called before it was clear
+            // that it needs a loopback driver.
+            "loopback" | "system" => Self::Loopback,
+            "mixed" => Self::Mixed,
+            _ => Self::Microphone,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureSourceInfo {
+    pub source: CaptureSource,
+    pub label: String,
+    pub available: bool,
+    pub device_id: Option<String>,
+    pub device_name: Option<String>,
+    pub unavailable_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MicrophoneInfo {
     pub id: String,
@@ -33,7 +176,7 @@ pub struct MicrophoneInfo {
     pub channels: Option<u16>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RecordedAudio {
     pub wav_bytes: Vec<u8>,
@@ -42,6 +185,53 @@ pub struct RecordedAudio {
     pub duration_ms: u64,
     pub device_id: String,
     pub device_name: String,
+    // Set instead of `wav_bytes` when a long recording was spooled to disk.
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub spooled_wav: Option<Arc<SpooledWav>>,
+}
+
+impl RecordedAudio {
+    pub fn take_wav(&mut self) -> RecordedWav {
+        match self.spooled_wav.take() {
+            Some(spooled) => RecordedWav::Spooled(spooled),
+            None => RecordedWav::Memory(std::mem::take(&mut self.wav_bytes)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum RecordedWav {
+    Memory(Vec<u8>),
+    Spooled(Arc<SpooledWav>),
+}
+
+impl RecordedWav {
+    pub fn byte_len(&self) -> u64 {
+        match self {
+            Self::Memory(bytes) => bytes.len() as u64,
+            Self::Spooled(spooled) => spooled.byte_len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.byte_len() == 0
+    }
+
+    pub fn duration_secs(&self) -> Option<f64> {
+        match self {
+            Self::Memory(bytes) => crate::transcription::chunked::wav_duration_secs(bytes),
+            Self::Spooled(spooled) => Some(spooled.duration_secs()),
+        }
+    }
+
+    // Loads spooled audio for the paths that need every sample in memory.
+    pub fn into_bytes(self) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Memory(bytes) => Ok(bytes),
+            Self::Spooled(spooled) => spooled.read(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -52,7 +242,31 @@ pub struct AudioInputChunk {
 
 pub type AudioInputChunkCallback = Arc<dyn Fn(AudioInputChunk) + Send + Sync + 'static>;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Where capture reports its level, spectrum and stream errors; the app
+// forwards them to the frontend as Tauri events.
+pub trait CaptureEventSink: Send + Sync + 'static {
+    fn emit_json(&self, event: &str, payload: serde_json::Value) -> Result<(), String>;
+}
+
+#[cfg(feature = "tauri")]
+impl<R: tauri::Runtime> CaptureEventSink for tauri::AppHandle<R> {
+    fn emit_json(&self, event: &str, payload: serde_json::Value) -> Result<(), String> {
+        tauri::Emitter::emit(self, event, payload).map_err(|error| error.to_string())
+    }
+}
+
+type CaptureEvents = Arc<dyn CaptureEventSink>;
+
+fn emit_capture_event<T: Serialize>(
+    events: &dyn CaptureEventSink,
+    event: &str,
+    payload: T,
+) -> Result<(), String> {
+    let payload = serde_json::to_value(payload).map_err(|error| error.to_string())?;
+    events.emit_json(event, payload)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AudioInputStreamErrorEvent {
     pub message: String,
@@ -61,17 +275,20 @@ pub struct AudioInputStreamErrorEvent {
 struct RecordingControl {
     stop_tx: Sender<()>,
     join_handle: JoinHandle<()>,
-    samples: Arc<Mutex<Vec<i16>>>,
+    samples: Arc<Mutex<SampleSpool>>,
+    system_samples: Arc<Mutex<SampleSpool>>,
     sample_rate_hz: u32,
+    system_sample_rate_hz: Option<u32>,
     channels: u16,
     started_at: Instant,
     device_id: String,
     device_name: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct RecordingRuntime {
     sample_rate_hz: u32,
+    system_sample_rate_hz: Option<u32>,
     channels: u16,
     device_id: String,
     device_name: String,
@@ -103,6 +320,10 @@ struct InputDeviceSelectionCandidate {
 pub struct AudioCaptureService {
     recording: Mutex<Option<RecordingControl>>,
     audio_level_bits: Arc<AtomicU32>,
+    spool_config: AudioSpoolConfig,
+    recovery_dir: Option<PathBuf>,
+    retro: RetroCapture,
+    warm: WarmCapture,
 }
 
 impl fmt::Debug for AudioCaptureService {
@@ -121,12 +342,28 @@ impl Default for AudioCaptureService {
 impl AudioCaptureService {
     pub fn new() -> Self {
         debug!("audio capture service initialized");
+        let audio_level_bits = Arc::new(AtomicU32::new(0.0_f32.to_bits()));
+        let spool_config = AudioSpoolConfig::from_env();
         Self {
             recording: Mutex::new(None),
-            audio_level_bits: Arc::new(AtomicU32::new(0.0_f32.to_bits())),
+            warm: WarmCapture::new(spool_config.clone()),
+            audio_level_bits,
+            spool_config,
+            recovery_dir: None,
+            retro: RetroCapture::default(),
         }
     }
 
+    // Checkpoints in-progress recordings into `dir` so they survive a crash.
+    pub fn with_recovery_dir(mut self, dir: PathBuf) -> Self {
+        self.recovery_dir = Some(dir);
+        self
+    }
+
+    pub fn recovery_dir(&self) -> Option<&Path> {
+        self.recovery_dir.as_deref()
+    }
+
     pub fn list_microphones(&self) -> Result<Vec<MicrophoneInfo>, String> {
         let host = cpal::default_host();
         let devices = enumerate_input_devices(&host)?;
@@ -144,16 +381,34 @@ impl AudioCaptureService {
             .collect())
     }
 
+    pub fn list_capture_sources(&self) -> Result<Vec<CaptureSourceInfo>, String> {
+        let host = cpal::default_host();
+        let candidates = enumerate_input_devices(&host)?
+            .into_iter()
+            .map(|device| InputDeviceSelectionCandidate {
+                id: device.id,
+                name: device.name,
+                is_default: device.is_default,
+            })
+            .collect::<Vec<_>>();
+        Ok(build_capture_sources(&candidates))
+    }
+
     pub fn start_recording(
         &self,
-        app_handle: AppHandle,
+        events: impl CaptureEventSink,
         preferred_device_id: Option<&str>,
+        capture_source: CaptureSource,
+        processing: InputProcessingConfig,
         on_input_chunk: Option<AudioInputChunkCallback>,
-    ) -> Result<(), String> {
+    ) -> Result<(), AppError> {
         info!(
             preferred_device_id = ?preferred_device_id,
+            capture_source = capture_source.as_str(),
+            noise_suppression = processing.noise_suppression,
             "audio capture start requested"
         );
+        let events: CaptureEvents = Arc::new(events);
         let mut recording_guard = self
             .recording
             .lock()
@@ -161,33 +416,63 @@ impl AudioCaptureService {
 
         if recording_guard.is_some() {
             warn!("recording start requested while already recording");
-            return Err("Recording is already in progress".to_string());
+            return Err(AppError::new(
+                ErrorCode::Conflict,
+                "Recording is already in progress",
+            ));
         }
 
         self.audio_level_bits
             .store(0.0_f32.to_bits(), Ordering::Relaxed);
 
-        let samples = Arc::new(Mutex::new(Vec::<i16>::new()));
-        let worker_samples = Arc::clone(&samples);
-        let worker_level_bits = Arc::clone(&self.audio_level_bits);
-        let worker_app_handle = app_handle.clone();
-        let worker_preferred_device_id = preferred_device_id.map(str::to_string);
-        let worker_chunk_callback = on_input_chunk;
-
-        let (ready_tx, ready_rx) = mpsc::channel::<Result<RecordingRuntime, String>>();
+        let system_samples = Arc::new(Mutex::new(SampleSpool::new(self.spool_config.clone())));
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<RecordingRuntime, AppError>>();
         let (stop_tx, stop_rx) = mpsc::channel::<()>();
 
-        let mut join_handle = Some(thread::spawn(move || {
-            recording_thread_main(
-                worker_preferred_device_id,
-                worker_samples,
-                worker_level_bits,
-                worker_app_handle,
-                worker_chunk_callback,
+        let warm = if capture_source == CaptureSource::Microphone {
+            self.warm.take(preferred_device_id, &processing)
+        } else {
+            None
+        };
+        let (samples, mut join_handle) = if let Some(warm) = warm {
+            info!("handing warmed-up microphone stream to recording");
+            let samples = warm.samples();
+            let join_handle = warm.activate(WarmActivation {
+                events: Arc::clone(&events),
+                on_input_chunk,
+                audio_level_bits: Arc::clone(&self.audio_level_bits),
+                recovery_dir: self.recovery_dir.clone(),
                 ready_tx,
                 stop_rx,
-            );
-        }));
+            });
+            (samples, Some(join_handle))
+        } else {
+            let samples = Arc::new(Mutex::new(SampleSpool::new(self.spool_config.clone())));
+            let worker_samples = Arc::clone(&samples);
+            let worker_system_samples = Arc::clone(&system_samples);
+            let worker_level_bits = Arc::clone(&self.audio_level_bits);
+            let worker_events = Arc::clone(&events);
+            let worker_preferred_device_id = preferred_device_id.map(str::to_string);
+            let worker_chunk_callback = on_input_chunk;
+            let worker_recovery_dir = self.recovery_dir.clone();
+
+            let join_handle = thread::spawn(move || {
+                recording_thread_main(
+                    worker_preferred_device_id,
+                    capture_source,
+                    processing,
+                    worker_samples,
+                    worker_system_samples,
+                    worker_level_bits,
+                    worker_events,
+                    worker_chunk_callback,
+                    worker_recovery_dir,
+                    ready_tx,
+                    stop_rx,
+                );
+            });
+            (samples, Some(join_handle))
+        };
 
         let runtime = await_worker_startup(
             &ready_rx,
@@ -199,7 +484,7 @@ impl AudioCaptureService {
         let join_handle =
             join_handle.ok_or_else(|| "Microphone worker was unavailable".to_string())?;
 
-        if let Err(error) = app_handle.emit(AUDIO_LEVEL_EVENT, 0.0_f32) {
+        if let Err(error) = emit_capture_event(&*events, AUDIO_LEVEL_EVENT, 0.0_f32) {
             warn!(%error, "failed to emit initial audio level event");
         }
 
@@ -207,7 +492,9 @@ impl AudioCaptureService {
             stop_tx,
             join_handle,
             samples,
+            system_samples,
             sample_rate_hz: runtime.sample_rate_hz,
+            system_sample_rate_hz: runtime.system_sample_rate_hz,
             channels: runtime.channels,
             started_at: Instant::now(),
             device_id: runtime.device_id,
@@ -218,7 +505,7 @@ impl AudioCaptureService {
         Ok(())
     }
 
-    pub fn stop_recording(&self, app_handle: AppHandle) -> Result<RecordedAudio, String> {
+    pub fn stop_recording(&self, events: impl CaptureEventSink) -> Result<RecordedAudio, AppError> {
         info!("audio capture stop requested");
         let control = {
             let mut recording_guard = self
@@ -227,14 +514,16 @@ impl AudioCaptureService {
                 .map_err(|_| "Audio capture state lock is poisoned".to_string())?;
             recording_guard
                 .take()
-                .ok_or_else(|| "Recording is not in progress".to_string())?
+                .ok_or_else(|| AppError::new(ErrorCode::Conflict, "Recording is not in progress"))?
         };
 
         let RecordingControl {
             stop_tx,
             join_handle,
             samples,
+            system_samples,
             sample_rate_hz,
+            system_sample_rate_hz,
             channels,
             started_at,
             device_id,
@@ -242,29 +531,78 @@ impl AudioCaptureService {
         } = control;
 
         let _ = stop_tx.send(());
-        if join_handle.join().is_err() {
-            error!("microphone capture thread panicked while stopping");
-            return Err("Microphone capture thread panicked while stopping".to_string());
+        let joined = join_handle.join();
+        self.warm.rearm();
+        if let Err(payload) = joined {
+            let message = crash_reporter::panic_message(payload.as_ref());
+            error!(%message, "microphone capture thread panicked while stopping");
+            return Err(AppError::from(format!(
+                "Microphone capture thread panicked while stopping: {message}"
+            )));
         }
 
-        let buffered_samples = {
-            let mut sample_guard = samples
-                .lock()
-                .map_err(|_| "Audio sample buffer lock is poisoned".to_string())?;
-            std::mem::take(&mut *sample_guard)
-        };
+        let mut sample_guard = samples
+            .lock()
+            .map_err(|_| "Audio sample buffer lock is poisoned".to_string())?;
+        let sample_count = sample_guard.len();
+        let mut duration_ms = started_at.elapsed().as_millis() as u64;
+        if duration_ms == 0 && sample_rate_hz > 0 {
+            duration_ms = (sample_count as u64 * 1000) / u64::from(sample_rate_hz);
+        }
 
-        self.audio_level_bits
-            .store(0.0_f32.to_bits(), Ordering::Relaxed);
-        if let Err(error) = app_handle.emit(AUDIO_LEVEL_EVENT, 0.0_f32) {
-            warn!(%error, "failed to emit audio level reset event after stop");
+        // Mixing needs both tracks in memory, so only single-source recordings
+        // stay on disk.
+        if sample_guard.is_spilled() && system_sample_rate_hz.is_none() {
+            let spooled = sample_guard.finish_spooled_wav(sample_rate_hz, channels)?;
+            sample_guard.discard_recovery();
+            drop(sample_guard);
+            self.reset_audio_level_after_stop(&events);
+            info!(
+                duration_ms,
+                sample_rate_hz,
+                channels,
+                sample_count,
+                wav_bytes = spooled.byte_len(),
+                device_id = %device_id,
+                device_name = %device_name,
+                "audio capture stopped with spooled recording"
+            );
+            return Ok(RecordedAudio {
+                wav_bytes: Vec::new(),
+                sample_rate_hz,
+                channels,
+                duration_ms,
+                device_id,
+                device_name,
+                spooled_wav: Some(Arc::new(spooled)),
+            });
         }
 
-        let mut duration_ms = started_at.elapsed().as_millis() as u64;
-        if duration_ms == 0 && sample_rate_hz > 0 {
-            duration_ms = (buffered_samples.len() as u64 * 1000) / u64::from(sample_rate_hz);
+        let mut buffered_samples = sample_guard.take_samples()?;
+        sample_guard.discard_recovery();
+        drop(sample_guard);
+
+        if let Some(system_sample_rate_hz) = system_sample_rate_hz {
+            let system_track = {
+                let mut sample_guard = system_samples
+                    .lock()
+                    .map_err(|_| "System audio sample buffer lock is poisoned".to_string())?;
+                sample_guard.take_samples()?
+            };
+            let resampled = crate::transcription::realtime::resample_pcm16_linear(
+                &system_track,
+                system_sample_rate_hz,
+                sample_rate_hz,
+            );
+            mix_pcm16_tracks(&mut buffered_samples, &resampled);
+            debug!(
+                system_sample_count = system_track.len(),
+                "mixed system audio track into recording"
+            );
         }
 
+        self.reset_audio_level_after_stop(&events);
+
         let wav_bytes = pcm16_to_wav_bytes(&buffered_samples, sample_rate_hz, channels)?;
         info!(
             duration_ms,
@@ -283,10 +621,19 @@ impl AudioCaptureService {
             duration_ms,
             device_id,
             device_name,
+            spooled_wav: None,
         })
     }
 
-    pub fn abort_recording(&self, app_handle: AppHandle) -> Result<bool, String> {
+    fn reset_audio_level_after_stop(&self, events: &dyn CaptureEventSink) {
+        self.audio_level_bits
+            .store(0.0_f32.to_bits(), Ordering::Relaxed);
+        if let Err(error) = emit_capture_event(events, AUDIO_LEVEL_EVENT, 0.0_f32) {
+            warn!(%error, "failed to emit audio level reset event after stop");
+        }
+    }
+
+    pub fn abort_recording(&self, events: impl CaptureEventSink) -> Result<bool, String> {
         warn!("aborting active audio capture");
         let control = {
             let mut recording_guard = self
@@ -299,6 +646,7 @@ impl AudioCaptureService {
         let Some(RecordingControl {
             stop_tx,
             join_handle,
+            samples,
             ..
         }) = control
         else {
@@ -307,17 +655,24 @@ impl AudioCaptureService {
         };
 
         let _ = stop_tx.send(());
+        self.warm.rearm();
         let join_target = join_handle.thread().id();
         if join_target == thread::current().id() {
             drop(join_handle);
-        } else if join_handle.join().is_err() {
-            error!("microphone capture thread panicked while aborting");
-            return Err("Microphone capture thread panicked while aborting".to_string());
+        } else if let Err(payload) = join_handle.join() {
+            let message = crash_reporter::panic_message(payload.as_ref());
+            error!(%message, "microphone capture thread panicked while aborting");
+            return Err(format!(
+                "Microphone capture thread panicked while aborting: {message}"
+            ));
+        }
+        if let Ok(mut spool) = samples.lock() {
+            spool.discard_recovery();
         }
 
         self.audio_level_bits
             .store(0.0_f32.to_bits(), Ordering::Relaxed);
-        if let Err(error) = app_handle.emit(AUDIO_LEVEL_EVENT, 0.0_f32) {
+        if let Err(error) = emit_capture_event(&events, AUDIO_LEVEL_EVENT, 0.0_f32) {
             warn!(%error, "failed to emit audio level reset event after abort");
         }
 
@@ -328,14 +683,43 @@ impl AudioCaptureService {
     pub fn get_audio_level(&self) -> f32 {
         f32::from_bits(self.audio_level_bits.load(Ordering::Relaxed))
     }
+
+    // The retro buffer is a second, always-on microphone stream, so it only
+    // runs while the user has opted in with a non-zero length.
+    pub fn configure_retro_buffer(
+        &self,
+        preferred_device_id: Option<&str>,
+        processing: InputProcessingConfig,
+        buffer_secs: u32,
+    ) -> Result<(), String> {
+        self.retro
+            .configure(preferred_device_id, processing, buffer_secs)
+    }
+
+    pub fn retro_wav(&self, secs: u32) -> Result<Vec<u8>, String> {
+        self.retro.snapshot_wav(secs)
+    }
+
+    // Keeps a microphone stream open between recordings so starting one does
+    // not wait for the device to spin up. The OS shows the microphone as in
+    // use the whole time, so this is opt-in.
+    pub fn configure_warm_microphone(
+        &self,
+        preferred_device_id: Option<&str>,
+        processing: InputProcessingConfig,
+        enabled: bool,
+    ) -> Result<(), String> {
+        self.warm
+            .configure(preferred_device_id, processing, enabled)
+    }
 }
 
 fn await_worker_startup(
-    ready_rx: &Receiver<Result<RecordingRuntime, String>>,
+    ready_rx: &Receiver<Result<RecordingRuntime, AppError>>,
     stop_tx: &Sender<()>,
     join_handle: &mut Option<JoinHandle<()>>,
     timeout: Duration,
-) -> Result<RecordingRuntime, String> {
+) -> Result<RecordingRuntime, AppError> {
     match ready_rx.recv_timeout(timeout) {
         Ok(Ok(runtime)) => Ok(runtime),
         Ok(Err(err)) => {
@@ -354,14 +738,19 @@ fn await_worker_startup(
                 timeout_ms = timeout.as_millis(),
                 "microphone worker timed out while starting"
             );
-            Err("Timed out while starting microphone stream".to_string())
+            Err(AppError::new(
+                ErrorCode::Timeout,
+                "Timed out while starting microphone stream",
+            ))
         }
         Err(RecvTimeoutError::Disconnected) => {
             if let Some(handle) = join_handle.take() {
                 let _ = handle.join();
             }
             error!("microphone worker disconnected during startup");
-            Err("Microphone stream failed to initialize".to_string())
+            Err(AppError::from(
+                "Microphone stream failed to initialize".to_string(),
+            ))
         }
     }
 }
@@ -374,9 +763,10 @@ fn spawn_worker_reaper_after_startup_timeout(handle: JoinHandle<()>, timeout: Du
         "microphone worker startup timed out; scheduled background join reaper"
     );
     thread::spawn(move || {
-        if handle.join().is_err() {
+        if let Err(payload) = handle.join() {
             error!(
                 worker_thread_id = %worker_thread_id,
+                message = %crash_reporter::panic_message(payload.as_ref()),
                 "microphone worker panicked while reaping after startup timeout"
             );
         } else {
@@ -388,27 +778,84 @@ fn spawn_worker_reaper_after_startup_timeout(handle: JoinHandle<()>, timeout: Du
     });
 }
 
+#[allow(clippy::too_many_arguments)]
 fn recording_thread_main(
     preferred_device_id: Option<String>,
-    samples: Arc<Mutex<Vec<i16>>>,
+    capture_source: CaptureSource,
+    processing: InputProcessingConfig,
+    samples: Arc<Mutex<SampleSpool>>,
+    system_samples: Arc<Mutex<SampleSpool>>,
     audio_level_bits: Arc<AtomicU32>,
-    app_handle: AppHandle,
+    events: CaptureEvents,
     on_input_chunk: Option<AudioInputChunkCallback>,
-    ready_tx: Sender<Result<RecordingRuntime, String>>,
+    recovery_dir: Option<PathBuf>,
+    ready_tx: Sender<Result<RecordingRuntime, AppError>>,
     stop_rx: Receiver<()>,
 ) {
     debug!(
         preferred_device_id = ?preferred_device_id.as_deref(),
+        capture_source = capture_source.as_str(),
         "microphone worker thread started"
     );
+    let (stream_error_tx, stream_error_rx) = mpsc::channel::<String>();
+    let primary_role = if capture_source == CaptureSource::Loopback {
+        InputDeviceRole::Loopback
+    } else {
+        InputDeviceRole::Microphone
+    };
+    // The spectrum taps the processed microphone chunks so the bands match
+    // what is recorded; the analysis itself runs on this thread, not the
+    // audio callback.
+    let spectrum_tap = Arc::new(Mutex::new(SpectrumTap::new()));
+    // In mixed mode the system track is summed into the microphone chunks so
+    // realtime transcription hears both sides, as the final recording does.
+    let pending_system_mix = Arc::new(Mutex::new(Vec::new()));
+    let realtime_chunks = on_input_chunk.map(|callback| {
+        if capture_source == CaptureSource::Mixed {
+            mix_system_into_input_chunks(&pending_system_mix, callback)
+        } else {
+            callback
+        }
+    });
+    let forwards_chunks = realtime_chunks.is_some();
+    let on_input_chunk = tap_input_chunks(&spectrum_tap, realtime_chunks);
+    // System audio is a clean digital signal, so only the microphone is
+    // filtered or channel-selected.
+    let primary_processing = if primary_role == InputDeviceRole::Microphone {
+        processing
+    } else {
+        InputProcessingConfig::default()
+    };
     let startup_result = start_recording_worker(
         preferred_device_id.as_deref(),
+        primary_role,
+        &primary_processing,
         Arc::clone(&samples),
         Arc::clone(&audio_level_bits),
-        on_input_chunk,
-    );
+        Some(on_input_chunk),
+        stream_error_tx.clone(),
+    )
+    .and_then(|(stream, mut runtime)| {
+        if capture_source != CaptureSource::Mixed {
+            return Ok(((stream, None), runtime));
+        }
+
+        let (system_stream, system_runtime) = start_recording_worker(
+            None,
+            InputDeviceRole::Loopback,
+            &InputProcessingConfig::default(),
+            Arc::clone(&system_samples),
+            Arc::new(AtomicU32::new(0.0_f32.to_bits())),
+            forwards_chunks
+                .then(|| buffer_system_mix_chunks(&pending_system_mix, runtime.sample_rate_hz)),
+            stream_error_tx,
+        )?;
+        runtime.system_sample_rate_hz = Some(system_runtime.sample_rate_hz);
+        runtime.device_name = format!("{} + {}", runtime.device_name, system_runtime.device_name);
+        Ok(((stream, Some(system_stream)), runtime))
+    });
 
-    let (stream, runtime, stream_error_rx) = match startup_result {
+    let (streams, runtime) = match startup_result {
         Ok(started) => started,
         Err(err) => {
             error!(
@@ -421,28 +868,179 @@ fn recording_thread_main(
         }
     };
 
+    run_capture_worker(
+        streams,
+        runtime,
+        CaptureWorker {
+            samples,
+            system_samples,
+            audio_level_bits,
+            level_mirror: None,
+            spectrum_tap,
+            events,
+            recovery_dir,
+            ready_tx,
+            stop_rx,
+            stream_error_rx,
+        },
+    );
+}
+
+fn tap_input_chunks(
+    spectrum_tap: &Arc<Mutex<SpectrumTap>>,
+    on_input_chunk: Option<AudioInputChunkCallback>,
+) -> AudioInputChunkCallback {
+    let tap = Arc::clone(spectrum_tap);
+    Arc::new(move |chunk: AudioInputChunk| {
+        if let Ok(mut tap) = tap.try_lock() {
+            tap.extend(&chunk.pcm16_mono_samples, chunk.sample_rate_hz);
+        }
+        if let Some(callback) = on_input_chunk.as_ref() {
+            callback(chunk);
+        }
+    })
+}
+
+fn mix_system_into_input_chunks(
+    pending_system_mix: &Arc<Mutex<Vec<i16>>>,
+    on_input_chunk: AudioInputChunkCallback,
+) -> AudioInputChunkCallback {
+    let pending = Arc::clone(pending_system_mix);
+    Arc::new(move |mut chunk: AudioInputChunk| {
+        if let Ok(mut pending) = pending.lock() {
+            let mixed_len = pending.len().min(chunk.pcm16_mono_samples.len());
+            mix_pcm16_tracks(&mut chunk.pcm16_mono_samples, &pending[..mixed_len]);
+            pending.drain(..mixed_len);
+        }
+        on_input_chunk(chunk);
+    })
+}
+
+// Queues system audio at the microphone's rate for the next microphone
+// chunks to pick up.
+fn buffer_system_mix_chunks(
+    pending_system_mix: &Arc<Mutex<Vec<i16>>>,
+    microphone_sample_rate_hz: u32,
+) -> AudioInputChunkCallback {
+    let pending = Arc::clone(pending_system_mix);
+    let max_pending = microphone_sample_rate_hz as usize * MAX_PENDING_SYSTEM_MIX_MS / 1_000;
+    Arc::new(move |chunk: AudioInputChunk| {
+        let resampled = crate::transcription::realtime::resample_pcm16_linear(
+            &chunk.pcm16_mono_samples,
+            chunk.sample_rate_hz,
+            microphone_sample_rate_hz,
+        );
+        if let Ok(mut pending) = pending.lock() {
+            pending.extend_from_slice(&resampled);
+            let excess = pending.len().saturating_sub(max_pending);
+            pending.drain(..excess);
+        }
+    })
+}
+
+// Everything a capture worker needs once its streams are running, whether
+// they were opened for this recording or handed over warm.
+struct CaptureWorker {
+    samples: Arc<Mutex<SampleSpool>>,
+    system_samples: Arc<Mutex<SampleSpool>>,
+    audio_level_bits: Arc<AtomicU32>,
+    // A warm stream meters into its own level; this is the service's, which
+    // `get_audio_level` reads.
+    level_mirror: Option<Arc<AtomicU32>>,
+    spectrum_tap: Arc<Mutex<SpectrumTap>>,
+    events: CaptureEvents,
+    recovery_dir: Option<PathBuf>,
+    ready_tx: Sender<Result<RecordingRuntime, AppError>>,
+    stop_rx: Receiver<()>,
+    stream_error_rx: Receiver<String>,
+}
+
+fn run_capture_worker<S>(streams: S, runtime: RecordingRuntime, worker: CaptureWorker) {
+    let CaptureWorker {
+        samples,
+        system_samples,
+        audio_level_bits,
+        level_mirror,
+        spectrum_tap,
+        events,
+        recovery_dir,
+        ready_tx,
+        stop_rx,
+        stream_error_rx,
+    } = worker;
+
+    // Only the primary track is checkpointed, so a recovered mixed recording
+    // holds just the microphone.
+    if let Some(dir) = recovery_dir.as_deref() {
+        match RecoveryCheckpoint::create(
+            dir,
+            runtime.sample_rate_hz,
+            runtime.channels,
+            &runtime.device_name,
+        ) {
+            Ok(checkpoint) => {
+                if let Ok(mut spool) = samples.lock() {
+                    spool.attach_recovery(checkpoint);
+                }
+            }
+            Err(error) => warn!(%error, "recording will not be checkpointed for crash recovery"),
+        }
+    }
+
     let _ = ready_tx.send(Ok(runtime));
     let mut last_emitted_level: Option<f32> = None;
+    let mut last_emitted_spectrum: Option<AudioSpectrumEvent> = None;
+    let mut level_history = LevelHistory::new();
     let loop_exit = run_recording_loop(&stop_rx, &stream_error_rx, || {
-        let level =
-            quantize_audio_level_for_emit(f32::from_bits(audio_level_bits.load(Ordering::Relaxed)));
+        for spool in [&samples, &system_samples] {
+            if let Ok(mut spool) = spool.lock() {
+                spool.checkpoint_if_due();
+                spool.spill_if_needed();
+            }
+        }
+
+        let raw_level = audio_level_bits.load(Ordering::Relaxed);
+        if let Some(mirror) = level_mirror.as_ref() {
+            mirror.store(raw_level, Ordering::Relaxed);
+        }
+        let level = quantize_audio_level_for_emit(f32::from_bits(raw_level));
+        level_history.push(level);
+        let spectrum = AudioSpectrumEvent {
+            bands: spectrum_tap
+                .lock()
+                .map(|tap| tap.bands())
+                .unwrap_or_default(),
+            levels: level_history.to_vec(),
+        };
+        if last_emitted_spectrum.as_ref() != Some(&spectrum) {
+            let _ = emit_capture_event(&*events, AUDIO_SPECTRUM_EVENT, &spectrum);
+            last_emitted_spectrum = Some(spectrum);
+        }
+
         if last_emitted_level.is_some_and(|last| (last - level).abs() < f32::EPSILON) {
             return;
         }
         last_emitted_level = Some(level);
-        let _ = app_handle.emit(AUDIO_LEVEL_EVENT, level);
+        let _ = emit_capture_event(&*events, AUDIO_LEVEL_EVENT, level);
     });
 
-    drop(stream);
-    audio_level_bits.store(0.0_f32.to_bits(), Ordering::Relaxed);
-    if let Err(error) = app_handle.emit(AUDIO_LEVEL_EVENT, 0.0_f32) {
+    drop(streams);
+    for level_bits in std::iter::once(&audio_level_bits).chain(level_mirror.as_ref()) {
+        level_bits.store(0.0_f32.to_bits(), Ordering::Relaxed);
+    }
+    if let Err(error) = emit_capture_event(&*events, AUDIO_LEVEL_EVENT, 0.0_f32) {
         warn!(%error, "failed to emit audio level reset from worker thread");
     }
+    if let Err(error) =
+        emit_capture_event(&*events, AUDIO_SPECTRUM_EVENT, AudioSpectrumEvent::silent())
+    {
+        warn!(%error, "failed to emit audio spectrum reset from worker thread");
+    }
 
     if let RecordingLoopExit::StreamError(message) = loop_exit {
         error!(message = %message, "microphone worker exited due to stream error");
         let payload = AudioInputStreamErrorEvent { message };
-        if let Err(error) = app_handle.emit(AUDIO_INPUT_STREAM_ERROR_EVENT, payload) {
+        if let Err(error) = emit_capture_event(&*events, AUDIO_INPUT_STREAM_ERROR_EVENT, payload) {
             warn!(%error, "failed to emit audio stream error event");
         }
     } else {
@@ -450,27 +1048,43 @@ fn recording_thread_main(
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InputDeviceRole {
+    Microphone,
+    Loopback,
+}
+
 fn start_recording_worker(
     preferred_device_id: Option<&str>,
-    samples: Arc<Mutex<Vec<i16>>>,
+    role: InputDeviceRole,
+    processing: &InputProcessingConfig,
+    samples: Arc<Mutex<SampleSpool>>,
     audio_level_bits: Arc<AtomicU32>,
     on_input_chunk: Option<AudioInputChunkCallback>,
-) -> Result<(Stream, RecordingRuntime, Receiver<String>), String> {
+    stream_error_tx: Sender<String>,
+) -> Result<(Stream, RecordingRuntime), AppError> {
     let host = cpal::default_host();
     let default_input_device_name = host.default_input_device().and_then(|d| d.name().ok());
     let devices = enumerate_input_devices(&host)?;
     if devices.is_empty() {
-        return Err("No microphone input devices are available".to_string());
+        return Err(match role {
+            InputDeviceRole::Microphone => no_microphone_error(),
+            InputDeviceRole::Loopback => loopback_unavailable_error(),
+        });
     }
 
-    let selected_device = select_input_device(
-        devices,
-        preferred_device_id,
-        default_input_device_name.as_deref(),
-    )?;
+    let selected_device = match role {
+        InputDeviceRole::Microphone => select_input_device(
+            devices,
+            preferred_device_id,
+            default_input_device_name.as_deref(),
+        )?,
+        InputDeviceRole::Loopback => select_loopback_device(devices)?,
+    };
     info!(
         device_id = %selected_device.id,
         device_name = %selected_device.name,
+        role = ?role,
         "starting recording worker for selected device"
     );
 
@@ -488,20 +1102,34 @@ fn start_recording_worker(
     let sample_format = supported_config.sample_format();
     let input_channels = usize::from(stream_config.channels);
     let sample_rate_hz = stream_config.sample_rate.0;
+    let channel_selection = processing.channel_selection_for(&selected_device.id);
+    if let ChannelSelection::Channel(channel) = channel_selection {
+        if channel >= input_channels {
+            warn!(
+                channel = channel + 1,
+                input_channels, "selected input channel is not available; downmixing instead"
+            );
+        }
+    }
+    let input_stage = InputStage {
+        channel_selection,
+        noise_suppressor: processing
+            .noise_suppression
+            .then(|| NoiseSuppressor::new(sample_rate_hz)),
+    };
 
     if let Ok(mut sample_buffer) = samples.lock() {
         sample_buffer.clear();
         sample_buffer.reserve(usize::try_from(sample_rate_hz).unwrap_or(48_000) * 10);
     }
 
-    let (stream_error_tx, stream_error_rx) = mpsc::channel::<String>();
-
     let stream = build_input_stream(
         &selected_device.device,
         &stream_config,
         sample_format,
         input_channels,
         sample_rate_hz,
+        input_stage,
         samples,
         audio_level_bits,
         on_input_chunk,
@@ -521,11 +1149,11 @@ fn start_recording_worker(
         stream,
         RecordingRuntime {
             sample_rate_hz,
+            system_sample_rate_hz: None,
             channels: 1,
             device_id: selected_device.id,
             device_name: selected_device.name,
         },
-        stream_error_rx,
     ))
 }
 
@@ -628,7 +1256,7 @@ fn select_input_device(
     mut devices: Vec<EnumeratedInputDevice>,
     preferred_device_id: Option<&str>,
     default_device_name: Option<&str>,
-) -> Result<EnumeratedInputDevice, String> {
+) -> Result<EnumeratedInputDevice, AppError> {
     let candidates = devices
         .iter()
         .map(|device| InputDeviceSelectionCandidate {
@@ -649,11 +1277,107 @@ fn select_input_device(
     Ok(selected)
 }
 
+fn select_loopback_device(
+    mut devices: Vec<EnumeratedInputDevice>,
+) -> Result<EnumeratedInputDevice, AppError> {
+    let index = devices
+        .iter()
+        .position(|device| is_loopback_device_name(&device.name))
+        .ok_or_else(loopback_unavailable_error)?;
+    let selected = devices.swap_remove(index);
+    debug!(
+        device_id = %selected.id,
+        device_name = %selected.name,
+        "selected loopback device"
+    );
+    Ok(selected)
+}
+
+fn is_loopback_device_name(name: &str) -> bool {
+    let normalized = name.to_lowercase();
+    LOOPBACK_DEVICE_NAME_HINTS
+        .iter()
+        .any(|hint| normalized.contains(hint))
+}
+
+fn loopback_unavailable_message() -> String {
+    "No loopback device is available. System audio can only be recorded through a loopback driver such as BlackHole, with the system output routed to it".to_string()
+}
+
+fn loopback_unavailable_error() -> AppError {
+    AppError::new(ErrorCode::NotFound, loopback_unavailable_message())
+}
+
+fn no_microphone_error() -> AppError {
+    AppError::new(
+        ErrorCode::NotFound,
+        "No microphone input devices are available",
+    )
+}
+
+fn build_capture_sources(devices: &[InputDeviceSelectionCandidate]) -> Vec<CaptureSourceInfo> {
+    let microphones = devices
+        .iter()
+        .filter(|device| !is_loopback_device_name(&device.name))
+        .cloned()
+        .collect::<Vec<_>>();
+    let microphone =
+        resolve_default_input_device_index(&microphones, None).map(|index| &microphones[index]);
+    let system = devices
+        .iter()
+        .find(|device| is_loopback_device_name(&device.name));
+
+    let microphone_reason = "No microphone input devices are available".to_string();
+    vec![
+        CaptureSourceInfo {
+            source: CaptureSource::Microphone,
+            label: "Microphone".to_string(),
+            available: microphone.is_some(),
+            device_id: microphone.map(|device| device.id.clone()),
+            device_name: microphone.map(|device| device.name.clone()),
+            unavailable_reason: microphone.is_none().then(|| microphone_reason.clone()),
+        },
+        CaptureSourceInfo {
+            source: CaptureSource::Loopback,
+            label: "Loopback device".to_string(),
+            available: system.is_some(),
+            device_id: system.map(|device| device.id.clone()),
+            device_name: system.map(|device| device.name.clone()),
+            unavailable_reason: system.is_none().then(loopback_unavailable_message),
+        },
+        CaptureSourceInfo {
+            source: CaptureSource::Mixed,
+            label: "Microphone + loopback device".to_string(),
+            available: microphone.is_some() && system.is_some(),
+            device_id: None,
+            device_name: None,
+            unavailable_reason: if microphone.is_none() {
+                Some(microphone_reason)
+            } else if system.is_none() {
+                Some(loopback_unavailable_message())
+            } else {
+                None
+            },
+        },
+    ]
+}
+
+fn mix_pcm16_tracks(primary: &mut Vec<i16>, secondary: &[i16]) {
+    if secondary.len() > primary.len() {
+        primary.resize(secondary.len(), 0);
+    }
+
+    for (target, &sample) in primary.iter_mut().zip(secondary) {
+        *target = (i32::from(*target) + i32::from(sample))
+            .clamp(i32::from(i16::MIN), i32::from(i16::MAX)) as i16;
+    }
+}
+
 fn select_input_device_index(
     devices: &[InputDeviceSelectionCandidate],
     preferred_device_id: Option<&str>,
     default_device_name: Option<&str>,
-) -> Result<usize, String> {
+) -> Result<usize, AppError> {
     if let Some(device_id) = preferred_device_id {
         if let Some(index) = devices.iter().position(|device| device.id == device_id) {
             debug!(device_id, "selected preferred input device by id");
@@ -674,7 +1398,7 @@ fn select_input_device_index(
         }
 
         let default_index = resolve_default_input_device_index(devices, default_device_name)
-            .ok_or_else(|| "No microphone input devices are available".to_string())?;
+            .ok_or_else(no_microphone_error)?;
         let fallback_device = &devices[default_index];
         warn!(
             preferred_device_id = device_id,
@@ -685,8 +1409,7 @@ fn select_input_device_index(
         return Ok(default_index);
     }
 
-    resolve_default_input_device_index(devices, default_device_name)
-        .ok_or_else(|| "No microphone input devices are available".to_string())
+    resolve_default_input_device_index(devices, default_device_name).ok_or_else(no_microphone_error)
 }
 
 fn resolve_default_input_device_index(
@@ -1073,7 +1796,8 @@ fn build_input_stream(
     sample_format: SampleFormat,
     input_channels: usize,
     sample_rate_hz: u32,
-    samples: Arc<Mutex<Vec<i16>>>,
+    mut input_stage: InputStage,
+    samples: Arc<Mutex<SampleSpool>>,
     audio_level_bits: Arc<AtomicU32>,
     on_input_chunk: Option<AudioInputChunkCallback>,
     stream_error_tx: Sender<String>,
@@ -1095,6 +1819,7 @@ fn build_input_stream(
                             &samples,
                             &level_bits,
                             sample_rate_hz,
+                            &mut input_stage,
                             on_input_chunk.as_ref(),
                         );
                     },
@@ -1121,6 +1846,7 @@ fn build_input_stream(
                             &samples,
                             &level_bits,
                             sample_rate_hz,
+                            &mut input_stage,
                             on_input_chunk.as_ref(),
                         );
                     },
@@ -1147,6 +1873,7 @@ fn build_input_stream(
                             &samples,
                             &level_bits,
                             sample_rate_hz,
+                            &mut input_stage,
                             on_input_chunk.as_ref(),
                         );
                     },
@@ -1163,13 +1890,43 @@ fn build_input_stream(
     }
 }
 
+// Per-stream state that turns one multichannel frame into a mono sample.
+struct InputStage {
+    channel_selection: ChannelSelection,
+    noise_suppressor: Option<NoiseSuppressor>,
+}
+
+impl InputStage {
+    fn mono_sample<T, F>(&mut self, frame: &[T], to_f32: &F) -> f32
+    where
+        T: Copy,
+        F: Fn(T) -> f32,
+    {
+        let selected = match self.channel_selection {
+            ChannelSelection::Channel(channel) => frame.get(channel).copied().map(to_f32),
+            ChannelSelection::Downmix => None,
+        };
+        let sample = selected.unwrap_or_else(|| {
+            frame.iter().map(|sample| to_f32(*sample)).sum::<f32>() / frame.len() as f32
+        });
+        match self.noise_suppressor.as_mut() {
+            Some(suppressor) => suppressor.process(sample),
+            None => sample,
+        }
+    }
+}
+
+// Channel selection and noise suppression run before samples are spooled or
+// streamed, so the buffered WAV and realtime chunks carry the same audio.
+#[allow(clippy::too_many_arguments)]
 fn process_input_frames<T, F>(
     data: &[T],
     channels: usize,
     to_f32: F,
-    samples: &Arc<Mutex<Vec<i16>>>,
+    samples: &Arc<Mutex<SampleSpool>>,
     audio_level_bits: &Arc<AtomicU32>,
     sample_rate_hz: u32,
+    input_stage: &mut InputStage,
     on_input_chunk: Option<&AudioInputChunkCallback>,
 ) where
     T: Copy,
@@ -1193,12 +1950,7 @@ fn process_input_frames<T, F>(
         sample_buffer.reserve(data.len() / channels);
 
         for frame in data.chunks_exact(channels) {
-            let mut mixed = 0.0_f32;
-            for &sample in frame {
-                mixed += to_f32(sample);
-            }
-
-            let normalized = (mixed / channels as f32).clamp(-1.0, 1.0);
+            let normalized = input_stage.mono_sample(frame, &to_f32).clamp(-1.0, 1.0);
             let mono_pcm16 = float_to_pcm16(normalized);
             sample_buffer.push(mono_pcm16);
             if let Some(chunk) = mono_chunk.as_mut() {
@@ -1234,6 +1986,49 @@ fn process_input_frames<T, F>(
     }
 }
 
+// Runs the audio callback path without a live stream, for the benchmarks.
+// The spool is emptied after every buffer so it never spills to disk.
+#[cfg(feature = "bench")]
+pub struct InputFrameBench {
+    channels: usize,
+    sample_rate_hz: u32,
+    input_stage: InputStage,
+    samples: Arc<Mutex<SampleSpool>>,
+    audio_level_bits: Arc<AtomicU32>,
+}
+
+#[cfg(feature = "bench")]
+impl InputFrameBench {
+    pub fn new(channels: usize, sample_rate_hz: u32, noise_suppression: bool) -> Self {
+        Self {
+            channels,
+            sample_rate_hz,
+            input_stage: InputStage {
+                channel_selection: ChannelSelection::Downmix,
+                noise_suppressor: noise_suppression.then(|| NoiseSuppressor::new(sample_rate_hz)),
+            },
+            samples: Arc::new(Mutex::new(SampleSpool::new(AudioSpoolConfig::default()))),
+            audio_level_bits: Arc::new(AtomicU32::new(0.0_f32.to_bits())),
+        }
+    }
+
+    pub fn process(&mut self, data: &[f32], on_input_chunk: Option<&AudioInputChunkCallback>) {
+        process_input_frames(
+            data,
+            self.channels,
+            |sample| sample,
+            &self.samples,
+            &self.audio_level_bits,
+            self.sample_rate_hz,
+            &mut self.input_stage,
+            on_input_chunk,
+        );
+        if let Ok(mut samples) = self.samples.lock() {
+            samples.clear();
+        }
+    }
+}
+
 fn quantize_audio_level_for_emit(level: f32) -> f32 {
     let clamped = level.clamp(0.0, 1.0);
     (clamped * 100.0).round() / 100.0
@@ -1250,42 +2045,13 @@ fn float_to_pcm16(sample: f32) -> i16 {
     }
 }
 
-fn pcm16_to_wav_bytes(
+pub(crate) fn pcm16_to_wav_bytes(
     samples: &[i16],
     sample_rate_hz: u32,
     channels: u16,
 ) -> Result<Vec<u8>, String> {
-    let bytes_per_sample = 2u16;
-    let block_align = channels
-        .checked_mul(bytes_per_sample)
-        .ok_or_else(|| "WAV header block alignment overflow".to_string())?;
-    let byte_rate = sample_rate_hz
-        .checked_mul(u32::from(block_align))
-        .ok_or_else(|| "WAV header byte rate overflow".to_string())?;
-
-    let sample_count_u32 = u32::try_from(samples.len())
-        .map_err(|_| "Audio clip is too long to encode as standard WAV".to_string())?;
-    let data_size = sample_count_u32
-        .checked_mul(u32::from(bytes_per_sample))
-        .ok_or_else(|| "WAV data size overflow".to_string())?;
-    let riff_chunk_size = 36u32
-        .checked_add(data_size)
-        .ok_or_else(|| "WAV RIFF chunk overflow".to_string())?;
-
-    let mut wav_bytes = Vec::with_capacity(44 + usize::try_from(data_size).unwrap_or(0));
-    wav_bytes.extend_from_slice(b"RIFF");
-    wav_bytes.extend_from_slice(&riff_chunk_size.to_le_bytes());
-    wav_bytes.extend_from_slice(b"WAVE");
-    wav_bytes.extend_from_slice(b"fmt ");
-    wav_bytes.extend_from_slice(&16u32.to_le_bytes());
-    wav_bytes.extend_from_slice(&1u16.to_le_bytes());
-    wav_bytes.extend_from_slice(&channels.to_le_bytes());
-    wav_bytes.extend_from_slice(&sample_rate_hz.to_le_bytes());
-    wav_bytes.extend_from_slice(&byte_rate.to_le_bytes());
-    wav_bytes.extend_from_slice(&block_align.to_le_bytes());
-    wav_bytes.extend_from_slice(&16u16.to_le_bytes());
-    wav_bytes.extend_from_slice(b"data");
-    wav_bytes.extend_from_slice(&data_size.to_le_bytes());
+    let mut wav_bytes = Vec::with_capacity(WAV_HEADER_LEN + samples.len() * 2);
+    wav_bytes.extend(pcm16_wav_header(samples.len(), sample_rate_hz, channels)?);
 
     #[cfg(target_endian = "little")]
     {
@@ -1310,22 +2076,65 @@ fn pcm16_to_wav_bytes(
     Ok(wav_bytes)
 }
 
+pub(crate) fn pcm16_wav_header(
+    sample_count: usize,
+    sample_rate_hz: u32,
+    channels: u16,
+) -> Result<Vec<u8>, String> {
+    let bytes_per_sample = 2u16;
+    let block_align = channels
+        .checked_mul(bytes_per_sample)
+        .ok_or_else(|| "WAV header block alignment overflow".to_string())?;
+    let byte_rate = sample_rate_hz
+        .checked_mul(u32::from(block_align))
+        .ok_or_else(|| "WAV header byte rate overflow".to_string())?;
+
+    let sample_count_u32 = u32::try_from(sample_count)
+        .map_err(|_| "Audio clip is too long to encode as standard WAV".to_string())?;
+    let data_size = sample_count_u32
+        .checked_mul(u32::from(bytes_per_sample))
+        .ok_or_else(|| "WAV data size overflow".to_string())?;
+    let riff_chunk_size = 36u32
+        .checked_add(data_size)
+        .ok_or_else(|| "WAV RIFF chunk overflow".to_string())?;
+
+    let mut header = Vec::with_capacity(WAV_HEADER_LEN);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&riff_chunk_size.to_le_bytes());
+    header.extend_from_slice(b"WAVE");
+    header.extend_from_slice(b"fmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes());
+    header.extend_from_slice(&channels.to_le_bytes());
+    header.extend_from_slice(&sample_rate_hz.to_le_bytes());
+    header.extend_from_slice(&byte_rate.to_le_bytes());
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&16u16.to_le_bytes());
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&data_size.to_le_bytes());
+    Ok(header)
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
         collections::HashMap,
-        sync::mpsc,
+        sync::{mpsc, Arc, Mutex},
         thread,
         time::{Duration, Instant},
     };
 
     use super::{
-        await_worker_startup, build_macos_identity_lookup_by_name, build_microphone_device_id,
-        ensure_unique_device_id, float_to_pcm16, legacy_device_slug, pcm16_to_wav_bytes,
-        quantize_audio_level_for_emit, run_recording_loop, select_input_device_index,
-        slugify_device_name, take_macos_identity_by_device_name, InputDeviceSelectionCandidate,
-        MacosCoreAudioDeviceIdentity, RecordingLoopExit, RecordingRuntime,
+        await_worker_startup, buffer_system_mix_chunks, build_capture_sources,
+        build_macos_identity_lookup_by_name, build_microphone_device_id, ensure_unique_device_id,
+        float_to_pcm16, is_loopback_device_name, legacy_device_slug, mix_pcm16_tracks,
+        mix_system_into_input_chunks, pcm16_to_wav_bytes, quantize_audio_level_for_emit,
+        run_recording_loop, select_input_device_index, slugify_device_name,
+        take_macos_identity_by_device_name, AudioInputChunk, CaptureSource, ChannelSelection,
+        InputDeviceSelectionCandidate, InputStage, MacosCoreAudioDeviceIdentity, RecordingLoopExit,
+        RecordingRuntime,
     };
+    use crate::app_error::AppError;
 
     #[test]
     fn slugify_device_name_normalizes_ascii() {
@@ -1591,7 +2400,7 @@ mod tests {
 
     #[test]
     fn startup_timeout_returns_promptly_without_waiting_for_worker_join() {
-        let (ready_tx, ready_rx) = mpsc::channel::<Result<RecordingRuntime, String>>();
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<RecordingRuntime, AppError>>();
         let (stop_tx, _stop_rx) = mpsc::channel::<()>();
         let mut join_handle = Some(thread::spawn(move || {
             let _keep_ready_sender_alive = ready_tx;
@@ -1607,9 +2416,7 @@ mod tests {
         );
 
         let elapsed = started_at.elapsed();
-        assert!(
-            matches!(result, Err(message) if message == "Timed out while starting microphone stream")
-        );
+        assert!(matches!(result, Err(error) if error.code == crate::app_error::ErrorCode::Timeout));
         assert!(
             elapsed < Duration::from_millis(250),
             "startup timeout should return quickly without waiting for worker thread; elapsed: {elapsed:?}"
@@ -1619,4 +2426,155 @@ mod tests {
             "worker handle should be detached on timeout"
         );
     }
+
+    #[test]
+    fn capture_source_parses_settings_values_with_microphone_fallback() {
+        assert_eq!(
+            CaptureSource::from_settings_value(" Loopback "),
+            CaptureSource::Loopback
+        );
+        assert_eq!(
+            CaptureSource::from_settings_value("system"),
+            CaptureSource::Loopback
+        );
+        assert_eq!(
+            CaptureSource::from_settings_value("mixed"),
+            CaptureSource::Mixed
+        );
+        assert_eq!(
+            CaptureSource::from_settings_value("unknown"),
+            CaptureSource::Microphone
+        );
+    }
+
+    #[test]
+    fn channel_selection_picks_configured_channel_and_downmixes_otherwise() {
+        assert_eq!(
+            ChannelSelection::from_settings_value("Right"),
+            ChannelSelection::Channel(1)
+        );
+        assert_eq!(
+            ChannelSelection::from_settings_value("channel_3"),
+            ChannelSelection::Channel(2)
+        );
+        assert_eq!(
+            ChannelSelection::from_settings_value("channel_0"),
+            ChannelSelection::Downmix
+        );
+
+        let frame = [0.2_f32, 0.6, -0.4];
+        let stage = |channel_selection| InputStage {
+            channel_selection,
+            noise_suppressor: None,
+        };
+        let identity = |sample: f32| sample;
+        assert_eq!(
+            stage(ChannelSelection::Channel(1)).mono_sample(&frame, &identity),
+            0.6
+        );
+        assert!(
+            (stage(ChannelSelection::Downmix).mono_sample(&frame, &identity) - 0.4 / 3.0).abs()
+                < f32::EPSILON
+        );
+        // A channel the device does not have falls back to the downmix.
+        assert_eq!(
+            stage(ChannelSelection::Channel(5)).mono_sample(&frame, &identity),
+            stage(ChannelSelection::Downmix).mono_sample(&frame, &identity)
+        );
+    }
+
+    #[test]
+    fn detects_common_loopback_device_names() {
+        assert!(is_loopback_device_name("BlackHole 2ch"));
+        assert!(is_loopback_device_name("Loopback Audio"));
+        assert!(!is_loopback_device_name("MacBook Pro Microphone"));
+    }
+
+    #[test]
+    fn capture_sources_report_loopback_unavailable_without_loopback_device() {
+        let sources = build_capture_sources(&[InputDeviceSelectionCandidate {
+            id: "mic-1".to_string(),
+            name: "MacBook Pro Microphone".to_string(),
+            is_default: true,
+        }]);
+
+        assert_eq!(sources.len(), 3);
+        assert!(sources[0].available);
+        assert_eq!(sources[0].device_id.as_deref(), Some("mic-1"));
+        assert!(!sources[1].available);
+        assert!(sources[1].unavailable_reason.is_some());
+        assert!(!sources[2].available);
+    }
+
+    #[test]
+    fn capture_sources_pair_default_microphone_with_loopback_device() {
+        let sources = build_capture_sources(&[
+            InputDeviceSelectionCandidate {
+                id: "loopback".to_string(),
+                name: "BlackHole 2ch".to_string(),
+                is_default: true,
+            },
+            InputDeviceSelectionCandidate {
+                id: "mic-1".to_string(),
+                name: "USB Microphone".to_string(),
+                is_default: false,
+            },
+        ]);
+
+        assert_eq!(sources[0].device_id.as_deref(), Some("mic-1"));
+        assert_eq!(sources[1].device_id.as_deref(), Some("loopback"));
+        assert!(sources[2].available);
+        assert_eq!(sources[2].unavailable_reason, None);
+    }
+
+    #[test]
+    fn mix_pcm16_tracks_sums_with_clipping_and_extends_shorter_primary() {
+        let mut primary = vec![100_i16, i16::MAX, -5];
+        mix_pcm16_tracks(&mut primary, &[50, 10, -5, 7]);
+
+        assert_eq!(primary, vec![150, i16::MAX, -10, 7]);
+    }
+
+    #[test]
+    fn mixed_capture_forwards_system_audio_in_realtime_chunks() {
+        let pending = Arc::new(Mutex::new(Vec::new()));
+        let forwarded = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&forwarded);
+        let on_microphone_chunk = mix_system_into_input_chunks(
+            &pending,
+            Arc::new(move |chunk: AudioInputChunk| {
+                sink.lock()
+                    .expect("forwarded chunks lock")
+                    .push(chunk.pcm16_mono_samples);
+            }),
+        );
+        let on_system_chunk = buffer_system_mix_chunks(&pending, 10);
+
+        on_system_chunk(AudioInputChunk {
+            pcm16_mono_samples: vec![1, 2, 3],
+            sample_rate_hz: 10,
+        });
+        on_microphone_chunk(AudioInputChunk {
+            pcm16_mono_samples: vec![10, 10],
+            sample_rate_hz: 10,
+        });
+        on_microphone_chunk(AudioInputChunk {
+            pcm16_mono_samples: vec![10, 10],
+            sample_rate_hz: 10,
+        });
+        assert_eq!(
+            *forwarded.lock().expect("forwarded chunks lock"),
+            vec![vec![11, 12], vec![13, 10]]
+        );
+
+        // A stalled microphone keeps only the last second of system audio.
+        on_system_chunk(AudioInputChunk {
+            pcm16_mono_samples: (0..15).collect(),
+            sample_rate_hz: 10,
+        });
+        assert_eq!(
+            *pending.lock().expect("pending mix lock"),
+            (5..15).collect::<Vec<i16>>()
+        );
+    }
 }
@@ -0,0 +1,176 @@
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+const HIGH_PASS_CUTOFF_HZ: f32 = 80.0;
+const ENVELOPE_ATTACK_MS: f32 = 2.0;
+const ENVELOPE_RELEASE_MS: f32 = 60.0;
+const GAIN_ATTACK_MS: f32 = 5.0;
+const GAIN_RELEASE_MS: f32 = 80.0;
+// The floor estimate follows quiet passages down immediately but creeps up
+// slowly, so sustained speech is never mistaken for background noise.
+const NOISE_FLOOR_RISE_DB_PER_SEC: f32 = 6.0;
+const INITIAL_NOISE_FLOOR: f32 = 0.002;
+const MIN_NOISE_FLOOR: f32 = 0.000_1;
+// Signal must be this far above the floor (~9.5 dB) for the gate to open.
+const GATE_OPEN_RATIO: f32 = 3.0;
+// Closed-gate attenuation (-18 dB); fully muting sounds unnatural and clips
+// soft word endings.
+const GATE_CLOSED_GAIN: f32 = 0.125;
+
+// High-pass filter followed by an adaptive noise gate, applied to the mono
+// mix of each capture callback. Per-sample and allocation-free so it is safe
+// to run on the audio thread.
+#[derive(Debug, Clone)]
+pub(super) struct NoiseSuppressor {
+    high_pass: Biquad,
+    envelope: f32,
+    noise_floor: f32,
+    gain: f32,
+    envelope_attack: f32,
+    envelope_release: f32,
+    gain_attack: f32,
+    gain_release: f32,
+    noise_floor_rise: f32,
+}
+
+impl NoiseSuppressor {
+    pub(super) fn new(sample_rate_hz: u32) -> Self {
+        let sample_rate_hz = sample_rate_hz.max(1) as f32;
+        Self {
+            high_pass: Biquad::high_pass(HIGH_PASS_CUTOFF_HZ, sample_rate_hz),
+            envelope: INITIAL_NOISE_FLOOR,
+            noise_floor: INITIAL_NOISE_FLOOR,
+            gain: 1.0,
+            envelope_attack: smoothing_coefficient(ENVELOPE_ATTACK_MS, sample_rate_hz),
+            envelope_release: smoothing_coefficient(ENVELOPE_RELEASE_MS, sample_rate_hz),
+            gain_attack: smoothing_coefficient(GAIN_ATTACK_MS, sample_rate_hz),
+            gain_release: smoothing_coefficient(GAIN_RELEASE_MS, sample_rate_hz),
+            noise_floor_rise: 10.0_f32.powf(NOISE_FLOOR_RISE_DB_PER_SEC / 20.0 / sample_rate_hz),
+        }
+    }
+
+    pub(super) fn process(&mut self, sample: f32) -> f32 {
+        let filtered = self.high_pass.process(sample);
+
+        let magnitude = filtered.abs();
+        let envelope_coefficient = if magnitude > self.envelope {
+            self.envelope_attack
+        } else {
+            self.envelope_release
+        };
+        self.envelope += (magnitude - self.envelope) * envelope_coefficient;
+
+        self.noise_floor = if self.envelope < self.noise_floor {
+            self.envelope.max(MIN_NOISE_FLOOR)
+        } else {
+            self.noise_floor * self.noise_floor_rise
+        };
+
+        let target_gain = if self.envelope > self.noise_floor * GATE_OPEN_RATIO {
+            1.0
+        } else {
+            GATE_CLOSED_GAIN
+        };
+        let gain_coefficient = if target_gain > self.gain {
+            self.gain_attack
+        } else {
+            self.gain_release
+        };
+        self.gain += (target_gain - self.gain) * gain_coefficient;
+
+        filtered * self.gain
+    }
+}
+
+fn smoothing_coefficient(time_ms: f32, sample_rate_hz: f32) -> f32 {
+    1.0 - (-1.0 / (time_ms / 1000.0 * sample_rate_hz)).exp()
+}
+
+// Direct form I biquad using the RBJ cookbook coefficients.
+#[derive(Debug, Clone)]
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl Biquad {
+    fn high_pass(cutoff_hz: f32, sample_rate_hz: f32) -> Self {
+        let cutoff_hz = cutoff_hz.min(sample_rate_hz * 0.45);
+        let omega = 2.0 * PI * cutoff_hz / sample_rate_hz;
+        let alpha = omega.sin() / (2.0 * FRAC_1_SQRT_2);
+        let cos_omega = omega.cos();
+        let a0 = 1.0 + alpha;
+        Self {
+            b0: (1.0 + cos_omega) / 2.0 / a0,
+            b1: -(1.0 + cos_omega) / a0,
+            b2: (1.0 + cos_omega) / 2.0 / a0,
+            a1: -2.0 * cos_omega / a0,
+            a2: (1.0 - alpha) / a0,
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+
+    fn process(&mut self, sample: f32) -> f32 {
+        let output = self.b0 * sample + self.b1 * self.x1 + self.b2 * self.x2
+            - self.a1 * self.y1
+            - self.a2 * self.y2;
+        self.x2 = self.x1;
+        self.x1 = sample;
+        self.y2 = self.y1;
+        self.y1 = output;
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use super::NoiseSuppressor;
+
+    const SAMPLE_RATE_HZ: u32 = 16_000;
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn removes_rumble_and_attenuates_background_noise_but_keeps_speech() {
+        let sine = |frequency_hz: f32, amplitude: f32, len: usize| {
+            (0..len)
+                .map(move |index| {
+                    (TAU * frequency_hz * index as f32 / SAMPLE_RATE_HZ as f32).sin() * amplitude
+                })
+                .collect::<Vec<_>>()
+        };
+        let one_second = SAMPLE_RATE_HZ as usize;
+
+        let mut suppressor = NoiseSuppressor::new(SAMPLE_RATE_HZ);
+        let rumble = sine(20.0, 0.5, one_second)
+            .into_iter()
+            .map(|sample| suppressor.process(sample))
+            .collect::<Vec<_>>();
+        assert!(rms(&rumble[one_second / 2..]) < 0.5 * 0.1);
+
+        let mut suppressor = NoiseSuppressor::new(SAMPLE_RATE_HZ);
+        let hiss = sine(3_000.0, 0.01, one_second * 2)
+            .into_iter()
+            .map(|sample| suppressor.process(sample))
+            .collect::<Vec<_>>();
+        let speech = sine(400.0, 0.3, one_second / 2)
+            .into_iter()
+            .map(|sample| suppressor.process(sample))
+            .collect::<Vec<_>>();
+        assert!(rms(&hiss[one_second * 3 / 2..]) < rms(&sine(3_000.0, 0.01, one_second)) * 0.3);
+        assert!(rms(&speech[one_second / 4..]) > 0.3 * 0.7 * 0.9);
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use super::pcm16_to_wav_bytes;
+
+const RECOVERY_DIR_NAME: &str = "recovery";
+const PCM_EXTENSION: &str = "pcm";
+const METADATA_EXTENSION: &str = "json";
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(2);
+
+pub fn recovery_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(RECOVERY_DIR_NAME)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct RecoveryMetadata {
+    sample_rate_hz: u32,
+    channels: u16,
+    device_name: String,
+    started_at_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredRecording {
+    pub id: String,
+    pub sample_rate_hz: u32,
+    pub channels: u16,
+    pub device_name: String,
+    pub started_at_ms: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RecoveryFiles {
+    pcm_path: PathBuf,
+    metadata_path: PathBuf,
+}
+
+impl RecoveryFiles {
+    fn for_id(dir: &Path, id: &str) -> Self {
+        Self {
+            pcm_path: dir.join(format!("{id}.{PCM_EXTENSION}")),
+            metadata_path: dir.join(format!("{id}.{METADATA_EXTENSION}")),
+        }
+    }
+
+    pub fn remove(&self) {
+        for path in [&self.pcm_path, &self.metadata_path] {
+            if let Err(error) = fs::remove_file(path) {
+                if error.kind() != std::io::ErrorKind::NotFound {
+                    warn!(path = %path.display(), %error, "failed to remove recovery file");
+                }
+            }
+        }
+    }
+}
+
+// Raw PCM appended every few seconds while recording, next to a metadata file
+// describing the format. Both are removed once the recording is handed off, so
+// anything left in the directory at launch was orphaned by a crash.
+pub(crate) struct RecoveryCheckpoint {
+    files: RecoveryFiles,
+    writer: BufWriter<File>,
+    last_checkpoint: Instant,
+}
+
+impl RecoveryCheckpoint {
+    pub fn create(
+        dir: &Path,
+        sample_rate_hz: u32,
+        channels: u16,
+        device_name: &str,
+    ) -> Result<Self, String> {
+        fs::create_dir_all(dir).map_err(recovery_io_error)?;
+        let id = format!("recording-{}", uuid::Uuid::new_v4());
+        let files = RecoveryFiles::for_id(dir, &id);
+        let metadata = RecoveryMetadata {
+            sample_rate_hz,
+            channels,
+            device_name: device_name.to_string(),
+            started_at_ms: now_ms(),
+        };
+        let metadata_json = serde_json::to_vec(&metadata)
+            .map_err(|error| format!("Failed to encode recovery metadata: {error}"))?;
+        fs::write(&files.metadata_path, metadata_json).map_err(recovery_io_error)?;
+        let file = OpenOptions::new()
+            .create_new(true)
+            .append(true)
+            .open(&files.pcm_path)
+            .map_err(|error| {
+                files.remove();
+                recovery_io_error(error)
+            })?;
+
+        debug!(path = %files.pcm_path.display(), "created recording recovery checkpoint");
+        Ok(Self {
+            files,
+            writer: BufWriter::new(file),
+            last_checkpoint: Instant::now(),
+        })
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL
+    }
+
+    pub fn append(&mut self, samples: &[i16]) -> Result<(), String> {
+        self.last_checkpoint = Instant::now();
+        for sample in samples {
+            self.writer
+                .write_all(&sample.to_le_bytes())
+                .map_err(recovery_io_error)?;
+        }
+        self.writer.flush().map_err(recovery_io_error)?;
+        self.writer.get_ref().sync_data().map_err(recovery_io_error)
+    }
+
+    pub fn files(&self) -> &RecoveryFiles {
+        &self.files
+    }
+}
+
+pub fn list_recovered_recordings(dir: &Path) -> Vec<RecoveredRecording> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(error) => {
+            warn!(path = %dir.display(), %error, "failed to read recovery directory");
+            return Vec::new();
+        }
+    };
+
+    let mut recordings = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some(PCM_EXTENSION))
+        .filter_map(|path| {
+            let id = path.file_stem()?.to_str()?.to_string();
+            match read_recovered_recording(dir, &id) {
+                Ok(recording) => Some(recording),
+                Err(error) => {
+                    warn!(id = %id, %error, "skipping unreadable recovered recording");
+                    None
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    recordings.sort_by_key(|recording| recording.started_at_ms);
+    recordings
+}
+
+pub fn load_recovered_recording_wav(dir: &Path, id: &str) -> Result<Vec<u8>, String> {
+    let files = recovery_files_for_request(dir, id)?;
+    let metadata = read_metadata(&files)?;
+    let bytes = fs::read(&files.pcm_path).map_err(recovery_io_error)?;
+    let samples = bytes
+        .chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+        .collect::<Vec<_>>();
+    info!(
+        id,
+        sample_count = samples.len(),
+        "loaded recovered recording"
+    );
+    pcm16_to_wav_bytes(&samples, metadata.sample_rate_hz, metadata.channels)
+}
+
+pub fn discard_recovered_recording(dir: &Path, id: &str) -> Result<bool, String> {
+    let files = recovery_files_for_request(dir, id)?;
+    let existed = files.pcm_path.exists() || files.metadata_path.exists();
+    files.remove();
+    info!(id, existed, "discarded recovered recording");
+    Ok(existed)
+}
+
+fn read_recovered_recording(dir: &Path, id: &str) -> Result<RecoveredRecording, String> {
+    let files = RecoveryFiles::for_id(dir, id);
+    let metadata = read_metadata(&files)?;
+    let byte_len = fs::metadata(&files.pcm_path)
+        .map_err(recovery_io_error)?
+        .len();
+    let frames = byte_len / 2 / u64::from(metadata.channels.max(1));
+    Ok(RecoveredRecording {
+        id: id.to_string(),
+        sample_rate_hz: metadata.sample_rate_hz,
+        channels: metadata.channels,
+        device_name: metadata.device_name,
+        started_at_ms: metadata.started_at_ms,
+        duration_ms: frames * 1000 / u64::from(metadata.sample_rate_hz.max(1)),
+    })
+}
+
+fn read_metadata(files: &RecoveryFiles) -> Result<RecoveryMetadata, String> {
+    let bytes = fs::read(&files.metadata_path).map_err(recovery_io_error)?;
+    serde_json::from_slice(&bytes)
+        .map_err(|error| format!("Recovery metadata is malformed: {error}"))
+}
+
+// Ids come from the frontend, so anything that could escape the directory is
+// rejected.
+fn recovery_files_for_request(dir: &Path, id: &str) -> Result<RecoveryFiles, String> {
+    let is_valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '-');
+    if !is_valid {
+        return Err(format!("Invalid recovered recording id `{id}`"));
+    }
+    Ok(RecoveryFiles::for_id(dir, id))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn recovery_io_error(error: std::io::Error) -> String {
+    format!("Recording recovery file error: {error}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{
+        discard_recovered_recording, list_recovered_recordings, load_recovered_recording_wav,
+        RecoveryCheckpoint,
+    };
+    use crate::transcription::chunked::parse_pcm16_mono_wav;
+
+    #[test]
+    fn orphaned_checkpoints_are_listed_loaded_and_discarded() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be after epoch")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("voice-recovery-{nanos}"));
+
+        let mut checkpoint =
+            RecoveryCheckpoint::create(&dir, 16_000, 1, "Built-in Microphone").expect("create");
+        checkpoint.append(&vec![7; 8_000]).expect("append");
+        checkpoint.append(&vec![-7; 8_000]).expect("append");
+        drop(checkpoint);
+
+        let recordings = list_recovered_recordings(&dir);
+        assert_eq!(recordings.len(), 1);
+        assert_eq!(recordings[0].duration_ms, 1_000);
+        assert_eq!(recordings[0].device_name, "Built-in Microphone");
+
+        let wav = load_recovered_recording_wav(&dir, &recordings[0].id).expect("load");
+        let parsed = parse_pcm16_mono_wav(&wav).expect("recovered wav should parse");
+        assert_eq!(parsed.samples.len(), 16_000);
+        assert_eq!(parsed.samples[8_000], -7);
+
+        assert!(load_recovered_recording_wav(&dir, "../settings").is_err());
+        assert!(discard_recovered_recording(&dir, &recordings[0].id).expect("discard"));
+        assert!(list_recovered_recordings(&dir).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
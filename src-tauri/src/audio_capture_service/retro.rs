@@ -0,0 +1,235 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::AtomicU32,
+        mpsc::{self, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+use tracing::{debug, error, info, warn};
+
+use super::{
+    await_worker_startup, pcm16_to_wav_bytes, run_recording_loop, spool::SampleSpool,
+    start_recording_worker, AudioInputChunk, AudioSpoolConfig, InputDeviceRole,
+    InputProcessingConfig, RecordingLoopExit, RecordingRuntime, WORKER_STARTUP_TIMEOUT,
+};
+use crate::{app_error::AppError, crash_reporter};
+
+// Keeps the last few seconds of microphone audio while no recording is
+// running. Samples older than the capacity fall off the front.
+#[derive(Debug)]
+pub(super) struct RetroBuffer {
+    samples: VecDeque<i16>,
+    capacity: usize,
+}
+
+impl RetroBuffer {
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub(super) fn extend(&mut self, samples: &[i16]) {
+        if self.capacity == 0 {
+            return;
+        }
+        let incoming = &samples[samples.len().saturating_sub(self.capacity)..];
+        let overflow = (self.samples.len() + incoming.len()).saturating_sub(self.capacity);
+        self.samples.drain(..overflow);
+        self.samples.extend(incoming);
+    }
+
+    pub(super) fn last(&self, count: usize) -> Vec<i16> {
+        let start = self.samples.len().saturating_sub(count);
+        self.samples.range(start..).copied().collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RetroCaptureConfig {
+    preferred_device_id: Option<String>,
+    processing: InputProcessingConfig,
+    buffer_secs: u32,
+}
+
+struct RetroCaptureControl {
+    config: RetroCaptureConfig,
+    stop_tx: Sender<()>,
+    join_handle: JoinHandle<()>,
+    buffer: Arc<Mutex<RetroBuffer>>,
+    sample_rate_hz: u32,
+}
+
+#[derive(Default)]
+pub(super) struct RetroCapture {
+    control: Mutex<Option<RetroCaptureControl>>,
+}
+
+impl RetroCapture {
+    // Restarts the background stream only when the device, processing or
+    // buffer length changed; zero seconds turns it off.
+    pub(super) fn configure(
+        &self,
+        preferred_device_id: Option<&str>,
+        processing: InputProcessingConfig,
+        buffer_secs: u32,
+    ) -> Result<(), String> {
+        let config = RetroCaptureConfig {
+            preferred_device_id: preferred_device_id.map(str::to_string),
+            processing,
+            buffer_secs,
+        };
+        let mut control = self
+            .control
+            .lock()
+            .map_err(|_| "Retro capture state lock is poisoned".to_string())?;
+        if control.as_ref().map(|control| &control.config) == Some(&config) {
+            return Ok(());
+        }
+        if let Some(previous) = control.take() {
+            stop_worker(previous);
+        }
+        if buffer_secs == 0 {
+            return Ok(());
+        }
+        *control = Some(start_worker(config)?);
+        Ok(())
+    }
+
+    pub(super) fn snapshot_wav(&self, secs: u32) -> Result<Vec<u8>, String> {
+        let control = self
+            .control
+            .lock()
+            .map_err(|_| "Retro capture state lock is poisoned".to_string())?;
+        let control = control
+            .as_ref()
+            .ok_or_else(|| "The retro buffer is turned off in settings".to_string())?;
+        let count = usize::try_from(u64::from(control.sample_rate_hz) * u64::from(secs))
+            .unwrap_or(usize::MAX);
+        let samples = control
+            .buffer
+            .lock()
+            .map_err(|_| "Retro buffer lock is poisoned".to_string())?
+            .last(count);
+        if samples.is_empty() {
+            return Err("The retro buffer has not captured any audio yet".to_string());
+        }
+        debug!(
+            requested_secs = secs,
+            samples = samples.len(),
+            "took retro buffer snapshot"
+        );
+        pcm16_to_wav_bytes(&samples, control.sample_rate_hz, 1)
+    }
+}
+
+fn start_worker(config: RetroCaptureConfig) -> Result<RetroCaptureControl, String> {
+    let buffer = Arc::new(Mutex::new(RetroBuffer::new(0)));
+    let worker_buffer = Arc::clone(&buffer);
+    let worker_config = config.clone();
+    let (ready_tx, ready_rx) = mpsc::channel::<Result<RecordingRuntime, AppError>>();
+    let (stop_tx, stop_rx) = mpsc::channel::<()>();
+
+    let mut join_handle = Some(thread::spawn(move || {
+        let (stream_error_tx, stream_error_rx) = mpsc::channel::<String>();
+        // The worker writes into a spool as well; it is emptied on every tick
+        // since only the ring buffer is kept.
+        let spool = Arc::new(Mutex::new(SampleSpool::new(AudioSpoolConfig::default())));
+        let chunk_buffer = Arc::clone(&worker_buffer);
+        let started = start_recording_worker(
+            worker_config.preferred_device_id.as_deref(),
+            InputDeviceRole::Microphone,
+            &worker_config.processing,
+            Arc::clone(&spool),
+            Arc::new(AtomicU32::new(0.0_f32.to_bits())),
+            Some(Arc::new(move |chunk: AudioInputChunk| {
+                if let Ok(mut buffer) = chunk_buffer.lock() {
+                    buffer.extend(&chunk.pcm16_mono_samples);
+                }
+            })),
+            stream_error_tx,
+        );
+        let (stream, runtime) = match started {
+            Ok(started) => started,
+            Err(error) => {
+                let _ = ready_tx.send(Err(error));
+                return;
+            }
+        };
+        if let Ok(mut buffer) = worker_buffer.lock() {
+            *buffer = RetroBuffer::new(
+                usize::try_from(runtime.sample_rate_hz).unwrap_or(48_000)
+                    * worker_config.buffer_secs as usize,
+            );
+        }
+        let _ = ready_tx.send(Ok(runtime));
+
+        let exit = run_recording_loop(&stop_rx, &stream_error_rx, || {
+            if let Ok(mut spool) = spool.lock() {
+                spool.clear();
+            }
+        });
+        drop(stream);
+        if let RecordingLoopExit::StreamError(message) = exit {
+            error!(%message, "retro buffer stream stopped after an error");
+        }
+    }));
+
+    let runtime = await_worker_startup(
+        &ready_rx,
+        &stop_tx,
+        &mut join_handle,
+        WORKER_STARTUP_TIMEOUT,
+    )
+    .map_err(|error| error.message)?;
+    let join_handle =
+        join_handle.ok_or_else(|| "Retro buffer worker was unavailable".to_string())?;
+    info!(
+        buffer_secs = config.buffer_secs,
+        sample_rate_hz = runtime.sample_rate_hz,
+        device_name = %runtime.device_name,
+        "retro buffer started"
+    );
+    Ok(RetroCaptureControl {
+        config,
+        stop_tx,
+        join_handle,
+        buffer,
+        sample_rate_hz: runtime.sample_rate_hz,
+    })
+}
+
+fn stop_worker(control: RetroCaptureControl) {
+    let _ = control.stop_tx.send(());
+    if let Err(payload) = control.join_handle.join() {
+        let message = crash_reporter::panic_message(payload.as_ref());
+        warn!(%message, "retro buffer thread panicked while stopping");
+    } else {
+        info!("retro buffer stopped");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RetroBuffer;
+
+    #[test]
+    fn keeps_only_the_most_recent_samples() {
+        let mut buffer = RetroBuffer::new(4);
+        buffer.extend(&[1, 2, 3]);
+        buffer.extend(&[4, 5]);
+        assert_eq!(buffer.last(10), vec![2, 3, 4, 5]);
+        assert_eq!(buffer.last(2), vec![4, 5]);
+
+        buffer.extend(&[6, 7, 8, 9, 10, 11]);
+        assert_eq!(buffer.last(4), vec![8, 9, 10, 11]);
+
+        let mut disabled = RetroBuffer::new(0);
+        disabled.extend(&[1, 2]);
+        assert!(disabled.last(2).is_empty());
+    }
+}
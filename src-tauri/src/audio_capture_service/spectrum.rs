@@ -0,0 +1,211 @@
+use std::{collections::VecDeque, f32::consts::PI};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ~10 ms at 48 kHz: long enough to resolve speech formants, short enough to
+// follow syllables at the 50 ms emit interval.
+const SPECTRUM_WINDOW_LEN: usize = 512;
+pub const SPECTRUM_BAND_COUNT: usize = 16;
+// 40 ticks of the 50 ms level interval, two seconds of history.
+pub const LEVEL_HISTORY_LEN: usize = 40;
+const LOWEST_BAND_HZ: f32 = 80.0;
+const HIGHEST_BAND_HZ: f32 = 8_000.0;
+// Band magnitudes are mapped from this many dB below full scale up to 0 dB.
+const SPECTRUM_FLOOR_DB: f32 = -60.0;
+
+// A frequency-band summary of the most recent audio plus the last couple of
+// seconds of levels, so the overlay can draw a waveform without raw PCM.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioSpectrumEvent {
+    // Log-spaced from 80 Hz to 8 kHz, each 0..1.
+    pub bands: Vec<f32>,
+    // Oldest first, each 0..1.
+    pub levels: Vec<f32>,
+}
+
+impl AudioSpectrumEvent {
+    pub fn silent() -> Self {
+        Self {
+            bands: vec![0.0; SPECTRUM_BAND_COUNT],
+            levels: vec![0.0; LEVEL_HISTORY_LEN],
+        }
+    }
+}
+
+// The newest mono samples from the capture callback, kept for the worker to
+// analyse on its next tick.
+#[derive(Debug)]
+pub(super) struct SpectrumTap {
+    samples: VecDeque<f32>,
+    sample_rate_hz: u32,
+}
+
+impl SpectrumTap {
+    pub(super) fn new() -> Self {
+        Self {
+            samples: VecDeque::with_capacity(SPECTRUM_WINDOW_LEN),
+            sample_rate_hz: 0,
+        }
+    }
+
+    pub(super) fn extend(&mut self, pcm16_mono_samples: &[i16], sample_rate_hz: u32) {
+        let incoming =
+            &pcm16_mono_samples[pcm16_mono_samples.len().saturating_sub(SPECTRUM_WINDOW_LEN)..];
+        let overflow = (self.samples.len() + incoming.len()).saturating_sub(SPECTRUM_WINDOW_LEN);
+        self.samples.drain(..overflow);
+        self.samples.extend(
+            incoming
+                .iter()
+                .map(|sample| f32::from(*sample) / f32::from(i16::MAX)),
+        );
+        self.sample_rate_hz = sample_rate_hz;
+    }
+
+    pub(super) fn bands(&self) -> Vec<f32> {
+        if self.samples.len() < SPECTRUM_WINDOW_LEN || self.sample_rate_hz == 0 {
+            return vec![0.0; SPECTRUM_BAND_COUNT];
+        }
+        let window: Vec<f32> = self.samples.iter().copied().collect();
+        band_levels(&window, self.sample_rate_hz)
+    }
+}
+
+// Rolling history of the per-tick level.
+#[derive(Debug)]
+pub(super) struct LevelHistory {
+    levels: VecDeque<f32>,
+}
+
+impl LevelHistory {
+    pub(super) fn new() -> Self {
+        Self {
+            levels: VecDeque::from(vec![0.0; LEVEL_HISTORY_LEN]),
+        }
+    }
+
+    pub(super) fn push(&mut self, level: f32) {
+        self.levels.pop_front();
+        self.levels.push_back(level.clamp(0.0, 1.0));
+    }
+
+    pub(super) fn to_vec(&self) -> Vec<f32> {
+        self.levels.iter().copied().collect()
+    }
+}
+
+// Hann-windowed DFT magnitudes averaged over log-spaced bands. Each band
+// covers at least the one DFT bin nearest its centre, since the low bands are
+// narrower than a bin.
+pub(super) fn band_levels(window: &[f32], sample_rate_hz: u32) -> Vec<f32> {
+    let len = window.len();
+    if len == 0 || sample_rate_hz == 0 {
+        return vec![0.0; SPECTRUM_BAND_COUNT];
+    }
+    let sample_rate_hz = sample_rate_hz as f32;
+    let hann: Vec<f32> = (0..len)
+        .map(|index| 0.5 - 0.5 * (2.0 * PI * index as f32 / len as f32).cos())
+        .collect();
+    let window_gain: f32 = hann.iter().sum();
+    let windowed: Vec<f32> = window
+        .iter()
+        .zip(&hann)
+        .map(|(sample, weight)| sample * weight)
+        .collect();
+
+    let bin_hz = sample_rate_hz / len as f32;
+    let max_bin = len / 2;
+    let highest_hz = HIGHEST_BAND_HZ.min(sample_rate_hz / 2.0);
+    let ratio = (highest_hz / LOWEST_BAND_HZ).powf(1.0 / SPECTRUM_BAND_COUNT as f32);
+
+    (0..SPECTRUM_BAND_COUNT)
+        .map(|band| {
+            let low_hz = LOWEST_BAND_HZ * ratio.powi(band as i32);
+            let high_hz = low_hz * ratio;
+            let centre_bin = ((low_hz * high_hz).sqrt() / bin_hz).round() as usize;
+            let first_bin = ((low_hz / bin_hz).ceil() as usize).min(centre_bin);
+            let last_bin = ((high_hz / bin_hz).floor() as usize).max(centre_bin);
+            let bins = first_bin.max(1)..=last_bin.min(max_bin);
+            let (power, count) = bins.fold((0.0_f32, 0_u32), |(power, count), bin| {
+                let amplitude = 2.0 * bin_magnitude(&windowed, bin) / window_gain;
+                (power + amplitude * amplitude, count + 1)
+            });
+            if count == 0 {
+                return 0.0;
+            }
+            let amplitude = (power / count as f32).sqrt();
+            let db = 20.0 * amplitude.max(f32::MIN_POSITIVE).log10();
+            let level = ((db - SPECTRUM_FLOOR_DB) / -SPECTRUM_FLOOR_DB).clamp(0.0, 1.0);
+            (level * 100.0).round() / 100.0
+        })
+        .collect()
+}
+
+// Goertzel recurrence for a single DFT bin.
+fn bin_magnitude(samples: &[f32], bin: usize) -> f32 {
+    let omega = 2.0 * PI * bin as f32 / samples.len() as f32;
+    let coefficient = 2.0 * omega.cos();
+    let (previous, before_previous) =
+        samples
+            .iter()
+            .fold((0.0_f32, 0.0_f32), |(previous, before_previous), sample| {
+                (sample + coefficient * previous - before_previous, previous)
+            });
+    (previous * previous + before_previous * before_previous
+        - coefficient * previous * before_previous)
+        .max(0.0)
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::{band_levels, LevelHistory, SpectrumTap, LEVEL_HISTORY_LEN, SPECTRUM_BAND_COUNT};
+
+    #[test]
+    fn a_tone_lights_up_only_the_band_that_contains_it() {
+        let sample_rate_hz = 48_000;
+        let tone: Vec<i16> = (0..2_048)
+            .map(|index| {
+                let phase = 2.0 * PI * 1_000.0 * index as f32 / sample_rate_hz as f32;
+                (phase.sin() * 0.5 * f32::from(i16::MAX)) as i16
+            })
+            .collect();
+        let mut tap = SpectrumTap::new();
+        tap.extend(&tone, sample_rate_hz);
+        let bands = tap.bands();
+
+        assert_eq!(bands.len(), SPECTRUM_BAND_COUNT);
+        let loudest = bands
+            .iter()
+            .enumerate()
+            .max_by(|(_, left), (_, right)| left.total_cmp(right))
+            .map(|(index, _)| index)
+            .expect("bands should not be empty");
+        // 1 kHz sits in the ninth of sixteen bands between 80 Hz and 8 kHz.
+        assert_eq!(loudest, 8);
+        assert!(bands[loudest] > 0.8);
+        assert!(bands[0] < 0.3);
+        assert!(bands[SPECTRUM_BAND_COUNT - 1] < 0.3);
+
+        assert!(band_levels(&[0.0; 512], sample_rate_hz)
+            .iter()
+            .all(|band| *band == 0.0));
+    }
+
+    #[test]
+    fn level_history_keeps_a_fixed_window_oldest_first() {
+        let mut history = LevelHistory::new();
+        history.push(0.4);
+        history.push(1.7);
+        let levels = history.to_vec();
+
+        assert_eq!(levels.len(), LEVEL_HISTORY_LEN);
+        assert_eq!(&levels[LEVEL_HISTORY_LEN - 2..], &[0.4, 1.0]);
+        assert!(levels[..LEVEL_HISTORY_LEN - 2]
+            .iter()
+            .all(|level| *level == 0.0));
+    }
+}
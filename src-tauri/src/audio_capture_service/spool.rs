@@ -0,0 +1,408 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use tracing::{debug, info, warn};
+
+use super::{pcm16_wav_header, recovery::RecoveryCheckpoint, WAV_HEADER_LEN};
+
+// Roughly 90 seconds of 48 kHz mono audio before samples move to disk.
+const DEFAULT_SPILL_THRESHOLD_BYTES: u64 = 8 * 1024 * 1024;
+const MIN_SPILL_THRESHOLD_BYTES: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioSpoolConfig {
+    pub spill_threshold_bytes: u64,
+    pub spool_dir: PathBuf,
+}
+
+impl Default for AudioSpoolConfig {
+    fn default() -> Self {
+        Self {
+            spill_threshold_bytes: DEFAULT_SPILL_THRESHOLD_BYTES,
+            spool_dir: std::env::temp_dir(),
+        }
+    }
+}
+
+impl AudioSpoolConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Some(threshold) = read_u64_env("VOICE_AUDIO_SPILL_THRESHOLD_BYTES") {
+            config.spill_threshold_bytes = threshold.max(MIN_SPILL_THRESHOLD_BYTES);
+        }
+
+        if let Some(spool_dir) = std::env::var_os("VOICE_AUDIO_SPOOL_DIR") {
+            config.spool_dir = PathBuf::from(spool_dir);
+        }
+
+        debug!(
+            spill_threshold_bytes = config.spill_threshold_bytes,
+            spool_dir = %config.spool_dir.display(),
+            "loaded audio spool config"
+        );
+        config
+    }
+}
+
+struct SpillFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    sample_count: usize,
+}
+
+// Capture callbacks only append to `memory`; the recording worker moves the
+// buffered samples to a WAV file on disk once they pass the threshold, so long
+// recordings never hold more than one threshold's worth of audio in memory.
+pub(crate) struct SampleSpool {
+    memory: Vec<i16>,
+    spill: Option<SpillFile>,
+    spill_disabled: bool,
+    config: AudioSpoolConfig,
+    recovery: Option<RecoveryCheckpoint>,
+    // Prefix of `memory` already written to the recovery checkpoint.
+    checkpointed_len: usize,
+}
+
+impl SampleSpool {
+    pub fn new(config: AudioSpoolConfig) -> Self {
+        Self {
+            memory: Vec::new(),
+            spill: None,
+            spill_disabled: false,
+            config,
+            recovery: None,
+            checkpointed_len: 0,
+        }
+    }
+
+    pub fn attach_recovery(&mut self, checkpoint: RecoveryCheckpoint) {
+        self.recovery = Some(checkpoint);
+        self.checkpointed_len = 0;
+    }
+
+    pub fn checkpoint_if_due(&mut self) {
+        if self
+            .recovery
+            .as_ref()
+            .is_some_and(RecoveryCheckpoint::is_due)
+        {
+            self.write_checkpoint();
+        }
+    }
+
+    // Called once the recording has been handed off or thrown away.
+    pub fn discard_recovery(&mut self) {
+        if let Some(checkpoint) = self.recovery.take() {
+            checkpoint.files().remove();
+        }
+    }
+
+    pub fn push(&mut self, sample: i16) {
+        self.memory.push(sample);
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.memory.reserve(additional);
+    }
+
+    pub fn len(&self) -> usize {
+        self.spill.as_ref().map_or(0, |spill| spill.sample_count) + self.memory.len()
+    }
+
+    pub fn is_spilled(&self) -> bool {
+        self.spill.is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.memory.clear();
+        self.checkpointed_len = 0;
+        self.remove_spill_file();
+    }
+
+    pub fn spill_if_needed(&mut self) {
+        let buffered_bytes = self.memory.len() as u64 * 2;
+        if self.spill_disabled || buffered_bytes < self.config.spill_threshold_bytes {
+            return;
+        }
+
+        if let Err(error) = self.spill_memory() {
+            // Keep recording in memory rather than dropping audio.
+            warn!(%error, "failed to spill audio samples to disk; keeping them in memory");
+            self.spill_disabled = true;
+        }
+    }
+
+    pub fn take_samples(&mut self) -> Result<Vec<i16>, String> {
+        self.checkpointed_len = 0;
+        let Some(mut spill) = self.spill.take() else {
+            return Ok(std::mem::take(&mut self.memory));
+        };
+
+        let result = read_spilled_samples(&mut spill).map(|mut samples| {
+            samples.append(&mut self.memory);
+            samples
+        });
+        remove_file_quietly(&spill.path);
+        result
+    }
+
+    // Finishes the spill file as a standalone WAV and hands ownership of it to
+    // the caller.
+    pub fn finish_spooled_wav(
+        &mut self,
+        sample_rate_hz: u32,
+        channels: u16,
+    ) -> Result<SpooledWav, String> {
+        let mut spill = self
+            .spill
+            .take()
+            .ok_or_else(|| "Recording was not spooled to disk".to_string())?;
+        let result = write_pcm16(&mut spill.writer, &self.memory)
+            .and_then(|()| {
+                spill.sample_count += self.memory.len();
+                self.memory.clear();
+                self.checkpointed_len = 0;
+                spill.writer.flush().map_err(spool_io_error)?;
+                let header = pcm16_wav_header(spill.sample_count, sample_rate_hz, channels)?;
+                let file = spill.writer.get_mut();
+                file.seek(SeekFrom::Start(0)).map_err(spool_io_error)?;
+                file.write_all(&header).map_err(spool_io_error)?;
+                file.sync_data().map_err(spool_io_error)
+            })
+            .map(|()| SpooledWav {
+                path: spill.path.clone(),
+                byte_len: (WAV_HEADER_LEN + spill.sample_count * 2) as u64,
+                duration_secs: spill.sample_count as f64 / f64::from(sample_rate_hz.max(1)),
+            });
+
+        if result.is_err() {
+            remove_file_quietly(&spill.path);
+        }
+        result
+    }
+
+    fn write_checkpoint(&mut self) {
+        let Some(checkpoint) = self.recovery.as_mut() else {
+            return;
+        };
+        let pending = &self.memory[self.checkpointed_len.min(self.memory.len())..];
+        match checkpoint.append(pending) {
+            Ok(()) => self.checkpointed_len = self.memory.len(),
+            Err(error) => {
+                // Recording carries on; only crash recovery is lost.
+                warn!(%error, "failed to checkpoint recording for crash recovery");
+                self.discard_recovery();
+            }
+        }
+    }
+
+    fn spill_memory(&mut self) -> Result<(), String> {
+        // Samples leave `memory` below, so the checkpoint has to catch up first.
+        self.write_checkpoint();
+        if self.spill.is_none() {
+            fs::create_dir_all(&self.config.spool_dir).map_err(spool_io_error)?;
+            let path = self
+                .config
+                .spool_dir
+                .join(format!("voice-recording-{}.wav", uuid::Uuid::new_v4()));
+            let file = OpenOptions::new()
+                .create_new(true)
+                .read(true)
+                .write(true)
+                .open(&path)
+                .map_err(spool_io_error)?;
+            let mut writer = BufWriter::new(file);
+            // Placeholder header, rewritten once the final length is known.
+            writer
+                .write_all(&[0u8; WAV_HEADER_LEN])
+                .map_err(spool_io_error)?;
+            info!(path = %path.display(), "spilling long recording to disk");
+            self.spill = Some(SpillFile {
+                path,
+                writer,
+                sample_count: 0,
+            });
+        }
+
+        let Some(spill) = self.spill.as_mut() else {
+            return Ok(());
+        };
+        write_pcm16(&mut spill.writer, &self.memory)?;
+        spill.sample_count += self.memory.len();
+        debug!(
+            spilled_samples = spill.sample_count,
+            "spilled buffered audio samples to disk"
+        );
+        self.memory.clear();
+        self.checkpointed_len = 0;
+        Ok(())
+    }
+
+    fn remove_spill_file(&mut self) {
+        if let Some(spill) = self.spill.take() {
+            remove_file_quietly(&spill.path);
+        }
+    }
+}
+
+impl Drop for SampleSpool {
+    fn drop(&mut self) {
+        self.remove_spill_file();
+    }
+}
+
+// A finished recording that lives on disk; the file is deleted on drop.
+#[derive(Debug)]
+pub struct SpooledWav {
+    path: PathBuf,
+    byte_len: u64,
+    duration_secs: f64,
+}
+
+impl SpooledWav {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn byte_len(&self) -> u64 {
+        self.byte_len
+    }
+
+    pub fn duration_secs(&self) -> f64 {
+        self.duration_secs
+    }
+
+    pub fn read(&self) -> Result<Vec<u8>, String> {
+        fs::read(&self.path).map_err(spool_io_error)
+    }
+}
+
+impl Drop for SpooledWav {
+    fn drop(&mut self) {
+        remove_file_quietly(&self.path);
+    }
+}
+
+fn read_spilled_samples(spill: &mut SpillFile) -> Result<Vec<i16>, String> {
+    spill.writer.flush().map_err(spool_io_error)?;
+    let file = spill.writer.get_mut();
+    file.seek(SeekFrom::Start(WAV_HEADER_LEN as u64))
+        .map_err(spool_io_error)?;
+    let mut bytes = Vec::with_capacity(spill.sample_count * 2);
+    file.read_to_end(&mut bytes).map_err(spool_io_error)?;
+    Ok(bytes
+        .chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+        .collect())
+}
+
+fn write_pcm16(writer: &mut BufWriter<File>, samples: &[i16]) -> Result<(), String> {
+    for sample in samples {
+        writer
+            .write_all(&sample.to_le_bytes())
+            .map_err(spool_io_error)?;
+    }
+    Ok(())
+}
+
+fn remove_file_quietly(path: &Path) {
+    if let Err(error) = fs::remove_file(path) {
+        if error.kind() != std::io::ErrorKind::NotFound {
+            warn!(path = %path.display(), %error, "failed to remove spooled audio file");
+        }
+    }
+}
+
+fn spool_io_error(error: std::io::Error) -> String {
+    format!("Audio spool file error: {error}")
+}
+
+fn read_u64_env(name: &str) -> Option<u64> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .and_then(|value| value.parse::<u64>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        path::{Path, PathBuf},
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::{AudioSpoolConfig, SampleSpool, MIN_SPILL_THRESHOLD_BYTES};
+    use crate::transcription::chunked::parse_pcm16_mono_wav;
+
+    fn spool_dir(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be after epoch")
+            .as_nanos();
+        std::env::temp_dir().join(format!("voice-spool-{label}-{nanos}"))
+    }
+
+    fn spool(dir: &Path) -> SampleSpool {
+        SampleSpool::new(AudioSpoolConfig {
+            spill_threshold_bytes: MIN_SPILL_THRESHOLD_BYTES,
+            spool_dir: dir.to_path_buf(),
+        })
+    }
+
+    fn push_samples(spool: &mut SampleSpool, range: std::ops::Range<i32>) {
+        for value in range {
+            spool.push(value as i16);
+        }
+        spool.spill_if_needed();
+    }
+
+    #[test]
+    fn spills_past_threshold_and_finishes_a_valid_wav_file() {
+        let dir = spool_dir("finish");
+        let mut spool = spool(&dir);
+        push_samples(&mut spool, 0..20_000);
+        assert!(!spool.is_spilled());
+        push_samples(&mut spool, 20_000..40_000);
+        assert!(spool.is_spilled());
+        push_samples(&mut spool, -500..0);
+
+        let spooled = spool
+            .finish_spooled_wav(16_000, 1)
+            .expect("spooled wav should finish");
+        let bytes = spooled.read().expect("spooled wav should be readable");
+        let wav = parse_pcm16_mono_wav(&bytes).expect("spooled wav should parse");
+
+        assert_eq!(spooled.byte_len(), bytes.len() as u64);
+        assert_eq!(wav.samples.len(), 40_500);
+        assert_eq!(wav.samples[39_999], 39_999_i32 as i16);
+        assert_eq!(wav.samples[40_000], -500);
+
+        let path = spooled.path().to_path_buf();
+        drop(spooled);
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn take_samples_reads_spilled_audio_back_and_cleans_up() {
+        let dir = spool_dir("take");
+        let mut spool = spool(&dir);
+        push_samples(&mut spool, 0..40_000);
+        push_samples(&mut spool, 0..10);
+
+        let samples = spool.take_samples().expect("samples should be read back");
+
+        assert_eq!(samples.len(), 40_010);
+        assert_eq!(&samples[40_000..], &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(
+            std::fs::read_dir(&dir).expect("spool dir exists").count(),
+            0
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,300 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::AtomicU32,
+        mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+use tracing::{debug, info, warn};
+
+use super::{
+    await_worker_startup, run_capture_worker, spectrum::SpectrumTap, spool::SampleSpool,
+    start_recording_worker, tap_input_chunks, AudioInputChunk, AudioInputChunkCallback,
+    AudioSpoolConfig, CaptureEvents, CaptureWorker, InputDeviceRole, InputProcessingConfig,
+    RecordingRuntime, LEVEL_EVENT_INTERVAL, WORKER_STARTUP_TIMEOUT,
+};
+use crate::{app_error::AppError, crash_reporter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct WarmCaptureConfig {
+    preferred_device_id: Option<String>,
+    processing: InputProcessingConfig,
+}
+
+// What a recording passes to a warm stream when it takes it over.
+pub(super) struct WarmActivation {
+    pub events: CaptureEvents,
+    pub on_input_chunk: Option<AudioInputChunkCallback>,
+    pub audio_level_bits: Arc<AtomicU32>,
+    pub recovery_dir: Option<PathBuf>,
+    pub ready_tx: Sender<Result<RecordingRuntime, AppError>>,
+    pub stop_rx: Receiver<()>,
+}
+
+pub(super) struct WarmStream {
+    activate_tx: Sender<WarmActivation>,
+    join_handle: JoinHandle<()>,
+    samples: Arc<Mutex<SampleSpool>>,
+}
+
+impl WarmStream {
+    pub(super) fn samples(&self) -> Arc<Mutex<SampleSpool>> {
+        Arc::clone(&self.samples)
+    }
+
+    // From here on the worker behaves like one started for the recording; it
+    // reports readiness on `ready_tx` and exits on `stop_rx`.
+    pub(super) fn activate(self, activation: WarmActivation) -> JoinHandle<()> {
+        if let Err(mpsc::SendError(activation)) = self.activate_tx.send(activation) {
+            let _ = activation.ready_tx.send(Err(AppError::from(
+                "Warmed-up microphone stream has stopped".to_string(),
+            )));
+        }
+        self.join_handle
+    }
+}
+
+#[derive(Default)]
+struct WarmState {
+    config: Option<WarmCaptureConfig>,
+    stream: Option<WarmStream>,
+}
+
+// An open microphone stream waiting for the next recording. Samples are
+// dropped on every tick until a recording takes the stream over; once that
+// recording stops, a fresh stream is opened in the background.
+pub(super) struct WarmCapture {
+    state: Arc<Mutex<WarmState>>,
+    spool_config: AudioSpoolConfig,
+}
+
+impl WarmCapture {
+    pub(super) fn new(spool_config: AudioSpoolConfig) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(WarmState::default())),
+            spool_config,
+        }
+    }
+
+    // Reopens the stream only when the device or processing changed.
+    pub(super) fn configure(
+        &self,
+        preferred_device_id: Option<&str>,
+        processing: InputProcessingConfig,
+        enabled: bool,
+    ) -> Result<(), String> {
+        let config = enabled.then(|| WarmCaptureConfig {
+            preferred_device_id: preferred_device_id.map(str::to_string),
+            processing,
+        });
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| "Warm microphone state lock is poisoned".to_string())?;
+        if state.config == config && (config.is_none() || state.stream.is_some()) {
+            return Ok(());
+        }
+        if let Some(previous) = state.stream.take() {
+            stop_worker(previous);
+        }
+        state.config = config.clone();
+        let Some(config) = config else {
+            info!("warm microphone turned off");
+            return Ok(());
+        };
+        state.stream = Some(start_worker(config, self.spool_config.clone())?);
+        Ok(())
+    }
+
+    // Hands over the stream when it is still running and was opened for the
+    // same device and processing the recording asks for.
+    pub(super) fn take(
+        &self,
+        preferred_device_id: Option<&str>,
+        processing: &InputProcessingConfig,
+    ) -> Option<WarmStream> {
+        let mut state = self.state.lock().ok()?;
+        let matches = state.config.as_ref().is_some_and(|config| {
+            config.preferred_device_id.as_deref() == preferred_device_id
+                && &config.processing == processing
+        });
+        if !matches {
+            return None;
+        }
+        let stream = state.stream.take()?;
+        if stream.join_handle.is_finished() {
+            debug!("warm microphone stream had stopped; starting a new one");
+            return None;
+        }
+        Some(stream)
+    }
+
+    // Opens the next stream off the caller's thread, so stopping a recording
+    // is not held up by the device.
+    pub(super) fn rearm(&self) {
+        let config = match self.state.lock() {
+            Ok(state) if state.stream.is_none() => state.config.clone(),
+            _ => None,
+        };
+        let Some(config) = config else {
+            return;
+        };
+        let state = Arc::clone(&self.state);
+        let spool_config = self.spool_config.clone();
+        thread::spawn(move || {
+            let stream = match start_worker(config.clone(), spool_config) {
+                Ok(stream) => stream,
+                Err(error) => {
+                    warn!(%error, "failed to reopen warm microphone stream");
+                    return;
+                }
+            };
+            let Ok(mut state) = state.lock() else {
+                return;
+            };
+            if state.config.as_ref() == Some(&config) && state.stream.is_none() {
+                state.stream = Some(stream);
+            } else {
+                stop_worker(stream);
+            }
+        });
+    }
+}
+
+fn start_worker(
+    config: WarmCaptureConfig,
+    spool_config: AudioSpoolConfig,
+) -> Result<WarmStream, String> {
+    let samples = Arc::new(Mutex::new(SampleSpool::new(spool_config)));
+    let worker_samples = Arc::clone(&samples);
+    let (ready_tx, ready_rx) = mpsc::channel::<Result<RecordingRuntime, AppError>>();
+    let (activate_tx, activate_rx) = mpsc::channel::<WarmActivation>();
+    // Idle streams have no stop channel of their own; dropping `activate_tx`
+    // ends them. This one only unblocks `await_worker_startup` on timeout.
+    let (startup_stop_tx, _) = mpsc::channel::<()>();
+
+    let mut join_handle = Some(thread::spawn(move || {
+        let (stream_error_tx, stream_error_rx) = mpsc::channel::<String>();
+        let audio_level_bits = Arc::new(AtomicU32::new(0.0_f32.to_bits()));
+        let forward = Arc::new(Mutex::new(None::<AudioInputChunkCallback>));
+        let stream_forward = Arc::clone(&forward);
+        let started = start_recording_worker(
+            config.preferred_device_id.as_deref(),
+            InputDeviceRole::Microphone,
+            &config.processing,
+            Arc::clone(&worker_samples),
+            Arc::clone(&audio_level_bits),
+            Some(Arc::new(move |chunk: AudioInputChunk| {
+                if let Ok(forward) = stream_forward.lock() {
+                    if let Some(callback) = forward.as_ref() {
+                        callback(chunk);
+                    }
+                }
+            })),
+            stream_error_tx,
+        );
+        let (stream, runtime) = match started {
+            Ok(started) => started,
+            Err(error) => {
+                let _ = ready_tx.send(Err(error));
+                return;
+            }
+        };
+        let _ = ready_tx.send(Ok(runtime.clone()));
+
+        let Some(activation) =
+            idle_until_activated(&activate_rx, &stream_error_rx, &worker_samples)
+        else {
+            drop(stream);
+            return;
+        };
+
+        if let Ok(mut samples) = worker_samples.lock() {
+            samples.clear();
+        }
+        let spectrum_tap = Arc::new(Mutex::new(SpectrumTap::new()));
+        if let Ok(mut forward) = forward.lock() {
+            *forward = Some(tap_input_chunks(&spectrum_tap, activation.on_input_chunk));
+        }
+        run_capture_worker(
+            stream,
+            runtime,
+            CaptureWorker {
+                samples: worker_samples,
+                system_samples: Arc::new(Mutex::new(SampleSpool::new(AudioSpoolConfig::default()))),
+                audio_level_bits,
+                level_mirror: Some(activation.audio_level_bits),
+                spectrum_tap,
+                events: activation.events,
+                recovery_dir: activation.recovery_dir,
+                ready_tx: activation.ready_tx,
+                stop_rx: activation.stop_rx,
+                stream_error_rx,
+            },
+        );
+    }));
+
+    let runtime = await_worker_startup(
+        &ready_rx,
+        &startup_stop_tx,
+        &mut join_handle,
+        WORKER_STARTUP_TIMEOUT,
+    )
+    .map_err(|error| error.message)?;
+    let join_handle =
+        join_handle.ok_or_else(|| "Warm microphone worker was unavailable".to_string())?;
+    info!(
+        sample_rate_hz = runtime.sample_rate_hz,
+        device_name = %runtime.device_name,
+        "warm microphone stream opened"
+    );
+    Ok(WarmStream {
+        activate_tx,
+        join_handle,
+        samples,
+    })
+}
+
+fn idle_until_activated(
+    activate_rx: &Receiver<WarmActivation>,
+    stream_error_rx: &Receiver<String>,
+    samples: &Mutex<SampleSpool>,
+) -> Option<WarmActivation> {
+    loop {
+        match stream_error_rx.try_recv() {
+            Ok(message) => {
+                warn!(%message, "warm microphone stream stopped after an error");
+                return None;
+            }
+            Err(TryRecvError::Disconnected | TryRecvError::Empty) => {}
+        }
+
+        match activate_rx.recv_timeout(LEVEL_EVENT_INTERVAL) {
+            Ok(activation) => return Some(activation),
+            Err(RecvTimeoutError::Disconnected) => return None,
+            Err(RecvTimeoutError::Timeout) => {
+                if let Ok(mut samples) = samples.lock() {
+                    samples.clear();
+                }
+            }
+        }
+    }
+}
+
+fn stop_worker(stream: WarmStream) {
+    let WarmStream {
+        activate_tx,
+        join_handle,
+        ..
+    } = stream;
+    drop(activate_tx);
+    if let Err(payload) = join_handle.join() {
+        let message = crash_reporter::panic_message(payload.as_ref());
+        warn!(%message, "warm microphone thread panicked while stopping");
+    } else {
+        info!("warm microphone stream closed");
+    }
+}
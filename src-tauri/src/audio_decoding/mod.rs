@@ -0,0 +1,161 @@
+use std::{fs::File, io::ErrorKind, path::Path};
+
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{DecoderOptions, CODEC_TYPE_NULL},
+    errors::Error as SymphoniaError,
+    formats::FormatOptions,
+    io::MediaSourceStream,
+    meta::MetadataOptions,
+    probe::Hint,
+};
+use tracing::{debug, info};
+
+use crate::audio_capture_service::pcm16_to_wav_bytes;
+use crate::resample::resample_pcm16;
+
+// Providers downsample to 16 kHz mono anyway, and it keeps hour-long files to
+// a manageable upload.
+const DECODED_SAMPLE_RATE_HZ: u32 = 16_000;
+const SUPPORTED_AUDIO_EXTENSIONS: &[&str] =
+    &["wav", "mp3", "m4a", "mp4", "aac", "ogg", "oga", "flac"];
+
+pub fn is_supported_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            SUPPORTED_AUDIO_EXTENSIONS
+                .iter()
+                .any(|supported| supported.eq_ignore_ascii_case(extension))
+        })
+}
+
+// Decodes any supported file into the 16-bit mono WAV the transcription
+// pipeline expects.
+pub fn decode_audio_file_to_wav(path: &Path) -> Result<Vec<u8>, String> {
+    if !is_supported_audio_file(path) {
+        return Err(format!("Unsupported audio file type: {}", path.display()));
+    }
+    let file =
+        File::open(path).map_err(|error| format!("Failed to open {}: {error}", path.display()))?;
+    let (samples, sample_rate_hz) = decode_mono_samples(file, path)?;
+    if samples.is_empty() || sample_rate_hz == 0 {
+        return Err(format!("No audio found in {}", path.display()));
+    }
+
+    let samples = if sample_rate_hz == DECODED_SAMPLE_RATE_HZ {
+        samples
+    } else {
+        resample_pcm16(&samples, sample_rate_hz, DECODED_SAMPLE_RATE_HZ)?
+    };
+    info!(
+        path = %path.display(),
+        source_sample_rate_hz = sample_rate_hz,
+        duration_secs = samples.len() as f64 / f64::from(DECODED_SAMPLE_RATE_HZ),
+        "decoded audio file"
+    );
+    pcm16_to_wav_bytes(&samples, DECODED_SAMPLE_RATE_HZ, 1)
+}
+
+fn decode_mono_samples(file: File, path: &Path) -> Result<(Vec<i16>, u32), String> {
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+        hint.with_extension(extension);
+    }
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|error| format!("Unrecognized audio format: {error}"))?;
+    let mut format = probed.format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| "File has no audio track".to_string())?;
+    let track_id = track.id;
+    let mut sample_rate_hz = track.codec_params.sample_rate.unwrap_or(0);
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|error| format!("Unsupported audio codec: {error}"))?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(error)) if error.kind() == ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(error) => return Err(format!("Failed to read audio: {error}")),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt frame costs a few milliseconds of audio, not the file.
+            Err(SymphoniaError::DecodeError(error)) => {
+                debug!(%error, "skipping undecodable audio packet");
+                continue;
+            }
+            Err(error) => return Err(format!("Failed to decode audio: {error}")),
+        };
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        sample_rate_hz = spec.rate;
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend(buffer.samples().chunks(channels).map(|frame| {
+            let mono = frame.iter().sum::<f32>() / channels as f32;
+            (mono.clamp(-1.0, 1.0) * f32::from(i16::MAX)).round() as i16
+        }));
+    }
+    Ok((samples, sample_rate_hz))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{decode_audio_file_to_wav, is_supported_audio_file, DECODED_SAMPLE_RATE_HZ};
+    use crate::audio_capture_service::pcm16_to_wav_bytes;
+    use crate::transcription::chunked::parse_pcm16_mono_wav;
+
+    #[test]
+    fn decodes_stereo_wav_into_16khz_mono() {
+        let test_dir = std::env::temp_dir().join(format!("voice-decode-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&test_dir).expect("test dir should be created");
+        let path = test_dir.join("memo.WAV");
+        // Half a second at 44.1 kHz, left and right interleaved.
+        let stereo: Vec<i16> = (0..22_050).flat_map(|_| [8_000, 4_000]).collect();
+        std::fs::write(
+            &path,
+            pcm16_to_wav_bytes(&stereo, 44_100, 2).expect("wav should encode"),
+        )
+        .expect("wav should write");
+
+        let wav =
+            parse_pcm16_mono_wav(&decode_audio_file_to_wav(&path).expect("wav should decode"))
+                .expect("decoded wav should parse");
+        assert_eq!(wav.sample_rate_hz, DECODED_SAMPLE_RATE_HZ);
+        assert_eq!(wav.samples.len(), 8_000);
+        assert!(wav.samples[4_000].abs_diff(6_000) < 100);
+
+        let _ = std::fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn rejects_unsupported_and_missing_files() {
+        assert!(is_supported_audio_file(Path::new("/tmp/a.M4A")));
+        assert!(!is_supported_audio_file(Path::new("/tmp/a.txt")));
+        assert!(!is_supported_audio_file(Path::new("/tmp/wav")));
+        assert!(decode_audio_file_to_wav(Path::new("/tmp/notes.txt"))
+            .is_err_and(|error| error.starts_with("Unsupported audio file type")));
+        assert!(decode_audio_file_to_wav(Path::new("/nonexistent/memo.mp3")).is_err());
+    }
+}
@@ -0,0 +1,308 @@
+// Minimal FLAC encoder for 16-bit mono PCM: fixed blocks, fixed linear
+// predictors (orders 0-4) and single-partition Rice-coded residuals.
+
+const BLOCK_SIZE: usize = 4096;
+const BITS_PER_SAMPLE: u32 = 16;
+const MAX_FIXED_ORDER: usize = 4;
+const MAX_RICE_PARAMETER: u32 = 14;
+const MAX_SAMPLE_RATE_HZ: u32 = 655_350;
+
+pub fn encode_flac(samples: &[i16], sample_rate_hz: u32) -> Result<Vec<u8>, String> {
+    if sample_rate_hz == 0 || sample_rate_hz > MAX_SAMPLE_RATE_HZ {
+        return Err(format!(
+            "Failed to encode FLAC: unsupported sample rate {sample_rate_hz} Hz"
+        ));
+    }
+
+    let mut output = Vec::with_capacity(samples.len() + 64);
+    output.extend_from_slice(b"fLaC");
+    write_stream_info(&mut output, samples.len() as u64, sample_rate_hz);
+
+    for (frame_number, block) in samples.chunks(BLOCK_SIZE).enumerate() {
+        write_frame(&mut output, frame_number as u64, block);
+    }
+
+    Ok(output)
+}
+
+fn write_stream_info(output: &mut Vec<u8>, total_samples: u64, sample_rate_hz: u32) {
+    let mut writer = BitWriter::default();
+    // Last-metadata-block flag, STREAMINFO type, 34-byte length.
+    writer.write(1, 1);
+    writer.write(0, 7);
+    writer.write(34, 24);
+    // Fixed-blocksize stream: only the final frame may be shorter.
+    writer.write(BLOCK_SIZE as u64, 16);
+    writer.write(BLOCK_SIZE as u64, 16);
+    writer.write(0, 24);
+    writer.write(0, 24);
+    writer.write(u64::from(sample_rate_hz), 20);
+    writer.write(0, 3);
+    writer.write(u64::from(BITS_PER_SAMPLE - 1), 5);
+    writer.write(total_samples, 36);
+    // An all-zero MD5 signature means "not computed".
+    writer.write(0, 64);
+    writer.write(0, 64);
+    output.extend(writer.finish());
+}
+
+fn write_frame(output: &mut Vec<u8>, frame_number: u64, block: &[i16]) {
+    let mut writer = BitWriter::default();
+    writer.write(0b11_1111_1111_1110, 14);
+    writer.write(0, 1);
+    writer.write(0, 1);
+    // Block size stored as a 16-bit value after the header; sample rate comes
+    // from STREAMINFO; mono; 16 bits per sample.
+    writer.write(0b0111, 4);
+    writer.write(0b0000, 4);
+    writer.write(0b0000, 4);
+    writer.write(0b100, 3);
+    writer.write(0, 1);
+    for byte in utf8_coded_number(frame_number) {
+        writer.write(u64::from(byte), 8);
+    }
+    writer.write((block.len() - 1) as u64, 16);
+    let header_crc = crc8(writer.bytes());
+    writer.write(u64::from(header_crc), 8);
+
+    write_subframe(&mut writer, block);
+    writer.align();
+    let frame_crc = crc16(writer.bytes());
+    writer.write(u64::from(frame_crc), 16);
+    output.extend(writer.finish());
+}
+
+fn write_subframe(writer: &mut BitWriter, block: &[i16]) {
+    let samples = block
+        .iter()
+        .map(|sample| i32::from(*sample))
+        .collect::<Vec<_>>();
+
+    if samples.iter().all(|sample| *sample == samples[0]) {
+        writer.write(0, 1);
+        writer.write(0b000000, 6);
+        writer.write(0, 1);
+        write_signed(writer, samples[0], BITS_PER_SAMPLE);
+        return;
+    }
+
+    let best = (0..=MAX_FIXED_ORDER.min(samples.len() - 1))
+        .map(|order| {
+            let residuals = fixed_residuals(&samples, order);
+            let (rice_parameter, residual_bits) = best_rice_parameter(&residuals);
+            let bits = order as u64 * u64::from(BITS_PER_SAMPLE) + 6 + residual_bits;
+            (order, residuals, rice_parameter, bits)
+        })
+        .min_by_key(|(_, _, _, bits)| *bits);
+
+    let verbatim_bits = samples.len() as u64 * u64::from(BITS_PER_SAMPLE);
+    match best {
+        Some((order, residuals, rice_parameter, bits)) if bits < verbatim_bits => {
+            writer.write(0, 1);
+            writer.write(0b001000 | order as u64, 6);
+            writer.write(0, 1);
+            for sample in &samples[..order] {
+                write_signed(writer, *sample, BITS_PER_SAMPLE);
+            }
+            // Rice coding with 4-bit parameters and a single partition.
+            writer.write(0b00, 2);
+            writer.write(0, 4);
+            writer.write(u64::from(rice_parameter), 4);
+            for residual in residuals {
+                write_rice(writer, residual, rice_parameter);
+            }
+        }
+        _ => {
+            writer.write(0, 1);
+            writer.write(0b000001, 6);
+            writer.write(0, 1);
+            for sample in samples {
+                write_signed(writer, sample, BITS_PER_SAMPLE);
+            }
+        }
+    }
+}
+
+fn fixed_residuals(samples: &[i32], order: usize) -> Vec<i32> {
+    (order..samples.len())
+        .map(|index| {
+            let s = |offset: usize| samples[index - offset];
+            let prediction = match order {
+                0 => 0,
+                1 => s(1),
+                2 => 2 * s(1) - s(2),
+                3 => 3 * s(1) - 3 * s(2) + s(3),
+                _ => 4 * s(1) - 6 * s(2) + 4 * s(3) - s(4),
+            };
+            samples[index] - prediction
+        })
+        .collect()
+}
+
+fn best_rice_parameter(residuals: &[i32]) -> (u32, u64) {
+    (0..=MAX_RICE_PARAMETER)
+        .map(|parameter| {
+            let bits = residuals
+                .iter()
+                .map(|residual| {
+                    u64::from(zigzag(*residual) >> parameter) + 1 + u64::from(parameter)
+                })
+                .sum::<u64>();
+            (parameter, bits)
+        })
+        .min_by_key(|(_, bits)| *bits)
+        .unwrap_or((0, 0))
+}
+
+fn write_rice(writer: &mut BitWriter, residual: i32, parameter: u32) {
+    let value = zigzag(residual);
+    let mut quotient = value >> parameter;
+    while quotient >= 32 {
+        writer.write(0, 32);
+        quotient -= 32;
+    }
+    writer.write(1, quotient + 1);
+    if parameter > 0 {
+        writer.write(u64::from(value & ((1 << parameter) - 1)), parameter);
+    }
+}
+
+fn write_signed(writer: &mut BitWriter, value: i32, bits: u32) {
+    writer.write((value as u32 as u64) & ((1 << bits) - 1), bits);
+}
+
+fn zigzag(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+fn utf8_coded_number(value: u64) -> Vec<u8> {
+    if value < 0x80 {
+        return vec![value as u8];
+    }
+
+    let mut continuation = Vec::new();
+    let mut remaining = value;
+    let mut lead_capacity_bits = 6;
+    while remaining >= 1 << lead_capacity_bits {
+        continuation.push(0x80 | (remaining & 0x3F) as u8);
+        remaining >>= 6;
+        lead_capacity_bits -= 1;
+    }
+    let byte_count = continuation.len() + 1;
+    let lead_marker = !(0xFFu8 >> byte_count);
+    let mut bytes = vec![lead_marker | remaining as u8];
+    bytes.extend(continuation.into_iter().rev());
+    bytes
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |mut crc, byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0u16, |mut crc, byte| {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+#[derive(Debug, Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    accumulator: u64,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u64, bits: u32) {
+        for bit_index in (0..bits).rev() {
+            self.accumulator = (self.accumulator << 1) | ((value >> bit_index) & 1);
+            self.pending_bits += 1;
+            if self.pending_bits == 8 {
+                self.bytes.push(self.accumulator as u8);
+                self.accumulator = 0;
+                self.pending_bits = 0;
+            }
+        }
+    }
+
+    fn align(&mut self) {
+        if self.pending_bits > 0 {
+            self.write(0, 8 - self.pending_bits);
+        }
+    }
+
+    // Only valid on a byte boundary, which is where every CRC is taken.
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_flac, utf8_coded_number};
+
+    fn decode(bytes: &[u8]) -> (u32, Vec<i16>) {
+        let mut reader =
+            claxon::FlacReader::new(std::io::Cursor::new(bytes)).expect("flac should parse");
+        let sample_rate_hz = reader.streaminfo().sample_rate;
+        let samples = reader
+            .samples()
+            .map(|sample| sample.expect("sample should decode") as i16)
+            .collect();
+        (sample_rate_hz, samples)
+    }
+
+    #[test]
+    fn encodes_frame_numbers_like_utf8() {
+        assert_eq!(utf8_coded_number(0x41), vec![0x41]);
+        assert_eq!(utf8_coded_number(0x7FF), vec![0xDF, 0xBF]);
+        assert_eq!(utf8_coded_number(0x800), vec![0xE0, 0xA0, 0x80]);
+    }
+
+    #[test]
+    fn round_trips_speech_like_audio_losslessly_and_compresses_it() {
+        let samples = (0..20_000)
+            .map(|index| {
+                let t = index as f32 / 16_000.0;
+                let envelope = if (4_096..8_192).contains(&index) {
+                    0.0
+                } else {
+                    1.0
+                };
+                ((2.0 * std::f32::consts::PI * 180.0 * t).sin() * 6_000.0 * envelope
+                    + ((index * 7919) % 97) as f32
+                    - 48.0) as i16
+            })
+            .collect::<Vec<_>>();
+
+        let encoded = encode_flac(&samples, 16_000).expect("encoding should succeed");
+        let (sample_rate_hz, decoded) = decode(&encoded);
+
+        assert_eq!(sample_rate_hz, 16_000);
+        assert_eq!(decoded, samples);
+        assert!(encoded.len() < samples.len() * 2 * 3 / 4);
+    }
+}
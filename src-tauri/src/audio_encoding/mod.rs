@@ -0,0 +1,113 @@
+mod flac;
+mod opus;
+
+use tracing::{debug, warn};
+
+use crate::audio_capture_service::pcm16_to_wav_bytes;
+use crate::settings_store::{AUDIO_ENCODING_FLAC, AUDIO_ENCODING_OPUS};
+use crate::transcription::chunked::parse_pcm16_mono_wav;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AudioEncoding {
+    #[default]
+    Wav,
+    Flac,
+    Opus,
+}
+
+impl AudioEncoding {
+    pub fn from_settings_value(value: &str) -> Self {
+        match value {
+            AUDIO_ENCODING_FLAC => Self::Flac,
+            AUDIO_ENCODING_OPUS => Self::Opus,
+            _ => Self::Wav,
+        }
+    }
+
+    pub fn detect(audio_data: &[u8]) -> Self {
+        if audio_data.starts_with(b"fLaC") {
+            Self::Flac
+        } else if audio_data.starts_with(b"OggS") {
+            Self::Opus
+        } else {
+            Self::Wav
+        }
+    }
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Wav => "audio.wav",
+            Self::Flac => "audio.flac",
+            Self::Opus => "audio.ogg",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Wav => "audio/wav",
+            Self::Flac => "audio/flac",
+            Self::Opus => "audio/ogg",
+        }
+    }
+}
+
+pub fn encode_pcm16(
+    samples: &[i16],
+    sample_rate_hz: u32,
+    encoding: AudioEncoding,
+) -> Result<Vec<u8>, String> {
+    match encoding {
+        AudioEncoding::Wav => pcm16_to_wav_bytes(samples, sample_rate_hz, 1),
+        AudioEncoding::Flac => flac::encode_flac(samples, sample_rate_hz),
+        AudioEncoding::Opus => opus::encode_ogg_opus(samples, sample_rate_hz),
+    }
+}
+
+// Encoding is best-effort: any failure uploads the original WAV instead.
+pub fn encode_wav_for_upload(wav_bytes: Vec<u8>, encoding: AudioEncoding) -> Vec<u8> {
+    if encoding == AudioEncoding::Wav {
+        return wav_bytes;
+    }
+
+    let encoded = parse_pcm16_mono_wav(&wav_bytes)
+        .and_then(|wav| encode_pcm16(&wav.samples, wav.sample_rate_hz, encoding));
+    match encoded {
+        Ok(encoded) => {
+            debug!(
+                encoding = ?encoding,
+                wav_bytes = wav_bytes.len(),
+                encoded_bytes = encoded.len(),
+                "encoded audio for upload"
+            );
+            encoded
+        }
+        Err(error) => {
+            warn!(encoding = ?encoding, error = %error, "audio encoding failed; uploading WAV");
+            wav_bytes
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_wav_for_upload, pcm16_to_wav_bytes, AudioEncoding};
+
+    #[test]
+    fn encodes_flac_and_falls_back_to_wav_on_unparseable_input() {
+        let samples = (0..1_600)
+            .map(|index| (index % 64) as i16)
+            .collect::<Vec<_>>();
+        let wav_bytes = pcm16_to_wav_bytes(&samples, 16_000, 1).expect("wav should encode");
+
+        let flac = encode_wav_for_upload(wav_bytes.clone(), AudioEncoding::Flac);
+        assert_eq!(AudioEncoding::detect(&flac), AudioEncoding::Flac);
+        assert!(flac.len() < wav_bytes.len());
+
+        let garbage = b"not audio".to_vec();
+        assert_eq!(
+            encode_wav_for_upload(garbage.clone(), AudioEncoding::Flac),
+            garbage
+        );
+        assert_eq!(AudioEncoding::detect(&wav_bytes).file_name(), "audio.wav");
+    }
+}
@@ -0,0 +1,162 @@
+use ogg::{PacketWriteEndInfo, PacketWriter};
+
+use crate::transcription::realtime::resample_pcm16_linear;
+
+// Speech-tuned Opus: 16 kHz input, 20 ms frames at 24 kbps.
+const OPUS_SAMPLE_RATE_HZ: u32 = 16_000;
+#[cfg(target_os = "macos")]
+const OPUS_BITRATE_BPS: i32 = 24_000;
+const FRAME_SAMPLES: usize = 320;
+// Ogg Opus granule positions are always counted at 48 kHz.
+const GRANULE_SAMPLES_PER_INPUT_SAMPLE: u64 = 48_000 / OPUS_SAMPLE_RATE_HZ as u64;
+const OGG_STREAM_SERIAL: u32 = 0x766f_6963;
+
+pub fn encode_ogg_opus(samples: &[i16], sample_rate_hz: u32) -> Result<Vec<u8>, String> {
+    let samples = resample_pcm16_linear(samples, sample_rate_hz, OPUS_SAMPLE_RATE_HZ);
+    let (pre_skip, packets) = encode_packets(&samples)?;
+    write_ogg_opus(&packets, pre_skip, samples.len(), sample_rate_hz)
+}
+
+#[cfg(target_os = "macos")]
+fn encode_packets(samples: &[i16]) -> Result<(u16, Vec<Vec<u8>>), String> {
+    use audiopus::{coder::Encoder, Application, Bitrate, Channels, SampleRate};
+
+    let mut encoder = Encoder::new(SampleRate::Hz16000, Channels::Mono, Application::Voip)
+        .map_err(|error| format!("Failed to create Opus encoder: {error}"))?;
+    encoder
+        .set_bitrate(Bitrate::BitsPerSecond(OPUS_BITRATE_BPS))
+        .map_err(|error| format!("Failed to set Opus bitrate: {error}"))?;
+    let lookahead = encoder
+        .lookahead()
+        .map_err(|error| format!("Failed to read Opus lookahead: {error}"))?;
+    let pre_skip = u16::try_from(u64::from(lookahead) * GRANULE_SAMPLES_PER_INPUT_SAMPLE)
+        .map_err(|_| "Opus encoder lookahead is out of range".to_string())?;
+
+    let mut packets = Vec::with_capacity(samples.len() / FRAME_SAMPLES + 1);
+    let mut output = [0u8; 4000];
+    let mut frame = [0i16; FRAME_SAMPLES];
+    for chunk in samples.chunks(FRAME_SAMPLES) {
+        frame[..chunk.len()].copy_from_slice(chunk);
+        frame[chunk.len()..].fill(0);
+        let packet_len = encoder
+            .encode(&frame, &mut output)
+            .map_err(|error| format!("Failed to encode Opus frame: {error}"))?;
+        packets.push(output[..packet_len].to_vec());
+    }
+
+    Ok((pre_skip, packets))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn encode_packets(_samples: &[i16]) -> Result<(u16, Vec<Vec<u8>>), String> {
+    Err("Opus encoding is only available on macOS".to_string())
+}
+
+fn write_ogg_opus(
+    packets: &[Vec<u8>],
+    pre_skip: u16,
+    total_samples: usize,
+    original_sample_rate_hz: u32,
+) -> Result<Vec<u8>, String> {
+    let mut writer = PacketWriter::new(Vec::new());
+    let write_error = |error: std::io::Error| format!("Failed to write Ogg Opus stream: {error}");
+
+    writer
+        .write_packet(
+            opus_head(pre_skip, original_sample_rate_hz).into_boxed_slice(),
+            OGG_STREAM_SERIAL,
+            PacketWriteEndInfo::EndPage,
+            0,
+        )
+        .map_err(write_error)?;
+    writer
+        .write_packet(
+            opus_tags().into_boxed_slice(),
+            OGG_STREAM_SERIAL,
+            PacketWriteEndInfo::EndPage,
+            0,
+        )
+        .map_err(write_error)?;
+
+    // The final granule position trims the zero padding of the last frame.
+    let final_granule =
+        u64::from(pre_skip) + total_samples as u64 * GRANULE_SAMPLES_PER_INPUT_SAMPLE;
+    for (index, packet) in packets.iter().enumerate() {
+        let is_last = index + 1 == packets.len();
+        let granule = (u64::from(pre_skip)
+            + ((index + 1) * FRAME_SAMPLES) as u64 * GRANULE_SAMPLES_PER_INPUT_SAMPLE)
+            .min(final_granule);
+        let end_info = if is_last {
+            PacketWriteEndInfo::EndStream
+        } else {
+            PacketWriteEndInfo::NormalPacket
+        };
+        writer
+            .write_packet(
+                packet.clone().into_boxed_slice(),
+                OGG_STREAM_SERIAL,
+                end_info,
+                granule,
+            )
+            .map_err(write_error)?;
+    }
+
+    Ok(writer.into_inner())
+}
+
+fn opus_head(pre_skip: u16, original_sample_rate_hz: u32) -> Vec<u8> {
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    head.push(1);
+    head.push(1);
+    head.extend_from_slice(&pre_skip.to_le_bytes());
+    head.extend_from_slice(&original_sample_rate_hz.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes());
+    head.push(0);
+    head
+}
+
+fn opus_tags() -> Vec<u8> {
+    let vendor = b"voice";
+    let mut tags = Vec::with_capacity(16 + vendor.len());
+    tags.extend_from_slice(b"OpusTags");
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor);
+    tags.extend_from_slice(&0u32.to_le_bytes());
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use ogg::PacketReader;
+
+    use super::write_ogg_opus;
+
+    #[test]
+    fn muxes_header_pages_and_trims_final_granule_position() {
+        let packets = vec![vec![0xF8, 1], vec![0xF8, 2], vec![0xF8, 3]];
+
+        let bytes = write_ogg_opus(&packets, 312, 700, 44_100).expect("muxing should succeed");
+        let mut reader = PacketReader::new(std::io::Cursor::new(bytes));
+
+        let head = reader.read_packet_expected().expect("head packet");
+        assert_eq!(&head.data[..8], b"OpusHead");
+        assert_eq!(u16::from_le_bytes([head.data[10], head.data[11]]), 312);
+        assert_eq!(
+            u32::from_le_bytes([head.data[12], head.data[13], head.data[14], head.data[15]]),
+            44_100
+        );
+        assert!(head.last_in_page());
+
+        let tags = reader.read_packet_expected().expect("tags packet");
+        assert_eq!(&tags.data[..8], b"OpusTags");
+        assert!(tags.last_in_page());
+
+        let audio = (0..3)
+            .map(|_| reader.read_packet_expected().expect("audio packet"))
+            .collect::<Vec<_>>();
+        assert_eq!(audio[2].data, vec![0xF8, 3]);
+        assert!(audio[2].last_in_stream());
+        assert_eq!(audio[2].absgp_page(), 312 + 700 * 3);
+    }
+}
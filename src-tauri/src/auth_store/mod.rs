@@ -6,11 +6,15 @@ use std::{
     sync::{Arc, Mutex},
     time::{SystemTime, UNIX_EPOCH},
 };
-use tracing::debug;
+use tracing::{debug, info, warn};
 
 use crate::api_key_store::ApiKeyStore;
+use crate::app_error::{AppError, ErrorCode};
+use crate::keychain;
 
 const AUTH_CREDENTIALS_FILE_NAME: &str = "auth_credentials.json";
+const AUTH_KEYCHAIN_SERVICE: &str = "voice.auth";
+const AUTH_KEYCHAIN_ACCOUNT: &str = "credentials";
 const OPENAI_PROVIDER: &str = "openai";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -31,13 +35,16 @@ impl AuthMethod {
         }
     }
 
-    pub fn parse(value: &str) -> Result<Self, String> {
+    pub fn parse(value: &str) -> Result<Self, AppError> {
         match value.trim().to_lowercase().as_str() {
             "none" => Ok(Self::None),
             "api_key" => Ok(Self::ApiKey),
             "chatgpt_oauth" => Ok(Self::ChatgptOauth),
-            other => Err(format!(
-                "Unsupported auth method `{other}`. Expected `none`, `api_key`, or `chatgpt_oauth`"
+            other => Err(AppError::new(
+                ErrorCode::InvalidInput,
+                format!(
+                    "Unsupported auth method `{other}`. Expected `none`, `api_key`, or `chatgpt_oauth`"
+                ),
             )),
         }
     }
@@ -62,10 +69,21 @@ pub struct ChatGptStoredCredentials {
     pub account_id: String,
 }
 
+// The whole credentials record is one keychain item, stored as the same JSON
+// the file holds.
+#[derive(Debug, Clone)]
+enum CredentialsStorage {
+    File(PathBuf),
+    Keychain,
+}
+
 #[derive(Debug, Clone)]
 pub struct AuthStore {
-    file_path: PathBuf,
+    storage: CredentialsStorage,
     io_lock: Arc<Mutex<()>>,
+    // Refreshing rotates the refresh token, so two overlapping refreshes would
+    // leave one of them holding a revoked token.
+    refresh_lock: Arc<tokio::sync::Mutex<()>>,
 }
 
 impl AuthStore {
@@ -73,24 +91,32 @@ impl AuthStore {
         let file_path = app_data_dir.join(AUTH_CREDENTIALS_FILE_NAME);
         debug!(path = %file_path.display(), "auth store initialized");
         Self {
-            file_path,
+            storage: select_storage(file_path),
             io_lock: Arc::new(Mutex::new(())),
+            refresh_lock: Arc::new(tokio::sync::Mutex::new(())),
         }
     }
 
-    pub fn current(&self) -> Result<AuthCredentials, String> {
+    pub async fn lock_refresh(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.refresh_lock.lock().await
+    }
+
+    pub fn current(&self) -> Result<AuthCredentials, AppError> {
         let _guard = self
             .io_lock
             .lock()
             .map_err(|_| "auth store lock poisoned".to_string())?;
-        self.read_credentials()
+        Ok(self.read_credentials()?)
     }
 
-    pub fn current_auth_method(&self) -> Result<AuthMethod, String> {
+    pub fn current_auth_method(&self) -> Result<AuthMethod, AppError> {
         Ok(self.current()?.auth_method)
     }
 
-    pub fn effective_auth_method(&self, api_key_store: &ApiKeyStore) -> Result<AuthMethod, String> {
+    pub fn effective_auth_method(
+        &self,
+        api_key_store: &ApiKeyStore,
+    ) -> Result<AuthMethod, AppError> {
         let mut credentials = self.current()?;
         if credentials.auth_method == AuthMethod::None
             && api_key_store.has_api_key(OPENAI_PROVIDER)?
@@ -101,14 +127,14 @@ impl AuthStore {
         Ok(credentials.auth_method)
     }
 
-    pub fn set_auth_method(&self, method: AuthMethod) -> Result<AuthCredentials, String> {
+    pub fn set_auth_method(&self, method: AuthMethod) -> Result<AuthCredentials, AppError> {
         self.with_update(|credentials| {
             credentials.auth_method = method;
             Ok(())
         })
     }
 
-    pub fn set_api_key(&self, key: &str) -> Result<AuthCredentials, String> {
+    pub fn set_api_key(&self, key: &str) -> Result<AuthCredentials, AppError> {
         let normalized_key = normalize_required_string(Some(key.to_string()), "api_key")?;
         self.with_update(|credentials| {
             credentials.api_key = Some(normalized_key.clone());
@@ -117,7 +143,7 @@ impl AuthStore {
         })
     }
 
-    pub fn clear_api_key(&self) -> Result<AuthCredentials, String> {
+    pub fn clear_api_key(&self) -> Result<AuthCredentials, AppError> {
         self.with_update(|credentials| {
             credentials.api_key = None;
             if credentials.auth_method == AuthMethod::ApiKey {
@@ -133,7 +159,7 @@ impl AuthStore {
         refresh_token: &str,
         expires_at: u64,
         account_id: &str,
-    ) -> Result<AuthCredentials, String> {
+    ) -> Result<AuthCredentials, AppError> {
         let normalized_access =
             normalize_required_string(Some(access_token.to_string()), "access_token")?;
         let normalized_refresh =
@@ -157,7 +183,7 @@ impl AuthStore {
         refresh_token: &str,
         expires_at: u64,
         account_id: &str,
-    ) -> Result<AuthCredentials, String> {
+    ) -> Result<AuthCredentials, AppError> {
         let normalized_access =
             normalize_required_string(Some(access_token.to_string()), "access_token")?;
         let normalized_refresh =
@@ -177,7 +203,7 @@ impl AuthStore {
         })
     }
 
-    pub fn logout_chatgpt(&self) -> Result<AuthCredentials, String> {
+    pub fn logout_chatgpt(&self) -> Result<AuthCredentials, AppError> {
         self.with_update(|credentials| {
             credentials.access_token = None;
             credentials.refresh_token = None;
@@ -188,14 +214,14 @@ impl AuthStore {
         })
     }
 
-    pub fn chatgpt_credentials(&self) -> Result<Option<ChatGptStoredCredentials>, String> {
+    pub fn chatgpt_credentials(&self) -> Result<Option<ChatGptStoredCredentials>, AppError> {
         let credentials = self.current()?;
         Ok(resolve_chatgpt_credentials(&credentials))
     }
 
-    fn with_update<F>(&self, mut update: F) -> Result<AuthCredentials, String>
+    fn with_update<F>(&self, mut update: F) -> Result<AuthCredentials, AppError>
     where
-        F: FnMut(&mut AuthCredentials) -> Result<(), String>,
+        F: FnMut(&mut AuthCredentials) -> Result<(), AppError>,
     {
         let mut credentials = self.current()?;
         update(&mut credentials)?;
@@ -203,51 +229,112 @@ impl AuthStore {
         Ok(credentials)
     }
 
-    fn ensure_file_exists(&self) -> Result<(), String> {
-        if let Some(parent_dir) = self.file_path.parent() {
-            fs::create_dir_all(parent_dir).map_err(|error| {
-                format!(
-                    "Failed to create auth credentials directory `{}`: {error}",
-                    parent_dir.display()
-                )
-            })?;
+    fn read_credentials(&self) -> Result<AuthCredentials, String> {
+        match &self.storage {
+            CredentialsStorage::File(file_path) => read_credentials_file(file_path),
+            CredentialsStorage::Keychain => {
+                match keychain::get(AUTH_KEYCHAIN_SERVICE, AUTH_KEYCHAIN_ACCOUNT)? {
+                    Some(raw_contents) => parse_credentials(&raw_contents, "keychain item"),
+                    None => Ok(AuthCredentials::default()),
+                }
+            }
         }
+    }
 
-        if self.file_path.exists() {
-            return Ok(());
+    fn write_credentials(&self, credentials: &AuthCredentials) -> Result<(), String> {
+        let serialized = serde_json::to_vec_pretty(credentials)
+            .map_err(|error| format!("Failed to serialize auth credentials: {error}"))?;
+        match &self.storage {
+            CredentialsStorage::File(file_path) => write_atomic_file(file_path, &serialized),
+            CredentialsStorage::Keychain => {
+                keychain::set(AUTH_KEYCHAIN_SERVICE, AUTH_KEYCHAIN_ACCOUNT, &serialized)
+            }
         }
-
-        let serialized = serde_json::to_vec_pretty(&AuthCredentials::default())
-            .map_err(|error| format!("Failed to serialize default auth credentials: {error}"))?;
-        write_atomic_file(&self.file_path, &serialized)
     }
+}
 
-    fn read_credentials(&self) -> Result<AuthCredentials, String> {
-        self.ensure_file_exists()?;
-        let raw_contents = fs::read_to_string(&self.file_path).map_err(|error| {
-            format!(
-                "Failed to read auth credentials file `{}`: {error}",
-                self.file_path.display()
-            )
-        })?;
+// Tests stay on the file so they never touch the user's keychain.
+fn select_storage(file_path: PathBuf) -> CredentialsStorage {
+    if cfg!(test) || !keychain::is_available() {
+        return CredentialsStorage::File(file_path);
+    }
 
-        if raw_contents.trim().is_empty() {
-            return Ok(AuthCredentials::default());
+    let migration = migrate_plaintext_credentials(&file_path, |serialized| {
+        keychain::set(AUTH_KEYCHAIN_SERVICE, AUTH_KEYCHAIN_ACCOUNT, serialized)
+    });
+    match migration {
+        Ok(true) => info!("moved plaintext auth credentials into the keychain"),
+        Ok(false) => {}
+        Err(error) => {
+            warn!(%error, "failed to migrate auth credentials into the keychain; keeping file storage");
+            return CredentialsStorage::File(file_path);
         }
+    }
+    CredentialsStorage::Keychain
+}
+
+// The file is only removed once its contents have been stored elsewhere.
+fn migrate_plaintext_credentials<F>(file_path: &Path, store: F) -> Result<bool, String>
+where
+    F: FnOnce(&[u8]) -> Result<(), String>,
+{
+    if !file_path.exists() {
+        return Ok(false);
+    }
+
+    let credentials = read_credentials_file(file_path)?;
+    let serialized = serde_json::to_vec_pretty(&credentials)
+        .map_err(|error| format!("Failed to serialize auth credentials: {error}"))?;
+    store(&serialized)?;
+    fs::remove_file(file_path).map_err(|error| {
+        format!(
+            "Failed to remove migrated auth credentials file `{}`: {error}",
+            file_path.display()
+        )
+    })?;
+    Ok(true)
+}
+
+fn read_credentials_file(file_path: &Path) -> Result<AuthCredentials, String> {
+    ensure_credentials_file_exists(file_path)?;
+    let raw_contents = fs::read(file_path).map_err(|error| {
+        format!(
+            "Failed to read auth credentials file `{}`: {error}",
+            file_path.display()
+        )
+    })?;
+    parse_credentials(
+        &raw_contents,
+        &format!("auth credentials file `{}`", file_path.display()),
+    )
+}
 
-        serde_json::from_str::<AuthCredentials>(&raw_contents).map_err(|error| {
+fn parse_credentials(raw_contents: &[u8], source: &str) -> Result<AuthCredentials, String> {
+    if raw_contents.trim_ascii().is_empty() {
+        return Ok(AuthCredentials::default());
+    }
+
+    serde_json::from_slice::<AuthCredentials>(raw_contents)
+        .map_err(|error| format!("Failed to parse {source}: {error}"))
+}
+
+fn ensure_credentials_file_exists(file_path: &Path) -> Result<(), String> {
+    if let Some(parent_dir) = file_path.parent() {
+        fs::create_dir_all(parent_dir).map_err(|error| {
             format!(
-                "Failed to parse auth credentials file `{}`: {error}",
-                self.file_path.display()
+                "Failed to create auth credentials directory `{}`: {error}",
+                parent_dir.display()
             )
-        })
+        })?;
     }
 
-    fn write_credentials(&self, credentials: &AuthCredentials) -> Result<(), String> {
-        let serialized = serde_json::to_vec_pretty(credentials)
-            .map_err(|error| format!("Failed to serialize auth credentials: {error}"))?;
-        write_atomic_file(&self.file_path, &serialized)
+    if file_path.exists() {
+        return Ok(());
     }
+
+    let serialized = serde_json::to_vec_pretty(&AuthCredentials::default())
+        .map_err(|error| format!("Failed to serialize default auth credentials: {error}"))?;
+    write_atomic_file(file_path, &serialized)
 }
 
 pub fn now_epoch_seconds() -> u64 {
@@ -271,14 +358,13 @@ fn resolve_chatgpt_credentials(credentials: &AuthCredentials) -> Option<ChatGptS
     })
 }
 
-fn normalize_required_string(value: Option<String>, field_name: &str) -> Result<String, String> {
-    let Some(value) = value else {
-        return Err(format!("Missing required `{field_name}` value"));
-    };
-
-    let trimmed = value.trim();
+fn normalize_required_string(value: Option<String>, field_name: &str) -> Result<String, AppError> {
+    let trimmed = value.as_deref().map(str::trim).unwrap_or_default();
     if trimmed.is_empty() {
-        return Err(format!("Missing required `{field_name}` value"));
+        return Err(AppError::new(
+            ErrorCode::InvalidInput,
+            format!("Missing required `{field_name}` value"),
+        ));
     }
 
     Ok(trimmed.to_string())
@@ -406,4 +492,30 @@ mod tests {
             AuthMethod::ApiKey
         );
     }
+
+    #[test]
+    fn migrates_plaintext_credentials_and_removes_the_file() {
+        let app_data_dir = temp_app_data_dir("keychain-migration");
+        AuthStore::new(app_data_dir.clone())
+            .save_chatgpt_login("access", "refresh", 1234, "acct_1")
+            .expect("oauth login should persist");
+        let file_path = app_data_dir.join(AUTH_CREDENTIALS_FILE_NAME);
+
+        let mut stored = Vec::new();
+        let migrated = migrate_plaintext_credentials(&file_path, |serialized| {
+            stored = serialized.to_vec();
+            Ok(())
+        })
+        .expect("migration should succeed");
+
+        assert!(migrated);
+        assert!(!file_path.exists(), "expected plaintext file to be removed");
+        let credentials =
+            parse_credentials(&stored, "migrated credentials").expect("credentials should parse");
+        assert_eq!(credentials.auth_method, AuthMethod::ChatgptOauth);
+        assert_eq!(credentials.refresh_token.as_deref(), Some("refresh"));
+        assert!(
+            !migrate_plaintext_credentials(&file_path, |_| Ok(())).expect("rerun should succeed")
+        );
+    }
 }
@@ -0,0 +1,254 @@
+use std::{path::PathBuf, sync::Arc};
+
+use futures_util::future::BoxFuture;
+use tauri::AppHandle;
+
+// Apple event codes from `Buzz.sdef`; each command's code is the suite code
+// followed by one of these.
+const EVENT_START_DICTATION: &[u8; 4] = b"Strt";
+const EVENT_STOP_DICTATION: &[u8; 4] = b"Stop";
+const EVENT_TRANSCRIBE_FILE: &[u8; 4] = b"Trnf";
+const EVENT_LAST_TRANSCRIPT: &[u8; 4] = b"Last";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub enum AutomationCommand {
+    StartDictation,
+    // Replies with the transcript once the stopped session finishes, so a
+    // Shortcuts automation can pass it to its next action.
+    StopDictation,
+    TranscribeFile { path: PathBuf },
+    LastTranscript,
+}
+
+impl AutomationCommand {
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    fn from_event_code(event_code: u32, file_path: Option<String>) -> Result<Self, String> {
+        match &event_code.to_be_bytes() {
+            EVENT_START_DICTATION => Ok(Self::StartDictation),
+            EVENT_STOP_DICTATION => Ok(Self::StopDictation),
+            EVENT_TRANSCRIBE_FILE => file_path
+                .filter(|path| !path.trim().is_empty())
+                .map(|path| Self::TranscribeFile {
+                    path: PathBuf::from(path),
+                })
+                .ok_or_else(|| "transcribe file needs an audio file".to_string()),
+            EVENT_LAST_TRANSCRIPT => Ok(Self::LastTranscript),
+            _ => Err(format!(
+                "Unknown automation command `{}`",
+                String::from_utf8_lossy(&event_code.to_be_bytes())
+            )),
+        }
+    }
+}
+
+// The reply text, if the command has one.
+pub type AutomationHandler = Arc<
+    dyn Fn(AutomationCommand) -> BoxFuture<'static, Result<Option<String>, String>> + Send + Sync,
+>;
+
+// Makes the commands in `Buzz.sdef` available to AppleScript, and through its
+// "Run AppleScript" action to Shortcuts. Cocoa scripting looks the command
+// class up by name when the first Apple event arrives, so this only has to
+// run once at startup.
+pub fn install(app: &AppHandle, handler: AutomationHandler) {
+    platform::install(app, handler);
+}
+
+#[cfg(target_os = "macos")]
+#[allow(unexpected_cfgs)]
+mod platform {
+    use std::{
+        ffi::{c_char, CStr, CString},
+        ptr,
+        sync::OnceLock,
+    };
+
+    use objc::{
+        class,
+        declare::ClassDecl,
+        msg_send,
+        runtime::{Object, Sel, BOOL, YES},
+        sel, sel_impl,
+    };
+    use tauri::AppHandle;
+    use tracing::{debug, info, warn};
+
+    use super::{AutomationCommand, AutomationHandler};
+
+    // Named by the `cocoa class` elements in `Buzz.sdef`.
+    const COMMAND_CLASS_NAME: &str = "VoiceScriptCommand";
+    // `errAEEventFailed`, which AppleScript reports with the error string.
+    const SCRIPT_ERROR_EVENT_FAILED: isize = -10_000;
+
+    static BRIDGE: OnceLock<(AppHandle, AutomationHandler)> = OnceLock::new();
+
+    // A suspended command waiting for its reply. Cocoa scripting only touches
+    // it again on the main thread.
+    struct PendingCommand(*mut Object);
+
+    unsafe impl Send for PendingCommand {}
+
+    impl PendingCommand {
+        unsafe fn resume(self, result: Result<Option<String>, String>) {
+            let command = self.0;
+            let reply = match result {
+                Ok(Some(text)) => ns_string(&text),
+                Ok(None) => ptr::null_mut(),
+                Err(error) => {
+                    warn!(%error, "automation command failed");
+                    set_script_error(command, &error);
+                    ptr::null_mut()
+                }
+            };
+            let _: () = msg_send![command, resumeExecutionWithResult: reply];
+            let _: () = msg_send![command, release];
+        }
+    }
+
+    pub(super) fn install(app: &AppHandle, handler: AutomationHandler) {
+        if BRIDGE.set((app.clone(), handler)).is_err() {
+            debug!("automation bridge already installed");
+            return;
+        }
+        let Some(mut decl) = ClassDecl::new(COMMAND_CLASS_NAME, class!(NSScriptCommand)) else {
+            warn!("automation command class is already registered");
+            return;
+        };
+        unsafe {
+            decl.add_method(
+                sel!(performDefaultImplementation),
+                perform_default_implementation as extern "C" fn(&mut Object, Sel) -> *mut Object,
+            );
+        }
+        decl.register();
+        info!("AppleScript automation installed");
+    }
+
+    // Runs on the main thread. The command is suspended so dictation and
+    // transcription can finish without blocking the UI, then resumed with the
+    // handler's reply.
+    extern "C" fn perform_default_implementation(this: &mut Object, _cmd: Sel) -> *mut Object {
+        unsafe {
+            let command: *mut Object = this;
+            let Some((app, handler)) = BRIDGE.get() else {
+                set_script_error(command, "Automation is not available yet");
+                return ptr::null_mut();
+            };
+            let description: *mut Object = msg_send![command, commandDescription];
+            let event_code: u32 = msg_send![description, appleEventCode];
+            let direct_parameter: *mut Object = msg_send![command, directParameter];
+            let automation_command =
+                match AutomationCommand::from_event_code(event_code, file_path(direct_parameter)) {
+                    Ok(automation_command) => automation_command,
+                    Err(error) => {
+                        set_script_error(command, &error);
+                        return ptr::null_mut();
+                    }
+                };
+            info!(?automation_command, "automation command received");
+
+            let _: () = msg_send![command, suspendExecution];
+            let _: *mut Object = msg_send![command, retain];
+            let pending = PendingCommand(command);
+            let reply = handler(automation_command);
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let result = reply.await;
+                let resumed = app.run_on_main_thread(move || unsafe { pending.resume(result) });
+                if let Err(error) = resumed {
+                    warn!(%error, "failed to resume automation command");
+                }
+            });
+            ptr::null_mut()
+        }
+    }
+
+    // Files arrive as `NSURL`s; a plain string is taken as a POSIX path.
+    unsafe fn file_path(value: *mut Object) -> Option<String> {
+        if value.is_null() {
+            return None;
+        }
+        let is_url: BOOL = msg_send![value, isKindOfClass: class!(NSURL)];
+        if is_url == YES {
+            let path: *mut Object = msg_send![value, path];
+            return ns_string_to_string(path);
+        }
+        let is_string: BOOL = msg_send![value, isKindOfClass: class!(NSString)];
+        if is_string == YES {
+            return ns_string_to_string(value);
+        }
+        None
+    }
+
+    unsafe fn set_script_error(command: *mut Object, message: &str) {
+        let _: () = msg_send![command, setScriptErrorNumber: SCRIPT_ERROR_EVENT_FAILED];
+        let _: () = msg_send![command, setScriptErrorString: ns_string(message)];
+    }
+
+    unsafe fn ns_string(value: &str) -> *mut Object {
+        let value = CString::new(value.replace('\0', "")).unwrap_or_default();
+        msg_send![class!(NSString), stringWithUTF8String: value.as_ptr()]
+    }
+
+    unsafe fn ns_string_to_string(value: *mut Object) -> Option<String> {
+        if value.is_null() {
+            return None;
+        }
+        let utf8: *const c_char = msg_send![value, UTF8String];
+        if utf8.is_null() {
+            return None;
+        }
+        Some(CStr::from_ptr(utf8).to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use tauri::AppHandle;
+    use tracing::debug;
+
+    use super::AutomationHandler;
+
+    pub(super) fn install(_app: &AppHandle, _handler: AutomationHandler) {
+        debug!("AppleScript automation is only available on macOS");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::AutomationCommand;
+
+    fn event_code(code: &[u8; 4]) -> u32 {
+        u32::from_be_bytes(*code)
+    }
+
+    #[test]
+    fn apple_event_codes_map_to_commands() {
+        assert_eq!(
+            AutomationCommand::from_event_code(event_code(b"Strt"), None),
+            Ok(AutomationCommand::StartDictation)
+        );
+        assert_eq!(
+            AutomationCommand::from_event_code(event_code(b"Stop"), None),
+            Ok(AutomationCommand::StopDictation)
+        );
+        assert_eq!(
+            AutomationCommand::from_event_code(
+                event_code(b"Trnf"),
+                Some("/Users/me/memo.m4a".to_string())
+            ),
+            Ok(AutomationCommand::TranscribeFile {
+                path: PathBuf::from("/Users/me/memo.m4a")
+            })
+        );
+        assert!(AutomationCommand::from_event_code(event_code(b"Trnf"), None).is_err());
+        assert_eq!(
+            AutomationCommand::from_event_code(event_code(b"Last"), None),
+            Ok(AutomationCommand::LastTranscript)
+        );
+        assert!(AutomationCommand::from_event_code(event_code(b"Quit"), None).is_err());
+    }
+}
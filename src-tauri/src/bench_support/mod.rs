@@ -0,0 +1,16 @@
+// Hot paths exposed to the criterion benchmarks in `benches/`. Only built with
+// the `bench` feature; nothing here is a stable API.
+pub use crate::audio_capture_service::{AudioInputChunk, AudioInputChunkCallback, InputFrameBench};
+pub use crate::redaction::{RedactionAction, RedactionEngine, RedactionEntity, RedactionRule};
+pub use crate::silence_trim::SpeechActivity;
+pub use crate::voice_pipeline::hallucination::detect_silence_hallucination;
+pub use crate::voice_pipeline::progress::DictationProgress;
+pub use crate::word_count::WordCountStrategy;
+
+pub fn pcm16_to_wav_bytes(samples: &[i16], sample_rate_hz: u32) -> Result<Vec<u8>, String> {
+    crate::audio_capture_service::pcm16_to_wav_bytes(samples, sample_rate_hz, 1)
+}
+
+pub fn normalize_transcript_text(raw_text: &str) -> String {
+    crate::transcription::normalize_transcript_text(raw_text)
+}
@@ -0,0 +1,22 @@
+// Writes JSON Schemas for the frontend command and event contracts.
+// Usage: cargo run --bin schema_export [output-dir]
+use std::path::PathBuf;
+
+fn main() {
+    let output_dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("schemas"));
+
+    match tauri_app_lib::schema_export::write_schemas(&output_dir) {
+        Ok(paths) => {
+            for path in paths {
+                println!("{}", path.display());
+            }
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,664 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tauri::async_runtime::JoinHandle;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, oneshot},
+};
+use tokio_tungstenite::{
+    accept_hdr_async,
+    tungstenite::{
+        handshake::server::{ErrorResponse, Request, Response},
+        http::StatusCode,
+        Message,
+    },
+};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+pub const DEFAULT_BRIDGE_PORT: u16 = 47_821;
+const DEFAULT_INSERT_TIMEOUT_MS: u64 = 800;
+const PAIRING_TOKEN_FILE_NAME: &str = "browser_bridge_token";
+// Web pages cannot forge the Origin header, but local processes and any
+// installed extension can, so the origin check only keeps web pages out.
+// Transcripts only go to clients that also present the pairing token.
+const ALLOWED_ORIGIN_PREFIXES: [&str; 3] = [
+    "chrome-extension://",
+    "moz-extension://",
+    "safari-web-extension://",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserBridgeConfig {
+    pub port: u16,
+    pub insert_timeout_ms: u64,
+}
+
+impl Default for BrowserBridgeConfig {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_BRIDGE_PORT,
+            insert_timeout_ms: DEFAULT_INSERT_TIMEOUT_MS,
+        }
+    }
+}
+
+impl BrowserBridgeConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Some(port) = read_u64_env("VOICE_BROWSER_BRIDGE_PORT") {
+            config.port = u16::try_from(port).unwrap_or(DEFAULT_BRIDGE_PORT);
+        }
+
+        if let Some(timeout_ms) = read_u64_env("VOICE_BROWSER_BRIDGE_INSERT_TIMEOUT_MS") {
+            config.insert_timeout_ms = timeout_ms.max(1);
+        }
+
+        debug!(
+            port = config.port,
+            insert_timeout_ms = config.insert_timeout_ms,
+            "loaded browser bridge config"
+        );
+        config
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ExtensionMessage {
+    #[serde(rename_all = "camelCase")]
+    Hello {
+        #[serde(default)]
+        browser: Option<String>,
+        #[serde(default)]
+        version: Option<String>,
+        #[serde(default)]
+        token: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Focus { editable: bool },
+    #[serde(rename_all = "camelCase")]
+    InsertResult {
+        id: String,
+        ok: bool,
+        #[serde(default)]
+        error: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum BridgeMessage<'a> {
+    Insert { id: &'a str, text: &'a str },
+    // Tells the extension whether to ask the user for the pairing token.
+    HelloResult { paired: bool },
+}
+
+#[derive(Debug)]
+struct BridgeClient {
+    id: u64,
+    sender: mpsc::UnboundedSender<String>,
+    paired: bool,
+    editable_focus: bool,
+    focus_sequence: u64,
+}
+
+#[derive(Debug)]
+struct BridgeState {
+    pairing_token: String,
+    clients: Vec<BridgeClient>,
+    pending_inserts: HashMap<String, (u64, oneshot::Sender<bool>)>,
+    next_client_id: u64,
+    next_focus_sequence: u64,
+}
+
+impl BridgeState {
+    fn new(pairing_token: String) -> Self {
+        Self {
+            pairing_token,
+            clients: Vec::new(),
+            pending_inserts: HashMap::new(),
+            next_client_id: 0,
+            next_focus_sequence: 0,
+        }
+    }
+
+    fn register_client(&mut self, sender: mpsc::UnboundedSender<String>) -> u64 {
+        self.next_client_id += 1;
+        self.clients.push(BridgeClient {
+            id: self.next_client_id,
+            sender,
+            paired: false,
+            editable_focus: false,
+            focus_sequence: 0,
+        });
+        self.next_client_id
+    }
+
+    fn remove_client(&mut self, client_id: u64) {
+        self.clients.retain(|client| client.id != client_id);
+        // Dropping the ack senders wakes any insert waiting on this client.
+        self.pending_inserts
+            .retain(|_, (pending_client_id, _)| *pending_client_id != client_id);
+    }
+
+    // Returns whether the client presented this install's token.
+    fn pair(&mut self, client_id: u64, token: Option<&str>) -> bool {
+        let paired = token.is_some_and(|token| tokens_match(token, &self.pairing_token));
+        if let Some(client) = self
+            .clients
+            .iter_mut()
+            .find(|client| client.id == client_id)
+        {
+            client.paired = paired;
+            let reply = serde_json::to_string(&BridgeMessage::HelloResult { paired });
+            if let Ok(reply) = reply {
+                let _ = client.sender.send(reply);
+            }
+        }
+        paired
+    }
+
+    // Focus reports from unpaired clients are ignored, so they can never be
+    // picked to receive a transcript.
+    fn set_focus(&mut self, client_id: u64, editable: bool) {
+        self.next_focus_sequence += 1;
+        let sequence = self.next_focus_sequence;
+        if let Some(client) = self
+            .clients
+            .iter_mut()
+            .find(|client| client.id == client_id && client.paired)
+        {
+            client.editable_focus = editable;
+            client.focus_sequence = sequence;
+        }
+    }
+
+    // The most recent focus report wins when several browsers are connected.
+    fn focused_client(&self) -> Option<&BridgeClient> {
+        self.clients
+            .iter()
+            .filter(|client| client.paired && client.editable_focus)
+            .max_by_key(|client| client.focus_sequence)
+    }
+}
+
+#[derive(Debug)]
+pub struct BrowserBridge {
+    config: BrowserBridgeConfig,
+    pairing_token: String,
+    state: Arc<Mutex<BridgeState>>,
+    listener_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl BrowserBridge {
+    // Without a stored token the extension has to pair again every launch.
+    pub fn new(config: BrowserBridgeConfig) -> Self {
+        debug!(port = config.port, "browser bridge initialized");
+        let pairing_token = new_pairing_token();
+        Self {
+            config,
+            state: Arc::new(Mutex::new(BridgeState::new(pairing_token.clone()))),
+            pairing_token,
+            listener_task: Mutex::new(None),
+        }
+    }
+
+    pub fn with_pairing_token_dir(mut self, app_data_dir: &Path) -> Self {
+        self.pairing_token = load_or_create_pairing_token(app_data_dir);
+        self.state = Arc::new(Mutex::new(BridgeState::new(self.pairing_token.clone())));
+        self
+    }
+
+    // Shown to the user so they can paste it into the extension once.
+    pub fn pairing_token(&self) -> &str {
+        &self.pairing_token
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        let Ok(mut listener_task) = self.listener_task.lock() else {
+            warn!("browser bridge listener lock poisoned");
+            return;
+        };
+
+        match (enabled, listener_task.is_some()) {
+            (true, false) => {
+                let port = self.config.port;
+                let state = self.state.clone();
+                *listener_task = Some(tauri::async_runtime::spawn(async move {
+                    match TcpListener::bind(("127.0.0.1", port)).await {
+                        Ok(listener) => {
+                            info!(port, "browser bridge listening");
+                            serve(listener, state).await;
+                        }
+                        Err(error) => warn!(port, %error, "failed to start browser bridge"),
+                    }
+                }));
+            }
+            (false, true) => {
+                if let Some(task) = listener_task.take() {
+                    task.abort();
+                }
+                if let Ok(mut state) = self.state.lock() {
+                    *state = BridgeState::new(self.pairing_token.clone());
+                }
+                info!("browser bridge stopped");
+            }
+            _ => {}
+        }
+    }
+
+    // Returns false whenever the extension could not confirm the insert so the
+    // caller can fall back to synthetic keystrokes.
+    pub async fn try_insert(&self, text: &str) -> bool {
+        try_insert_with_state(
+            &self.state,
+            text,
+            Duration::from_millis(self.config.insert_timeout_ms),
+        )
+        .await
+    }
+}
+
+async fn try_insert_with_state(state: &Mutex<BridgeState>, text: &str, timeout: Duration) -> bool {
+    let request_id = Uuid::new_v4().to_string();
+    let (ack_tx, ack_rx) = oneshot::channel();
+    {
+        let Ok(mut state) = state.lock() else {
+            warn!("browser bridge state lock poisoned");
+            return false;
+        };
+        let Some(client) = state.focused_client() else {
+            return false;
+        };
+        let client_id = client.id;
+        let payload = match serde_json::to_string(&BridgeMessage::Insert {
+            id: &request_id,
+            text,
+        }) {
+            Ok(payload) => payload,
+            Err(error) => {
+                warn!(%error, "failed to serialize browser bridge insert");
+                return false;
+            }
+        };
+        if client.sender.send(payload).is_err() {
+            return false;
+        }
+        state
+            .pending_inserts
+            .insert(request_id.clone(), (client_id, ack_tx));
+    }
+
+    let inserted = matches!(tokio::time::timeout(timeout, ack_rx).await, Ok(Ok(true)));
+    if let Ok(mut state) = state.lock() {
+        state.pending_inserts.remove(&request_id);
+    }
+    info!(
+        inserted,
+        chars = text.chars().count(),
+        "browser bridge insert completed"
+    );
+    inserted
+}
+
+async fn serve(listener: TcpListener, state: Arc<Mutex<BridgeState>>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle_connection(stream, state.clone()));
+            }
+            Err(error) => {
+                warn!(%error, "browser bridge accept failed");
+            }
+        }
+    }
+}
+
+async fn handle_connection(stream: TcpStream, state: Arc<Mutex<BridgeState>>) {
+    let ws_stream = match accept_hdr_async(stream, check_origin).await {
+        Ok(ws_stream) => ws_stream,
+        Err(error) => {
+            debug!(%error, "browser bridge handshake failed");
+            return;
+        }
+    };
+
+    let (mut ws_writer, mut ws_reader) = ws_stream.split();
+    let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<String>();
+    let Some(client_id) = state
+        .lock()
+        .ok()
+        .map(|mut state| state.register_client(outgoing_tx))
+    else {
+        return;
+    };
+    info!(client_id, "browser extension connected");
+
+    loop {
+        tokio::select! {
+            maybe_outgoing = outgoing_rx.recv() => {
+                let Some(payload) = maybe_outgoing else {
+                    break;
+                };
+                if let Err(error) = ws_writer.send(Message::Text(payload.into())).await {
+                    warn!(client_id, %error, "failed to send browser bridge message");
+                    break;
+                }
+            }
+            maybe_message = ws_reader.next() => {
+                let Some(Ok(message)) = maybe_message else {
+                    break;
+                };
+                match message {
+                    Message::Text(text) => handle_extension_message(&state, client_id, text.as_ref()),
+                    Message::Ping(payload) => {
+                        let _ = ws_writer.send(Message::Pong(payload)).await;
+                    }
+                    Message::Close(_) => break,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    if let Ok(mut state) = state.lock() {
+        state.remove_client(client_id);
+    }
+    info!(client_id, "browser extension disconnected");
+}
+
+// The error type is dictated by tungstenite's handshake callback.
+#[allow(clippy::result_large_err)]
+fn check_origin(request: &Request, response: Response) -> Result<Response, ErrorResponse> {
+    let origin = request
+        .headers()
+        .get("origin")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if is_allowed_origin(origin) {
+        Ok(response)
+    } else {
+        warn!(origin, "rejected browser bridge connection");
+        let mut rejection = ErrorResponse::new(Some("Origin not allowed".to_string()));
+        *rejection.status_mut() = StatusCode::FORBIDDEN;
+        Err(rejection)
+    }
+}
+
+fn handle_extension_message(state: &Mutex<BridgeState>, client_id: u64, raw_message: &str) {
+    let message = match serde_json::from_str::<ExtensionMessage>(raw_message) {
+        Ok(message) => message,
+        Err(error) => {
+            debug!(client_id, %error, "ignoring unrecognized browser bridge message");
+            return;
+        }
+    };
+    let Ok(mut state) = state.lock() else {
+        return;
+    };
+
+    match message {
+        ExtensionMessage::Hello {
+            browser,
+            version,
+            token,
+        } => {
+            let paired = state.pair(client_id, token.as_deref());
+            if paired {
+                info!(client_id, ?browser, ?version, "browser extension paired");
+            } else {
+                warn!(
+                    client_id,
+                    ?browser,
+                    ?version,
+                    "browser extension presented a missing or wrong pairing token"
+                );
+            }
+        }
+        ExtensionMessage::Focus { editable } => {
+            debug!(client_id, editable, "browser extension focus changed");
+            state.set_focus(client_id, editable);
+        }
+        ExtensionMessage::InsertResult { id, ok, error } => {
+            if let Some(error) = error.as_deref() {
+                warn!(client_id, %error, "browser extension insert failed");
+            }
+            if let Some((_, ack_tx)) = state.pending_inserts.remove(&id) {
+                let _ = ack_tx.send(ok);
+            }
+        }
+    }
+}
+
+fn is_allowed_origin(origin: &str) -> bool {
+    ALLOWED_ORIGIN_PREFIXES
+        .iter()
+        .any(|prefix| origin.starts_with(prefix) && origin.len() > prefix.len())
+}
+
+fn new_pairing_token() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+// Compares every byte so the time taken does not reveal how much of a guess
+// was right.
+fn tokens_match(candidate: &str, expected: &str) -> bool {
+    candidate.len() == expected.len()
+        && candidate
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |difference, (left, right)| difference | (left ^ right))
+            == 0
+}
+
+// Falls back to a token for this launch only when the file cannot be read or
+// written.
+fn load_or_create_pairing_token(app_data_dir: &Path) -> String {
+    let file_path = app_data_dir.join(PAIRING_TOKEN_FILE_NAME);
+    if let Ok(stored) = fs::read_to_string(&file_path) {
+        let stored = stored.trim();
+        if !stored.is_empty() {
+            return stored.to_string();
+        }
+    }
+
+    let token = new_pairing_token();
+    let written = fs::create_dir_all(app_data_dir)
+        .and_then(|()| fs::write(&file_path, &token))
+        .and_then(|()| restrict_to_owner(&file_path));
+    if let Err(error) = written {
+        warn!(%error, "failed to persist browser bridge pairing token");
+    }
+    token
+}
+
+#[cfg(unix)]
+fn restrict_to_owner(file_path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(file_path, fs::Permissions::from_mode(0o600))
+}
+
+#[cfg(not(unix))]
+fn restrict_to_owner(_file_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+fn read_u64_env(name: &str) -> Option<u64> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .and_then(|value| value.parse::<u64>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::{json, Value};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_tungstenite::{
+        connect_async,
+        tungstenite::{client::IntoClientRequest, http::HeaderValue, Message},
+        MaybeTlsStream, WebSocketStream,
+    };
+
+    use super::{
+        is_allowed_origin, load_or_create_pairing_token, serve, try_insert_with_state, BridgeState,
+    };
+
+    const TEST_TOKEN: &str = "test-pairing-token";
+
+    type ExtensionSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    async fn connect_extension(url: &str) -> ExtensionSocket {
+        let mut request = url.into_client_request().expect("valid request");
+        request.headers_mut().insert(
+            "origin",
+            HeaderValue::from_static("chrome-extension://voice"),
+        );
+        let (extension, _) = connect_async(request)
+            .await
+            .expect("extension should connect");
+        extension
+    }
+
+    async fn send_json(extension: &mut ExtensionSocket, message: Value) {
+        extension
+            .send(Message::Text(message.to_string().into()))
+            .await
+            .expect("message should send");
+    }
+
+    #[test]
+    fn only_extension_origins_are_allowed() {
+        assert!(is_allowed_origin("chrome-extension://abcdef"));
+        assert!(is_allowed_origin("moz-extension://1234-5678"));
+        assert!(!is_allowed_origin("chrome-extension://"));
+        assert!(!is_allowed_origin("https://example.com"));
+        assert!(!is_allowed_origin(""));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn inserts_through_focused_extension_and_rejects_web_origins() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let address = listener.local_addr().expect("listener should have address");
+        let state = Arc::new(Mutex::new(BridgeState::new(TEST_TOKEN.to_string())));
+        tokio::spawn(serve(listener, state.clone()));
+        let url = format!("ws://{address}");
+
+        let mut web_request = url.clone().into_client_request().expect("valid request");
+        web_request
+            .headers_mut()
+            .insert("origin", HeaderValue::from_static("https://example.com"));
+        assert!(connect_async(web_request).await.is_err());
+
+        let mut extension = connect_extension(&url).await;
+        send_json(
+            &mut extension,
+            json!({ "type": "hello", "browser": "chrome", "token": TEST_TOKEN }),
+        )
+        .await;
+        let Some(Ok(Message::Text(text))) = extension.next().await else {
+            panic!("extension should receive a hello result");
+        };
+        let hello: Value = serde_json::from_str(text.as_ref()).expect("reply should be json");
+        assert_eq!(hello, json!({ "type": "helloResult", "paired": true }));
+        send_json(&mut extension, json!({ "type": "focus", "editable": true })).await;
+        while state
+            .lock()
+            .map(|state| state.focused_client().is_none())
+            .unwrap_or(true)
+        {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let insert_state = state.clone();
+        let insert = tokio::spawn(async move {
+            try_insert_with_state(&insert_state, "hello browser", Duration::from_secs(5)).await
+        });
+
+        let Some(Ok(Message::Text(text))) = extension.next().await else {
+            panic!("extension should receive an insert request");
+        };
+        let payload: Value = serde_json::from_str(text.as_ref()).expect("insert should be json");
+        assert_eq!(payload["type"], "insert");
+        assert_eq!(payload["text"], "hello browser");
+        extension
+            .send(Message::Text(
+                json!({ "type": "insertResult", "id": payload["id"], "ok": true })
+                    .to_string()
+                    .into(),
+            ))
+            .await
+            .expect("ack should send");
+
+        assert!(insert.await.expect("insert task should join"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn unpaired_extension_never_receives_transcripts() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let address = listener.local_addr().expect("listener should have address");
+        let state = Arc::new(Mutex::new(BridgeState::new(TEST_TOKEN.to_string())));
+        tokio::spawn(serve(listener, state.clone()));
+        let url = format!("ws://{address}");
+
+        let mut impostor = connect_extension(&url).await;
+        send_json(
+            &mut impostor,
+            json!({ "type": "hello", "token": "guessed-token" }),
+        )
+        .await;
+        let Some(Ok(Message::Text(text))) = impostor.next().await else {
+            panic!("impostor should receive a hello result");
+        };
+        let hello: Value = serde_json::from_str(text.as_ref()).expect("reply should be json");
+        assert_eq!(hello["paired"], false);
+        send_json(&mut impostor, json!({ "type": "focus", "editable": true })).await;
+        // Focus is handled in order after hello, so a ping round trip is
+        // enough to know it has been processed.
+        impostor
+            .send(Message::Ping(Vec::new().into()))
+            .await
+            .expect("ping should send");
+        let Some(Ok(Message::Pong(_))) = impostor.next().await else {
+            panic!("impostor should receive a pong");
+        };
+
+        assert!(state
+            .lock()
+            .map(|state| state.focused_client().is_none())
+            .unwrap_or(false));
+        assert!(!try_insert_with_state(&state, "secret words", Duration::from_millis(50)).await);
+    }
+
+    #[test]
+    fn pairing_token_is_created_once_per_install() {
+        let test_dir =
+            std::env::temp_dir().join(format!("voice-bridge-token-{}", uuid::Uuid::new_v4()));
+        let token = load_or_create_pairing_token(&test_dir);
+        assert_eq!(token.len(), 32);
+        assert_eq!(load_or_create_pairing_token(&test_dir), token);
+        let _ = std::fs::remove_dir_all(test_dir);
+    }
+}
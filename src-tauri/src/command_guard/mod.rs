@@ -0,0 +1,111 @@
+use std::sync::{Mutex, PoisonError};
+
+use tracing::debug;
+
+use crate::app_error::{AppError, ErrorCode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandResource {
+    // Settings file plus the OS state mirrored from it (hotkey registration,
+    // launch at login).
+    Settings,
+    // API key file and auth store, which are written together.
+    Credentials,
+}
+
+// Several windows can invoke the same commands at once. Commands that
+// read-modify-write a resource run one at a time per resource; unrelated
+// resources do not wait on each other.
+#[derive(Debug, Default)]
+pub struct CommandGuard {
+    settings: Mutex<()>,
+    credentials: Mutex<()>,
+}
+
+impl CommandGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn run<T, E>(
+        &self,
+        resource: CommandResource,
+        command: &str,
+        operation: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E> {
+        let lock = match resource {
+            CommandResource::Settings => &self.settings,
+            CommandResource::Credentials => &self.credentials,
+        };
+        // A panic in an earlier command leaves nothing half-written behind
+        // the lock itself, so keep serving rather than failing every call.
+        let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+        debug!(?resource, command, "running serialized command");
+        operation()
+    }
+}
+
+// A caller's view of the settings is stale; the conflict code lets the UI
+// tell that apart from a validation failure.
+pub fn check_revision(expected: Option<u64>, current: u64) -> Result<(), AppError> {
+    match expected {
+        Some(expected) if expected != current => Err(AppError::new(
+            ErrorCode::Conflict,
+            format!(
+                "Settings changed since revision {expected} (now {current}); reload and try again"
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
+    use super::{check_revision, CommandGuard, CommandResource};
+    use crate::app_error::{AppError, ErrorCode};
+
+    #[test]
+    fn serializes_commands_per_resource_and_rejects_stale_revisions() {
+        let guard = Arc::new(CommandGuard::new());
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        let handles = (0..4)
+            .map(|_| {
+                let guard = Arc::clone(&guard);
+                let running = Arc::clone(&running);
+                let max_running = Arc::clone(&max_running);
+                thread::spawn(move || {
+                    guard.run(CommandResource::Settings, "apply_settings", || {
+                        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_running.fetch_max(now, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(10));
+                        running.fetch_sub(1, Ordering::SeqCst);
+                        Ok::<(), AppError>(())
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle
+                .join()
+                .expect("thread should finish")
+                .expect("command should succeed");
+        }
+        assert_eq!(max_running.load(Ordering::SeqCst), 1);
+
+        assert_eq!(check_revision(None, 7), Ok(()));
+        assert_eq!(check_revision(Some(7), 7), Ok(()));
+        let conflict = check_revision(Some(6), 7).expect_err("stale revision should conflict");
+        assert_eq!(conflict.code, ErrorCode::Conflict);
+    }
+}
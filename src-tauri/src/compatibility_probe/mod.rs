@@ -0,0 +1,265 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+const COMPATIBILITY_FILE_NAME: &str = "compatibility.json";
+// Any HTTP response, including 401, proves the API host is reachable.
+const REACHABILITY_URL: &str = "https://api.openai.com/v1/models";
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CompatibilityFeature {
+    Recording,
+    FnHotkey,
+    Realtime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityWarning {
+    pub feature: CompatibilityFeature,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityReport {
+    pub probed_at: String,
+    pub app_version: String,
+    pub os_version: Option<String>,
+    pub microphone_available: bool,
+    pub event_tap_available: bool,
+    pub network_reachable: bool,
+    pub warnings: Vec<CompatibilityWarning>,
+}
+
+impl CompatibilityReport {
+    fn new(
+        app_version: &str,
+        os_version: Option<String>,
+        microphone_available: bool,
+        event_tap_available: bool,
+        network_reachable: bool,
+    ) -> Self {
+        let mut warnings = Vec::new();
+        if !microphone_available {
+            warnings.push(CompatibilityWarning {
+                feature: CompatibilityFeature::Recording,
+                message: "No microphone was found. Connect one before recording.".to_string(),
+            });
+        }
+        if !event_tap_available {
+            warnings.push(CompatibilityWarning {
+                feature: CompatibilityFeature::FnHotkey,
+                message: "Voice can't listen for the Fn key on this Mac. Allow Input Monitoring or pick a hotkey without Fn.".to_string(),
+            });
+        }
+        if !network_reachable {
+            warnings.push(CompatibilityWarning {
+                feature: CompatibilityFeature::Realtime,
+                message: "OpenAI couldn't be reached. Check your connection or proxy; realtime transcription will not work until it can.".to_string(),
+            });
+        }
+
+        Self {
+            probed_at: chrono::Utc::now().to_rfc3339(),
+            app_version: app_version.to_string(),
+            os_version,
+            microphone_available,
+            event_tap_available,
+            network_reachable,
+            warnings,
+        }
+    }
+}
+
+// Runs once on first launch and keeps the result on disk so onboarding can
+// warn about unsupported setups without re-probing the network every time.
+#[derive(Debug)]
+pub struct CompatibilityProbe {
+    file_path: PathBuf,
+    report: Mutex<Option<CompatibilityReport>>,
+}
+
+impl CompatibilityProbe {
+    pub fn new(app_data_dir: &Path) -> Self {
+        let file_path = app_data_dir.join(COMPATIBILITY_FILE_NAME);
+        let report = load_report(&file_path);
+        debug!(
+            path = %file_path.display(),
+            has_report = report.is_some(),
+            "compatibility probe initialized"
+        );
+        Self {
+            file_path,
+            report: Mutex::new(report),
+        }
+    }
+
+    pub fn report(&self) -> Option<CompatibilityReport> {
+        self.report
+            .lock()
+            .map(|report| report.clone())
+            .unwrap_or_else(|_| {
+                warn!("compatibility report lock poisoned while reading report");
+                None
+            })
+    }
+
+    pub async fn run(
+        &self,
+        app_version: &str,
+        microphone_available: bool,
+    ) -> Result<CompatibilityReport, String> {
+        let report = CompatibilityReport::new(
+            app_version,
+            os_version(),
+            microphone_available,
+            event_tap_available(),
+            network_reachable().await,
+        );
+        info!(
+            os_version = ?report.os_version,
+            microphone_available = report.microphone_available,
+            event_tap_available = report.event_tap_available,
+            network_reachable = report.network_reachable,
+            warnings = report.warnings.len(),
+            "compatibility probe completed"
+        );
+
+        self.persist(&report)?;
+        if let Ok(mut stored) = self.report.lock() {
+            *stored = Some(report.clone());
+        }
+        Ok(report)
+    }
+
+    fn persist(&self, report: &CompatibilityReport) -> Result<(), String> {
+        if let Some(parent_dir) = self.file_path.parent() {
+            fs::create_dir_all(parent_dir)
+                .map_err(|error| format!("Failed to create compatibility directory: {error}"))?;
+        }
+        let serialized = serde_json::to_vec_pretty(report)
+            .map_err(|error| format!("Failed to serialize compatibility report: {error}"))?;
+        fs::write(&self.file_path, serialized)
+            .map_err(|error| format!("Failed to write compatibility report: {error}"))
+    }
+}
+
+fn load_report(file_path: &Path) -> Option<CompatibilityReport> {
+    let raw_contents = fs::read_to_string(file_path).ok()?;
+    match serde_json::from_str(&raw_contents) {
+        Ok(report) => Some(report),
+        Err(error) => {
+            warn!(path = %file_path.display(), %error, "ignoring malformed compatibility report");
+            None
+        }
+    }
+}
+
+async fn network_reachable() -> bool {
+    match crate::http_client::shared_client()
+        .get(REACHABILITY_URL)
+        .timeout(REACHABILITY_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(response) => {
+            debug!(
+                status = response.status().as_u16(),
+                "reachability check answered"
+            );
+            true
+        }
+        Err(error) => {
+            warn!(%error, "reachability check failed");
+            false
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn os_version() -> Option<String> {
+    let output = std::process::Command::new("sw_vers")
+        .arg("-productVersion")
+        .output()
+        .ok()?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !version.is_empty()).then_some(version)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn os_version() -> Option<String> {
+    None
+}
+
+// Listen-only event taps (needed for Fn hotkeys) require Input Monitoring
+// access; the preflight check never prompts.
+#[cfg(target_os = "macos")]
+fn event_tap_available() -> bool {
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGPreflightListenEventAccess() -> bool;
+    }
+
+    unsafe { CGPreflightListenEventAccess() }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn event_tap_available() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::{CompatibilityFeature, CompatibilityProbe, CompatibilityReport};
+
+    #[test]
+    fn warns_per_missing_capability_and_reloads_persisted_report() {
+        let healthy = CompatibilityReport::new("1.0.0", Some("14.5".to_string()), true, true, true);
+        assert!(healthy.warnings.is_empty());
+
+        let degraded = CompatibilityReport::new("1.0.0", None, false, false, false);
+        assert_eq!(
+            degraded
+                .warnings
+                .iter()
+                .map(|warning| warning.feature)
+                .collect::<Vec<_>>(),
+            vec![
+                CompatibilityFeature::Recording,
+                CompatibilityFeature::FnHotkey,
+                CompatibilityFeature::Realtime,
+            ]
+        );
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after epoch")
+            .as_nanos();
+        let app_data_dir = std::env::temp_dir().join(format!("voice-compatibility-{nanos}"));
+        let probe = CompatibilityProbe::new(&app_data_dir);
+        assert_eq!(probe.report(), None);
+
+        probe.persist(&degraded).expect("report should persist");
+        assert_eq!(
+            CompatibilityProbe::new(&app_data_dir).report(),
+            Some(degraded)
+        );
+
+        let _ = fs::remove_dir_all(app_data_dir);
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    fs,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tauri::async_runtime::JoinHandle;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+};
+use tracing::{debug, info, warn};
+
+use crate::status_notifier::AppStatus;
+
+const CONTROL_SOCKET_DIR_NAME: &str = "control";
+const CONTROL_SOCKET_FILE_NAME: &str = "control.sock";
+// Only the user running the app may connect; the permissions are the
+// authentication. The socket lives in its own private directory, so it is
+// never reachable by others, even between `bind` and the chmod.
+const CONTROL_SOCKET_DIR_MODE: u32 = 0o700;
+const CONTROL_SOCKET_MODE: u32 = 0o600;
+const ACCEPT_RETRY_MIN: Duration = Duration::from_millis(50);
+const ACCEPT_RETRY_MAX: Duration = Duration::from_secs(5);
+
+pub fn socket_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir
+        .join(CONTROL_SOCKET_DIR_NAME)
+        .join(CONTROL_SOCKET_FILE_NAME)
+}
+
+// One JSON object per line, e.g. `{"command":"start"}` or
+// `{"command":"transcribeFile","path":"/tmp/memo.wav"}`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase")]
+pub enum ControlRequest {
+    Start,
+    Stop,
+    Cancel,
+    Status,
+    TranscribeFile { path: PathBuf },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlResponse {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<AppStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ControlResponse {
+    pub fn status(status: AppStatus) -> Self {
+        Self {
+            ok: true,
+            status: Some(status),
+            ..Self::default()
+        }
+    }
+
+    pub fn text(text: String) -> Self {
+        Self {
+            ok: true,
+            text: Some(text),
+            ..Self::default()
+        }
+    }
+
+    pub fn error(message: String) -> Self {
+        Self {
+            ok: false,
+            error: Some(message),
+            ..Self::default()
+        }
+    }
+}
+
+pub type ControlHandler =
+    Arc<dyn Fn(ControlRequest) -> BoxFuture<'static, ControlResponse> + Send + Sync>;
+
+// Local control socket so scripts and launchers (Raycast, Alfred, Keyboard
+// Maestro) can drive dictation without the GUI.
+#[derive(Debug)]
+pub struct ControlServer {
+    socket_path: PathBuf,
+    listener_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ControlServer {
+    pub fn new(socket_path: PathBuf) -> Self {
+        Self {
+            socket_path,
+            listener_task: Mutex::new(None),
+        }
+    }
+
+    pub fn set_enabled(&self, enabled: bool, handler: ControlHandler) {
+        let Ok(mut listener_task) = self.listener_task.lock() else {
+            warn!("control server listener lock poisoned");
+            return;
+        };
+
+        match (enabled, listener_task.is_some()) {
+            (true, false) => match bind(&self.socket_path) {
+                Ok(listener) => {
+                    info!(path = %self.socket_path.display(), "control server listening");
+                    *listener_task = Some(tauri::async_runtime::spawn(serve(listener, handler)));
+                }
+                Err(error) => warn!(%error, "failed to start control server"),
+            },
+            (false, true) => {
+                if let Some(task) = listener_task.take() {
+                    task.abort();
+                }
+                let _ = fs::remove_file(&self.socket_path);
+                info!("control server stopped");
+            }
+            _ => {}
+        }
+    }
+}
+
+// A leftover socket from a crashed run is replaced, but one another instance
+// is still answering on is left alone.
+fn bind(socket_path: &Path) -> Result<UnixListener, String> {
+    if let Some(parent_dir) = socket_path.parent() {
+        fs::create_dir_all(parent_dir)
+            .map_err(|error| format!("Failed to create control socket directory: {error}"))?;
+        fs::set_permissions(
+            parent_dir,
+            fs::Permissions::from_mode(CONTROL_SOCKET_DIR_MODE),
+        )
+        .map_err(|error| format!("Failed to restrict control socket directory: {error}"))?;
+    }
+    if socket_path.exists() {
+        if std::os::unix::net::UnixStream::connect(socket_path).is_ok() {
+            return Err(format!(
+                "Another process is already listening on {}",
+                socket_path.display()
+            ));
+        }
+        fs::remove_file(socket_path)
+            .map_err(|error| format!("Failed to remove stale control socket: {error}"))?;
+    }
+    let listener = UnixListener::bind(socket_path)
+        .map_err(|error| format!("Failed to bind control socket: {error}"))?;
+    fs::set_permissions(socket_path, fs::Permissions::from_mode(CONTROL_SOCKET_MODE))
+        .map_err(|error| format!("Failed to restrict control socket permissions: {error}"))?;
+    Ok(listener)
+}
+
+// Accept errors such as running out of file descriptors tend to repeat, so
+// each retry waits longer, up to a cap.
+async fn serve(listener: UnixListener, handler: ControlHandler) {
+    let mut retry_delay = ACCEPT_RETRY_MIN;
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                retry_delay = ACCEPT_RETRY_MIN;
+                tokio::spawn(handle_connection(stream, handler.clone()));
+            }
+            Err(error) => {
+                warn!(%error, ?retry_delay, "control server accept failed");
+                tokio::time::sleep(retry_delay).await;
+                retry_delay = (retry_delay * 2).min(ACCEPT_RETRY_MAX);
+            }
+        }
+    }
+}
+
+async fn handle_connection(stream: UnixStream, handler: ControlHandler) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(error) => {
+                debug!(%error, "control connection read failed");
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<ControlRequest>(&line) {
+            Ok(request) => handler(request).await,
+            Err(error) => ControlResponse::error(format!("Invalid control request: {error}")),
+        };
+        let mut payload = serde_json::to_string(&response).unwrap_or_else(|error| {
+            format!(r#"{{"ok":false,"error":"Failed to encode response: {error}"}}"#)
+        });
+        payload.push('\n');
+        if let Err(error) = writer.write_all(payload.as_bytes()).await {
+            debug!(%error, "control connection write failed");
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{os::unix::fs::PermissionsExt, sync::Arc};
+
+    use serde_json::Value;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
+        net::{unix::OwnedReadHalf, UnixStream},
+    };
+
+    use super::{bind, serve, socket_path, ControlHandler, ControlRequest, ControlResponse};
+    use crate::status_notifier::AppStatus;
+
+    async fn next_reply(replies: &mut Lines<BufReader<OwnedReadHalf>>) -> Value {
+        let line = replies
+            .next_line()
+            .await
+            .expect("reply should read")
+            .expect("reply should arrive");
+        serde_json::from_str(&line).expect("reply should be json")
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn answers_line_delimited_requests_over_the_socket() {
+        let test_dir = std::env::temp_dir().join(format!("voice-ctl-{}", uuid::Uuid::new_v4()));
+        let socket_path = socket_path(&test_dir);
+        let handler: ControlHandler = Arc::new(|request| {
+            Box::pin(async move {
+                match request {
+                    ControlRequest::Status => ControlResponse::status(AppStatus::Idle),
+                    ControlRequest::TranscribeFile { path } => {
+                        ControlResponse::text(path.display().to_string())
+                    }
+                    _ => ControlResponse::error("unsupported".to_string()),
+                }
+            })
+        });
+        tokio::spawn(serve(
+            bind(&socket_path).expect("socket should bind"),
+            handler,
+        ));
+        assert!(bind(&socket_path).is_err());
+        let socket_dir_mode = std::fs::metadata(socket_path.parent().expect("socket has a dir"))
+            .expect("socket dir should exist")
+            .permissions()
+            .mode();
+        assert_eq!(socket_dir_mode & 0o777, 0o700);
+
+        let stream = UnixStream::connect(&socket_path)
+            .await
+            .expect("client should connect");
+        let (reader, mut writer) = stream.into_split();
+        let mut replies = BufReader::new(reader).lines();
+        writer
+            .write_all(
+                b"{\"command\":\"status\"}\n\n{\"command\":\"transcribeFile\",\"path\":\"/tmp/a.wav\"}\nnope\n",
+            )
+            .await
+            .expect("requests should send");
+
+        let status = next_reply(&mut replies).await;
+        assert_eq!(status["ok"], true);
+        assert_eq!(status["status"], "idle");
+        assert!(status.get("error").is_none());
+        assert_eq!(next_reply(&mut replies).await["text"], "/tmp/a.wav");
+        let invalid = next_reply(&mut replies).await;
+        assert_eq!(invalid["ok"], false);
+        assert!(invalid["error"]
+            .as_str()
+            .is_some_and(|error| error.starts_with("Invalid control request")));
+
+        let _ = std::fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,260 @@
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    fs,
+    panic::{self, PanicHookInfo},
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+    thread,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+const CRASH_REPORTS_DIR_NAME: &str = "crash_reports";
+// Enough to see a pattern; a crash loop should not fill the disk.
+const MAX_CRASH_REPORTS: usize = 20;
+
+static LAST_PIPELINE_STAGE: Mutex<Option<String>> = Mutex::new(None);
+static INSTALLED: OnceLock<()> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    pub created_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub last_pipeline_stage: Option<String>,
+    #[serde(default)]
+    pub uploaded_at: Option<String>,
+}
+
+impl CrashReport {
+    fn from_panic(info: &PanicHookInfo<'_>, app_version: &str) -> Self {
+        let current = thread::current();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            app_version: app_version.to_string(),
+            os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+            thread: current
+                .name()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{:?}", current.id())),
+            message: panic_message(info.payload()),
+            location: info
+                .location()
+                .map(|location| format!("{}:{}", location.file(), location.line())),
+            backtrace: Backtrace::force_capture().to_string(),
+            last_pipeline_stage: last_pipeline_stage(),
+            uploaded_at: None,
+        }
+    }
+}
+
+pub fn crash_reports_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(CRASH_REPORTS_DIR_NAME)
+}
+
+// Chains onto the default hook, so panics still reach stderr and abort
+// behaviour is unchanged; this only leaves a report behind. Covers every
+// thread, including audio workers whose panics otherwise surface only as a
+// failed `join`.
+pub fn install(app_data_dir: &Path, app_version: &str) {
+    if INSTALLED.set(()).is_err() {
+        return;
+    }
+    let dir = crash_reports_dir(app_data_dir);
+    if let Err(error) = prune_reports(&dir, MAX_CRASH_REPORTS) {
+        warn!(%error, "failed to prune crash reports");
+    }
+
+    let app_version = app_version.to_string();
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let report = CrashReport::from_panic(info, &app_version);
+        // No tracing here: the panic may have happened while the log writer
+        // held its lock.
+        match write_report(&dir, &report) {
+            Ok(path) => eprintln!("crash report written to {}", path.display()),
+            Err(error) => eprintln!("failed to write crash report: {error}"),
+        }
+        previous_hook(info);
+    }));
+    info!("crash reporter installed");
+}
+
+// Called on every status change and pipeline error so a report can say what
+// the app was doing when it went down.
+pub fn record_pipeline_stage(stage: &str) {
+    let mut last_stage = LAST_PIPELINE_STAGE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if last_stage.as_deref() != Some(stage) {
+        *last_stage = Some(stage.to_string());
+    }
+}
+
+fn last_pipeline_stage() -> Option<String> {
+    LAST_PIPELINE_STAGE
+        .try_lock()
+        .ok()
+        .and_then(|stage| stage.clone())
+}
+
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panic with a non-string payload".to_string())
+}
+
+fn write_report(dir: &Path, report: &CrashReport) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir)
+        .map_err(|error| format!("Failed to create crash report directory: {error}"))?;
+    let path = dir.join(format!("{}.json", report.id));
+    let serialized = serde_json::to_vec_pretty(report)
+        .map_err(|error| format!("Failed to serialize crash report: {error}"))?;
+    fs::write(&path, serialized)
+        .map_err(|error| format!("Failed to write crash report: {error}"))?;
+    Ok(path)
+}
+
+// Newest first; unreadable files are skipped rather than failing the list.
+pub fn list_crash_reports(dir: &Path) -> Result<Vec<CrashReport>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(format!("Failed to read crash reports: {error}")),
+    };
+    let mut reports = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .filter_map(|path| {
+            let report = fs::read(&path)
+                .map_err(|error| error.to_string())
+                .and_then(|contents| {
+                    serde_json::from_slice::<CrashReport>(&contents)
+                        .map_err(|error| error.to_string())
+                });
+            match report {
+                Ok(report) => Some(report),
+                Err(error) => {
+                    warn!(path = %path.display(), %error, "skipping unreadable crash report");
+                    None
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    reports.sort_by(|left, right| right.created_at.cmp(&left.created_at));
+    Ok(reports)
+}
+
+fn prune_reports(dir: &Path, keep: usize) -> Result<(), String> {
+    for report in list_crash_reports(dir)?.into_iter().skip(keep) {
+        let _ = fs::remove_file(dir.join(format!("{}.json", report.id)));
+    }
+    Ok(())
+}
+
+// Sends reports that have not been uploaded yet, one request each, and marks
+// them so they are sent only once. Only runs when the user opted in.
+pub async fn upload_pending(dir: &Path, url: &str) -> Result<usize, String> {
+    let mut uploaded = 0;
+    for mut report in list_crash_reports(dir)? {
+        if report.uploaded_at.is_some() {
+            continue;
+        }
+        let response = crate::http_client::shared_client()
+            .post(url)
+            .json(&report)
+            .send()
+            .await
+            .map_err(|error| format!("Failed to upload crash report: {error}"))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Crash report upload was rejected with status {}",
+                response.status()
+            ));
+        }
+        report.uploaded_at = Some(chrono::Utc::now().to_rfc3339());
+        write_report(dir, &report)?;
+        uploaded += 1;
+    }
+    if uploaded > 0 {
+        info!(uploaded, "crash reports uploaded");
+    }
+    Ok(uploaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list_crash_reports, panic_message, prune_reports, write_report, CrashReport};
+
+    fn report(id: &str, created_at: &str) -> CrashReport {
+        CrashReport {
+            id: id.to_string(),
+            created_at: created_at.to_string(),
+            app_version: "1.0.0".to_string(),
+            os: "macos aarch64".to_string(),
+            thread: "main".to_string(),
+            message: "boom".to_string(),
+            location: Some("src/lib.rs:1".to_string()),
+            backtrace: String::new(),
+            last_pipeline_stage: Some("listening".to_string()),
+            uploaded_at: None,
+        }
+    }
+
+    #[test]
+    fn reports_are_listed_newest_first_and_pruned() {
+        let dir = std::env::temp_dir().join(format!("voice-crashes-{}", uuid::Uuid::new_v4()));
+        for (id, created_at) in [
+            ("a", "2026-01-01T00:00:00Z"),
+            ("c", "2026-01-03T00:00:00Z"),
+            ("b", "2026-01-02T00:00:00Z"),
+        ] {
+            write_report(&dir, &report(id, created_at)).expect("report should write");
+        }
+        std::fs::write(dir.join("broken.json"), "{").expect("broken report should write");
+
+        let ids = |reports: Vec<CrashReport>| {
+            reports
+                .into_iter()
+                .map(|report| report.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids(list_crash_reports(&dir).expect("reports should list")),
+            ["c", "b", "a"]
+        );
+
+        prune_reports(&dir, 2).expect("reports should prune");
+        assert_eq!(
+            ids(list_crash_reports(&dir).expect("reports should list")),
+            ["c", "b"]
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn worker_panics_keep_their_message() {
+        let payload = std::thread::spawn(|| panic!("device vanished: {}", 7))
+            .join()
+            .expect_err("thread should panic");
+
+        assert_eq!(panic_message(payload.as_ref()), "device vanished: 7");
+    }
+}
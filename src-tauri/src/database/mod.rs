@@ -0,0 +1,214 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rusqlite::{Connection, ErrorCode, TransactionBehavior};
+use tracing::{info, warn};
+
+pub const DATABASE_FILE_NAME: &str = "voice.sqlite3";
+// History and stats share one database, so a second connection can be busy
+// for a moment while the other commits.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Applied in order; `PRAGMA user_version` records how many have run. Never
+// edit a shipped migration, append a new one instead.
+const MIGRATIONS: &[&str] = &[r#"
+CREATE TABLE history_entries (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    id TEXT NOT NULL,
+    timestamp TEXT NOT NULL,
+    payload BLOB NOT NULL
+);
+CREATE INDEX history_entries_id ON history_entries (id);
+CREATE INDEX history_entries_order ON history_entries (timestamp DESC, seq);
+
+CREATE TABLE usage_totals (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    total_transcriptions INTEGER NOT NULL DEFAULT 0,
+    total_words INTEGER NOT NULL DEFAULT 0,
+    total_recording_seconds REAL NOT NULL DEFAULT 0,
+    total_trimmed_silence_ms INTEGER NOT NULL DEFAULT 0,
+    transcriptions_without_word_count INTEGER NOT NULL DEFAULT 0,
+    race_wins_realtime INTEGER NOT NULL DEFAULT 0,
+    race_wins_rest INTEGER NOT NULL DEFAULT 0,
+    last_updated TEXT NOT NULL DEFAULT ''
+);
+CREATE TABLE usage_daily (
+    date TEXT PRIMARY KEY,
+    transcriptions INTEGER NOT NULL DEFAULT 0,
+    words INTEGER NOT NULL DEFAULT 0,
+    recording_seconds REAL NOT NULL DEFAULT 0,
+    trimmed_silence_ms INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE usage_apps (
+    app_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL DEFAULT '',
+    transcriptions INTEGER NOT NULL DEFAULT 0,
+    words INTEGER NOT NULL DEFAULT 0,
+    recording_seconds REAL NOT NULL DEFAULT 0,
+    last_used TEXT NOT NULL DEFAULT ''
+);
+"#];
+
+// Opens the app database in WAL mode and brings its schema up to date. A
+// file that is not a database at all is moved aside and replaced.
+pub fn open_database(path: &Path) -> Result<Connection, String> {
+    if let Some(parent_dir) = path.parent() {
+        fs::create_dir_all(parent_dir)
+            .map_err(|error| format!("Failed to create database directory: {error}"))?;
+    }
+
+    let connection = match open_and_migrate(path) {
+        Ok(connection) => connection,
+        Err(error) if is_not_a_database(&error) => {
+            let backup_path = backup_corrupt_database(path)?;
+            warn!(
+                path = %path.display(),
+                backup = %backup_path.display(),
+                %error,
+                "recovered malformed database"
+            );
+            open_and_migrate(path).map_err(|error| format!("Failed to open database: {error}"))?
+        }
+        Err(error) => return Err(format!("Failed to open database: {error}")),
+    };
+    Ok(connection)
+}
+
+// Rebuilds the file and empties the WAL, so pages written before a bulk
+// rewrite (such as turning on history encryption) are gone from both.
+pub fn compact_database(connection: &Connection) -> Result<(), String> {
+    connection
+        .execute_batch("VACUUM")
+        .map_err(|error| format!("Failed to compact database: {error}"))?;
+    let busy: i64 = connection
+        .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| row.get(0))
+        .map_err(|error| format!("Failed to checkpoint database: {error}"))?;
+    if busy != 0 {
+        return Err("Failed to checkpoint database: it is busy in another connection".to_string());
+    }
+    Ok(())
+}
+
+fn open_and_migrate(path: &Path) -> rusqlite::Result<Connection> {
+    let mut connection = Connection::open(path)?;
+    connection.busy_timeout(BUSY_TIMEOUT)?;
+    connection
+        .pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+    connection.pragma_update(None, "synchronous", "NORMAL")?;
+    // Deleted and overwritten rows are zeroed on disk rather than left in
+    // free pages, so a dropped transcript can't be read back from the file.
+    connection.pragma_update(None, "secure_delete", "ON")?;
+    migrate(&mut connection)?;
+    Ok(connection)
+}
+
+fn migrate(connection: &mut Connection) -> rusqlite::Result<()> {
+    let transaction = connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let version: usize = transaction.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > MIGRATIONS.len() {
+        // A newer build already migrated this file; its extra tables and
+        // columns are additive, so keep going with the ones we know.
+        warn!(
+            version,
+            known = MIGRATIONS.len(),
+            "database schema is newer than this build"
+        );
+        return Ok(());
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", index + 1)?;
+        info!(version = index + 1, "applied database migration");
+    }
+    transaction.commit()
+}
+
+fn is_not_a_database(error: &rusqlite::Error) -> bool {
+    error.sqlite_error_code() == Some(ErrorCode::NotADatabase)
+}
+
+fn backup_corrupt_database(path: &Path) -> Result<PathBuf, String> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let file_name = path
+        .file_name()
+        .and_then(|value| value.to_str())
+        .unwrap_or(DATABASE_FILE_NAME);
+    let backup_path = path.with_file_name(format!(
+        "{file_name}.corrupt-{}-{timestamp}.bak",
+        std::process::id()
+    ));
+
+    fs::rename(path, &backup_path).map_err(|error| {
+        format!(
+            "Failed to backup malformed database `{}` to `{}`: {error}",
+            path.display(),
+            backup_path.display()
+        )
+    })?;
+    // A leftover log from the old file would be replayed into the new one.
+    for suffix in ["-wal", "-shm"] {
+        let _ = fs::remove_file(path.with_file_name(format!("{file_name}{suffix}")));
+    }
+
+    Ok(backup_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{open_database, DATABASE_FILE_NAME, MIGRATIONS};
+
+    #[test]
+    fn migrations_run_once_and_enable_wal() {
+        let test_dir =
+            std::env::temp_dir().join(format!("voice-database-{}", uuid::Uuid::new_v4()));
+        let path = test_dir.join(DATABASE_FILE_NAME);
+
+        let connection = open_database(&path).expect("database should open");
+        connection
+            .execute("INSERT INTO usage_daily (date) VALUES ('2026-01-01')", [])
+            .expect("migrated table should accept rows");
+        drop(connection);
+
+        let connection = open_database(&path).expect("database should reopen");
+        let version: usize = connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .expect("version should read");
+        let journal_mode: String = connection
+            .pragma_query_value(None, "journal_mode", |row| row.get(0))
+            .expect("journal mode should read");
+        let rows: i64 = connection
+            .query_row("SELECT COUNT(*) FROM usage_daily", [], |row| row.get(0))
+            .expect("rows should count");
+        assert_eq!(version, MIGRATIONS.len());
+        assert_eq!(journal_mode, "wal");
+        assert_eq!(rows, 1);
+
+        let _ = std::fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn replaces_a_file_that_is_not_a_database() {
+        let test_dir =
+            std::env::temp_dir().join(format!("voice-database-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&test_dir).expect("test dir should be created");
+        let path = test_dir.join(DATABASE_FILE_NAME);
+        std::fs::write(&path, vec![0x5a; 4096]).expect("garbage should write");
+
+        open_database(&path).expect("database should be recreated");
+        let backups = std::fs::read_dir(&test_dir)
+            .expect("test dir should list")
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().contains(".corrupt-"))
+            .count();
+        assert_eq!(backups, 1);
+
+        let _ = std::fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,133 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::Url;
+
+use crate::settings_store::{
+    TRANSCRIPTION_STYLE_CASUAL, TRANSCRIPTION_STYLE_CLEAN, TRANSCRIPTION_STYLE_CUSTOM,
+    TRANSCRIPTION_STYLE_VERBATIM,
+};
+
+pub const DEEP_LINK_SCHEME: &str = "voice";
+
+// A profile names the transcription style to use for one session, e.g.
+// `voice://record?profile=casual`.
+const PROFILES: &[&str] = &[
+    TRANSCRIPTION_STYLE_CLEAN,
+    TRANSCRIPTION_STYLE_CASUAL,
+    TRANSCRIPTION_STYLE_VERBATIM,
+    TRANSCRIPTION_STYLE_CUSTOM,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLinkAction {
+    Record { profile: Option<String> },
+    Stop,
+    Open(DeepLinkNavigation),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum DeepLinkView {
+    History,
+    Settings,
+}
+
+// Sent to the main window so it can switch views.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkNavigation {
+    pub view: DeepLinkView,
+    pub history_id: Option<String>,
+}
+
+// `voice://record` and `voice:record` route the same way: the host, when
+// there is one, is just the first path segment.
+pub fn parse_deep_link(url: &Url) -> Result<DeepLinkAction, String> {
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return Err(format!("Unsupported deep link scheme: {}", url.scheme()));
+    }
+    let segments: Vec<&str> = url
+        .host_str()
+        .into_iter()
+        .chain(url.path().split('/'))
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    match segments.as_slice() {
+        ["record"] | ["start"] => {
+            let profile = url
+                .query_pairs()
+                .find(|(key, _)| key == "profile")
+                .map(|(_, value)| value.trim().to_lowercase())
+                .filter(|profile| !profile.is_empty());
+            if let Some(profile) = &profile {
+                if !PROFILES.contains(&profile.as_str()) {
+                    return Err(format!("Unknown profile: {profile}"));
+                }
+            }
+            Ok(DeepLinkAction::Record { profile })
+        }
+        ["stop"] => Ok(DeepLinkAction::Stop),
+        ["history"] => Ok(DeepLinkAction::Open(DeepLinkNavigation {
+            view: DeepLinkView::History,
+            history_id: None,
+        })),
+        ["history", id] => Ok(DeepLinkAction::Open(DeepLinkNavigation {
+            view: DeepLinkView::History,
+            history_id: Some((*id).to_string()),
+        })),
+        ["settings"] => Ok(DeepLinkAction::Open(DeepLinkNavigation {
+            view: DeepLinkView::Settings,
+            history_id: None,
+        })),
+        _ => Err(format!("Unknown deep link: {url}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tauri::Url;
+
+    use super::{parse_deep_link, DeepLinkAction, DeepLinkNavigation, DeepLinkView};
+
+    fn parse(url: &str) -> Result<DeepLinkAction, String> {
+        parse_deep_link(&Url::parse(url).expect("url should parse"))
+    }
+
+    #[test]
+    fn routes_recording_and_navigation_links() {
+        assert_eq!(
+            parse("voice://record?profile=%20Casual%20"),
+            Ok(DeepLinkAction::Record {
+                profile: Some("casual".to_string())
+            })
+        );
+        assert_eq!(
+            parse("voice:start"),
+            Ok(DeepLinkAction::Record { profile: None })
+        );
+        assert_eq!(parse("voice://stop/"), Ok(DeepLinkAction::Stop));
+        assert_eq!(
+            parse("voice://history/entry-42"),
+            Ok(DeepLinkAction::Open(DeepLinkNavigation {
+                view: DeepLinkView::History,
+                history_id: Some("entry-42".to_string()),
+            }))
+        );
+        assert_eq!(
+            parse("voice://settings"),
+            Ok(DeepLinkAction::Open(DeepLinkNavigation {
+                view: DeepLinkView::Settings,
+                history_id: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_routes_profiles_and_schemes() {
+        assert!(parse("voice://record?profile=email").is_err());
+        assert!(parse("voice://history/a/b").is_err());
+        assert!(parse("voice://launch").is_err());
+        assert!(parse("https://record").is_err());
+    }
+}
@@ -0,0 +1,274 @@
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use tracing::info;
+
+use crate::{
+    app_error::{AppError, ErrorCode},
+    audio_capture_service::pcm16_to_wav_bytes,
+    permission_service::PermissionState,
+    transcription::{TranscriptionError, TranscriptionResult},
+};
+
+// Short enough to cost next to nothing, long enough that providers accept it.
+const SAMPLE_DURATION_MS: u32 = 500;
+const SAMPLE_RATE_HZ: u32 = 16_000;
+const SAMPLE_TONE_HZ: f32 = 440.0;
+// How long the capture check keeps the stream open before closing it.
+pub const CAPTURE_CHECK_HOLD: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticCheckId {
+    MicrophonePermission,
+    CaptureStream,
+    Credentials,
+    ProviderLatency,
+    AccessibilityPermission,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCheck {
+    pub id: DiagnosticCheckId,
+    pub status: DiagnosticStatus,
+    pub message: String,
+    pub duration_ms: Option<u64>,
+    pub error: Option<AppError>,
+}
+
+impl DiagnosticCheck {
+    pub fn passed(id: DiagnosticCheckId, message: impl Into<String>, elapsed: Duration) -> Self {
+        Self {
+            id,
+            status: DiagnosticStatus::Passed,
+            message: message.into(),
+            duration_ms: Some(elapsed.as_millis() as u64),
+            error: None,
+        }
+    }
+
+    pub fn failed(id: DiagnosticCheckId, error: AppError, elapsed: Option<Duration>) -> Self {
+        Self {
+            id,
+            status: DiagnosticStatus::Failed,
+            message: error.message.clone(),
+            duration_ms: elapsed.map(|elapsed| elapsed.as_millis() as u64),
+            error: Some(error),
+        }
+    }
+
+    pub fn skipped(id: DiagnosticCheckId, reason: impl Into<String>) -> Self {
+        Self {
+            id,
+            status: DiagnosticStatus::Skipped,
+            message: reason.into(),
+            duration_ms: None,
+            error: None,
+        }
+    }
+}
+
+// Everything the support screen shows after "Run diagnostics", in the order
+// the checks ran.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsReport {
+    pub ran_at: String,
+    pub app_version: String,
+    pub passed: bool,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+impl DiagnosticsReport {
+    pub fn new(app_version: &str, checks: Vec<DiagnosticCheck>) -> Self {
+        let passed = checks
+            .iter()
+            .all(|check| check.status != DiagnosticStatus::Failed);
+        info!(
+            passed,
+            failed = checks
+                .iter()
+                .filter(|check| check.status == DiagnosticStatus::Failed)
+                .count(),
+            "diagnostics completed"
+        );
+        Self {
+            ran_at: chrono::Utc::now().to_rfc3339(),
+            app_version: app_version.to_string(),
+            passed,
+            checks,
+        }
+    }
+}
+
+pub fn permission_check(id: DiagnosticCheckId, state: PermissionState) -> DiagnosticCheck {
+    let name = match id {
+        DiagnosticCheckId::AccessibilityPermission => "Accessibility",
+        _ => "Microphone",
+    };
+    match state {
+        PermissionState::Granted => {
+            DiagnosticCheck::passed(id, format!("{name} access is granted"), Duration::ZERO)
+        }
+        PermissionState::Denied => DiagnosticCheck::failed(
+            id,
+            AppError::new(
+                ErrorCode::PermissionDenied,
+                format!("{name} access is denied"),
+            ),
+            None,
+        ),
+        PermissionState::NotDetermined => DiagnosticCheck::failed(
+            id,
+            AppError::new(
+                ErrorCode::PermissionDenied,
+                format!("{name} access has not been requested yet"),
+            ),
+            None,
+        ),
+    }
+}
+
+// One provider call answers two questions: whether the credentials are
+// accepted, and how long a round trip takes.
+pub fn provider_checks(
+    result: Result<TranscriptionResult, TranscriptionError>,
+    elapsed: Duration,
+) -> [DiagnosticCheck; 2] {
+    let error = match result {
+        Ok(_) => {
+            return [
+                DiagnosticCheck::passed(
+                    DiagnosticCheckId::Credentials,
+                    "The provider accepted the credentials",
+                    elapsed,
+                ),
+                DiagnosticCheck::passed(
+                    DiagnosticCheckId::ProviderLatency,
+                    format!("Round trip took {} ms", elapsed.as_millis()),
+                    elapsed,
+                ),
+            ];
+        }
+        Err(error) => AppError::from(error),
+    };
+
+    match error.code {
+        ErrorCode::NoAuth => [
+            DiagnosticCheck::failed(DiagnosticCheckId::Credentials, error, Some(elapsed)),
+            DiagnosticCheck::skipped(
+                DiagnosticCheckId::ProviderLatency,
+                "Needs working credentials",
+            ),
+        ],
+        // The provider answered, so it knew who was asking.
+        ErrorCode::Quota | ErrorCode::RateLimited => [
+            DiagnosticCheck::passed(
+                DiagnosticCheckId::Credentials,
+                "The provider accepted the credentials",
+                elapsed,
+            ),
+            DiagnosticCheck::failed(DiagnosticCheckId::ProviderLatency, error, Some(elapsed)),
+        ],
+        _ => [
+            DiagnosticCheck::skipped(
+                DiagnosticCheckId::Credentials,
+                "The provider did not answer, so the credentials were not checked",
+            ),
+            DiagnosticCheck::failed(DiagnosticCheckId::ProviderLatency, error, Some(elapsed)),
+        ],
+    }
+}
+
+// A quiet tone rather than silence, since some providers reject empty audio.
+pub fn sample_wav() -> Result<Vec<u8>, String> {
+    let sample_count = SAMPLE_RATE_HZ * SAMPLE_DURATION_MS / 1_000;
+    let samples = (0..sample_count)
+        .map(|index| {
+            let phase =
+                index as f32 * SAMPLE_TONE_HZ * std::f32::consts::TAU / SAMPLE_RATE_HZ as f32;
+            (phase.sin() * 2_000.0) as i16
+        })
+        .collect::<Vec<_>>();
+    pcm16_to_wav_bytes(&samples, SAMPLE_RATE_HZ, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{
+        permission_check, provider_checks, sample_wav, DiagnosticCheckId, DiagnosticStatus,
+        DiagnosticsReport,
+    };
+    use crate::{
+        permission_service::PermissionState,
+        transcription::{chunked::parse_pcm16_mono_wav, TranscriptionError},
+    };
+
+    #[test]
+    fn provider_failures_are_attributed_to_the_right_check() {
+        let elapsed = Duration::from_millis(420);
+        let statuses =
+            |result| provider_checks(result, elapsed).map(|check| (check.id, check.status));
+
+        assert_eq!(
+            statuses(Err(TranscriptionError::Authentication(
+                "invalid api key".to_string()
+            ))),
+            [
+                (DiagnosticCheckId::Credentials, DiagnosticStatus::Failed),
+                (
+                    DiagnosticCheckId::ProviderLatency,
+                    DiagnosticStatus::Skipped
+                ),
+            ]
+        );
+        assert_eq!(
+            statuses(Err(TranscriptionError::Network("offline".to_string()))),
+            [
+                (DiagnosticCheckId::Credentials, DiagnosticStatus::Skipped),
+                (DiagnosticCheckId::ProviderLatency, DiagnosticStatus::Failed),
+            ]
+        );
+        assert_eq!(
+            statuses(Err(TranscriptionError::RateLimited(
+                "slow down".to_string()
+            ))),
+            [
+                (DiagnosticCheckId::Credentials, DiagnosticStatus::Passed),
+                (DiagnosticCheckId::ProviderLatency, DiagnosticStatus::Failed),
+            ]
+        );
+    }
+
+    #[test]
+    fn report_fails_when_any_check_fails_and_sample_is_valid_wav() {
+        let granted = permission_check(
+            DiagnosticCheckId::MicrophonePermission,
+            PermissionState::Granted,
+        );
+        let denied = permission_check(
+            DiagnosticCheckId::AccessibilityPermission,
+            PermissionState::Denied,
+        );
+
+        assert!(DiagnosticsReport::new("1.0.0", vec![granted.clone()]).passed);
+        assert!(!DiagnosticsReport::new("1.0.0", vec![granted, denied]).passed);
+
+        let wav = parse_pcm16_mono_wav(&sample_wav().expect("sample should encode"))
+            .expect("sample should parse");
+        assert_eq!(wav.sample_rate_hz, 16_000);
+        assert_eq!(wav.samples.len(), 8_000);
+    }
+}
@@ -0,0 +1,328 @@
+// Embeds the dictation engine without the desktop shell: transcription,
+// credentials, settings, history and usage stats, all rooted in one data
+// directory. Builds without the `tauri` feature; hotkeys and text insertion
+// stay with the app, so callers bring their own WAV recordings.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use tracing::info;
+
+pub use crate::app_error::{AppError, ErrorCode};
+pub use crate::auth_store::AuthMethod;
+pub use crate::history_store::HistoryEntry;
+pub use crate::settings_store::{VoiceSettings, VoiceSettingsUpdate};
+pub use crate::transcription::{TranscriptionOptions, TranscriptionResult};
+
+use crate::api_key_store::ApiKeyStore;
+use crate::audio_capture_service::RecordedWav;
+use crate::auth_store::AuthStore;
+use crate::history_store::{encryption::HistoryCipher, HistoryStore};
+use crate::redaction::RedactionEngine;
+use crate::settings_store::{
+    settings_file_path, SettingsStore, DEFAULT_TRANSCRIPTION_PROVIDER,
+    GEMINI_TRANSCRIPTION_PROVIDER,
+};
+use crate::stats_store::StatsStore;
+use crate::transcription::chatgpt::{ChatGptTranscriptionConfig, ChatGptTranscriptionProvider};
+use crate::transcription::endpoint::SharedProviderConfig;
+use crate::transcription::gemini::{GeminiTranscriptionConfig, GeminiTranscriptionProvider};
+use crate::transcription::language::language_hint;
+use crate::transcription::openai::{OpenAiTranscriptionConfig, OpenAiTranscriptionProvider};
+use crate::transcription::TranscriptionOrchestrator;
+use crate::voice_pipeline::rest::{self, RestProviders, RestTranscription};
+use crate::voice_pipeline::PipelineTranscript;
+
+const OPENAI_PROVIDER: &str = "openai";
+
+#[derive(Debug)]
+pub struct VoiceEngine {
+    app_data_dir: PathBuf,
+    settings_store: SettingsStore,
+    api_key_store: ApiKeyStore,
+    auth_store: AuthStore,
+    history_store: HistoryStore,
+    stats_store: StatsStore,
+    transcription_orchestrator: TranscriptionOrchestrator,
+    gemini_transcription_orchestrator: TranscriptionOrchestrator,
+    chatgpt_transcription_provider: ChatGptTranscriptionProvider,
+    provider_config: SharedProviderConfig,
+    gemini_provider_config: SharedProviderConfig,
+    load_history_cipher: fn() -> Result<HistoryCipher, String>,
+}
+
+impl VoiceEngine {
+    // Uses the same files as the app, so pointing it at the app's data
+    // directory shares settings, credentials and history with it.
+    pub fn open(app_data_dir: impl Into<PathBuf>) -> Result<Self, AppError> {
+        Self::open_with_history_cipher(app_data_dir.into(), HistoryCipher::from_keychain)
+    }
+
+    fn open_with_history_cipher(
+        app_data_dir: PathBuf,
+        load_history_cipher: fn() -> Result<HistoryCipher, String>,
+    ) -> Result<Self, AppError> {
+        fs::create_dir_all(&app_data_dir).map_err(|error| {
+            format!(
+                "Failed to create data directory `{}`: {error}",
+                app_data_dir.display()
+            )
+        })?;
+
+        let settings_store = SettingsStore::new();
+        settings_store.load_from_path(&settings_file_path(&app_data_dir))?;
+        let api_key_store = ApiKeyStore::new(app_data_dir.clone());
+        let auth_store = AuthStore::new(app_data_dir.clone());
+        let provider_config = SharedProviderConfig::default();
+        let mut openai_config = OpenAiTranscriptionConfig::from_env();
+        openai_config.api_key_store_app_data_dir = Some(app_data_dir.clone());
+        openai_config.overrides = provider_config.clone();
+        let transcription_orchestrator = TranscriptionOrchestrator::new(Arc::new(
+            OpenAiTranscriptionProvider::new(openai_config),
+        ));
+        let gemini_provider_config = SharedProviderConfig::default();
+        let mut gemini_config = GeminiTranscriptionConfig::from_env();
+        gemini_config.api_key_store_app_data_dir = Some(app_data_dir.clone());
+        gemini_config.overrides = gemini_provider_config.clone();
+        let gemini_transcription_orchestrator = TranscriptionOrchestrator::new(Arc::new(
+            GeminiTranscriptionProvider::new(gemini_config),
+        ));
+        let chatgpt_transcription_provider = ChatGptTranscriptionProvider::new(
+            ChatGptTranscriptionConfig::from_env(),
+            auth_store.clone(),
+        );
+        let history_store = HistoryStore::new_in_dir(&app_data_dir)?;
+        sync_history_encryption(
+            &history_store,
+            settings_store.current().history_encryption,
+            load_history_cipher,
+        )?;
+        info!(path = %app_data_dir.display(), "voice engine opened");
+
+        Ok(Self {
+            history_store,
+            stats_store: StatsStore::new_in_dir(&app_data_dir)?,
+            app_data_dir,
+            settings_store,
+            api_key_store,
+            auth_store,
+            transcription_orchestrator,
+            gemini_transcription_orchestrator,
+            chatgpt_transcription_provider,
+            provider_config,
+            gemini_provider_config,
+            load_history_cipher,
+        })
+    }
+
+    pub fn app_data_dir(&self) -> &Path {
+        &self.app_data_dir
+    }
+
+    pub fn settings(&self) -> VoiceSettings {
+        self.settings_store.current()
+    }
+
+    pub fn update_settings(&self, update: VoiceSettingsUpdate) -> Result<VoiceSettings, AppError> {
+        let settings = self
+            .settings_store
+            .update_at_path(&settings_file_path(&self.app_data_dir), update)?;
+        sync_history_encryption(
+            &self.history_store,
+            settings.history_encryption,
+            self.load_history_cipher,
+        )?;
+        Ok(settings)
+    }
+
+    // Stores the OpenAI key and makes it the active credential.
+    pub fn set_api_key(&self, key: &str) -> Result<(), AppError> {
+        self.api_key_store.set_api_key(OPENAI_PROVIDER, key)?;
+        self.auth_store.set_api_key(key)?;
+        Ok(())
+    }
+
+    pub fn auth_method(&self) -> Result<AuthMethod, AppError> {
+        self.auth_store.effective_auth_method(&self.api_key_store)
+    }
+
+    pub fn history(&self, limit: usize, offset: usize) -> Result<Vec<HistoryEntry>, AppError> {
+        self.history_store.list_entries(limit, offset)
+    }
+
+    // Runs a 16-bit mono WAV through the app's REST path: silence trim,
+    // resampling and meeting chunking per settings, the style prompt, and the
+    // active credential. The transcript is saved to history and usage stats
+    // unless private dictation is on.
+    pub async fn transcribe_wav(
+        &self,
+        wav_bytes: Vec<u8>,
+    ) -> Result<TranscriptionResult, AppError> {
+        let settings = self.settings();
+        let wav = RecordedWav::Memory(wav_bytes);
+        let recording_secs = wav.duration_secs().unwrap_or(0.0);
+        let auth_method = self.auth_method()?;
+        self.provider_config
+            .set(settings.provider_config_for(DEFAULT_TRANSCRIPTION_PROVIDER));
+        self.gemini_provider_config
+            .set(settings.provider_config_for(GEMINI_TRANSCRIPTION_PROVIDER));
+        crate::proxy::set_configured(settings.proxy.clone());
+        let providers = RestProviders {
+            api_key: if settings.transcription_provider == GEMINI_TRANSCRIPTION_PROVIDER {
+                &self.gemini_transcription_orchestrator
+            } else {
+                &self.transcription_orchestrator
+            },
+            chatgpt: &self.chatgpt_transcription_provider,
+        };
+        let options = rest::transcription_options(
+            &settings,
+            language_hint(&settings.preferred_languages),
+            None,
+        );
+        let meeting_mode_duration_secs = rest::meeting_mode_duration_secs(&settings, &wav);
+        let RestTranscription {
+            transcription,
+            trimmed_silence_ms,
+        } = rest::transcribe_with_rest(
+            &settings,
+            wav,
+            auth_method,
+            providers,
+            options,
+            meeting_mode_duration_secs,
+        )
+        .await?;
+        if settings.private_dictation {
+            return Ok(transcription);
+        }
+
+        let transcript = PipelineTranscript::from_transcription(
+            transcription.clone(),
+            rest::rest_provider_name(auth_method, &settings.transcription_provider).to_string(),
+        );
+        let (entry, _) = rest::history_entry(
+            &transcript,
+            &RedactionEngine::new(&settings.redaction_rules)?,
+        );
+        // Never writes plaintext into history that should be encrypted.
+        sync_history_encryption(
+            &self.history_store,
+            settings.history_encryption,
+            self.load_history_cipher,
+        )?;
+        self.history_store.add_entry(entry)?;
+        rest::record_usage_stats(
+            &self.stats_store,
+            &settings,
+            &transcript.text,
+            transcript.language.as_deref(),
+            recording_secs,
+            trimmed_silence_ms,
+            None,
+        )?;
+        Ok(transcription)
+    }
+}
+
+// Matches the app's handling of the setting; only rewrites history when it
+// actually changes.
+fn sync_history_encryption(
+    history_store: &HistoryStore,
+    enabled: bool,
+    load_history_cipher: fn() -> Result<HistoryCipher, String>,
+) -> Result<(), AppError> {
+    if history_store.is_encrypted() == enabled {
+        return Ok(());
+    }
+    let cipher = enabled.then(load_history_cipher).transpose()?;
+    history_store.set_encryption(cipher)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use super::{HistoryCipher, HistoryEntry, VoiceEngine, VoiceSettingsUpdate};
+
+    #[tokio::test]
+    async fn opens_in_a_data_directory_and_persists_settings() {
+        let app_data_dir =
+            std::env::temp_dir().join(format!("voice-engine-tests-{}", uuid::Uuid::new_v4()));
+        let engine = VoiceEngine::open(&app_data_dir).expect("engine should open");
+
+        engine
+            .update_settings(VoiceSettingsUpdate {
+                language: Some(Some("fr".to_string())),
+                ..VoiceSettingsUpdate::default()
+            })
+            .expect("settings should update");
+        assert_eq!(
+            VoiceEngine::open(&app_data_dir)
+                .expect("engine should reopen")
+                .settings()
+                .language
+                .as_deref(),
+            Some("fr")
+        );
+        assert!(engine
+            .history(10, 0)
+            .expect("history should load")
+            .is_empty());
+        assert!(engine.transcribe_wav(Vec::new()).await.is_err());
+
+        let _ = std::fs::remove_dir_all(app_data_dir);
+    }
+
+    fn test_cipher() -> Result<HistoryCipher, String> {
+        static CIPHER: OnceLock<HistoryCipher> = OnceLock::new();
+        Ok(CIPHER.get_or_init(HistoryCipher::generate).clone())
+    }
+
+    fn missing_keychain() -> Result<HistoryCipher, String> {
+        Err("History encryption needs the OS keychain to store its key".to_string())
+    }
+
+    #[test]
+    fn applies_history_encryption_from_settings() {
+        let app_data_dir = std::env::temp_dir().join(format!(
+            "voice-engine-encryption-tests-{}",
+            uuid::Uuid::new_v4()
+        ));
+        let engine = VoiceEngine::open_with_history_cipher(app_data_dir.clone(), test_cipher)
+            .expect("engine should open");
+        engine
+            .history_store
+            .add_entry(HistoryEntry::new(
+                "patient notes".to_string(),
+                Some(1.0),
+                None,
+                "openai".to_string(),
+            ))
+            .expect("entry should be added");
+        engine
+            .update_settings(VoiceSettingsUpdate {
+                history_encryption: Some(true),
+                ..VoiceSettingsUpdate::default()
+            })
+            .expect("encryption should enable");
+        assert!(engine.history_store.is_encrypted());
+
+        let reopened = VoiceEngine::open_with_history_cipher(app_data_dir.clone(), test_cipher)
+            .expect("engine should reopen with the key");
+        assert_eq!(
+            reopened
+                .history(10, 0)
+                .expect("history should decrypt")
+                .len(),
+            1
+        );
+        assert!(
+            VoiceEngine::open_with_history_cipher(app_data_dir.clone(), missing_keychain).is_err()
+        );
+
+        let _ = std::fs::remove_dir_all(app_data_dir);
+    }
+}
@@ -0,0 +1,181 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "tauri")]
+use tauri::{AppHandle, Emitter, Runtime};
+use tracing::{debug, info, warn};
+
+pub const EVENT_FOCUSED_APP_CHANGED: &str = "voice://focused-app-changed";
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusedApp {
+    pub bundle_id: Option<String>,
+    pub name: String,
+}
+
+// Remembers the last frontmost app other than Voice itself, so "add current
+// app" in the settings window still refers to the app the user came from.
+#[derive(Debug)]
+struct FocusedAppTracker {
+    own_bundle_id: String,
+    last: Option<FocusedApp>,
+}
+
+impl FocusedAppTracker {
+    fn new(own_bundle_id: String) -> Self {
+        Self {
+            own_bundle_id,
+            last: None,
+        }
+    }
+
+    fn observe(&mut self, frontmost: Option<FocusedApp>) -> Option<FocusedApp> {
+        let frontmost = frontmost?;
+        if frontmost.bundle_id.as_deref() == Some(self.own_bundle_id.as_str())
+            || self.last.as_ref() == Some(&frontmost)
+        {
+            return None;
+        }
+        self.last = Some(frontmost.clone());
+        Some(frontmost)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FocusedAppWatcher {
+    current: Arc<Mutex<Option<FocusedApp>>>,
+}
+
+impl FocusedAppWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn current(&self) -> Option<FocusedApp> {
+        self.current
+            .lock()
+            .map(|current| current.clone())
+            .unwrap_or_else(|_| {
+                warn!("focused app lock poisoned while reading current app");
+                None
+            })
+    }
+
+    #[cfg(feature = "tauri")]
+    pub fn start<R: Runtime>(&self, app: AppHandle<R>) {
+        let current = Arc::clone(&self.current);
+        let mut tracker = FocusedAppTracker::new(app.config().identifier.clone());
+        let spawn_result = thread::Builder::new()
+            .name("focused-app-watcher".to_string())
+            .spawn(move || loop {
+                if let Some(focused_app) = tracker.observe(frontmost_app()) {
+                    debug!(
+                        bundle_id = ?focused_app.bundle_id,
+                        name = %focused_app.name,
+                        "focused app changed"
+                    );
+                    if let Ok(mut current) = current.lock() {
+                        *current = Some(focused_app.clone());
+                    }
+                    if let Err(error) = app.emit(EVENT_FOCUSED_APP_CHANGED, focused_app) {
+                        warn!(%error, "failed to emit focused app changed event");
+                    }
+                }
+                thread::sleep(POLL_INTERVAL);
+            });
+
+        match spawn_result {
+            Ok(_) => info!("focused app watcher started"),
+            Err(error) => warn!(%error, "failed to start focused app watcher"),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn frontmost_app() -> Option<FocusedApp> {
+    macos::frontmost_app()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn frontmost_app() -> Option<FocusedApp> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+#[allow(unexpected_cfgs)]
+mod macos {
+    use std::ffi::{c_char, CStr};
+
+    use objc::{class, msg_send, rc::autoreleasepool, runtime::Object, sel, sel_impl};
+
+    use super::FocusedApp;
+
+    pub(super) fn frontmost_app() -> Option<FocusedApp> {
+        autoreleasepool(|| unsafe {
+            let workspace: *mut Object = msg_send![class!(NSWorkspace), sharedWorkspace];
+            if workspace.is_null() {
+                return None;
+            }
+            let application: *mut Object = msg_send![workspace, frontmostApplication];
+            if application.is_null() {
+                return None;
+            }
+            let bundle_id: *mut Object = msg_send![application, bundleIdentifier];
+            let name: *mut Object = msg_send![application, localizedName];
+            let bundle_id = ns_string_to_string(bundle_id);
+            Some(FocusedApp {
+                name: ns_string_to_string(name)
+                    .or_else(|| bundle_id.clone())
+                    .unwrap_or_default(),
+                bundle_id,
+            })
+        })
+    }
+
+    unsafe fn ns_string_to_string(value: *mut Object) -> Option<String> {
+        if value.is_null() {
+            return None;
+        }
+        let utf8: *const c_char = msg_send![value, UTF8String];
+        if utf8.is_null() {
+            return None;
+        }
+        Some(CStr::from_ptr(utf8).to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FocusedApp, FocusedAppTracker};
+
+    fn app(bundle_id: &str, name: &str) -> Option<FocusedApp> {
+        Some(FocusedApp {
+            bundle_id: Some(bundle_id.to_string()),
+            name: name.to_string(),
+        })
+    }
+
+    #[test]
+    fn reports_changes_and_ignores_own_window() {
+        let mut tracker = FocusedAppTracker::new("com.sawyerhood.buzz".to_string());
+
+        assert_eq!(
+            tracker.observe(app("com.apple.Safari", "Safari")),
+            app("com.apple.Safari", "Safari")
+        );
+        assert_eq!(tracker.observe(app("com.apple.Safari", "Safari")), None);
+        assert_eq!(tracker.observe(app("com.sawyerhood.buzz", "Buzz")), None);
+        assert_eq!(tracker.observe(None), None);
+        assert_eq!(
+            tracker.observe(app("com.apple.Notes", "Notes")),
+            app("com.apple.Notes", "Notes")
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+
+use crate::keychain;
+
+const KEYCHAIN_SERVICE: &str = "voice.history";
+const KEYCHAIN_ACCOUNT: &str = "encryption-key";
+// Leads every encrypted history file so reads can tell it apart from the
+// plain JSON written before encryption was turned on.
+const ENCRYPTED_MAGIC: &[u8] = b"VOICEENC1";
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+#[derive(Clone)]
+pub struct HistoryCipher {
+    cipher: ChaCha20Poly1305,
+}
+
+impl std::fmt::Debug for HistoryCipher {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("HistoryCipher(..)")
+    }
+}
+
+impl HistoryCipher {
+    pub fn from_key(key: &[u8]) -> Result<Self, String> {
+        if key.len() != KEY_LEN {
+            return Err(format!(
+                "History encryption key must be {KEY_LEN} bytes, got {}",
+                key.len()
+            ));
+        }
+        Ok(Self {
+            cipher: ChaCha20Poly1305::new(Key::from_slice(key)),
+        })
+    }
+
+    #[cfg(test)]
+    pub fn generate() -> Self {
+        Self {
+            cipher: ChaCha20Poly1305::new(&ChaCha20Poly1305::generate_key(&mut OsRng)),
+        }
+    }
+
+    // The key is created on first use and never leaves the keychain, so
+    // history stays readable across launches but not from a copied file.
+    pub fn from_keychain() -> Result<Self, String> {
+        if !keychain::is_available() {
+            return Err("History encryption needs the OS keychain to store its key".to_string());
+        }
+        if let Some(key) = keychain::get(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)? {
+            return Self::from_key(&key);
+        }
+
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        keychain::set(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT, &key)?;
+        Ok(Self {
+            cipher: ChaCha20Poly1305::new(&key),
+        })
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| "Failed to encrypt transcript history".to_string())?;
+
+        let mut output = Vec::with_capacity(ENCRYPTED_MAGIC.len() + NONCE_LEN + ciphertext.len());
+        output.extend_from_slice(ENCRYPTED_MAGIC);
+        output.extend_from_slice(&nonce);
+        output.extend_from_slice(&ciphertext);
+        Ok(output)
+    }
+
+    pub fn decrypt(&self, contents: &[u8]) -> Result<Vec<u8>, String> {
+        let payload = contents
+            .strip_prefix(ENCRYPTED_MAGIC)
+            .filter(|payload| payload.len() >= NONCE_LEN)
+            .ok_or_else(|| "Transcript history file is not encrypted".to_string())?;
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                "Failed to decrypt transcript history; the key may have changed".to_string()
+            })
+    }
+}
+
+pub fn is_encrypted(contents: &[u8]) -> bool {
+    contents.starts_with(ENCRYPTED_MAGIC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_encrypted, HistoryCipher};
+
+    #[test]
+    fn round_trips_and_rejects_tampered_or_foreign_ciphertext() {
+        let cipher = HistoryCipher::generate();
+        let encrypted = cipher.encrypt(b"[]").expect("history should encrypt");
+        assert!(is_encrypted(&encrypted));
+        assert!(!is_encrypted(b"[]"));
+        assert_eq!(cipher.decrypt(&encrypted).expect("should decrypt"), b"[]");
+
+        let mut tampered = encrypted.clone();
+        *tampered.last_mut().expect("ciphertext is not empty") ^= 1;
+        assert!(cipher.decrypt(&tampered).is_err());
+        assert!(HistoryCipher::generate().decrypt(&encrypted).is_err());
+        assert!(HistoryCipher::from_key(&[0; 16]).is_err());
+    }
+}
@@ -0,0 +1,293 @@
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::HistoryEntry;
+
+pub const IMPORTED_PROVIDER: &str = "imported";
+
+// Other dictation tools name the same fields differently; the first key found
+// wins. superwhisper keeps the cleaned-up text in `result` and the raw
+// transcript in `rawResult`, MacWhisper exports `Transcription` columns.
+const TEXT_KEYS: &[&str] = &[
+    "text",
+    "transcript",
+    "transcription",
+    "result",
+    "rawresult",
+    "content",
+];
+const TIMESTAMP_KEYS: &[&str] = &[
+    "timestamp",
+    "datetime",
+    "date",
+    "createdat",
+    "created",
+    "time",
+];
+const DURATION_SECS_KEYS: &[&str] = &["durationsecs", "duration", "length"];
+const DURATION_MS_KEYS: &[&str] = &["durationms"];
+const LANGUAGE_KEYS: &[&str] = &["language", "languagecode", "lang"];
+const ENTRY_LIST_KEYS: &[&str] = &["entries", "history", "recordings", "transcripts", "items"];
+const NAIVE_TIMESTAMP_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+];
+// Unix timestamps above this are taken to be milliseconds.
+const UNIX_MILLIS_THRESHOLD: f64 = 100_000_000_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryImportFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryImportReport {
+    pub total: usize,
+    pub imported: usize,
+    pub duplicates: usize,
+    // Rows with no text or an unreadable timestamp.
+    pub skipped: usize,
+    // Oldest entries dropped to stay within the history limit.
+    pub pruned: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryImportProgressEvent {
+    pub processed: usize,
+    pub total: usize,
+}
+
+pub struct ParsedImport {
+    pub entries: Vec<HistoryEntry>,
+    pub total: usize,
+}
+
+pub fn parse_import(contents: &str, format: HistoryImportFormat) -> Result<ParsedImport, String> {
+    let records = match format {
+        HistoryImportFormat::Json => json_records(contents)?,
+        HistoryImportFormat::Csv => csv_records(contents)?,
+    };
+    let total = records.len();
+    let entries = records
+        .into_iter()
+        .filter_map(|record| entry_from_record(&record))
+        .collect();
+    Ok(ParsedImport { entries, total })
+}
+
+fn json_records(contents: &str) -> Result<Vec<Map<String, Value>>, String> {
+    let value = serde_json::from_str::<Value>(contents)
+        .map_err(|error| format!("Failed to parse history import JSON: {error}"))?;
+    let list = match value {
+        Value::Array(items) => items,
+        Value::Object(object) => {
+            let nested = object.iter().find_map(|(key, value)| {
+                ENTRY_LIST_KEYS
+                    .contains(&normalize_key(key).as_str())
+                    .then(|| value.as_array().cloned())
+                    .flatten()
+            });
+            match nested {
+                Some(items) => items,
+                None => vec![Value::Object(object)],
+            }
+        }
+        _ => return Err("History import JSON must be an object or an array".to_string()),
+    };
+    Ok(list
+        .into_iter()
+        .filter_map(|item| match item {
+            Value::Object(object) => Some(normalize_keys(object)),
+            _ => None,
+        })
+        .collect())
+}
+
+fn csv_records(contents: &str) -> Result<Vec<Map<String, Value>>, String> {
+    let mut rows = parse_csv(contents.trim_start_matches('\u{feff}'))?.into_iter();
+    let Some(header) = rows.next() else {
+        return Ok(Vec::new());
+    };
+    let header = header
+        .iter()
+        .map(|column| normalize_key(column))
+        .collect::<Vec<_>>();
+    Ok(rows
+        .filter(|row| row.iter().any(|cell| !cell.trim().is_empty()))
+        .map(|row| {
+            header
+                .iter()
+                .cloned()
+                .zip(row.into_iter().map(Value::String))
+                .collect()
+        })
+        .collect())
+}
+
+// RFC 4180: quoted fields may hold commas, newlines and doubled quotes.
+fn parse_csv(contents: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = contents.chars().peekable();
+
+    while let Some(character) = chars.next() {
+        match (in_quotes, character) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => in_quotes = false,
+            (true, _) => field.push(character),
+            (false, '"') if field.is_empty() => in_quotes = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, _) => field.push(character),
+        }
+    }
+    if in_quotes {
+        return Err("History import CSV has an unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+fn entry_from_record(record: &Map<String, Value>) -> Option<HistoryEntry> {
+    let text = first_string(record, TEXT_KEYS)?;
+    let timestamp = parse_timestamp(record.get(first_key(record, TIMESTAMP_KEYS)?)?)?;
+    let duration_secs = first_number(record, DURATION_SECS_KEYS)
+        .or_else(|| first_number(record, DURATION_MS_KEYS).map(|millis| millis / 1000.0))
+        .filter(|secs| secs.is_finite() && *secs >= 0.0);
+
+    let mut entry = HistoryEntry::new(
+        text,
+        duration_secs,
+        first_string(record, LANGUAGE_KEYS),
+        IMPORTED_PROVIDER.to_string(),
+    );
+    entry.timestamp = timestamp;
+    Some(entry)
+}
+
+fn first_key<'a>(record: &Map<String, Value>, keys: &[&'a str]) -> Option<&'a str> {
+    keys.iter().copied().find(|key| record.contains_key(*key))
+}
+
+fn first_string(record: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| record.get(*key)?.as_str())
+        .map(str::trim)
+        .find(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn first_number(record: &Map<String, Value>, keys: &[&str]) -> Option<f64> {
+    keys.iter().find_map(|key| match record.get(*key)? {
+        Value::Number(number) => number.as_f64(),
+        Value::String(value) => value.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+fn parse_timestamp(value: &Value) -> Option<String> {
+    let parsed = match value {
+        Value::Number(number) => from_unix(number.as_f64()?),
+        Value::String(value) => {
+            let value = value.trim();
+            DateTime::parse_from_rfc3339(value)
+                .map(|timestamp| timestamp.with_timezone(&Utc))
+                .ok()
+                .or_else(|| {
+                    NAIVE_TIMESTAMP_FORMATS.iter().find_map(|format| {
+                        NaiveDateTime::parse_from_str(value, format)
+                            .ok()
+                            .map(|timestamp| timestamp.and_utc())
+                    })
+                })
+                .or_else(|| value.parse().ok().and_then(from_unix))
+        }
+        _ => None,
+    }?;
+    Some(parsed.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+fn from_unix(value: f64) -> Option<DateTime<Utc>> {
+    let millis = if value.abs() >= UNIX_MILLIS_THRESHOLD {
+        value
+    } else {
+        value * 1000.0
+    };
+    Utc.timestamp_millis_opt(millis as i64).single()
+}
+
+fn normalize_keys(object: Map<String, Value>) -> Map<String, Value> {
+    object
+        .into_iter()
+        .map(|(key, value)| (normalize_key(&key), value))
+        .collect()
+}
+
+// `createdAt`, `created_at` and `Created At` all map to `createdat`.
+fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|character| character.is_ascii_alphanumeric())
+        .map(|character| character.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_import, HistoryImportFormat, IMPORTED_PROVIDER};
+
+    #[test]
+    fn maps_json_and_csv_exports_into_entries() {
+        let json = r#"{
+            "recordings": [
+                {"result": "Hello there", "rawResult": "hello there", "datetime": "2024-03-01T09:30:00Z", "durationMs": 2500},
+                {"result": "   ", "datetime": "2024-03-01T09:31:00Z"},
+                {"result": "No date"}
+            ]
+        }"#;
+        let parsed = parse_import(json, HistoryImportFormat::Json).expect("json should parse");
+        assert_eq!(parsed.total, 3);
+        assert_eq!(parsed.entries.len(), 1);
+        let entry = &parsed.entries[0];
+        assert_eq!(entry.text, "Hello there");
+        assert_eq!(entry.timestamp, "2024-03-01T09:30:00.000Z");
+        assert_eq!(entry.duration_secs, Some(2.5));
+        assert_eq!(entry.provider, IMPORTED_PROVIDER);
+
+        let csv = "\u{feff}Date,Transcription,Language\r\n\
+            2024-03-02 10:00:00,\"Line one, with comma\nand \"\"quotes\"\"\",en\r\n\
+            1709373600,Unix seconds,\r\n";
+        let parsed = parse_import(csv, HistoryImportFormat::Csv).expect("csv should parse");
+        assert_eq!(parsed.total, 2);
+        assert_eq!(
+            parsed.entries[0].text,
+            "Line one, with comma\nand \"quotes\""
+        );
+        assert_eq!(parsed.entries[0].language.as_deref(), Some("en"));
+        assert_eq!(parsed.entries[1].timestamp, "2024-03-02T10:00:00.000Z");
+        assert_eq!(parsed.entries[1].language, None);
+
+        assert!(parse_import("\"open", HistoryImportFormat::Csv).is_err());
+        assert!(parse_import("42", HistoryImportFormat::Json).is_err());
+    }
+}
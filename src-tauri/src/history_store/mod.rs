@@ -1,22 +1,40 @@
+pub mod encryption;
+pub mod import;
+pub mod reprocess;
+pub mod subtitles;
+pub mod vocabulary;
+
 use std::{
+    collections::HashSet,
     fs,
-    io::Write,
     path::{Path, PathBuf},
     sync::Mutex,
     time::{SystemTime, UNIX_EPOCH},
 };
 
 use chrono::{SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "tauri")]
 use tauri::{AppHandle, Manager};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use crate::app_error::{AppError, ErrorCode};
+use crate::database::{compact_database, open_database, DATABASE_FILE_NAME};
+use crate::transcription::{TranscriptionSegment, TranscriptionWord};
+use encryption::HistoryCipher;
+use import::HistoryImportReport;
+
+// Only read to migrate history written before the database existed.
 const HISTORY_FILE_NAME: &str = "transcript_history.json";
 pub const MAX_HISTORY_PAGE_SIZE: usize = 200;
 pub const MAX_HISTORY_ENTRIES: usize = 500;
+const IMPORT_PROGRESS_INTERVAL: usize = 250;
+const HISTORY_PREVIEW_CHARS: usize = 200;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HistoryEntry {
     pub id: String,
@@ -27,6 +45,54 @@ pub struct HistoryEntry {
     #[serde(default)]
     pub language: Option<String>,
     pub provider: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<TranscriptionSegment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<TranscriptionWord>,
+    // The provider's confidence in the whole transcript, from 0 to 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation: Option<HistoryTranslation>,
+    // Rewrites made after the fact; `text` always stays the original.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revisions: Vec<HistoryRevision>,
+    #[serde(default, skip_serializing_if = "HistorySource::is_dictation")]
+    pub source: HistorySource,
+    // Only filled in for list responses; never persisted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryTranslation {
+    pub language: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRevision {
+    pub text: String,
+    pub profile: String,
+    pub created_at: String,
+}
+
+// Where the audio came from; entries written before this existed are all
+// dictations.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum HistorySource {
+    #[default]
+    Dictation,
+    File,
+}
+
+impl HistorySource {
+    fn is_dictation(&self) -> bool {
+        *self == Self::Dictation
+    }
 }
 
 impl HistoryEntry {
@@ -43,38 +109,214 @@ impl HistoryEntry {
             duration_secs,
             language: normalize_optional(language),
             provider: provider.trim().to_string(),
+            segments: Vec::new(),
+            words: Vec::new(),
+            confidence: None,
+            translation: None,
+            revisions: Vec::new(),
+            source: HistorySource::Dictation,
+            preview: None,
         }
     }
+
+    pub fn with_source(mut self, source: HistorySource) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn with_segments(mut self, segments: Vec<TranscriptionSegment>) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    pub fn with_words(mut self, words: Vec<TranscriptionWord>) -> Self {
+        self.words = words;
+        self
+    }
+
+    pub fn with_confidence(mut self, confidence: Option<f32>) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    pub fn with_translation(mut self, translation: Option<HistoryTranslation>) -> Self {
+        self.translation = translation;
+        self
+    }
+
+    // Long meeting transcripts make list pages heavy, so lists can carry just
+    // the preview and leave the full text to `get_history_entry`.
+    pub fn into_list_item(mut self, include_text: bool) -> Self {
+        self.preview = Some(preview_text(&self.text));
+        if !include_text {
+            self.text.clear();
+            self.segments.clear();
+            self.words.clear();
+            self.revisions.clear();
+        }
+        self
+    }
+}
+
+fn preview_text(text: &str) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(HISTORY_PREVIEW_CHARS) {
+        Some((cut, _)) => format!("{}…", text[..cut].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+// Translated entries export both texts under labeled headings.
+pub fn render_transcript_export(entry: &HistoryEntry) -> String {
+    let original = render_original_transcript(entry);
+    match &entry.translation {
+        Some(translation) => format!(
+            "Original ({})\n{original}\nTranslation ({})\n{}\n",
+            entry.language.as_deref().unwrap_or("detected"),
+            translation.language,
+            translation.text.trim()
+        ),
+        None => original,
+    }
+}
+
+// Diarized entries render as "Speaker N" sections with start offsets; entries
+// without speaker labels export as plain text.
+fn render_original_transcript(entry: &HistoryEntry) -> String {
+    if !entry
+        .segments
+        .iter()
+        .any(|segment| segment.speaker.is_some())
+    {
+        return format!("{}\n", entry.text.trim());
+    }
+
+    let mut output = String::new();
+    let mut current_speaker: Option<&str> = None;
+    for segment in &entry.segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let speaker = segment.speaker.as_deref().unwrap_or("Unknown speaker");
+        if current_speaker != Some(speaker) {
+            if !output.is_empty() {
+                output.push_str("\n\n");
+            }
+            output.push_str(&format!(
+                "{speaker} [{}]\n",
+                format_timestamp(segment.start_secs)
+            ));
+            current_speaker = Some(speaker);
+        } else {
+            output.push(' ');
+        }
+        output.push_str(text);
+    }
+    output.push('\n');
+    output
+}
+
+fn format_timestamp(seconds: f64) -> String {
+    let total_secs = seconds.max(0.0).floor() as u64;
+    format!(
+        "{:02}:{:02}:{:02}",
+        total_secs / 3600,
+        (total_secs / 60) % 60,
+        total_secs % 60
+    )
 }
 
 #[derive(Debug)]
 pub struct HistoryStore {
-    file_path: PathBuf,
-    io_lock: Mutex<()>,
+    connection: Mutex<Connection>,
+    legacy_file_path: PathBuf,
+    cipher: Mutex<Option<HistoryCipher>>,
 }
 
 impl HistoryStore {
-    pub fn new(app: &AppHandle) -> Result<Self, String> {
+    #[cfg(feature = "tauri")]
+    pub fn new(app: &AppHandle) -> Result<Self, AppError> {
         let app_data_dir = app
             .path()
             .app_data_dir()
             .map_err(|error| format!("Failed to resolve app data directory: {error}"))?;
+        Self::new_in_dir(&app_data_dir)
+    }
 
-        let file_path = app_data_dir.join(HISTORY_FILE_NAME);
-        debug!(path = %file_path.display(), "initializing history store");
-        Self::new_with_file_path(file_path)
+    pub fn new_in_dir(app_data_dir: &Path) -> Result<Self, AppError> {
+        let database_path = app_data_dir.join(DATABASE_FILE_NAME);
+        debug!(path = %database_path.display(), "initializing history store");
+        let store = Self {
+            connection: Mutex::new(open_database(&database_path)?),
+            legacy_file_path: app_data_dir.join(HISTORY_FILE_NAME),
+            cipher: Mutex::new(None),
+        };
+        let mut connection = store.lock_connection()?;
+        if let Err(error) = store.import_legacy_file(&mut connection, None) {
+            warn!(%error, "failed to migrate history file into the database");
+        }
+        drop(connection);
+        Ok(store)
     }
 
-    pub fn new_with_file_path(file_path: PathBuf) -> Result<Self, String> {
-        ensure_history_file(&file_path)?;
-        Ok(Self {
-            file_path,
-            io_lock: Mutex::new(()),
-        })
+    pub fn is_encrypted(&self) -> bool {
+        self.current_cipher().is_some()
+    }
+
+    // Rewrites every entry under the new setting, so turning encryption on
+    // migrates existing plaintext history and turning it off decrypts it.
+    // Entries that are already encrypted are read with the incoming key.
+    pub fn set_encryption(&self, cipher: Option<HistoryCipher>) -> Result<(), AppError> {
+        let mut connection = self.lock_connection()?;
+        let read_cipher = self.current_cipher().or_else(|| cipher.clone());
+        let transaction = connection
+            .transaction()
+            .map_err(|error| format!("Failed to update history encryption: {error}"))?;
+        let rows = {
+            let mut statement = transaction
+                .prepare("SELECT seq, payload FROM history_entries")
+                .map_err(|error| format!("Failed to read transcript history: {error}"))?;
+            let rows = statement
+                .query_map([], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?))
+                })
+                .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+                .map_err(|error| format!("Failed to read transcript history: {error}"))?;
+            rows
+        };
+        for (seq, payload) in &rows {
+            let plaintext = open_payload(payload, read_cipher.as_ref())?;
+            let payload = seal_payload(&plaintext, cipher.as_ref())?;
+            transaction
+                .execute(
+                    "UPDATE history_entries SET payload = ?1 WHERE seq = ?2",
+                    params![payload, seq],
+                )
+                .map_err(|error| format!("Failed to update history encryption: {error}"))?;
+        }
+        transaction
+            .commit()
+            .map_err(|error| format!("Failed to update history encryption: {error}"))?;
+
+        let enabled = cipher.is_some();
+        self.replace_cipher(cipher)?;
+        info!(enabled, entries = rows.len(), "updated history encryption");
+        // The old payloads are still in the WAL and in pages the update
+        // freed; the store already reads the new ones, so a failure here is
+        // reported rather than undone.
+        compact_database(&connection)?;
+        // An encrypted legacy file could not be migrated until now.
+        if let Err(error) = self.import_legacy_file(&mut connection, read_cipher.as_ref()) {
+            warn!(%error, "failed to migrate history file into the database");
+        }
+        Ok(())
     }
 
-    pub fn add_entry(&self, entry: HistoryEntry) -> Result<(), String> {
-        validate_entry(&entry)?;
+    pub fn add_entry(&self, entry: HistoryEntry) -> Result<(), AppError> {
+        validate_entry(&entry)
+            .map_err(|message| AppError::new(ErrorCode::InvalidInput, message))?;
         debug!(
             entry_id = %entry.id,
             provider = %entry.provider,
@@ -82,192 +324,380 @@ impl HistoryStore {
             "adding history entry"
         );
 
-        let _guard = self
-            .io_lock
-            .lock()
-            .map_err(|_| "History store lock is poisoned".to_string())?;
-        let mut entries = self.read_entries()?;
-
-        let insert_at = entries.partition_point(|existing| existing.timestamp >= entry.timestamp);
-        entries.insert(insert_at, entry);
-        if entries.len() > MAX_HISTORY_ENTRIES {
-            let pruned_entries = entries.len() - MAX_HISTORY_ENTRIES;
-            entries.truncate(MAX_HISTORY_ENTRIES);
+        let mut connection = self.lock_connection()?;
+        let transaction = connection
+            .transaction()
+            .map_err(|error| format!("Failed to write transcript history: {error}"))?;
+        insert_entry(&transaction, &entry, self.current_cipher().as_ref())?;
+        let pruned_entries = prune_entries(&transaction)?;
+        transaction
+            .commit()
+            .map_err(|error| format!("Failed to write transcript history: {error}"))?;
+        if pruned_entries > 0 {
             info!(
                 pruned_entries,
                 max_entries = MAX_HISTORY_ENTRIES,
                 "pruned oldest history entries"
             );
         }
+        Ok(())
+    }
+
+    // Merges a whole batch in one transaction. Entries that match an existing
+    // one on text and timestamp are skipped, so running the same import twice
+    // is harmless.
+    pub fn import_entries(
+        &self,
+        imported: Vec<HistoryEntry>,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Result<HistoryImportReport, AppError> {
+        let mut connection = self.lock_connection()?;
+        let cipher = self.current_cipher();
+        let mut seen = read_entries(&connection, cipher.as_ref(), -1, 0)?
+            .into_iter()
+            .map(|entry| (entry.text.trim().to_string(), entry.timestamp))
+            .collect::<HashSet<_>>();
+
+        let transaction = connection
+            .transaction()
+            .map_err(|error| format!("Failed to import transcript history: {error}"))?;
+        let total = imported.len();
+        let mut report = HistoryImportReport {
+            total,
+            ..HistoryImportReport::default()
+        };
+        for (index, entry) in imported.into_iter().enumerate() {
+            if validate_entry(&entry).is_err() {
+                report.skipped += 1;
+            } else if seen.insert((entry.text.trim().to_string(), entry.timestamp.clone())) {
+                insert_entry(&transaction, &entry, cipher.as_ref())?;
+                report.imported += 1;
+            } else {
+                report.duplicates += 1;
+            }
+            if (index + 1) % IMPORT_PROGRESS_INTERVAL == 0 {
+                on_progress(index + 1, total);
+            }
+        }
 
-        self.write_entries(&entries)
+        if report.imported > 0 {
+            report.pruned = prune_entries(&transaction)?;
+        }
+        transaction
+            .commit()
+            .map_err(|error| format!("Failed to import transcript history: {error}"))?;
+        on_progress(total, total);
+        info!(
+            imported = report.imported,
+            duplicates = report.duplicates,
+            skipped = report.skipped,
+            pruned = report.pruned,
+            "imported history entries"
+        );
+        Ok(report)
     }
 
-    pub fn list_entries(&self, limit: usize, offset: usize) -> Result<Vec<HistoryEntry>, String> {
+    pub fn list_entries(&self, limit: usize, offset: usize) -> Result<Vec<HistoryEntry>, AppError> {
         if limit == 0 {
             return Ok(Vec::new());
         }
         debug!(limit, offset, "listing history entries");
 
-        let _guard = self
-            .io_lock
-            .lock()
-            .map_err(|_| "History store lock is poisoned".to_string())?;
-        let entries = self.read_entries()?;
+        let connection = self.lock_connection()?;
+        Ok(read_entries(
+            &connection,
+            self.current_cipher().as_ref(),
+            limit.min(MAX_HISTORY_PAGE_SIZE) as i64,
+            i64::try_from(offset).unwrap_or(i64::MAX),
+        )?)
+    }
 
-        Ok(entries
-            .into_iter()
-            .skip(offset)
-            .take(limit.min(MAX_HISTORY_PAGE_SIZE))
-            .collect())
+    pub fn all_entries(&self) -> Result<Vec<HistoryEntry>, AppError> {
+        let connection = self.lock_connection()?;
+        Ok(read_entries(
+            &connection,
+            self.current_cipher().as_ref(),
+            -1,
+            0,
+        )?)
     }
 
-    pub fn get_entry(&self, id: &str) -> Result<Option<HistoryEntry>, String> {
+    pub fn get_entry(&self, id: &str) -> Result<Option<HistoryEntry>, AppError> {
         debug!(id, "fetching history entry");
-        let _guard = self
-            .io_lock
-            .lock()
-            .map_err(|_| "History store lock is poisoned".to_string())?;
-        let entries = self.read_entries()?;
-
-        Ok(entries.into_iter().find(|entry| entry.id == id))
+        let connection = self.lock_connection()?;
+        let payload = connection
+            .query_row(
+                "SELECT payload FROM history_entries WHERE id = ?1 ORDER BY seq LIMIT 1",
+                [id],
+                |row| row.get::<_, Vec<u8>>(0),
+            )
+            .optional()
+            .map_err(|error| format!("Failed to read transcript history: {error}"))?;
+
+        Ok(payload
+            .map(|payload| decode_entry(&payload, self.current_cipher().as_ref()))
+            .transpose()?)
     }
 
-    pub fn delete_entry(&self, id: &str) -> Result<bool, String> {
-        info!(id, "deleting history entry");
-        let _guard = self
-            .io_lock
-            .lock()
-            .map_err(|_| "History store lock is poisoned".to_string())?;
-        let mut entries = self.read_entries()?;
-        let original_len = entries.len();
-
-        entries.retain(|entry| entry.id != id);
-        let deleted = entries.len() != original_len;
+    // Returns the updated entry, or `None` when it no longer exists.
+    pub fn add_revision(
+        &self,
+        id: &str,
+        revision: HistoryRevision,
+    ) -> Result<Option<HistoryEntry>, AppError> {
+        let connection = self.lock_connection()?;
+        let cipher = self.current_cipher();
+        let row = connection
+            .query_row(
+                "SELECT seq, payload FROM history_entries WHERE id = ?1 ORDER BY seq LIMIT 1",
+                [id],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?)),
+            )
+            .optional()
+            .map_err(|error| format!("Failed to read transcript history: {error}"))?;
+        let Some((seq, payload)) = row else {
+            return Ok(None);
+        };
 
-        if deleted {
-            self.write_entries(&entries)?;
-        }
+        let mut entry = decode_entry(&payload, cipher.as_ref())?;
+        entry.revisions.push(revision);
+        let plaintext = serde_json::to_vec(&entry)
+            .map_err(|error| format!("Failed to serialize transcript history entry: {error}"))?;
+        connection
+            .execute(
+                "UPDATE history_entries SET payload = ?1 WHERE seq = ?2",
+                params![seal_payload(&plaintext, cipher.as_ref())?, seq],
+            )
+            .map_err(|error| format!("Failed to update history entry: {error}"))?;
+        debug!(
+            id,
+            revisions = entry.revisions.len(),
+            "added history entry revision"
+        );
+        Ok(Some(entry))
+    }
 
-        Ok(deleted)
+    pub fn delete_entry(&self, id: &str) -> Result<bool, AppError> {
+        info!(id, "deleting history entry");
+        let connection = self.lock_connection()?;
+        let deleted = connection
+            .execute("DELETE FROM history_entries WHERE id = ?1", [id])
+            .map_err(|error| format!("Failed to delete history entry: {error}"))?;
+        Ok(deleted > 0)
     }
 
-    pub fn clear_history(&self) -> Result<(), String> {
+    pub fn clear_history(&self) -> Result<(), AppError> {
         info!("clearing history entries");
-        let _guard = self
-            .io_lock
-            .lock()
-            .map_err(|_| "History store lock is poisoned".to_string())?;
-        self.write_entries(&[])
+        let connection = self.lock_connection()?;
+        connection
+            .execute("DELETE FROM history_entries", [])
+            .map_err(|error| format!("Failed to clear transcript history: {error}"))?;
+        Ok(())
     }
 
-    fn read_entries(&self) -> Result<Vec<HistoryEntry>, String> {
-        if !self.file_path.exists() {
-            return Ok(Vec::new());
+    // History used to live in one JSON file. It is copied into the database
+    // once and then removed; an encrypted file waits until encryption is
+    // turned on with its key.
+    fn import_legacy_file(
+        &self,
+        connection: &mut Connection,
+        read_cipher: Option<&HistoryCipher>,
+    ) -> Result<(), String> {
+        let file_path = &self.legacy_file_path;
+        if !file_path.exists() {
+            return Ok(());
         }
 
-        let raw_contents = fs::read_to_string(&self.file_path)
+        let raw_bytes = fs::read(file_path)
             .map_err(|error| format!("Failed to read transcript history file: {error}"))?;
-
-        if raw_contents.trim().is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let mut entries = match serde_json::from_str::<Vec<HistoryEntry>>(&raw_contents) {
-            Ok(parsed) => parsed,
-            Err(error) => {
-                self.recover_malformed_history_file(format!(
-                    "Failed to parse transcript history file: {error}"
-                ))?;
-                return Ok(Vec::new());
+        let raw_bytes = if encryption::is_encrypted(&raw_bytes) {
+            let Some(cipher) = read_cipher else {
+                info!("legacy history file is encrypted; migrating once the key is available");
+                return Ok(());
+            };
+            cipher.decrypt(&raw_bytes)?
+        } else {
+            raw_bytes
+        };
+        let mut entries = match parse_legacy_entries(raw_bytes) {
+            Ok(entries) => entries,
+            Err(reason) => {
+                let backup_path = backup_corrupt_history_file(file_path)?;
+                warn!(
+                    path = %file_path.display(),
+                    backup = %backup_path.display(),
+                    reason = %reason,
+                    "recovered malformed history file"
+                );
+                return Ok(());
             }
         };
 
-        if let Err(error) = entries.iter().try_for_each(validate_entry) {
-            self.recover_malformed_history_file(format!(
-                "Failed to validate transcript history file: {error}"
-            ))?;
-            return Ok(Vec::new());
-        }
-
-        if !entries
-            .windows(2)
-            .all(|window| window[0].timestamp >= window[1].timestamp)
-        {
-            entries.sort_by(|left, right| right.timestamp.cmp(&left.timestamp));
+        // Skip ids that are already present in case an earlier migration
+        // committed but could not remove the file.
+        let existing_ids = {
+            let mut statement = connection
+                .prepare("SELECT id FROM history_entries")
+                .map_err(|error| format!("Failed to read transcript history: {error}"))?;
+            let ids = statement
+                .query_map([], |row| row.get::<_, String>(0))
+                .and_then(Iterator::collect::<Result<HashSet<_>, _>>)
+                .map_err(|error| format!("Failed to read transcript history: {error}"))?;
+            ids
+        };
+        entries.retain(|entry| !existing_ids.contains(&entry.id));
+
+        let cipher = self.current_cipher();
+        let transaction = connection
+            .transaction()
+            .map_err(|error| format!("Failed to migrate transcript history: {error}"))?;
+        for entry in &entries {
+            insert_entry(&transaction, entry, cipher.as_ref())?;
         }
-
-        Ok(entries)
+        prune_entries(&transaction)?;
+        transaction
+            .commit()
+            .map_err(|error| format!("Failed to migrate transcript history: {error}"))?;
+        fs::remove_file(file_path)
+            .map_err(|error| format!("Failed to remove migrated history file: {error}"))?;
+        info!(
+            entries = entries.len(),
+            "migrated history file into the database"
+        );
+        Ok(())
     }
 
-    fn write_entries(&self, entries: &[HistoryEntry]) -> Result<(), String> {
-        let serialized = serde_json::to_vec_pretty(entries)
-            .map_err(|error| format!("Failed to serialize transcript history entries: {error}"))?;
-        let temp_path = temp_file_path_for(&self.file_path);
+    fn lock_connection(&self) -> Result<std::sync::MutexGuard<'_, Connection>, String> {
+        self.connection
+            .lock()
+            .map_err(|_| "History store lock is poisoned".to_string())
+    }
 
-        let mut temp_file = fs::OpenOptions::new()
-            .create_new(true)
-            .write(true)
-            .open(&temp_path)
-            .map_err(|error| {
-                format!(
-                    "Failed to create transcript history temp file `{}`: {error}",
-                    temp_path.display()
-                )
-            })?;
+    fn replace_cipher(
+        &self,
+        cipher: Option<HistoryCipher>,
+    ) -> Result<Option<HistoryCipher>, String> {
+        let mut current = self
+            .cipher
+            .lock()
+            .map_err(|_| "History cipher lock is poisoned".to_string())?;
+        Ok(std::mem::replace(&mut *current, cipher))
+    }
 
-        if let Err(error) = temp_file.write_all(&serialized) {
-            let _ = fs::remove_file(&temp_path);
-            return Err(format!(
-                "Failed to write transcript history temp file `{}`: {error}",
-                temp_path.display()
-            ));
-        }
+    pub fn current_cipher(&self) -> Option<HistoryCipher> {
+        self.cipher
+            .lock()
+            .map(|cipher| cipher.clone())
+            .unwrap_or(None)
+    }
+}
 
-        if let Err(error) = temp_file.sync_all() {
-            let _ = fs::remove_file(&temp_path);
-            return Err(format!(
-                "Failed to flush transcript history temp file `{}`: {error}",
-                temp_path.display()
-            ));
+// Newest first; entries that share a timestamp keep the order they were
+// added in. A limit of -1 reads everything.
+fn read_entries(
+    connection: &Connection,
+    cipher: Option<&HistoryCipher>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<HistoryEntry>, String> {
+    let mut statement = connection
+        .prepare(
+            "SELECT seq, payload FROM history_entries
+             ORDER BY timestamp DESC, seq ASC LIMIT ?1 OFFSET ?2",
+        )
+        .map_err(|error| format!("Failed to read transcript history: {error}"))?;
+    let rows = statement
+        .query_map(params![limit, offset], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })
+        .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+        .map_err(|error| format!("Failed to read transcript history: {error}"))?;
+
+    let mut entries = Vec::with_capacity(rows.len());
+    for (seq, payload) in rows {
+        // A row that cannot be parsed costs that one entry, not the history;
+        // a missing key is still an error so it is never mistaken for this.
+        let plaintext = open_payload(&payload, cipher)?;
+        match parse_entry(&plaintext) {
+            Ok(entry) => entries.push(entry),
+            Err(error) => warn!(seq, %error, "skipping unreadable history entry"),
         }
+    }
+    Ok(entries)
+}
 
-        drop(temp_file);
+// Timestamps stay in the clear even when encryption is on, so ordering and
+// pruning work without the key.
+fn insert_entry(
+    connection: &Connection,
+    entry: &HistoryEntry,
+    cipher: Option<&HistoryCipher>,
+) -> Result<(), String> {
+    let plaintext = serde_json::to_vec(entry)
+        .map_err(|error| format!("Failed to serialize transcript history entry: {error}"))?;
+    connection
+        .execute(
+            "INSERT INTO history_entries (id, timestamp, payload) VALUES (?1, ?2, ?3)",
+            params![entry.id, entry.timestamp, seal_payload(&plaintext, cipher)?],
+        )
+        .map_err(|error| format!("Failed to write transcript history: {error}"))?;
+    Ok(())
+}
 
-        fs::rename(&temp_path, &self.file_path).map_err(|error| {
-            let _ = fs::remove_file(&temp_path);
-            format!("Failed to finalize transcript history file: {error}")
-        })?;
+fn prune_entries(connection: &Connection) -> Result<usize, String> {
+    connection
+        .execute(
+            "DELETE FROM history_entries WHERE seq NOT IN (
+                SELECT seq FROM history_entries ORDER BY timestamp DESC, seq ASC LIMIT ?1
+             )",
+            [MAX_HISTORY_ENTRIES as i64],
+        )
+        .map_err(|error| format!("Failed to prune transcript history: {error}"))
+}
 
-        Ok(())
+fn open_payload(payload: &[u8], cipher: Option<&HistoryCipher>) -> Result<Vec<u8>, String> {
+    if !encryption::is_encrypted(payload) {
+        return Ok(payload.to_vec());
     }
+    let cipher = cipher.ok_or_else(|| {
+        "Transcript history is encrypted; enable history encryption to read it".to_string()
+    })?;
+    cipher.decrypt(payload)
+}
 
-    fn recover_malformed_history_file(&self, reason: String) -> Result<(), String> {
-        let backup_path = backup_corrupt_history_file(&self.file_path)?;
-        self.write_entries(&[])?;
-        warn!(
-            path = %self.file_path.display(),
-            backup = %backup_path.display(),
-            reason = %reason,
-            "recovered malformed history file"
-        );
-        Ok(())
+fn seal_payload(plaintext: &[u8], cipher: Option<&HistoryCipher>) -> Result<Vec<u8>, String> {
+    match cipher {
+        Some(cipher) => cipher.encrypt(plaintext),
+        None => Ok(plaintext.to_vec()),
     }
 }
 
-fn ensure_history_file(file_path: &Path) -> Result<(), String> {
-    if let Some(parent_dir) = file_path.parent() {
-        fs::create_dir_all(parent_dir)
-            .map_err(|error| format!("Failed to create history directory: {error}"))?;
-    }
+fn parse_entry(plaintext: &[u8]) -> Result<HistoryEntry, String> {
+    let entry = serde_json::from_slice::<HistoryEntry>(plaintext)
+        .map_err(|error| format!("Failed to parse transcript history entry: {error}"))?;
+    validate_entry(&entry)?;
+    Ok(entry)
+}
 
-    if !file_path.exists() {
-        fs::write(file_path, "[]")
-            .map_err(|error| format!("Failed to initialize history file: {error}"))?;
-        info!(path = %file_path.display(), "created history file");
+fn decode_entry(payload: &[u8], cipher: Option<&HistoryCipher>) -> Result<HistoryEntry, String> {
+    parse_entry(&open_payload(payload, cipher)?)
+}
+
+fn parse_legacy_entries(raw_bytes: Vec<u8>) -> Result<Vec<HistoryEntry>, String> {
+    let raw_contents = String::from_utf8(raw_bytes)
+        .map_err(|error| format!("Failed to read transcript history file: {error}"))?;
+    if raw_contents.trim().is_empty() {
+        return Ok(Vec::new());
     }
 
-    Ok(())
+    let mut entries = serde_json::from_str::<Vec<HistoryEntry>>(&raw_contents)
+        .map_err(|error| format!("Failed to parse transcript history file: {error}"))?;
+    entries
+        .iter()
+        .try_for_each(validate_entry)
+        .map_err(|error| format!("Failed to validate transcript history file: {error}"))?;
+    entries.sort_by(|left, right| right.timestamp.cmp(&left.timestamp));
+    Ok(entries)
 }
 
 fn normalize_optional(value: Option<String>) -> Option<String> {
@@ -281,22 +711,6 @@ fn normalize_optional(value: Option<String>) -> Option<String> {
     })
 }
 
-fn temp_file_path_for(file_path: &Path) -> PathBuf {
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_nanos();
-    let file_name = file_path
-        .file_name()
-        .and_then(|value| value.to_str())
-        .unwrap_or("transcript_history.json");
-
-    file_path.with_file_name(format!(
-        ".{file_name}.{}.{timestamp}.tmp",
-        std::process::id()
-    ))
-}
-
 fn backup_corrupt_history_file(file_path: &Path) -> Result<PathBuf, String> {
     let timestamp = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -349,12 +763,24 @@ mod tests {
     fn create_test_store() -> (HistoryStore, PathBuf, PathBuf) {
         let test_dir = std::env::temp_dir().join(format!("voice-history-store-{}", Uuid::new_v4()));
         let file_path = test_dir.join(HISTORY_FILE_NAME);
-        let store = HistoryStore::new_with_file_path(file_path.clone())
-            .expect("history store should initialize for tests");
+        let store =
+            HistoryStore::new_in_dir(&test_dir).expect("history store should initialize for tests");
 
         (store, file_path, test_dir)
     }
 
+    fn stored_payloads(store: &HistoryStore) -> Vec<Vec<u8>> {
+        let connection = store.connection.lock().expect("connection should lock");
+        let mut statement = connection
+            .prepare("SELECT payload FROM history_entries")
+            .expect("payload query should prepare");
+        let payloads = statement
+            .query_map([], |row| row.get(0))
+            .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+            .expect("payloads should read");
+        payloads
+    }
+
     fn cleanup_test_dir(test_dir: &Path) {
         let _ = fs::remove_dir_all(test_dir);
     }
@@ -391,9 +817,54 @@ mod tests {
             duration_secs: Some(2.5),
             language: Some("en".to_string()),
             provider: "openai".to_string(),
+            segments: Vec::new(),
+            words: Vec::new(),
+            confidence: None,
+            translation: None,
+            revisions: Vec::new(),
+            source: HistorySource::Dictation,
+            preview: None,
         }
     }
 
+    #[test]
+    fn list_items_carry_a_bounded_preview_and_can_drop_the_text() {
+        let long_text = format!("{} tail", "word ".repeat(60));
+        let entry = test_entry(&long_text, "2024-01-01T00:00:00.000Z");
+
+        let with_text = entry.clone().into_list_item(true);
+        assert_eq!(with_text.text, long_text);
+        let preview = with_text.preview.expect("preview should be set");
+        assert!(preview.ends_with("word…"));
+        assert_eq!(preview.chars().count(), 200);
+
+        let without_text = entry.into_list_item(false);
+        assert!(without_text.text.is_empty());
+        assert_eq!(without_text.preview.as_deref(), Some(preview.as_str()));
+
+        let short = test_entry("  short note ", "2024-01-01T00:00:00.000Z").into_list_item(false);
+        assert_eq!(short.preview.as_deref(), Some("short note"));
+    }
+
+    #[test]
+    fn source_is_only_written_for_non_dictation_entries() {
+        let dictation = test_entry("hello", "2024-01-01T00:00:00.000Z");
+        let dictation_json = serde_json::to_value(&dictation).expect("entry should serialize");
+        assert!(dictation_json.get("source").is_none());
+
+        let file = dictation.with_source(HistorySource::File);
+        let file_json = serde_json::to_value(&file).expect("entry should serialize");
+        assert_eq!(file_json["source"], "file");
+
+        let mut legacy = file_json;
+        legacy
+            .as_object_mut()
+            .expect("entry is an object")
+            .remove("source");
+        let legacy: HistoryEntry = serde_json::from_value(legacy).expect("entry should parse");
+        assert_eq!(legacy.source, HistorySource::Dictation);
+    }
+
     #[test]
     fn supports_add_get_delete_and_clear() {
         let (store, _file_path, test_dir) = create_test_store();
@@ -450,6 +921,48 @@ mod tests {
         cleanup_test_dir(&test_dir);
     }
 
+    #[test]
+    fn renders_speaker_sections_for_diarized_entries() {
+        let segment = |start_secs: f64, speaker: &str, text: &str| TranscriptionSegment {
+            start_secs,
+            end_secs: start_secs + 1.0,
+            text: text.to_string(),
+            speaker: Some(speaker.to_string()),
+            confidence: None,
+        };
+        let entry = test_entry("hi there hello again", "2026-01-01T09:00:00Z").with_segments(vec![
+            segment(0.0, "Speaker 1", "hi"),
+            segment(1.0, "Speaker 1", "there"),
+            segment(65.0, "Speaker 2", "hello"),
+            segment(3_700.0, "Speaker 1", "again"),
+        ]);
+
+        assert_eq!(
+            render_transcript_export(&entry),
+            "Speaker 1 [00:00:00]\nhi there\n\nSpeaker 2 [00:01:05]\nhello\n\nSpeaker 1 [01:01:40]\nagain\n"
+        );
+        assert_eq!(
+            render_transcript_export(&test_entry(" plain ", "2026-01-01T09:00:00Z")),
+            "plain\n"
+        );
+    }
+
+    #[test]
+    fn labels_original_and_translated_text_in_exports() {
+        let mut entry = test_entry("hola mundo", "2026-01-01T09:00:00Z").with_translation(Some(
+            HistoryTranslation {
+                language: "English".to_string(),
+                text: " hello world ".to_string(),
+            },
+        ));
+        entry.language = Some("es".to_string());
+
+        assert_eq!(
+            render_transcript_export(&entry),
+            "Original (es)\nhola mundo\n\nTranslation (English)\nhello world\n"
+        );
+    }
+
     #[test]
     fn lists_newest_first_with_pagination() {
         let (store, _file_path, test_dir) = create_test_store();
@@ -527,38 +1040,140 @@ mod tests {
             duration_secs: None,
             language: None,
             provider: "openai".to_string(),
+            segments: Vec::new(),
+            words: Vec::new(),
+            confidence: None,
+            translation: None,
+            revisions: Vec::new(),
+            source: HistorySource::Dictation,
+            preview: None,
         };
 
         let error = store
             .add_entry(invalid_entry)
             .expect_err("entry with an empty id should be rejected");
-        assert!(error.contains("id"));
+        assert_eq!(error.code, ErrorCode::InvalidInput);
+        assert!(error.message.contains("id"));
 
         cleanup_test_dir(&test_dir);
     }
 
     #[test]
-    fn reports_invalid_json_file_contents() {
-        let (store, file_path, test_dir) = create_test_store();
+    fn backs_up_a_malformed_legacy_history_file() {
+        let (_store, file_path, test_dir) = create_test_store();
 
         fs::write(&file_path, "{ not valid json")
             .expect("test should be able to write malformed json");
-        let listed = store
-            .list_entries(10, 0)
+        let store = HistoryStore::new_in_dir(&test_dir)
             .expect("malformed json should be recovered automatically");
 
-        assert!(listed.is_empty());
+        assert!(store
+            .list_entries(10, 0)
+            .expect("history should list")
+            .is_empty());
         assert_eq!(corrupt_backup_paths(&file_path).len(), 1);
-        assert_eq!(
-            fs::read_to_string(&file_path).expect("recovered history file should be readable"),
-            "[]"
-        );
+        assert!(!file_path.exists());
         cleanup_test_dir(&test_dir);
     }
 
     #[test]
-    fn list_entries_enforces_max_page_size() {
+    fn legacy_history_file_is_migrated_once() {
         let (store, file_path, test_dir) = create_test_store();
+        store
+            .add_entry(test_entry(
+                "recorded after upgrade",
+                "2026-01-03T00:00:00.000Z",
+            ))
+            .expect("entry should be added");
+        let legacy = vec![
+            test_entry("second", "2026-01-02T00:00:00.000Z"),
+            test_entry("first", "2026-01-01T00:00:00.000Z"),
+        ];
+        fs::write(
+            &file_path,
+            serde_json::to_vec_pretty(&legacy).expect("entries should serialize"),
+        )
+        .expect("legacy history should be written");
+
+        let migrated = HistoryStore::new_in_dir(&test_dir).expect("store should reopen");
+        assert!(!file_path.exists());
+        // Reopening after the file is gone must not duplicate anything.
+        let reopened = HistoryStore::new_in_dir(&test_dir).expect("store should reopen");
+        for store in [migrated, reopened] {
+            let texts = store
+                .all_entries()
+                .expect("history should read")
+                .into_iter()
+                .map(|entry| entry.text)
+                .collect::<Vec<_>>();
+            assert_eq!(texts, vec!["recorded after upgrade", "second", "first"]);
+        }
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn encryption_migrates_existing_history_both_ways() {
+        let (store, file_path, test_dir) = create_test_store();
+        store
+            .add_entry(test_entry("patient notes", "2026-01-01T00:00:00.000Z"))
+            .expect("entry should be added");
+
+        let cipher = HistoryCipher::generate();
+        store
+            .set_encryption(Some(cipher.clone()))
+            .expect("encryption should enable");
+        let payloads = stored_payloads(&store);
+        assert!(encryption::is_encrypted(&payloads[0]));
+        assert!(!String::from_utf8_lossy(&payloads[0]).contains("patient notes"));
+        assert_eq!(store.all_entries().expect("should decrypt").len(), 1);
+        // Nothing of the plaintext payload survives in free pages or the WAL.
+        for file_name in [
+            DATABASE_FILE_NAME.to_string(),
+            format!("{DATABASE_FILE_NAME}-wal"),
+        ] {
+            let bytes = fs::read(test_dir.join(file_name)).unwrap_or_default();
+            assert!(!String::from_utf8_lossy(&bytes).contains("patient notes"));
+        }
+
+        let locked_out =
+            HistoryStore::new_in_dir(&test_dir).expect("second store should initialize");
+        assert!(locked_out.all_entries().is_err());
+        assert!(corrupt_backup_paths(&file_path).is_empty());
+        locked_out
+            .set_encryption(Some(cipher.clone()))
+            .expect("encrypted history should open with its key");
+        assert_eq!(locked_out.all_entries().expect("should decrypt").len(), 1);
+
+        store
+            .set_encryption(None)
+            .expect("encryption should disable");
+        assert!(String::from_utf8_lossy(&stored_payloads(&store)[0]).contains("patient notes"));
+
+        // A legacy file written while encryption was on waits for the key.
+        fs::write(
+            &file_path,
+            cipher
+                .encrypt(
+                    &serde_json::to_vec(&vec![test_entry("old notes", "2025-01-01T00:00:00Z")])
+                        .expect("entries should serialize"),
+                )
+                .expect("legacy history should encrypt"),
+        )
+        .expect("legacy history should be written");
+        let reopened = HistoryStore::new_in_dir(&test_dir).expect("store should reopen");
+        assert!(file_path.exists());
+        reopened
+            .set_encryption(Some(cipher))
+            .expect("encryption should enable");
+        assert!(!file_path.exists());
+        assert_eq!(reopened.all_entries().expect("should decrypt").len(), 2);
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn list_entries_enforces_max_page_size() {
+        let (_store, file_path, test_dir) = create_test_store();
         let entry_count = MAX_HISTORY_PAGE_SIZE + 5;
         let entries: Vec<HistoryEntry> = (0..entry_count)
             .map(|index| HistoryEntry {
@@ -568,6 +1183,13 @@ mod tests {
                 duration_secs: None,
                 language: None,
                 provider: "openai".to_string(),
+                segments: Vec::new(),
+                words: Vec::new(),
+                confidence: None,
+                translation: None,
+                revisions: Vec::new(),
+                source: HistorySource::Dictation,
+                preview: None,
             })
             .collect();
         fs::write(
@@ -576,6 +1198,7 @@ mod tests {
         )
         .expect("history file should be written");
 
+        let store = HistoryStore::new_in_dir(&test_dir).expect("store should reopen");
         let page = store
             .list_entries(usize::MAX, 0)
             .expect("list should respect page cap");
@@ -584,9 +1207,45 @@ mod tests {
         cleanup_test_dir(&test_dir);
     }
 
+    #[test]
+    fn import_merges_by_timestamp_and_skips_duplicates() {
+        let (store, _file_path, test_dir) = create_test_store();
+        store
+            .add_entry(test_entry("existing", "2026-01-02T00:00:00.000Z"))
+            .expect("entry should be added");
+
+        let mut progress = Vec::new();
+        let report = store
+            .import_entries(
+                vec![
+                    test_entry("older", "2026-01-01T00:00:00.000Z"),
+                    test_entry("existing", "2026-01-02T00:00:00.000Z"),
+                    test_entry("newer", "2026-01-03T00:00:00.000Z"),
+                    test_entry("newer", "2026-01-03T00:00:00.000Z"),
+                    test_entry(" ", "2026-01-04T00:00:00.000Z"),
+                ],
+                |processed, total| progress.push((processed, total)),
+            )
+            .expect("import should succeed");
+
+        assert_eq!(report.imported, 2);
+        assert_eq!(report.duplicates, 2);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(progress.last(), Some(&(5, 5)));
+        let texts = store
+            .all_entries()
+            .expect("history should read")
+            .into_iter()
+            .map(|entry| entry.text)
+            .collect::<Vec<_>>();
+        assert_eq!(texts, vec!["newer", "existing", "older"]);
+
+        cleanup_test_dir(&test_dir);
+    }
+
     #[test]
     fn add_entry_prunes_oldest_entries_when_over_max() {
-        let (store, file_path, test_dir) = create_test_store();
+        let (store, _file_path, test_dir) = create_test_store();
         let entry_count = MAX_HISTORY_ENTRIES + 25;
 
         for index in 0..entry_count {
@@ -598,13 +1257,18 @@ mod tests {
                     duration_secs: None,
                     language: None,
                     provider: "openai".to_string(),
+                    segments: Vec::new(),
+                    words: Vec::new(),
+                    confidence: None,
+                    translation: None,
+                    revisions: Vec::new(),
+                    source: HistorySource::Dictation,
+                    preview: None,
                 })
                 .expect("entry should be added");
         }
 
-        let persisted = fs::read_to_string(&file_path).expect("history file should be readable");
-        let entries: Vec<HistoryEntry> =
-            serde_json::from_str(&persisted).expect("history JSON should parse");
+        let entries = store.all_entries().expect("history should read");
         let expected_newest = format!("entry-{}", entry_count - 1);
         let expected_oldest_retained = format!("entry-{}", entry_count - MAX_HISTORY_ENTRIES);
 
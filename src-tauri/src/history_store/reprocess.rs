@@ -0,0 +1,93 @@
+use chrono::{SecondsFormat, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::HistoryRevision;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryReprocessReport {
+    pub total: usize,
+    pub reprocessed: usize,
+    // Ids that were deleted or pruned before their turn came.
+    pub missing: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryReprocessProgressEvent {
+    pub processed: usize,
+    pub total: usize,
+    pub entry_id: String,
+    pub error: Option<String>,
+}
+
+// Each id once, in the order given; the UI may send a selection that
+// overlaps itself.
+pub fn unique_entry_ids(entry_ids: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    entry_ids
+        .into_iter()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty() && seen.insert(id.clone()))
+        .collect()
+}
+
+pub fn revision(text: String, profile: &str) -> HistoryRevision {
+    HistoryRevision {
+        text,
+        profile: profile.to_string(),
+        created_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{revision, unique_entry_ids};
+    use crate::history_store::HistoryStore;
+
+    #[test]
+    fn revisions_are_appended_and_keep_the_original_text() {
+        let dir = std::env::temp_dir().join(format!("voice-reprocess-{}", uuid::Uuid::new_v4()));
+        let store = HistoryStore::new_in_dir(&dir).expect("store should open");
+        let entry = crate::history_store::HistoryEntry::new(
+            "um so the meeting is at three".to_string(),
+            None,
+            None,
+            "openai".to_string(),
+        );
+        let id = entry.id.clone();
+        store.add_entry(entry).expect("entry should save");
+
+        store
+            .add_revision(
+                &id,
+                revision("The meeting is at three.".to_string(), "clean"),
+            )
+            .expect("revision should save");
+        let updated = store
+            .get_entry(&id)
+            .expect("entry should load")
+            .expect("entry should exist");
+        assert_eq!(updated.text, "um so the meeting is at three");
+        assert_eq!(updated.revisions.len(), 1);
+        assert_eq!(updated.revisions[0].profile, "clean");
+        assert!(store
+            .add_revision("gone", revision(String::new(), "clean"))
+            .expect("missing entry is not an error")
+            .is_none());
+
+        assert_eq!(
+            unique_entry_ids(vec![
+                "a".to_string(),
+                " b ".to_string(),
+                "a".to_string(),
+                String::new(),
+            ]),
+            ["a", "b"]
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
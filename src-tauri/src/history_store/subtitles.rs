@@ -0,0 +1,222 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::HistoryEntry;
+
+// Used when an entry has neither timestamps nor a recorded duration.
+const ESTIMATED_CHARS_PER_SEC: f64 = 15.0;
+const MAX_WORD_CUE_SECS: f64 = 6.0;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SubtitleCue {
+    start_secs: f64,
+    end_secs: f64,
+    speaker: Option<String>,
+    text: String,
+}
+
+pub fn render_subtitles(entry: &HistoryEntry, format: SubtitleFormat) -> String {
+    let cues = subtitle_cues(entry);
+    let mut output = match format {
+        SubtitleFormat::Srt => String::new(),
+        SubtitleFormat::Vtt => "WEBVTT\n\n".to_string(),
+    };
+
+    for (index, cue) in cues.iter().enumerate() {
+        let start = format_cue_timestamp(cue.start_secs, format);
+        let end = format_cue_timestamp(cue.end_secs, format);
+        match format {
+            SubtitleFormat::Srt => {
+                output.push_str(&format!("{}\n{start} --> {end}\n", index + 1));
+                if let Some(speaker) = cue.speaker.as_deref() {
+                    output.push_str(&format!("{speaker}: "));
+                }
+            }
+            SubtitleFormat::Vtt => {
+                output.push_str(&format!("{start} --> {end}\n"));
+                if let Some(speaker) = cue.speaker.as_deref() {
+                    output.push_str(&format!("<v {speaker}>"));
+                }
+            }
+        }
+        output.push_str(&cue.text);
+        output.push_str("\n\n");
+    }
+
+    output
+}
+
+// Prefer provider segments, then word timings, and finally spread sentences
+// across the recording proportionally to their length.
+fn subtitle_cues(entry: &HistoryEntry) -> Vec<SubtitleCue> {
+    let segment_cues = entry
+        .segments
+        .iter()
+        .filter(|segment| !segment.text.trim().is_empty())
+        .map(|segment| SubtitleCue {
+            start_secs: segment.start_secs,
+            end_secs: segment.end_secs.max(segment.start_secs),
+            speaker: segment.speaker.clone(),
+            text: segment.text.trim().to_string(),
+        })
+        .collect::<Vec<_>>();
+    if !segment_cues.is_empty() {
+        return segment_cues;
+    }
+
+    if !entry.words.is_empty() {
+        return word_cues(entry);
+    }
+
+    heuristic_cues(entry.text.trim(), entry.duration_secs)
+}
+
+fn word_cues(entry: &HistoryEntry) -> Vec<SubtitleCue> {
+    let mut cues: Vec<SubtitleCue> = Vec::new();
+    let mut current: Option<SubtitleCue> = None;
+    for word in &entry.words {
+        let cue = current.get_or_insert_with(|| SubtitleCue {
+            start_secs: word.start_secs,
+            end_secs: word.end_secs,
+            speaker: None,
+            text: String::new(),
+        });
+        if !cue.text.is_empty() {
+            cue.text.push(' ');
+        }
+        cue.text.push_str(word.text.trim());
+        cue.end_secs = word.end_secs.max(cue.start_secs);
+
+        if ends_sentence(&cue.text) || cue.end_secs - cue.start_secs >= MAX_WORD_CUE_SECS {
+            cues.extend(current.take());
+        }
+    }
+    cues.extend(current);
+    cues
+}
+
+fn heuristic_cues(text: &str, duration_secs: Option<f64>) -> Vec<SubtitleCue> {
+    let sentences = split_sentences(text);
+    let total_chars = sentences
+        .iter()
+        .map(|sentence| sentence.chars().count())
+        .sum::<usize>()
+        .max(1) as f64;
+    let duration_secs = duration_secs
+        .filter(|duration| *duration > 0.0)
+        .unwrap_or(total_chars / ESTIMATED_CHARS_PER_SEC);
+
+    let mut start_secs = 0.0;
+    sentences
+        .into_iter()
+        .map(|sentence| {
+            let share = sentence.chars().count() as f64 / total_chars;
+            let end_secs = start_secs + duration_secs * share;
+            let cue = SubtitleCue {
+                start_secs,
+                end_secs,
+                speaker: None,
+                text: sentence,
+            };
+            start_secs = end_secs;
+            cue
+        })
+        .collect()
+}
+
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+        if ends_sentence(word) {
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        sentences.push(current);
+    }
+    sentences
+}
+
+fn ends_sentence(text: &str) -> bool {
+    text.trim_end_matches(['"', '\'', ')'])
+        .ends_with(['.', '!', '?'])
+}
+
+fn format_cue_timestamp(seconds: f64, format: SubtitleFormat) -> String {
+    let total_millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    let separator = match format {
+        SubtitleFormat::Srt => ',',
+        SubtitleFormat::Vtt => '.',
+    };
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:03}",
+        total_millis / 3_600_000,
+        (total_millis / 60_000) % 60,
+        (total_millis / 1000) % 60,
+        total_millis % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_subtitles, SubtitleFormat};
+    use crate::{history_store::HistoryEntry, transcription::TranscriptionSegment};
+
+    fn entry(text: &str, duration_secs: Option<f64>) -> HistoryEntry {
+        HistoryEntry::new(text.to_string(), duration_secs, None, "openai".to_string())
+    }
+
+    #[test]
+    fn renders_srt_and_vtt_from_segment_timestamps() {
+        let entry = entry("hi there. hello", Some(3.0)).with_segments(vec![
+            TranscriptionSegment {
+                start_secs: 0.0,
+                end_secs: 1.25,
+                text: " hi there. ".to_string(),
+                speaker: Some("Speaker 1".to_string()),
+                confidence: None,
+            },
+            TranscriptionSegment {
+                start_secs: 1.25,
+                end_secs: 3661.5,
+                text: "hello".to_string(),
+                speaker: None,
+                confidence: None,
+            },
+        ]);
+
+        assert_eq!(
+            render_subtitles(&entry, SubtitleFormat::Srt),
+            "1\n00:00:00,000 --> 00:00:01,250\nSpeaker 1: hi there.\n\n\
+             2\n00:00:01,250 --> 01:01:01,500\nhello\n\n"
+        );
+        assert_eq!(
+            render_subtitles(&entry, SubtitleFormat::Vtt),
+            "WEBVTT\n\n00:00:00.000 --> 00:00:01.250\n<v Speaker 1>hi there.\n\n\
+             00:00:01.250 --> 01:01:01.500\nhello\n\n"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_sentence_timing_without_timestamps() {
+        let entry = entry("One two. Six ten!", Some(9.0));
+
+        assert_eq!(
+            render_subtitles(&entry, SubtitleFormat::Srt),
+            "1\n00:00:00,000 --> 00:00:04,500\nOne two.\n\n\
+             2\n00:00:04,500 --> 00:00:09,000\nSix ten!\n\n"
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::HistoryEntry;
+
+const TOP_TERMS_LIMIT: usize = 25;
+const MAX_VOCABULARY_CANDIDATES: usize = 20;
+const MIN_CANDIDATE_OCCURRENCES: u64 = 2;
+const MIN_WORD_CHARS: usize = 2;
+
+// Function words plus the filler that dictation picks up; none of these say
+// anything about what the user talks about.
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "after", "again", "all", "also", "am", "an", "and", "any", "are", "as", "at",
+    "be", "because", "been", "before", "being", "but", "by", "can", "could", "did", "do", "does",
+    "doing", "don't", "down", "each", "even", "few", "for", "from", "get", "got", "had", "has",
+    "have", "having", "he", "her", "here", "him", "his", "how", "i", "i'm", "i've", "i'll", "if",
+    "in", "into", "is", "it", "it's", "its", "just", "know", "let", "like", "me", "more", "most",
+    "my", "no", "not", "now", "of", "off", "oh", "ok", "okay", "on", "once", "one", "only", "or",
+    "other", "our", "out", "over", "really", "right", "so", "some", "such", "than", "that",
+    "that's", "the", "their", "them", "then", "there", "these", "they", "thing", "things", "think",
+    "this", "those", "through", "to", "too", "uh", "um", "up", "very", "was", "we", "well", "were",
+    "what", "when", "where", "which", "while", "who", "why", "will", "with", "would", "yeah",
+    "yes", "you", "your",
+];
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VocabularyRange {
+    Last7Days,
+    Last30Days,
+    #[default]
+    AllTime,
+}
+
+impl VocabularyRange {
+    fn cutoff(self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Last7Days => Some(now - Duration::days(7)),
+            Self::Last30Days => Some(now - Duration::days(30)),
+            Self::AllTime => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TermFrequency {
+    pub term: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VocabularyReport {
+    pub range: VocabularyRange,
+    pub entries_analyzed: usize,
+    pub total_words: u64,
+    pub top_words: Vec<TermFrequency>,
+    pub top_bigrams: Vec<TermFrequency>,
+    // Repeated words that look like names, products or jargon (capitalised
+    // mid-sentence, or mixing letters with digits), as custom vocabulary
+    // suggestions.
+    pub vocabulary_candidates: Vec<String>,
+}
+
+pub fn build_vocabulary_report(
+    entries: &[HistoryEntry],
+    range: VocabularyRange,
+    now: DateTime<Utc>,
+) -> VocabularyReport {
+    let cutoff = range.cutoff(now);
+    let mut word_counts = HashMap::<String, u64>::new();
+    let mut bigram_counts = HashMap::<String, u64>::new();
+    let mut candidate_counts = HashMap::<String, (String, u64)>::new();
+    let mut entries_analyzed = 0;
+    let mut total_words = 0;
+
+    for entry in entries {
+        let in_range = cutoff.is_none_or(|cutoff| {
+            DateTime::parse_from_rfc3339(&entry.timestamp)
+                .is_ok_and(|timestamp| timestamp.with_timezone(&Utc) >= cutoff)
+        });
+        if !in_range {
+            continue;
+        }
+        entries_analyzed += 1;
+
+        for sentence in entry.text.split(['.', '!', '?', ';', ':', '\n']) {
+            let mut previous_content_word: Option<String> = None;
+            for (position, token) in tokenize(sentence).enumerate() {
+                total_words += 1;
+                let word = token.to_lowercase();
+                if is_stop_word(&word) || word.chars().count() < MIN_WORD_CHARS {
+                    previous_content_word = None;
+                    continue;
+                }
+
+                *word_counts.entry(word.clone()).or_default() += 1;
+                if let Some(previous) = previous_content_word.replace(word.clone()) {
+                    *bigram_counts
+                        .entry(format!("{previous} {word}"))
+                        .or_default() += 1;
+                }
+                if looks_like_vocabulary_term(token, position) {
+                    candidate_counts
+                        .entry(word)
+                        .or_insert_with(|| (token.to_string(), 0))
+                        .1 += 1;
+                }
+            }
+        }
+    }
+
+    let mut candidates = candidate_counts
+        .into_values()
+        .filter(|(_, count)| *count >= MIN_CANDIDATE_OCCURRENCES)
+        .collect::<Vec<_>>();
+    candidates.sort_by(|(left_term, left), (right_term, right)| {
+        right.cmp(left).then_with(|| left_term.cmp(right_term))
+    });
+
+    VocabularyReport {
+        range,
+        entries_analyzed,
+        total_words,
+        top_words: top_terms(word_counts),
+        // A bigram seen once is just a phrase, not a topic.
+        top_bigrams: top_terms(
+            bigram_counts
+                .into_iter()
+                .filter(|(_, count)| *count > 1)
+                .collect(),
+        ),
+        vocabulary_candidates: candidates
+            .into_iter()
+            .take(MAX_VOCABULARY_CANDIDATES)
+            .map(|(term, _)| term)
+            .collect(),
+    }
+}
+
+fn tokenize(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|character: char| {
+        !(character.is_alphanumeric() || character == '\'' || character == '-')
+    })
+    .map(|token| token.trim_matches(|character| character == '\'' || character == '-'))
+    .filter(|token| !token.is_empty() && !token.chars().all(|character| character.is_numeric()))
+}
+
+fn is_stop_word(word: &str) -> bool {
+    STOP_WORDS.contains(&word)
+}
+
+// The first word of a sentence is capitalised anyway, so it only counts when
+// it mixes in digits or inner capitals ("iOS", "GPT4").
+fn looks_like_vocabulary_term(token: &str, position_in_sentence: usize) -> bool {
+    let has_digit = token.chars().any(|character| character.is_ascii_digit());
+    let has_inner_capital = token.chars().skip(1).any(char::is_uppercase);
+    let starts_capitalised = token.chars().next().is_some_and(char::is_uppercase);
+    has_digit || has_inner_capital || (starts_capitalised && position_in_sentence > 0)
+}
+
+fn top_terms(counts: HashMap<String, u64>) -> Vec<TermFrequency> {
+    let mut terms = counts
+        .into_iter()
+        .map(|(term, count)| TermFrequency { term, count })
+        .collect::<Vec<_>>();
+    terms.sort_by(|left, right| {
+        right
+            .count
+            .cmp(&left.count)
+            .then_with(|| left.term.cmp(&right.term))
+    });
+    terms.truncate(TOP_TERMS_LIMIT);
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, SecondsFormat, Utc};
+
+    use super::{build_vocabulary_report, TermFrequency, VocabularyRange};
+    use crate::history_store::HistoryEntry;
+
+    fn entry(text: &str, age_days: i64) -> HistoryEntry {
+        let mut entry = HistoryEntry::new(text.to_string(), None, None, "openai".to_string());
+        entry.timestamp =
+            (Utc::now() - Duration::days(age_days)).to_rfc3339_opts(SecondsFormat::Millis, true);
+        entry
+    }
+
+    #[test]
+    fn counts_content_words_bigrams_and_candidates_within_range() {
+        let entries = vec![
+            entry(
+                "Deploy the Kubernetes cluster today. The cluster needs GPT4 access.",
+                1,
+            ),
+            entry(
+                "Um, so the Kubernetes cluster is down again, ping Priya about it.",
+                2,
+            ),
+            entry("Ask Priya to review the cluster dashboard.", 3),
+            entry("Quarterly budget budget budget budget budget planning.", 40),
+        ];
+
+        let report = build_vocabulary_report(&entries, VocabularyRange::Last30Days, Utc::now());
+
+        assert_eq!(report.entries_analyzed, 3);
+        assert_eq!(
+            report.top_words.first(),
+            Some(&TermFrequency {
+                term: "cluster".to_string(),
+                count: 4
+            })
+        );
+        assert!(report.top_words.iter().all(|word| word.term != "the"));
+        assert!(report.top_words.iter().all(|word| word.term != "budget"));
+        assert_eq!(
+            report.top_bigrams,
+            vec![TermFrequency {
+                term: "kubernetes cluster".to_string(),
+                count: 2
+            }]
+        );
+        assert_eq!(report.vocabulary_candidates, vec!["Kubernetes", "Priya"]);
+
+        let all_time = build_vocabulary_report(&entries, VocabularyRange::AllTime, Utc::now());
+        assert_eq!(all_time.entries_analyzed, 4);
+        assert_eq!(all_time.top_words[0].term, "budget");
+    }
+}
@@ -3,18 +3,21 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, Runtime, State};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 use tracing::{debug, error, info, warn};
 
+use crate::app_error::AppError;
+
 pub const DEFAULT_SHORTCUT: &str = "Alt+Space";
 pub const EVENT_HOTKEY_CONFIG_CHANGED: &str = "voice://hotkey-config-changed";
 pub const EVENT_RECORDING_STATE_CHANGED: &str = "voice://recording-state-changed";
 pub const EVENT_RECORDING_STARTED: &str = "voice://recording-started";
 pub const EVENT_RECORDING_STOPPED: &str = "voice://recording-stopped";
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RecordingMode {
     HoldToTalk,
@@ -27,11 +30,13 @@ impl Default for RecordingMode {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HotkeyConfig {
     pub shortcut: String,
     pub mode: RecordingMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
 }
 
 impl Default for HotkeyConfig {
@@ -39,11 +44,36 @@ impl Default for HotkeyConfig {
         Self {
             shortcut: DEFAULT_SHORTCUT.to_string(),
             mode: RecordingMode::HoldToTalk,
+            name: None,
+        }
+    }
+}
+
+impl HotkeyConfig {
+    // Unnamed bindings are labelled by their shortcut so the overlay always
+    // has something to show.
+    pub fn binding_name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.shortcut.clone())
+    }
+
+    fn active_binding(&self) -> ActiveHotkeyBinding {
+        ActiveHotkeyBinding {
+            name: self.binding_name(),
+            shortcut: self.shortcut.clone(),
+            mode: self.mode,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveHotkeyBinding {
+    pub name: String,
+    pub shortcut: String,
+    pub mode: RecordingMode,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RecordingTransition {
     Started,
@@ -58,7 +88,7 @@ pub enum StopProcessingDecision {
     Process,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum HotkeyTrigger {
     Pressed,
@@ -74,16 +104,28 @@ impl From<ShortcutState> for HotkeyTrigger {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RecordingStateChangedEvent {
     pub is_recording: bool,
+    pub binding_name: String,
     pub mode: RecordingMode,
     pub shortcut: String,
     pub transition: RecordingTransition,
     pub trigger: HotkeyTrigger,
 }
 
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyStateSnapshot {
+    pub config: HotkeyConfig,
+    pub registered_shortcut: Option<String>,
+    pub is_recording: bool,
+    pub desired_recording: bool,
+    pub pending_transitions: Vec<RecordingTransition>,
+    pub session_binding: Option<ActiveHotkeyBinding>,
+}
+
 #[derive(Debug)]
 struct HotkeyRuntimeState {
     config: HotkeyConfig,
@@ -91,6 +133,7 @@ struct HotkeyRuntimeState {
     is_recording: bool,
     desired_recording: bool,
     pending_transitions: VecDeque<RecordingTransition>,
+    session_binding: Option<ActiveHotkeyBinding>,
 }
 
 impl Default for HotkeyRuntimeState {
@@ -101,21 +144,45 @@ impl Default for HotkeyRuntimeState {
             is_recording: false,
             desired_recording: false,
             pending_transitions: VecDeque::new(),
+            session_binding: None,
         }
     }
 }
 
 impl HotkeyRuntimeState {
-    fn apply_shortcut_event(
-        &mut self,
-        shortcut_state: ShortcutState,
-    ) -> Option<RecordingTransition> {
+    fn apply_trigger(&mut self, trigger: HotkeyTrigger) -> Option<RecordingTransition> {
         let (next_recording_state, transition) =
-            resolve_transition(self.config.mode, self.desired_recording, shortcut_state)?;
+            resolve_transition(self.config.mode, self.desired_recording, trigger)?;
+        Some(self.push_transition(next_recording_state, transition))
+    }
+
+    // Remote start/stop requests only move towards the requested state, so a
+    // repeated "start" never toggles a recording off.
+    fn apply_recording_request(&mut self, recording: bool) -> Option<RecordingTransition> {
+        if self.desired_recording == recording {
+            return None;
+        }
+        let transition = if recording {
+            RecordingTransition::Started
+        } else {
+            RecordingTransition::Stopped
+        };
+        Some(self.push_transition(recording, transition))
+    }
 
+    fn push_transition(
+        &mut self,
+        next_recording_state: bool,
+        transition: RecordingTransition,
+    ) -> RecordingTransition {
         self.desired_recording = next_recording_state;
         self.pending_transitions.push_back(transition);
-        Some(transition)
+        // The binding stays attached after stop so the overlay can keep
+        // labelling the transcribing phase of the same session.
+        if transition == RecordingTransition::Started {
+            self.session_binding = Some(self.config.active_binding());
+        }
+        transition
     }
 
     fn acknowledge_transition(&mut self, transition: RecordingTransition, success: bool) {
@@ -153,6 +220,47 @@ impl HotkeyRuntimeState {
         self.pending_transitions.clear();
     }
 
+    // Drops everything except the configured binding and hands back the
+    // shortcut that still needs unregistering.
+    fn reset(&mut self) -> Option<String> {
+        let registered_shortcut = self.registered_shortcut.take();
+        self.clear_registered_shortcut();
+        self.session_binding = None;
+        registered_shortcut
+    }
+
+    fn snapshot(&self) -> HotkeyStateSnapshot {
+        HotkeyStateSnapshot {
+            config: self.config.clone(),
+            registered_shortcut: self.registered_shortcut.clone(),
+            is_recording: self.is_recording,
+            desired_recording: self.desired_recording,
+            pending_transitions: self.pending_transitions.iter().copied().collect(),
+            session_binding: self.session_binding.clone(),
+        }
+    }
+
+    // Labelled with the binding that started the session, so rebinding or a
+    // second binding firing mid-session doesn't relabel it.
+    fn recording_state_event(
+        &self,
+        transition: RecordingTransition,
+        trigger: HotkeyTrigger,
+    ) -> RecordingStateChangedEvent {
+        let binding = self
+            .session_binding
+            .clone()
+            .unwrap_or_else(|| self.config.active_binding());
+        RecordingStateChangedEvent {
+            is_recording: self.is_recording,
+            binding_name: binding.name,
+            mode: binding.mode,
+            shortcut: binding.shortcut,
+            transition,
+            trigger,
+        }
+    }
+
     fn stop_processing_decision(&self) -> StopProcessingDecision {
         let Some(stop_index) = self
             .pending_transitions
@@ -221,6 +329,44 @@ impl HotkeyService {
             .unwrap_or(false)
     }
 
+    pub fn session_binding(&self) -> Option<ActiveHotkeyBinding> {
+        self.state
+            .lock()
+            .ok()
+            .and_then(|state| state.session_binding.clone())
+    }
+
+    pub fn clear_session_binding(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.session_binding = None;
+        }
+    }
+
+    pub fn snapshot(&self) -> Result<HotkeyStateSnapshot, String> {
+        self.state
+            .lock()
+            .map(|state| state.snapshot())
+            .map_err(|_| lock_error())
+    }
+
+    pub fn reset_runtime_state<R: Runtime>(&self, app: &AppHandle<R>) -> Result<(), String> {
+        let registered_shortcut = {
+            let mut state = self.state.lock().map_err(|_| lock_error())?;
+            state.reset()
+        };
+
+        if let Some(shortcut) = registered_shortcut {
+            // The plugin may have already lost the registration; that is the
+            // kind of drift a reset is meant to recover from.
+            if let Err(error) = app.global_shortcut().unregister(shortcut.as_str()) {
+                warn!(%shortcut, %error, "failed to unregister hotkey during reset");
+            }
+        }
+
+        info!("hotkey runtime state reset");
+        Ok(())
+    }
+
     pub fn acknowledge_transition(&self, transition: RecordingTransition, success: bool) {
         if let Ok(mut state) = self.state.lock() {
             debug!(?transition, success, "acknowledging hotkey transition");
@@ -260,16 +406,11 @@ impl HotkeyService {
             state.desired_recording = false;
             state.pending_transitions.clear();
 
-            RecordingStateChangedEvent {
-                is_recording: false,
-                mode: state.config.mode,
-                shortcut: state.config.shortcut.clone(),
-                transition: RecordingTransition::Stopped,
-                trigger: HotkeyTrigger::Released,
-            }
+            state.recording_state_event(RecordingTransition::Stopped, HotkeyTrigger::Released)
         };
 
         info!(
+            binding = %payload.binding_name,
             mode = ?payload.mode,
             shortcut = %payload.shortcut,
             "forced hotkey recording stop"
@@ -303,7 +444,7 @@ impl HotkeyService {
                 let callback_service = service.clone();
                 app.global_shortcut()
                     .on_shortcut(shortcut, move |app, _shortcut, event| {
-                        callback_service.handle_shortcut_event(app, event.state);
+                        callback_service.handle_trigger(app, event.state.into());
                     })
                     .map_err(|error| error.to_string())
             },
@@ -311,58 +452,75 @@ impl HotkeyService {
         )
     }
 
-    fn handle_shortcut_event<R: Runtime>(&self, app: &AppHandle<R>, shortcut_state: ShortcutState) {
+    // Shared by the keyboard shortcut and the device backends in
+    // `input_triggers`, so a pedal follows the same recording mode.
+    pub fn handle_trigger<R: Runtime>(&self, app: &AppHandle<R>, trigger: HotkeyTrigger) {
         let event_payload = {
             let mut state = match self.state.lock() {
                 Ok(state) => state,
                 Err(_) => {
-                    error!("hotkey state lock poisoned while handling shortcut event");
+                    error!("hotkey state lock poisoned while handling trigger event");
                     return;
                 }
             };
 
-            let transition = match state.apply_shortcut_event(shortcut_state) {
+            let transition = match state.apply_trigger(trigger) {
                 Some(transition) => transition,
                 None => {
-                    debug!(
-                        ?shortcut_state,
-                        "ignoring shortcut event with no state transition"
-                    );
+                    debug!(?trigger, "ignoring trigger event with no state transition");
                     return;
                 }
             };
 
-            RecordingStateChangedEvent {
-                is_recording: state.is_recording,
-                mode: state.config.mode,
-                shortcut: state.config.shortcut.clone(),
-                transition,
-                trigger: shortcut_state.into(),
-            }
+            state.recording_state_event(transition, trigger)
         };
+        emit_transition(app, event_payload);
+    }
 
-        info!(
-            transition = ?event_payload.transition,
-            trigger = ?event_payload.trigger,
-            mode = ?event_payload.mode,
-            is_recording = event_payload.is_recording,
-            shortcut = %event_payload.shortcut,
-            "hotkey transition emitted"
-        );
-        if let Err(error) = app.emit(EVENT_RECORDING_STATE_CHANGED, &event_payload) {
-            warn!(%error, "failed to emit recording state change event");
-        }
+    // Starts or stops recording as though the shortcut had been pressed, so
+    // callers outside the GUI get the same session handling. Returns false
+    // when recording is already in the requested state.
+    pub fn request_recording<R: Runtime>(&self, app: &AppHandle<R>, recording: bool) -> bool {
+        let event_payload = {
+            let Ok(mut state) = self.state.lock() else {
+                error!("hotkey state lock poisoned while handling recording request");
+                return false;
+            };
+            let Some(transition) = state.apply_recording_request(recording) else {
+                debug!(recording, "recording request needs no transition");
+                return false;
+            };
 
-        match event_payload.transition {
-            RecordingTransition::Started => {
-                if let Err(error) = app.emit(EVENT_RECORDING_STARTED, &event_payload) {
-                    warn!(%error, "failed to emit recording started event");
-                }
+            state.recording_state_event(transition, HotkeyTrigger::Pressed)
+        };
+        emit_transition(app, event_payload);
+        true
+    }
+}
+
+fn emit_transition<R: Runtime>(app: &AppHandle<R>, event_payload: RecordingStateChangedEvent) {
+    info!(
+        transition = ?event_payload.transition,
+        trigger = ?event_payload.trigger,
+        binding = %event_payload.binding_name,
+        mode = ?event_payload.mode,
+        is_recording = event_payload.is_recording,
+        shortcut = %event_payload.shortcut,
+        "hotkey transition emitted"
+    );
+    if let Err(error) = app.emit(EVENT_RECORDING_STATE_CHANGED, &event_payload) {
+        warn!(%error, "failed to emit recording state change event");
+    }
+
+    match event_payload.transition {
+        RecordingTransition::Started => {
+            if let Err(error) = app.emit(EVENT_RECORDING_STARTED, &event_payload) {
+                warn!(%error, "failed to emit recording started event");
             }
-            RecordingTransition::Stopped => {
-                if let Err(error) = app.emit(EVENT_RECORDING_STOPPED, &event_payload) {
-                    warn!(%error, "failed to emit recording stopped event");
-                }
+        }
+        RecordingTransition::Stopped => {
+            if let Err(error) = app.emit(EVENT_RECORDING_STOPPED, &event_payload) {
+                warn!(%error, "failed to emit recording stopped event");
             }
         }
     }
@@ -477,13 +635,22 @@ pub fn get_hotkey_recording_state(service: State<'_, HotkeyService>) -> bool {
     service.is_recording()
 }
 
+#[tauri::command]
+pub fn dump_hotkey_state(
+    service: State<'_, HotkeyService>,
+) -> Result<HotkeyStateSnapshot, AppError> {
+    let snapshot = service.snapshot()?;
+    debug!(?snapshot, "hotkey state dump requested");
+    Ok(snapshot)
+}
+
 #[tauri::command]
 pub fn set_hotkey_config(
     app: AppHandle,
     service: State<'_, HotkeyService>,
     config: HotkeyConfig,
-) -> Result<HotkeyConfig, String> {
-    service.apply_config(&app, config)
+) -> Result<HotkeyConfig, AppError> {
+    service.apply_config(&app, config).map_err(AppError::from)
 }
 
 fn normalize_config(mut config: HotkeyConfig) -> HotkeyConfig {
@@ -493,6 +660,10 @@ fn normalize_config(mut config: HotkeyConfig) -> HotkeyConfig {
     } else {
         trimmed_shortcut.to_string()
     };
+    config.name = config
+        .name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
 
     config
 }
@@ -514,18 +685,18 @@ fn shortcuts_match(left: &str, right: &str) -> bool {
 fn resolve_transition(
     mode: RecordingMode,
     is_recording: bool,
-    shortcut_state: ShortcutState,
+    trigger: HotkeyTrigger,
 ) -> Option<(bool, RecordingTransition)> {
     match mode {
-        RecordingMode::HoldToTalk => match shortcut_state {
-            ShortcutState::Pressed if !is_recording => Some((true, RecordingTransition::Started)),
-            ShortcutState::Released if is_recording => Some((false, RecordingTransition::Stopped)),
+        RecordingMode::HoldToTalk => match trigger {
+            HotkeyTrigger::Pressed if !is_recording => Some((true, RecordingTransition::Started)),
+            HotkeyTrigger::Released if is_recording => Some((false, RecordingTransition::Stopped)),
             _ => None,
         },
-        RecordingMode::Toggle => match shortcut_state {
-            ShortcutState::Pressed if is_recording => Some((false, RecordingTransition::Stopped)),
-            ShortcutState::Pressed => Some((true, RecordingTransition::Started)),
-            ShortcutState::Released => None,
+        RecordingMode::Toggle => match trigger {
+            HotkeyTrigger::Pressed if is_recording => Some((false, RecordingTransition::Stopped)),
+            HotkeyTrigger::Pressed => Some((true, RecordingTransition::Started)),
+            HotkeyTrigger::Released => None,
         },
     }
 }
@@ -573,6 +744,8 @@ mod tests {
     use async_trait::async_trait;
 
     use crate::{
+        app_error::{AppError, ErrorCode},
+        audio_capture_service::RecordedWav,
         status_notifier::AppStatus,
         voice_pipeline::{
             PipelineError, PipelineErrorStage, PipelineTranscript, VoicePipeline,
@@ -592,7 +765,7 @@ mod tests {
     impl StartFailurePipelineDelegate {
         fn new_with_pending_start() -> Self {
             let mut hotkey_state = HotkeyRuntimeState::default();
-            hotkey_state.apply_shortcut_event(ShortcutState::Pressed);
+            hotkey_state.apply_trigger(HotkeyTrigger::Pressed);
 
             Self {
                 hotkey_state: Mutex::new(hotkey_state),
@@ -625,7 +798,7 @@ mod tests {
                 .push(status);
         }
 
-        fn emit_transcript(&self, _transcript: &str) {}
+        fn emit_transcript(&self, _transcript: &PipelineTranscript) {}
 
         fn emit_error(&self, error: &PipelineError) {
             self.errors
@@ -642,19 +815,19 @@ mod tests {
             hotkey_state.acknowledge_transition(RecordingTransition::Started, success);
         }
 
-        fn start_recording(&self) -> Result<(), String> {
-            Err("microphone unavailable".to_string())
+        fn start_recording(&self) -> Result<(), AppError> {
+            Err(AppError::new(ErrorCode::NotFound, "microphone unavailable"))
         }
 
-        fn stop_recording(&self) -> Result<Vec<u8>, String> {
+        fn stop_recording(&self) -> Result<RecordedWav, AppError> {
             panic!("stop should not be called for start failure scenario");
         }
 
-        async fn transcribe(&self, _wav_bytes: Vec<u8>) -> Result<PipelineTranscript, String> {
+        async fn transcribe(&self, _wav: RecordedWav) -> Result<PipelineTranscript, AppError> {
             panic!("transcribe should not be called for start failure scenario");
         }
 
-        fn insert_text(&self, _transcript: &str) -> Result<(), String> {
+        async fn insert_text(&self, _transcript: &str) -> Result<(), AppError> {
             panic!("insert_text should not be called for start failure scenario");
         }
     }
@@ -671,12 +844,82 @@ mod tests {
         let config = HotkeyConfig {
             shortcut: "   ".to_string(),
             mode: RecordingMode::Toggle,
+            name: Some("  ".to_string()),
         };
 
         let normalized = normalize_config(config);
 
         assert_eq!(normalized.shortcut, DEFAULT_SHORTCUT);
         assert_eq!(normalized.mode, RecordingMode::Toggle);
+        assert_eq!(normalized.name, None);
+    }
+
+    #[test]
+    fn session_binding_tracks_the_config_that_started_recording() {
+        let mut state = HotkeyRuntimeState {
+            config: HotkeyConfig {
+                shortcut: "Ctrl+Space".to_string(),
+                mode: RecordingMode::Toggle,
+                name: Some("Meetings".to_string()),
+            },
+            ..HotkeyRuntimeState::default()
+        };
+
+        state.apply_trigger(HotkeyTrigger::Pressed);
+        state.acknowledge_transition(RecordingTransition::Started, true);
+        state.config = HotkeyConfig::default();
+        state.apply_trigger(HotkeyTrigger::Pressed);
+
+        assert_eq!(
+            state.session_binding,
+            Some(ActiveHotkeyBinding {
+                name: "Meetings".to_string(),
+                shortcut: "Ctrl+Space".to_string(),
+                mode: RecordingMode::Toggle,
+            })
+        );
+        let event =
+            state.recording_state_event(RecordingTransition::Stopped, HotkeyTrigger::Pressed);
+        assert_eq!(event.binding_name, "Meetings");
+        assert_eq!(event.shortcut, "Ctrl+Space");
+        assert_eq!(HotkeyConfig::default().binding_name(), DEFAULT_SHORTCUT);
+    }
+
+    #[test]
+    fn reset_clears_stuck_recording_state_but_keeps_config() {
+        let config = HotkeyConfig {
+            shortcut: "Ctrl+Space".to_string(),
+            mode: RecordingMode::Toggle,
+            name: None,
+        };
+        let mut state = HotkeyRuntimeState {
+            config: config.clone(),
+            registered_shortcut: Some("Ctrl+Space".to_string()),
+            ..HotkeyRuntimeState::default()
+        };
+        state.apply_trigger(HotkeyTrigger::Pressed);
+        state.acknowledge_transition(RecordingTransition::Started, true);
+        state.apply_trigger(HotkeyTrigger::Pressed);
+
+        let stuck = state.snapshot();
+        assert!(stuck.is_recording);
+        assert_eq!(
+            stuck.pending_transitions,
+            vec![RecordingTransition::Stopped]
+        );
+
+        assert_eq!(state.reset().as_deref(), Some("Ctrl+Space"));
+        assert_eq!(
+            state.snapshot(),
+            HotkeyStateSnapshot {
+                config,
+                registered_shortcut: None,
+                is_recording: false,
+                desired_recording: false,
+                pending_transitions: Vec::new(),
+                session_binding: None,
+            }
+        );
     }
 
     #[test]
@@ -688,17 +931,17 @@ mod tests {
     #[test]
     fn hold_to_talk_transitions_on_press_and_release_only() {
         assert_eq!(
-            resolve_transition(RecordingMode::HoldToTalk, false, ShortcutState::Pressed),
+            resolve_transition(RecordingMode::HoldToTalk, false, HotkeyTrigger::Pressed),
             Some((true, RecordingTransition::Started))
         );
 
         assert_eq!(
-            resolve_transition(RecordingMode::HoldToTalk, true, ShortcutState::Released),
+            resolve_transition(RecordingMode::HoldToTalk, true, HotkeyTrigger::Released),
             Some((false, RecordingTransition::Stopped))
         );
 
         assert_eq!(
-            resolve_transition(RecordingMode::HoldToTalk, false, ShortcutState::Released),
+            resolve_transition(RecordingMode::HoldToTalk, false, HotkeyTrigger::Released),
             None
         );
     }
@@ -706,21 +949,43 @@ mod tests {
     #[test]
     fn toggle_mode_transitions_on_pressed_only() {
         assert_eq!(
-            resolve_transition(RecordingMode::Toggle, false, ShortcutState::Pressed),
+            resolve_transition(RecordingMode::Toggle, false, HotkeyTrigger::Pressed),
             Some((true, RecordingTransition::Started))
         );
 
         assert_eq!(
-            resolve_transition(RecordingMode::Toggle, true, ShortcutState::Pressed),
+            resolve_transition(RecordingMode::Toggle, true, HotkeyTrigger::Pressed),
             Some((false, RecordingTransition::Stopped))
         );
 
         assert_eq!(
-            resolve_transition(RecordingMode::Toggle, true, ShortcutState::Released),
+            resolve_transition(RecordingMode::Toggle, true, HotkeyTrigger::Released),
             None
         );
     }
 
+    #[test]
+    fn recording_requests_only_move_towards_the_requested_state() {
+        let mut state = HotkeyRuntimeState::default();
+        assert_eq!(state.apply_recording_request(false), None);
+        assert_eq!(
+            state.apply_recording_request(true),
+            Some(RecordingTransition::Started)
+        );
+        assert_eq!(state.apply_recording_request(true), None);
+        assert!(state.session_binding.is_some());
+
+        state.acknowledge_transition(RecordingTransition::Started, true);
+        assert_eq!(
+            state.apply_recording_request(false),
+            Some(RecordingTransition::Stopped)
+        );
+        assert_eq!(
+            state.stop_processing_decision(),
+            StopProcessingDecision::Process
+        );
+    }
+
     #[test]
     fn shortcut_comparison_ignores_case_and_alias_formatting() {
         assert!(shortcuts_match("alt+space", "Alt+Space"));
@@ -731,7 +996,7 @@ mod tests {
         let mut state = HotkeyRuntimeState::default();
 
         assert_eq!(
-            state.apply_shortcut_event(ShortcutState::Pressed),
+            state.apply_trigger(HotkeyTrigger::Pressed),
             Some(RecordingTransition::Started)
         );
         assert!(!state.is_recording);
@@ -748,7 +1013,7 @@ mod tests {
     fn acknowledge_started_success_marks_recording_as_confirmed() {
         let mut state = HotkeyRuntimeState::default();
 
-        state.apply_shortcut_event(ShortcutState::Pressed);
+        state.apply_trigger(HotkeyTrigger::Pressed);
         state.acknowledge_transition(RecordingTransition::Started, true);
 
         assert!(state.is_recording);
@@ -760,8 +1025,8 @@ mod tests {
     fn desired_state_recomputes_from_pending_transitions() {
         let mut state = HotkeyRuntimeState::default();
 
-        state.apply_shortcut_event(ShortcutState::Pressed);
-        state.apply_shortcut_event(ShortcutState::Released);
+        state.apply_trigger(HotkeyTrigger::Pressed);
+        state.apply_trigger(HotkeyTrigger::Released);
         state.acknowledge_transition(RecordingTransition::Started, false);
 
         assert!(!state.is_recording);
@@ -776,8 +1041,8 @@ mod tests {
     fn quick_release_defers_stop_until_start_ack_then_processes() {
         let mut state = HotkeyRuntimeState::default();
 
-        state.apply_shortcut_event(ShortcutState::Pressed);
-        state.apply_shortcut_event(ShortcutState::Released);
+        state.apply_trigger(HotkeyTrigger::Pressed);
+        state.apply_trigger(HotkeyTrigger::Released);
 
         assert_eq!(
             state.stop_processing_decision(),
@@ -801,8 +1066,8 @@ mod tests {
     fn quick_release_after_start_failure_acknowledges_stop_without_processing() {
         let mut state = HotkeyRuntimeState::default();
 
-        state.apply_shortcut_event(ShortcutState::Pressed);
-        state.apply_shortcut_event(ShortcutState::Released);
+        state.apply_trigger(HotkeyTrigger::Pressed);
+        state.apply_trigger(HotkeyTrigger::Released);
         state.acknowledge_transition(RecordingTransition::Started, false);
 
         assert_eq!(
@@ -834,10 +1099,10 @@ mod tests {
         assert_eq!(delegate.statuses(), vec![AppStatus::Error, AppStatus::Idle]);
         assert_eq!(
             delegate.errors(),
-            vec![PipelineError {
-                stage: PipelineErrorStage::RecordingStart,
-                message: "microphone unavailable".to_string(),
-            }]
+            vec![PipelineError::new(
+                PipelineErrorStage::RecordingStart,
+                AppError::new(ErrorCode::NotFound, "microphone unavailable")
+            )]
         );
     }
 
@@ -849,6 +1114,7 @@ mod tests {
             is_recording: true,
             desired_recording: true,
             pending_transitions: VecDeque::from([RecordingTransition::Started]),
+            session_binding: None,
         }));
         let mut unregister_attempts = Vec::new();
         let mut register_attempts = Vec::new();
@@ -859,6 +1125,7 @@ mod tests {
             HotkeyConfig {
                 shortcut: "Ctrl+Space".to_string(),
                 mode: RecordingMode::Toggle,
+                name: None,
             },
             |shortcut| {
                 unregister_attempts.push(shortcut.to_string());
@@ -898,6 +1165,7 @@ mod tests {
             is_recording: true,
             desired_recording: true,
             pending_transitions: VecDeque::from([RecordingTransition::Started]),
+            session_binding: None,
         }));
         let mut unregister_attempts = Vec::new();
         let mut register_attempts = Vec::new();
@@ -908,6 +1176,7 @@ mod tests {
             HotkeyConfig {
                 shortcut: "Ctrl+Space".to_string(),
                 mode: RecordingMode::Toggle,
+                name: None,
             },
             |shortcut| {
                 unregister_attempts.push(shortcut.to_string());
@@ -955,6 +1224,7 @@ mod tests {
             is_recording: true,
             desired_recording: true,
             pending_transitions: VecDeque::from([RecordingTransition::Started]),
+            session_binding: None,
         };
 
         state.clear_registered_shortcut();
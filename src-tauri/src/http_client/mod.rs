@@ -0,0 +1,189 @@
+use reqwest::Client;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, OnceLock,
+    },
+    time::Duration,
+};
+use tracing::{debug, warn};
+
+const DEFAULT_POOL_MAX_IDLE_PER_HOST: usize = 4;
+const DEFAULT_POOL_IDLE_TIMEOUT_SECS: u64 = 300;
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_TCP_KEEPALIVE_SECS: u64 = 60;
+const DEFAULT_HTTP2_KEEPALIVE_INTERVAL_SECS: u64 = 30;
+const DEFAULT_HTTP2_KEEPALIVE_TIMEOUT_SECS: u64 = 10;
+
+static ENV_CONFIG: OnceLock<HttpClientConfig> = OnceLock::new();
+static SHARED_CLIENT: Mutex<Option<(HttpClientConfig, Client)>> = Mutex::new(None);
+// Zero until settings are applied; the environment value is used until then.
+static CONFIGURED_CONNECT_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpClientConfig {
+    pub pool_max_idle_per_host: usize,
+    pub pool_idle_timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+    pub tcp_keepalive_secs: u64,
+    pub http2_keepalive_interval_secs: u64,
+    pub http2_keepalive_timeout_secs: u64,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: DEFAULT_POOL_MAX_IDLE_PER_HOST,
+            pool_idle_timeout_secs: DEFAULT_POOL_IDLE_TIMEOUT_SECS,
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+            tcp_keepalive_secs: DEFAULT_TCP_KEEPALIVE_SECS,
+            http2_keepalive_interval_secs: DEFAULT_HTTP2_KEEPALIVE_INTERVAL_SECS,
+            http2_keepalive_timeout_secs: DEFAULT_HTTP2_KEEPALIVE_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl HttpClientConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Some(pool_size) = read_u64_env("VOICE_HTTP_POOL_MAX_IDLE_PER_HOST") {
+            config.pool_max_idle_per_host = pool_size as usize;
+        }
+
+        if let Some(idle_timeout_secs) = read_u64_env("VOICE_HTTP_POOL_IDLE_TIMEOUT_SECS") {
+            config.pool_idle_timeout_secs = idle_timeout_secs;
+        }
+
+        if let Some(connect_timeout_secs) = read_u64_env("VOICE_HTTP_CONNECT_TIMEOUT_SECS") {
+            config.connect_timeout_secs = connect_timeout_secs.max(1);
+        }
+
+        if let Some(keepalive_secs) = read_u64_env("VOICE_HTTP_TCP_KEEPALIVE_SECS") {
+            config.tcp_keepalive_secs = keepalive_secs;
+        }
+
+        if let Some(interval_secs) = read_u64_env("VOICE_HTTP2_KEEPALIVE_INTERVAL_SECS") {
+            config.http2_keepalive_interval_secs = interval_secs;
+        }
+
+        if let Some(timeout_secs) = read_u64_env("VOICE_HTTP2_KEEPALIVE_TIMEOUT_SECS") {
+            config.http2_keepalive_timeout_secs = timeout_secs.max(1);
+        }
+
+        debug!(
+            pool_max_idle_per_host = config.pool_max_idle_per_host,
+            pool_idle_timeout_secs = config.pool_idle_timeout_secs,
+            connect_timeout_secs = config.connect_timeout_secs,
+            tcp_keepalive_secs = config.tcp_keepalive_secs,
+            http2_keepalive_interval_secs = config.http2_keepalive_interval_secs,
+            "loaded HTTP client config"
+        );
+        config
+    }
+}
+
+// Applied from settings. The connect timeout is fixed per client, so the
+// next `shared_client` call rebuilds the pool when it changes.
+pub fn set_connect_timeout_secs(secs: u64) {
+    CONFIGURED_CONNECT_TIMEOUT_SECS.store(secs, Ordering::Relaxed);
+}
+
+// All REST providers share one connection pool so a warm TLS session (and its
+// rustls resumption ticket) survives across dictations and provider switches.
+// Callers fetch it per request rather than holding on to a clone.
+pub fn shared_client() -> Client {
+    let mut config = ENV_CONFIG.get_or_init(HttpClientConfig::from_env).clone();
+    let configured_connect_timeout_secs = CONFIGURED_CONNECT_TIMEOUT_SECS.load(Ordering::Relaxed);
+    if configured_connect_timeout_secs > 0 {
+        config.connect_timeout_secs = configured_connect_timeout_secs;
+    }
+
+    let Ok(mut shared) = SHARED_CLIENT.lock() else {
+        return build_client_or_default(&config);
+    };
+    match shared.as_ref() {
+        Some((built_with, client)) if *built_with == config => client.clone(),
+        _ => {
+            if shared.is_some() {
+                debug!(
+                    connect_timeout_secs = config.connect_timeout_secs,
+                    "rebuilding shared HTTP client after config change"
+                );
+            }
+            let client = build_client_or_default(&config);
+            *shared = Some((config, client.clone()));
+            client
+        }
+    }
+}
+
+fn build_client_or_default(config: &HttpClientConfig) -> Client {
+    build_client(config).unwrap_or_else(|error| {
+        warn!(%error, "falling back to default HTTP client");
+        Client::new()
+    })
+}
+
+pub fn build_client(config: &HttpClientConfig) -> Result<Client, String> {
+    let mut builder = Client::builder()
+        .pool_max_idle_per_host(config.pool_max_idle_per_host)
+        .connect_timeout(Duration::from_secs(config.connect_timeout_secs.max(1)))
+        .http2_adaptive_window(true)
+        .proxy(crate::proxy::reqwest_proxy());
+
+    builder = if config.pool_idle_timeout_secs == 0 {
+        builder.pool_idle_timeout(None)
+    } else {
+        builder.pool_idle_timeout(Duration::from_secs(config.pool_idle_timeout_secs))
+    };
+
+    if config.tcp_keepalive_secs > 0 {
+        builder = builder.tcp_keepalive(Duration::from_secs(config.tcp_keepalive_secs));
+    }
+
+    if config.http2_keepalive_interval_secs > 0 {
+        builder = builder
+            .http2_keep_alive_interval(Duration::from_secs(config.http2_keepalive_interval_secs))
+            .http2_keep_alive_timeout(Duration::from_secs(
+                config.http2_keepalive_timeout_secs.max(1),
+            ))
+            .http2_keep_alive_while_idle(true);
+    }
+
+    builder
+        .build()
+        .map_err(|error| format!("Failed to build HTTP client: {error}"))
+}
+
+fn read_u64_env(name: &str) -> Option<u64> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .and_then(|value| value.parse::<u64>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_client, HttpClientConfig};
+
+    #[test]
+    fn builds_client_with_default_config() {
+        assert!(build_client(&HttpClientConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn builds_client_with_keepalive_and_pool_idle_timeout_disabled() {
+        let config = HttpClientConfig {
+            pool_max_idle_per_host: 0,
+            pool_idle_timeout_secs: 0,
+            connect_timeout_secs: 0,
+            tcp_keepalive_secs: 0,
+            http2_keepalive_interval_secs: 0,
+            http2_keepalive_timeout_secs: 0,
+        };
+
+        assert!(build_client(&config).is_ok());
+    }
+}
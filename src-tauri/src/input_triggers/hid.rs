@@ -0,0 +1,120 @@
+use super::{MessageHandler, TriggerConnection};
+
+// Device ids are `vendor:product` in four-digit lowercase hex, e.g. `05f3:00ff`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn format_device_id(vendor_id: u16, product_id: u16) -> String {
+    format!("{vendor_id:04x}:{product_id:04x}")
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_device_id(device_id: &str) -> Result<(u16, u16), String> {
+    let invalid = || format!("Invalid HID device id `{device_id}`");
+    let (vendor_id, product_id) = device_id.split_once(':').ok_or_else(invalid)?;
+    let vendor_id = u16::from_str_radix(vendor_id, 16).map_err(|_| invalid())?;
+    let product_id = u16::from_str_radix(product_id, 16).map_err(|_| invalid())?;
+    Ok((vendor_id, product_id))
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::sync::atomic::Ordering;
+
+    use hidapi::{HidApi, HidError};
+    use tracing::warn;
+
+    use super::{format_device_id, parse_device_id, MessageHandler, TriggerConnection};
+
+    const REPORT_BUFFER_LEN: usize = 64;
+    // Short enough that dropping the connection closes the device promptly.
+    const READ_TIMEOUT_MS: i32 = 250;
+
+    pub fn list_devices() -> Result<Vec<(String, String)>, String> {
+        let api = HidApi::new().map_err(hid_error)?;
+        let mut devices: Vec<(String, String)> = Vec::new();
+        // A device shows up once per interface; one entry is enough.
+        for info in api.device_list() {
+            let device_id = format_device_id(info.vendor_id(), info.product_id());
+            if devices.iter().any(|(listed, _)| *listed == device_id) {
+                continue;
+            }
+            let name = info
+                .product_string()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .unwrap_or_else(|| device_id.clone());
+            devices.push((device_id, name));
+        }
+        Ok(devices)
+    }
+
+    pub fn connect(
+        device_id: &str,
+        mut on_report: MessageHandler,
+    ) -> Result<TriggerConnection, String> {
+        let (vendor_id, product_id) = parse_device_id(device_id)?;
+        TriggerConnection::spawn("hid-trigger", move |stop, opened| {
+            // The API handle stays alive for as long as the device is open.
+            let opened_device = HidApi::new()
+                .and_then(|api| api.open(vendor_id, product_id).map(|device| (api, device)));
+            let (_api, device) = match opened_device {
+                Ok(opened_device) => opened_device,
+                Err(error) => {
+                    let _ = opened.send(Err(hid_error(error)));
+                    return;
+                }
+            };
+            let _ = opened.send(Ok(()));
+
+            let mut report = [0u8; REPORT_BUFFER_LEN];
+            while !stop.load(Ordering::Relaxed) {
+                match device.read_timeout(&mut report, READ_TIMEOUT_MS) {
+                    Ok(0) => {}
+                    Ok(len) => on_report(&report[..len]),
+                    // Usually the device was unplugged; the next sync reopens it.
+                    Err(error) => {
+                        warn!(%error, "HID trigger read failed");
+                        return;
+                    }
+                }
+            }
+        })
+    }
+
+    fn hid_error(error: HidError) -> String {
+        format!("HID device error: {error}")
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use super::{MessageHandler, TriggerConnection};
+
+    const UNAVAILABLE: &str = "HID input triggers are not available on this platform";
+
+    pub fn list_devices() -> Result<Vec<(String, String)>, String> {
+        Err(UNAVAILABLE.to_string())
+    }
+
+    pub fn connect(
+        _device_id: &str,
+        _on_report: MessageHandler,
+    ) -> Result<TriggerConnection, String> {
+        Err(UNAVAILABLE.to_string())
+    }
+}
+
+pub(super) use platform::{connect, list_devices};
+
+#[cfg(test)]
+mod tests {
+    use super::{format_device_id, parse_device_id};
+
+    #[test]
+    fn device_ids_round_trip() {
+        assert_eq!(format_device_id(0x05f3, 0x00ff), "05f3:00ff");
+        assert_eq!(parse_device_id("05f3:00ff"), Ok((0x05f3, 0x00ff)));
+        assert!(parse_device_id("05f3").is_err());
+        assert!(parse_device_id("pedal:00ff").is_err());
+    }
+}
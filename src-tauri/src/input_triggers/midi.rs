@@ -0,0 +1,109 @@
+use super::{MessageHandler, TriggerConnection};
+
+// MIDI ports are identified by name, which is what controllers show in Audio
+// MIDI Setup and stays the same across reconnects.
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::{sync::atomic::Ordering, time::Duration};
+
+    use midir::MidiInput;
+    use tracing::info;
+
+    use super::{MessageHandler, TriggerConnection};
+
+    const CLIENT_NAME: &str = "Voice";
+    const CONNECTION_NAME: &str = "voice-trigger";
+    // CoreMIDI keeps a connection open after its device is unplugged, so the
+    // port list is checked this often to notice.
+    const PORT_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+    pub fn list_devices() -> Result<Vec<(String, String)>, String> {
+        let input = MidiInput::new(CLIENT_NAME).map_err(midi_error)?;
+        Ok(port_names(&input)
+            .into_iter()
+            .map(|name| (name.clone(), name))
+            .collect())
+    }
+
+    pub fn connect(
+        device_id: &str,
+        mut on_message: MessageHandler,
+    ) -> Result<TriggerConnection, String> {
+        let device_id = device_id.to_string();
+        TriggerConnection::spawn("midi-trigger", move |stop, opened| {
+            let (input, watcher) = match MidiInput::new(CLIENT_NAME)
+                .and_then(|input| MidiInput::new(CLIENT_NAME).map(|watcher| (input, watcher)))
+            {
+                Ok(clients) => clients,
+                Err(error) => {
+                    let _ = opened.send(Err(midi_error(error)));
+                    return;
+                }
+            };
+            let port = input
+                .ports()
+                .into_iter()
+                .find(|port| input.port_name(port).is_ok_and(|name| name == device_id));
+            let Some(port) = port else {
+                let _ = opened.send(Err(format!("MIDI device `{device_id}` is not connected")));
+                return;
+            };
+            let connection = match input.connect(
+                &port,
+                CONNECTION_NAME,
+                move |_timestamp, message, _| on_message(message),
+                (),
+            ) {
+                Ok(connection) => connection,
+                Err(error) => {
+                    let _ = opened.send(Err(format!(
+                        "Failed to connect to MIDI device `{device_id}`: {error}"
+                    )));
+                    return;
+                }
+            };
+            let _ = opened.send(Ok(()));
+
+            while !stop.load(Ordering::Relaxed) {
+                std::thread::sleep(PORT_CHECK_INTERVAL);
+                if !port_names(&watcher).contains(&device_id) {
+                    info!(device = %device_id, "MIDI trigger device went away");
+                    break;
+                }
+            }
+            connection.close();
+        })
+    }
+
+    fn port_names(input: &MidiInput) -> Vec<String> {
+        input
+            .ports()
+            .iter()
+            .filter_map(|port| input.port_name(port).ok())
+            .collect()
+    }
+
+    fn midi_error(error: midir::InitError) -> String {
+        format!("MIDI error: {error}")
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use super::{MessageHandler, TriggerConnection};
+
+    const UNAVAILABLE: &str = "MIDI input triggers are not available on this platform";
+
+    pub fn list_devices() -> Result<Vec<(String, String)>, String> {
+        Err(UNAVAILABLE.to_string())
+    }
+
+    pub fn connect(
+        _device_id: &str,
+        _on_message: MessageHandler,
+    ) -> Result<TriggerConnection, String> {
+        Err(UNAVAILABLE.to_string())
+    }
+}
+
+pub(super) use platform::{connect, list_devices};
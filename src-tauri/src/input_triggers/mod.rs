@@ -0,0 +1,320 @@
+mod hid;
+mod midi;
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use tracing::{debug, info, warn};
+
+use crate::{
+    hotkey_service::HotkeyTrigger,
+    settings_store::{InputTriggerBinding, INPUT_TRIGGER_KIND_HID, INPUT_TRIGGER_KIND_MIDI},
+};
+
+// How often bound devices that are missing are looked for again, so a pedal
+// plugged in after launch starts working without touching settings.
+pub const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+// A device that has not opened by then is treated as unavailable.
+const OPEN_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub type TriggerHandler = Arc<dyn Fn(HotkeyTrigger) + Send + Sync>;
+type MessageHandler = Box<dyn FnMut(&[u8]) + Send>;
+type BindingKey = (String, String, Option<u8>);
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InputTriggerDevice {
+    pub kind: String,
+    pub device_id: String,
+    pub name: String,
+    pub connected: bool,
+    pub bound: bool,
+}
+
+// A backend listening to one device on its own thread. Dropping it stops the
+// thread, which closes the device.
+struct TriggerConnection {
+    stop: Arc<AtomicBool>,
+    alive: Arc<AtomicBool>,
+}
+
+impl TriggerConnection {
+    // `listen` opens the device, reports whether that worked through the
+    // sender, then reads until `stop` is set or the device goes away.
+    fn spawn<F>(thread_name: &str, listen: F) -> Result<Self, String>
+    where
+        F: FnOnce(&AtomicBool, &mpsc::Sender<Result<(), String>>) + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let alive = Arc::new(AtomicBool::new(true));
+        let (opened_tx, opened_rx) = mpsc::channel();
+        let thread_stop = Arc::clone(&stop);
+        let thread_alive = Arc::clone(&alive);
+        std::thread::Builder::new()
+            .name(thread_name.to_string())
+            .spawn(move || {
+                listen(&thread_stop, &opened_tx);
+                thread_alive.store(false, Ordering::Relaxed);
+            })
+            .map_err(|error| format!("Failed to start input trigger thread: {error}"))?;
+
+        match opened_rx.recv_timeout(OPEN_TIMEOUT) {
+            Ok(Ok(())) => Ok(Self { stop, alive }),
+            Ok(Err(error)) => Err(error),
+            Err(_) => {
+                stop.store(true, Ordering::Relaxed);
+                Err("Input trigger device did not open".to_string())
+            }
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for TriggerConnection {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+// Devices other than the keyboard that drive recording. Each enabled binding
+// gets a connection that feeds presses into the hotkey state machine, so a
+// pedal behaves like the shortcut in both hold-to-talk and toggle mode.
+#[derive(Default)]
+pub struct InputTriggerService {
+    connections: Mutex<BTreeMap<BindingKey, TriggerConnection>>,
+}
+
+impl InputTriggerService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Keeps connections that are still bound and alive, and tries to open the
+    // rest; a device that is not plugged in is simply retried next time.
+    pub fn sync(&self, bindings: &[InputTriggerBinding], handler: TriggerHandler) {
+        let enabled: Vec<&InputTriggerBinding> =
+            bindings.iter().filter(|binding| binding.enabled).collect();
+        let Ok(mut connections) = self.connections.lock() else {
+            warn!("input trigger lock poisoned");
+            return;
+        };
+
+        connections.retain(|key, connection| {
+            let keep =
+                connection.is_alive() && enabled.iter().any(|binding| binding_key(binding) == *key);
+            if !keep {
+                info!(kind = %key.0, device = %key.1, "input trigger disconnected");
+            }
+            keep
+        });
+        for binding in enabled {
+            let key = binding_key(binding);
+            if connections.contains_key(&key) {
+                continue;
+            }
+            match connect(binding, Arc::clone(&handler)) {
+                Ok(connection) => {
+                    info!(
+                        kind = %binding.kind,
+                        device = %binding.device_id,
+                        control = ?binding.control,
+                        "input trigger connected"
+                    );
+                    connections.insert(key, connection);
+                }
+                Err(error) => {
+                    debug!(kind = %binding.kind, device = %binding.device_id, %error, "input trigger unavailable");
+                }
+            }
+        }
+    }
+}
+
+// Every device the backends can see, plus bound devices that are unplugged so
+// their bindings can still be removed.
+pub fn list_devices(bindings: &[InputTriggerBinding]) -> Vec<InputTriggerDevice> {
+    let mut devices = Vec::new();
+    for (kind, listed) in [
+        (INPUT_TRIGGER_KIND_HID, hid::list_devices()),
+        (INPUT_TRIGGER_KIND_MIDI, midi::list_devices()),
+    ] {
+        match listed {
+            Ok(listed) => devices.extend(listed.into_iter().map(|(device_id, name)| {
+                InputTriggerDevice {
+                    kind: kind.to_string(),
+                    bound: bindings
+                        .iter()
+                        .any(|binding| binding.kind == kind && binding.device_id == device_id),
+                    device_id,
+                    name,
+                    connected: true,
+                }
+            })),
+            Err(error) => debug!(kind, %error, "input trigger backend unavailable"),
+        }
+    }
+    for binding in bindings {
+        let listed = devices
+            .iter()
+            .any(|device| device.kind == binding.kind && device.device_id == binding.device_id);
+        if !listed {
+            devices.push(InputTriggerDevice {
+                kind: binding.kind.clone(),
+                device_id: binding.device_id.clone(),
+                name: binding.device_id.clone(),
+                connected: false,
+                bound: true,
+            });
+        }
+    }
+    devices
+}
+
+fn connect(
+    binding: &InputTriggerBinding,
+    handler: TriggerHandler,
+) -> Result<TriggerConnection, String> {
+    let control = binding.control;
+    let mut edge = TriggerEdge::default();
+    let mut on_state = move |pressed: bool| {
+        if let Some(trigger) = edge.update(pressed) {
+            handler(trigger);
+        }
+    };
+    match binding.kind.as_str() {
+        INPUT_TRIGGER_KIND_HID => hid::connect(
+            &binding.device_id,
+            Box::new(move |report: &[u8]| on_state(hid_control_state(report, control))),
+        ),
+        INPUT_TRIGGER_KIND_MIDI => midi::connect(
+            &binding.device_id,
+            Box::new(move |message: &[u8]| {
+                if let Some(pressed) = midi_control_state(message, control) {
+                    on_state(pressed);
+                }
+            }),
+        ),
+        kind => Err(format!("Unknown input trigger kind `{kind}`")),
+    }
+}
+
+fn binding_key(binding: &InputTriggerBinding) -> BindingKey {
+    (
+        binding.kind.clone(),
+        binding.device_id.clone(),
+        binding.control,
+    )
+}
+
+// Turns a stream of readings into press and release edges, since HID reports
+// repeat while a pedal is held.
+#[derive(Debug, Default)]
+struct TriggerEdge {
+    pressed: bool,
+}
+
+impl TriggerEdge {
+    fn update(&mut self, pressed: bool) -> Option<HotkeyTrigger> {
+        if pressed == self.pressed {
+            return None;
+        }
+        self.pressed = pressed;
+        Some(if pressed {
+            HotkeyTrigger::Pressed
+        } else {
+            HotkeyTrigger::Released
+        })
+    }
+}
+
+// Most USB foot switches report their pedals as a bitmask, one bit per pedal
+// starting at the lowest bit of the first byte.
+fn hid_control_state(report: &[u8], control: Option<u8>) -> bool {
+    match control {
+        Some(control) => report
+            .get(usize::from(control / 8))
+            .is_some_and(|byte| byte & (1 << (control % 8)) != 0),
+        None => report.iter().any(|byte| *byte != 0),
+    }
+}
+
+// Notes press on note-on and release on note-off (or note-on at zero
+// velocity); controllers follow the sustain pedal convention of 64 and up
+// meaning down. Anything else, or another note, is not a reading.
+fn midi_control_state(message: &[u8], control: Option<u8>) -> Option<bool> {
+    let &[status, number, value, ..] = message else {
+        return None;
+    };
+    if control.is_some_and(|control| control != number) {
+        return None;
+    }
+    match status & 0xF0 {
+        0x80 => Some(false),
+        0x90 => Some(value > 0),
+        0xB0 => Some(value >= 64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_readings_produce_one_edge() {
+        let mut edge = TriggerEdge::default();
+        assert_eq!(edge.update(false), None);
+        assert_eq!(edge.update(true), Some(HotkeyTrigger::Pressed));
+        assert_eq!(edge.update(true), None);
+        assert_eq!(edge.update(false), Some(HotkeyTrigger::Released));
+    }
+
+    #[test]
+    fn hid_reports_are_read_as_pedal_bitmasks() {
+        assert!(hid_control_state(&[0b0000_0010], Some(1)));
+        assert!(!hid_control_state(&[0b0000_0010], Some(0)));
+        assert!(hid_control_state(&[0, 0b0000_0001], Some(8)));
+        assert!(!hid_control_state(&[0b0000_0001], Some(8)));
+        assert!(hid_control_state(&[0, 4], None));
+        assert!(!hid_control_state(&[0, 0], None));
+    }
+
+    #[test]
+    fn midi_notes_and_controllers_map_to_pressed_state() {
+        assert_eq!(midi_control_state(&[0x90, 60, 100], None), Some(true));
+        assert_eq!(midi_control_state(&[0x91, 60, 0], Some(60)), Some(false));
+        assert_eq!(midi_control_state(&[0x80, 60, 64], Some(60)), Some(false));
+        assert_eq!(midi_control_state(&[0x90, 61, 100], Some(60)), None);
+        assert_eq!(midi_control_state(&[0xB0, 64, 127], Some(64)), Some(true));
+        assert_eq!(midi_control_state(&[0xB0, 64, 10], Some(64)), Some(false));
+        assert_eq!(midi_control_state(&[0xE0, 0, 64], None), None);
+        assert_eq!(midi_control_state(&[0xF8], None), None);
+    }
+
+    #[test]
+    fn unplugged_bindings_stay_listed() {
+        let bindings = vec![InputTriggerBinding {
+            kind: INPUT_TRIGGER_KIND_MIDI.to_string(),
+            device_id: "Not A Real Port".to_string(),
+            ..InputTriggerBinding::default()
+        }];
+        let devices = list_devices(&bindings);
+        let device = devices
+            .iter()
+            .find(|device| device.device_id == "Not A Real Port")
+            .expect("bound device should be listed");
+        assert!(device.bound);
+        assert!(!device.connected);
+    }
+}
@@ -0,0 +1,227 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::focused_app_watcher::FocusedApp;
+use crate::text_insertion_service::InsertionStrategy;
+
+const AUDIT_FILE_NAME: &str = "insertion_audit.jsonl";
+pub const MAX_AUDIT_PAGE_SIZE: usize = 1000;
+
+// One line per delivered transcript. Only metadata is kept, never the text.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertionAuditEntry {
+    pub timestamp: String,
+    #[serde(default)]
+    pub session_id: Option<u64>,
+    #[serde(default)]
+    pub target_app: Option<String>,
+    #[serde(default)]
+    pub target_bundle_id: Option<String>,
+    pub strategy: InsertionStrategy,
+    pub char_count: usize,
+}
+
+impl InsertionAuditEntry {
+    pub fn new(
+        session_id: Option<u64>,
+        target_app: Option<&FocusedApp>,
+        strategy: InsertionStrategy,
+        text: &str,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            session_id,
+            target_app: target_app.map(|app| app.name.clone()),
+            target_bundle_id: target_app.and_then(|app| app.bundle_id.clone()),
+            strategy,
+            char_count: text.chars().count(),
+        }
+    }
+}
+
+// Append-only JSON lines; the only rewrite is dropping entries past the
+// retention window.
+#[derive(Debug)]
+pub struct InsertionAuditLog {
+    file_path: PathBuf,
+    io_lock: Mutex<()>,
+}
+
+impl InsertionAuditLog {
+    pub fn new_in_dir(app_data_dir: &Path) -> Self {
+        Self::new_with_file_path(app_data_dir.join(AUDIT_FILE_NAME))
+    }
+
+    pub fn new_with_file_path(file_path: PathBuf) -> Self {
+        Self {
+            file_path,
+            io_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn append(&self, entry: &InsertionAuditEntry) -> Result<(), String> {
+        let mut line = serde_json::to_string(entry)
+            .map_err(|error| format!("Failed to serialize insertion audit entry: {error}"))?;
+        line.push('\n');
+
+        let _guard = self
+            .io_lock
+            .lock()
+            .map_err(|_| "Insertion audit lock is poisoned".to_string())?;
+        if let Some(parent_dir) = self.file_path.parent() {
+            fs::create_dir_all(parent_dir)
+                .map_err(|error| format!("Failed to create insertion audit directory: {error}"))?;
+        }
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file_path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|error| format!("Failed to append to insertion audit log: {error}"))?;
+        debug!(
+            session_id = ?entry.session_id,
+            strategy = ?entry.strategy,
+            "recorded insertion audit entry"
+        );
+        Ok(())
+    }
+
+    // Newest first.
+    pub fn recent(&self, limit: usize) -> Result<Vec<InsertionAuditEntry>, String> {
+        let _guard = self
+            .io_lock
+            .lock()
+            .map_err(|_| "Insertion audit lock is poisoned".to_string())?;
+        let mut entries = self.read_entries()?;
+        entries.reverse();
+        entries.truncate(limit.min(MAX_AUDIT_PAGE_SIZE));
+        Ok(entries)
+    }
+
+    // A retention of zero days keeps everything.
+    pub fn prune(&self, retention_days: u32, now: DateTime<Utc>) -> Result<usize, String> {
+        if retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = now - Duration::days(i64::from(retention_days));
+
+        let _guard = self
+            .io_lock
+            .lock()
+            .map_err(|_| "Insertion audit lock is poisoned".to_string())?;
+        let entries = self.read_entries()?;
+        let original_len = entries.len();
+        let retained = entries
+            .into_iter()
+            .filter(|entry| {
+                DateTime::parse_from_rfc3339(&entry.timestamp)
+                    .is_ok_and(|timestamp| timestamp >= cutoff)
+            })
+            .collect::<Vec<_>>();
+        let pruned = original_len - retained.len();
+        if pruned == 0 {
+            return Ok(0);
+        }
+
+        let mut contents = String::new();
+        for entry in &retained {
+            let line = serde_json::to_string(entry)
+                .map_err(|error| format!("Failed to serialize insertion audit entry: {error}"))?;
+            contents.push_str(&line);
+            contents.push('\n');
+        }
+        let temp_path = self.file_path.with_extension("jsonl.tmp");
+        fs::write(&temp_path, contents)
+            .and_then(|()| fs::rename(&temp_path, &self.file_path))
+            .map_err(|error| {
+                let _ = fs::remove_file(&temp_path);
+                format!("Failed to rewrite insertion audit log: {error}")
+            })?;
+        info!(pruned, retention_days, "pruned insertion audit log");
+        Ok(pruned)
+    }
+
+    fn read_entries(&self) -> Result<Vec<InsertionAuditEntry>, String> {
+        if !self.file_path.exists() {
+            return Ok(Vec::new());
+        }
+        let contents = fs::read_to_string(&self.file_path)
+            .map_err(|error| format!("Failed to read insertion audit log: {error}"))?;
+
+        Ok(contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(
+                |line| match serde_json::from_str::<InsertionAuditEntry>(line) {
+                    Ok(entry) => Some(entry),
+                    Err(error) => {
+                        warn!(%error, "skipping malformed insertion audit line");
+                        None
+                    }
+                },
+            )
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::{Duration, SecondsFormat, Utc};
+
+    use super::{InsertionAuditEntry, InsertionAuditLog};
+    use crate::{focused_app_watcher::FocusedApp, text_insertion_service::InsertionStrategy};
+
+    #[test]
+    fn appends_lists_newest_first_and_prunes_past_retention() {
+        let test_dir =
+            std::env::temp_dir().join(format!("voice-insertion-audit-{}", uuid::Uuid::new_v4()));
+        let log = InsertionAuditLog::new_in_dir(&test_dir);
+        assert!(log.recent(10).expect("empty log should read").is_empty());
+
+        let app = FocusedApp {
+            bundle_id: Some("com.apple.Notes".to_string()),
+            name: "Notes".to_string(),
+        };
+        let mut stale =
+            InsertionAuditEntry::new(Some(1), None, InsertionStrategy::ClipboardOnly, "old");
+        stale.timestamp =
+            (Utc::now() - Duration::days(40)).to_rfc3339_opts(SecondsFormat::Millis, true);
+        log.append(&stale).expect("stale entry should append");
+        log.append(&InsertionAuditEntry::new(
+            Some(2),
+            Some(&app),
+            InsertionStrategy::DirectTyping,
+            "héllo",
+        ))
+        .expect("entry should append");
+
+        let recent = log.recent(10).expect("log should read");
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].session_id, Some(2));
+        assert_eq!(
+            recent[0].target_bundle_id.as_deref(),
+            Some("com.apple.Notes")
+        );
+        assert_eq!(recent[0].char_count, 5);
+
+        assert_eq!(log.prune(0, Utc::now()), Ok(0));
+        assert_eq!(log.prune(30, Utc::now()), Ok(1));
+        let remaining = log.recent(10).expect("pruned log should read");
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].strategy, InsertionStrategy::DirectTyping);
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
@@ -0,0 +1,178 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+pub const EVENT_CONFLICTING_INSTANCE: &str = "voice://conflicting-instance";
+
+// Bundles and binaries that register the same hotkey and open the same
+// microphone. `Voice.app` is the build shipped under the legacy identifier;
+// `tauri-app` is the unbundled development binary.
+const LEGACY_APP_BUNDLES: &[&str] = &["/Voice.app/"];
+const CURRENT_APP_BUNDLES: &[&str] = &["/Buzz.app/"];
+const DEV_BINARY_NAME: &str = "tauri-app";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictingInstance {
+    pub pid: u32,
+    pub path: String,
+    pub legacy: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictingInstanceReport {
+    pub instances: Vec<ConflictingInstance>,
+    pub remediation: String,
+}
+
+impl ConflictingInstanceReport {
+    fn new(instances: Vec<ConflictingInstance>) -> Option<Self> {
+        if instances.is_empty() {
+            return None;
+        }
+        let remediation = if instances.iter().any(|instance| instance.legacy) {
+            "An older copy of Voice is running and can take the hotkey and microphone. Quit it and delete it from Applications, then re-save your hotkey."
+        } else {
+            "Another copy of the app is running and can take the hotkey and microphone. Quit the other copy, then re-save your hotkey."
+        };
+        Some(Self {
+            instances,
+            remediation: remediation.to_string(),
+        })
+    }
+}
+
+pub fn detect() -> Option<ConflictingInstanceReport> {
+    let listing = match list_processes() {
+        Ok(listing) => listing,
+        Err(error) => {
+            warn!(%error, "failed to list processes for instance conflict check");
+            return None;
+        }
+    };
+    let instances = parse_process_listing(&listing, std::process::id());
+    debug!(
+        conflicting_instances = instances.len(),
+        "instance conflict check completed"
+    );
+    ConflictingInstanceReport::new(instances)
+}
+
+// Only processes that are still detected as conflicting can be signalled, so
+// the frontend cannot use this to stop arbitrary pids.
+pub fn request_quit(pid: u32) -> Result<(), String> {
+    let detected = detect().map(|report| report.instances).unwrap_or_default();
+    if !detected.iter().any(|instance| instance.pid == pid) {
+        return Err(format!("Process {pid} is not a conflicting instance"));
+    }
+    send_terminate(pid)?;
+    info!(pid, "asked conflicting instance to quit");
+    Ok(())
+}
+
+fn parse_process_listing(listing: &str, own_pid: u32) -> Vec<ConflictingInstance> {
+    listing
+        .lines()
+        .filter_map(|line| {
+            let (pid, path) = line.trim().split_once(char::is_whitespace)?;
+            let pid = pid.parse::<u32>().ok()?;
+            let path = path.trim();
+            if pid == own_pid {
+                return None;
+            }
+            let legacy = LEGACY_APP_BUNDLES
+                .iter()
+                .any(|bundle| path.contains(bundle));
+            let current = CURRENT_APP_BUNDLES
+                .iter()
+                .any(|bundle| path.contains(bundle))
+                || path.rsplit('/').next() == Some(DEV_BINARY_NAME);
+            (legacy || current).then(|| ConflictingInstance {
+                pid,
+                path: path.to_string(),
+                legacy,
+            })
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+fn list_processes() -> Result<String, String> {
+    let output = std::process::Command::new("ps")
+        .args(["-axo", "pid=,comm="])
+        .output()
+        .map_err(|error| format!("Failed to run ps: {error}"))?;
+    if !output.status.success() {
+        return Err(format!("ps exited with status {}", output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(not(unix))]
+fn list_processes() -> Result<String, String> {
+    Ok(String::new())
+}
+
+#[cfg(unix)]
+fn send_terminate(pid: u32) -> Result<(), String> {
+    let status = std::process::Command::new("kill")
+        .args(["-TERM", &pid.to_string()])
+        .status()
+        .map_err(|error| format!("Failed to run kill: {error}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "kill exited with status {status} for process {pid}"
+        ))
+    }
+}
+
+#[cfg(not(unix))]
+fn send_terminate(pid: u32) -> Result<(), String> {
+    Err(format!(
+        "Quitting process {pid} is not supported on this platform"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_process_listing, ConflictingInstance, ConflictingInstanceReport};
+
+    #[test]
+    fn finds_other_app_processes_and_flags_legacy_copies() {
+        let listing = "\
+  101 /Applications/Voice.app/Contents/MacOS/voice
+  202 /Applications/Buzz.app/Contents/MacOS/buzz
+  303 /usr/sbin/coreaudiod
+  404 /Users/me/voice/src-tauri/target/debug/tauri-app
+  505 /Applications/Buzz.app/Contents/MacOS/buzz
+";
+        let instances = parse_process_listing(listing, 505);
+        assert_eq!(
+            instances,
+            vec![
+                ConflictingInstance {
+                    pid: 101,
+                    path: "/Applications/Voice.app/Contents/MacOS/voice".to_string(),
+                    legacy: true,
+                },
+                ConflictingInstance {
+                    pid: 202,
+                    path: "/Applications/Buzz.app/Contents/MacOS/buzz".to_string(),
+                    legacy: false,
+                },
+                ConflictingInstance {
+                    pid: 404,
+                    path: "/Users/me/voice/src-tauri/target/debug/tauri-app".to_string(),
+                    legacy: false,
+                },
+            ]
+        );
+
+        let report = ConflictingInstanceReport::new(instances).expect("report should exist");
+        assert!(report.remediation.contains("older copy"));
+        assert!(ConflictingInstanceReport::new(Vec::new()).is_none());
+    }
+}
@@ -0,0 +1,59 @@
+// Generic-password items in the user's login keychain. Platforms without a
+// supported keychain report it as unavailable and the stores keep their files.
+
+pub fn is_available() -> bool {
+    cfg!(target_os = "macos")
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use security_framework::passwords::{
+        delete_generic_password, get_generic_password, set_generic_password,
+    };
+
+    const ERR_SEC_ITEM_NOT_FOUND: i32 = -25300;
+
+    pub fn get(service: &str, account: &str) -> Result<Option<Vec<u8>>, String> {
+        match get_generic_password(service, account) {
+            Ok(secret) => Ok(Some(secret)),
+            Err(error) if error.code() == ERR_SEC_ITEM_NOT_FOUND => Ok(None),
+            Err(error) => Err(format!(
+                "Failed to read `{service}/{account}` from the keychain: {error}"
+            )),
+        }
+    }
+
+    pub fn set(service: &str, account: &str, secret: &[u8]) -> Result<(), String> {
+        set_generic_password(service, account, secret).map_err(|error| {
+            format!("Failed to write `{service}/{account}` to the keychain: {error}")
+        })
+    }
+
+    pub fn delete(service: &str, account: &str) -> Result<(), String> {
+        match delete_generic_password(service, account) {
+            Err(error) if error.code() != ERR_SEC_ITEM_NOT_FOUND => Err(format!(
+                "Failed to delete `{service}/{account}` from the keychain: {error}"
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    const UNAVAILABLE: &str = "The OS keychain is not available on this platform";
+
+    pub fn get(_service: &str, _account: &str) -> Result<Option<Vec<u8>>, String> {
+        Err(UNAVAILABLE.to_string())
+    }
+
+    pub fn set(_service: &str, _account: &str, _secret: &[u8]) -> Result<(), String> {
+        Err(UNAVAILABLE.to_string())
+    }
+
+    pub fn delete(_service: &str, _account: &str) -> Result<(), String> {
+        Err(UNAVAILABLE.to_string())
+    }
+}
+
+pub use platform::{delete, get, set};
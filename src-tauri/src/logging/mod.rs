@@ -5,37 +5,100 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager, Runtime};
 use tracing::info;
-use tracing_subscriber::{fmt::MakeWriter, prelude::*, EnvFilter};
+use tracing_subscriber::{fmt::MakeWriter, prelude::*, reload, EnvFilter, Registry};
 
 const LOG_FILE_NAME: &str = "voice.log";
 const DEFAULT_LOG_FILTER: &str = "info,tauri_app_lib=debug";
 const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+// `voice.log.1` is the most recent rotated file; older ones are deleted.
+const MAX_ROTATED_LOG_FILES: usize = 3;
+pub const DEFAULT_LOG_QUERY_LIMIT: usize = 500;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Info => "info",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "ERROR" => Some(Self::Error),
+            "WARN" => Some(Self::Warn),
+            "INFO" => Some(Self::Info),
+            "DEBUG" => Some(Self::Debug),
+            "TRACE" => Some(Self::Trace),
+            _ => None,
+        }
+    }
+
+    // Dependencies stay at info even when the app itself is more verbose,
+    // otherwise the HTTP stack drowns out everything else.
+    fn filter_directive(self) -> String {
+        let dependencies = self.min(Self::Info);
+        format!("{},tauri_app_lib={}", dependencies.as_str(), self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: LogLevel,
+    pub target: String,
+    pub message: String,
+}
 
 #[derive(Debug, Clone)]
 pub struct LoggingState {
     log_file_path: Arc<PathBuf>,
+    filter: reload::Handle<EnvFilter, Registry>,
 }
 
 impl LoggingState {
-    pub fn new(log_file_path: PathBuf) -> Self {
-        Self {
-            log_file_path: Arc::new(log_file_path),
-        }
-    }
-
     pub fn log_file_path(&self) -> &Path {
         self.log_file_path.as_ref().as_path()
     }
+
+    // Takes effect for the next event; nothing is persisted, so a restart
+    // goes back to the default filter.
+    pub fn set_level(&self, level: LogLevel) -> Result<(), String> {
+        let directive = level.filter_directive();
+        self.filter
+            .reload(EnvFilter::new(&directive))
+            .map_err(|error| format!("Failed to change log level: {error}"))?;
+        info!(filter = %directive, "log level changed");
+        Ok(())
+    }
 }
 
 pub fn initialize<R: Runtime>(app: &AppHandle<R>) -> Result<LoggingState, String> {
     let log_file_path = resolve_log_file_path(app)?;
-    let log_file = open_log_file(&log_file_path)?;
-    let writer = SharedLogWriterFactory::new(log_file);
+    let writer = SharedLogWriterFactory::new(RotatingLogFile::open(log_file_path.clone())?);
     let env_filter =
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER));
+    let (env_filter, filter) = reload::Layer::new(env_filter);
 
     let subscriber = tracing_subscriber::registry()
         .with(env_filter)
@@ -57,13 +120,89 @@ pub fn initialize<R: Runtime>(app: &AppHandle<R>) -> Result<LoggingState, String
         .map_err(|error| format!("Failed to initialize diagnostics logger: {error}"))?;
 
     info!(log_file = %log_file_path.display(), "diagnostic logging initialized");
-    Ok(LoggingState::new(log_file_path))
+    Ok(LoggingState {
+        log_file_path: Arc::new(log_file_path),
+        filter,
+    })
 }
 
 pub fn export_log_contents(state: &LoggingState) -> Result<String, String> {
     read_log_file(state.log_file_path())
 }
 
+// Reads the current and rotated files oldest first and returns the newest
+// `limit` entries at or above `level`, still in chronological order.
+pub fn query_logs(
+    state: &LoggingState,
+    level: Option<LogLevel>,
+    since: Option<&str>,
+    limit: usize,
+) -> Result<Vec<LogEntry>, String> {
+    let since = since
+        .map(|value| {
+            DateTime::parse_from_rfc3339(value)
+                .map(|value| value.with_timezone(&Utc))
+                .map_err(|error| format!("Invalid `since` timestamp `{value}`: {error}"))
+        })
+        .transpose()?;
+    let log_file_path = state.log_file_path();
+    let mut entries = Vec::new();
+    for index in (0..=MAX_ROTATED_LOG_FILES).rev() {
+        let contents = read_log_file(&log_file_path_at(log_file_path, index))?;
+        entries.extend(parse_log_entries(&contents).into_iter().filter(|entry| {
+            level.is_none_or(|level| entry.level <= level)
+                && since.is_none_or(|since| {
+                    DateTime::parse_from_rfc3339(&entry.timestamp)
+                        .is_ok_and(|timestamp| timestamp >= since)
+                })
+        }));
+    }
+    let skip = entries.len().saturating_sub(limit);
+    Ok(entries.split_off(skip))
+}
+
+// Parses the plain `fmt` layer output: `<timestamp> <LEVEL> <target>: <message>`.
+// Lines that do not start that way belong to the previous entry's message.
+fn parse_log_entries(contents: &str) -> Vec<LogEntry> {
+    let mut entries: Vec<LogEntry> = Vec::new();
+    for line in contents.lines() {
+        match parse_log_line(line) {
+            Some(entry) => entries.push(entry),
+            None => {
+                if let Some(previous) = entries.last_mut() {
+                    previous.message.push('\n');
+                    previous.message.push_str(line);
+                }
+            }
+        }
+    }
+    entries
+}
+
+fn parse_log_line(line: &str) -> Option<LogEntry> {
+    let (timestamp, rest) = line.split_once(' ')?;
+    DateTime::parse_from_rfc3339(timestamp).ok()?;
+    let rest = rest.trim_start();
+    let (level, rest) = rest.split_once(' ')?;
+    let level = LogLevel::parse(level)?;
+    let (target, message) = rest.split_once(": ").unwrap_or(("", rest));
+    Some(LogEntry {
+        timestamp: timestamp.to_string(),
+        level,
+        target: target.to_string(),
+        message: message.to_string(),
+    })
+}
+
+fn log_file_path_at(log_file_path: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        return log_file_path.to_path_buf();
+    }
+    let mut file_name = log_file_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".{index}"));
+    log_file_path.with_file_name(file_name)
+}
+
 fn resolve_log_file_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
     let app_data_dir = app
         .path()
@@ -83,7 +222,6 @@ fn open_log_file(log_file_path: &Path) -> Result<File, String> {
         })?;
     }
 
-    cap_log_file_size(log_file_path, MAX_LOG_FILE_BYTES)?;
     OpenOptions::new()
         .create(true)
         .append(true)
@@ -96,34 +234,76 @@ fn open_log_file(log_file_path: &Path) -> Result<File, String> {
         })
 }
 
-fn cap_log_file_size(log_file_path: &Path, max_bytes: u64) -> Result<(), String> {
-    let metadata = match fs::metadata(log_file_path) {
-        Ok(metadata) => metadata,
-        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
-        Err(error) => {
-            return Err(format!(
-                "Failed to inspect diagnostics log file `{}`: {error}",
-                log_file_path.display()
-            ))
+// Shifts `voice.log` to `voice.log.1`, `.1` to `.2` and so on, dropping the
+// oldest file.
+fn rotate_log_files(log_file_path: &Path, max_rotated_files: usize) -> Result<(), String> {
+    let _ = fs::remove_file(log_file_path_at(log_file_path, max_rotated_files));
+    for index in (0..max_rotated_files).rev() {
+        let from = log_file_path_at(log_file_path, index);
+        match fs::rename(&from, log_file_path_at(log_file_path, index + 1)) {
+            Ok(()) => {}
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => {
+                return Err(format!(
+                    "Failed to rotate diagnostics log file `{}`: {error}",
+                    from.display()
+                ))
+            }
         }
-    };
+    }
+    Ok(())
+}
 
-    if metadata.len() <= max_bytes {
-        return Ok(());
+struct RotatingLogFile {
+    path: PathBuf,
+    file: File,
+    len: u64,
+    max_bytes: u64,
+}
+
+impl RotatingLogFile {
+    fn open(path: PathBuf) -> Result<Self, String> {
+        Self::open_with_max_bytes(path, MAX_LOG_FILE_BYTES)
     }
 
-    OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .open(log_file_path)
-        .map_err(|error| {
-            format!(
-                "Failed to truncate oversized diagnostics log file `{}`: {error}",
-                log_file_path.display()
-            )
-        })?;
+    fn open_with_max_bytes(path: PathBuf, max_bytes: u64) -> Result<Self, String> {
+        let file = open_log_file(&path)?;
+        let len = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        let mut log_file = Self {
+            path,
+            file,
+            len,
+            max_bytes,
+        };
+        if log_file.len > max_bytes {
+            log_file.rotate()?;
+        }
+        Ok(log_file)
+    }
 
-    Ok(())
+    fn rotate(&mut self) -> Result<(), String> {
+        rotate_log_files(&self.path, MAX_ROTATED_LOG_FILES)?;
+        self.file = open_log_file(&self.path)?;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+impl io::Write for RotatingLogFile {
+    // Each event arrives as one write, so rotating afterwards never splits
+    // an entry across files.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write_all(buf)?;
+        self.len += buf.len() as u64;
+        if self.len > self.max_bytes {
+            self.rotate().map_err(io::Error::other)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
 }
 
 fn read_log_file(log_file_path: &Path) -> Result<String, String> {
@@ -141,13 +321,13 @@ fn read_log_file(log_file_path: &Path) -> Result<String, String> {
     Ok(String::from_utf8_lossy(&contents).into_owned())
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 struct SharedLogWriterFactory {
-    file: Arc<Mutex<File>>,
+    file: Arc<Mutex<RotatingLogFile>>,
 }
 
 impl SharedLogWriterFactory {
-    fn new(file: File) -> Self {
+    fn new(file: RotatingLogFile) -> Self {
         Self {
             file: Arc::new(Mutex::new(file)),
         }
@@ -165,7 +345,7 @@ impl<'a> MakeWriter<'a> for SharedLogWriterFactory {
 }
 
 struct SharedLogWriter {
-    file: Arc<Mutex<File>>,
+    file: Arc<Mutex<RotatingLogFile>>,
 }
 
 impl io::Write for SharedLogWriter {
@@ -189,29 +369,69 @@ impl io::Write for SharedLogWriter {
 
 #[cfg(test)]
 mod tests {
-    use std::{fs, time::SystemTime};
+    use std::{fs, io::Write, time::SystemTime};
 
-    use super::{cap_log_file_size, read_log_file};
+    use super::{
+        log_file_path_at, parse_log_entries, read_log_file, LogLevel, RotatingLogFile,
+        MAX_ROTATED_LOG_FILES,
+    };
 
     fn temp_log_path(prefix: &str) -> std::path::PathBuf {
         let nanos = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .expect("clock should progress")
             .as_nanos();
-        std::env::temp_dir().join(format!("{prefix}-{nanos}.log"))
+        std::env::temp_dir()
+            .join(format!("{prefix}-{nanos}"))
+            .join("voice.log")
     }
 
     #[test]
-    fn capping_oversized_log_file_truncates_contents() {
-        let path = temp_log_path("voice-log-cap");
-        fs::write(&path, "x".repeat(1024)).expect("should write test log file");
+    fn oversized_log_file_rotates_and_keeps_a_bounded_history() {
+        let path = temp_log_path("voice-log-rotate");
+        let mut log_file =
+            RotatingLogFile::open_with_max_bytes(path.clone(), 16).expect("log should open");
+
+        for index in 0..=MAX_ROTATED_LOG_FILES + 1 {
+            log_file
+                .write_all(format!("entry {index} padded past the cap\n").as_bytes())
+                .expect("entry should write");
+        }
 
-        cap_log_file_size(&path, 128).expect("capping should succeed");
+        assert!(fs::read_to_string(&path)
+            .expect("current log should exist")
+            .is_empty());
+        assert!(fs::read_to_string(log_file_path_at(&path, 1))
+            .expect("newest rotation should exist")
+            .starts_with(&format!("entry {}", MAX_ROTATED_LOG_FILES + 1)));
+        assert!(log_file_path_at(&path, MAX_ROTATED_LOG_FILES).exists());
+        assert!(!log_file_path_at(&path, MAX_ROTATED_LOG_FILES + 1).exists());
 
-        let truncated = fs::read_to_string(&path).expect("should read truncated log file");
-        assert!(truncated.is_empty());
+        let _ = fs::remove_dir_all(path.parent().expect("log should have a directory"));
+    }
 
-        let _ = fs::remove_file(path);
+    #[test]
+    fn parses_fmt_lines_into_entries() {
+        let entries = parse_log_entries(concat!(
+            "2026-03-01T10:00:00.000001Z  INFO tauri_app_lib::logging: diagnostic logging initialized log_file=/tmp/voice.log\n",
+            "2026-03-01T10:00:01.000001Z DEBUG tauri_app_lib: first line\n",
+            "second line\n",
+            "third line\n",
+        ));
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].level, LogLevel::Info);
+        assert_eq!(entries[0].target, "tauri_app_lib::logging");
+        assert!(entries[0]
+            .message
+            .starts_with("diagnostic logging initialized"));
+        assert_eq!(entries[1].level, LogLevel::Debug);
+        assert_eq!(entries[1].message, "first line\nsecond line\nthird line");
+        assert_eq!(
+            LogLevel::Debug.filter_directive(),
+            "info,tauri_app_lib=debug"
+        );
+        assert_eq!(LogLevel::Warn.filter_directive(), "warn,tauri_app_lib=warn");
     }
 
     #[test]
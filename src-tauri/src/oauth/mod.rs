@@ -1,9 +1,12 @@
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use reqwest::Url;
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
+#[cfg(feature = "tauri")]
 use tauri::{AppHandle, Runtime};
+#[cfg(feature = "tauri")]
 use tauri_plugin_opener::OpenerExt;
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
@@ -13,10 +16,12 @@ use tokio::{
 use tracing::{info, warn};
 use uuid::Uuid;
 
-use crate::auth_store::now_epoch_seconds;
+use crate::auth_store::{now_epoch_seconds, AuthMethod, AuthStore, ChatGptStoredCredentials};
+
+pub const EVENT_AUTH_EXPIRED: &str = "voice://auth-expired";
 
 const AUTHORIZE_URL: &str = "https://auth.openai.com/oauth/authorize";
-const TOKEN_URL: &str = "https://auth.openai.com/oauth/token";
+pub const TOKEN_URL: &str = "https://auth.openai.com/oauth/token";
 const CLIENT_ID: &str = "app_EMoamEEZ73f0CkXaXp7hrann";
 const OAUTH_SCOPE: &str = "openid profile email offline_access";
 const JWT_AUTH_CLAIM_PATH: &str = "https://api.openai.com/auth";
@@ -26,6 +31,11 @@ const OAUTH_CALLBACK_BIND_PORT: u16 = 1455;
 const OAUTH_REDIRECT_URI: &str = "http://localhost:1455/auth/callback";
 const OAUTH_TIMEOUT_SECS: u64 = 300;
 const OAUTH_ORIGINATOR: &str = "pi";
+// Background renewal runs this long before the access token expires, and
+// re-reads the credentials at least this often to notice logins and logouts.
+const RENEW_BEFORE_EXPIRY_SECS: u64 = 300;
+const MIN_RENEWAL_CHECK_SECS: u64 = 5;
+const MAX_RENEWAL_CHECK_SECS: u64 = 900;
 
 const SUCCESS_HTML: &str = "<!doctype html>\
 <html lang=\"en\">\
@@ -49,6 +59,12 @@ pub struct OAuthRefreshResult {
     pub account_id: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthExpiredEvent {
+    pub message: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct OAuthTokenResponse {
     access_token: String,
@@ -62,6 +78,7 @@ struct CallbackPayload {
     code: String,
 }
 
+#[cfg(feature = "tauri")]
 pub async fn start_chatgpt_login<R: Runtime>(
     app: &AppHandle<R>,
 ) -> Result<OAuthLoginResult, String> {
@@ -105,12 +122,15 @@ pub async fn start_chatgpt_login<R: Runtime>(
     })
 }
 
-pub async fn refresh_access_token(refresh_token: &str) -> Result<OAuthRefreshResult, String> {
+pub async fn refresh_access_token(
+    token_url: &str,
+    refresh_token: &str,
+) -> Result<OAuthRefreshResult, String> {
     let normalized_refresh_token =
         normalize_required_string(Some(refresh_token.to_string()), "refresh_token")?;
 
-    let response = reqwest::Client::new()
-        .post(TOKEN_URL)
+    let response = crate::http_client::shared_client()
+        .post(token_url)
         .form(&[
             ("grant_type", "refresh_token"),
             ("refresh_token", normalized_refresh_token.as_str()),
@@ -147,6 +167,98 @@ pub async fn refresh_access_token(refresh_token: &str) -> Result<OAuthRefreshRes
     })
 }
 
+// Refreshes the stored tokens unless another caller already replaced
+// `stale_access_token` while this one waited for the refresh lock.
+pub async fn refresh_stored_tokens(
+    auth_store: &AuthStore,
+    token_url: &str,
+    stale_access_token: &str,
+) -> Result<ChatGptStoredCredentials, String> {
+    let _refresh_guard = auth_store.lock_refresh().await;
+    let credentials = auth_store
+        .chatgpt_credentials()
+        .map_err(|error| error.message)?
+        .ok_or_else(|| "Missing ChatGPT OAuth credentials. Please login again.".to_string())?;
+    if credentials.access_token != stale_access_token {
+        return Ok(credentials);
+    }
+
+    let refreshed = refresh_access_token(token_url, &credentials.refresh_token).await?;
+    let refreshed = ChatGptStoredCredentials {
+        access_token: refreshed.access_token,
+        refresh_token: refreshed.refresh_token.unwrap_or(credentials.refresh_token),
+        expires_at: refreshed.expires_at,
+        account_id: refreshed.account_id.unwrap_or(credentials.account_id),
+    };
+    auth_store
+        .update_chatgpt_tokens(
+            &refreshed.access_token,
+            &refreshed.refresh_token,
+            refreshed.expires_at,
+            &refreshed.account_id,
+        )
+        .map_err(|error| error.message)?;
+    info!(
+        expires_at = refreshed.expires_at,
+        "ChatGPT OAuth tokens refreshed"
+    );
+    Ok(refreshed)
+}
+
+// Keeps the ChatGPT access token fresh so dictation never waits on a refresh.
+// A failed refresh is reported once per token; the user has to log in again.
+pub async fn run_token_renewal<F>(auth_store: AuthStore, on_expired: F)
+where
+    F: Fn(AuthExpiredEvent),
+{
+    let mut failed_access_token: Option<String> = None;
+    loop {
+        let credentials = active_chatgpt_credentials(&auth_store);
+        let delay = match &credentials {
+            Some(credentials)
+                if failed_access_token.as_ref() != Some(&credentials.access_token) =>
+            {
+                renewal_delay(credentials.expires_at, now_epoch_seconds())
+            }
+            _ => Duration::from_secs(MAX_RENEWAL_CHECK_SECS),
+        };
+        tokio::time::sleep(delay).await;
+
+        let Some(credentials) = active_chatgpt_credentials(&auth_store) else {
+            continue;
+        };
+        if failed_access_token.as_ref() == Some(&credentials.access_token)
+            || credentials.expires_at > now_epoch_seconds().saturating_add(RENEW_BEFORE_EXPIRY_SECS)
+        {
+            continue;
+        }
+        match refresh_stored_tokens(&auth_store, TOKEN_URL, &credentials.access_token).await {
+            Ok(_) => failed_access_token = None,
+            Err(message) => {
+                warn!(%message, "background ChatGPT token renewal failed");
+                failed_access_token = Some(credentials.access_token);
+                on_expired(AuthExpiredEvent { message });
+            }
+        }
+    }
+}
+
+fn active_chatgpt_credentials(auth_store: &AuthStore) -> Option<ChatGptStoredCredentials> {
+    if auth_store.current_auth_method().ok()? != AuthMethod::ChatgptOauth {
+        return None;
+    }
+    auth_store.chatgpt_credentials().ok().flatten()
+}
+
+fn renewal_delay(expires_at: u64, now: u64) -> Duration {
+    Duration::from_secs(
+        expires_at
+            .saturating_sub(RENEW_BEFORE_EXPIRY_SECS)
+            .saturating_sub(now)
+            .clamp(MIN_RENEWAL_CHECK_SECS, MAX_RENEWAL_CHECK_SECS),
+    )
+}
+
 pub fn extract_chatgpt_account_id(access_token: &str) -> Option<String> {
     let payload = decode_jwt_payload(access_token)?;
     payload
@@ -180,6 +292,7 @@ fn build_authorize_url(
     Ok(authorize_url)
 }
 
+#[cfg(feature = "tauri")]
 fn open_authorize_url_in_system_browser<R: Runtime>(
     app: &AppHandle<R>,
     authorize_url: &Url,
@@ -253,7 +366,7 @@ async fn exchange_authorization_code(
     code_verifier: &str,
     redirect_uri: &str,
 ) -> Result<OAuthTokenResponse, String> {
-    let response = reqwest::Client::new()
+    let response = crate::http_client::shared_client()
         .post(TOKEN_URL)
         .form(&[
             ("grant_type", "authorization_code"),
@@ -446,6 +559,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn renewal_runs_ahead_of_expiry_within_check_bounds() {
+        assert_eq!(renewal_delay(1_000 + 600, 1_000), Duration::from_secs(300));
+        assert_eq!(
+            renewal_delay(1_000 + 60, 1_000),
+            Duration::from_secs(MIN_RENEWAL_CHECK_SECS)
+        );
+        assert_eq!(
+            renewal_delay(1_000 + 86_400, 1_000),
+            Duration::from_secs(MAX_RENEWAL_CHECK_SECS)
+        );
+    }
+
     #[test]
     fn parse_request_line_requires_method_and_target() {
         assert!(parse_request_line("GET /auth/callback HTTP/1.1\r\nHost: localhost").is_ok());
@@ -0,0 +1,150 @@
+use std::sync::{Arc, Mutex};
+
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tracing::{debug, info, warn};
+
+use crate::settings_store::VoiceSettings;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayKeyAction {
+    Cancel,
+    StopAndInsert,
+    CopyOnly,
+    Private,
+}
+
+impl OverlayKeyAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Cancel => "cancel",
+            Self::StopAndInsert => "stop_and_insert",
+            Self::CopyOnly => "copy_only",
+            Self::Private => "private",
+        }
+    }
+}
+
+// Keyboard stand-ins for the overlay's buttons, since the overlay never takes
+// focus. They are registered as global shortcuts only while recording so the
+// keys keep working normally the rest of the time.
+#[derive(Debug, Clone, Default)]
+pub struct OverlayKeyService {
+    registered: Arc<Mutex<Vec<String>>>,
+}
+
+impl OverlayKeyService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Registration goes through the global shortcut plugin, which holds its
+    // handler lock while dispatching, so this must not run inside a shortcut
+    // handler.
+    pub fn set_active<R, F>(
+        &self,
+        app: &AppHandle<R>,
+        is_recording: impl FnOnce() -> bool,
+        settings: &VoiceSettings,
+        on_action: F,
+    ) where
+        R: Runtime,
+        F: Fn(&AppHandle<R>, OverlayKeyAction) + Clone + Send + Sync + 'static,
+    {
+        let Ok(mut registered) = self.registered.lock() else {
+            warn!("overlay key registration lock poisoned");
+            return;
+        };
+
+        for key in registered.drain(..) {
+            if let Err(error) = app.global_shortcut().unregister(key.as_str()) {
+                warn!(%key, %error, "failed to unregister overlay key");
+            }
+        }
+        // Checked under the lock so overlapping calls settle on the latest status.
+        if !settings.overlay_keys_enabled || !is_recording() {
+            return;
+        }
+
+        for (key, action) in resolve_bindings(settings) {
+            let on_action = on_action.clone();
+            let result =
+                app.global_shortcut()
+                    .on_shortcut(key.as_str(), move |app, _shortcut, event| {
+                        if event.state == ShortcutState::Pressed {
+                            info!(action = action.as_str(), "overlay key pressed");
+                            on_action(app, action);
+                        }
+                    });
+            match result {
+                Ok(()) => {
+                    debug!(%key, action = action.as_str(), "registered overlay key");
+                    registered.push(key);
+                }
+                Err(error) => warn!(%key, %error, "failed to register overlay key"),
+            }
+        }
+    }
+}
+
+// Keys that fail to parse, repeat an earlier binding, or collide with the
+// recording hotkey are skipped.
+fn resolve_bindings(settings: &VoiceSettings) -> Vec<(String, OverlayKeyAction)> {
+    let hotkey_id = settings
+        .hotkey_shortcut
+        .parse::<Shortcut>()
+        .ok()
+        .map(|shortcut| shortcut.id());
+    let mut seen_ids = Vec::new();
+    [
+        (&settings.overlay_cancel_key, OverlayKeyAction::Cancel),
+        (&settings.overlay_stop_key, OverlayKeyAction::StopAndInsert),
+        (&settings.overlay_copy_key, OverlayKeyAction::CopyOnly),
+        (&settings.overlay_private_key, OverlayKeyAction::Private),
+    ]
+    .into_iter()
+    .filter_map(|(key, action)| {
+        let key = key.as_deref()?;
+        let id = match key.parse::<Shortcut>() {
+            Ok(shortcut) => shortcut.id(),
+            Err(error) => {
+                warn!(key, %error, "ignoring invalid overlay key");
+                return None;
+            }
+        };
+        if Some(id) == hotkey_id || seen_ids.contains(&id) {
+            warn!(key, "ignoring overlay key that is already bound");
+            return None;
+        }
+        seen_ids.push(id);
+        Some((key.to_string(), action))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve_bindings, OverlayKeyAction};
+    use crate::settings_store::VoiceSettings;
+
+    #[test]
+    fn skips_invalid_duplicate_and_hotkey_bindings() {
+        let defaults = VoiceSettings::default();
+        assert_eq!(
+            resolve_bindings(&defaults),
+            vec![
+                ("Escape".to_string(), OverlayKeyAction::Cancel),
+                ("Enter".to_string(), OverlayKeyAction::StopAndInsert),
+                ("KeyC".to_string(), OverlayKeyAction::CopyOnly),
+            ]
+        );
+
+        let settings = VoiceSettings {
+            hotkey_shortcut: "Escape".to_string(),
+            overlay_stop_key: Some("not a key".to_string()),
+            overlay_copy_key: Some("Escape".to_string()),
+            ..VoiceSettings::default()
+        };
+        assert!(resolve_bindings(&settings).is_empty());
+    }
+}
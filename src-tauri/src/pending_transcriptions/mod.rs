@@ -0,0 +1,370 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{SecondsFormat, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, MutexGuard};
+use tracing::{debug, info, warn};
+
+use crate::app_error::{AppError, ErrorCode};
+use crate::history_store::encryption::{self, HistoryCipher};
+
+const PENDING_DIR_NAME: &str = "pending_transcriptions";
+const WAV_EXTENSION: &str = "wav";
+const METADATA_EXTENSION: &str = "json";
+// How often the queue checks whether the provider can be reached again.
+pub const PENDING_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+// After this many failed attempts a recording waits for a manual retry, so a
+// provider that rejects it does not get it resent forever.
+pub const MAX_AUTOMATIC_ATTEMPTS: u32 = 5;
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_REACHABILITY_URL: &str = "https://api.openai.com";
+const GEMINI_REACHABILITY_URL: &str = "https://generativelanguage.googleapis.com";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTranscription {
+    pub id: String,
+    pub created_at: String,
+    pub duration_secs: Option<f64>,
+    pub attempts: u32,
+    pub last_error: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTranscriptionCompletedEvent {
+    pub id: String,
+    pub text: String,
+}
+
+// Recordings that could not be transcribed because the provider was out of
+// reach. Each is a WAV next to a metadata file, so the audio survives a
+// restart; both are removed once the transcript is saved. While history is
+// encrypted the WAV is sealed with the same key.
+#[derive(Debug)]
+pub struct PendingTranscriptionStore {
+    dir: PathBuf,
+    processing: Mutex<()>,
+}
+
+impl PendingTranscriptionStore {
+    pub fn new_in_dir(app_data_dir: &Path) -> Self {
+        let store = Self {
+            dir: app_data_dir.join(PENDING_DIR_NAME),
+            processing: Mutex::new(()),
+        };
+        debug!(
+            pending = store.list().len(),
+            "pending transcriptions initialized"
+        );
+        store
+    }
+
+    pub fn enqueue(
+        &self,
+        wav_bytes: &[u8],
+        duration_secs: Option<f64>,
+        error: &str,
+        cipher: Option<&HistoryCipher>,
+    ) -> Result<PendingTranscription, AppError> {
+        fs::create_dir_all(&self.dir).map_err(pending_io_error)?;
+        let pending = PendingTranscription {
+            id: uuid::Uuid::new_v4().to_string(),
+            // Nanoseconds keep recordings queued back to back in order.
+            created_at: Utc::now().to_rfc3339_opts(SecondsFormat::Nanos, true),
+            duration_secs,
+            attempts: 1,
+            last_error: error.to_string(),
+        };
+        let (wav_path, metadata_path) = self.paths(&pending.id);
+        write_audio(&wav_path, wav_bytes, cipher)?;
+        if let Err(error) = self.write_metadata(&metadata_path, &pending) {
+            let _ = fs::remove_file(&wav_path);
+            return Err(error.into());
+        }
+        info!(id = %pending.id, audio_bytes = wav_bytes.len(), "recording queued for transcription");
+        Ok(pending)
+    }
+
+    // Oldest first, the order they are transcribed in.
+    pub fn list(&self) -> Vec<PendingTranscription> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+            Err(error) => {
+                warn!(path = %self.dir.display(), %error, "failed to read pending transcriptions");
+                return Vec::new();
+            }
+        };
+        let mut pending = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension().and_then(|ext| ext.to_str()) == Some(METADATA_EXTENSION)
+            })
+            .filter_map(|path| match read_metadata(&path) {
+                Ok(pending) => Some(pending),
+                Err(error) => {
+                    warn!(path = %path.display(), %error, "skipping unreadable pending transcription");
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        pending.sort_by(|left, right| left.created_at.cmp(&right.created_at));
+        pending
+    }
+
+    pub fn load_wav(&self, id: &str, cipher: Option<&HistoryCipher>) -> Result<Vec<u8>, AppError> {
+        let (wav_path, _) = self.paths_for_request(id)?;
+        let contents = fs::read(wav_path).map_err(pending_io_error)?;
+        if !encryption::is_encrypted(&contents) {
+            return Ok(contents);
+        }
+        let cipher = cipher.ok_or_else(|| {
+            "This recording was queued while history was encrypted; turn history encryption back on to transcribe it".to_string()
+        })?;
+        Ok(cipher.decrypt(&contents)?)
+    }
+
+    // Rewrites the queued audio under a new history encryption setting, as
+    // `HistoryStore::set_encryption` does for entries. Audio that is already
+    // encrypted is read with `read_cipher`.
+    pub fn set_encryption(
+        &self,
+        read_cipher: Option<&HistoryCipher>,
+        cipher: Option<&HistoryCipher>,
+    ) -> Result<(), AppError> {
+        let pending = self.list();
+        for pending in &pending {
+            let wav_bytes = self.load_wav(&pending.id, read_cipher)?;
+            let (wav_path, _) = self.paths(&pending.id);
+            write_audio(&wav_path, &wav_bytes, cipher)?;
+        }
+        info!(
+            encrypted = cipher.is_some(),
+            pending = pending.len(),
+            "updated queued recording encryption"
+        );
+        Ok(())
+    }
+
+    pub fn record_failure(&self, id: &str, error: &str) -> Result<(), AppError> {
+        let (_, metadata_path) = self.paths_for_request(id)?;
+        let mut pending = read_metadata(&metadata_path)?;
+        pending.attempts += 1;
+        pending.last_error = error.to_string();
+        Ok(self.write_metadata(&metadata_path, &pending)?)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<bool, AppError> {
+        let (wav_path, metadata_path) = self.paths_for_request(id)?;
+        let existed = metadata_path.exists();
+        for path in [&wav_path, &metadata_path] {
+            match fs::remove_file(path) {
+                Ok(()) => {}
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => return Err(pending_io_error(error).into()),
+            }
+        }
+        Ok(existed)
+    }
+
+    // One pass over the queue at a time; a manual retry while the background
+    // pass runs gets `None`.
+    pub fn try_begin_processing(&self) -> Option<MutexGuard<'_, ()>> {
+        self.processing.try_lock().ok()
+    }
+
+    fn paths(&self, id: &str) -> (PathBuf, PathBuf) {
+        (
+            self.dir.join(format!("{id}.{WAV_EXTENSION}")),
+            self.dir.join(format!("{id}.{METADATA_EXTENSION}")),
+        )
+    }
+
+    // Ids come from the frontend, so anything that could escape the
+    // directory is rejected.
+    fn paths_for_request(&self, id: &str) -> Result<(PathBuf, PathBuf), AppError> {
+        let is_valid = !id.is_empty()
+            && id
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || character == '-');
+        if !is_valid {
+            return Err(AppError::new(
+                ErrorCode::InvalidInput,
+                format!("Invalid pending transcription id `{id}`"),
+            ));
+        }
+        Ok(self.paths(id))
+    }
+
+    fn write_metadata(&self, path: &Path, pending: &PendingTranscription) -> Result<(), String> {
+        let serialized = serde_json::to_vec_pretty(pending)
+            .map_err(|error| format!("Failed to serialize pending transcription: {error}"))?;
+        fs::write(path, serialized).map_err(pending_io_error)
+    }
+}
+
+// Written beside the target and renamed over it, so a failed rewrite never
+// leaves half a recording behind.
+fn write_audio(
+    path: &Path,
+    wav_bytes: &[u8],
+    cipher: Option<&HistoryCipher>,
+) -> Result<(), String> {
+    let contents = match cipher {
+        Some(cipher) => cipher.encrypt(wav_bytes)?,
+        None => wav_bytes.to_vec(),
+    };
+    let temp_path = path.with_extension(format!("{WAV_EXTENSION}.tmp"));
+    fs::write(&temp_path, contents).map_err(pending_io_error)?;
+    fs::rename(&temp_path, path).map_err(|error| {
+        let _ = fs::remove_file(&temp_path);
+        pending_io_error(error)
+    })
+}
+
+fn read_metadata(path: &Path) -> Result<PendingTranscription, String> {
+    let bytes = fs::read(path).map_err(pending_io_error)?;
+    serde_json::from_slice(&bytes)
+        .map_err(|error| format!("Pending transcription metadata is malformed: {error}"))
+}
+
+fn pending_io_error(error: std::io::Error) -> String {
+    format!("Pending transcription file error: {error}")
+}
+
+pub fn reachability_url(base_url: Option<&str>, transcription_provider: &str) -> String {
+    match base_url {
+        Some(base_url) => base_url.to_string(),
+        None if transcription_provider == crate::settings_store::GEMINI_TRANSCRIPTION_PROVIDER => {
+            GEMINI_REACHABILITY_URL.to_string()
+        }
+        None => DEFAULT_REACHABILITY_URL.to_string(),
+    }
+}
+
+// Any HTTP answer, even an error status, means the network path is back; only
+// a failure to connect counts as offline.
+pub async fn is_reachable(url: &str) -> bool {
+    crate::http_client::shared_client()
+        .head(url)
+        .timeout(REACHABILITY_TIMEOUT)
+        .send()
+        .await
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_reachable, PendingTranscriptionStore};
+    use crate::history_store::encryption::{self, HistoryCipher};
+
+    #[test]
+    fn queued_recordings_persist_until_removed() {
+        let dir = std::env::temp_dir().join(format!("voice-pending-{}", uuid::Uuid::new_v4()));
+        let store = PendingTranscriptionStore::new_in_dir(&dir);
+        let first = store
+            .enqueue(b"RIFF-first", Some(1.5), "Network error: offline", None)
+            .expect("recording should queue");
+        let second = store
+            .enqueue(b"RIFF-second", None, "Network error: offline", None)
+            .expect("recording should queue");
+        store
+            .record_failure(&first.id, "Network error: still offline")
+            .expect("failure should record");
+
+        let reopened = PendingTranscriptionStore::new_in_dir(&dir);
+        let pending = reopened.list();
+        assert_eq!(
+            pending
+                .iter()
+                .map(|pending| pending.id.as_str())
+                .collect::<Vec<_>>(),
+            [first.id.as_str(), second.id.as_str()]
+        );
+        assert_eq!(pending[0].attempts, 2);
+        assert_eq!(pending[0].last_error, "Network error: still offline");
+        assert_eq!(
+            reopened
+                .load_wav(&second.id, None)
+                .expect("audio should load"),
+            b"RIFF-second"
+        );
+
+        assert!(reopened.remove(&first.id).expect("remove should succeed"));
+        assert!(!reopened.remove(&first.id).expect("remove should succeed"));
+        assert_eq!(
+            reopened
+                .load_wav("../settings", None)
+                .expect_err("ids that leave the queue directory are rejected")
+                .code,
+            crate::app_error::ErrorCode::InvalidInput
+        );
+        assert_eq!(reopened.list().len(), 1);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn queued_audio_follows_history_encryption() {
+        let dir = std::env::temp_dir().join(format!("voice-pending-{}", uuid::Uuid::new_v4()));
+        let store = PendingTranscriptionStore::new_in_dir(&dir);
+        let cipher = HistoryCipher::generate();
+        let plain = store
+            .enqueue(b"RIFF-plain", None, "Network error: offline", None)
+            .expect("recording should queue");
+        let sealed = store
+            .enqueue(
+                b"RIFF-sealed",
+                None,
+                "Network error: offline",
+                Some(&cipher),
+            )
+            .expect("recording should queue");
+        let wav_file = |id: &str| {
+            std::fs::read(dir.join("pending_transcriptions").join(format!("{id}.wav")))
+                .expect("queued audio should exist")
+        };
+        assert!(encryption::is_encrypted(&wav_file(&sealed.id)));
+        assert!(store.load_wav(&sealed.id, None).is_err());
+        assert_eq!(
+            store
+                .load_wav(&sealed.id, Some(&cipher))
+                .expect("audio should decrypt"),
+            b"RIFF-sealed"
+        );
+
+        store
+            .set_encryption(Some(&cipher), Some(&cipher))
+            .expect("queued audio should be encrypted");
+        assert!(encryption::is_encrypted(&wav_file(&plain.id)));
+
+        store
+            .set_encryption(Some(&cipher), None)
+            .expect("queued audio should be decrypted");
+        assert_eq!(wav_file(&plain.id), b"RIFF-plain");
+        assert_eq!(wav_file(&sealed.id), b"RIFF-sealed");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn any_http_answer_counts_as_reachable() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("HEAD", "/")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        assert!(is_reachable(&server.url()).await);
+        mock.assert_async().await;
+        assert!(!is_reachable("http://127.0.0.1:9").await);
+    }
+}
@@ -1,9 +1,22 @@
-use serde::{Deserialize, Serialize};
-use tracing::debug;
+use std::{thread, time::Duration};
 
-const MICROPHONE_SETTINGS_URL: &str =
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "tauri")]
+use tauri::{AppHandle, Emitter, Runtime};
+use tracing::{debug, info, warn};
+
+pub const EVENT_PERMISSIONS_CHANGED: &str = "voice://permissions-changed";
+// Sent once per permission that flipped, alongside the full snapshot above.
+pub const EVENT_PERMISSION_CHANGED: &str = "voice://permission-changed";
+// macOS posts no notification for either permission, so the watcher polls.
+const PERMISSION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+// Upper bound for `wait_for_permission`, so a forgotten wait cannot keep a
+// task alive for the rest of the session.
+const MAX_PERMISSION_WAIT: Duration = Duration::from_secs(10 * 60);
+pub const MICROPHONE_SETTINGS_URL: &str =
     "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone";
-const ACCESSIBILITY_SETTINGS_URL: &str =
+pub const ACCESSIBILITY_SETTINGS_URL: &str =
     "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility";
 
 const AV_AUTHORIZATION_STATUS_NOT_DETERMINED: i64 = 0;
@@ -11,7 +24,7 @@ const AV_AUTHORIZATION_STATUS_RESTRICTED: i64 = 1;
 const AV_AUTHORIZATION_STATUS_DENIED: i64 = 2;
 const AV_AUTHORIZATION_STATUS_AUTHORIZED: i64 = 3;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PermissionState {
     NotDetermined,
@@ -19,14 +32,14 @@ pub enum PermissionState {
     Denied,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PermissionType {
     Microphone,
     Accessibility,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PermissionSnapshot {
     pub microphone: PermissionState,
@@ -43,6 +56,47 @@ impl PermissionSnapshot {
                 && accessibility == PermissionState::Granted,
         }
     }
+
+    pub fn state(&self, permission_type: PermissionType) -> PermissionState {
+        match permission_type {
+            PermissionType::Microphone => self.microphone,
+            PermissionType::Accessibility => self.accessibility,
+        }
+    }
+
+    fn changes_since(&self, previous: &PermissionSnapshot) -> Vec<PermissionChangedEvent> {
+        [PermissionType::Microphone, PermissionType::Accessibility]
+            .into_iter()
+            .filter(|permission| self.state(*permission) != previous.state(*permission))
+            .map(|permission| PermissionChangedEvent {
+                permission,
+                previous: previous.state(permission),
+                state: self.state(permission),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionChangedEvent {
+    pub permission: PermissionType,
+    pub previous: PermissionState,
+    pub state: PermissionState,
+}
+
+// Reports a snapshot only when it differs from the previous one; the first
+// observation is the baseline the UI already fetched.
+#[derive(Debug, Default)]
+struct PermissionChangeTracker {
+    last: Option<PermissionSnapshot>,
+}
+
+impl PermissionChangeTracker {
+    fn observe(&mut self, snapshot: PermissionSnapshot) -> Option<PermissionSnapshot> {
+        let previous = self.last.replace(snapshot)?;
+        (previous != snapshot).then_some(snapshot)
+    }
 }
 
 #[derive(Debug, Default)]
@@ -60,6 +114,63 @@ impl PermissionService {
         PermissionSnapshot::new(microphone, accessibility)
     }
 
+    // Lets the settings and onboarding screens update as soon as the user
+    // flips a toggle in System Settings.
+    #[cfg(feature = "tauri")]
+    pub fn start_watching<R: Runtime>(&self, app: AppHandle<R>) {
+        let mut tracker = PermissionChangeTracker::default();
+        let spawn_result = thread::Builder::new()
+            .name("permission-watcher".to_string())
+            .spawn(move || loop {
+                let previous = tracker.last;
+                if let Some(snapshot) = tracker.observe(current_snapshot()) {
+                    info!(
+                        microphone = ?snapshot.microphone,
+                        accessibility = ?snapshot.accessibility,
+                        "permissions changed"
+                    );
+                    if let Err(error) = app.emit(EVENT_PERMISSIONS_CHANGED, snapshot) {
+                        warn!(%error, "failed to emit permissions changed event");
+                    }
+                    let changes = previous
+                        .map(|previous| snapshot.changes_since(&previous))
+                        .unwrap_or_default();
+                    for change in changes {
+                        if let Err(error) = app.emit(EVENT_PERMISSION_CHANGED, change) {
+                            warn!(%error, "failed to emit permission changed event");
+                        }
+                    }
+                }
+                thread::sleep(PERMISSION_POLL_INTERVAL);
+            });
+
+        match spawn_result {
+            Ok(_) => info!("permission watcher started"),
+            Err(error) => warn!(%error, "failed to start permission watcher"),
+        }
+    }
+
+    // Resolves as soon as the permission is granted, or with its state at the
+    // time `timeout` runs out, so onboarding can move on without the user
+    // having to check again.
+    pub async fn wait_for_permission(
+        &self,
+        permission_type: PermissionType,
+        timeout: Duration,
+    ) -> PermissionState {
+        let deadline = tokio::time::Instant::now() + timeout.min(MAX_PERMISSION_WAIT);
+        debug!(?permission_type, ?timeout, "waiting for permission");
+        loop {
+            let state = current_snapshot().state(permission_type);
+            let now = tokio::time::Instant::now();
+            if state == PermissionState::Granted || now >= deadline {
+                info!(?permission_type, ?state, "permission wait finished");
+                return state;
+            }
+            tokio::time::sleep((deadline - now).min(PERMISSION_POLL_INTERVAL)).await;
+        }
+    }
+
     pub fn microphone_permission(&self) -> PermissionState {
         debug!("microphone permission check requested");
 
@@ -142,6 +253,21 @@ impl PermissionService {
     }
 }
 
+// Same as `check_permissions` without the per-call debug logging, which would
+// flood the log at the watcher's poll rate.
+#[cfg(target_os = "macos")]
+fn current_snapshot() -> PermissionSnapshot {
+    PermissionSnapshot::new(
+        macos::microphone_permission(),
+        macos::accessibility_permission(),
+    )
+}
+
+#[cfg(not(target_os = "macos"))]
+fn current_snapshot() -> PermissionSnapshot {
+    PermissionSnapshot::new(PermissionState::Granted, PermissionState::Granted)
+}
+
 pub fn map_microphone_authorization_status(status: i64) -> PermissionState {
     match status {
         AV_AUTHORIZATION_STATUS_AUTHORIZED => PermissionState::Granted,
@@ -290,7 +416,44 @@ mod macos {
 
 #[cfg(test)]
 mod tests {
-    use super::{map_microphone_authorization_status, PermissionSnapshot, PermissionState};
+    use super::{
+        map_microphone_authorization_status, PermissionChangeTracker, PermissionChangedEvent,
+        PermissionSnapshot, PermissionState, PermissionType,
+    };
+
+    #[test]
+    fn change_tracker_reports_only_transitions_after_the_baseline() {
+        let denied = PermissionSnapshot::new(PermissionState::Granted, PermissionState::Denied);
+        let granted = PermissionSnapshot::new(PermissionState::Granted, PermissionState::Granted);
+        let mut tracker = PermissionChangeTracker::default();
+
+        assert_eq!(tracker.observe(denied), None);
+        assert_eq!(tracker.observe(denied), None);
+        assert_eq!(tracker.observe(granted), Some(granted));
+        assert_eq!(tracker.observe(granted), None);
+        assert_eq!(tracker.observe(denied), Some(denied));
+    }
+
+    #[test]
+    fn snapshot_changes_list_each_permission_that_flipped() {
+        let before =
+            PermissionSnapshot::new(PermissionState::NotDetermined, PermissionState::Denied);
+        let mic_granted =
+            PermissionSnapshot::new(PermissionState::Granted, PermissionState::Denied);
+        let all_granted =
+            PermissionSnapshot::new(PermissionState::Granted, PermissionState::Granted);
+
+        assert_eq!(
+            mic_granted.changes_since(&before),
+            vec![PermissionChangedEvent {
+                permission: PermissionType::Microphone,
+                previous: PermissionState::NotDetermined,
+                state: PermissionState::Granted,
+            }]
+        );
+        assert_eq!(all_granted.changes_since(&before).len(), 2);
+        assert!(all_granted.changes_since(&all_granted).is_empty());
+    }
 
     #[test]
     fn maps_microphone_status_to_not_determined() {
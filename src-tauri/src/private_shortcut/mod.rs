@@ -0,0 +1,131 @@
+use std::sync::{Arc, Mutex};
+
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+use tracing::{debug, info, warn};
+
+use crate::{hotkey_service::HotkeyTrigger, settings_store::VoiceSettings};
+
+// Global shortcut that starts a private dictation. It stays registered
+// whenever one is set, since it has to work before any session exists.
+#[derive(Debug, Clone, Default)]
+pub struct PrivateShortcutService {
+    registered: Arc<Mutex<Option<String>>>,
+}
+
+impl PrivateShortcutService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Same constraint as `OverlayKeyService::set_active`: never call this from
+    // inside a shortcut handler.
+    pub fn apply<R, F>(&self, app: &AppHandle<R>, settings: &VoiceSettings, on_trigger: F)
+    where
+        R: Runtime,
+        F: Fn(&AppHandle<R>, HotkeyTrigger) + Send + Sync + 'static,
+    {
+        let Ok(mut registered) = self.registered.lock() else {
+            warn!("private shortcut registration lock poisoned");
+            return;
+        };
+
+        let key = resolve_key(settings);
+        if *registered == key {
+            return;
+        }
+        if let Some(previous) = registered.take() {
+            if let Err(error) = app.global_shortcut().unregister(previous.as_str()) {
+                warn!(key = %previous, %error, "failed to unregister private shortcut");
+            }
+        }
+        let Some(key) = key else {
+            return;
+        };
+
+        let result =
+            app.global_shortcut()
+                .on_shortcut(key.as_str(), move |app, _shortcut, event| {
+                    let trigger = HotkeyTrigger::from(event.state);
+                    info!(?trigger, "private shortcut triggered");
+                    on_trigger(app, trigger);
+                });
+        match result {
+            Ok(()) => {
+                debug!(%key, "registered private shortcut");
+                *registered = Some(key);
+            }
+            Err(error) => warn!(%key, %error, "failed to register private shortcut"),
+        }
+    }
+}
+
+// No key when unset, when it fails to parse, or when it collides with the
+// recording hotkey or the retro shortcut.
+fn resolve_key(settings: &VoiceSettings) -> Option<String> {
+    let key = settings.private_shortcut.as_deref()?;
+    let id = match key.parse::<Shortcut>() {
+        Ok(shortcut) => shortcut.id(),
+        Err(error) => {
+            warn!(key, %error, "ignoring invalid private shortcut");
+            return None;
+        }
+    };
+    let taken = [
+        Some(settings.hotkey_shortcut.as_str()),
+        settings.retro_shortcut.as_deref(),
+    ];
+    if taken
+        .into_iter()
+        .flatten()
+        .filter_map(|taken| taken.parse::<Shortcut>().ok())
+        .any(|shortcut| shortcut.id() == id)
+    {
+        warn!(
+            key,
+            "ignoring private shortcut that matches another shortcut"
+        );
+        return None;
+    }
+    Some(key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_key;
+    use crate::settings_store::VoiceSettings;
+
+    #[test]
+    fn resolves_key_only_when_set_valid_and_free() {
+        let settings = VoiceSettings {
+            private_shortcut: Some("Alt+Shift+KeyP".to_string()),
+            retro_shortcut: Some("Alt+Shift+KeyR".to_string()),
+            ..VoiceSettings::default()
+        };
+        assert_eq!(resolve_key(&settings).as_deref(), Some("Alt+Shift+KeyP"));
+
+        let unset = VoiceSettings {
+            private_shortcut: None,
+            ..settings.clone()
+        };
+        assert_eq!(resolve_key(&unset), None);
+
+        let colliding_hotkey = VoiceSettings {
+            private_shortcut: Some(settings.hotkey_shortcut.clone()),
+            ..settings.clone()
+        };
+        assert_eq!(resolve_key(&colliding_hotkey), None);
+
+        let colliding_retro = VoiceSettings {
+            private_shortcut: settings.retro_shortcut.clone(),
+            ..settings.clone()
+        };
+        assert_eq!(resolve_key(&colliding_retro), None);
+
+        let invalid = VoiceSettings {
+            private_shortcut: Some("not a key".to_string()),
+            ..settings
+        };
+        assert_eq!(resolve_key(&invalid), None);
+    }
+}
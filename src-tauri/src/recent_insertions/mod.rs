@@ -0,0 +1,98 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+use tracing::warn;
+
+use crate::{focused_app_watcher::FocusedApp, settings_store::MAX_RECENT_TEXT_CONTEXT_WORDS};
+
+const MAX_RECENT_WORDS: usize = MAX_RECENT_TEXT_CONTEXT_WORDS as usize;
+
+// The words last inserted into each app, used as context for the next
+// dictation into it. Kept in memory only, so nothing outlives the process.
+#[derive(Debug, Default)]
+pub struct RecentInsertions {
+    words_by_app: Mutex<HashMap<String, VecDeque<String>>>,
+}
+
+impl RecentInsertions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, app: &FocusedApp, text: &str) {
+        let Ok(mut words_by_app) = self.words_by_app.lock() else {
+            warn!("recent insertions lock poisoned; skipping");
+            return;
+        };
+        let words = words_by_app.entry(app_key(app)).or_default();
+        words.extend(text.split_whitespace().map(str::to_string));
+        let overflow = words.len().saturating_sub(MAX_RECENT_WORDS);
+        words.drain(..overflow);
+    }
+
+    pub fn recent_text(&self, app: &FocusedApp, word_count: usize) -> Option<String> {
+        let words_by_app = self.words_by_app.lock().ok()?;
+        let words = words_by_app.get(&app_key(app))?;
+        let skip = words.len().saturating_sub(word_count);
+        let text = words
+            .iter()
+            .skip(skip)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ");
+        (!text.is_empty()).then_some(text)
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut words_by_app) = self.words_by_app.lock() {
+            words_by_app.clear();
+        }
+    }
+}
+
+// Apps are told apart by bundle id, or by name for apps without one.
+fn app_key(app: &FocusedApp) -> String {
+    app.bundle_id.clone().unwrap_or_else(|| app.name.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(bundle_id: &str) -> FocusedApp {
+        FocusedApp {
+            bundle_id: Some(bundle_id.to_string()),
+            name: bundle_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn keeps_the_last_words_per_app() {
+        let recent = RecentInsertions::new();
+        let mail = app("com.apple.mail");
+        recent.record(&mail, "Hi Dana,\nthe Kubernetes rollout");
+        recent.record(&mail, " finished  early. ");
+        recent.record(&app("com.tinyspeck.slackmacgap"), "lunch?");
+
+        assert_eq!(
+            recent.recent_text(&mail, 3).as_deref(),
+            Some("rollout finished early.")
+        );
+        assert_eq!(
+            recent.recent_text(&mail, 50).as_deref(),
+            Some("Hi Dana, the Kubernetes rollout finished early.")
+        );
+        assert_eq!(recent.recent_text(&app("com.apple.notes"), 50), None);
+
+        recent.record(&mail, &"word ".repeat(MAX_RECENT_WORDS));
+        assert_eq!(
+            recent.recent_text(&mail, MAX_RECENT_WORDS * 2),
+            Some(vec!["word"; MAX_RECENT_WORDS].join(" "))
+        );
+
+        recent.clear();
+        assert_eq!(recent.recent_text(&mail, 50), None);
+    }
+}
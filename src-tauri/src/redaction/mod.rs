@@ -0,0 +1,270 @@
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::history_store::HistoryEntry;
+
+const MASK: &str = "[REDACTED]";
+const EMAIL_PATTERN: &str = r"(?i)\b[A-Z0-9._%+-]+@[A-Z0-9.-]+\.[A-Z]{2,}\b";
+const SSN_PATTERN: &str = r"\b\d{3}[- ]\d{2}[- ]\d{4}\b";
+// Candidates are confirmed with a Luhn check so order numbers and phone
+// numbers of the same length are left alone.
+const CREDIT_CARD_PATTERN: &str = r"\b\d(?:[ -]?\d){12,18}\b";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionEntity {
+    CreditCard,
+    Email,
+    Ssn,
+    // Matches the rule's own `pattern`.
+    Custom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionAction {
+    Mask,
+    Drop,
+    // Leaves the text as is and only reports the match.
+    Warn,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RedactionRule {
+    pub name: String,
+    pub entity: RedactionEntity,
+    #[serde(default)]
+    pub pattern: Option<String>,
+    pub action: RedactionAction,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Redacted {
+    pub text: String,
+    pub changed: bool,
+    // Names of `warn` rules that matched.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    name: String,
+    entity: RedactionEntity,
+    regex: Regex,
+    action: RedactionAction,
+}
+
+#[derive(Debug, Default)]
+pub struct RedactionEngine {
+    rules: Vec<CompiledRule>,
+}
+
+impl RedactionEngine {
+    pub fn new(rules: &[RedactionRule]) -> Result<Self, String> {
+        let rules = rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .map(|rule| {
+                let pattern = match rule.entity {
+                    RedactionEntity::CreditCard => CREDIT_CARD_PATTERN,
+                    RedactionEntity::Email => EMAIL_PATTERN,
+                    RedactionEntity::Ssn => SSN_PATTERN,
+                    RedactionEntity::Custom => rule.pattern.as_deref().unwrap_or_default(),
+                };
+                let regex = Regex::new(pattern).map_err(|error| {
+                    format!(
+                        "Invalid pattern for redaction rule `{}`: {error}",
+                        rule.name
+                    )
+                })?;
+                Ok(CompiledRule {
+                    name: rule.name.clone(),
+                    entity: rule.entity,
+                    regex,
+                    action: rule.action,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn apply(&self, text: &str) -> Redacted {
+        let mut redacted = Redacted {
+            text: text.to_string(),
+            ..Redacted::default()
+        };
+        for rule in &self.rules {
+            let is_match = |candidate: &str| {
+                rule.entity != RedactionEntity::CreditCard || passes_luhn(candidate)
+            };
+            if !rule
+                .regex
+                .find_iter(&redacted.text)
+                .any(|found| is_match(found.as_str()))
+            {
+                continue;
+            }
+
+            let replacement = match rule.action {
+                RedactionAction::Warn => {
+                    redacted.warnings.push(rule.name.clone());
+                    continue;
+                }
+                RedactionAction::Mask => MASK,
+                RedactionAction::Drop => "",
+            };
+            redacted.text = rule
+                .regex
+                .replace_all(&redacted.text, |captures: &regex::Captures| {
+                    let found = &captures[0];
+                    if is_match(found) {
+                        replacement.to_string()
+                    } else {
+                        found.to_string()
+                    }
+                })
+                .into_owned();
+            redacted.changed = true;
+        }
+        if redacted.changed {
+            redacted.text = redacted
+                .text
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+        redacted
+    }
+
+    // Segment and word timings carry the raw text, so they are dropped
+    // whenever the transcript itself had to change.
+    pub fn redact_history_entry(&self, mut entry: HistoryEntry) -> (HistoryEntry, Vec<String>) {
+        let redacted = self.apply(&entry.text);
+        let mut warnings = redacted.warnings;
+        if redacted.changed {
+            entry.text = redacted.text;
+            entry.segments.clear();
+            entry.words.clear();
+        }
+        if let Some(translation) = entry.translation.as_mut() {
+            let redacted = self.apply(&translation.text);
+            translation.text = redacted.text;
+            for name in redacted.warnings {
+                if !warnings.contains(&name) {
+                    warnings.push(name);
+                }
+            }
+        }
+        (entry, warnings)
+    }
+}
+
+// Rules are checked when saved so a bad pattern is reported to the user
+// instead of silently disabling redaction later.
+pub fn validate_rules(rules: Vec<RedactionRule>) -> Result<Vec<RedactionRule>, String> {
+    let rules = rules
+        .into_iter()
+        .map(|mut rule| {
+            rule.name = rule.name.trim().to_string();
+            if rule.name.is_empty() {
+                return Err("Redaction rules need a name".to_string());
+            }
+            rule.pattern = rule
+                .pattern
+                .map(|pattern| pattern.trim().to_string())
+                .filter(|pattern| !pattern.is_empty());
+            if rule.entity == RedactionEntity::Custom && rule.pattern.is_none() {
+                return Err(format!(
+                    "Custom redaction rule `{}` needs a pattern",
+                    rule.name
+                ));
+            }
+            Ok(rule)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    RedactionEngine::new(&rules)?;
+    Ok(rules)
+}
+
+fn passes_luhn(candidate: &str) -> bool {
+    let digits = candidate
+        .chars()
+        .filter_map(|character| character.to_digit(10))
+        .collect::<Vec<_>>();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| match (index % 2 == 1, digit * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => *digit,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_rules, RedactionAction, RedactionEngine, RedactionEntity, RedactionRule};
+
+    fn rule(name: &str, entity: RedactionEntity, action: RedactionAction) -> RedactionRule {
+        RedactionRule {
+            name: name.to_string(),
+            entity,
+            pattern: None,
+            action,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn masks_drops_and_warns_per_rule() {
+        let engine = RedactionEngine::new(&[
+            rule("cards", RedactionEntity::CreditCard, RedactionAction::Mask),
+            rule("emails", RedactionEntity::Email, RedactionAction::Drop),
+            rule("ssn", RedactionEntity::Ssn, RedactionAction::Warn),
+            RedactionRule {
+                pattern: Some(r"(?i)\bproject falcon\b".to_string()),
+                ..rule("codename", RedactionEntity::Custom, RedactionAction::Mask)
+            },
+        ])
+        .expect("rules should compile");
+
+        let redacted = engine.apply(
+            "Card 4111 1111 1111 1111, order 1234567890123, mail jo@example.com about Project Falcon, ssn 123-45-6789",
+        );
+        assert_eq!(
+            redacted.text,
+            "Card [REDACTED], order 1234567890123, mail about [REDACTED], ssn 123-45-6789"
+        );
+        assert!(redacted.changed);
+        assert_eq!(redacted.warnings, vec!["ssn".to_string()]);
+
+        let untouched = engine.apply("nothing sensitive here");
+        assert!(!untouched.changed);
+        assert!(untouched.warnings.is_empty());
+
+        assert!(validate_rules(vec![RedactionRule {
+            pattern: Some("(".to_string()),
+            ..rule("broken", RedactionEntity::Custom, RedactionAction::Mask)
+        }])
+        .is_err());
+        assert!(validate_rules(vec![rule(
+            " ",
+            RedactionEntity::Email,
+            RedactionAction::Mask
+        )])
+        .is_err());
+    }
+}
@@ -0,0 +1,127 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+// Gets a new entry with every version bump.
+const BUNDLED_RELEASE_NOTES: &str = include_str!("release_notes.json");
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingMigrationNote {
+    pub setting: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseNote {
+    pub version: String,
+    #[serde(default)]
+    pub highlights: Vec<String>,
+    #[serde(default)]
+    pub settings_migrations: Vec<SettingMigrationNote>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseNotesReport {
+    pub current_version: String,
+    pub since_version: Option<String>,
+    pub releases: Vec<ReleaseNote>,
+}
+
+// Releases newer than `since_version` (all of them when `None`) up to and
+// including `current_version`, newest first.
+pub fn release_notes_since(
+    since_version: Option<&str>,
+    current_version: &str,
+) -> Result<ReleaseNotesReport, String> {
+    let current = parse_version(current_version)
+        .ok_or_else(|| format!("Invalid app version `{current_version}`"))?;
+    let since = since_version
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| parse_version(value).ok_or_else(|| format!("Invalid version `{value}`")))
+        .transpose()?;
+
+    let mut releases = bundled_release_notes()?
+        .into_iter()
+        .filter_map(|note| {
+            let version = parse_version(&note.version)?;
+            let is_newer = since.is_none_or(|since| version > since);
+            (is_newer && version <= current).then_some((version, note))
+        })
+        .collect::<Vec<_>>();
+    releases.sort_by(|(left, _), (right, _)| right.cmp(left));
+
+    debug!(
+        since_version = ?since_version,
+        current_version,
+        releases = releases.len(),
+        "resolved release notes"
+    );
+    Ok(ReleaseNotesReport {
+        current_version: current_version.to_string(),
+        since_version: since_version.map(str::to_string),
+        releases: releases.into_iter().map(|(_, note)| note).collect(),
+    })
+}
+
+fn bundled_release_notes() -> Result<Vec<ReleaseNote>, String> {
+    serde_json::from_str(BUNDLED_RELEASE_NOTES)
+        .map_err(|error| format!("Bundled release notes are malformed: {error}"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+
+// Accepts `1.2.3`, `v1.2` and `1.2.3-beta.1`; pre-release tags are ignored.
+fn parse_version(value: &str) -> Option<Version> {
+    let value = value.trim().trim_start_matches('v');
+    let core = value.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let version = Version {
+        major: parts.next()??,
+        minor: parts.next().unwrap_or(Some(0))?,
+        patch: parts.next().unwrap_or(Some(0))?,
+    };
+    parts.next().is_none().then_some(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bundled_release_notes, parse_version, release_notes_since};
+
+    #[test]
+    fn bundled_notes_parse_and_cover_the_current_version() {
+        let notes = bundled_release_notes().expect("bundled notes should parse");
+        assert!(notes
+            .iter()
+            .all(|note| parse_version(&note.version).is_some()));
+
+        let report = release_notes_since(None, env!("CARGO_PKG_VERSION"))
+            .expect("release notes should resolve");
+        assert_eq!(
+            report.releases.first().map(|note| note.version.as_str()),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+    }
+
+    #[test]
+    fn only_returns_releases_after_the_since_version() {
+        let current = env!("CARGO_PKG_VERSION");
+
+        let up_to_date = release_notes_since(Some(current), current).expect("should resolve");
+        assert!(up_to_date.releases.is_empty());
+
+        let from_old = release_notes_since(Some("v0.0.1"), current).expect("should resolve");
+        assert!(!from_old.releases.is_empty());
+
+        assert!(release_notes_since(Some("not-a-version"), current).is_err());
+        assert!(parse_version("1.2.3-beta.1") > parse_version("1.2"));
+    }
+}
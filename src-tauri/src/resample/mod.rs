@@ -0,0 +1,138 @@
+use rubato::{FftFixedInOut, Resampler};
+use tracing::{debug, info, warn};
+
+use crate::audio_capture_service::pcm16_to_wav_bytes;
+use crate::transcription::chunked::parse_pcm16_mono_wav;
+
+const CHUNK_FRAMES: usize = 1_024;
+
+// Best-effort: returns `None` whenever the recording should upload unchanged.
+pub fn resample_wav_for_upload(wav_bytes: &[u8], target_sample_rate_hz: u32) -> Option<Vec<u8>> {
+    let wav = match parse_pcm16_mono_wav(wav_bytes) {
+        Ok(wav) => wav,
+        Err(error) => {
+            warn!(%error, "skipping resampling for unparseable recording");
+            return None;
+        }
+    };
+    if wav.sample_rate_hz == target_sample_rate_hz || wav.samples.is_empty() {
+        debug!(
+            sample_rate_hz = wav.sample_rate_hz,
+            "recording already at target sample rate"
+        );
+        return None;
+    }
+
+    let resampled = match resample_pcm16(&wav.samples, wav.sample_rate_hz, target_sample_rate_hz) {
+        Ok(resampled) => resampled,
+        Err(error) => {
+            warn!(%error, "failed to resample recording; uploading original audio");
+            return None;
+        }
+    };
+    match pcm16_to_wav_bytes(&resampled, target_sample_rate_hz, 1) {
+        Ok(resampled_bytes) => {
+            info!(
+                from_sample_rate_hz = wav.sample_rate_hz,
+                to_sample_rate_hz = target_sample_rate_hz,
+                original_bytes = wav_bytes.len(),
+                resampled_bytes = resampled_bytes.len(),
+                "resampled recording for upload"
+            );
+            Some(resampled_bytes)
+        }
+        Err(error) => {
+            warn!(%error, "failed to encode resampled recording; uploading original audio");
+            None
+        }
+    }
+}
+
+// Band-limited FFT resampling, so downsampling does not fold high-frequency
+// noise back into the speech band the way linear interpolation does.
+pub(crate) fn resample_pcm16(
+    samples: &[i16],
+    from_rate_hz: u32,
+    to_rate_hz: u32,
+) -> Result<Vec<i16>, String> {
+    let mut resampler =
+        FftFixedInOut::<f32>::new(from_rate_hz as usize, to_rate_hz as usize, CHUNK_FRAMES, 1)
+            .map_err(|error| format!("Failed to create resampler: {error}"))?;
+    let input = samples
+        .iter()
+        .map(|sample| f32::from(*sample) / f32::from(i16::MAX))
+        .collect::<Vec<_>>();
+    let expected_len =
+        (samples.len() as u64 * u64::from(to_rate_hz) / u64::from(from_rate_hz)) as usize;
+    let delay = resampler.output_delay();
+
+    let mut output = Vec::with_capacity(expected_len + delay + resampler.output_frames_max());
+    let mut position = 0;
+    // The tail is zero-padded until the resampler's delay has been flushed.
+    while output.len() < expected_len + delay {
+        let frames = resampler.input_frames_next();
+        let mut chunk =
+            input[position.min(input.len())..(position + frames).min(input.len())].to_vec();
+        chunk.resize(frames, 0.0);
+        let processed = resampler
+            .process(&[chunk], None)
+            .map_err(|error| format!("Failed to resample audio: {error}"))?;
+        output.extend_from_slice(&processed[0]);
+        position += frames;
+    }
+
+    Ok(output[delay..delay + expected_len]
+        .iter()
+        .map(|sample| (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)).round() as i16)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use super::resample_wav_for_upload;
+    use crate::audio_capture_service::pcm16_to_wav_bytes;
+    use crate::transcription::chunked::parse_pcm16_mono_wav;
+
+    fn sine_wav(frequency_hz: f32, sample_rate_hz: u32, len: usize) -> Vec<u8> {
+        let samples = (0..len)
+            .map(|index| {
+                ((TAU * frequency_hz * index as f32 / sample_rate_hz as f32).sin() * 16_000.0)
+                    as i16
+            })
+            .collect::<Vec<_>>();
+        pcm16_to_wav_bytes(&samples, sample_rate_hz, 1).expect("wav should encode")
+    }
+
+    fn rms(samples: &[i16]) -> f32 {
+        (samples
+            .iter()
+            .map(|sample| f32::from(*sample).powi(2))
+            .sum::<f32>()
+            / samples.len() as f32)
+            .sqrt()
+    }
+
+    #[test]
+    fn downsamples_to_target_rate_and_filters_content_above_nyquist() {
+        let speech = sine_wav(440.0, 48_000, 48_000);
+        let resampled = parse_pcm16_mono_wav(
+            &resample_wav_for_upload(&speech, 16_000).expect("recording should resample"),
+        )
+        .expect("resampled wav should parse");
+        assert_eq!(resampled.sample_rate_hz, 16_000);
+        assert_eq!(resampled.samples.len(), 16_000);
+        let steady = &resampled.samples[1_000..15_000];
+        assert!((rms(steady) - 16_000.0 / 2.0_f32.sqrt()).abs() < 200.0);
+
+        let hiss = sine_wav(12_000.0, 48_000, 48_000);
+        let resampled = parse_pcm16_mono_wav(
+            &resample_wav_for_upload(&hiss, 16_000).expect("recording should resample"),
+        )
+        .expect("resampled wav should parse");
+        assert!(rms(&resampled.samples[1_000..15_000]) < 100.0);
+
+        assert_eq!(resample_wav_for_upload(&speech, 48_000), None);
+    }
+}
@@ -0,0 +1,126 @@
+use std::sync::{Arc, Mutex};
+
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tracing::{debug, info, warn};
+
+use crate::settings_store::VoiceSettings;
+
+// Global shortcut for "transcribe the last N seconds". Unlike the overlay
+// keys it stays registered while the retro buffer is on, since there is no
+// recording session to scope it to.
+#[derive(Debug, Clone, Default)]
+pub struct RetroShortcutService {
+    registered: Arc<Mutex<Option<String>>>,
+}
+
+impl RetroShortcutService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Same constraint as `OverlayKeyService::set_active`: never call this from
+    // inside a shortcut handler.
+    pub fn apply<R, F>(&self, app: &AppHandle<R>, settings: &VoiceSettings, on_pressed: F)
+    where
+        R: Runtime,
+        F: Fn(&AppHandle<R>) + Send + Sync + 'static,
+    {
+        let Ok(mut registered) = self.registered.lock() else {
+            warn!("retro shortcut registration lock poisoned");
+            return;
+        };
+
+        let key = resolve_key(settings);
+        if *registered == key {
+            return;
+        }
+        if let Some(previous) = registered.take() {
+            if let Err(error) = app.global_shortcut().unregister(previous.as_str()) {
+                warn!(key = %previous, %error, "failed to unregister retro shortcut");
+            }
+        }
+        let Some(key) = key else {
+            return;
+        };
+
+        let result =
+            app.global_shortcut()
+                .on_shortcut(key.as_str(), move |app, _shortcut, event| {
+                    if event.state == ShortcutState::Pressed {
+                        info!("retro shortcut pressed");
+                        on_pressed(app);
+                    }
+                });
+        match result {
+            Ok(()) => {
+                debug!(%key, "registered retro shortcut");
+                *registered = Some(key);
+            }
+            Err(error) => warn!(%key, %error, "failed to register retro shortcut"),
+        }
+    }
+}
+
+// No key while the buffer is off, and none when it fails to parse or collides
+// with the recording hotkey.
+fn resolve_key(settings: &VoiceSettings) -> Option<String> {
+    if settings.retro_buffer_secs == 0 {
+        return None;
+    }
+    let key = settings.retro_shortcut.as_deref()?;
+    let id = match key.parse::<Shortcut>() {
+        Ok(shortcut) => shortcut.id(),
+        Err(error) => {
+            warn!(key, %error, "ignoring invalid retro shortcut");
+            return None;
+        }
+    };
+    let hotkey_id = settings
+        .hotkey_shortcut
+        .parse::<Shortcut>()
+        .ok()
+        .map(|shortcut| shortcut.id());
+    if Some(id) == hotkey_id {
+        warn!(
+            key,
+            "ignoring retro shortcut that matches the recording hotkey"
+        );
+        return None;
+    }
+    Some(key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_key;
+    use crate::settings_store::VoiceSettings;
+
+    #[test]
+    fn resolves_key_only_when_buffer_is_on_and_key_is_free() {
+        let settings = VoiceSettings {
+            retro_buffer_secs: 60,
+            retro_shortcut: Some("Alt+Shift+KeyR".to_string()),
+            ..VoiceSettings::default()
+        };
+        assert_eq!(resolve_key(&settings).as_deref(), Some("Alt+Shift+KeyR"));
+
+        let disabled = VoiceSettings {
+            retro_buffer_secs: 0,
+            ..settings.clone()
+        };
+        assert_eq!(resolve_key(&disabled), None);
+
+        let colliding = VoiceSettings {
+            retro_shortcut: Some(settings.hotkey_shortcut.clone()),
+            ..settings.clone()
+        };
+        assert_eq!(resolve_key(&colliding), None);
+
+        let invalid = VoiceSettings {
+            retro_shortcut: Some("not a key".to_string()),
+            ..settings
+        };
+        assert_eq!(resolve_key(&invalid), None);
+    }
+}
@@ -0,0 +1,124 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use tracing::info;
+
+use crate::audio_capture_service::RecordedAudio;
+
+pub const SELFTEST_PHRASE: &str = "Voice check: the quick brown fox jumps over the lazy dog.";
+pub const SELFTEST_LEAD_IN_MS: u64 = 400;
+pub const SELFTEST_TAIL_MS: u64 = 700;
+// Speaker-to-mic playback loses a word now and then, so a perfect match is not
+// required for the setup to count as working.
+const MIN_MATCHED_WORD_RATIO: f64 = 0.7;
+
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub expected_phrase: String,
+    pub transcript: String,
+    pub matched_words: Vec<String>,
+    pub missing_words: Vec<String>,
+    pub recorded_duration_ms: u64,
+    pub device_name: String,
+}
+
+pub fn evaluate_selftest(
+    expected_phrase: &str,
+    transcript: &str,
+    recorded: &RecordedAudio,
+) -> SelfTestReport {
+    let heard = normalized_words(transcript);
+    let (matched_words, missing_words): (Vec<_>, Vec<_>) = normalized_words(expected_phrase)
+        .into_iter()
+        .partition(|word| heard.contains(word));
+    let expected_count = matched_words.len() + missing_words.len();
+    let passed = expected_count > 0
+        && matched_words.len() as f64 / expected_count as f64 >= MIN_MATCHED_WORD_RATIO;
+
+    info!(
+        passed,
+        matched = matched_words.len(),
+        missing = missing_words.len(),
+        device = %recorded.device_name,
+        "end-to-end self-test evaluated"
+    );
+    SelfTestReport {
+        passed,
+        expected_phrase: expected_phrase.to_string(),
+        transcript: transcript.to_string(),
+        matched_words,
+        missing_words,
+        recorded_duration_ms: recorded.duration_ms,
+        device_name: recorded.device_name.clone(),
+    }
+}
+
+// Uses the system voice so no audio asset has to ship with the app.
+#[cfg(target_os = "macos")]
+pub fn speak_phrase(phrase: &str) -> Result<(), String> {
+    info!("speaking self-test phrase");
+    let status = std::process::Command::new("say")
+        .arg(phrase)
+        .status()
+        .map_err(|error| format!("Failed to play self-test phrase: {error}"))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to play self-test phrase: `say` exited with {status}"
+        ))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn speak_phrase(_phrase: &str) -> Result<(), String> {
+    Err("The end-to-end self-test is only supported on macOS".to_string())
+}
+
+fn normalized_words(text: &str) -> Vec<String> {
+    text.split(|character: char| !character.is_alphanumeric() && character != '\'')
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate_selftest, SELFTEST_PHRASE};
+    use crate::audio_capture_service::RecordedAudio;
+
+    fn recorded() -> RecordedAudio {
+        RecordedAudio {
+            wav_bytes: Vec::new(),
+            sample_rate_hz: 16_000,
+            channels: 1,
+            duration_ms: 4_200,
+            device_name: "MacBook Pro Microphone".to_string(),
+            device_id: "builtin".to_string(),
+            spooled_wav: None,
+        }
+    }
+
+    #[test]
+    fn passes_when_most_words_are_heard_and_reports_the_missing_ones() {
+        let report = evaluate_selftest(
+            SELFTEST_PHRASE,
+            "Voice check. The quick brown fox jumps over the dog!",
+            &recorded(),
+        );
+
+        assert!(report.passed);
+        assert_eq!(report.missing_words, vec!["lazy".to_string()]);
+        assert_eq!(report.recorded_duration_ms, 4_200);
+    }
+
+    #[test]
+    fn fails_when_the_transcript_does_not_contain_the_phrase() {
+        let report = evaluate_selftest(SELFTEST_PHRASE, "Thanks for watching.", &recorded());
+
+        assert!(!report.passed);
+        assert!(report.matched_words.is_empty());
+    }
+}
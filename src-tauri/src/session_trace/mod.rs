@@ -0,0 +1,365 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::Instant,
+};
+
+use chrono::{SecondsFormat, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, info, warn};
+
+const TRACE_DIR_NAME: &str = "session_traces";
+const MAX_TRACE_FILES: usize = 20;
+// Sessions overlap when one is still transcribing while the next records.
+const MAX_OPEN_TRACES: usize = 4;
+// Not a pipeline event; written after each stage returns.
+pub const STAGE_FINISHED_TRACE_EVENT: &str = "stage-finished";
+// Transcripts, their segments and words all carry their text under this key.
+const TRANSCRIPT_TEXT_KEY: &str = "text";
+
+// One line of a session trace. Payloads are the JSON the frontend received
+// for the event with transcript text scrubbed, so a trace reads like a
+// replay of the session.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTraceEvent {
+    pub session_id: u64,
+    pub sequence: u64,
+    pub timestamp: String,
+    // Since the session's first traced event.
+    pub elapsed_ms: u64,
+    pub event: String,
+    pub payload: Value,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StageFinished {
+    pub stage: String,
+    pub duration_ms: u64,
+    pub success: bool,
+}
+
+#[derive(Debug)]
+struct OpenTrace {
+    file_path: PathBuf,
+    started_at: Instant,
+    next_sequence: u64,
+}
+
+// Writes one JSON lines file per session while enabled. Traces can hold
+// redacted transcript text, so they are off by default and only the newest
+// few are kept.
+#[derive(Debug)]
+pub struct SessionTraceRecorder {
+    trace_dir: PathBuf,
+    enabled: AtomicBool,
+    open_traces: Mutex<BTreeMap<u64, OpenTrace>>,
+}
+
+impl SessionTraceRecorder {
+    pub fn new_in_dir(app_data_dir: &Path) -> Self {
+        Self {
+            trace_dir: app_data_dir.join(TRACE_DIR_NAME),
+            enabled: AtomicBool::new(false),
+            open_traces: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        if self.enabled.swap(enabled, Ordering::Relaxed) == enabled {
+            return;
+        }
+        if !enabled {
+            if let Ok(mut open_traces) = self.open_traces.lock() {
+                open_traces.clear();
+            }
+        }
+        info!(enabled, "session tracing toggled");
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn record<T: Serialize>(
+        &self,
+        session_id: u64,
+        event: &str,
+        payload: &T,
+    ) -> Result<(), String> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        let payload = serde_json::to_value(payload)
+            .map_err(|error| format!("Failed to serialize session trace payload: {error}"))?;
+
+        let mut open_traces = self
+            .open_traces
+            .lock()
+            .map_err(|_| "Session trace lock is poisoned".to_string())?;
+        if !open_traces.contains_key(&session_id) {
+            let file_path = self.open_trace_file(session_id)?;
+            open_traces.retain(|id, _| id + MAX_OPEN_TRACES as u64 > session_id);
+            open_traces.insert(
+                session_id,
+                OpenTrace {
+                    file_path,
+                    started_at: Instant::now(),
+                    next_sequence: 0,
+                },
+            );
+        }
+        let Some(trace) = open_traces.get_mut(&session_id) else {
+            return Ok(());
+        };
+
+        let entry = SessionTraceEvent {
+            session_id,
+            sequence: trace.next_sequence,
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            elapsed_ms: trace.started_at.elapsed().as_millis() as u64,
+            event: event.to_string(),
+            payload,
+        };
+        trace.next_sequence += 1;
+        let mut line = serde_json::to_string(&entry)
+            .map_err(|error| format!("Failed to serialize session trace event: {error}"))?;
+        line.push('\n');
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&trace.file_path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|error| format!("Failed to append to session trace: {error}"))
+    }
+
+    // The most recently started session, in the order its events happened.
+    pub fn last_trace(&self) -> Result<Vec<SessionTraceEvent>, String> {
+        let Some(file_path) = self.trace_files()?.pop() else {
+            return Ok(Vec::new());
+        };
+        let contents = fs::read_to_string(&file_path)
+            .map_err(|error| format!("Failed to read session trace: {error}"))?;
+
+        Ok(contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(
+                |line| match serde_json::from_str::<SessionTraceEvent>(line) {
+                    Ok(entry) => Some(entry),
+                    Err(error) => {
+                        warn!(%error, "skipping malformed session trace line");
+                        None
+                    }
+                },
+            )
+            .collect())
+    }
+
+    // File names start with the time the session was first traced, so they
+    // sort oldest first; session ids restart with every launch.
+    fn open_trace_file(&self, session_id: u64) -> Result<PathBuf, String> {
+        fs::create_dir_all(&self.trace_dir)
+            .map_err(|error| format!("Failed to create session trace directory: {error}"))?;
+        let file_path = self.trace_dir.join(format!(
+            "{}-session-{session_id}.jsonl",
+            Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+        ));
+
+        let trace_files = self.trace_files()?;
+        let excess = (trace_files.len() + 1).saturating_sub(MAX_TRACE_FILES);
+        for stale in trace_files.iter().take(excess) {
+            if let Err(error) = fs::remove_file(stale) {
+                warn!(%error, path = %stale.display(), "failed to remove old session trace");
+            }
+        }
+        debug!(session_id, path = %file_path.display(), "opened session trace");
+        Ok(file_path)
+    }
+
+    fn trace_files(&self) -> Result<Vec<PathBuf>, String> {
+        if !self.trace_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut files = fs::read_dir(&self.trace_dir)
+            .map_err(|error| format!("Failed to list session traces: {error}"))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "jsonl")
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        Ok(files)
+    }
+}
+
+// Rewrites every transcript text field in place; `None` from `scrub` drops
+// the text and leaves a null so the payload keeps its shape.
+pub fn scrub_transcript_text(payload: &mut Value, scrub: &dyn Fn(&str) -> Option<String>) {
+    match payload {
+        Value::Object(fields) => {
+            for (key, value) in fields.iter_mut() {
+                match value {
+                    Value::String(text) if key == TRANSCRIPT_TEXT_KEY => {
+                        *value = scrub(text).map_or(Value::Null, Value::String);
+                    }
+                    _ => scrub_transcript_text(value, scrub),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                scrub_transcript_text(item, scrub);
+            }
+        }
+        _ => {}
+    }
+}
+
+// The copy that goes into support bundles: event names and timings only,
+// keeping payloads just for stage timings.
+pub fn trace_timeline(trace: Vec<SessionTraceEvent>) -> Vec<SessionTraceEvent> {
+    trace
+        .into_iter()
+        .map(|mut entry| {
+            if entry.event != STAGE_FINISHED_TRACE_EVENT {
+                entry.payload = Value::Null;
+            }
+            entry
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{
+        scrub_transcript_text, trace_timeline, SessionTraceRecorder, StageFinished,
+        STAGE_FINISHED_TRACE_EVENT,
+    };
+
+    #[test]
+    fn records_ordered_events_per_session_only_while_enabled() {
+        let test_dir =
+            std::env::temp_dir().join(format!("voice-session-trace-{}", uuid::Uuid::new_v4()));
+        let recorder = SessionTraceRecorder::new_in_dir(&test_dir);
+        recorder
+            .record(1, "voice://status-changed", &"listening")
+            .expect("disabled recorder should ignore events");
+        assert!(recorder.last_trace().expect("no traces yet").is_empty());
+
+        recorder.set_enabled(true);
+        recorder
+            .record(1, "voice://status-changed", &"listening")
+            .expect("event should record");
+        recorder
+            .record(
+                1,
+                STAGE_FINISHED_TRACE_EVENT,
+                &StageFinished {
+                    stage: "recording_stop".to_string(),
+                    duration_ms: 12,
+                    success: true,
+                },
+            )
+            .expect("stage timing should record");
+        let first = recorder.last_trace().expect("trace should read");
+        assert_eq!(
+            first
+                .iter()
+                .map(|event| (event.sequence, event.event.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, "voice://status-changed"),
+                (1, STAGE_FINISHED_TRACE_EVENT)
+            ]
+        );
+        assert_eq!(
+            first[1].payload,
+            json!({ "stage": "recording_stop", "durationMs": 12, "success": true })
+        );
+
+        // Sessions sharing a millisecond would sort by id alone.
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        recorder
+            .record(2, "voice://transcription-delta", &"hello")
+            .expect("second session should record");
+        recorder
+            .record(1, "voice://status-changed", &"idle")
+            .expect("earlier session should keep its own trace");
+        let second = recorder.last_trace().expect("trace should read");
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].session_id, 2);
+        assert_eq!(second[0].sequence, 0);
+
+        recorder.set_enabled(false);
+        recorder
+            .record(2, "voice://status-changed", &"idle")
+            .expect("disabled recorder should ignore events");
+        assert_eq!(recorder.last_trace().expect("trace should read").len(), 1);
+
+        let _ = std::fs::remove_dir_all(test_dir);
+    }
+
+    #[test]
+    fn scrubs_transcript_text_and_strips_payloads_from_the_timeline() {
+        let mut payload = json!({
+            "text": "call 555 0100",
+            "confidence": 0.9,
+            "segments": [{ "text": "call 555 0100", "speaker": "A" }],
+            "words": [{ "text": "call", "confidence": 0.8 }]
+        });
+        scrub_transcript_text(&mut payload, &|text| {
+            Some(text.replace("555 0100", "[phone]"))
+        });
+        assert_eq!(
+            payload,
+            json!({
+                "text": "call [phone]",
+                "confidence": 0.9,
+                "segments": [{ "text": "call [phone]", "speaker": "A" }],
+                "words": [{ "text": "call", "confidence": 0.8 }]
+            })
+        );
+        scrub_transcript_text(&mut payload, &|_| None);
+        assert_eq!(payload["text"], Value::Null);
+        assert_eq!(payload["segments"][0]["text"], Value::Null);
+        assert_eq!(payload["segments"][0]["speaker"], "A");
+
+        let test_dir =
+            std::env::temp_dir().join(format!("voice-session-trace-{}", uuid::Uuid::new_v4()));
+        let recorder = SessionTraceRecorder::new_in_dir(&test_dir);
+        recorder.set_enabled(true);
+        recorder
+            .record(1, "voice://transcript-ready", &json!({ "text": "secret" }))
+            .expect("event should record");
+        recorder
+            .record(
+                1,
+                STAGE_FINISHED_TRACE_EVENT,
+                &StageFinished {
+                    stage: "transcription".to_string(),
+                    duration_ms: 40,
+                    success: true,
+                },
+            )
+            .expect("stage timing should record");
+        let timeline = trace_timeline(recorder.last_trace().expect("trace should read"));
+        assert_eq!(timeline[0].event, "voice://transcript-ready");
+        assert_eq!(timeline[0].payload, Value::Null);
+        assert_eq!(timeline[1].payload["durationMs"], 40);
+
+        let _ = std::fs::remove_dir_all(test_dir);
+    }
+}
@@ -1,38 +1,322 @@
 use std::{
+    collections::{BTreeMap, HashSet},
     fs,
     io::Write,
     path::{Path, PathBuf},
-    sync::{Mutex, RwLock},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, RwLock,
+    },
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+#[cfg(feature = "tauri")]
 use tauri::{AppHandle, Manager, Runtime};
 use tracing::{debug, info, warn};
 
+use crate::app_error::{AppError, ErrorCode};
+use crate::command_guard::check_revision;
+use crate::proxy::ProxySettings;
+use crate::redaction::{validate_rules, RedactionRule};
+use crate::text_formatting::FormattingOptions;
+use crate::transcription::{decoding::DecodingOptions, endpoint::ProviderConfig};
+use crate::word_count::{WORD_COUNT_MODE_AUTO, WORD_COUNT_MODE_WHITESPACE};
+
 pub const DEFAULT_HOTKEY_SHORTCUT: &str = "Alt+Space";
 pub const RECORDING_MODE_HOLD_TO_TALK: &str = "hold_to_talk";
 pub const RECORDING_MODE_TOGGLE: &str = "toggle";
 pub const DEFAULT_TRANSCRIPTION_PROVIDER: &str = "openai";
+pub const GEMINI_TRANSCRIPTION_PROVIDER: &str = "gemini";
 pub const TRANSCRIPTION_STYLE_CLEAN: &str = "clean";
 pub const TRANSCRIPTION_STYLE_CASUAL: &str = "casual";
 pub const TRANSCRIPTION_STYLE_VERBATIM: &str = "verbatim";
 pub const TRANSCRIPTION_STYLE_CUSTOM: &str = "custom";
 pub const DEFAULT_TRANSCRIPTION_STYLE: &str = TRANSCRIPTION_STYLE_CLEAN;
+pub const CAPTURE_SOURCE_MICROPHONE: &str = "microphone";
+// Records a loopback driver's input device, the only way system audio is
+// captured; see `audio_capture_service::CaptureSource::Loopback`.
+pub const CAPTURE_SOURCE_LOOPBACK: &str = "loopback";
+// What `loopback` was called before it was renamed.
+const LEGACY_CAPTURE_SOURCE_SYSTEM: &str = "system";
+pub const CAPTURE_SOURCE_MIXED: &str = "mixed";
+pub const AUDIO_ENCODING_WAV: &str = "wav";
+pub const AUDIO_ENCODING_FLAC: &str = "flac";
+pub const AUDIO_ENCODING_OPUS: &str = "opus";
+pub const DEFAULT_TIME_STRETCH_FACTOR_PERCENT: u32 = 125;
+const MIN_TIME_STRETCH_FACTOR_PERCENT: u32 = 120;
+const MAX_TIME_STRETCH_FACTOR_PERCENT: u32 = 150;
+pub const DEFAULT_SILENCE_TRIM_PADDING_MS: u32 = 250;
+const MAX_SILENCE_TRIM_PADDING_MS: u32 = 2_000;
+pub const DEFAULT_SILENCE_TRIM_THRESHOLD_DBFS: i32 = -45;
+const MIN_SILENCE_TRIM_THRESHOLD_DBFS: i32 = -80;
+const MAX_SILENCE_TRIM_THRESHOLD_DBFS: i32 = -20;
+const MIN_TARGET_SAMPLE_RATE_HZ: u32 = 8_000;
+const MAX_TARGET_SAMPLE_RATE_HZ: u32 = 48_000;
+// More than a handful only dilutes the re-transcribe suggestions.
+const MAX_PREFERRED_LANGUAGES: usize = 5;
+// Prompts are limited in length; past this the hints crowd out the style.
+const MAX_DICTIONARY_WORDS: usize = 100;
+pub const AUTO_INSERT_ALWAYS: &str = "always";
+pub const AUTO_INSERT_IF_FOCUSED: &str = "if_focused";
+pub const AUTO_INSERT_CLIPBOARD_ONLY: &str = "clipboard_only";
+pub const AUTO_INSERT_ASK: &str = "ask";
+pub const INPUT_TRIGGER_KIND_HID: &str = "hid";
+pub const INPUT_TRIGGER_KIND_MIDI: &str = "midi";
+pub const MICROPHONE_CHANNEL_DOWNMIX: &str = "downmix";
+pub const MICROPHONE_CHANNEL_LEFT: &str = "left";
+pub const MICROPHONE_CHANNEL_RIGHT: &str = "right";
+pub const MICROPHONE_CHANNEL_NUMBERED_PREFIX: &str = "channel_";
+pub const INSERTION_PACING_INSTANT: &str = "instant";
+pub const INSERTION_PACING_GENTLE: &str = "gentle";
+pub const INSERTION_PACING_CAUTIOUS: &str = "cautious";
+pub const INSERTION_PACING_TYPE_OUT: &str = "type_out";
+pub const INSERTION_SUFFIX_NONE: &str = "none";
+pub const INSERTION_SUFFIX_SPACE: &str = "space";
+pub const INSERTION_SUFFIX_NEWLINE: &str = "newline";
+pub const DEFAULT_TYPING_CHARS_PER_SECOND: u32 = 30;
+const MIN_TYPING_CHARS_PER_SECOND: u32 = 1;
+const MAX_TYPING_CHARS_PER_SECOND: u32 = 200;
+// Long enough for most apps to read a paste before the old clipboard returns.
+pub const DEFAULT_CLIPBOARD_RESTORE_DELAY_MS: u32 = 75;
+const MAX_CLIPBOARD_RESTORE_DELAY_MS: u32 = 5_000;
+pub const HALLUCINATION_FILTER_DISCARD: &str = "discard";
+pub const HALLUCINATION_FILTER_FLAG: &str = "flag";
+pub const HALLUCINATION_FILTER_OFF: &str = "off";
+pub const TRANSLATION_INSERT_ORIGINAL: &str = "original";
+pub const TRANSLATION_INSERT_TRANSLATION: &str = "translation";
+pub const DEFAULT_SOUND_FEEDBACK_VOLUME_PERCENT: u32 = 60;
+pub const DEFAULT_LOW_CONFIDENCE_THRESHOLD_PERCENT: u32 = 50;
+const MAX_LOW_CONFIDENCE_THRESHOLD_PERCENT: u32 = 100;
+const MAX_SOUND_FEEDBACK_VOLUME_PERCENT: u32 = 100;
+pub const DEFAULT_OVERLAY_CANCEL_KEY: &str = "Escape";
+pub const DEFAULT_OVERLAY_STOP_KEY: &str = "Enter";
+pub const DEFAULT_OVERLAY_COPY_KEY: &str = "KeyC";
+pub const OVERLAY_PLACEMENT_TOP_CENTER: &str = "top_center";
+pub const OVERLAY_PLACEMENT_BOTTOM_CENTER: &str = "bottom_center";
+pub const OVERLAY_PLACEMENT_NEAR_CURSOR: &str = "near_cursor";
+pub const OVERLAY_PLACEMENT_NEAR_CARET: &str = "near_caret";
+pub const OVERLAY_PLACEMENT_FIXED_DISPLAY: &str = "fixed_display";
+const MAX_OVERLAY_OFFSET: i32 = 10_000;
+pub const DEFAULT_MEETING_MODE_THRESHOLD_SECS: u32 = 120;
+const MIN_MEETING_MODE_THRESHOLD_SECS: u32 = 30;
+const MAX_RETRO_BUFFER_SECS: u32 = 300;
+pub const DEFAULT_RECENT_TEXT_CONTEXT_WORDS: u32 = 50;
+// Whisper only reads the last 224 tokens of a prompt, so more words than
+// this would just be cut off.
+pub const MAX_RECENT_TEXT_CONTEXT_WORDS: u32 = 200;
+pub const DEFAULT_TRANSCRIPTION_CONNECT_TIMEOUT_SECS: u32 = 10;
+pub const DEFAULT_TRANSCRIPTION_TIMEOUT_SECS: u32 = 180;
+const MIN_TRANSCRIPTION_TIMEOUT_SECS: u32 = 1;
+const MAX_TRANSCRIPTION_CONNECT_TIMEOUT_SECS: u32 = 120;
+const MAX_TRANSCRIPTION_TIMEOUT_SECS: u32 = 1_800;
+pub const DEFAULT_MIN_RECORDING_DURATION_MS: u64 = 200;
+const MAX_MIN_RECORDING_DURATION_MS: u64 = 5_000;
+pub const DEFAULT_MAX_RECORDING_DURATION_MINS: u32 = 60;
+const MAX_MAX_RECORDING_DURATION_MINS: u32 = 600;
 
 const SETTINGS_FILE_NAME: &str = "settings.json";
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+// A nudge from the computed overlay position on one display, in logical points.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct OverlayOffset {
+    pub x: i32,
+    pub y: i32,
+}
+
+// A folder whose new audio files are transcribed as they appear. The
+// transcript goes into a `.txt` next to the file, into history, or both.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct WatchFolder {
+    pub path: String,
+    pub enabled: bool,
+    pub write_text_file: bool,
+    pub save_to_history: bool,
+}
+
+impl Default for WatchFolder {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            enabled: true,
+            write_text_file: true,
+            save_to_history: true,
+        }
+    }
+}
+
+// A foot pedal or MIDI controller that starts and stops recording like the
+// hotkey. `device_id` is `vendor:product` in hex for HID devices and the port
+// name for MIDI; `control` picks one pedal or note, and any of them counts
+// when it is unset.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct InputTriggerBinding {
+    pub kind: String,
+    pub device_id: String,
+    pub control: Option<u8>,
+    pub enabled: bool,
+}
+
+impl Default for InputTriggerBinding {
+    fn default() -> Self {
+        Self {
+            kind: INPUT_TRIGGER_KIND_HID.to_string(),
+            device_id: String::new(),
+            control: None,
+            enabled: true,
+        }
+    }
+}
+
+// Vocabulary and style for one of the languages the user dictates in. The
+// dictionary is passed to the provider as spelling hints; the style replaces
+// `transcription_style` when the dictation is known to be in this language.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct LanguageProfile {
+    pub dictionary: Vec<String>,
+    pub transcription_style: Option<String>,
+}
+
+// Replaces the global insertion prefix, suffix or smart spacing for one app;
+// unset fields keep the global value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct InsertionSpacingOverride {
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+    pub smart_spacing: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(default)]
 pub struct VoiceSettings {
     pub hotkey_shortcut: String,
     pub recording_mode: String,
+    // Shorter recordings are treated as accidental taps and dropped.
+    pub min_recording_duration_ms: u64,
+    // Recordings are stopped and transcribed after this long; 0 turns the
+    // limit off.
+    pub max_recording_duration_mins: u32,
+    pub hotkey_name: Option<String>,
+    pub input_triggers: Vec<InputTriggerBinding>,
     pub microphone_id: Option<String>,
+    pub capture_source: String,
+    // Always the first of `preferred_languages`; kept for clients that only
+    // know a single language.
     pub language: Option<String>,
+    // In order of preference. A single language is passed to the provider as
+    // a hint; with several, the provider detects which one was spoken.
+    pub preferred_languages: Vec<String>,
+    // Keyed by language code.
+    pub language_profiles: BTreeMap<String, LanguageProfile>,
+    pub pin_realtime_language: bool,
     pub transcription_provider: String,
+    pub provider_config: ProviderConfig,
+    // Keyed by provider; the model picked for it in settings. A provider
+    // without an entry uses its configured default.
+    pub transcription_models: BTreeMap<String, String>,
+    pub decoding: DecodingOptions,
+    pub proxy: ProxySettings,
+    pub audio_encoding: String,
+    pub time_stretch_enabled: bool,
+    pub time_stretch_factor_percent: u32,
+    pub silence_trim_enabled: bool,
+    pub silence_trim_padding_ms: u32,
+    pub silence_trim_threshold_dbfs: i32,
+    // What happens to text transcribed from silent or noise-only audio:
+    // `discard` drops it, `flag` sends it to the review window, `off` inserts
+    // it like any other transcript.
+    pub hallucination_filter: String,
+    pub translation_enabled: bool,
+    pub translation_insert: String,
+    pub sound_feedback_enabled: bool,
+    pub sound_feedback_volume_percent: u32,
+    pub overlay_keys_enabled: bool,
+    pub overlay_cancel_key: Option<String>,
+    pub overlay_stop_key: Option<String>,
+    pub overlay_copy_key: Option<String>,
+    pub overlay_private_key: Option<String>,
+    pub overlay_placement: String,
+    pub overlay_display: Option<String>,
+    pub overlay_offsets: BTreeMap<String, OverlayOffset>,
+    pub retro_shortcut: Option<String>,
+    // Starts a dictation that skips history and usage stats, with the same
+    // hold or toggle behaviour as the recording hotkey.
+    pub private_shortcut: Option<String>,
+    pub transcription_connect_timeout_secs: u32,
+    pub transcription_timeout_secs: u32,
+    pub stats_privacy_mode: bool,
+    pub word_count_mode: String,
+    pub private_dictation: bool,
+    pub history_encryption: bool,
+    pub insertion_audit_retention_days: u32,
+    pub redaction_rules: Vec<RedactionRule>,
+    pub redact_before_insertion: bool,
+    pub noise_suppression: bool,
+    pub retro_buffer_secs: u32,
+    // Keeps the microphone open between recordings to skip device start-up.
+    pub microphone_warm_up: bool,
+    pub microphone_channels: BTreeMap<String, String>,
+    pub target_sample_rate: Option<u32>,
+    pub transcription_race_mode: bool,
+    pub insertion_pacing: BTreeMap<String, String>,
+    // Typed before every insertion, as is.
+    pub insertion_prefix: String,
+    // `none`, `space` or `newline`, typed after every insertion.
+    pub insertion_suffix: String,
+    // Holds a trailing space back until the next insertion into the same app,
+    // and drops it when that insertion starts with punctuation.
+    pub smart_spacing: bool,
+    // Keyed by app bundle id (or name for apps without one).
+    pub insertion_spacing_overrides: BTreeMap<String, InsertionSpacingOverride>,
+    pub typing_chars_per_second: u32,
+    pub preserve_clipboard: bool,
+    pub clipboard_restore_delay_ms: u32,
+    pub verify_insertion: bool,
     pub transcription_style: String,
     pub custom_transcription_prompt: String,
-    pub auto_insert: bool,
+    // Keyed by profile (transcription style). Names, jargon and topics sent
+    // with each dictation so the provider spells them right.
+    pub context_prompts: BTreeMap<String, String>,
+    // Keyed by profile. Deterministic clean-up run on every transcript
+    // dictated with that profile; profiles without an entry are left alone.
+    pub formatting: BTreeMap<String, FormattingOptions>,
+    // Also sends the last words inserted into the app being dictated into,
+    // which are only remembered while this is on.
+    pub recent_text_context: bool,
+    pub recent_text_context_words: u32,
+    // Older settings files stored a boolean here; see `deserialize_auto_insert`.
+    #[serde(deserialize_with = "deserialize_auto_insert")]
+    pub auto_insert: String,
+    pub confirm_before_insert: bool,
+    // Sends a transcript to the review window when the provider's confidence
+    // falls below the threshold. Transcripts without a score insert as usual.
+    pub low_confidence_review: bool,
+    pub low_confidence_threshold_percent: u32,
+    pub browser_bridge_enabled: bool,
+    pub control_server_enabled: bool,
+    // Takes effect at next launch: no menu bar icon, and the control socket
+    // stays on so the app can still be driven.
+    pub background_only: bool,
+    pub watch_folders: Vec<WatchFolder>,
+    pub meeting_mode: bool,
+    pub meeting_mode_threshold_secs: u32,
+    pub meeting_diarization: bool,
+    // Crash reports are always written locally; they only leave the machine
+    // when this is on and an endpoint is set.
+    pub crash_report_upload_enabled: bool,
+    pub crash_report_upload_url: Option<String>,
+    // Off by default. Turning it off also drops anything still queued.
+    pub telemetry_enabled: bool,
+    pub telemetry_endpoint: Option<String>,
+    // Writes every pipeline event of each dictation to a local trace file for
+    // bug reports. Traces include transcript text; private sessions are
+    // never traced.
+    pub session_trace_enabled: bool,
     pub launch_at_login: bool,
     pub onboarding_completed: bool,
 }
@@ -42,12 +326,88 @@ impl Default for VoiceSettings {
         Self {
             hotkey_shortcut: DEFAULT_HOTKEY_SHORTCUT.to_string(),
             recording_mode: RECORDING_MODE_TOGGLE.to_string(),
+            min_recording_duration_ms: DEFAULT_MIN_RECORDING_DURATION_MS,
+            max_recording_duration_mins: DEFAULT_MAX_RECORDING_DURATION_MINS,
+            hotkey_name: None,
+            input_triggers: Vec::new(),
             microphone_id: None,
+            capture_source: CAPTURE_SOURCE_MICROPHONE.to_string(),
             language: None,
+            preferred_languages: Vec::new(),
+            language_profiles: BTreeMap::new(),
+            pin_realtime_language: false,
             transcription_provider: DEFAULT_TRANSCRIPTION_PROVIDER.to_string(),
+            provider_config: ProviderConfig::default(),
+            transcription_models: BTreeMap::new(),
+            decoding: DecodingOptions::default(),
+            proxy: ProxySettings::default(),
+            audio_encoding: AUDIO_ENCODING_WAV.to_string(),
+            time_stretch_enabled: false,
+            time_stretch_factor_percent: DEFAULT_TIME_STRETCH_FACTOR_PERCENT,
+            silence_trim_enabled: true,
+            silence_trim_padding_ms: DEFAULT_SILENCE_TRIM_PADDING_MS,
+            silence_trim_threshold_dbfs: DEFAULT_SILENCE_TRIM_THRESHOLD_DBFS,
+            translation_enabled: false,
+            hallucination_filter: HALLUCINATION_FILTER_DISCARD.to_string(),
+            translation_insert: TRANSLATION_INSERT_ORIGINAL.to_string(),
+            sound_feedback_enabled: false,
+            sound_feedback_volume_percent: DEFAULT_SOUND_FEEDBACK_VOLUME_PERCENT,
+            overlay_keys_enabled: false,
+            overlay_cancel_key: Some(DEFAULT_OVERLAY_CANCEL_KEY.to_string()),
+            overlay_stop_key: Some(DEFAULT_OVERLAY_STOP_KEY.to_string()),
+            overlay_copy_key: Some(DEFAULT_OVERLAY_COPY_KEY.to_string()),
+            overlay_private_key: None,
+            overlay_placement: OVERLAY_PLACEMENT_TOP_CENTER.to_string(),
+            overlay_display: None,
+            overlay_offsets: BTreeMap::new(),
+            retro_shortcut: None,
+            private_shortcut: None,
+            transcription_connect_timeout_secs: DEFAULT_TRANSCRIPTION_CONNECT_TIMEOUT_SECS,
+            transcription_timeout_secs: DEFAULT_TRANSCRIPTION_TIMEOUT_SECS,
+            stats_privacy_mode: false,
+            word_count_mode: WORD_COUNT_MODE_AUTO.to_string(),
+            private_dictation: false,
+            history_encryption: false,
+            insertion_audit_retention_days: 30,
+            redaction_rules: Vec::new(),
+            redact_before_insertion: false,
+            noise_suppression: false,
+            retro_buffer_secs: 0,
+            microphone_warm_up: false,
+            microphone_channels: BTreeMap::new(),
+            target_sample_rate: None,
+            transcription_race_mode: false,
+            insertion_pacing: BTreeMap::new(),
+            insertion_prefix: String::new(),
+            insertion_suffix: INSERTION_SUFFIX_NONE.to_string(),
+            smart_spacing: true,
+            insertion_spacing_overrides: BTreeMap::new(),
+            typing_chars_per_second: DEFAULT_TYPING_CHARS_PER_SECOND,
+            preserve_clipboard: true,
+            clipboard_restore_delay_ms: DEFAULT_CLIPBOARD_RESTORE_DELAY_MS,
+            verify_insertion: true,
             transcription_style: DEFAULT_TRANSCRIPTION_STYLE.to_string(),
             custom_transcription_prompt: String::new(),
-            auto_insert: true,
+            context_prompts: BTreeMap::new(),
+            formatting: BTreeMap::new(),
+            recent_text_context: false,
+            recent_text_context_words: DEFAULT_RECENT_TEXT_CONTEXT_WORDS,
+            auto_insert: AUTO_INSERT_ALWAYS.to_string(),
+            confirm_before_insert: false,
+            low_confidence_review: false,
+            low_confidence_threshold_percent: DEFAULT_LOW_CONFIDENCE_THRESHOLD_PERCENT,
+            browser_bridge_enabled: false,
+            control_server_enabled: false,
+            background_only: false,
+            watch_folders: Vec::new(),
+            meeting_mode: false,
+            meeting_mode_threshold_secs: DEFAULT_MEETING_MODE_THRESHOLD_SECS,
+            meeting_diarization: true,
+            crash_report_upload_enabled: false,
+            crash_report_upload_url: None,
+            telemetry_enabled: false,
+            telemetry_endpoint: None,
+            session_trace_enabled: false,
             launch_at_login: false,
             onboarding_completed: false,
         }
@@ -55,17 +415,116 @@ impl Default for VoiceSettings {
 }
 
 impl VoiceSettings {
+    // The overrides a provider is built with: the model picked for it wins
+    // over one typed into `provider_config`. Endpoint and headers only apply
+    // to the OpenAI-compatible providers.
+    pub fn provider_config_for(&self, provider: &str) -> ProviderConfig {
+        let picked_model = self.transcription_models.get(provider).cloned();
+        if provider == DEFAULT_TRANSCRIPTION_PROVIDER {
+            ProviderConfig {
+                model: picked_model.or_else(|| self.provider_config.model.clone()),
+                ..self.provider_config.clone()
+            }
+        } else {
+            ProviderConfig {
+                model: picked_model,
+                ..ProviderConfig::default()
+            }
+        }
+    }
+
     fn normalized(mut self) -> Result<Self, String> {
         self.hotkey_shortcut = normalize_required_string(self.hotkey_shortcut, "hotkey_shortcut")?;
         self.recording_mode = normalize_recording_mode(self.recording_mode)?;
+        self.hotkey_name = normalize_optional_string(self.hotkey_name);
+        self.input_triggers = normalize_input_triggers(self.input_triggers);
         self.microphone_id = normalize_optional_string(self.microphone_id);
+        self.capture_source = normalize_capture_source(self.capture_source)?;
         self.language = normalize_optional_string(self.language);
+        self.preferred_languages =
+            normalize_preferred_languages(self.language.take(), self.preferred_languages);
+        self.language = self.preferred_languages.first().cloned();
+        self.language_profiles = normalize_language_profiles(self.language_profiles);
         self.transcription_provider =
             normalize_transcription_provider(self.transcription_provider)?;
+        self.audio_encoding = normalize_audio_encoding(self.audio_encoding)?;
         self.transcription_style = normalize_transcription_style(self.transcription_style);
         self.custom_transcription_prompt =
             normalize_optional_string(Some(self.custom_transcription_prompt)).unwrap_or_default();
-
+        self.context_prompts = normalize_context_prompts(self.context_prompts)?;
+        self.formatting = normalize_formatting(self.formatting)?;
+        self.recent_text_context_words = self
+            .recent_text_context_words
+            .clamp(1, MAX_RECENT_TEXT_CONTEXT_WORDS);
+        self.meeting_mode_threshold_secs = self
+            .meeting_mode_threshold_secs
+            .max(MIN_MEETING_MODE_THRESHOLD_SECS);
+        self.min_recording_duration_ms = self
+            .min_recording_duration_ms
+            .min(MAX_MIN_RECORDING_DURATION_MS);
+        self.max_recording_duration_mins = self
+            .max_recording_duration_mins
+            .min(MAX_MAX_RECORDING_DURATION_MINS);
+
+        self.time_stretch_factor_percent = self.time_stretch_factor_percent.clamp(
+            MIN_TIME_STRETCH_FACTOR_PERCENT,
+            MAX_TIME_STRETCH_FACTOR_PERCENT,
+        );
+        self.silence_trim_padding_ms = self
+            .silence_trim_padding_ms
+            .min(MAX_SILENCE_TRIM_PADDING_MS);
+        self.silence_trim_threshold_dbfs = self.silence_trim_threshold_dbfs.clamp(
+            MIN_SILENCE_TRIM_THRESHOLD_DBFS,
+            MAX_SILENCE_TRIM_THRESHOLD_DBFS,
+        );
+        self.auto_insert = normalize_auto_insert(self.auto_insert)?;
+        self.hallucination_filter = normalize_hallucination_filter(self.hallucination_filter);
+        self.translation_insert = normalize_translation_insert(self.translation_insert);
+        self.sound_feedback_volume_percent = self
+            .sound_feedback_volume_percent
+            .min(MAX_SOUND_FEEDBACK_VOLUME_PERCENT);
+        self.low_confidence_threshold_percent = self
+            .low_confidence_threshold_percent
+            .min(MAX_LOW_CONFIDENCE_THRESHOLD_PERCENT);
+        self.overlay_cancel_key = normalize_optional_string(self.overlay_cancel_key);
+        self.overlay_stop_key = normalize_optional_string(self.overlay_stop_key);
+        self.overlay_copy_key = normalize_optional_string(self.overlay_copy_key);
+        self.microphone_channels = normalize_microphone_channels(self.microphone_channels)?;
+        self.target_sample_rate = normalize_target_sample_rate(self.target_sample_rate)?;
+        self.insertion_pacing = normalize_insertion_pacing(self.insertion_pacing)?;
+        self.insertion_suffix = normalize_insertion_suffix(self.insertion_suffix, "insertion")?;
+        self.insertion_spacing_overrides =
+            normalize_insertion_spacing_overrides(self.insertion_spacing_overrides)?;
+        self.overlay_private_key = normalize_optional_string(self.overlay_private_key);
+        self.redaction_rules = validate_rules(self.redaction_rules)?;
+        self.word_count_mode = normalize_word_count_mode(self.word_count_mode);
+        self.provider_config = self.provider_config.normalized()?;
+        self.transcription_models = normalize_transcription_models(self.transcription_models)?;
+        self.decoding = self.decoding.normalized()?;
+        self.proxy = self.proxy.normalized()?;
+        self.retro_buffer_secs = self.retro_buffer_secs.min(MAX_RETRO_BUFFER_SECS);
+        self.retro_shortcut = normalize_optional_string(self.retro_shortcut);
+        self.private_shortcut = normalize_optional_string(self.private_shortcut);
+        self.transcription_connect_timeout_secs = self.transcription_connect_timeout_secs.clamp(
+            MIN_TRANSCRIPTION_TIMEOUT_SECS,
+            MAX_TRANSCRIPTION_CONNECT_TIMEOUT_SECS,
+        );
+        self.transcription_timeout_secs = self.transcription_timeout_secs.clamp(
+            MIN_TRANSCRIPTION_TIMEOUT_SECS,
+            MAX_TRANSCRIPTION_TIMEOUT_SECS,
+        );
+        self.typing_chars_per_second = self
+            .typing_chars_per_second
+            .clamp(MIN_TYPING_CHARS_PER_SECOND, MAX_TYPING_CHARS_PER_SECOND);
+        self.clipboard_restore_delay_ms = self
+            .clipboard_restore_delay_ms
+            .min(MAX_CLIPBOARD_RESTORE_DELAY_MS);
+        self.overlay_placement = normalize_overlay_placement(self.overlay_placement)?;
+        self.overlay_display = normalize_optional_string(self.overlay_display);
+        self.overlay_offsets = normalize_overlay_offsets(self.overlay_offsets);
+        self.watch_folders = normalize_watch_folders(self.watch_folders);
+        self.crash_report_upload_url = normalize_optional_string(self.crash_report_upload_url);
+        self.telemetry_endpoint = normalize_optional_string(self.telemetry_endpoint);
         Ok(self)
     }
 
@@ -78,18 +537,253 @@ impl VoiceSettings {
             self.recording_mode = recording_mode;
         }
 
+        if let Some(min_recording_duration_ms) = update.min_recording_duration_ms {
+            self.min_recording_duration_ms = min_recording_duration_ms;
+        }
+
+        if let Some(max_recording_duration_mins) = update.max_recording_duration_mins {
+            self.max_recording_duration_mins = max_recording_duration_mins;
+        }
+
+        if let Some(hotkey_name) = update.hotkey_name {
+            self.hotkey_name = hotkey_name;
+        }
+
+        if let Some(input_triggers) = update.input_triggers {
+            self.input_triggers = input_triggers;
+        }
+
         if let Some(microphone_id) = update.microphone_id {
             self.microphone_id = microphone_id;
         }
 
+        if let Some(capture_source) = update.capture_source {
+            self.capture_source = capture_source;
+        }
+
         if let Some(language) = update.language {
+            // Picking a language moves it to the front of the list; clearing
+            // it goes back to detecting every dictation.
+            if language.is_none() {
+                self.preferred_languages.clear();
+            }
             self.language = language;
         }
 
+        if let Some(preferred_languages) = update.preferred_languages {
+            self.language = preferred_languages.first().cloned();
+            self.preferred_languages = preferred_languages;
+        }
+
+        if let Some(language_profiles) = update.language_profiles {
+            self.language_profiles = language_profiles;
+        }
+
+        if let Some(pin_realtime_language) = update.pin_realtime_language {
+            self.pin_realtime_language = pin_realtime_language;
+        }
+
         if let Some(transcription_provider) = update.transcription_provider {
             self.transcription_provider = transcription_provider;
         }
 
+        if let Some(provider_config) = update.provider_config {
+            self.provider_config = provider_config;
+        }
+
+        if let Some(transcription_models) = update.transcription_models {
+            self.transcription_models = transcription_models;
+        }
+
+        if let Some(decoding) = update.decoding {
+            self.decoding = decoding;
+        }
+
+        if let Some(proxy) = update.proxy {
+            self.proxy = proxy;
+        }
+
+        if let Some(audio_encoding) = update.audio_encoding {
+            self.audio_encoding = audio_encoding;
+        }
+
+        if let Some(time_stretch_enabled) = update.time_stretch_enabled {
+            self.time_stretch_enabled = time_stretch_enabled;
+        }
+
+        if let Some(time_stretch_factor_percent) = update.time_stretch_factor_percent {
+            self.time_stretch_factor_percent = time_stretch_factor_percent;
+        }
+
+        if let Some(silence_trim_enabled) = update.silence_trim_enabled {
+            self.silence_trim_enabled = silence_trim_enabled;
+        }
+
+        if let Some(silence_trim_padding_ms) = update.silence_trim_padding_ms {
+            self.silence_trim_padding_ms = silence_trim_padding_ms;
+        }
+
+        if let Some(silence_trim_threshold_dbfs) = update.silence_trim_threshold_dbfs {
+            self.silence_trim_threshold_dbfs = silence_trim_threshold_dbfs;
+        }
+
+        if let Some(hallucination_filter) = update.hallucination_filter {
+            self.hallucination_filter = hallucination_filter;
+        }
+
+        if let Some(translation_enabled) = update.translation_enabled {
+            self.translation_enabled = translation_enabled;
+        }
+
+        if let Some(translation_insert) = update.translation_insert {
+            self.translation_insert = translation_insert;
+        }
+
+        if let Some(sound_feedback_enabled) = update.sound_feedback_enabled {
+            self.sound_feedback_enabled = sound_feedback_enabled;
+        }
+
+        if let Some(sound_feedback_volume_percent) = update.sound_feedback_volume_percent {
+            self.sound_feedback_volume_percent = sound_feedback_volume_percent;
+        }
+
+        if let Some(overlay_keys_enabled) = update.overlay_keys_enabled {
+            self.overlay_keys_enabled = overlay_keys_enabled;
+        }
+
+        if let Some(overlay_cancel_key) = update.overlay_cancel_key {
+            self.overlay_cancel_key = overlay_cancel_key;
+        }
+
+        if let Some(overlay_stop_key) = update.overlay_stop_key {
+            self.overlay_stop_key = overlay_stop_key;
+        }
+
+        if let Some(overlay_copy_key) = update.overlay_copy_key {
+            self.overlay_copy_key = overlay_copy_key;
+        }
+
+        if let Some(overlay_private_key) = update.overlay_private_key {
+            self.overlay_private_key = overlay_private_key;
+        }
+
+        if let Some(overlay_placement) = update.overlay_placement {
+            self.overlay_placement = overlay_placement;
+        }
+
+        if let Some(overlay_display) = update.overlay_display {
+            self.overlay_display = overlay_display;
+        }
+
+        if let Some(overlay_offsets) = update.overlay_offsets {
+            self.overlay_offsets = overlay_offsets;
+        }
+
+        if let Some(retro_shortcut) = update.retro_shortcut {
+            self.retro_shortcut = retro_shortcut;
+        }
+
+        if let Some(private_shortcut) = update.private_shortcut {
+            self.private_shortcut = private_shortcut;
+        }
+
+        if let Some(transcription_connect_timeout_secs) = update.transcription_connect_timeout_secs
+        {
+            self.transcription_connect_timeout_secs = transcription_connect_timeout_secs;
+        }
+
+        if let Some(transcription_timeout_secs) = update.transcription_timeout_secs {
+            self.transcription_timeout_secs = transcription_timeout_secs;
+        }
+
+        if let Some(stats_privacy_mode) = update.stats_privacy_mode {
+            self.stats_privacy_mode = stats_privacy_mode;
+        }
+
+        if let Some(word_count_mode) = update.word_count_mode {
+            self.word_count_mode = word_count_mode;
+        }
+
+        if let Some(private_dictation) = update.private_dictation {
+            self.private_dictation = private_dictation;
+        }
+
+        if let Some(history_encryption) = update.history_encryption {
+            self.history_encryption = history_encryption;
+        }
+
+        if let Some(insertion_audit_retention_days) = update.insertion_audit_retention_days {
+            self.insertion_audit_retention_days = insertion_audit_retention_days;
+        }
+
+        if let Some(redaction_rules) = update.redaction_rules {
+            self.redaction_rules = redaction_rules;
+        }
+
+        if let Some(redact_before_insertion) = update.redact_before_insertion {
+            self.redact_before_insertion = redact_before_insertion;
+        }
+
+        if let Some(noise_suppression) = update.noise_suppression {
+            self.noise_suppression = noise_suppression;
+        }
+
+        if let Some(retro_buffer_secs) = update.retro_buffer_secs {
+            self.retro_buffer_secs = retro_buffer_secs;
+        }
+
+        if let Some(microphone_warm_up) = update.microphone_warm_up {
+            self.microphone_warm_up = microphone_warm_up;
+        }
+
+        if let Some(microphone_channels) = update.microphone_channels {
+            self.microphone_channels = microphone_channels;
+        }
+
+        if let Some(target_sample_rate) = update.target_sample_rate {
+            self.target_sample_rate = target_sample_rate;
+        }
+
+        if let Some(transcription_race_mode) = update.transcription_race_mode {
+            self.transcription_race_mode = transcription_race_mode;
+        }
+
+        if let Some(insertion_pacing) = update.insertion_pacing {
+            self.insertion_pacing = insertion_pacing;
+        }
+
+        if let Some(insertion_prefix) = update.insertion_prefix {
+            self.insertion_prefix = insertion_prefix;
+        }
+
+        if let Some(insertion_suffix) = update.insertion_suffix {
+            self.insertion_suffix = insertion_suffix;
+        }
+
+        if let Some(smart_spacing) = update.smart_spacing {
+            self.smart_spacing = smart_spacing;
+        }
+
+        if let Some(insertion_spacing_overrides) = update.insertion_spacing_overrides {
+            self.insertion_spacing_overrides = insertion_spacing_overrides;
+        }
+
+        if let Some(typing_chars_per_second) = update.typing_chars_per_second {
+            self.typing_chars_per_second = typing_chars_per_second;
+        }
+
+        if let Some(preserve_clipboard) = update.preserve_clipboard {
+            self.preserve_clipboard = preserve_clipboard;
+        }
+
+        if let Some(clipboard_restore_delay_ms) = update.clipboard_restore_delay_ms {
+            self.clipboard_restore_delay_ms = clipboard_restore_delay_ms;
+        }
+
+        if let Some(verify_insertion) = update.verify_insertion {
+            self.verify_insertion = verify_insertion;
+        }
+
         if let Some(transcription_style) = update.transcription_style {
             self.transcription_style = transcription_style;
         }
@@ -98,10 +792,86 @@ impl VoiceSettings {
             self.custom_transcription_prompt = custom_transcription_prompt;
         }
 
+        if let Some(context_prompts) = update.context_prompts {
+            self.context_prompts = context_prompts;
+        }
+
+        if let Some(formatting) = update.formatting {
+            self.formatting = formatting;
+        }
+
+        if let Some(recent_text_context) = update.recent_text_context {
+            self.recent_text_context = recent_text_context;
+        }
+
+        if let Some(recent_text_context_words) = update.recent_text_context_words {
+            self.recent_text_context_words = recent_text_context_words;
+        }
+
         if let Some(auto_insert) = update.auto_insert {
             self.auto_insert = auto_insert;
         }
 
+        if let Some(confirm_before_insert) = update.confirm_before_insert {
+            self.confirm_before_insert = confirm_before_insert;
+        }
+
+        if let Some(low_confidence_review) = update.low_confidence_review {
+            self.low_confidence_review = low_confidence_review;
+        }
+
+        if let Some(low_confidence_threshold_percent) = update.low_confidence_threshold_percent {
+            self.low_confidence_threshold_percent = low_confidence_threshold_percent;
+        }
+
+        if let Some(browser_bridge_enabled) = update.browser_bridge_enabled {
+            self.browser_bridge_enabled = browser_bridge_enabled;
+        }
+
+        if let Some(control_server_enabled) = update.control_server_enabled {
+            self.control_server_enabled = control_server_enabled;
+        }
+
+        if let Some(background_only) = update.background_only {
+            self.background_only = background_only;
+        }
+
+        if let Some(watch_folders) = update.watch_folders {
+            self.watch_folders = watch_folders;
+        }
+
+        if let Some(meeting_mode) = update.meeting_mode {
+            self.meeting_mode = meeting_mode;
+        }
+
+        if let Some(meeting_mode_threshold_secs) = update.meeting_mode_threshold_secs {
+            self.meeting_mode_threshold_secs = meeting_mode_threshold_secs;
+        }
+
+        if let Some(meeting_diarization) = update.meeting_diarization {
+            self.meeting_diarization = meeting_diarization;
+        }
+
+        if let Some(crash_report_upload_enabled) = update.crash_report_upload_enabled {
+            self.crash_report_upload_enabled = crash_report_upload_enabled;
+        }
+
+        if let Some(crash_report_upload_url) = update.crash_report_upload_url {
+            self.crash_report_upload_url = crash_report_upload_url;
+        }
+
+        if let Some(telemetry_enabled) = update.telemetry_enabled {
+            self.telemetry_enabled = telemetry_enabled;
+        }
+
+        if let Some(telemetry_endpoint) = update.telemetry_endpoint {
+            self.telemetry_endpoint = telemetry_endpoint;
+        }
+
+        if let Some(session_trace_enabled) = update.session_trace_enabled {
+            self.session_trace_enabled = session_trace_enabled;
+        }
+
         if let Some(launch_at_login) = update.launch_at_login {
             self.launch_at_login = launch_at_login;
         }
@@ -114,25 +884,120 @@ impl VoiceSettings {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Default, JsonSchema)]
 #[serde(default)]
 pub struct VoiceSettingsUpdate {
     pub hotkey_shortcut: Option<String>,
     pub recording_mode: Option<String>,
+    pub min_recording_duration_ms: Option<u64>,
+    pub max_recording_duration_mins: Option<u32>,
+    pub hotkey_name: Option<Option<String>>,
+    pub input_triggers: Option<Vec<InputTriggerBinding>>,
     pub microphone_id: Option<Option<String>>,
+    pub capture_source: Option<String>,
     pub language: Option<Option<String>>,
+    pub preferred_languages: Option<Vec<String>>,
+    pub language_profiles: Option<BTreeMap<String, LanguageProfile>>,
+    pub pin_realtime_language: Option<bool>,
     pub transcription_provider: Option<String>,
+    pub provider_config: Option<ProviderConfig>,
+    pub transcription_models: Option<BTreeMap<String, String>>,
+    pub decoding: Option<DecodingOptions>,
+    pub proxy: Option<ProxySettings>,
+    pub audio_encoding: Option<String>,
+    pub time_stretch_enabled: Option<bool>,
+    pub time_stretch_factor_percent: Option<u32>,
+    pub silence_trim_enabled: Option<bool>,
+    pub silence_trim_padding_ms: Option<u32>,
+    pub silence_trim_threshold_dbfs: Option<i32>,
+    pub hallucination_filter: Option<String>,
+    pub translation_enabled: Option<bool>,
+    pub translation_insert: Option<String>,
+    pub sound_feedback_enabled: Option<bool>,
+    pub sound_feedback_volume_percent: Option<u32>,
+    pub overlay_keys_enabled: Option<bool>,
+    pub overlay_cancel_key: Option<Option<String>>,
+    pub overlay_stop_key: Option<Option<String>>,
+    pub overlay_copy_key: Option<Option<String>>,
+    pub overlay_private_key: Option<Option<String>>,
+    pub overlay_placement: Option<String>,
+    pub overlay_display: Option<Option<String>>,
+    pub overlay_offsets: Option<BTreeMap<String, OverlayOffset>>,
+    pub retro_shortcut: Option<Option<String>>,
+    pub private_shortcut: Option<Option<String>>,
+    pub transcription_connect_timeout_secs: Option<u32>,
+    pub transcription_timeout_secs: Option<u32>,
+    pub stats_privacy_mode: Option<bool>,
+    pub word_count_mode: Option<String>,
+    pub private_dictation: Option<bool>,
+    pub history_encryption: Option<bool>,
+    pub insertion_audit_retention_days: Option<u32>,
+    pub redaction_rules: Option<Vec<RedactionRule>>,
+    pub redact_before_insertion: Option<bool>,
+    pub noise_suppression: Option<bool>,
+    pub retro_buffer_secs: Option<u32>,
+    pub microphone_warm_up: Option<bool>,
+    pub microphone_channels: Option<BTreeMap<String, String>>,
+    pub target_sample_rate: Option<Option<u32>>,
+    pub transcription_race_mode: Option<bool>,
+    pub insertion_pacing: Option<BTreeMap<String, String>>,
+    pub insertion_prefix: Option<String>,
+    pub insertion_suffix: Option<String>,
+    pub smart_spacing: Option<bool>,
+    pub insertion_spacing_overrides: Option<BTreeMap<String, InsertionSpacingOverride>>,
+    pub typing_chars_per_second: Option<u32>,
+    pub preserve_clipboard: Option<bool>,
+    pub clipboard_restore_delay_ms: Option<u32>,
+    pub verify_insertion: Option<bool>,
     pub transcription_style: Option<String>,
     pub custom_transcription_prompt: Option<String>,
-    pub auto_insert: Option<bool>,
+    pub context_prompts: Option<BTreeMap<String, String>>,
+    pub formatting: Option<BTreeMap<String, FormattingOptions>>,
+    pub recent_text_context: Option<bool>,
+    pub recent_text_context_words: Option<u32>,
+    #[serde(deserialize_with = "deserialize_auto_insert_update")]
+    pub auto_insert: Option<String>,
+    pub confirm_before_insert: Option<bool>,
+    pub low_confidence_review: Option<bool>,
+    pub low_confidence_threshold_percent: Option<u32>,
+    pub browser_bridge_enabled: Option<bool>,
+    pub control_server_enabled: Option<bool>,
+    pub background_only: Option<bool>,
+    pub watch_folders: Option<Vec<WatchFolder>>,
+    pub meeting_mode: Option<bool>,
+    pub meeting_mode_threshold_secs: Option<u32>,
+    pub meeting_diarization: Option<bool>,
+    pub crash_report_upload_enabled: Option<bool>,
+    pub crash_report_upload_url: Option<Option<String>>,
+    pub telemetry_enabled: Option<bool>,
+    pub telemetry_endpoint: Option<Option<String>>,
+    pub session_trace_enabled: Option<bool>,
     pub launch_at_login: Option<bool>,
     pub onboarding_completed: Option<bool>,
 }
 
+// Settings along with the revision to send back as `expected_revision`.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsSnapshot {
+    pub settings: VoiceSettings,
+    pub revision: u64,
+}
+
 #[derive(Debug)]
 pub struct SettingsStore {
     settings: RwLock<VoiceSettings>,
     io_lock: Mutex<()>,
+    // Modification time of the file as this store last read or wrote it, so
+    // the watcher can tell hand edits apart from the store's own writes.
+    file_modified: Mutex<Option<SystemTime>>,
+    // Bumped whenever the in-memory settings are replaced, so callers can
+    // detect that settings changed between their read and their write.
+    revision: AtomicU64,
+}
+
+pub fn settings_file_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(SETTINGS_FILE_NAME)
 }
 
 impl Default for SettingsStore {
@@ -147,9 +1012,15 @@ impl SettingsStore {
         Self {
             settings: RwLock::new(VoiceSettings::default()),
             io_lock: Mutex::new(()),
+            file_modified: Mutex::new(None),
+            revision: AtomicU64::new(0),
         }
     }
 
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::SeqCst)
+    }
+
     pub fn current(&self) -> VoiceSettings {
         self.settings
             .read()
@@ -157,53 +1028,171 @@ impl SettingsStore {
             .unwrap_or_else(|_| VoiceSettings::default())
     }
 
-    pub fn load<R: Runtime>(&self, app: &AppHandle<R>) -> Result<VoiceSettings, String> {
+    // The revision is only bumped while the settings are write-locked, so
+    // reading it under the read lock always pairs it with these settings.
+    pub fn snapshot(&self) -> Result<SettingsSnapshot, AppError> {
+        let settings = self.settings.read().map_err(|_| lock_error())?;
+        Ok(SettingsSnapshot {
+            settings: settings.clone(),
+            revision: self.revision(),
+        })
+    }
+
+    #[cfg(feature = "tauri")]
+    pub fn load<R: Runtime>(&self, app: &AppHandle<R>) -> Result<VoiceSettings, AppError> {
         let settings_path = self.settings_path(app)?;
         debug!(path = %settings_path.display(), "loading settings from disk");
         self.load_from_path(&settings_path)
     }
 
+    #[cfg(feature = "tauri")]
     pub fn update<R: Runtime>(
         &self,
         app: &AppHandle<R>,
         update: VoiceSettingsUpdate,
-    ) -> Result<VoiceSettings, String> {
+    ) -> Result<VoiceSettings, AppError> {
+        self.update_checked(app, update, None)
+    }
+
+    #[cfg(feature = "tauri")]
+    pub fn update_checked<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        update: VoiceSettingsUpdate,
+        expected_revision: Option<u64>,
+    ) -> Result<VoiceSettings, AppError> {
         let settings_path = self.settings_path(app)?;
         debug!(path = %settings_path.display(), "updating settings on disk");
-        self.update_at_path(&settings_path, update)
+        self.update_checked_at_path(&settings_path, update, expected_revision)
+    }
+
+    #[cfg(feature = "tauri")]
+    pub fn replace<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        settings: VoiceSettings,
+    ) -> Result<VoiceSettings, AppError> {
+        let settings_path = self.settings_path(app)?;
+        debug!(path = %settings_path.display(), "replacing settings on disk");
+        self.replace_at_path(&settings_path, settings)
+    }
+
+    #[cfg(feature = "tauri")]
+    pub fn reload_if_changed<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+    ) -> Result<Option<VoiceSettings>, AppError> {
+        let settings_path = self.settings_path(app)?;
+        self.reload_if_changed_at_path(&settings_path)
     }
 
-    fn settings_path<R: Runtime>(&self, app: &AppHandle<R>) -> Result<PathBuf, String> {
-        let app_data_dir = app
-            .path()
-            .app_data_dir()
-            .map_err(|error| format!("Failed to resolve app data directory: {error}"))?;
+    #[cfg(feature = "tauri")]
+    fn settings_path<R: Runtime>(&self, app: &AppHandle<R>) -> Result<PathBuf, AppError> {
+        let app_data_dir = app.path().app_data_dir().map_err(|error| {
+            AppError::from(format!("Failed to resolve app data directory: {error}"))
+        })?;
 
-        Ok(app_data_dir.join(SETTINGS_FILE_NAME))
+        Ok(settings_file_path(&app_data_dir))
     }
 
-    fn load_from_path(&self, settings_path: &Path) -> Result<VoiceSettings, String> {
+    pub fn load_from_path(&self, settings_path: &Path) -> Result<VoiceSettings, AppError> {
         let _io_guard = self.io_lock.lock().map_err(|_| io_lock_error())?;
         let settings = read_settings_file_with_recovery(settings_path)?;
+        self.remember_file_modified(settings_path);
         let mut guard = self.settings.write().map_err(|_| lock_error())?;
         *guard = settings.clone();
+        self.revision.fetch_add(1, Ordering::SeqCst);
         Ok(settings)
     }
 
-    fn update_at_path(
+    pub fn update_at_path(
+        &self,
+        settings_path: &Path,
+        update: VoiceSettingsUpdate,
+    ) -> Result<VoiceSettings, AppError> {
+        self.update_checked_at_path(settings_path, update, None)
+    }
+
+    pub fn update_checked_at_path(
         &self,
         settings_path: &Path,
         update: VoiceSettingsUpdate,
-    ) -> Result<VoiceSettings, String> {
+        expected_revision: Option<u64>,
+    ) -> Result<VoiceSettings, AppError> {
         let _io_guard = self.io_lock.lock().map_err(|_| io_lock_error())?;
+        check_revision(expected_revision, self.revision())?;
         let current_settings = read_settings_file_with_recovery(settings_path)?;
-        let updated_settings = current_settings.with_update(update)?;
+        let updated_settings = current_settings
+            .with_update(update)
+            .map_err(invalid_settings)?;
         write_settings_file(settings_path, &updated_settings)?;
+        self.remember_file_modified(settings_path);
 
         let mut guard = self.settings.write().map_err(|_| lock_error())?;
         *guard = updated_settings.clone();
+        self.revision.fetch_add(1, Ordering::SeqCst);
         Ok(updated_settings)
     }
+
+    // Writes a whole settings value, e.g. one merged from an imported archive,
+    // after the same normalization an update gets.
+    pub fn replace_at_path(
+        &self,
+        settings_path: &Path,
+        settings: VoiceSettings,
+    ) -> Result<VoiceSettings, AppError> {
+        let _io_guard = self.io_lock.lock().map_err(|_| io_lock_error())?;
+        let settings = settings.normalized().map_err(invalid_settings)?;
+        write_settings_file(settings_path, &settings)?;
+        self.remember_file_modified(settings_path);
+
+        let mut guard = self.settings.write().map_err(|_| lock_error())?;
+        *guard = settings.clone();
+        self.revision.fetch_add(1, Ordering::SeqCst);
+        Ok(settings)
+    }
+
+    // Picks up edits made to the file outside the app. Unlike a load, an
+    // invalid file is reported and left alone rather than reset to defaults,
+    // since it is most likely still being edited; the current settings stay in
+    // effect until the next valid save.
+    pub fn reload_if_changed_at_path(
+        &self,
+        settings_path: &Path,
+    ) -> Result<Option<VoiceSettings>, AppError> {
+        let _io_guard = self.io_lock.lock().map_err(|_| io_lock_error())?;
+        let modified = file_modified_time(settings_path);
+        {
+            let mut last_modified = self.file_modified.lock().map_err(|_| lock_error())?;
+            if modified.is_none() || *last_modified == modified {
+                return Ok(None);
+            }
+            *last_modified = modified;
+        }
+
+        let settings = read_settings_file(settings_path)
+            .map_err(|error| AppError::new(ErrorCode::InvalidInput, error.message))?;
+        let mut guard = self.settings.write().map_err(|_| lock_error())?;
+        if *guard == settings {
+            return Ok(None);
+        }
+        *guard = settings.clone();
+        self.revision.fetch_add(1, Ordering::SeqCst);
+        info!(path = %settings_path.display(), "reloaded externally edited settings");
+        Ok(Some(settings))
+    }
+
+    fn remember_file_modified(&self, settings_path: &Path) {
+        if let Ok(mut last_modified) = self.file_modified.lock() {
+            *last_modified = file_modified_time(settings_path);
+        }
+    }
+}
+
+fn file_modified_time(file_path: &Path) -> Option<SystemTime> {
+    fs::metadata(file_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
 }
 
 #[derive(Debug)]
@@ -294,7 +1283,7 @@ fn write_settings_file(settings_path: &Path, settings: &VoiceSettings) -> Result
     info!(
         path = %settings_path.display(),
         recording_mode = %settings.recording_mode,
-        auto_insert = settings.auto_insert,
+        auto_insert = %settings.auto_insert,
         "settings file written"
     );
     Ok(())
@@ -392,16 +1381,68 @@ fn normalize_optional_string(value: Option<String>) -> Option<String> {
     })
 }
 
-fn normalize_required_string(value: String, field_name: &str) -> Result<String, String> {
-    let trimmed = value.trim();
-    if trimmed.is_empty() {
-        return Err(format!("`{field_name}` cannot be empty"));
+// `primary` goes first; blanks and repeats are dropped, ignoring case.
+fn normalize_preferred_languages(primary: Option<String>, languages: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for language in primary.into_iter().chain(languages) {
+        let Some(language) = normalize_optional_string(Some(language)) else {
+            continue;
+        };
+        if normalized.len() < MAX_PREFERRED_LANGUAGES
+            && !normalized
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(&language))
+        {
+            normalized.push(language);
+        }
     }
-
-    Ok(trimmed.to_string())
+    normalized
 }
 
-fn normalize_recording_mode(value: String) -> Result<String, String> {
+// Keys are lowercased so "EN" and "en" share a profile; words repeat at most
+// once and profiles with nothing in them are dropped.
+fn normalize_language_profiles(
+    value: BTreeMap<String, LanguageProfile>,
+) -> BTreeMap<String, LanguageProfile> {
+    let mut profiles = BTreeMap::<String, LanguageProfile>::new();
+    for (language, profile) in value {
+        let Some(language) = normalize_optional_string(Some(language)) else {
+            continue;
+        };
+        let merged = profiles.entry(language.to_lowercase()).or_default();
+        for word in profile.dictionary {
+            let Some(word) = normalize_optional_string(Some(word)) else {
+                continue;
+            };
+            if merged.dictionary.len() < MAX_DICTIONARY_WORDS
+                && !merged
+                    .dictionary
+                    .iter()
+                    .any(|existing| existing.eq_ignore_ascii_case(&word))
+            {
+                merged.dictionary.push(word);
+            }
+        }
+        if let Some(style) = normalize_optional_string(profile.transcription_style) {
+            merged.transcription_style = Some(normalize_transcription_style(style));
+        }
+    }
+    profiles.retain(|_, profile| {
+        !profile.dictionary.is_empty() || profile.transcription_style.is_some()
+    });
+    profiles
+}
+
+fn normalize_required_string(value: String, field_name: &str) -> Result<String, String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err(format!("`{field_name}` cannot be empty"));
+    }
+
+    Ok(trimmed.to_string())
+}
+
+fn normalize_recording_mode(value: String) -> Result<String, String> {
     let normalized = normalize_required_string(value, "recording_mode")?.to_lowercase();
     match normalized.as_str() {
         RECORDING_MODE_HOLD_TO_TALK | RECORDING_MODE_TOGGLE => Ok(normalized),
@@ -411,12 +1452,46 @@ fn normalize_recording_mode(value: String) -> Result<String, String> {
     }
 }
 
+fn normalize_capture_source(value: String) -> Result<String, String> {
+    let normalized = normalize_required_string(value, "capture_source")?.to_lowercase();
+    match normalized.as_str() {
+        CAPTURE_SOURCE_MICROPHONE | CAPTURE_SOURCE_LOOPBACK | CAPTURE_SOURCE_MIXED => Ok(normalized),
+        LEGACY_CAPTURE_SOURCE_SYSTEM => Ok(CAPTURE_SOURCE_LOOPBACK.to_string()),
+        _ => Err(format!(
+            "Unsupported capture source `{normalized}`. Expected `{CAPTURE_SOURCE_MICROPHONE}`, `{CAPTURE_SOURCE_LOOPBACK}`, or `{CAPTURE_SOURCE_MIXED}`"
+        )),
+    }
+}
+
 fn normalize_transcription_provider(value: String) -> Result<String, String> {
     let normalized = normalize_required_string(value, "transcription_provider")?.to_lowercase();
     match normalized.as_str() {
-        DEFAULT_TRANSCRIPTION_PROVIDER => Ok(normalized),
+        DEFAULT_TRANSCRIPTION_PROVIDER | GEMINI_TRANSCRIPTION_PROVIDER => Ok(normalized),
+        _ => Err(format!(
+            "Unsupported transcription provider `{normalized}`. Expected `{DEFAULT_TRANSCRIPTION_PROVIDER}` or `{GEMINI_TRANSCRIPTION_PROVIDER}`"
+        )),
+    }
+}
+
+// Keys are providers; an empty model clears the pick for that provider.
+fn normalize_transcription_models(
+    value: BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, String> {
+    value
+        .into_iter()
+        .filter_map(|(provider, model)| {
+            normalize_optional_string(Some(model)).map(|model| (provider, model))
+        })
+        .map(|(provider, model)| Ok((normalize_transcription_provider(provider)?, model)))
+        .collect()
+}
+
+fn normalize_audio_encoding(value: String) -> Result<String, String> {
+    let normalized = normalize_required_string(value, "audio_encoding")?.to_lowercase();
+    match normalized.as_str() {
+        AUDIO_ENCODING_WAV | AUDIO_ENCODING_FLAC | AUDIO_ENCODING_OPUS => Ok(normalized),
         _ => Err(format!(
-            "Unsupported transcription provider `{normalized}`. Expected `{DEFAULT_TRANSCRIPTION_PROVIDER}`"
+            "Unsupported audio encoding `{normalized}`. Expected `{AUDIO_ENCODING_WAV}`, `{AUDIO_ENCODING_FLAC}`, or `{AUDIO_ENCODING_OPUS}`"
         )),
     }
 }
@@ -431,10 +1506,290 @@ fn normalize_transcription_style(value: String) -> String {
     }
 }
 
+// Keys are profiles; an empty prompt clears it.
+fn normalize_context_prompts(
+    value: BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, String> {
+    value
+        .into_iter()
+        .filter_map(|(profile, prompt)| {
+            normalize_optional_string(Some(prompt)).map(|prompt| (profile, prompt))
+        })
+        .map(|(profile, prompt)| Ok((normalize_profile(&profile, "a context prompt")?, prompt)))
+        .collect()
+}
+
+// Keys are profiles; entries with every pass off are dropped.
+fn normalize_formatting(
+    value: BTreeMap<String, FormattingOptions>,
+) -> Result<BTreeMap<String, FormattingOptions>, String> {
+    value
+        .into_iter()
+        .filter(|(_, options)| !options.is_empty())
+        .map(|(profile, options)| Ok((normalize_profile(&profile, "formatting")?, options)))
+        .collect()
+}
+
+fn normalize_profile(profile: &str, purpose: &str) -> Result<String, String> {
+    let normalized = profile.trim().to_lowercase();
+    match normalized.as_str() {
+        TRANSCRIPTION_STYLE_CLEAN
+        | TRANSCRIPTION_STYLE_CASUAL
+        | TRANSCRIPTION_STYLE_VERBATIM
+        | TRANSCRIPTION_STYLE_CUSTOM => Ok(normalized),
+        _ => Err(format!(
+            "Unsupported profile `{normalized}` for {purpose}. Expected `{TRANSCRIPTION_STYLE_CLEAN}`, `{TRANSCRIPTION_STYLE_CASUAL}`, `{TRANSCRIPTION_STYLE_VERBATIM}`, or `{TRANSCRIPTION_STYLE_CUSTOM}`"
+        )),
+    }
+}
+
+fn normalize_auto_insert(value: String) -> Result<String, String> {
+    let normalized = normalize_required_string(value, "auto_insert")?.to_lowercase();
+    match normalized.as_str() {
+        AUTO_INSERT_ALWAYS | AUTO_INSERT_IF_FOCUSED | AUTO_INSERT_CLIPBOARD_ONLY | AUTO_INSERT_ASK => {
+            Ok(normalized)
+        }
+        _ => Err(format!(
+            "Unsupported auto insert mode `{normalized}`. Expected `{AUTO_INSERT_ALWAYS}`, `{AUTO_INSERT_IF_FOCUSED}`, `{AUTO_INSERT_CLIPBOARD_ONLY}`, or `{AUTO_INSERT_ASK}`"
+        )),
+    }
+}
+
+// `auto_insert` used to be a boolean; `true` maps to always inserting and
+// `false` to copying to the clipboard, which is what the old toggle did.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AutoInsertValue {
+    Legacy(bool),
+    Mode(String),
+}
+
+impl From<AutoInsertValue> for String {
+    fn from(value: AutoInsertValue) -> Self {
+        match value {
+            AutoInsertValue::Legacy(true) => AUTO_INSERT_ALWAYS.to_string(),
+            AutoInsertValue::Legacy(false) => AUTO_INSERT_CLIPBOARD_ONLY.to_string(),
+            AutoInsertValue::Mode(mode) => mode,
+        }
+    }
+}
+
+fn deserialize_auto_insert<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    AutoInsertValue::deserialize(deserializer).map(String::from)
+}
+
+fn deserialize_auto_insert_update<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    Option::<AutoInsertValue>::deserialize(deserializer).map(|value| value.map(String::from))
+}
+
+// Keys are device ids; values pick `downmix`, `left`, `right`, or a one-based
+// `channel_<n>`.
+fn normalize_microphone_channels(
+    value: BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, String> {
+    value
+        .into_iter()
+        .filter_map(|(device_id, selection)| {
+            normalize_optional_string(Some(device_id)).map(|device_id| (device_id, selection))
+        })
+        .map(|(device_id, selection)| {
+            let normalized = selection.trim().to_lowercase();
+            let is_numbered_channel = normalized
+                .strip_prefix(MICROPHONE_CHANNEL_NUMBERED_PREFIX)
+                .and_then(|number| number.parse::<usize>().ok())
+                .is_some_and(|number| number > 0);
+            match normalized.as_str() {
+                MICROPHONE_CHANNEL_DOWNMIX | MICROPHONE_CHANNEL_LEFT | MICROPHONE_CHANNEL_RIGHT => {
+                    Ok((device_id, normalized))
+                }
+                _ if is_numbered_channel => Ok((device_id, normalized)),
+                _ => Err(format!(
+                    "Unsupported microphone channel `{normalized}` for `{device_id}`. Expected `{MICROPHONE_CHANNEL_DOWNMIX}`, `{MICROPHONE_CHANNEL_LEFT}`, `{MICROPHONE_CHANNEL_RIGHT}`, or `{MICROPHONE_CHANNEL_NUMBERED_PREFIX}<n>`"
+                )),
+            }
+        })
+        .collect()
+}
+
+// `None` uploads at the capture device's native rate.
+fn normalize_target_sample_rate(value: Option<u32>) -> Result<Option<u32>, String> {
+    match value {
+        Some(rate)
+            if !(MIN_TARGET_SAMPLE_RATE_HZ..=MAX_TARGET_SAMPLE_RATE_HZ).contains(&rate) =>
+        {
+            Err(format!(
+                "Unsupported target sample rate `{rate}`. Expected {MIN_TARGET_SAMPLE_RATE_HZ}-{MAX_TARGET_SAMPLE_RATE_HZ} Hz"
+            ))
+        }
+        value => Ok(value),
+    }
+}
+
+// Keys are app bundle ids (or names for apps without one).
+fn normalize_insertion_pacing(
+    value: BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, String> {
+    value
+        .into_iter()
+        .filter_map(|(app_id, pacing)| {
+            normalize_optional_string(Some(app_id)).map(|app_id| (app_id, pacing))
+        })
+        .map(|(app_id, pacing)| {
+            let normalized = pacing.trim().to_lowercase();
+            match normalized.as_str() {
+                INSERTION_PACING_INSTANT
+                | INSERTION_PACING_GENTLE
+                | INSERTION_PACING_CAUTIOUS
+                | INSERTION_PACING_TYPE_OUT => Ok((app_id, normalized)),
+                _ => Err(format!(
+                    "Unsupported insertion pacing `{normalized}` for `{app_id}`. Expected `{INSERTION_PACING_INSTANT}`, `{INSERTION_PACING_GENTLE}`, `{INSERTION_PACING_CAUTIOUS}`, or `{INSERTION_PACING_TYPE_OUT}`"
+                )),
+            }
+        })
+        .collect()
+}
+
+fn normalize_insertion_suffix(value: String, target: &str) -> Result<String, String> {
+    let normalized = value.trim().to_lowercase();
+    match normalized.as_str() {
+        "" => Ok(INSERTION_SUFFIX_NONE.to_string()),
+        INSERTION_SUFFIX_NONE | INSERTION_SUFFIX_SPACE | INSERTION_SUFFIX_NEWLINE => Ok(normalized),
+        _ => Err(format!(
+            "Unsupported {target} suffix `{normalized}`. Expected `{INSERTION_SUFFIX_NONE}`, `{INSERTION_SUFFIX_SPACE}`, or `{INSERTION_SUFFIX_NEWLINE}`"
+        )),
+    }
+}
+
+// Keys are app bundle ids (or names for apps without one). Overrides that
+// change nothing are dropped.
+fn normalize_insertion_spacing_overrides(
+    value: BTreeMap<String, InsertionSpacingOverride>,
+) -> Result<BTreeMap<String, InsertionSpacingOverride>, String> {
+    value
+        .into_iter()
+        .filter_map(|(app_id, spacing)| {
+            normalize_optional_string(Some(app_id)).map(|app_id| (app_id, spacing))
+        })
+        .filter(|(_, spacing)| *spacing != InsertionSpacingOverride::default())
+        .map(|(app_id, spacing)| {
+            let suffix = spacing
+                .suffix
+                .map(|suffix| normalize_insertion_suffix(suffix, &format!("`{app_id}`")))
+                .transpose()?;
+            Ok((app_id, InsertionSpacingOverride { suffix, ..spacing }))
+        })
+        .collect()
+}
+
+fn normalize_overlay_placement(value: String) -> Result<String, String> {
+    let normalized = normalize_required_string(value, "overlay_placement")?.to_lowercase();
+    match normalized.as_str() {
+        OVERLAY_PLACEMENT_TOP_CENTER
+        | OVERLAY_PLACEMENT_BOTTOM_CENTER
+        | OVERLAY_PLACEMENT_NEAR_CURSOR
+        | OVERLAY_PLACEMENT_NEAR_CARET
+        | OVERLAY_PLACEMENT_FIXED_DISPLAY => Ok(normalized),
+        _ => Err(format!(
+            "Unsupported overlay placement `{normalized}`. Expected `{OVERLAY_PLACEMENT_TOP_CENTER}`, `{OVERLAY_PLACEMENT_BOTTOM_CENTER}`, `{OVERLAY_PLACEMENT_NEAR_CURSOR}`, `{OVERLAY_PLACEMENT_NEAR_CARET}`, or `{OVERLAY_PLACEMENT_FIXED_DISPLAY}`"
+        )),
+    }
+}
+
+// Offsets are keyed by display name; blank names are dropped.
+fn normalize_overlay_offsets(
+    value: BTreeMap<String, OverlayOffset>,
+) -> BTreeMap<String, OverlayOffset> {
+    value
+        .into_iter()
+        .filter_map(|(display, offset)| {
+            normalize_optional_string(Some(display)).map(|display| {
+                let offset = OverlayOffset {
+                    x: offset.x.clamp(-MAX_OVERLAY_OFFSET, MAX_OVERLAY_OFFSET),
+                    y: offset.y.clamp(-MAX_OVERLAY_OFFSET, MAX_OVERLAY_OFFSET),
+                };
+                (display, offset)
+            })
+        })
+        .collect()
+}
+
+// One entry per folder, first wins. A folder with nowhere to put the
+// transcript keeps it in history rather than transcribing for nothing.
+fn normalize_watch_folders(value: Vec<WatchFolder>) -> Vec<WatchFolder> {
+    let mut seen = HashSet::new();
+    value
+        .into_iter()
+        .filter_map(|folder| {
+            let path = normalize_optional_string(Some(folder.path))?;
+            seen.insert(path.clone()).then_some(WatchFolder {
+                path,
+                save_to_history: folder.save_to_history || !folder.write_text_file,
+                ..folder
+            })
+        })
+        .collect()
+}
+
+// Unknown kinds and bindings without a device are dropped; HID ids are
+// lowercased so `05F3:00FF` and `05f3:00ff` are one device.
+fn normalize_input_triggers(value: Vec<InputTriggerBinding>) -> Vec<InputTriggerBinding> {
+    let mut seen = HashSet::new();
+    value
+        .into_iter()
+        .filter_map(|binding| {
+            let kind = match binding.kind.trim().to_lowercase().as_str() {
+                INPUT_TRIGGER_KIND_HID => INPUT_TRIGGER_KIND_HID,
+                INPUT_TRIGGER_KIND_MIDI => INPUT_TRIGGER_KIND_MIDI,
+                _ => return None,
+            };
+            let device_id = normalize_optional_string(Some(binding.device_id))?;
+            let device_id = if kind == INPUT_TRIGGER_KIND_HID {
+                device_id.to_lowercase()
+            } else {
+                device_id
+            };
+            seen.insert((kind, device_id.clone(), binding.control))
+                .then_some(InputTriggerBinding {
+                    kind: kind.to_string(),
+                    device_id,
+                    ..binding
+                })
+        })
+        .collect()
+}
+
+fn normalize_hallucination_filter(value: String) -> String {
+    match value.trim().to_lowercase().as_str() {
+        HALLUCINATION_FILTER_FLAG => HALLUCINATION_FILTER_FLAG.to_string(),
+        HALLUCINATION_FILTER_OFF => HALLUCINATION_FILTER_OFF.to_string(),
+        _ => HALLUCINATION_FILTER_DISCARD.to_string(),
+    }
+}
+
+fn normalize_translation_insert(value: String) -> String {
+    match value.trim().to_lowercase().as_str() {
+        TRANSLATION_INSERT_TRANSLATION => TRANSLATION_INSERT_TRANSLATION.to_string(),
+        _ => TRANSLATION_INSERT_ORIGINAL.to_string(),
+    }
+}
+
+fn normalize_word_count_mode(value: String) -> String {
+    match value.trim().to_lowercase().as_str() {
+        WORD_COUNT_MODE_WHITESPACE => WORD_COUNT_MODE_WHITESPACE.to_string(),
+        _ => WORD_COUNT_MODE_AUTO.to_string(),
+    }
+}
+
 fn lock_error() -> String {
     "Settings store lock was poisoned".to_string()
 }
 
+fn invalid_settings(message: String) -> AppError {
+    AppError::new(ErrorCode::InvalidInput, message)
+}
+
 fn io_lock_error() -> String {
     "Settings store IO lock was poisoned".to_string()
 }
@@ -447,6 +1802,7 @@ mod tests {
     };
 
     use super::*;
+    use crate::redaction::{RedactionAction, RedactionEntity};
 
     fn unique_settings_path(prefix: &str) -> PathBuf {
         let timestamp = SystemTime::now()
@@ -494,17 +1850,141 @@ mod tests {
 
         assert_eq!(defaults.hotkey_shortcut, DEFAULT_HOTKEY_SHORTCUT);
         assert_eq!(defaults.recording_mode, RECORDING_MODE_TOGGLE);
+        assert_eq!(
+            defaults.min_recording_duration_ms,
+            DEFAULT_MIN_RECORDING_DURATION_MS
+        );
+        assert_eq!(
+            defaults.max_recording_duration_mins,
+            DEFAULT_MAX_RECORDING_DURATION_MINS
+        );
+        assert_eq!(defaults.hotkey_name, None);
+        assert!(defaults.input_triggers.is_empty());
         assert_eq!(defaults.microphone_id, None);
+        assert_eq!(defaults.capture_source, CAPTURE_SOURCE_MICROPHONE);
         assert_eq!(defaults.language, None);
+        assert!(defaults.preferred_languages.is_empty());
+        assert!(defaults.language_profiles.is_empty());
+        assert!(!defaults.pin_realtime_language);
         assert_eq!(
             defaults.transcription_provider,
             DEFAULT_TRANSCRIPTION_PROVIDER
         );
+        assert_eq!(defaults.audio_encoding, AUDIO_ENCODING_WAV);
         assert_eq!(defaults.transcription_style, DEFAULT_TRANSCRIPTION_STYLE);
         assert_eq!(defaults.custom_transcription_prompt, "");
-        assert!(defaults.auto_insert);
+        assert!(defaults.context_prompts.is_empty());
+        assert!(defaults.formatting.is_empty());
+        assert!(!defaults.recent_text_context);
+        assert_eq!(
+            defaults.recent_text_context_words,
+            DEFAULT_RECENT_TEXT_CONTEXT_WORDS
+        );
+        assert_eq!(defaults.auto_insert, AUTO_INSERT_ALWAYS);
+        assert!(!defaults.browser_bridge_enabled);
+        assert!(!defaults.meeting_mode);
+        assert_eq!(
+            defaults.meeting_mode_threshold_secs,
+            DEFAULT_MEETING_MODE_THRESHOLD_SECS
+        );
+        assert!(defaults.meeting_diarization);
         assert!(!defaults.launch_at_login);
         assert!(!defaults.onboarding_completed);
+        assert!(!defaults.time_stretch_enabled);
+        assert_eq!(
+            defaults.time_stretch_factor_percent,
+            DEFAULT_TIME_STRETCH_FACTOR_PERCENT
+        );
+        assert!(defaults.silence_trim_enabled);
+        assert_eq!(
+            defaults.silence_trim_padding_ms,
+            DEFAULT_SILENCE_TRIM_PADDING_MS
+        );
+        assert_eq!(
+            defaults.silence_trim_threshold_dbfs,
+            DEFAULT_SILENCE_TRIM_THRESHOLD_DBFS
+        );
+        assert!(!defaults.translation_enabled);
+        assert_eq!(defaults.hallucination_filter, HALLUCINATION_FILTER_DISCARD);
+        assert_eq!(defaults.translation_insert, TRANSLATION_INSERT_ORIGINAL);
+        assert!(!defaults.sound_feedback_enabled);
+        assert_eq!(
+            defaults.sound_feedback_volume_percent,
+            DEFAULT_SOUND_FEEDBACK_VOLUME_PERCENT
+        );
+        assert!(!defaults.overlay_keys_enabled);
+        assert_eq!(
+            defaults.overlay_cancel_key.as_deref(),
+            Some(DEFAULT_OVERLAY_CANCEL_KEY)
+        );
+        assert_eq!(
+            defaults.overlay_stop_key.as_deref(),
+            Some(DEFAULT_OVERLAY_STOP_KEY)
+        );
+        assert_eq!(
+            defaults.overlay_copy_key.as_deref(),
+            Some(DEFAULT_OVERLAY_COPY_KEY)
+        );
+        assert!(!defaults.stats_privacy_mode);
+        assert!(!defaults.noise_suppression);
+        assert!(defaults.microphone_channels.is_empty());
+        assert_eq!(defaults.target_sample_rate, None);
+        assert!(!defaults.transcription_race_mode);
+        assert!(defaults.insertion_pacing.is_empty());
+        assert_eq!(defaults.insertion_prefix, "");
+        assert_eq!(defaults.insertion_suffix, INSERTION_SUFFIX_NONE);
+        assert!(defaults.smart_spacing);
+        assert!(defaults.insertion_spacing_overrides.is_empty());
+        assert!(!defaults.history_encryption);
+        assert_eq!(defaults.insertion_audit_retention_days, 30);
+        assert!(!defaults.private_dictation);
+        assert_eq!(defaults.overlay_private_key, None);
+        assert!(defaults.redaction_rules.is_empty());
+        assert!(!defaults.redact_before_insertion);
+        assert_eq!(defaults.word_count_mode, WORD_COUNT_MODE_AUTO);
+        assert_eq!(defaults.provider_config, ProviderConfig::default());
+        assert!(defaults.transcription_models.is_empty());
+        assert_eq!(defaults.decoding, DecodingOptions::default());
+        assert_eq!(defaults.proxy, ProxySettings::default());
+        assert_eq!(defaults.retro_buffer_secs, 0);
+        assert!(!defaults.microphone_warm_up);
+        assert_eq!(defaults.retro_shortcut, None);
+        assert_eq!(defaults.private_shortcut, None);
+        assert_eq!(
+            defaults.transcription_connect_timeout_secs,
+            DEFAULT_TRANSCRIPTION_CONNECT_TIMEOUT_SECS
+        );
+        assert_eq!(
+            defaults.transcription_timeout_secs,
+            DEFAULT_TRANSCRIPTION_TIMEOUT_SECS
+        );
+        assert_eq!(
+            defaults.typing_chars_per_second,
+            DEFAULT_TYPING_CHARS_PER_SECOND
+        );
+        assert!(defaults.preserve_clipboard);
+        assert_eq!(
+            defaults.clipboard_restore_delay_ms,
+            DEFAULT_CLIPBOARD_RESTORE_DELAY_MS
+        );
+        assert!(defaults.verify_insertion);
+        assert!(!defaults.confirm_before_insert);
+        assert!(!defaults.low_confidence_review);
+        assert_eq!(
+            defaults.low_confidence_threshold_percent,
+            DEFAULT_LOW_CONFIDENCE_THRESHOLD_PERCENT
+        );
+        assert_eq!(defaults.overlay_placement, OVERLAY_PLACEMENT_TOP_CENTER);
+        assert_eq!(defaults.overlay_display, None);
+        assert!(defaults.overlay_offsets.is_empty());
+        assert!(!defaults.control_server_enabled);
+        assert!(!defaults.background_only);
+        assert!(defaults.watch_folders.is_empty());
+        assert!(!defaults.crash_report_upload_enabled);
+        assert_eq!(defaults.crash_report_upload_url, None);
+        assert!(!defaults.telemetry_enabled);
+        assert_eq!(defaults.telemetry_endpoint, None);
+        assert!(!defaults.session_trace_enabled);
     }
 
     #[test]
@@ -552,6 +2032,41 @@ mod tests {
         assert!(!loaded.onboarding_completed);
         assert_eq!(loaded.transcription_style, DEFAULT_TRANSCRIPTION_STYLE);
         assert_eq!(loaded.custom_transcription_prompt, "");
+        assert_eq!(loaded.auto_insert, AUTO_INSERT_ALWAYS);
+        cleanup_settings_path(&settings_path);
+    }
+
+    #[test]
+    fn migrates_legacy_boolean_auto_insert_in_files_and_updates() {
+        let store = SettingsStore::new();
+        let settings_path = unique_settings_path("legacy-auto-insert");
+
+        if let Some(parent_dir) = settings_path.parent() {
+            fs::create_dir_all(parent_dir).expect("legacy test directory should be created");
+        }
+        fs::write(&settings_path, r#"{ "auto_insert": false }"#)
+            .expect("legacy settings file should be written");
+
+        let loaded = store
+            .load_from_path(&settings_path)
+            .expect("legacy settings should load");
+        assert_eq!(loaded.auto_insert, AUTO_INSERT_CLIPBOARD_ONLY);
+
+        let update = serde_json::from_str::<VoiceSettingsUpdate>(r#"{ "auto_insert": true }"#)
+            .expect("legacy boolean update should parse");
+        let updated = store
+            .update_at_path(&settings_path, update)
+            .expect("legacy boolean update should apply");
+        assert_eq!(updated.auto_insert, AUTO_INSERT_ALWAYS);
+
+        let invalid = store.update_at_path(
+            &settings_path,
+            VoiceSettingsUpdate {
+                auto_insert: Some("sometimes".to_string()),
+                ..VoiceSettingsUpdate::default()
+            },
+        );
+        assert!(invalid.is_err());
         cleanup_settings_path(&settings_path);
     }
 
@@ -566,12 +2081,201 @@ mod tests {
                 VoiceSettingsUpdate {
                     hotkey_shortcut: Some("Cmd+Shift+Space".to_string()),
                     recording_mode: Some("toggle".to_string()),
+                    min_recording_duration_ms: Some(60_000),
+                    max_recording_duration_mins: Some(0),
+                    hotkey_name: Some(Some("  Meetings ".to_string())),
+                    input_triggers: Some(vec![
+                        InputTriggerBinding {
+                            device_id: " 05F3:00FF ".to_string(),
+                            control: Some(1),
+                            ..InputTriggerBinding::default()
+                        },
+                        InputTriggerBinding {
+                            device_id: "05f3:00ff".to_string(),
+                            control: Some(1),
+                            ..InputTriggerBinding::default()
+                        },
+                        InputTriggerBinding {
+                            kind: " MIDI ".to_string(),
+                            device_id: "nanoKONTROL2 SLIDER/KNOB".to_string(),
+                            ..InputTriggerBinding::default()
+                        },
+                        InputTriggerBinding {
+                            kind: "bluetooth".to_string(),
+                            device_id: "pedal".to_string(),
+                            ..InputTriggerBinding::default()
+                        },
+                    ]),
                     microphone_id: Some(Some("mic-42".to_string())),
+                    capture_source: Some("Mixed".to_string()),
                     language: Some(Some("en".to_string())),
+                    preferred_languages: Some(vec![
+                        "en".to_string(),
+                        " de ".to_string(),
+                        "EN".to_string(),
+                    ]),
+                    language_profiles: Some(BTreeMap::from([
+                        (
+                            " DE ".to_string(),
+                            LanguageProfile {
+                                dictionary: vec![
+                                    " Straßenbahn ".to_string(),
+                                    "straßenbahn".to_string(),
+                                    String::new(),
+                                ],
+                                transcription_style: Some(" Casual ".to_string()),
+                            },
+                        ),
+                        ("fr".to_string(), LanguageProfile::default()),
+                    ])),
+                    pin_realtime_language: Some(true),
                     transcription_provider: Some("OpenAI".to_string()),
+                    provider_config: Some(ProviderConfig {
+                        base_url: Some("http://localhost:8000/v1/".to_string()),
+                        model: Some("whisper-1".to_string()),
+                        ..ProviderConfig::default()
+                    }),
+                    transcription_models: Some(BTreeMap::from([
+                        (" Gemini ".to_string(), " gemini-2.5-flash ".to_string()),
+                        ("openai".to_string(), "  ".to_string()),
+                    ])),
+                    decoding: Some(DecodingOptions {
+                        temperature_percent: Some(20),
+                        response_format: Some(" JSON ".to_string()),
+                        ..DecodingOptions::default()
+                    }),
+                    proxy: Some(ProxySettings {
+                        url: Some(" socks5://proxy.corp:1080 ".to_string()),
+                        bypass: vec!["intranet.corp, .local".to_string()],
+                        ..ProxySettings::default()
+                    }),
+                    audio_encoding: Some(" Opus ".to_string()),
+                    time_stretch_enabled: Some(true),
+                    time_stretch_factor_percent: Some(200),
+                    silence_trim_enabled: Some(false),
+                    silence_trim_padding_ms: Some(10_000),
+                    silence_trim_threshold_dbfs: Some(-120),
+                    translation_enabled: Some(true),
+                    hallucination_filter: Some(" Flag ".to_string()),
+                    translation_insert: Some(" Translation ".to_string()),
+                    sound_feedback_enabled: Some(true),
+                    sound_feedback_volume_percent: Some(250),
+                    overlay_keys_enabled: Some(true),
+                    overlay_cancel_key: Some(Some("  ".to_string())),
+                    overlay_stop_key: Some(Some(" Return ".to_string())),
+                    overlay_copy_key: None,
+                    overlay_private_key: Some(Some(" KeyP ".to_string())),
+                    overlay_placement: Some(" Near_Caret ".to_string()),
+                    overlay_display: Some(Some("  ".to_string())),
+                    overlay_offsets: Some(BTreeMap::from([
+                        (
+                            "Built-in Retina Display".to_string(),
+                            OverlayOffset { x: 40, y: -90_000 },
+                        ),
+                        (" ".to_string(), OverlayOffset::default()),
+                    ])),
+                    retro_shortcut: Some(Some(" Alt+Shift+R ".to_string())),
+                    private_shortcut: Some(Some(" Alt+Shift+P ".to_string())),
+                    transcription_connect_timeout_secs: Some(0),
+                    transcription_timeout_secs: Some(5_000),
+                    stats_privacy_mode: Some(true),
+                    word_count_mode: Some(" Whitespace ".to_string()),
+                    private_dictation: Some(true),
+                    history_encryption: Some(true),
+                    insertion_audit_retention_days: Some(7),
+                    redaction_rules: Some(vec![RedactionRule {
+                        name: " cards ".to_string(),
+                        entity: RedactionEntity::CreditCard,
+                        pattern: None,
+                        action: RedactionAction::Mask,
+                        enabled: true,
+                    }]),
+                    redact_before_insertion: Some(true),
+                    noise_suppression: Some(true),
+                    retro_buffer_secs: Some(900),
+                    microphone_warm_up: Some(true),
+                    microphone_channels: Some(BTreeMap::from([(
+                        " usb-interface ".to_string(),
+                        " Right ".to_string(),
+                    )])),
+                    target_sample_rate: Some(Some(16_000)),
+                    transcription_race_mode: Some(true),
+                    insertion_pacing: Some(BTreeMap::from([(
+                        "com.google.Chrome".to_string(),
+                        "Gentle".to_string(),
+                    )])),
+                    insertion_prefix: Some("> ".to_string()),
+                    insertion_suffix: Some(" Space ".to_string()),
+                    smart_spacing: Some(false),
+                    insertion_spacing_overrides: Some(BTreeMap::from([
+                        (
+                            "com.apple.Terminal".to_string(),
+                            InsertionSpacingOverride {
+                                suffix: Some(" None ".to_string()),
+                                ..InsertionSpacingOverride::default()
+                            },
+                        ),
+                        ("Notes".to_string(), InsertionSpacingOverride::default()),
+                    ])),
+                    typing_chars_per_second: Some(0),
+                    preserve_clipboard: Some(false),
+                    clipboard_restore_delay_ms: Some(60_000),
+                    verify_insertion: Some(false),
                     transcription_style: Some("Casual".to_string()),
                     custom_transcription_prompt: Some("   Keep filler words.  ".to_string()),
-                    auto_insert: Some(false),
+                    context_prompts: Some(BTreeMap::from([
+                        (
+                            " Clean ".to_string(),
+                            " Kubernetes, Dana Whitfield ".to_string(),
+                        ),
+                        ("casual".to_string(), "   ".to_string()),
+                    ])),
+                    formatting: Some(BTreeMap::from([
+                        (
+                            " Clean ".to_string(),
+                            FormattingOptions {
+                                sentence_case: true,
+                                numerals: true,
+                                ..FormattingOptions::default()
+                            },
+                        ),
+                        ("verbatim".to_string(), FormattingOptions::default()),
+                    ])),
+                    recent_text_context: Some(true),
+                    recent_text_context_words: Some(10_000),
+                    auto_insert: Some(" If_Focused ".to_string()),
+                    confirm_before_insert: Some(true),
+                    low_confidence_review: Some(true),
+                    low_confidence_threshold_percent: Some(150),
+                    browser_bridge_enabled: Some(true),
+                    control_server_enabled: Some(true),
+                    background_only: Some(true),
+                    watch_folders: Some(vec![
+                        WatchFolder {
+                            path: " /Users/me/Voice Memos/ ".to_string(),
+                            write_text_file: false,
+                            save_to_history: false,
+                            ..WatchFolder::default()
+                        },
+                        WatchFolder {
+                            path: "/Users/me/Voice Memos/".to_string(),
+                            ..WatchFolder::default()
+                        },
+                        WatchFolder {
+                            path: "  ".to_string(),
+                            ..WatchFolder::default()
+                        },
+                    ]),
+                    meeting_mode: Some(true),
+                    meeting_mode_threshold_secs: Some(5),
+                    meeting_diarization: Some(false),
+                    crash_report_upload_enabled: Some(true),
+                    crash_report_upload_url: Some(Some(
+                        " https://crashes.example.com ".to_string(),
+                    )),
+                    telemetry_enabled: Some(true),
+                    telemetry_endpoint: Some(Some(" ".to_string())),
+                    session_trace_enabled: Some(true),
                     launch_at_login: Some(true),
                     onboarding_completed: Some(true),
                 },
@@ -582,14 +2286,219 @@ mod tests {
 
         assert_eq!(updated.hotkey_shortcut, "Cmd+Shift+Space");
         assert_eq!(updated.recording_mode, RECORDING_MODE_TOGGLE);
+        assert_eq!(
+            updated.min_recording_duration_ms,
+            MAX_MIN_RECORDING_DURATION_MS
+        );
+        assert_eq!(updated.max_recording_duration_mins, 0);
+        assert_eq!(updated.hotkey_name.as_deref(), Some("Meetings"));
+        assert_eq!(
+            updated.input_triggers,
+            vec![
+                InputTriggerBinding {
+                    device_id: "05f3:00ff".to_string(),
+                    control: Some(1),
+                    ..InputTriggerBinding::default()
+                },
+                InputTriggerBinding {
+                    kind: INPUT_TRIGGER_KIND_MIDI.to_string(),
+                    device_id: "nanoKONTROL2 SLIDER/KNOB".to_string(),
+                    ..InputTriggerBinding::default()
+                },
+            ]
+        );
         assert_eq!(updated.microphone_id.as_deref(), Some("mic-42"));
+        assert_eq!(updated.capture_source, CAPTURE_SOURCE_MIXED);
         assert_eq!(updated.language.as_deref(), Some("en"));
+        assert_eq!(updated.preferred_languages, ["en", "de"]);
+        assert_eq!(
+            updated.language_profiles,
+            BTreeMap::from([(
+                "de".to_string(),
+                LanguageProfile {
+                    dictionary: vec!["Straßenbahn".to_string()],
+                    transcription_style: Some(TRANSCRIPTION_STYLE_CASUAL.to_string()),
+                },
+            )])
+        );
+        assert!(updated.pin_realtime_language);
         assert_eq!(updated.transcription_provider, "openai");
+        assert_eq!(
+            updated.proxy.url.as_deref(),
+            Some("socks5://proxy.corp:1080")
+        );
+        assert_eq!(updated.proxy.bypass, vec!["intranet.corp", ".local"]);
+        assert_eq!(updated.audio_encoding, AUDIO_ENCODING_OPUS);
         assert_eq!(updated.transcription_style, "casual");
         assert_eq!(updated.custom_transcription_prompt, "Keep filler words.");
-        assert!(!updated.auto_insert);
+        assert_eq!(
+            updated.context_prompts,
+            BTreeMap::from([(
+                TRANSCRIPTION_STYLE_CLEAN.to_string(),
+                "Kubernetes, Dana Whitfield".to_string(),
+            )])
+        );
+        assert_eq!(
+            updated.formatting,
+            BTreeMap::from([(
+                TRANSCRIPTION_STYLE_CLEAN.to_string(),
+                FormattingOptions {
+                    sentence_case: true,
+                    numerals: true,
+                    ..FormattingOptions::default()
+                },
+            )])
+        );
+        assert!(updated.recent_text_context);
+        assert_eq!(
+            updated.recent_text_context_words,
+            MAX_RECENT_TEXT_CONTEXT_WORDS
+        );
+        assert_eq!(updated.auto_insert, AUTO_INSERT_IF_FOCUSED);
+        assert!(updated.browser_bridge_enabled);
+        assert!(updated.meeting_mode);
+        assert_eq!(
+            updated.meeting_mode_threshold_secs,
+            MIN_MEETING_MODE_THRESHOLD_SECS
+        );
+        assert!(!updated.meeting_diarization);
+        assert!(updated.crash_report_upload_enabled);
+        assert_eq!(
+            updated.crash_report_upload_url.as_deref(),
+            Some("https://crashes.example.com")
+        );
+        assert!(updated.telemetry_enabled);
+        assert_eq!(updated.telemetry_endpoint, None);
+        assert!(updated.session_trace_enabled);
         assert!(updated.launch_at_login);
         assert!(updated.onboarding_completed);
+        assert!(updated.time_stretch_enabled);
+        assert_eq!(
+            updated.time_stretch_factor_percent,
+            MAX_TIME_STRETCH_FACTOR_PERCENT
+        );
+        assert!(!updated.silence_trim_enabled);
+        assert_eq!(updated.silence_trim_padding_ms, MAX_SILENCE_TRIM_PADDING_MS);
+        assert_eq!(
+            updated.silence_trim_threshold_dbfs,
+            MIN_SILENCE_TRIM_THRESHOLD_DBFS
+        );
+        assert!(updated.translation_enabled);
+        assert_eq!(updated.hallucination_filter, HALLUCINATION_FILTER_FLAG);
+        assert_eq!(updated.translation_insert, TRANSLATION_INSERT_TRANSLATION);
+        assert!(updated.sound_feedback_enabled);
+        assert_eq!(
+            updated.sound_feedback_volume_percent,
+            MAX_SOUND_FEEDBACK_VOLUME_PERCENT
+        );
+        assert!(updated.overlay_keys_enabled);
+        assert_eq!(updated.overlay_cancel_key, None);
+        assert_eq!(updated.overlay_stop_key.as_deref(), Some("Return"));
+        assert!(updated.stats_privacy_mode);
+        assert!(updated.noise_suppression);
+        assert_eq!(
+            updated
+                .microphone_channels
+                .get("usb-interface")
+                .map(String::as_str),
+            Some("right")
+        );
+        assert_eq!(updated.target_sample_rate, Some(16_000));
+        assert!(updated.transcription_race_mode);
+        assert_eq!(
+            updated
+                .insertion_pacing
+                .get("com.google.Chrome")
+                .map(String::as_str),
+            Some("gentle")
+        );
+        assert_eq!(updated.insertion_prefix, "> ");
+        assert_eq!(updated.insertion_suffix, INSERTION_SUFFIX_SPACE);
+        assert!(!updated.smart_spacing);
+        assert_eq!(
+            updated.insertion_spacing_overrides,
+            BTreeMap::from([(
+                "com.apple.Terminal".to_string(),
+                InsertionSpacingOverride {
+                    suffix: Some(INSERTION_SUFFIX_NONE.to_string()),
+                    ..InsertionSpacingOverride::default()
+                },
+            )])
+        );
+        assert!(updated.history_encryption);
+        assert_eq!(updated.insertion_audit_retention_days, 7);
+        assert_eq!(updated.redaction_rules[0].name, "cards");
+        assert!(updated.redact_before_insertion);
+        assert!(updated.private_dictation);
+        assert_eq!(updated.overlay_private_key.as_deref(), Some("KeyP"));
+        assert_eq!(updated.word_count_mode, WORD_COUNT_MODE_WHITESPACE);
+        assert_eq!(
+            updated.provider_config.base_url.as_deref(),
+            Some("http://localhost:8000/v1")
+        );
+        assert_eq!(
+            updated.transcription_models,
+            BTreeMap::from([("gemini".to_string(), "gemini-2.5-flash".to_string())])
+        );
+        let openai_config = updated.provider_config_for(DEFAULT_TRANSCRIPTION_PROVIDER);
+        assert_eq!(openai_config.model.as_deref(), Some("whisper-1"));
+        assert_eq!(
+            openai_config.base_url.as_deref(),
+            Some("http://localhost:8000/v1")
+        );
+        let gemini_config = updated.provider_config_for(GEMINI_TRANSCRIPTION_PROVIDER);
+        assert_eq!(gemini_config.model.as_deref(), Some("gemini-2.5-flash"));
+        assert_eq!(gemini_config.base_url, None);
+        assert_eq!(updated.decoding.temperature_percent, Some(20));
+        assert_eq!(updated.decoding.response_format.as_deref(), Some("json"));
+        assert_eq!(updated.retro_buffer_secs, MAX_RETRO_BUFFER_SECS);
+        assert!(updated.microphone_warm_up);
+        assert_eq!(updated.retro_shortcut.as_deref(), Some("Alt+Shift+R"));
+        assert_eq!(updated.private_shortcut.as_deref(), Some("Alt+Shift+P"));
+        assert_eq!(
+            updated.transcription_connect_timeout_secs,
+            MIN_TRANSCRIPTION_TIMEOUT_SECS
+        );
+        assert_eq!(
+            updated.transcription_timeout_secs,
+            MAX_TRANSCRIPTION_TIMEOUT_SECS
+        );
+        assert_eq!(updated.typing_chars_per_second, MIN_TYPING_CHARS_PER_SECOND);
+        assert!(!updated.preserve_clipboard);
+        assert_eq!(
+            updated.clipboard_restore_delay_ms,
+            MAX_CLIPBOARD_RESTORE_DELAY_MS
+        );
+        assert!(!updated.verify_insertion);
+        assert!(updated.confirm_before_insert);
+        assert!(updated.low_confidence_review);
+        assert_eq!(
+            updated.low_confidence_threshold_percent,
+            MAX_LOW_CONFIDENCE_THRESHOLD_PERCENT
+        );
+        assert_eq!(updated.overlay_placement, OVERLAY_PLACEMENT_NEAR_CARET);
+        assert_eq!(updated.overlay_display, None);
+        assert_eq!(
+            updated.overlay_offsets,
+            BTreeMap::from([(
+                "Built-in Retina Display".to_string(),
+                OverlayOffset {
+                    x: 40,
+                    y: -MAX_OVERLAY_OFFSET
+                }
+            )])
+        );
+        assert!(updated.control_server_enabled);
+        assert!(updated.background_only);
+        assert_eq!(
+            updated.watch_folders,
+            vec![WatchFolder {
+                path: "/Users/me/Voice Memos/".to_string(),
+                enabled: true,
+                write_text_file: false,
+                save_to_history: true,
+            }]
+        );
         assert_eq!(reloaded, updated);
 
         cleanup_settings_path(&settings_path);
@@ -624,6 +2533,47 @@ mod tests {
 
         assert_eq!(cleared.microphone_id, None);
         assert_eq!(cleared.language, None);
+        assert!(cleared.preferred_languages.is_empty());
+
+        cleanup_settings_path(&settings_path);
+    }
+
+    #[test]
+    fn single_language_becomes_the_first_preferred_language() {
+        let store = SettingsStore::new();
+        let settings_path = unique_settings_path("preferred-languages");
+        if let Some(parent_dir) = settings_path.parent() {
+            fs::create_dir_all(parent_dir).expect("test directory should be created");
+        }
+        fs::write(&settings_path, r#"{ "language": "fr" }"#)
+            .expect("legacy settings file should be written");
+
+        let loaded = store
+            .load_from_path(&settings_path)
+            .expect("legacy language should load");
+        assert_eq!(loaded.preferred_languages, ["fr"]);
+
+        let updated = store
+            .update_at_path(
+                &settings_path,
+                VoiceSettingsUpdate {
+                    preferred_languages: Some(vec!["de".to_string(), "fr".to_string()]),
+                    ..VoiceSettingsUpdate::default()
+                },
+            )
+            .expect("preferred languages should update");
+        assert_eq!(updated.language.as_deref(), Some("de"));
+
+        let picked = store
+            .update_at_path(
+                &settings_path,
+                VoiceSettingsUpdate {
+                    language: Some(Some("fr".to_string())),
+                    ..VoiceSettingsUpdate::default()
+                },
+            )
+            .expect("language should update");
+        assert_eq!(picked.preferred_languages, ["fr", "de"]);
 
         cleanup_settings_path(&settings_path);
     }
@@ -647,6 +2597,34 @@ mod tests {
         cleanup_settings_path(&settings_path);
     }
 
+    #[test]
+    fn update_rejects_stale_expected_revision() {
+        let store = SettingsStore::new();
+        let settings_path = unique_settings_path("stale-revision");
+        store
+            .load_from_path(&settings_path)
+            .expect("settings should load");
+        let loaded_revision = store.snapshot().expect("snapshot should read").revision;
+        let update = || VoiceSettingsUpdate {
+            meeting_mode: Some(true),
+            ..VoiceSettingsUpdate::default()
+        };
+
+        store
+            .update_checked_at_path(&settings_path, update(), Some(loaded_revision))
+            .expect("fresh revision should apply");
+        let snapshot = store.snapshot().expect("snapshot should read");
+        assert_eq!(snapshot.revision, loaded_revision + 1);
+        assert!(snapshot.settings.meeting_mode);
+
+        let error = store
+            .update_checked_at_path(&settings_path, update(), Some(loaded_revision))
+            .expect_err("stale revision should conflict");
+        assert_eq!(error.code, ErrorCode::Conflict);
+        assert_eq!(store.revision(), loaded_revision + 1);
+        cleanup_settings_path(&settings_path);
+    }
+
     #[test]
     fn update_rejects_invalid_recording_mode() {
         let store = SettingsStore::new();
@@ -662,7 +2640,46 @@ mod tests {
             )
             .expect_err("invalid mode should fail");
 
-        assert!(error.contains("Unsupported recording mode"));
+        assert_eq!(error.code, ErrorCode::InvalidInput);
+        assert!(error.message.contains("Unsupported recording mode"));
+        cleanup_settings_path(&settings_path);
+    }
+
+    #[test]
+    fn update_rejects_unknown_capture_source() {
+        let store = SettingsStore::new();
+        let settings_path = unique_settings_path("invalid-capture-source");
+
+        let error = store
+            .update_at_path(
+                &settings_path,
+                VoiceSettingsUpdate {
+                    capture_source: Some("webcam".to_string()),
+                    ..VoiceSettingsUpdate::default()
+                },
+            )
+            .expect_err("unsupported capture source should fail");
+
+        assert!(error.message.contains("Unsupported capture source"));
+        cleanup_settings_path(&settings_path);
+    }
+
+    #[test]
+    fn update_reads_the_old_system_capture_source_as_loopback() {
+        let store = SettingsStore::new();
+        let settings_path = unique_settings_path("legacy-capture-source");
+
+        let updated = store
+            .update_at_path(
+                &settings_path,
+                VoiceSettingsUpdate {
+                    capture_source: Some(" System ".to_string()),
+                    ..VoiceSettingsUpdate::default()
+                },
+            )
+            .expect("legacy capture source should be accepted");
+
+        assert_eq!(updated.capture_source, CAPTURE_SOURCE_LOOPBACK);
         cleanup_settings_path(&settings_path);
     }
 
@@ -681,7 +2698,37 @@ mod tests {
             )
             .expect_err("unsupported provider should fail");
 
-        assert!(error.contains("Unsupported transcription provider"));
+        assert!(error.message.contains("Unsupported transcription provider"));
+
+        let error = store
+            .update_at_path(
+                &settings_path,
+                VoiceSettingsUpdate {
+                    transcription_models: Some(BTreeMap::from([(
+                        "anthropic".to_string(),
+                        "claude".to_string(),
+                    )])),
+                    ..VoiceSettingsUpdate::default()
+                },
+            )
+            .expect_err("a model for an unsupported provider should fail");
+
+        assert!(error.message.contains("Unsupported transcription provider"));
+
+        let error = store
+            .update_at_path(
+                &settings_path,
+                VoiceSettingsUpdate {
+                    decoding: Some(DecodingOptions {
+                        beam_size: Some(64),
+                        ..DecodingOptions::default()
+                    }),
+                    ..VoiceSettingsUpdate::default()
+                },
+            )
+            .expect_err("an out of range beam size should fail");
+
+        assert!(error.message.contains("Beam size"));
         cleanup_settings_path(&settings_path);
     }
 
@@ -728,13 +2775,13 @@ mod tests {
             .update_at_path(
                 &settings_path,
                 VoiceSettingsUpdate {
-                    auto_insert: Some(false),
+                    auto_insert: Some(AUTO_INSERT_CLIPBOARD_ONLY.to_string()),
                     ..VoiceSettingsUpdate::default()
                 },
             )
             .expect("update should recover malformed settings");
 
-        assert!(!updated.auto_insert);
+        assert_eq!(updated.auto_insert, AUTO_INSERT_CLIPBOARD_ONLY);
         assert_eq!(
             updated.transcription_provider,
             DEFAULT_TRANSCRIPTION_PROVIDER
@@ -742,4 +2789,46 @@ mod tests {
         assert_eq!(corrupt_backup_paths(&settings_path).len(), 1);
         cleanup_settings_path(&settings_path);
     }
+
+    #[test]
+    fn reloads_hand_edits_but_ignores_own_writes_and_invalid_files() {
+        let store = SettingsStore::new();
+        let settings_path = unique_settings_path("reload");
+        let touch = |contents: &str, seconds_ahead: u64| {
+            fs::write(&settings_path, contents).expect("settings should be written");
+            fs::File::options()
+                .write(true)
+                .open(&settings_path)
+                .and_then(|file| {
+                    file.set_modified(
+                        SystemTime::now() + std::time::Duration::from_secs(seconds_ahead),
+                    )
+                })
+                .expect("modification time should be set");
+        };
+
+        let saved = store
+            .update_at_path(&settings_path, VoiceSettingsUpdate::default())
+            .expect("settings should save");
+        assert_eq!(store.reload_if_changed_at_path(&settings_path), Ok(None));
+
+        let mut edited = saved.clone();
+        edited.auto_insert = AUTO_INSERT_CLIPBOARD_ONLY.to_string();
+        touch(
+            &serde_json::to_string(&edited).expect("settings should serialize"),
+            10,
+        );
+        assert_eq!(
+            store.reload_if_changed_at_path(&settings_path),
+            Ok(Some(edited.clone()))
+        );
+        assert_eq!(store.current(), edited);
+
+        touch("{ half typed", 20);
+        assert!(store.reload_if_changed_at_path(&settings_path).is_err());
+        assert_eq!(store.reload_if_changed_at_path(&settings_path), Ok(None));
+        assert_eq!(store.current(), edited);
+        assert!(corrupt_backup_paths(&settings_path).is_empty());
+        cleanup_settings_path(&settings_path);
+    }
 }
@@ -0,0 +1,224 @@
+use std::ops::Range;
+
+use tracing::{debug, warn};
+
+use crate::audio_capture_service::pcm16_to_wav_bytes;
+use crate::settings_store::VoiceSettings;
+use crate::transcription::{chunked::parse_pcm16_mono_wav, TranscriptionResult};
+
+const FRAME_MS: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SilenceTrimConfig {
+    pub threshold_dbfs: i32,
+    pub padding_ms: u32,
+}
+
+impl SilenceTrimConfig {
+    pub fn from_settings(settings: &VoiceSettings) -> Self {
+        Self {
+            threshold_dbfs: settings.silence_trim_threshold_dbfs,
+            padding_ms: settings.silence_trim_padding_ms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrimmedWav {
+    pub wav_bytes: Vec<u8>,
+    pub leading_ms: u64,
+    pub trailing_ms: u64,
+}
+
+impl TrimmedWav {
+    pub fn trimmed_ms(&self) -> u64 {
+        self.leading_ms + self.trailing_ms
+    }
+
+    fn untouched(wav_bytes: Vec<u8>) -> Self {
+        Self {
+            wav_bytes,
+            leading_ms: 0,
+            trailing_ms: 0,
+        }
+    }
+}
+
+// Best-effort: recordings that cannot be parsed, or that never rise above the
+// threshold, are returned unchanged.
+pub fn trim_wav_silence(wav_bytes: Vec<u8>, config: SilenceTrimConfig) -> TrimmedWav {
+    let wav = match parse_pcm16_mono_wav(&wav_bytes) {
+        Ok(wav) => wav,
+        Err(error) => {
+            warn!(%error, "skipping silence trim for unparseable recording");
+            return TrimmedWav::untouched(wav_bytes);
+        }
+    };
+    let Some(range) = speech_range(&wav.samples, wav.sample_rate_hz, config) else {
+        debug!("no audio above the silence threshold; leaving recording untrimmed");
+        return TrimmedWav::untouched(wav_bytes);
+    };
+    if range.len() == wav.samples.len() {
+        return TrimmedWav::untouched(wav_bytes);
+    }
+
+    let samples_to_ms =
+        |samples: usize| samples as u64 * 1000 / u64::from(wav.sample_rate_hz.max(1));
+    let leading_ms = samples_to_ms(range.start);
+    let trailing_ms = samples_to_ms(wav.samples.len() - range.end);
+    match pcm16_to_wav_bytes(&wav.samples[range], wav.sample_rate_hz, 1) {
+        Ok(trimmed_bytes) => {
+            debug!(
+                leading_ms,
+                trailing_ms,
+                original_bytes = wav_bytes.len(),
+                trimmed_bytes = trimmed_bytes.len(),
+                "trimmed silence from recording boundaries"
+            );
+            TrimmedWav {
+                wav_bytes: trimmed_bytes,
+                leading_ms,
+                trailing_ms,
+            }
+        }
+        Err(error) => {
+            warn!(%error, "failed to encode trimmed recording; uploading it untrimmed");
+            TrimmedWav::untouched(wav_bytes)
+        }
+    }
+}
+
+// Provider timestamps are relative to the trimmed upload; shift them back so
+// they line up with the original recording.
+pub fn shift_timeline(result: &mut TranscriptionResult, offset_secs: f64) {
+    if offset_secs <= 0.0 {
+        return;
+    }
+    for segment in &mut result.segments {
+        segment.start_secs += offset_secs;
+        segment.end_secs += offset_secs;
+    }
+    for word in &mut result.words {
+        word.start_secs += offset_secs;
+        word.end_secs += offset_secs;
+    }
+}
+
+// Voice activity measured with the same frame threshold the trim uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpeechActivity {
+    pub speech_ms: u64,
+    pub total_ms: u64,
+}
+
+impl SpeechActivity {
+    pub fn speech_ratio(&self) -> f64 {
+        if self.total_ms == 0 {
+            return 0.0;
+        }
+        self.speech_ms as f64 / self.total_ms as f64
+    }
+}
+
+pub fn measure_speech_activity(wav_bytes: &[u8], threshold_dbfs: i32) -> Option<SpeechActivity> {
+    let wav = parse_pcm16_mono_wav(wav_bytes).ok()?;
+    let frame_len = frame_len(wav.sample_rate_hz);
+    let loud_frames = loud_frames(&wav.samples, frame_len, threshold_dbfs)
+        .filter(|loud| *loud)
+        .count();
+    let total_ms = wav.samples.len() as u64 * 1000 / u64::from(wav.sample_rate_hz.max(1));
+    Some(SpeechActivity {
+        // The last frame may be partial.
+        speech_ms: (loud_frames as u64 * u64::from(FRAME_MS)).min(total_ms),
+        total_ms,
+    })
+}
+
+fn frame_len(sample_rate_hz: u32) -> usize {
+    ((sample_rate_hz * FRAME_MS / 1000) as usize).max(1)
+}
+
+fn loud_frames(
+    samples: &[i16],
+    frame_len: usize,
+    threshold_dbfs: i32,
+) -> impl Iterator<Item = bool> + '_ {
+    let threshold = f64::from(i16::MAX) * 10f64.powf(f64::from(threshold_dbfs) / 20.0);
+    samples.chunks(frame_len).map(move |frame| {
+        let sum = frame
+            .iter()
+            .map(|sample| f64::from(*sample).powi(2))
+            .sum::<f64>();
+        (sum / frame.len() as f64).sqrt() >= threshold
+    })
+}
+
+fn speech_range(
+    samples: &[i16],
+    sample_rate_hz: u32,
+    config: SilenceTrimConfig,
+) -> Option<Range<usize>> {
+    let frame_len = frame_len(sample_rate_hz);
+    let frames = loud_frames(samples, frame_len, config.threshold_dbfs).collect::<Vec<_>>();
+    let first = frames.iter().position(|loud| *loud)?;
+    let last = frames.iter().rposition(|loud| *loud)?;
+    let padding = (u64::from(sample_rate_hz) * u64::from(config.padding_ms) / 1000) as usize;
+
+    let start = (first * frame_len).saturating_sub(padding);
+    let end = ((last + 1) * frame_len)
+        .saturating_add(padding)
+        .min(samples.len());
+    Some(start..end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{measure_speech_activity, trim_wav_silence, SilenceTrimConfig};
+    use crate::audio_capture_service::pcm16_to_wav_bytes;
+    use crate::transcription::chunked::parse_pcm16_mono_wav;
+
+    const CONFIG: SilenceTrimConfig = SilenceTrimConfig {
+        threshold_dbfs: -45,
+        padding_ms: 100,
+    };
+
+    fn clip(leading_ms: usize, speech_ms: usize, trailing_ms: usize) -> Vec<u8> {
+        let per_ms = 16;
+        let mut samples = vec![3i16; leading_ms * per_ms];
+        samples.extend(
+            (0..speech_ms * per_ms).map(|index| ((index as f32 * 0.08).sin() * 8_000.0) as i16),
+        );
+        samples.extend(vec![-3i16; trailing_ms * per_ms]);
+        pcm16_to_wav_bytes(&samples, 16_000, 1).expect("wav should encode")
+    }
+
+    #[test]
+    fn trims_boundary_silence_but_keeps_padding() {
+        let trimmed = trim_wav_silence(clip(1_000, 500, 2_000), CONFIG);
+
+        assert_eq!(trimmed.leading_ms, 900);
+        assert_eq!(trimmed.trailing_ms, 1_900);
+        let wav = parse_pcm16_mono_wav(&trimmed.wav_bytes).expect("trimmed wav should parse");
+        assert_eq!(wav.samples.len(), 700 * 16);
+    }
+
+    #[test]
+    fn leaves_all_silent_recordings_untouched() {
+        let original = clip(1_000, 0, 1_000);
+
+        let trimmed = trim_wav_silence(original.clone(), CONFIG);
+
+        assert_eq!(trimmed.trimmed_ms(), 0);
+        assert_eq!(trimmed.wav_bytes, original);
+    }
+
+    #[test]
+    fn measures_speech_activity_against_threshold() {
+        let activity = measure_speech_activity(&clip(1_000, 500, 2_500), CONFIG.threshold_dbfs)
+            .expect("wav should parse");
+
+        assert_eq!(activity.total_ms, 4_000);
+        assert_eq!(activity.speech_ms, 500);
+        assert!((activity.speech_ratio() - 0.125).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,228 @@
+use std::ops::Range;
+
+use super::Snippet;
+
+// The whole rest of the utterance after the trigger.
+const TEXT_PLACEHOLDER: &str = "text";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Word {
+    text: String,
+    span: Range<usize>,
+}
+
+// Words are runs of letters and digits, lowercased, so "Sign-off." and
+// "sign off" compare equal.
+fn words(text: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (index, character) in text.char_indices() {
+        match (character.is_alphanumeric(), start) {
+            (true, None) => start = Some(index),
+            (false, Some(word_start)) => {
+                words.push(Word {
+                    text: text[word_start..index].to_lowercase(),
+                    span: word_start..index,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        words.push(Word {
+            text: text[word_start..].to_lowercase(),
+            span: word_start..text.len(),
+        });
+    }
+    words
+}
+
+pub(super) fn trigger_words(trigger: &str) -> Vec<String> {
+    words(trigger).into_iter().map(|word| word.text).collect()
+}
+
+fn starts_with_words(haystack: &[Word], needle: &[String]) -> bool {
+    !needle.is_empty()
+        && haystack.len() >= needle.len()
+        && haystack
+            .iter()
+            .zip(needle)
+            .all(|(word, expected)| word.text == *expected)
+}
+
+fn is_separator(character: char) -> bool {
+    character.is_whitespace() || matches!(character, '.' | ',' | ';' | ':' | '!' | '?' | '-')
+}
+
+// A trigger only counts at the very start of the utterance, and the longest
+// matching trigger wins so "standup update" beats "standup".
+pub(super) fn expand(snippets: &[Snippet], text: &str) -> Option<(String, String)> {
+    let utterance = words(text);
+    let (snippet, trigger_len) = snippets
+        .iter()
+        .map(|snippet| (snippet, trigger_words(&snippet.trigger)))
+        .filter(|(_, trigger)| starts_with_words(&utterance, trigger))
+        .max_by_key(|(_, trigger)| trigger.len())
+        .map(|(snippet, trigger)| (snippet, trigger.len()))?;
+
+    let rest_start = utterance[trigger_len - 1].span.end;
+    let rest = text[rest_start..].trim_start_matches(is_separator);
+    Some((snippet.id.clone(), render(&snippet.template, rest)))
+}
+
+fn placeholders(template: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut remaining = template;
+    while let Some(open) = remaining.find('{') {
+        let after_open = &remaining[open + 1..];
+        let Some(close) = after_open.find('}') else {
+            break;
+        };
+        let name = &after_open[..close];
+        if is_placeholder_name(name) && !names.iter().any(|existing| existing == name) {
+            names.push(name.to_string());
+        }
+        remaining = &after_open[close + 1..];
+    }
+    names
+}
+
+fn is_placeholder_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|character| character.is_alphanumeric() || character == '_')
+}
+
+// Named placeholders are filled by saying the name followed by the value:
+// "{yesterday}" takes everything after "yesterday" up to the next spoken
+// placeholder name. Underscores in a name are spoken as spaces.
+fn fill_named(rest: &str, names: &[String]) -> Vec<(String, String)> {
+    let rest_words = words(rest);
+    let mut markers: Vec<(usize, usize, &String)> = Vec::new();
+    for name in names.iter().filter(|name| *name != TEXT_PLACEHOLDER) {
+        let spoken = trigger_words(&name.replace('_', " "));
+        let found = (0..rest_words.len()).find(|&start| {
+            starts_with_words(&rest_words[start..], &spoken)
+                && !markers.iter().any(|(other_start, other_end, _)| {
+                    start < *other_end && *other_start < start + spoken.len()
+                })
+        });
+        if let Some(start) = found {
+            markers.push((start, start + spoken.len(), name));
+        }
+    }
+    markers.sort_by_key(|(start, _, _)| *start);
+
+    markers
+        .iter()
+        .enumerate()
+        .map(|(index, (_, end, name))| {
+            let value_start = rest_words[end - 1].span.end;
+            let value_end = markers
+                .get(index + 1)
+                .map_or(rest.len(), |(next_start, _, _)| {
+                    rest_words[*next_start].span.start
+                });
+            (
+                (*name).clone(),
+                rest[value_start..value_end]
+                    .trim_matches(is_separator)
+                    .to_string(),
+            )
+        })
+        .collect()
+}
+
+fn render(template: &str, rest: &str) -> String {
+    let names = placeholders(template);
+    if names.is_empty() {
+        return if rest.is_empty() {
+            template.to_string()
+        } else {
+            format!("{template} {rest}")
+        };
+    }
+
+    let values = fill_named(rest, &names);
+    let mut rendered = template.to_string();
+    for name in &names {
+        let value = if name == TEXT_PLACEHOLDER {
+            rest
+        } else {
+            values
+                .iter()
+                .find(|(filled, _)| filled == name)
+                .map_or("", |(_, value)| value.as_str())
+        };
+        rendered = rendered.replace(&format!("{{{name}}}"), value);
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand, render};
+    use crate::snippets_store::Snippet;
+
+    fn snippet(id: &str, trigger: &str, template: &str) -> Snippet {
+        Snippet {
+            id: id.to_string(),
+            trigger: trigger.to_string(),
+            template: template.to_string(),
+        }
+    }
+
+    #[test]
+    fn matches_longest_trigger_at_start_ignoring_case_and_punctuation() {
+        let snippets = vec![
+            snippet("sign-off", "my email sign-off", "Best,\nSawyer"),
+            snippet("standup", "standup", "Standup: {text}"),
+            snippet(
+                "standup-update",
+                "standup update",
+                "Yesterday: {yesterday}\nToday: {today}\nBlockers: {blockers}",
+            ),
+        ];
+
+        assert_eq!(
+            expand(&snippets, "My email sign off."),
+            Some(("sign-off".to_string(), "Best,\nSawyer".to_string()))
+        );
+        assert_eq!(
+            expand(
+                &snippets,
+                "Standup update. Yesterday I fixed the login bug, today reviews, blockers none."
+            ),
+            Some((
+                "standup-update".to_string(),
+                "Yesterday: I fixed the login bug\nToday: reviews\nBlockers: none".to_string()
+            ))
+        );
+        assert_eq!(
+            expand(&snippets, "Standup, shipped the beta."),
+            Some((
+                "standup".to_string(),
+                "Standup: shipped the beta.".to_string()
+            ))
+        );
+        assert_eq!(expand(&snippets, "Send my email sign-off"), None);
+    }
+
+    #[test]
+    fn unfilled_placeholders_are_blank_and_plain_templates_keep_the_rest() {
+        assert_eq!(
+            render("Hi {name}, {greeting}", "greeting thanks for the note"),
+            "Hi , thanks for the note"
+        );
+        assert_eq!(
+            render("Best,\nSawyer", "see you soon"),
+            "Best,\nSawyer see you soon"
+        );
+        assert_eq!(
+            render("Follow up with {next_steps}", "next steps ship it"),
+            "Follow up with ship it"
+        );
+    }
+}
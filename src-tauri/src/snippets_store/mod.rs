@@ -0,0 +1,247 @@
+mod expand;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::app_error::{AppError, ErrorCode};
+
+const SNIPPETS_FILE_NAME: &str = "snippets.json";
+
+// A template inserted in place of a spoken trigger phrase. `{text}` in the
+// template takes the rest of the utterance; any other `{name}` takes what was
+// said after "name".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    pub id: String,
+    pub trigger: String,
+    pub template: String,
+}
+
+#[derive(Debug)]
+pub struct SnippetsStore {
+    file_path: PathBuf,
+    snippets: Mutex<Vec<Snippet>>,
+}
+
+impl SnippetsStore {
+    pub fn new_in_dir(app_data_dir: &Path) -> Self {
+        let file_path = app_data_dir.join(SNIPPETS_FILE_NAME);
+        let snippets = match read_snippets(&file_path) {
+            Ok(snippets) => snippets,
+            Err(error) => {
+                warn!(%error, "failed to load snippets; starting with none");
+                Vec::new()
+            }
+        };
+        debug!(count = snippets.len(), "snippets store initialized");
+        Self {
+            file_path,
+            snippets: Mutex::new(snippets),
+        }
+    }
+
+    pub fn list(&self) -> Vec<Snippet> {
+        self.snippets
+            .lock()
+            .map(|snippets| snippets.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn create(&self, trigger: &str, template: &str) -> Result<Snippet, AppError> {
+        let mut snippets = self.lock()?;
+        let snippet = validate(
+            &snippets,
+            Snippet {
+                id: Uuid::new_v4().to_string(),
+                trigger: trigger.trim().to_string(),
+                template: template.to_string(),
+            },
+        )?;
+        let mut updated = snippets.clone();
+        updated.push(snippet.clone());
+        self.persist(&updated)?;
+        *snippets = updated;
+        info!(id = %snippet.id, "created snippet");
+        Ok(snippet)
+    }
+
+    pub fn update(&self, id: &str, trigger: &str, template: &str) -> Result<Snippet, AppError> {
+        let mut snippets = self.lock()?;
+        let index = snippets
+            .iter()
+            .position(|snippet| snippet.id == id)
+            .ok_or_else(|| {
+                AppError::new(ErrorCode::NotFound, format!("No snippet with id {id}"))
+            })?;
+        let snippet = validate(
+            &snippets,
+            Snippet {
+                id: id.to_string(),
+                trigger: trigger.trim().to_string(),
+                template: template.to_string(),
+            },
+        )?;
+        let mut updated = snippets.clone();
+        updated[index] = snippet.clone();
+        self.persist(&updated)?;
+        *snippets = updated;
+        info!(%id, "updated snippet");
+        Ok(snippet)
+    }
+
+    // Returns false when there was no snippet with that id.
+    pub fn delete(&self, id: &str) -> Result<bool, AppError> {
+        let mut snippets = self.lock()?;
+        let mut updated = snippets.clone();
+        updated.retain(|snippet| snippet.id != id);
+        if updated.len() == snippets.len() {
+            return Ok(false);
+        }
+        self.persist(&updated)?;
+        *snippets = updated;
+        info!(%id, "deleted snippet");
+        Ok(true)
+    }
+
+    // Swaps in a whole list, e.g. one merged from an imported archive.
+    pub fn replace_all(&self, replacement: Vec<Snippet>) -> Result<(), AppError> {
+        let mut snippets = self.lock()?;
+        let mut updated: Vec<Snippet> = Vec::with_capacity(replacement.len());
+        for snippet in replacement {
+            let snippet = validate(&updated, snippet)?;
+            updated.push(snippet);
+        }
+        self.persist(&updated)?;
+        *snippets = updated;
+        info!(count = snippets.len(), "replaced snippets");
+        Ok(())
+    }
+
+    // Returns the expanded text when the utterance starts with a trigger.
+    pub fn expand(&self, text: &str) -> Option<String> {
+        let snippets = self.snippets.lock().ok()?;
+        let (id, expanded) = expand::expand(&snippets, text)?;
+        debug!(%id, "expanded snippet trigger");
+        Some(expanded)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Vec<Snippet>>, String> {
+        self.snippets
+            .lock()
+            .map_err(|_| "Snippets lock is poisoned".to_string())
+    }
+
+    fn persist(&self, snippets: &[Snippet]) -> Result<(), String> {
+        if let Some(parent_dir) = self.file_path.parent() {
+            fs::create_dir_all(parent_dir)
+                .map_err(|error| format!("Failed to create snippets directory: {error}"))?;
+        }
+        let contents = serde_json::to_string_pretty(snippets)
+            .map_err(|error| format!("Failed to serialize snippets: {error}"))?;
+        let temp_path = self.file_path.with_extension("json.tmp");
+        fs::write(&temp_path, contents)
+            .and_then(|()| fs::rename(&temp_path, &self.file_path))
+            .map_err(|error| {
+                let _ = fs::remove_file(&temp_path);
+                format!("Failed to write snippets: {error}")
+            })
+    }
+}
+
+fn read_snippets(file_path: &Path) -> Result<Vec<Snippet>, String> {
+    if !file_path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(file_path)
+        .map_err(|error| format!("Failed to read snippets: {error}"))?;
+    serde_json::from_str(&contents).map_err(|error| format!("Failed to parse snippets: {error}"))
+}
+
+pub fn same_trigger(left: &str, right: &str) -> bool {
+    expand::trigger_words(left) == expand::trigger_words(right)
+}
+
+fn validate(existing: &[Snippet], snippet: Snippet) -> Result<Snippet, AppError> {
+    let trigger = expand::trigger_words(&snippet.trigger);
+    if trigger.is_empty() {
+        return Err(AppError::new(
+            ErrorCode::InvalidInput,
+            "Snippet trigger must contain at least one word",
+        ));
+    }
+    if snippet.template.trim().is_empty() {
+        return Err(AppError::new(
+            ErrorCode::InvalidInput,
+            "Snippet template cannot be empty",
+        ));
+    }
+    if existing
+        .iter()
+        .any(|other| other.id != snippet.id && expand::trigger_words(&other.trigger) == trigger)
+    {
+        return Err(AppError::new(
+            ErrorCode::InvalidInput,
+            format!(
+                "Another snippet already uses the trigger \"{}\"",
+                snippet.trigger
+            ),
+        ));
+    }
+    Ok(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::SnippetsStore;
+    use crate::app_error::ErrorCode;
+
+    #[test]
+    fn crud_round_trips_through_disk_and_rejects_duplicate_triggers() {
+        let test_dir =
+            std::env::temp_dir().join(format!("voice-snippets-{}", uuid::Uuid::new_v4()));
+        let store = SnippetsStore::new_in_dir(&test_dir);
+        assert!(store.list().is_empty());
+
+        let sign_off = store
+            .create("  My email sign-off ", "Best,\nSawyer")
+            .expect("snippet should save");
+        assert_eq!(sign_off.trigger, "My email sign-off");
+        assert!(store.create("my email sign off", "Cheers").is_err());
+        assert!(store.create("...", "Cheers").is_err());
+        assert!(store.create("standup", "  ").is_err());
+        assert_eq!(
+            store
+                .update("missing", "standup", "Yesterday")
+                .expect_err("unknown id should fail")
+                .code,
+            ErrorCode::NotFound
+        );
+
+        let updated = store
+            .update(&sign_off.id, "sign off", "Thanks,\nSawyer")
+            .expect("snippet should update");
+        assert_eq!(
+            store.expand("Sign off."),
+            Some("Thanks,\nSawyer".to_string())
+        );
+
+        let reloaded = SnippetsStore::new_in_dir(&test_dir);
+        assert_eq!(reloaded.list(), vec![updated.clone()]);
+        assert_eq!(reloaded.delete(&updated.id), Ok(true));
+        assert_eq!(reloaded.delete(&updated.id), Ok(false));
+        assert!(SnippetsStore::new_in_dir(&test_dir).list().is_empty());
+
+        let _ = fs::remove_dir_all(test_dir);
+    }
+}
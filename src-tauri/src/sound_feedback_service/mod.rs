@@ -0,0 +1,330 @@
+use std::{
+    f32::consts::TAU,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use cpal::{
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+    SampleFormat, Stream, StreamConfig,
+};
+use tracing::{debug, info, warn};
+
+use crate::transcription::chunked::parse_pcm16_mono_wav;
+
+const SOUNDS_DIR_NAME: &str = "sounds";
+const TONE_AMPLITUDE: f32 = 0.5;
+const TONE_FADE_MS: u32 = 5;
+const PLAYBACK_TAIL: Duration = Duration::from_millis(60);
+
+pub fn sounds_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(SOUNDS_DIR_NAME)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundCue {
+    RecordingStarted,
+    RecordingStopped,
+    TranscriptionCompleted,
+    Error,
+}
+
+impl SoundCue {
+    // Dropping `<name>.wav` (16-bit mono PCM) into the sounds directory replaces
+    // the built-in tone for that cue.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::RecordingStarted => "recording-started.wav",
+            Self::RecordingStopped => "recording-stopped.wav",
+            Self::TranscriptionCompleted => "transcription-completed.wav",
+            Self::Error => "error.wav",
+        }
+    }
+
+    // (frequency Hz, duration ms); a zero frequency is a rest. The start cue is
+    // kept short because the microphone is already open when it plays.
+    fn notes(self) -> &'static [(f32, u32)] {
+        match self {
+            Self::RecordingStarted => &[(660.0, 60), (880.0, 80)],
+            Self::RecordingStopped => &[(880.0, 60), (660.0, 80)],
+            Self::TranscriptionCompleted => &[(1046.5, 90)],
+            Self::Error => &[(330.0, 120), (0.0, 50), (330.0, 120)],
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SoundRequest {
+    cue: SoundCue,
+    volume_percent: u32,
+}
+
+// Cues play one at a time on a dedicated output stream so they never touch the
+// capture stream or block the pipeline.
+#[derive(Debug, Default)]
+pub struct SoundFeedbackService {
+    custom_sounds_dir: Option<PathBuf>,
+    worker_tx: Mutex<Option<Sender<SoundRequest>>>,
+}
+
+impl SoundFeedbackService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_custom_sounds_dir(mut self, custom_sounds_dir: PathBuf) -> Self {
+        self.custom_sounds_dir = Some(custom_sounds_dir);
+        self
+    }
+
+    pub fn play(&self, cue: SoundCue, volume_percent: u32) {
+        if volume_percent == 0 {
+            return;
+        }
+
+        let Ok(mut worker_tx) = self.worker_tx.lock() else {
+            warn!("sound feedback worker lock poisoned; skipping cue");
+            return;
+        };
+        let request = SoundRequest {
+            cue,
+            volume_percent: volume_percent.min(100),
+        };
+        // A worker that died (e.g. after a panic in the audio backend) is
+        // replaced on the next cue.
+        let request = match worker_tx.as_ref() {
+            Some(sender) => match sender.send(request) {
+                Ok(()) => return,
+                Err(mpsc::SendError(request)) => request,
+            },
+            None => request,
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let custom_sounds_dir = self.custom_sounds_dir.clone();
+        if let Err(error) = thread::Builder::new()
+            .name("sound-feedback".to_string())
+            .spawn(move || run_worker(receiver, custom_sounds_dir))
+        {
+            warn!(%error, "failed to start sound feedback worker");
+            return;
+        }
+        let _ = sender.send(request);
+        *worker_tx = Some(sender);
+    }
+}
+
+fn run_worker(receiver: Receiver<SoundRequest>, custom_sounds_dir: Option<PathBuf>) {
+    debug!("sound feedback worker started");
+    while let Ok(request) = receiver.recv() {
+        if let Err(error) = play_cue(&request, custom_sounds_dir.as_deref()) {
+            warn!(cue = ?request.cue, %error, "failed to play sound cue");
+        }
+    }
+}
+
+fn play_cue(request: &SoundRequest, custom_sounds_dir: Option<&Path>) -> Result<(), String> {
+    let host = cpal::default_host();
+    let device = host
+        .default_output_device()
+        .ok_or_else(|| "No audio output device available".to_string())?;
+    let supported_config = device
+        .default_output_config()
+        .map_err(|error| format!("Failed to read output device config: {error}"))?;
+    let sample_format = supported_config.sample_format();
+    let stream_config: StreamConfig = supported_config.into();
+    let sample_rate_hz = stream_config.sample_rate.0;
+
+    let mut samples = custom_sounds_dir
+        .and_then(|dir| load_custom_sound(&dir.join(request.cue.file_name()), sample_rate_hz))
+        .unwrap_or_else(|| render_tone(request.cue, sample_rate_hz));
+    let gain = request.volume_percent as f32 / 100.0;
+    for sample in &mut samples {
+        *sample *= gain;
+    }
+
+    let duration = Duration::from_secs_f64(samples.len() as f64 / f64::from(sample_rate_hz));
+    let stream = build_output_stream(
+        &device,
+        &stream_config,
+        sample_format,
+        Arc::new(Mutex::new(SampleCursor {
+            samples,
+            position: 0,
+        })),
+    )?;
+    stream
+        .play()
+        .map_err(|error| format!("Failed to start sound cue playback: {error}"))?;
+    debug!(cue = ?request.cue, ?duration, "playing sound cue");
+    thread::sleep(duration + PLAYBACK_TAIL);
+    Ok(())
+}
+
+fn load_custom_sound(path: &Path, sample_rate_hz: u32) -> Option<Vec<f32>> {
+    let bytes = std::fs::read(path).ok()?;
+    match parse_pcm16_mono_wav(&bytes) {
+        Ok(wav) => {
+            info!(path = %path.display(), "using custom sound cue");
+            let samples = wav
+                .samples
+                .iter()
+                .map(|sample| f32::from(*sample) / f32::from(i16::MAX))
+                .collect::<Vec<_>>();
+            Some(resample_linear(
+                &samples,
+                wav.sample_rate_hz,
+                sample_rate_hz,
+            ))
+        }
+        Err(error) => {
+            warn!(path = %path.display(), %error, "ignoring unreadable custom sound cue");
+            None
+        }
+    }
+}
+
+fn render_tone(cue: SoundCue, sample_rate_hz: u32) -> Vec<f32> {
+    let fade_len = (sample_rate_hz * TONE_FADE_MS / 1000).max(1) as usize;
+    let mut samples = Vec::new();
+    for &(frequency_hz, duration_ms) in cue.notes() {
+        let note_len = (u64::from(sample_rate_hz) * u64::from(duration_ms) / 1000) as usize;
+        samples.extend((0..note_len).map(|index| {
+            if frequency_hz <= 0.0 {
+                return 0.0;
+            }
+            let envelope = (index.min(note_len - 1 - index) as f32 / fade_len as f32).min(1.0);
+            let phase = TAU * frequency_hz * index as f32 / sample_rate_hz as f32;
+            phase.sin() * TONE_AMPLITUDE * envelope
+        }));
+    }
+    samples
+}
+
+fn resample_linear(samples: &[f32], from_rate_hz: u32, to_rate_hz: u32) -> Vec<f32> {
+    if from_rate_hz == to_rate_hz || samples.len() < 2 || from_rate_hz == 0 {
+        return samples.to_vec();
+    }
+    let output_len =
+        (samples.len() as u64 * u64::from(to_rate_hz) / u64::from(from_rate_hz)) as usize;
+    let step = f64::from(from_rate_hz) / f64::from(to_rate_hz);
+    (0..output_len)
+        .map(|index| {
+            let position = index as f64 * step;
+            let left = (position.floor() as usize).min(samples.len() - 1);
+            let right = (left + 1).min(samples.len() - 1);
+            let fraction = (position - left as f64) as f32;
+            samples[left] + (samples[right] - samples[left]) * fraction
+        })
+        .collect()
+}
+
+struct SampleCursor {
+    samples: Vec<f32>,
+    position: usize,
+}
+
+impl SampleCursor {
+    fn next_sample(&mut self) -> f32 {
+        let sample = self.samples.get(self.position).copied().unwrap_or(0.0);
+        self.position += 1;
+        sample
+    }
+}
+
+fn build_output_stream(
+    device: &cpal::Device,
+    stream_config: &StreamConfig,
+    sample_format: SampleFormat,
+    cursor: Arc<Mutex<SampleCursor>>,
+) -> Result<Stream, String> {
+    let channels = usize::from(stream_config.channels.max(1));
+    let on_error = |error| warn!(%error, "sound cue output stream error");
+    match sample_format {
+        SampleFormat::F32 => device
+            .build_output_stream(
+                stream_config,
+                move |data: &mut [f32], _| {
+                    fill_output_frames(data, channels, &cursor, |sample| sample);
+                },
+                on_error,
+                None,
+            )
+            .map_err(|error| format!("Failed to build f32 output stream: {error}")),
+        SampleFormat::I16 => device
+            .build_output_stream(
+                stream_config,
+                move |data: &mut [i16], _| {
+                    fill_output_frames(data, channels, &cursor, |sample| {
+                        (sample * f32::from(i16::MAX)) as i16
+                    });
+                },
+                on_error,
+                None,
+            )
+            .map_err(|error| format!("Failed to build i16 output stream: {error}")),
+        SampleFormat::U16 => device
+            .build_output_stream(
+                stream_config,
+                move |data: &mut [u16], _| {
+                    fill_output_frames(data, channels, &cursor, |sample| {
+                        ((sample + 1.0) * 0.5 * f32::from(u16::MAX)) as u16
+                    });
+                },
+                on_error,
+                None,
+            )
+            .map_err(|error| format!("Failed to build u16 output stream: {error}")),
+        _ => Err(format!(
+            "Unsupported output sample format: {sample_format:?}"
+        )),
+    }
+}
+
+fn fill_output_frames<T, F>(
+    data: &mut [T],
+    channels: usize,
+    cursor: &Mutex<SampleCursor>,
+    from_f32: F,
+) where
+    T: Copy,
+    F: Fn(f32) -> T,
+{
+    let Ok(mut cursor) = cursor.lock() else {
+        return;
+    };
+    for frame in data.chunks_mut(channels) {
+        let value = from_f32(cursor.next_sample().clamp(-1.0, 1.0));
+        frame.fill(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_tone, resample_linear, SoundCue};
+
+    #[test]
+    fn tones_fade_in_and_out_and_resampling_preserves_duration() {
+        let tone = render_tone(SoundCue::TranscriptionCompleted, 48_000);
+
+        assert_eq!(tone.len(), 48_000 * 90 / 1000);
+        assert_eq!(tone[0], 0.0);
+        assert!(tone[tone.len() - 1].abs() < 0.01);
+        assert!(tone.iter().all(|sample| sample.abs() <= 0.5));
+        assert!(tone.iter().any(|sample| sample.abs() > 0.4));
+
+        let error_tone = render_tone(SoundCue::Error, 16_000);
+        assert!(error_tone[16 * 130..16 * 160]
+            .iter()
+            .all(|sample| *sample == 0.0));
+
+        let resampled = resample_linear(&[0.0, 1.0, 0.0, -1.0], 16_000, 48_000);
+        assert_eq!(resampled.len(), 12);
+        assert!((resampled[1] - 1.0 / 3.0).abs() < 1e-6);
+    }
+}
@@ -1,21 +1,37 @@
 use std::{
     collections::BTreeMap,
     fs,
-    io::Write,
     path::{Path, PathBuf},
     sync::Mutex,
     time::{SystemTime, UNIX_EPOCH},
 };
 
 use chrono::{Duration, Local, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "tauri")]
 use tauri::{AppHandle, Manager};
 use tracing::{debug, info, warn};
 
+use crate::app_error::{AppError, ErrorCode};
+use crate::database::{open_database, DATABASE_FILE_NAME};
+use crate::focused_app_watcher::FocusedApp;
+
+// Only read to migrate stats written before the database existed.
 const STATS_FILE_NAME: &str = "stats.json";
 const DEFAULT_HISTORY_WINDOW_DAYS: usize = 30;
-
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+const MAX_RANGE_DAYS: i64 = 366;
+// Report fields derived from transcript content, blanked in privacy mode.
+const CONTENT_DERIVED_REPORT_FIELDS: [&str; 5] = [
+    "totalWords",
+    "wordsPerMinute",
+    "averageTranscriptionLength",
+    "today.words",
+    "dailyWordHistory",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DailyStats {
     #[serde(default)]
@@ -24,6 +40,73 @@ pub struct DailyStats {
     pub words: u64,
     #[serde(default)]
     pub recording_seconds: f64,
+    #[serde(default)]
+    pub trimmed_silence_ms: u64,
+}
+
+// Keyed by bundle id (or name when there is none) of the app that was focused
+// when recording started.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppUsageStats {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub transcriptions: u64,
+    #[serde(default)]
+    pub words: u64,
+    #[serde(default)]
+    pub recording_seconds: f64,
+    #[serde(default)]
+    pub last_used: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyUsage {
+    pub date: String,
+    #[serde(flatten)]
+    pub stats: DailyStats,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppUsage {
+    pub app_id: String,
+    #[serde(flatten)]
+    pub stats: AppUsageStats,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptionRacePath {
+    Realtime,
+    Rest,
+}
+
+impl TranscriptionRacePath {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Realtime => "realtime",
+            Self::Rest => "rest",
+        }
+    }
+
+    pub fn other(self) -> Self {
+        match self {
+            Self::Realtime => Self::Rest,
+            Self::Rest => Self::Realtime,
+        }
+    }
+}
+
+// How often each path delivered first in race mode.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionRaceWins {
+    #[serde(default)]
+    pub realtime: u64,
+    #[serde(default)]
+    pub rest: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -36,7 +119,15 @@ pub struct UsageStats {
     #[serde(default)]
     pub total_recording_seconds: f64,
     #[serde(default)]
+    pub total_trimmed_silence_ms: u64,
+    #[serde(default)]
+    pub transcriptions_without_word_count: u64,
+    #[serde(default)]
+    pub race_wins: TranscriptionRaceWins,
+    #[serde(default)]
     pub daily_stats: BTreeMap<String, DailyStats>,
+    #[serde(default)]
+    pub apps: BTreeMap<String, AppUsageStats>,
     #[serde(default = "today_date_key")]
     pub last_updated: String,
 }
@@ -47,211 +138,469 @@ impl Default for UsageStats {
             total_transcriptions: 0,
             total_words: 0,
             total_recording_seconds: 0.0,
+            total_trimmed_silence_ms: 0,
+            transcriptions_without_word_count: 0,
+            race_wins: TranscriptionRaceWins::default(),
             daily_stats: BTreeMap::new(),
+            apps: BTreeMap::new(),
             last_updated: today_date_key(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DailyWordCount {
     pub date: String,
     pub words: u64,
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UsageStatsReport {
     pub total_transcriptions: u64,
     pub total_words: u64,
     pub total_recording_seconds: f64,
+    pub total_trimmed_silence_ms: u64,
+    // Transcriptions recorded in privacy mode, which count toward totals but
+    // not toward any word metric.
+    pub transcriptions_without_word_count: u64,
+    pub race_wins: TranscriptionRaceWins,
     pub words_per_minute: f64,
     pub average_transcription_length: f64,
     pub streak_days: u64,
     pub today: DailyStats,
     pub daily_word_history: Vec<DailyWordCount>,
+    // Content-derived fields that were zeroed because privacy mode is on.
+    pub omitted_fields: Vec<String>,
     pub last_updated: String,
 }
 
 #[derive(Debug)]
 pub struct StatsStore {
-    file_path: PathBuf,
-    io_lock: Mutex<()>,
+    connection: Mutex<Connection>,
+    legacy_file_path: PathBuf,
 }
 
 impl StatsStore {
-    pub fn new(app: &AppHandle) -> Result<Self, String> {
+    #[cfg(feature = "tauri")]
+    pub fn new(app: &AppHandle) -> Result<Self, AppError> {
         let app_data_dir = app
             .path()
             .app_data_dir()
             .map_err(|error| format!("Failed to resolve app data directory: {error}"))?;
-
-        let file_path = app_data_dir.join(STATS_FILE_NAME);
-        debug!(path = %file_path.display(), "initializing usage stats store");
-        Self::new_with_file_path(file_path)
+        Self::new_in_dir(&app_data_dir)
     }
 
-    pub fn new_with_file_path(file_path: PathBuf) -> Result<Self, String> {
-        ensure_stats_file(&file_path)?;
-        Ok(Self {
-            file_path,
-            io_lock: Mutex::new(()),
-        })
+    pub fn new_in_dir(app_data_dir: &Path) -> Result<Self, AppError> {
+        let database_path = app_data_dir.join(DATABASE_FILE_NAME);
+        debug!(path = %database_path.display(), "initializing usage stats store");
+        let store = Self {
+            connection: Mutex::new(open_database(&database_path)?),
+            legacy_file_path: app_data_dir.join(STATS_FILE_NAME),
+        };
+        if let Err(error) = store.import_legacy_file() {
+            warn!(%error, "failed to migrate usage stats file into the database");
+        }
+        Ok(store)
     }
 
+    // Pass `None` for the word count in privacy mode so nothing derived from the
+    // transcript's content is persisted.
     pub fn record_transcription(
         &self,
-        word_count: u64,
+        word_count: Option<u64>,
         recording_duration_secs: f64,
-    ) -> Result<(), String> {
+        trimmed_silence_ms: u64,
+        app: Option<&FocusedApp>,
+    ) -> Result<(), AppError> {
         let sanitized_duration = sanitize_seconds(recording_duration_secs);
         let today = today_date_key();
         debug!(
-            word_count,
+            ?word_count,
             recording_duration_secs = sanitized_duration,
+            trimmed_silence_ms,
             date = %today,
             "recording usage stats for transcription"
         );
 
-        let _guard = self
-            .io_lock
-            .lock()
-            .map_err(|_| "Stats store lock is poisoned".to_string())?;
-        let mut stats = self.read_usage_stats()?;
-
-        stats.total_transcriptions = stats.total_transcriptions.saturating_add(1);
-        stats.total_words = stats.total_words.saturating_add(word_count);
-        stats.total_recording_seconds =
-            sanitize_seconds(stats.total_recording_seconds + sanitized_duration);
-
-        let day_stats = stats.daily_stats.entry(today.clone()).or_default();
-        day_stats.transcriptions = day_stats.transcriptions.saturating_add(1);
-        day_stats.words = day_stats.words.saturating_add(word_count);
-        day_stats.recording_seconds =
-            sanitize_seconds(day_stats.recording_seconds + sanitized_duration);
+        let without_word_count = u64::from(word_count.is_none());
+        let word_count = word_count.unwrap_or(0);
+        let mut connection = self.lock_connection()?;
+        let transaction = connection
+            .transaction()
+            .map_err(|error| format!("Failed to write usage stats: {error}"))?;
+        transaction
+            .execute(
+                "INSERT INTO usage_totals (
+                    id, total_transcriptions, total_words, total_recording_seconds,
+                    total_trimmed_silence_ms, transcriptions_without_word_count, last_updated
+                 ) VALUES (1, 1, ?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (id) DO UPDATE SET
+                    total_transcriptions = total_transcriptions + 1,
+                    total_words = total_words + excluded.total_words,
+                    total_recording_seconds =
+                        total_recording_seconds + excluded.total_recording_seconds,
+                    total_trimmed_silence_ms =
+                        total_trimmed_silence_ms + excluded.total_trimmed_silence_ms,
+                    transcriptions_without_word_count = transcriptions_without_word_count
+                        + excluded.transcriptions_without_word_count,
+                    last_updated = excluded.last_updated",
+                params![
+                    word_count,
+                    sanitized_duration,
+                    trimmed_silence_ms,
+                    without_word_count,
+                    today
+                ],
+            )
+            .map_err(|error| format!("Failed to write usage stats: {error}"))?;
+        transaction
+            .execute(
+                "INSERT INTO usage_daily (
+                    date, transcriptions, words, recording_seconds, trimmed_silence_ms
+                 ) VALUES (?1, 1, ?2, ?3, ?4)
+                 ON CONFLICT (date) DO UPDATE SET
+                    transcriptions = transcriptions + 1,
+                    words = words + excluded.words,
+                    recording_seconds = recording_seconds + excluded.recording_seconds,
+                    trimmed_silence_ms = trimmed_silence_ms + excluded.trimmed_silence_ms",
+                params![today, word_count, sanitized_duration, trimmed_silence_ms],
+            )
+            .map_err(|error| format!("Failed to write usage stats: {error}"))?;
+
+        if let Some(app) = app {
+            let app_id = app.bundle_id.clone().unwrap_or_else(|| app.name.clone());
+            transaction
+                .execute(
+                    "INSERT INTO usage_apps (
+                        app_id, name, transcriptions, words, recording_seconds, last_used
+                     ) VALUES (?1, ?2, 1, ?3, ?4, ?5)
+                     ON CONFLICT (app_id) DO UPDATE SET
+                        name = excluded.name,
+                        transcriptions = transcriptions + 1,
+                        words = words + excluded.words,
+                        recording_seconds = recording_seconds + excluded.recording_seconds,
+                        last_used = excluded.last_used",
+                    params![app_id, app.name, word_count, sanitized_duration, today],
+                )
+                .map_err(|error| format!("Failed to write usage stats: {error}"))?;
+        }
 
-        stats.last_updated = today;
-        self.write_usage_stats(&stats)
+        transaction
+            .commit()
+            .map_err(|error| format!("Failed to write usage stats: {error}"))?;
+        Ok(())
     }
 
-    pub fn get_usage_stats(&self) -> Result<UsageStatsReport, String> {
-        let _guard = self
-            .io_lock
-            .lock()
-            .map_err(|_| "Stats store lock is poisoned".to_string())?;
-        let stats = self.read_usage_stats()?;
-        Ok(build_usage_report(
-            &stats,
-            today_local_date(),
-            DEFAULT_HISTORY_WINDOW_DAYS,
-        ))
+    pub fn record_transcription_race_win(
+        &self,
+        winner: TranscriptionRacePath,
+    ) -> Result<(), AppError> {
+        debug!(
+            winner = winner.as_str(),
+            "recording transcription race winner"
+        );
+        let column = match winner {
+            TranscriptionRacePath::Realtime => "race_wins_realtime",
+            TranscriptionRacePath::Rest => "race_wins_rest",
+        };
+        let connection = self.lock_connection()?;
+        connection
+            .execute(
+                &format!(
+                    "INSERT INTO usage_totals (id, {column}, last_updated) VALUES (1, 1, ?1)
+                     ON CONFLICT (id) DO UPDATE SET {column} = {column} + 1"
+                ),
+                [today_date_key()],
+            )
+            .map_err(|error| format!("Failed to write usage stats: {error}"))?;
+        Ok(())
     }
 
-    pub fn reset_usage_stats(&self) -> Result<(), String> {
-        info!("resetting usage stats");
-        let _guard = self
-            .io_lock
-            .lock()
-            .map_err(|_| "Stats store lock is poisoned".to_string())?;
-        self.write_usage_stats(&UsageStats::default())
+    pub fn get_usage_stats(&self, privacy_mode: bool) -> Result<UsageStatsReport, AppError> {
+        let connection = self.lock_connection()?;
+        let stats = read_usage_stats(&connection)?;
+        let report = build_usage_report(&stats, today_local_date(), DEFAULT_HISTORY_WINDOW_DAYS);
+        Ok(if privacy_mode {
+            omit_content_derived_fields(report)
+        } else {
+            report
+        })
     }
 
-    fn read_usage_stats(&self) -> Result<UsageStats, String> {
-        if !self.file_path.exists() {
-            return Ok(UsageStats::default());
+    // Inclusive range of local dates, with empty days filled in so charts get a
+    // continuous series.
+    pub fn get_usage_stats_range(
+        &self,
+        from: &str,
+        to: &str,
+        privacy_mode: bool,
+    ) -> Result<Vec<DailyUsage>, AppError> {
+        let invalid_range = |message: String| AppError::new(ErrorCode::InvalidInput, message);
+        let from_date = parse_date_key(from).ok_or_else(|| {
+            invalid_range(format!("Invalid start date `{from}`. Expected YYYY-MM-DD"))
+        })?;
+        let to_date = parse_date_key(to).ok_or_else(|| {
+            invalid_range(format!("Invalid end date `{to}`. Expected YYYY-MM-DD"))
+        })?;
+        if from_date > to_date {
+            return Err(invalid_range(format!(
+                "Start date `{from}` is after end date `{to}`"
+            )));
         }
-
-        let raw_contents = fs::read_to_string(&self.file_path)
-            .map_err(|error| format!("Failed to read usage stats file: {error}"))?;
-        if raw_contents.trim().is_empty() {
-            return Ok(UsageStats::default());
+        if (to_date - from_date).num_days() >= MAX_RANGE_DAYS {
+            return Err(invalid_range(format!(
+                "Usage stats ranges are limited to {MAX_RANGE_DAYS} days"
+            )));
         }
 
-        let mut stats = match serde_json::from_str::<UsageStats>(&raw_contents) {
-            Ok(parsed) => parsed,
-            Err(error) => {
-                self.recover_malformed_stats_file(format!(
-                    "Failed to parse usage stats file: {error}"
-                ))?;
-                return Ok(UsageStats::default());
-            }
-        };
+        let connection = self.lock_connection()?;
+        let stats = read_usage_stats(&connection)?;
+        Ok(from_date
+            .iter_days()
+            .take_while(|date| *date <= to_date)
+            .map(|date| {
+                let date = date_key(date);
+                let mut day_stats = stats.daily_stats.get(&date).cloned().unwrap_or_default();
+                if privacy_mode {
+                    day_stats.words = 0;
+                }
+                DailyUsage {
+                    date,
+                    stats: day_stats,
+                }
+            })
+            .collect())
+    }
 
-        normalize_usage_stats(&mut stats);
-        Ok(stats)
+    // Most-used apps first.
+    pub fn get_usage_stats_by_app(&self, privacy_mode: bool) -> Result<Vec<AppUsage>, AppError> {
+        let connection = self.lock_connection()?;
+        let stats = read_usage_stats(&connection)?;
+        let mut apps = stats
+            .apps
+            .into_iter()
+            .map(|(app_id, mut app_stats)| {
+                if privacy_mode {
+                    app_stats.words = 0;
+                }
+                AppUsage {
+                    app_id,
+                    stats: app_stats,
+                }
+            })
+            .collect::<Vec<_>>();
+        apps.sort_by(|left, right| {
+            right
+                .stats
+                .transcriptions
+                .cmp(&left.stats.transcriptions)
+                .then_with(|| left.app_id.cmp(&right.app_id))
+        });
+        Ok(apps)
     }
 
-    fn write_usage_stats(&self, stats: &UsageStats) -> Result<(), String> {
-        let serialized = serde_json::to_vec_pretty(stats)
-            .map_err(|error| format!("Failed to serialize usage stats: {error}"))?;
-        let temp_path = temp_file_path_for(&self.file_path);
+    pub fn reset_usage_stats(&self) -> Result<(), AppError> {
+        info!("resetting usage stats");
+        let connection = self.lock_connection()?;
+        connection
+            .execute_batch(
+                "BEGIN;
+                 DELETE FROM usage_totals;
+                 DELETE FROM usage_daily;
+                 DELETE FROM usage_apps;
+                 COMMIT;",
+            )
+            .map_err(|error| format!("Failed to reset usage stats: {error}"))?;
+        Ok(())
+    }
 
-        let mut temp_file = fs::OpenOptions::new()
-            .create_new(true)
-            .write(true)
-            .open(&temp_path)
-            .map_err(|error| {
-                format!(
-                    "Failed to create usage stats temp file `{}`: {error}",
-                    temp_path.display()
-                )
-            })?;
-
-        if let Err(error) = temp_file.write_all(&serialized) {
-            let _ = fs::remove_file(&temp_path);
-            return Err(format!(
-                "Failed to write usage stats temp file `{}`: {error}",
-                temp_path.display()
-            ));
+    // Stats used to live in one JSON file. It is copied into the database once
+    // and then removed; if the database already has stats, an earlier
+    // migration got as far as committing them.
+    fn import_legacy_file(&self) -> Result<(), String> {
+        let file_path = &self.legacy_file_path;
+        if !file_path.exists() {
+            return Ok(());
         }
 
-        if let Err(error) = temp_file.sync_all() {
-            let _ = fs::remove_file(&temp_path);
-            return Err(format!(
-                "Failed to flush usage stats temp file `{}`: {error}",
-                temp_path.display()
-            ));
+        let mut connection = self.lock_connection()?;
+        let already_migrated = connection
+            .query_row("SELECT EXISTS (SELECT 1 FROM usage_totals)", [], |row| {
+                row.get::<_, bool>(0)
+            })
+            .map_err(|error| format!("Failed to read usage stats: {error}"))?;
+        if !already_migrated {
+            let raw_contents = fs::read_to_string(file_path)
+                .map_err(|error| format!("Failed to read usage stats file: {error}"))?;
+            let stats = if raw_contents.trim().is_empty() {
+                UsageStats::default()
+            } else {
+                match serde_json::from_str::<UsageStats>(&raw_contents) {
+                    Ok(stats) => stats,
+                    Err(error) => {
+                        let backup_path = backup_corrupt_stats_file(file_path)?;
+                        warn!(
+                            path = %file_path.display(),
+                            backup = %backup_path.display(),
+                            reason = %format!("Failed to parse usage stats file: {error}"),
+                            "recovered malformed usage stats file"
+                        );
+                        return Ok(());
+                    }
+                }
+            };
+            write_usage_stats(&mut connection, &stats)?;
         }
 
-        drop(temp_file);
-
-        fs::rename(&temp_path, &self.file_path).map_err(|error| {
-            let _ = fs::remove_file(&temp_path);
-            format!("Failed to finalize usage stats file: {error}")
-        })?;
-
+        fs::remove_file(file_path)
+            .map_err(|error| format!("Failed to remove migrated usage stats file: {error}"))?;
+        info!("migrated usage stats file into the database");
         Ok(())
     }
 
-    fn recover_malformed_stats_file(&self, reason: String) -> Result<(), String> {
-        let backup_path = backup_corrupt_stats_file(&self.file_path)?;
-        self.write_usage_stats(&UsageStats::default())?;
-        warn!(
-            path = %self.file_path.display(),
-            backup = %backup_path.display(),
-            reason = %reason,
-            "recovered malformed usage stats file"
-        );
-        Ok(())
+    fn lock_connection(&self) -> Result<std::sync::MutexGuard<'_, Connection>, String> {
+        self.connection
+            .lock()
+            .map_err(|_| "Stats store lock is poisoned".to_string())
     }
 }
 
-fn ensure_stats_file(file_path: &Path) -> Result<(), String> {
-    if let Some(parent_dir) = file_path.parent() {
-        fs::create_dir_all(parent_dir)
-            .map_err(|error| format!("Failed to create usage stats directory: {error}"))?;
-    }
+fn read_usage_stats(connection: &Connection) -> Result<UsageStats, String> {
+    let mut stats = connection
+        .query_row(
+            "SELECT total_transcriptions, total_words, total_recording_seconds,
+                    total_trimmed_silence_ms, transcriptions_without_word_count,
+                    race_wins_realtime, race_wins_rest, last_updated
+             FROM usage_totals WHERE id = 1",
+            [],
+            |row| {
+                Ok(UsageStats {
+                    total_transcriptions: row.get(0)?,
+                    total_words: row.get(1)?,
+                    total_recording_seconds: row.get(2)?,
+                    total_trimmed_silence_ms: row.get(3)?,
+                    transcriptions_without_word_count: row.get(4)?,
+                    race_wins: TranscriptionRaceWins {
+                        realtime: row.get(5)?,
+                        rest: row.get(6)?,
+                    },
+                    last_updated: row.get(7)?,
+                    ..UsageStats::default()
+                })
+            },
+        )
+        .optional()
+        .map_err(|error| format!("Failed to read usage stats: {error}"))?
+        .unwrap_or_default();
 
-    if !file_path.exists() {
-        let initial_stats = serde_json::to_vec_pretty(&UsageStats::default())
-            .map_err(|error| format!("Failed to serialize initial usage stats: {error}"))?;
-        fs::write(file_path, initial_stats)
-            .map_err(|error| format!("Failed to initialize usage stats file: {error}"))?;
-        info!(path = %file_path.display(), "created usage stats file");
-    }
+    let mut daily_statement = connection
+        .prepare(
+            "SELECT date, transcriptions, words, recording_seconds, trimmed_silence_ms
+             FROM usage_daily",
+        )
+        .map_err(|error| format!("Failed to read usage stats: {error}"))?;
+    stats.daily_stats = daily_statement
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                DailyStats {
+                    transcriptions: row.get(1)?,
+                    words: row.get(2)?,
+                    recording_seconds: row.get(3)?,
+                    trimmed_silence_ms: row.get(4)?,
+                },
+            ))
+        })
+        .and_then(Iterator::collect)
+        .map_err(|error| format!("Failed to read usage stats: {error}"))?;
+
+    let mut apps_statement = connection
+        .prepare(
+            "SELECT app_id, name, transcriptions, words, recording_seconds, last_used
+             FROM usage_apps",
+        )
+        .map_err(|error| format!("Failed to read usage stats: {error}"))?;
+    stats.apps = apps_statement
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                AppUsageStats {
+                    name: row.get(1)?,
+                    transcriptions: row.get(2)?,
+                    words: row.get(3)?,
+                    recording_seconds: row.get(4)?,
+                    last_used: row.get(5)?,
+                },
+            ))
+        })
+        .and_then(Iterator::collect)
+        .map_err(|error| format!("Failed to read usage stats: {error}"))?;
+
+    normalize_usage_stats(&mut stats);
+    Ok(stats)
+}
 
-    Ok(())
+fn write_usage_stats(connection: &mut Connection, stats: &UsageStats) -> Result<(), String> {
+    let transaction = connection
+        .transaction()
+        .map_err(|error| format!("Failed to write usage stats: {error}"))?;
+    transaction
+        .execute(
+            "INSERT OR REPLACE INTO usage_totals (
+                id, total_transcriptions, total_words, total_recording_seconds,
+                total_trimmed_silence_ms, transcriptions_without_word_count,
+                race_wins_realtime, race_wins_rest, last_updated
+             ) VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                stats.total_transcriptions,
+                stats.total_words,
+                stats.total_recording_seconds,
+                stats.total_trimmed_silence_ms,
+                stats.transcriptions_without_word_count,
+                stats.race_wins.realtime,
+                stats.race_wins.rest,
+                stats.last_updated
+            ],
+        )
+        .map_err(|error| format!("Failed to write usage stats: {error}"))?;
+    for (date, day_stats) in &stats.daily_stats {
+        transaction
+            .execute(
+                "INSERT OR REPLACE INTO usage_daily (
+                    date, transcriptions, words, recording_seconds, trimmed_silence_ms
+                 ) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    date,
+                    day_stats.transcriptions,
+                    day_stats.words,
+                    day_stats.recording_seconds,
+                    day_stats.trimmed_silence_ms
+                ],
+            )
+            .map_err(|error| format!("Failed to write usage stats: {error}"))?;
+    }
+    for (app_id, app_stats) in &stats.apps {
+        transaction
+            .execute(
+                "INSERT OR REPLACE INTO usage_apps (
+                    app_id, name, transcriptions, words, recording_seconds, last_used
+                 ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    app_id,
+                    app_stats.name,
+                    app_stats.transcriptions,
+                    app_stats.words,
+                    app_stats.recording_seconds,
+                    app_stats.last_used
+                ],
+            )
+            .map_err(|error| format!("Failed to write usage stats: {error}"))?;
+    }
+    transaction
+        .commit()
+        .map_err(|error| format!("Failed to write usage stats: {error}"))
 }
 
 fn normalize_usage_stats(stats: &mut UsageStats) {
@@ -268,6 +617,9 @@ fn normalize_usage_stats(stats: &mut UsageStats) {
         day_stats.recording_seconds = sanitize_seconds(day_stats.recording_seconds);
         true
     });
+    for app_stats in stats.apps.values_mut() {
+        app_stats.recording_seconds = sanitize_seconds(app_stats.recording_seconds);
+    }
 }
 
 fn build_usage_report(
@@ -296,15 +648,33 @@ fn build_usage_report(
         total_transcriptions: stats.total_transcriptions,
         total_words: stats.total_words,
         total_recording_seconds: stats.total_recording_seconds,
+        total_trimmed_silence_ms: stats.total_trimmed_silence_ms,
+        transcriptions_without_word_count: stats.transcriptions_without_word_count,
+        race_wins: stats.race_wins,
         words_per_minute,
         average_transcription_length,
         streak_days: calculate_streak_days(&stats.daily_stats, today),
         today: today_stats,
         daily_word_history: build_daily_word_history(&stats.daily_stats, today, history_days),
+        omitted_fields: Vec::new(),
         last_updated: stats.last_updated.clone(),
     }
 }
 
+// Words recorded before privacy mode was enabled are hidden as well.
+fn omit_content_derived_fields(mut report: UsageStatsReport) -> UsageStatsReport {
+    report.total_words = 0;
+    report.words_per_minute = 0.0;
+    report.average_transcription_length = 0.0;
+    report.today.words = 0;
+    report.daily_word_history.clear();
+    report.omitted_fields = CONTENT_DERIVED_REPORT_FIELDS
+        .iter()
+        .map(|field| field.to_string())
+        .collect();
+    report
+}
+
 fn calculate_streak_days(daily_stats: &BTreeMap<String, DailyStats>, today: NaiveDate) -> u64 {
     let mut streak = 0_u64;
     let mut cursor = today;
@@ -382,22 +752,6 @@ fn parse_date_key(value: &str) -> Option<NaiveDate> {
     NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok()
 }
 
-fn temp_file_path_for(file_path: &Path) -> PathBuf {
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_nanos();
-    let file_name = file_path
-        .file_name()
-        .and_then(|value| value.to_str())
-        .unwrap_or(STATS_FILE_NAME);
-
-    file_path.with_file_name(format!(
-        ".{file_name}.{}.{timestamp}.tmp",
-        std::process::id()
-    ))
-}
-
 fn backup_corrupt_stats_file(file_path: &Path) -> Result<PathBuf, String> {
     let timestamp = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -431,8 +785,8 @@ mod tests {
     fn create_test_store() -> (StatsStore, PathBuf, PathBuf) {
         let test_dir = std::env::temp_dir().join(format!("voice-stats-store-{}", Uuid::new_v4()));
         let file_path = test_dir.join(STATS_FILE_NAME);
-        let store = StatsStore::new_with_file_path(file_path.clone())
-            .expect("stats store should initialize for tests");
+        let store =
+            StatsStore::new_in_dir(&test_dir).expect("stats store should initialize for tests");
         (store, file_path, test_dir)
     }
 
@@ -476,10 +830,10 @@ mod tests {
         let (store, _file_path, test_dir) = create_test_store();
 
         store
-            .record_transcription(12, 45.5)
+            .record_transcription(Some(12), 45.5, 0, None)
             .expect("stats recording should succeed");
         let report = store
-            .get_usage_stats()
+            .get_usage_stats(false)
             .expect("stats should load after recording");
 
         assert_eq!(report.total_transcriptions, 1);
@@ -493,25 +847,145 @@ mod tests {
         cleanup_test_dir(&test_dir);
     }
 
+    #[test]
+    fn privacy_mode_skips_word_counts_and_marks_omitted_fields() {
+        let (store, _file_path, test_dir) = create_test_store();
+
+        store
+            .record_transcription(Some(30), 20.0, 0, None)
+            .expect("first record should succeed");
+        store
+            .record_transcription(None, 40.0, 0, None)
+            .expect("privacy record should succeed");
+
+        let report = store.get_usage_stats(false).expect("stats should load");
+        assert_eq!(report.total_transcriptions, 2);
+        assert_eq!(report.total_words, 30);
+        assert_eq!(report.transcriptions_without_word_count, 1);
+        assert!(report.omitted_fields.is_empty());
+
+        let private_report = store.get_usage_stats(true).expect("stats should load");
+        assert_eq!(private_report.total_transcriptions, 2);
+        assert_almost_eq(private_report.total_recording_seconds, 60.0);
+        assert_eq!(private_report.total_words, 0);
+        assert_eq!(private_report.today.words, 0);
+        assert!(private_report.daily_word_history.is_empty());
+        assert!(private_report
+            .omitted_fields
+            .contains(&"wordsPerMinute".to_string()));
+
+        cleanup_test_dir(&test_dir);
+    }
+
     #[test]
     fn metrics_accumulate_across_multiple_transcriptions() {
         let (store, _file_path, test_dir) = create_test_store();
 
         store
-            .record_transcription(120, 60.0)
+            .record_transcription(Some(120), 60.0, 1_200, None)
             .expect("first record should succeed");
         store
-            .record_transcription(60, 30.0)
+            .record_transcription(Some(60), 30.0, 300, None)
             .expect("second record should succeed");
 
         let report = store
-            .get_usage_stats()
+            .get_usage_stats(false)
             .expect("stats should load after multiple records");
         assert_eq!(report.total_transcriptions, 2);
         assert_eq!(report.total_words, 180);
         assert_almost_eq(report.total_recording_seconds, 90.0);
         assert_almost_eq(report.words_per_minute, 120.0);
         assert_almost_eq(report.average_transcription_length, 90.0);
+        assert_eq!(report.total_trimmed_silence_ms, 1_500);
+        assert_eq!(report.today.trimmed_silence_ms, 1_500);
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn range_and_app_breakdowns_cover_recorded_sessions() {
+        let (store, _file_path, test_dir) = create_test_store();
+        let notes = FocusedApp {
+            bundle_id: Some("com.apple.Notes".to_string()),
+            name: "Notes".to_string(),
+        };
+        let terminal = FocusedApp {
+            bundle_id: None,
+            name: "Terminal".to_string(),
+        };
+
+        store
+            .record_transcription(Some(10), 6.0, 0, Some(&notes))
+            .expect("first record should succeed");
+        store
+            .record_transcription(Some(20), 12.0, 0, Some(&notes))
+            .expect("second record should succeed");
+        store
+            .record_transcription(Some(5), 3.0, 0, Some(&terminal))
+            .expect("third record should succeed");
+        store
+            .record_transcription(Some(7), 4.0, 0, None)
+            .expect("unattributed record should succeed");
+
+        let today = today_local_date();
+        let from = date_key(today - Duration::days(2));
+        let range = store
+            .get_usage_stats_range(&from, &date_key(today), false)
+            .expect("range should load");
+        assert_eq!(range.len(), 3);
+        assert_eq!(range[0].stats, DailyStats::default());
+        assert_eq!(range[2].stats.transcriptions, 4);
+        assert_eq!(range[2].stats.words, 42);
+        assert!(store
+            .get_usage_stats_range(&date_key(today), &from, false)
+            .is_err());
+
+        let apps = store
+            .get_usage_stats_by_app(false)
+            .expect("app breakdown should load");
+        assert_eq!(
+            apps.iter()
+                .map(|app| (
+                    app.app_id.as_str(),
+                    app.stats.transcriptions,
+                    app.stats.words
+                ))
+                .collect::<Vec<_>>(),
+            vec![("com.apple.Notes", 2, 30), ("Terminal", 1, 5)]
+        );
+        assert_almost_eq(apps[0].stats.recording_seconds, 18.0);
+        assert!(store
+            .get_usage_stats_by_app(true)
+            .expect("private app breakdown should load")
+            .iter()
+            .all(|app| app.stats.words == 0));
+
+        cleanup_test_dir(&test_dir);
+    }
+
+    #[test]
+    fn race_wins_are_counted_per_path() {
+        let (store, _file_path, test_dir) = create_test_store();
+
+        for winner in [
+            TranscriptionRacePath::Rest,
+            TranscriptionRacePath::Realtime,
+            TranscriptionRacePath::Rest,
+        ] {
+            store
+                .record_transcription_race_win(winner)
+                .expect("race winner should record");
+        }
+
+        let report = store.get_usage_stats(false).expect("stats should load");
+        assert_eq!(
+            report.race_wins,
+            TranscriptionRaceWins {
+                realtime: 1,
+                rest: 2
+            }
+        );
+        assert_eq!(report.total_transcriptions, 0);
 
         cleanup_test_dir(&test_dir);
     }
@@ -521,14 +995,14 @@ mod tests {
         let (store, _file_path, test_dir) = create_test_store();
 
         store
-            .record_transcription(25, 15.0)
+            .record_transcription(Some(25), 15.0, 0, None)
             .expect("stats recording should succeed");
         store
             .reset_usage_stats()
             .expect("stats reset should succeed");
 
         let report = store
-            .get_usage_stats()
+            .get_usage_stats(false)
             .expect("stats should load after reset");
         assert_eq!(report.total_transcriptions, 0);
         assert_eq!(report.total_words, 0);
@@ -547,7 +1021,7 @@ mod tests {
 
     #[test]
     fn streak_counts_consecutive_days_with_activity() {
-        let (store, file_path, test_dir) = create_test_store();
+        let (_store, file_path, test_dir) = create_test_store();
         let today = today_local_date();
         let yesterday = today
             .checked_sub_signed(Duration::days(1))
@@ -563,6 +1037,7 @@ mod tests {
                 transcriptions: 2,
                 words: 40,
                 recording_seconds: 20.0,
+                trimmed_silence_ms: 0,
             },
         );
         daily_stats.insert(
@@ -571,6 +1046,7 @@ mod tests {
                 transcriptions: 1,
                 words: 18,
                 recording_seconds: 8.0,
+                trimmed_silence_ms: 0,
             },
         );
         daily_stats.insert(
@@ -579,6 +1055,7 @@ mod tests {
                 transcriptions: 1,
                 words: 10,
                 recording_seconds: 5.0,
+                trimmed_silence_ms: 0,
             },
         );
 
@@ -586,7 +1063,11 @@ mod tests {
             total_transcriptions: 4,
             total_words: 68,
             total_recording_seconds: 33.0,
+            total_trimmed_silence_ms: 0,
+            transcriptions_without_word_count: 0,
+            race_wins: TranscriptionRaceWins::default(),
             daily_stats,
+            apps: BTreeMap::new(),
             last_updated: today_date_key(),
         };
 
@@ -596,8 +1077,10 @@ mod tests {
         )
         .expect("seeded usage stats file should be writable");
 
+        let store = StatsStore::new_in_dir(&test_dir).expect("legacy stats should migrate");
+        assert!(!file_path.exists());
         let report = store
-            .get_usage_stats()
+            .get_usage_stats(false)
             .expect("report should load seeded stats");
         assert_eq!(report.streak_days, 2);
         assert_eq!(report.today.words, 40);
@@ -607,12 +1090,13 @@ mod tests {
 
     #[test]
     fn recovers_from_malformed_stats_file() {
-        let (store, file_path, test_dir) = create_test_store();
+        let (_store, file_path, test_dir) = create_test_store();
         fs::write(&file_path, "{ malformed json")
             .expect("test should be able to write malformed stats json");
 
+        let store = StatsStore::new_in_dir(&test_dir).expect("store should reopen");
         let report = store
-            .get_usage_stats()
+            .get_usage_stats(false)
             .expect("store should recover malformed stats file");
         assert_eq!(report.total_transcriptions, 0);
         assert_eq!(corrupt_backup_paths(&file_path).len(), 1);
@@ -625,13 +1109,13 @@ mod tests {
         let (store, _file_path, test_dir) = create_test_store();
 
         store
-            .record_transcription(5, f64::NAN)
+            .record_transcription(Some(5), f64::NAN, 0, None)
             .expect("stats record should ignore NaN duration");
         store
-            .record_transcription(5, -10.0)
+            .record_transcription(Some(5), -10.0, 0, None)
             .expect("stats record should clamp negative duration");
 
-        let report = store.get_usage_stats().expect("stats should load");
+        let report = store.get_usage_stats(false).expect("stats should load");
         assert_eq!(report.total_transcriptions, 2);
         assert_eq!(report.total_words, 10);
         assert_almost_eq(report.total_recording_seconds, 0.0);
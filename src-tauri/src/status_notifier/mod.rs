@@ -1,7 +1,10 @@
+use std::time::Instant;
+
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum AppStatus {
     Idle,
@@ -10,6 +13,17 @@ pub enum AppStatus {
     Error,
 }
 
+impl AppStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Listening => "listening",
+            Self::Transcribing => "transcribing",
+            Self::Error => "error",
+        }
+    }
+}
+
 impl Default for AppStatus {
     fn default() -> Self {
         Self::Idle
@@ -19,6 +33,7 @@ impl Default for AppStatus {
 #[derive(Debug, Default)]
 pub struct StatusNotifier {
     current: AppStatus,
+    listening_since: Option<Instant>,
 }
 
 impl StatusNotifier {
@@ -26,8 +41,17 @@ impl StatusNotifier {
         self.current
     }
 
+    pub fn listening_since(&self) -> Option<Instant> {
+        self.listening_since
+    }
+
     pub fn set(&mut self, status: AppStatus) {
         debug!(from = ?self.current, to = ?status, "status notifier updated");
+        if status != AppStatus::Listening {
+            self.listening_since = None;
+        } else if self.current != AppStatus::Listening || self.listening_since.is_none() {
+            self.listening_since = Some(Instant::now());
+        }
         self.current = status;
     }
 }
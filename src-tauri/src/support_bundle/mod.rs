@@ -0,0 +1,299 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+const SUPPORT_BUNDLE_DIR_NAME: &str = "support";
+const SUPPORT_BUNDLE_FORMAT_VERSION: u32 = 1;
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+const REDACTED_VALUE: &str = "[redacted]";
+const SENSITIVE_KEY_FRAGMENTS: [&str; 6] =
+    ["api_key", "apikey", "token", "secret", "password", "prompt"];
+// Header values carry credentials under names like `Authorization` that the
+// key fragments above cannot catch, so the whole map is dropped.
+const CREDENTIAL_HEADER_KEYS: [&str; 1] = ["extra_headers"];
+
+#[derive(Debug, Clone, Default)]
+pub struct SupportBundleContents {
+    pub app_version: String,
+    pub logs: String,
+    pub diagnostics: Value,
+    pub settings: Value,
+    pub devices: Value,
+    pub permissions: Value,
+    pub session_metrics: Value,
+    // Empty unless session tracing is on.
+    pub last_session_trace: Value,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportBundleManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: String,
+    pub os: String,
+    pub arch: String,
+    pub files: Vec<SupportBundleFile>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportBundleFile {
+    pub name: String,
+    pub description: String,
+    pub size_bytes: u64,
+}
+
+pub fn default_bundle_path(app_data_dir: &Path) -> PathBuf {
+    let timestamp = Utc::now().format("%Y%m%d-%H%M%S");
+    app_data_dir
+        .join(SUPPORT_BUNDLE_DIR_NAME)
+        .join(format!("voice-support-{timestamp}.zip"))
+}
+
+pub fn write_support_bundle(
+    bundle_path: &Path,
+    contents: SupportBundleContents,
+) -> Result<SupportBundleManifest, String> {
+    if let Some(parent_dir) = bundle_path.parent() {
+        fs::create_dir_all(parent_dir).map_err(|error| {
+            format!(
+                "Failed to create support bundle directory `{}`: {error}",
+                parent_dir.display()
+            )
+        })?;
+    }
+
+    let mut settings = contents.settings;
+    strip_credential_headers(&mut settings);
+    redact_sensitive_values(&mut settings);
+
+    let entries = vec![
+        (
+            "logs/voice.log",
+            "Diagnostic log file",
+            contents.logs.into_bytes(),
+        ),
+        (
+            "diagnostics.json",
+            "App, platform, and pipeline diagnostics",
+            to_pretty_json(&contents.diagnostics)?,
+        ),
+        (
+            "settings.json",
+            "Current settings with sensitive values redacted",
+            to_pretty_json(&settings)?,
+        ),
+        (
+            "devices.json",
+            "Available input devices",
+            to_pretty_json(&contents.devices)?,
+        ),
+        (
+            "permissions.json",
+            "Permission snapshot",
+            to_pretty_json(&contents.permissions)?,
+        ),
+        (
+            "session_metrics.json",
+            "Recent usage and session metrics",
+            to_pretty_json(&contents.session_metrics)?,
+        ),
+        (
+            "last_session_trace.json",
+            "Pipeline events of the most recent traced dictation",
+            to_pretty_json(&contents.last_session_trace)?,
+        ),
+    ];
+
+    let file = File::create(bundle_path).map_err(|error| {
+        format!(
+            "Failed to create support bundle `{}`: {error}",
+            bundle_path.display()
+        )
+    })?;
+    let mut writer = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut files = Vec::with_capacity(entries.len());
+    for (name, description, bytes) in entries {
+        write_zip_entry(&mut writer, options, name, &bytes)?;
+        files.push(SupportBundleFile {
+            name: name.to_string(),
+            description: description.to_string(),
+            size_bytes: bytes.len() as u64,
+        });
+    }
+
+    let manifest = SupportBundleManifest {
+        format_version: SUPPORT_BUNDLE_FORMAT_VERSION,
+        app_version: contents.app_version,
+        created_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        files,
+    };
+    let manifest_bytes = serde_json::to_vec_pretty(&manifest)
+        .map_err(|error| format!("Failed to serialize support bundle manifest: {error}"))?;
+    write_zip_entry(&mut writer, options, MANIFEST_FILE_NAME, &manifest_bytes)?;
+
+    writer
+        .finish()
+        .map_err(|error| format!("Failed to finalize support bundle: {error}"))?;
+
+    Ok(manifest)
+}
+
+pub fn redact_sensitive_values(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, entry) in map.iter_mut() {
+                if is_sensitive_key(key) {
+                    if !entry.is_null() {
+                        *entry = Value::String(REDACTED_VALUE.to_string());
+                    }
+                } else {
+                    redact_sensitive_values(entry);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_sensitive_values),
+        _ => {}
+    }
+}
+
+fn strip_credential_headers(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, _| !CREDENTIAL_HEADER_KEYS.contains(&key.as_str()));
+            map.values_mut().for_each(strip_credential_headers);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_credential_headers),
+        _ => {}
+    }
+}
+
+fn is_sensitive_key(key: &str) -> bool {
+    let normalized = key.to_ascii_lowercase();
+    SENSITIVE_KEY_FRAGMENTS
+        .iter()
+        .any(|fragment| normalized.contains(fragment))
+}
+
+fn to_pretty_json(value: &Value) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value)
+        .map_err(|error| format!("Failed to serialize support bundle entry: {error}"))
+}
+
+fn write_zip_entry(
+    writer: &mut ZipWriter<File>,
+    options: SimpleFileOptions,
+    name: &str,
+    bytes: &[u8],
+) -> Result<(), String> {
+    writer
+        .start_file(name, options)
+        .map_err(|error| format!("Failed to add `{name}` to support bundle: {error}"))?;
+    writer
+        .write_all(bytes)
+        .map_err(|error| format!("Failed to write `{name}` to support bundle: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{redact_sensitive_values, write_support_bundle, SupportBundleContents};
+    use serde_json::{json, Value};
+    use std::{fs, io::Read, path::PathBuf};
+
+    fn temp_bundle_path(label: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!(
+                "voice-support-bundle-tests-{label}-{}",
+                uuid::Uuid::new_v4()
+            ))
+            .join("bundle.zip")
+    }
+
+    fn read_zip_entry(path: &PathBuf, name: &str) -> String {
+        let file = fs::File::open(path).expect("bundle should exist");
+        let mut archive = zip::ZipArchive::new(file).expect("bundle should be a zip archive");
+        let mut entry = archive.by_name(name).expect("bundle entry should exist");
+        let mut contents = String::new();
+        entry
+            .read_to_string(&mut contents)
+            .expect("bundle entry should be readable");
+        contents
+    }
+
+    #[test]
+    fn redacts_sensitive_keys_recursively() {
+        let mut value = json!({
+            "language": "en",
+            "custom_transcription_prompt": "my private vocabulary",
+            "nested": { "apiKey": "sk-test", "items": [{ "refresh_token": "abc" }] },
+            "access_token": null
+        });
+
+        redact_sensitive_values(&mut value);
+
+        assert_eq!(value["language"], "en");
+        assert_eq!(value["custom_transcription_prompt"], "[redacted]");
+        assert_eq!(value["nested"]["apiKey"], "[redacted]");
+        assert_eq!(value["nested"]["items"][0]["refresh_token"], "[redacted]");
+        assert_eq!(value["access_token"], Value::Null);
+    }
+
+    #[test]
+    fn writes_bundle_with_manifest_and_redacted_settings() {
+        let bundle_path = temp_bundle_path("write");
+        let manifest = write_support_bundle(
+            &bundle_path,
+            SupportBundleContents {
+                app_version: "1.2.3".to_string(),
+                logs: "log line".to_string(),
+                diagnostics: json!({ "status": "idle" }),
+                settings: json!({
+                    "custom_transcription_prompt": "secret words",
+                    "provider_config": {
+                        "base_url": "https://llm.example.com/v1",
+                        "extra_headers": {
+                            "Authorization": "Bearer header-credential",
+                            "x-goog-api-key": "goog-credential"
+                        }
+                    }
+                }),
+                devices: json!([{ "id": "mic-1" }]),
+                permissions: json!({ "allGranted": true }),
+                session_metrics: json!({ "totalTranscriptions": 3 }),
+                last_session_trace: json!([{ "event": "voice://status-changed" }]),
+            },
+        )
+        .expect("bundle should be written");
+
+        assert_eq!(manifest.app_version, "1.2.3");
+        assert_eq!(manifest.files.len(), 7);
+        assert_eq!(read_zip_entry(&bundle_path, "logs/voice.log"), "log line");
+        let settings = read_zip_entry(&bundle_path, "settings.json");
+        assert!(!settings.contains("secret words"));
+        assert!(!settings.contains("header-credential"));
+        assert!(!settings.contains("goog-credential"));
+        assert!(settings.contains("https://llm.example.com/v1"));
+
+        let manifest_json: Value =
+            serde_json::from_str(&read_zip_entry(&bundle_path, "manifest.json"))
+                .expect("manifest should be valid json");
+        assert_eq!(manifest_json["formatVersion"], 1);
+        assert_eq!(manifest_json["files"][0]["name"], "logs/voice.log");
+
+        if let Some(parent) = bundle_path.parent() {
+            let _ = fs::remove_dir_all(parent);
+        }
+    }
+}
@@ -0,0 +1,309 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, info, warn};
+
+use crate::app_error::ErrorCode;
+
+const QUEUE_FILE_NAME: &str = "telemetry_queue.json";
+// Sent once this many events are waiting, and at launch.
+pub const TELEMETRY_BATCH_SIZE: usize = 20;
+// An unreachable endpoint should not grow the queue forever.
+const MAX_QUEUED_EVENTS: usize = 500;
+
+// Deliberately coarse: no transcript text, app names, durations or ids that
+// could tie events to a person.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TelemetryEventKind {
+    #[serde(rename_all = "camelCase")]
+    DictationCompleted {
+        recording_mode: String,
+        provider: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    DictationFailed {
+        stage: String,
+        error_code: ErrorCode,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryEvent {
+    // Day only, so event order within a day cannot be reconstructed.
+    pub date: String,
+    #[serde(flatten)]
+    pub kind: TelemetryEventKind,
+}
+
+impl TelemetryEvent {
+    pub fn new(kind: TelemetryEventKind) -> Self {
+        Self {
+            date: Utc::now().format("%Y-%m-%d").to_string(),
+            kind,
+        }
+    }
+}
+
+// Events wait on disk until a batch is sent, so the user can inspect exactly
+// what would leave the machine.
+#[derive(Debug)]
+pub struct TelemetryQueue {
+    file_path: PathBuf,
+    events: Mutex<Vec<TelemetryEvent>>,
+    // Bumped by `clear`, so a batch that fails to send after the kill switch
+    // is not put back.
+    clear_count: AtomicU64,
+}
+
+impl TelemetryQueue {
+    pub fn new_in_dir(app_data_dir: &Path) -> Self {
+        let file_path = app_data_dir.join(QUEUE_FILE_NAME);
+        let events = load_queue(&file_path);
+        debug!(queued = events.len(), "telemetry queue initialized");
+        Self {
+            file_path,
+            events: Mutex::new(events),
+            clear_count: AtomicU64::new(0),
+        }
+    }
+
+    pub fn pending(&self) -> Vec<TelemetryEvent> {
+        self.events
+            .lock()
+            .map(|events| events.clone())
+            .unwrap_or_default()
+    }
+
+    // Returns how many events are now waiting.
+    pub fn record(&self, event: TelemetryEvent) -> Result<usize, String> {
+        let mut events = self
+            .events
+            .lock()
+            .map_err(|_| "Telemetry queue lock is poisoned".to_string())?;
+        events.push(event);
+        drop_overflow(&mut events);
+        self.persist(&events)?;
+        Ok(events.len())
+    }
+
+    // The kill switch: turning telemetry off drops anything not yet sent.
+    pub fn clear(&self) -> Result<(), String> {
+        let mut events = self
+            .events
+            .lock()
+            .map_err(|_| "Telemetry queue lock is poisoned".to_string())?;
+        self.clear_count.fetch_add(1, Ordering::Relaxed);
+        if events.is_empty() && !self.file_path.exists() {
+            return Ok(());
+        }
+        events.clear();
+        match fs::remove_file(&self.file_path) {
+            Ok(()) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(format!("Failed to clear telemetry queue: {error}")),
+        }
+        info!("telemetry queue cleared");
+        Ok(())
+    }
+
+    // Takes everything queued for one request, so events recorded meanwhile
+    // wait for the next batch. A batch that fails goes back in front of them.
+    pub async fn flush(&self, endpoint: &str, app_version: &str) -> Result<usize, String> {
+        let (batch, clear_count) = {
+            let mut events = self
+                .events
+                .lock()
+                .map_err(|_| "Telemetry queue lock is poisoned".to_string())?;
+            (
+                std::mem::take(&mut *events),
+                self.clear_count.load(Ordering::Relaxed),
+            )
+        };
+        if batch.is_empty() {
+            return Ok(0);
+        }
+        let sent = batch.len();
+        let result = send_batch(endpoint, app_version, &batch).await;
+
+        let mut events = self
+            .events
+            .lock()
+            .map_err(|_| "Telemetry queue lock is poisoned".to_string())?;
+        if let Err(error) = result {
+            if self.clear_count.load(Ordering::Relaxed) == clear_count {
+                let recorded = std::mem::replace(&mut *events, batch);
+                events.extend(recorded);
+                drop_overflow(&mut events);
+                if let Err(persist_error) = self.persist(&events) {
+                    warn!(error = %persist_error, "failed to requeue unsent telemetry");
+                }
+            }
+            return Err(error);
+        }
+        self.persist(&events)?;
+        info!(sent, "telemetry batch sent");
+        Ok(sent)
+    }
+
+    fn persist(&self, events: &[TelemetryEvent]) -> Result<(), String> {
+        if let Some(parent_dir) = self.file_path.parent() {
+            fs::create_dir_all(parent_dir)
+                .map_err(|error| format!("Failed to create telemetry directory: {error}"))?;
+        }
+        let serialized = serde_json::to_vec_pretty(events)
+            .map_err(|error| format!("Failed to serialize telemetry queue: {error}"))?;
+        fs::write(&self.file_path, serialized)
+            .map_err(|error| format!("Failed to write telemetry queue: {error}"))
+    }
+}
+
+async fn send_batch(
+    endpoint: &str,
+    app_version: &str,
+    batch: &[TelemetryEvent],
+) -> Result<(), String> {
+    let response = crate::http_client::shared_client()
+        .post(endpoint)
+        .json(&json!({ "appVersion": app_version, "events": batch }))
+        .send()
+        .await
+        .map_err(|error| format!("Failed to send telemetry: {error}"))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Telemetry endpoint rejected the batch with status {}",
+            response.status()
+        ));
+    }
+    Ok(())
+}
+
+// The oldest events go first.
+fn drop_overflow(events: &mut Vec<TelemetryEvent>) {
+    let overflow = events.len().saturating_sub(MAX_QUEUED_EVENTS);
+    events.drain(..overflow);
+}
+
+fn load_queue(file_path: &Path) -> Vec<TelemetryEvent> {
+    let Ok(contents) = fs::read(file_path) else {
+        return Vec::new();
+    };
+    serde_json::from_slice(&contents).unwrap_or_else(|error| {
+        warn!(path = %file_path.display(), %error, "dropping malformed telemetry queue");
+        Vec::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{TelemetryEvent, TelemetryEventKind, TelemetryQueue, MAX_QUEUED_EVENTS};
+    use crate::app_error::ErrorCode;
+
+    fn completed() -> TelemetryEvent {
+        TelemetryEvent::new(TelemetryEventKind::DictationCompleted {
+            recording_mode: "toggle".to_string(),
+            provider: "openai".to_string(),
+        })
+    }
+
+    #[test]
+    fn queue_survives_restart_is_capped_and_clears() {
+        let dir = std::env::temp_dir().join(format!("voice-telemetry-{}", uuid::Uuid::new_v4()));
+        let queue = TelemetryQueue::new_in_dir(&dir);
+        for _ in 0..MAX_QUEUED_EVENTS {
+            queue.record(completed()).expect("event should record");
+        }
+        let queued = queue
+            .record(TelemetryEvent::new(TelemetryEventKind::DictationFailed {
+                stage: "transcription".to_string(),
+                error_code: ErrorCode::Network,
+            }))
+            .expect("event should record");
+        assert_eq!(queued, MAX_QUEUED_EVENTS);
+
+        let reloaded = TelemetryQueue::new_in_dir(&dir).pending();
+        assert_eq!(reloaded.len(), MAX_QUEUED_EVENTS);
+        assert!(matches!(
+            reloaded.last().map(|event| &event.kind),
+            Some(TelemetryEventKind::DictationFailed { .. })
+        ));
+
+        queue.clear().expect("queue should clear");
+        assert!(queue.pending().is_empty());
+        assert!(TelemetryQueue::new_in_dir(&dir).pending().is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    // Each request records an event while it is in flight, which is how a
+    // dictation finishing during a flush looks to the queue.
+    #[tokio::test]
+    async fn flush_keeps_events_recorded_during_the_send_and_requeues_failures() {
+        let dir = std::env::temp_dir().join(format!("voice-telemetry-{}", uuid::Uuid::new_v4()));
+        let queue = Arc::new(TelemetryQueue::new_in_dir(&dir));
+        let failed = || {
+            TelemetryEvent::new(TelemetryEventKind::DictationFailed {
+                stage: "insertion".to_string(),
+                error_code: ErrorCode::PermissionDenied,
+            })
+        };
+        let mut server = mockito::Server::new_async().await;
+        let recording_queue = queue.clone();
+        let rejected = server
+            .mock("POST", "/")
+            .with_status_code_from_request(move |_| {
+                recording_queue
+                    .record(failed())
+                    .expect("event should record");
+                500
+            })
+            .create_async()
+            .await;
+
+        queue.record(completed()).expect("event should record");
+        assert!(queue.flush(&server.url(), "1.0.0").await.is_err());
+        rejected.assert_async().await;
+        assert_eq!(queue.pending(), [completed(), failed()]);
+        rejected.remove_async().await;
+
+        let recording_queue = queue.clone();
+        let accepted = server
+            .mock("POST", "/")
+            .with_status_code_from_request(move |_| {
+                recording_queue
+                    .record(completed())
+                    .expect("event should record");
+                200
+            })
+            .create_async()
+            .await;
+        assert_eq!(queue.flush(&server.url(), "1.0.0").await, Ok(2));
+        accepted.assert_async().await;
+        assert_eq!(queue.pending(), [completed()]);
+        assert_eq!(TelemetryQueue::new_in_dir(&dir).pending(), [completed()]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn events_serialize_flat_and_coarse() {
+        let value = serde_json::to_value(completed()).expect("event should serialize");
+
+        assert_eq!(value["kind"], "dictation_completed");
+        assert_eq!(value["recordingMode"], "toggle");
+        assert_eq!(value["date"].as_str().map(str::len), Some(10));
+    }
+}
@@ -0,0 +1,390 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Spoken before a list item: "groceries dash milk dash eggs".
+const BULLET_WORD: &str = "dash";
+// Spoken before a number to force digits even for one to nine.
+const NUMBER_WORD: &str = "number";
+
+// Deterministic clean-up of a finished transcript, chosen per profile. Every
+// pass is off by default so transcripts arrive as the provider wrote them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FormattingOptions {
+    // Capitalizes the first letter of every sentence and list item.
+    pub sentence_case: bool,
+    // "twenty three" becomes "23"; English only.
+    pub numerals: bool,
+    // Two or more spoken "dash"es turn the transcript into a bulleted list.
+    pub bullet_lists: bool,
+    // Straight quotes and apostrophes become curly ones.
+    pub smart_quotes: bool,
+}
+
+impl FormattingOptions {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+pub fn format_transcript(text: &str, options: FormattingOptions, language: Option<&str>) -> String {
+    let mut text = text.to_string();
+    if options.bullet_lists {
+        text = format_bullet_lists(&text);
+    }
+    if options.numerals && is_english(language) {
+        text = text
+            .lines()
+            .map(convert_numerals)
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+    if options.sentence_case {
+        text = sentence_case(&text);
+    }
+    if options.smart_quotes {
+        text = smart_quotes(&text);
+    }
+    text
+}
+
+// Untagged transcripts are assumed to be English, like the providers do.
+fn is_english(language: Option<&str>) -> bool {
+    language.is_none_or(|language| {
+        let language = language.trim().to_ascii_lowercase();
+        language == "english" || language.split(['-', '_']).next() == Some("en")
+    })
+}
+
+fn bare_word(word: &str) -> String {
+    word.trim_matches(|character: char| !character.is_alphanumeric())
+        .to_lowercase()
+}
+
+fn is_bullet(word: &str) -> bool {
+    bare_word(word) == BULLET_WORD
+}
+
+// A single "dash" mid-sentence is more likely the word than a bullet, so a
+// list needs two of them or one at the very start.
+fn format_bullet_lists(text: &str) -> String {
+    let words = text.split_whitespace().collect::<Vec<_>>();
+    let bullets = words.iter().filter(|word| is_bullet(word)).count();
+    if bullets == 0 || (bullets == 1 && !words.first().is_some_and(|word| is_bullet(word))) {
+        return text.to_string();
+    }
+
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    let mut in_item = false;
+    for word in words {
+        if is_bullet(word) {
+            push_list_line(&mut lines, &line, in_item);
+            line.clear();
+            in_item = true;
+        } else {
+            line.push(word);
+        }
+    }
+    push_list_line(&mut lines, &line, in_item);
+    lines.join("\n")
+}
+
+fn push_list_line(lines: &mut Vec<String>, words: &[&str], item: bool) {
+    let line = words.join(" ");
+    let line = if item {
+        line.trim_end_matches([',', ';', '.'])
+    } else {
+        line.trim_end_matches([',', ';'])
+    };
+    if line.is_empty() {
+        return;
+    }
+    if item {
+        lines.push(format!("- {line}"));
+    } else {
+        lines.push(line.to_string());
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberWord {
+    Unit(u64),
+    Ten(u64),
+    Hundred,
+    Scale(u64),
+}
+
+fn number_word(word: &str) -> Option<NumberWord> {
+    let value = match word.to_lowercase().as_str() {
+        "zero" => NumberWord::Unit(0),
+        "one" => NumberWord::Unit(1),
+        "two" => NumberWord::Unit(2),
+        "three" => NumberWord::Unit(3),
+        "four" => NumberWord::Unit(4),
+        "five" => NumberWord::Unit(5),
+        "six" => NumberWord::Unit(6),
+        "seven" => NumberWord::Unit(7),
+        "eight" => NumberWord::Unit(8),
+        "nine" => NumberWord::Unit(9),
+        "ten" => NumberWord::Unit(10),
+        "eleven" => NumberWord::Unit(11),
+        "twelve" => NumberWord::Unit(12),
+        "thirteen" => NumberWord::Unit(13),
+        "fourteen" => NumberWord::Unit(14),
+        "fifteen" => NumberWord::Unit(15),
+        "sixteen" => NumberWord::Unit(16),
+        "seventeen" => NumberWord::Unit(17),
+        "eighteen" => NumberWord::Unit(18),
+        "nineteen" => NumberWord::Unit(19),
+        "twenty" => NumberWord::Ten(20),
+        "thirty" => NumberWord::Ten(30),
+        "forty" => NumberWord::Ten(40),
+        "fifty" => NumberWord::Ten(50),
+        "sixty" => NumberWord::Ten(60),
+        "seventy" => NumberWord::Ten(70),
+        "eighty" => NumberWord::Ten(80),
+        "ninety" => NumberWord::Ten(90),
+        "hundred" => NumberWord::Hundred,
+        "thousand" => NumberWord::Scale(1_000),
+        "million" => NumberWord::Scale(1_000_000),
+        "billion" => NumberWord::Scale(1_000_000_000),
+        _ => return None,
+    };
+    Some(value)
+}
+
+// A number being read left to right. Words that cannot follow the previous
+// one ("twenty twenty", "three four") end it.
+#[derive(Debug, Clone, Copy, Default)]
+struct SpokenNumber {
+    total: u64,
+    current: u64,
+    last: Option<NumberWord>,
+}
+
+impl SpokenNumber {
+    fn push(&mut self, word: NumberWord) -> bool {
+        let accepted = match (self.last, word) {
+            (Some(NumberWord::Unit(0)), _) => false,
+            (None, NumberWord::Unit(_) | NumberWord::Ten(_)) => true,
+            (Some(NumberWord::Hundred | NumberWord::Scale(_)), NumberWord::Unit(0)) => false,
+            (
+                Some(NumberWord::Hundred | NumberWord::Scale(_)),
+                NumberWord::Unit(_) | NumberWord::Ten(_),
+            ) => true,
+            (Some(NumberWord::Ten(_)), NumberWord::Unit(unit)) => (1..10).contains(&unit),
+            (Some(NumberWord::Unit(_)), NumberWord::Hundred) => self.current % 100 < 20,
+            (
+                Some(NumberWord::Unit(_) | NumberWord::Ten(_) | NumberWord::Hundred),
+                NumberWord::Scale(_),
+            ) => true,
+            _ => false,
+        };
+        if !accepted {
+            return false;
+        }
+        match word {
+            NumberWord::Unit(value) | NumberWord::Ten(value) => self.current += value,
+            NumberWord::Hundred => self.current *= 100,
+            NumberWord::Scale(scale) => {
+                self.total += self.current * scale;
+                self.current = 0;
+            }
+        }
+        self.last = Some(word);
+        true
+    }
+
+    fn value(&self) -> u64 {
+        self.total + self.current
+    }
+}
+
+// Reads the longest number at the start of `tokens`, returning its value,
+// how many tokens it used, and any punctuation after its last word.
+fn read_number<'a>(tokens: &[&'a str]) -> Option<(u64, usize, &'a str)> {
+    let mut number = SpokenNumber::default();
+    let mut consumed = 0;
+    let mut trailing = "";
+    while let Some(token) = tokens.get(consumed) {
+        let core = token.trim_end_matches(|character: char| !character.is_alphanumeric());
+        let punctuation = &token[core.len()..];
+        if core.eq_ignore_ascii_case("and") && punctuation.is_empty() && number.last.is_some() {
+            // Only "one hundred and five", never a trailing "and".
+            let continues = matches!(
+                number.last,
+                Some(NumberWord::Hundred | NumberWord::Scale(_))
+            ) && tokens
+                .get(consumed + 1)
+                .and_then(|next| number_word(next.split('-').next().unwrap_or(next)))
+                .is_some_and(|word| matches!(word, NumberWord::Unit(_) | NumberWord::Ten(_)));
+            if !continues {
+                break;
+            }
+            consumed += 1;
+            continue;
+        }
+        let mut next = number;
+        let parsed = !core.is_empty()
+            && core
+                .split('-')
+                .all(|part| number_word(part).is_some_and(|word| next.push(word)));
+        if !parsed {
+            break;
+        }
+        number = next;
+        consumed += 1;
+        if !punctuation.is_empty() {
+            trailing = punctuation;
+            break;
+        }
+    }
+    number.last?;
+    Some((number.value(), consumed, trailing))
+}
+
+// One to nine stay as words unless "number" was said first, which is
+// dropped: "number five" becomes "5".
+fn convert_numerals(line: &str) -> String {
+    let tokens = line.split_whitespace().collect::<Vec<_>>();
+    let mut converted: Vec<String> = Vec::with_capacity(tokens.len());
+    let mut index = 0;
+    while index < tokens.len() {
+        let after_marker = index > 0 && tokens[index - 1].eq_ignore_ascii_case(NUMBER_WORD);
+        match read_number(&tokens[index..]) {
+            Some((value, consumed, trailing)) if consumed > 1 || value >= 10 || after_marker => {
+                if after_marker {
+                    converted.pop();
+                }
+                converted.push(format!("{value}{trailing}"));
+                index += consumed;
+            }
+            _ => {
+                converted.push(tokens[index].to_string());
+                index += 1;
+            }
+        }
+    }
+    let indent = &line[..line.len() - line.trim_start().len()];
+    format!("{indent}{}", converted.join(" "))
+}
+
+// Sentences start at the beginning, on every new line, and after `.`, `!` or
+// `?` followed by whitespace, so "3.5" and "node.js" are left alone.
+fn sentence_case(text: &str) -> String {
+    let mut cased = String::with_capacity(text.len());
+    let mut capitalize = true;
+    let mut after_terminator = false;
+    for character in text.chars() {
+        if character.is_alphanumeric() {
+            if capitalize {
+                cased.extend(character.to_uppercase());
+            } else {
+                cased.push(character);
+            }
+            capitalize = false;
+            after_terminator = false;
+            continue;
+        }
+        if character == '\n' || (after_terminator && character.is_whitespace()) {
+            capitalize = true;
+        }
+        after_terminator = matches!(character, '.' | '!' | '?')
+            || (after_terminator && !character.is_whitespace());
+        cased.push(character);
+    }
+    cased
+}
+
+// Quotes open after whitespace or an opening bracket and close everywhere
+// else, which also turns apostrophes into `’`.
+fn smart_quotes(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len());
+    let mut previous: Option<char> = None;
+    for character in text.chars() {
+        let opens = previous.is_none_or(|previous| {
+            previous.is_whitespace()
+                || matches!(previous, '(' | '[' | '{' | '\u{201C}' | '\u{2018}')
+        });
+        let replaced = match character {
+            '"' if opens => '\u{201C}',
+            '"' => '\u{201D}',
+            '\'' if opens => '\u{2018}',
+            '\'' => '\u{2019}',
+            other => other,
+        };
+        quoted.push(replaced);
+        previous = Some(replaced);
+    }
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_transcript, FormattingOptions};
+
+    fn all_passes() -> FormattingOptions {
+        FormattingOptions {
+            sentence_case: true,
+            numerals: true,
+            bullet_lists: true,
+            smart_quotes: true,
+        }
+    }
+
+    #[test]
+    fn converts_spoken_numbers_but_keeps_small_ones_as_words() {
+        let numerals = FormattingOptions {
+            numerals: true,
+            ..FormattingOptions::default()
+        };
+        let format = |text: &str| format_transcript(text, numerals, Some("en"));
+
+        assert_eq!(format("room number twenty three"), "room 23");
+        assert_eq!(
+            format("one of the three options"),
+            "one of the three options"
+        );
+        assert_eq!(format("take number five."), "take 5.");
+        assert_eq!(
+            format("about two thousand four hundred and fifty-six people, roughly"),
+            "about 2456 people, roughly"
+        );
+        assert_eq!(format("twelve, twenty and nineteen"), "12, 20 and 19");
+        assert_eq!(format("twenty twenty one"), "20 21");
+        assert_eq!(format("one hundred and then some"), "100 and then some");
+        assert_eq!(
+            format_transcript("vingt trois", numerals, Some("fr")),
+            "vingt trois"
+        );
+    }
+
+    #[test]
+    fn formats_lists_sentences_and_quotes() {
+        assert_eq!(
+            format_transcript(
+                "groceries, dash milk, dash eggs dash twelve apples.",
+                all_passes(),
+                None
+            ),
+            "Groceries\n- Milk\n- Eggs\n- 12 apples"
+        );
+        assert_eq!(
+            format_transcript(
+                "she said \"it's done.\" then left! version 3.5 of node.js works",
+                all_passes(),
+                None
+            ),
+            "She said \u{201C}it\u{2019}s done.\u{201D} Then left! Version 3.5 of node.js works"
+        );
+        assert_eq!(
+            format_transcript("hit the dash key", all_passes(), None),
+            "Hit the dash key"
+        );
+        assert_eq!(
+            format_transcript("leave this alone", FormattingOptions::default(), None),
+            "leave this alone"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::{ffi::c_void, ptr};
+
+use super::{
+    kCFAllocatorDefault, verification::copy_attribute, AXError, AXUIElementCreateSystemWide,
+    AXUIElementRef, Boolean, CFRelease, CFStringCreateWithCString, CFStringRef, CFTypeRef,
+    AX_SUCCESS, K_CF_STRING_ENCODING_UTF8,
+};
+
+const K_AX_VALUE_CG_RECT_TYPE: u32 = 3;
+const AX_BOUNDS_FOR_RANGE_ATTRIBUTE: &[u8] = b"AXBoundsForRange\0";
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+struct CGRect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+#[link(name = "ApplicationServices", kind = "framework")]
+unsafe extern "C" {
+    fn AXUIElementCopyParameterizedAttributeValue(
+        element: AXUIElementRef,
+        parameterized_attribute: CFStringRef,
+        parameter: CFTypeRef,
+        result: *mut CFTypeRef,
+    ) -> AXError;
+    fn AXValueGetValue(value: CFTypeRef, value_type: u32, value_ptr: *mut c_void) -> Boolean;
+}
+
+// The point just below the text caret of the focused element, in global
+// top-left screen points, when the app exposes its selection bounds.
+pub fn focused_caret_position() -> Option<(f64, f64)> {
+    unsafe {
+        let system_wide = AXUIElementCreateSystemWide();
+        if system_wide.is_null() {
+            return None;
+        }
+        let element = copy_attribute(system_wide, b"AXFocusedUIElement\0");
+        CFRelease(system_wide as CFTypeRef);
+        if element.is_null() {
+            return None;
+        }
+
+        let range = copy_attribute(element, b"AXSelectedTextRange\0");
+        let bounds = if range.is_null() {
+            None
+        } else {
+            let bounds = bounds_for_range(element, range);
+            CFRelease(range);
+            bounds
+        };
+        CFRelease(element);
+
+        bounds
+            .filter(|rect| rect.x.is_finite() && rect.y.is_finite() && rect.height >= 0.0)
+            .map(|rect| (rect.x, rect.y + rect.height))
+    }
+}
+
+unsafe fn bounds_for_range(element: AXUIElementRef, range: CFTypeRef) -> Option<CGRect> {
+    let attribute = CFStringCreateWithCString(
+        kCFAllocatorDefault,
+        AX_BOUNDS_FOR_RANGE_ATTRIBUTE.as_ptr() as *const i8,
+        K_CF_STRING_ENCODING_UTF8,
+    );
+    if attribute.is_null() {
+        return None;
+    }
+    let mut value: CFTypeRef = ptr::null();
+    let status = AXUIElementCopyParameterizedAttributeValue(element, attribute, range, &mut value);
+    CFRelease(attribute);
+    if value.is_null() {
+        return None;
+    }
+
+    let mut rect = CGRect::default();
+    let converted = status == AX_SUCCESS
+        && AXValueGetValue(
+            value,
+            K_AX_VALUE_CG_RECT_TYPE,
+            &mut rect as *mut CGRect as *mut c_void,
+        ) != 0;
+    CFRelease(value);
+    converted.then_some(rect)
+}
@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+use crate::settings_store::VoiceSettings;
+
+// Every representation of every pasteboard item (plain text, RTF, images,
+// file URLs), keyed by type identifier, so a paste can put back exactly what
+// the user had copied.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClipboardSnapshot {
+    items: Vec<Vec<(String, Vec<u8>)>>,
+}
+
+#[cfg(test)]
+impl ClipboardSnapshot {
+    pub(super) fn from_text(text: &str) -> Self {
+        Self {
+            items: vec![vec![(
+                "public.utf8-plain-text".to_string(),
+                text.as_bytes().to_vec(),
+            )]],
+        }
+    }
+
+    pub(super) fn text(&self) -> Option<String> {
+        self.items.iter().flatten().find_map(|(kind, bytes)| {
+            (kind == "public.utf8-plain-text").then(|| String::from_utf8_lossy(bytes).into_owned())
+        })
+    }
+}
+
+// Whether a paste puts the previous clipboard back, and how long to wait for
+// the target app to read the transcript first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClipboardRestore {
+    pub enabled: bool,
+    pub delay: Duration,
+}
+
+impl ClipboardRestore {
+    pub fn from_settings(settings: &VoiceSettings) -> Self {
+        Self {
+            enabled: settings.preserve_clipboard,
+            delay: Duration::from_millis(u64::from(settings.clipboard_restore_delay_ms)),
+        }
+    }
+}
+
+impl Default for ClipboardRestore {
+    fn default() -> Self {
+        Self::from_settings(&VoiceSettings::default())
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub(super) use macos::{restore_clipboard, snapshot_clipboard};
+
+#[cfg(not(target_os = "macos"))]
+pub(super) fn snapshot_clipboard() -> Result<ClipboardSnapshot, String> {
+    Err("Clipboard snapshots are only supported on macOS".to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub(super) fn restore_clipboard(_snapshot: &ClipboardSnapshot) -> Result<(), String> {
+    Err("Clipboard snapshots are only supported on macOS".to_string())
+}
+
+#[cfg(target_os = "macos")]
+#[allow(unexpected_cfgs)]
+mod macos {
+    use std::ffi::{c_char, c_void, CStr, CString};
+
+    use objc::{
+        class, msg_send,
+        rc::autoreleasepool,
+        runtime::{Object, BOOL, NO},
+        sel, sel_impl,
+    };
+
+    use super::ClipboardSnapshot;
+
+    pub(in crate::text_insertion_service) fn snapshot_clipboard(
+    ) -> Result<ClipboardSnapshot, String> {
+        autoreleasepool(|| unsafe {
+            let pasteboard = general_pasteboard()?;
+            let mut snapshot = ClipboardSnapshot::default();
+            let items: *mut Object = msg_send![pasteboard, pasteboardItems];
+            if items.is_null() {
+                return Ok(snapshot);
+            }
+            let item_count: usize = msg_send![items, count];
+            for item_index in 0..item_count {
+                let item: *mut Object = msg_send![items, objectAtIndex: item_index];
+                let types: *mut Object = msg_send![item, types];
+                let type_count: usize = if types.is_null() {
+                    0
+                } else {
+                    msg_send![types, count]
+                };
+                let mut representations = Vec::with_capacity(type_count);
+                for type_index in 0..type_count {
+                    let pasteboard_type: *mut Object = msg_send![types, objectAtIndex: type_index];
+                    let data: *mut Object = msg_send![item, dataForType: pasteboard_type];
+                    let Some(kind) = ns_string_to_string(pasteboard_type) else {
+                        continue;
+                    };
+                    if data.is_null() {
+                        continue;
+                    }
+                    let length: usize = msg_send![data, length];
+                    let bytes: *const u8 = msg_send![data, bytes];
+                    let bytes = if length == 0 || bytes.is_null() {
+                        Vec::new()
+                    } else {
+                        std::slice::from_raw_parts(bytes, length).to_vec()
+                    };
+                    representations.push((kind, bytes));
+                }
+                if !representations.is_empty() {
+                    snapshot.items.push(representations);
+                }
+            }
+            Ok(snapshot)
+        })
+    }
+
+    pub(in crate::text_insertion_service) fn restore_clipboard(
+        snapshot: &ClipboardSnapshot,
+    ) -> Result<(), String> {
+        autoreleasepool(|| unsafe {
+            let pasteboard = general_pasteboard()?;
+            let _: isize = msg_send![pasteboard, clearContents];
+            if snapshot.items.is_empty() {
+                return Ok(());
+            }
+
+            let items: *mut Object = msg_send![class!(NSMutableArray), array];
+            for representations in &snapshot.items {
+                let item: *mut Object = msg_send![class!(NSPasteboardItem), new];
+                for (kind, bytes) in representations {
+                    let Ok(kind) = CString::new(kind.as_str()) else {
+                        continue;
+                    };
+                    let kind: *mut Object =
+                        msg_send![class!(NSString), stringWithUTF8String: kind.as_ptr()];
+                    let data: *mut Object = msg_send![
+                        class!(NSData),
+                        dataWithBytes: bytes.as_ptr() as *const c_void
+                        length: bytes.len()
+                    ];
+                    let _: BOOL = msg_send![item, setData: data forType: kind];
+                }
+                let _: () = msg_send![items, addObject: item];
+                let _: () = msg_send![item, release];
+            }
+
+            let written: BOOL = msg_send![pasteboard, writeObjects: items];
+            if written == NO {
+                return Err("The pasteboard rejected the saved clipboard contents".to_string());
+            }
+            Ok(())
+        })
+    }
+
+    unsafe fn general_pasteboard() -> Result<*mut Object, String> {
+        let pasteboard: *mut Object = msg_send![class!(NSPasteboard), generalPasteboard];
+        if pasteboard.is_null() {
+            return Err("The general pasteboard is unavailable".to_string());
+        }
+        Ok(pasteboard)
+    }
+
+    unsafe fn ns_string_to_string(value: *mut Object) -> Option<String> {
+        if value.is_null() {
+            return None;
+        }
+        let utf8: *const c_char = msg_send![value, UTF8String];
+        if utf8.is_null() {
+            return None;
+        }
+        Some(CStr::from_ptr(utf8).to_string_lossy().into_owned())
+    }
+}
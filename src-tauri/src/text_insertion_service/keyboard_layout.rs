@@ -0,0 +1,204 @@
+use std::{collections::HashMap, ffi::c_void};
+
+use tracing::debug;
+
+use super::{CFRelease, CFStringRef, CFTypeRef, CGEventFlags, CGKeyCode};
+
+const K_UC_KEY_ACTION_DISPLAY: u16 = 3;
+const KEY_CODE_COUNT: CGKeyCode = 128;
+// `UCKeyTranslate` takes the Carbon modifier bits shifted down by eight.
+const CARBON_SHIFT: u32 = 0x02;
+const CARBON_OPTION: u32 = 0x08;
+const K_CG_EVENT_FLAG_MASK_SHIFT: CGEventFlags = 0x0002_0000;
+const K_CG_EVENT_FLAG_MASK_ALTERNATE: CGEventFlags = 0x0008_0000;
+// Fewest modifiers first, so a character reachable several ways gets the
+// plainest key.
+const MODIFIER_STATES: [(u32, CGEventFlags); 4] = [
+    (0, 0),
+    (CARBON_SHIFT, K_CG_EVENT_FLAG_MASK_SHIFT),
+    (CARBON_OPTION, K_CG_EVENT_FLAG_MASK_ALTERNATE),
+    (
+        CARBON_SHIFT | CARBON_OPTION,
+        K_CG_EVENT_FLAG_MASK_SHIFT | K_CG_EVENT_FLAG_MASK_ALTERNATE,
+    ),
+];
+
+type TISInputSourceRef = *mut c_void;
+type CFDataRef = *const c_void;
+
+#[link(name = "Carbon", kind = "framework")]
+unsafe extern "C" {
+    fn TISCopyCurrentKeyboardLayoutInputSource() -> TISInputSourceRef;
+    fn TISGetInputSourceProperty(source: TISInputSourceRef, key: CFStringRef) -> CFTypeRef;
+    fn LMGetKbdType() -> u8;
+    fn UCKeyTranslate(
+        key_layout: *const c_void,
+        virtual_key_code: u16,
+        key_action: u16,
+        modifier_key_state: u32,
+        keyboard_type: u32,
+        key_translate_options: u32,
+        dead_key_state: *mut u32,
+        max_string_length: usize,
+        actual_string_length: *mut usize,
+        unicode_string: *mut u16,
+    ) -> i32;
+
+    static kTISPropertyUnicodeKeyLayoutData: CFStringRef;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+unsafe extern "C" {
+    fn CFDataGetBytePtr(data: CFDataRef) -> *const u8;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct LayoutKey {
+    pub key_code: CGKeyCode,
+    pub flags: CGEventFlags,
+}
+
+// Unicode keystrokes are posted on key code 0 with the text attached. Most
+// apps read the text, but terminals, Java apps and remote desktop clients
+// read the key code, which is "a" only on US-style layouts; on AZERTY or a
+// Cyrillic layout they typed the wrong letters. Sending the key that really
+// produces the character on the active layout keeps both readings in step.
+#[derive(Debug, Default)]
+pub(super) struct KeyboardLayoutMap {
+    keys: HashMap<char, LayoutKey>,
+}
+
+impl KeyboardLayoutMap {
+    // Empty when the layout cannot be read, e.g. for input methods without
+    // key layout data; every character then keeps the Unicode-only keystroke.
+    pub(super) fn current() -> Self {
+        let map = Self::from_translations(translate_current_layout());
+        debug!(mapped_characters = map.keys.len(), "keyboard layout mapped");
+        map
+    }
+
+    fn from_translations(
+        translations: impl IntoIterator<Item = (CGKeyCode, CGEventFlags, String)>,
+    ) -> Self {
+        let mut keys = HashMap::new();
+        for (key_code, flags, output) in translations {
+            let mut characters = output.chars();
+            let (Some(character), None) = (characters.next(), characters.next()) else {
+                continue;
+            };
+            if !character.is_control() {
+                keys.entry(character)
+                    .or_insert(LayoutKey { key_code, flags });
+            }
+        }
+        Self { keys }
+    }
+
+    // Only keystrokes carrying a single character can be matched to a key.
+    pub(super) fn key_for(&self, chunk: &[u16]) -> Option<LayoutKey> {
+        let mut characters = char::decode_utf16(chunk.iter().copied());
+        match (characters.next(), characters.next()) {
+            (Some(Ok(character)), None) => self.keys.get(&character).copied(),
+            _ => None,
+        }
+    }
+}
+
+fn translate_current_layout() -> Vec<(CGKeyCode, CGEventFlags, String)> {
+    let mut translations = Vec::new();
+    unsafe {
+        let source = TISCopyCurrentKeyboardLayoutInputSource();
+        if source.is_null() {
+            return translations;
+        }
+        let layout_data = TISGetInputSourceProperty(source, kTISPropertyUnicodeKeyLayoutData);
+        if !layout_data.is_null() {
+            let layout = CFDataGetBytePtr(layout_data) as *const c_void;
+            let keyboard_type = u32::from(LMGetKbdType());
+            for (modifiers, flags) in MODIFIER_STATES {
+                for key_code in 0..KEY_CODE_COUNT {
+                    if let Some(output) = translate_key(layout, key_code, modifiers, keyboard_type)
+                    {
+                        translations.push((key_code, flags, output));
+                    }
+                }
+            }
+        }
+        CFRelease(source as CFTypeRef);
+    }
+    translations
+}
+
+// Dead keys (the accent keys on many European layouts) are skipped: posting
+// one would start a composition in apps that read key codes.
+unsafe fn translate_key(
+    layout: *const c_void,
+    key_code: CGKeyCode,
+    modifiers: u32,
+    keyboard_type: u32,
+) -> Option<String> {
+    let mut dead_key_state = 0_u32;
+    let mut buffer = [0_u16; 4];
+    let mut length = 0_usize;
+    let status = UCKeyTranslate(
+        layout,
+        key_code,
+        K_UC_KEY_ACTION_DISPLAY,
+        modifiers,
+        keyboard_type,
+        0,
+        &mut dead_key_state,
+        buffer.len(),
+        &mut length,
+        buffer.as_mut_ptr(),
+    );
+    if status != 0 || dead_key_state != 0 || length == 0 {
+        return None;
+    }
+    String::from_utf16(&buffer[..length.min(buffer.len())]).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        KeyboardLayoutMap, LayoutKey, K_CG_EVENT_FLAG_MASK_ALTERNATE, K_CG_EVENT_FLAG_MASK_SHIFT,
+    };
+
+    fn utf16(text: &str) -> Vec<u16> {
+        text.encode_utf16().collect()
+    }
+
+    #[test]
+    fn characters_map_to_the_plainest_key_that_types_them() {
+        // A slice of the French AZERTY layout.
+        let map = KeyboardLayoutMap::from_translations([
+            (0, 0, "q".to_string()),
+            (12, 0, "a".to_string()),
+            (12, K_CG_EVENT_FLAG_MASK_SHIFT, "A".to_string()),
+            (19, 0, "é".to_string()),
+            (19, K_CG_EVENT_FLAG_MASK_ALTERNATE, "ë".to_string()),
+            (36, 0, "\r".to_string()),
+            (40, 0, "ab".to_string()),
+            (12, K_CG_EVENT_FLAG_MASK_ALTERNATE, "a".to_string()),
+        ]);
+
+        assert_eq!(
+            map.key_for(&utf16("a")),
+            Some(LayoutKey {
+                key_code: 12,
+                flags: 0
+            })
+        );
+        assert_eq!(
+            map.key_for(&utf16("A")),
+            Some(LayoutKey {
+                key_code: 12,
+                flags: K_CG_EVENT_FLAG_MASK_SHIFT
+            })
+        );
+        assert_eq!(map.key_for(&utf16("é")).map(|key| key.key_code), Some(19));
+        assert_eq!(map.key_for(&utf16("ж")), None);
+        assert_eq!(map.key_for(&utf16("\r")), None);
+        assert_eq!(map.key_for(&utf16("qa")), None);
+    }
+}
@@ -1,12 +1,32 @@
+mod caret;
+mod clipboard;
+mod keyboard_layout;
+mod pacing;
+mod spacing;
+mod verification;
+
 use std::{
     ffi::c_void,
     io::Write,
     process::{Command, Stdio},
     ptr,
+    sync::Mutex,
     thread::sleep,
     time::Duration,
 };
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{focused_app_watcher::FocusedApp, settings_store::VoiceSettings};
+pub use caret::focused_caret_position;
+pub use clipboard::{ClipboardRestore, ClipboardSnapshot};
+use keyboard_layout::{KeyboardLayoutMap, LayoutKey};
+pub use pacing::InsertionPacing;
+pub use spacing::InsertionSpacing;
+use verification::{verify_insertion, FocusedElement, Verification};
 
 const AX_SUCCESS: i32 = 0;
 const K_CG_ANNOTATED_SESSION_EVENT_TAP: u32 = 2;
@@ -16,7 +36,7 @@ const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
 
 const DIRECT_TYPE_THRESHOLD_CHARS: usize = 400;
 const UNICODE_CHUNK_SIZE: usize = 48;
-const PASTE_REGISTER_DELAY_MS: u64 = 75;
+const VERIFICATION_SETTLE_DELAY: Duration = Duration::from_millis(50);
 
 type CFTypeRef = *const c_void;
 type CFAllocatorRef = *const c_void;
@@ -74,13 +94,116 @@ pub enum InsertionMode {
     CopyOnly,
 }
 
+// How a finished transcript is delivered, from the `auto_insert` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoInsertMode {
+    Always,
+    IfFocused,
+    ClipboardOnly,
+    Ask,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsertionDecision {
+    Insert,
+    CopyToClipboard,
+    AwaitConfirmation,
+}
+
+impl AutoInsertMode {
+    pub fn from_settings_value(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "if_focused" => Self::IfFocused,
+            "clipboard_only" => Self::ClipboardOnly,
+            "ask" => Self::Ask,
+            _ => Self::Always,
+        }
+    }
+
+    // `confirm_before_insert` sends every transcript through the review window,
+    // whatever `auto_insert` says.
+    pub fn from_settings(settings: &VoiceSettings) -> Self {
+        if settings.confirm_before_insert {
+            Self::Ask
+        } else {
+            Self::from_settings_value(&settings.auto_insert)
+        }
+    }
+
+    // Like `from_settings`, but also holds back transcripts the provider was
+    // unsure of when `low_confidence_review` is on.
+    pub fn for_transcript(settings: &VoiceSettings, confidence: Option<f32>) -> Self {
+        let threshold = settings.low_confidence_threshold_percent as f32 / 100.0;
+        match confidence {
+            Some(confidence) if settings.low_confidence_review && confidence < threshold => {
+                Self::Ask
+            }
+            _ => Self::from_settings(settings),
+        }
+    }
+
+    // A copy-only request from the overlay always wins; otherwise the mode
+    // decides, with `IfFocused` falling back to the clipboard when the user has
+    // switched away from the app they were dictating into.
+    pub fn decide(self, copy_only: bool, origin_still_focused: bool) -> InsertionDecision {
+        if copy_only {
+            return InsertionDecision::CopyToClipboard;
+        }
+        match self {
+            Self::Always => InsertionDecision::Insert,
+            Self::IfFocused if origin_still_focused => InsertionDecision::Insert,
+            Self::IfFocused | Self::ClipboardOnly => InsertionDecision::CopyToClipboard,
+            Self::Ask => InsertionDecision::AwaitConfirmation,
+        }
+    }
+}
+
+// How text actually reached the target app, recorded in the insertion audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InsertionStrategy {
+    DirectTyping,
+    ClipboardPaste,
+    ClipboardOnly,
+    BrowserExtension,
+    // Typing or pasting did not reach the focused field, so the transcript was
+    // left on the clipboard for the user to paste themselves.
+    ClipboardFallback,
+}
+
+// How one insertion is delivered, resolved from settings and the target app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InsertionOptions {
+    pub pacing: InsertionPacing,
+    pub clipboard: ClipboardRestore,
+    pub verify: bool,
+}
+
+impl InsertionOptions {
+    pub fn for_app(settings: &VoiceSettings, app: Option<&FocusedApp>) -> Self {
+        Self {
+            pacing: InsertionPacing::for_app(settings, app),
+            clipboard: ClipboardRestore::from_settings(settings),
+            verify: settings.verify_insertion,
+        }
+    }
+}
+
+impl Default for InsertionOptions {
+    fn default() -> Self {
+        Self::for_app(&VoiceSettings::default(), None)
+    }
+}
+
 trait InsertionBackend {
     fn has_focused_input_target(&self) -> bool;
-    fn type_unicode_text(&self, text: &str) -> Result<(), String>;
-    fn read_text_from_clipboard(&self) -> Result<String, String>;
+    fn focused_element(&self) -> FocusedElement;
+    fn type_unicode_text(&self, text: &str, pacing: InsertionPacing) -> Result<(), String>;
+    fn snapshot_clipboard(&self) -> Result<ClipboardSnapshot, String>;
     fn write_text_to_clipboard(&self, text: &str) -> Result<(), String>;
+    fn restore_clipboard(&self, snapshot: &ClipboardSnapshot) -> Result<(), String>;
     fn post_command_v(&self) -> Result<(), String>;
-    fn wait_for_paste_to_register(&self);
+    fn wait_for_paste_to_register(&self, delay: Duration);
 }
 
 #[derive(Debug, Default)]
@@ -91,30 +214,40 @@ impl InsertionBackend for MacOsInsertionBackend {
         has_focused_input_target()
     }
 
-    fn type_unicode_text(&self, text: &str) -> Result<(), String> {
-        type_unicode_text(text)
+    fn focused_element(&self) -> FocusedElement {
+        verification::read_focused_element()
     }
 
-    fn read_text_from_clipboard(&self) -> Result<String, String> {
-        read_text_from_clipboard()
+    fn type_unicode_text(&self, text: &str, pacing: InsertionPacing) -> Result<(), String> {
+        type_unicode_text(text, pacing)
+    }
+
+    fn snapshot_clipboard(&self) -> Result<ClipboardSnapshot, String> {
+        clipboard::snapshot_clipboard()
     }
 
     fn write_text_to_clipboard(&self, text: &str) -> Result<(), String> {
         write_text_to_clipboard(text)
     }
 
+    fn restore_clipboard(&self, snapshot: &ClipboardSnapshot) -> Result<(), String> {
+        clipboard::restore_clipboard(snapshot)
+    }
+
     fn post_command_v(&self) -> Result<(), String> {
         post_command_v()
     }
 
-    fn wait_for_paste_to_register(&self) {
-        wait_for_paste_to_register();
+    fn wait_for_paste_to_register(&self, delay: Duration) {
+        sleep(delay);
     }
 }
 
 #[derive(Debug, Default)]
 pub struct TextInsertionService {
     backend: MacOsInsertionBackend,
+    // The app a smart-spacing trailing space was last held back for.
+    held_space_app: Mutex<Option<String>>,
 }
 
 impl TextInsertionService {
@@ -123,14 +256,54 @@ impl TextInsertionService {
         Self::default()
     }
 
-    pub fn insert_text(&self, text: &str) -> Result<(), String> {
-        info!(chars = text.chars().count(), "text insertion requested");
-        insert_text_with_backend(&self.backend, text, InsertionMode::Auto)
+    pub fn insert_text(
+        &self,
+        text: &str,
+        options: InsertionOptions,
+    ) -> Result<Option<InsertionStrategy>, String> {
+        info!(
+            chars = text.chars().count(),
+            pacing = ?options.pacing,
+            preserve_clipboard = options.clipboard.enabled,
+            verify = options.verify,
+            "text insertion requested"
+        );
+        insert_text_with_backend(&self.backend, text, InsertionMode::Auto, options)
+    }
+
+    // Adds the prefix and suffix for `app`. A held-back space is only typed
+    // when the next insertion goes to the same app; switching apps drops it.
+    pub fn space_text(
+        &self,
+        text: &str,
+        spacing: &InsertionSpacing,
+        app: Option<&FocusedApp>,
+    ) -> String {
+        let app_key = app.map(|app| app.bundle_id.clone().unwrap_or_else(|| app.name.clone()));
+        let held_space_app = self.held_space_app.lock();
+        let (Some(app_key), Ok(mut held_space_app)) = (app_key, held_space_app) else {
+            // Nothing to match the next insertion against, so the space is
+            // typed straight away.
+            let spacing = InsertionSpacing {
+                smart: false,
+                ..spacing.clone()
+            };
+            return spacing.apply(text, false).0;
+        };
+        let space_pending = held_space_app.as_deref() == Some(app_key.as_str());
+        let (spaced, hold_space) = spacing.apply(text, space_pending);
+        *held_space_app = hold_space.then_some(app_key);
+        spaced
     }
 
-    pub fn copy_to_clipboard(&self, text: &str) -> Result<(), String> {
+    pub fn copy_to_clipboard(&self, text: &str) -> Result<Option<InsertionStrategy>, String> {
         info!(chars = text.chars().count(), "copy to clipboard requested");
-        insert_text_with_backend(&self.backend, text, InsertionMode::CopyOnly)
+        insert_text_with_backend(
+            &self.backend,
+            text,
+            InsertionMode::CopyOnly,
+            InsertionOptions::default(),
+        )
     }
 }
 
@@ -138,48 +311,110 @@ fn insert_text_with_backend<B: InsertionBackend>(
     backend: &B,
     text: &str,
     mode: InsertionMode,
-) -> Result<(), String> {
+    options: InsertionOptions,
+) -> Result<Option<InsertionStrategy>, String> {
     if text.is_empty() {
         debug!("skipping text insertion because payload is empty");
-        return Ok(());
+        return Ok(None);
     }
 
     if matches!(mode, InsertionMode::CopyOnly) {
         debug!("executing clipboard-only insertion mode");
-        return backend.write_text_to_clipboard(text);
+        backend.write_text_to_clipboard(text)?;
+        return Ok(Some(InsertionStrategy::ClipboardOnly));
     }
 
-    let should_use_paste_fallback =
-        text.chars().count() > DIRECT_TYPE_THRESHOLD_CHARS || !backend.has_focused_input_target();
+    // Typed-out apps are mostly ones the accessibility API cannot read back,
+    // so they are never verified.
+    let InsertionOptions {
+        pacing, clipboard, ..
+    } = options;
+    let verify = options.verify && !pacing.types_out();
+    let before = if verify {
+        backend.focused_element()
+    } else {
+        FocusedElement::default()
+    };
+    if before.is_non_editable() {
+        warn!(
+            role = ?before.role,
+            "focused element does not accept text; copying transcript instead"
+        );
+        backend.write_text_to_clipboard(text)?;
+        return Ok(Some(InsertionStrategy::ClipboardFallback));
+    }
+
+    let strategy = deliver_text(backend, text, pacing, clipboard)?;
+    if !verify || before.value.is_none() {
+        return Ok(Some(strategy));
+    }
+
+    backend.wait_for_paste_to_register(VERIFICATION_SETTLE_DELAY);
+    let verification = verify_insertion(&before, &backend.focused_element(), text);
+    debug!(?strategy, ?verification, "verified transcript insertion");
+    if verification != Verification::Missing {
+        return Ok(Some(strategy));
+    }
+    warn!(
+        ?strategy,
+        "focused element did not change after insertion; copying transcript instead"
+    );
+    backend.write_text_to_clipboard(text)?;
+    Ok(Some(InsertionStrategy::ClipboardFallback))
+}
+
+fn deliver_text<B: InsertionBackend>(
+    backend: &B,
+    text: &str,
+    pacing: InsertionPacing,
+    clipboard: ClipboardRestore,
+) -> Result<InsertionStrategy, String> {
+    // Typed-out apps are often ones the accessibility check cannot see into
+    // (terminals, remote desktops), so neither the length nor the focus check
+    // applies to them.
+    let should_use_paste_fallback = !pacing.types_out()
+        && (text.chars().count() > DIRECT_TYPE_THRESHOLD_CHARS
+            || !backend.has_focused_input_target());
 
     if should_use_paste_fallback {
         warn!(
             chars = text.chars().count(),
             "using clipboard paste fallback instead of direct typing"
         );
-        return paste_via_clipboard(backend, text);
+        paste_via_clipboard(backend, text, clipboard)?;
+        return Ok(InsertionStrategy::ClipboardPaste);
     }
 
-    match backend.type_unicode_text(text) {
+    match backend.type_unicode_text(text, pacing) {
         Ok(()) => {
             debug!("direct unicode typing succeeded");
-            Ok(())
+            Ok(InsertionStrategy::DirectTyping)
         }
-        Err(direct_error) => paste_via_clipboard(backend, text).map_err(|paste_error| {
-            format!(
-                "Direct insertion failed ({direct_error}); clipboard fallback failed ({paste_error})"
-            )
-        }),
+        Err(direct_error) => paste_via_clipboard(backend, text, clipboard)
+            .map(|()| InsertionStrategy::ClipboardPaste)
+            .map_err(|paste_error| {
+                format!(
+                    "Direct insertion failed ({direct_error}); clipboard fallback failed ({paste_error})"
+                )
+            }),
     }
 }
 
-fn paste_via_clipboard<B: InsertionBackend>(backend: &B, text: &str) -> Result<(), String> {
-    let previous_clipboard = match backend.read_text_from_clipboard() {
-        Ok(clipboard) => Some(clipboard),
-        Err(error) => {
-            warn!(%error, "failed to read clipboard before paste fallback");
-            None
+fn paste_via_clipboard<B: InsertionBackend>(
+    backend: &B,
+    text: &str,
+    clipboard: ClipboardRestore,
+) -> Result<(), String> {
+    let previous_clipboard = if clipboard.enabled {
+        match backend.snapshot_clipboard() {
+            Ok(snapshot) => Some(snapshot),
+            Err(error) => {
+                warn!(%error, "failed to snapshot clipboard before paste fallback");
+                None
+            }
         }
+    } else {
+        None
     };
 
     debug!("writing fallback text to clipboard");
@@ -187,11 +422,11 @@ fn paste_via_clipboard<B: InsertionBackend>(backend: &B, text: &str) -> Result<(
     let paste_result = backend.post_command_v();
     if paste_result.is_ok() {
         debug!("clipboard paste shortcut posted successfully");
-        backend.wait_for_paste_to_register();
+        backend.wait_for_paste_to_register(clipboard.delay);
     }
 
     if let Some(previous_clipboard) = previous_clipboard {
-        if let Err(error) = backend.write_text_to_clipboard(&previous_clipboard) {
+        if let Err(error) = backend.restore_clipboard(&previous_clipboard) {
             warn!(%error, "failed to restore clipboard after paste fallback");
         }
     }
@@ -199,19 +434,6 @@ fn paste_via_clipboard<B: InsertionBackend>(backend: &B, text: &str) -> Result<(
     paste_result
 }
 
-fn read_text_from_clipboard() -> Result<String, String> {
-    let output = Command::new("pbpaste")
-        .output()
-        .map_err(|error| format!("Failed to start pbpaste: {error}"))?;
-
-    if !output.status.success() {
-        return Err(format!("pbpaste exited with status: {}", output.status));
-    }
-
-    String::from_utf8(output.stdout)
-        .map_err(|error| format!("Clipboard is not UTF-8 text: {error}"))
-}
-
 fn write_text_to_clipboard(text: &str) -> Result<(), String> {
     let mut child = Command::new("pbcopy")
         .stdin(Stdio::piped())
@@ -296,16 +518,23 @@ fn has_focused_input_target() -> bool {
     }
 }
 
-fn type_unicode_text(text: &str) -> Result<(), String> {
-    for chunk in utf16_chunks_preserving_char_boundaries(text, UNICODE_CHUNK_SIZE) {
-        post_unicode_keystroke(&chunk, true)?;
-        post_unicode_keystroke(&chunk, false)?;
+fn type_unicode_text(text: &str, pacing: InsertionPacing) -> Result<(), String> {
+    let layout = KeyboardLayoutMap::current();
+    for (chunk, delay) in pacing.plan(text) {
+        let key = layout.key_for(&chunk);
+        post_unicode_keystroke(&chunk, key, true)?;
+        post_unicode_keystroke(&chunk, key, false)?;
+        if !delay.is_zero() {
+            sleep(delay);
+        }
     }
 
     Ok(())
 }
 
-fn utf16_chunks_preserving_char_boundaries(text: &str, max_units: usize) -> Vec<Vec<u16>> {
+// Splits on grapheme clusters, so a letter and its combining accents, or a
+// joined emoji, always arrive in the same keystroke.
+fn utf16_chunks_preserving_graphemes(text: &str, max_units: usize) -> Vec<Vec<u16>> {
     if max_units == 0 {
         return Vec::new();
     }
@@ -313,16 +542,15 @@ fn utf16_chunks_preserving_char_boundaries(text: &str, max_units: usize) -> Vec<
     let mut chunks: Vec<Vec<u16>> = Vec::new();
     let mut current_chunk: Vec<u16> = Vec::with_capacity(max_units);
 
-    for character in text.chars() {
-        let mut character_utf16 = [0_u16; 2];
-        let encoded_character = character.encode_utf16(&mut character_utf16);
+    for grapheme in text.graphemes(true) {
+        let encoded_grapheme = grapheme.encode_utf16().collect::<Vec<_>>();
 
-        if current_chunk.len() + encoded_character.len() > max_units && !current_chunk.is_empty() {
+        if current_chunk.len() + encoded_grapheme.len() > max_units && !current_chunk.is_empty() {
             chunks.push(current_chunk);
             current_chunk = Vec::with_capacity(max_units);
         }
 
-        current_chunk.extend_from_slice(encoded_character);
+        current_chunk.extend_from_slice(&encoded_grapheme);
     }
 
     if !current_chunk.is_empty() {
@@ -332,13 +560,21 @@ fn utf16_chunks_preserving_char_boundaries(text: &str, max_units: usize) -> Vec<
     chunks
 }
 
-fn post_unicode_keystroke(chunk: &[u16], key_down: bool) -> Result<(), String> {
+// Flags are always set explicitly: a modifier still held from the hotkey
+// would otherwise change what apps reading the key code type.
+fn post_unicode_keystroke(
+    chunk: &[u16],
+    key: Option<LayoutKey>,
+    key_down: bool,
+) -> Result<(), String> {
+    let (key_code, flags) = key.map_or((0, 0), |key| (key.key_code, key.flags));
     unsafe {
-        let event = CGEventCreateKeyboardEvent(ptr::null_mut(), 0, key_down as Boolean);
+        let event = CGEventCreateKeyboardEvent(ptr::null_mut(), key_code, key_down as Boolean);
         if event.is_null() {
             return Err("Failed to create keyboard event".to_string());
         }
 
+        CGEventSetFlags(event, flags);
         CGEventKeyboardSetUnicodeString(event, chunk.len() as CFIndex, chunk.as_ptr());
         CGEventPost(K_CG_ANNOTATED_SESSION_EVENT_TAP, event);
         CFRelease(event as CFTypeRef);
@@ -369,18 +605,17 @@ fn post_command_v() -> Result<(), String> {
     Ok(())
 }
 
-fn wait_for_paste_to_register() {
-    sleep(Duration::from_millis(PASTE_REGISTER_DELAY_MS));
-}
-
 #[cfg(test)]
 mod tests {
-    use std::cell::RefCell;
+    use std::{cell::RefCell, collections::VecDeque, time::Duration};
 
     use super::{
-        insert_text_with_backend, utf16_chunks_preserving_char_boundaries, InsertionBackend,
-        InsertionMode, DIRECT_TYPE_THRESHOLD_CHARS, UNICODE_CHUNK_SIZE,
+        insert_text_with_backend, utf16_chunks_preserving_graphemes, AutoInsertMode,
+        ClipboardRestore, ClipboardSnapshot, FocusedElement, InsertionBackend, InsertionDecision,
+        InsertionMode, InsertionOptions, InsertionPacing, InsertionStrategy,
+        DIRECT_TYPE_THRESHOLD_CHARS, UNICODE_CHUNK_SIZE,
     };
+    use crate::settings_store::VoiceSettings;
 
     #[derive(Debug)]
     struct MockBackend {
@@ -389,7 +624,8 @@ mod tests {
         copy_result: Result<(), String>,
         restore_result: Result<(), String>,
         paste_result: Result<(), String>,
-        clipboard_read_result: Result<String, String>,
+        clipboard_read_result: Result<ClipboardSnapshot, String>,
+        focused_elements: RefCell<VecDeque<FocusedElement>>,
         calls: RefCell<Vec<&'static str>>,
         clipboard_writes: RefCell<Vec<String>>,
     }
@@ -402,7 +638,8 @@ mod tests {
                 copy_result: Ok(()),
                 restore_result: Ok(()),
                 paste_result: Ok(()),
-                clipboard_read_result: Ok("previous clipboard".to_string()),
+                clipboard_read_result: Ok(ClipboardSnapshot::from_text("previous clipboard")),
+                focused_elements: RefCell::new(VecDeque::new()),
                 calls: RefCell::new(Vec::new()),
                 clipboard_writes: RefCell::new(Vec::new()),
             }
@@ -425,27 +662,35 @@ mod tests {
             self.focused_input
         }
 
-        fn type_unicode_text(&self, _text: &str) -> Result<(), String> {
+        fn focused_element(&self) -> FocusedElement {
+            self.focused_elements
+                .borrow_mut()
+                .pop_front()
+                .unwrap_or_default()
+        }
+
+        fn type_unicode_text(&self, _text: &str, _pacing: InsertionPacing) -> Result<(), String> {
             self.calls.borrow_mut().push("direct_type");
             self.type_result.clone()
         }
 
-        fn read_text_from_clipboard(&self) -> Result<String, String> {
+        fn snapshot_clipboard(&self) -> Result<ClipboardSnapshot, String> {
             self.calls.borrow_mut().push("clipboard_read");
             self.clipboard_read_result.clone()
         }
 
         fn write_text_to_clipboard(&self, text: &str) -> Result<(), String> {
             self.calls.borrow_mut().push("copy");
-            let mut clipboard_writes = self.clipboard_writes.borrow_mut();
-            let write_index = clipboard_writes.len();
-            clipboard_writes.push(text.to_string());
-
-            if write_index == 0 {
-                self.copy_result.clone()
-            } else {
-                self.restore_result.clone()
-            }
+            self.clipboard_writes.borrow_mut().push(text.to_string());
+            self.copy_result.clone()
+        }
+
+        fn restore_clipboard(&self, snapshot: &ClipboardSnapshot) -> Result<(), String> {
+            self.calls.borrow_mut().push("restore");
+            self.clipboard_writes
+                .borrow_mut()
+                .push(snapshot.text().unwrap_or_default());
+            self.restore_result.clone()
         }
 
         fn post_command_v(&self) -> Result<(), String> {
@@ -453,7 +698,7 @@ mod tests {
             self.paste_result.clone()
         }
 
-        fn wait_for_paste_to_register(&self) {
+        fn wait_for_paste_to_register(&self, _delay: Duration) {
             self.calls.borrow_mut().push("wait");
         }
     }
@@ -462,7 +707,12 @@ mod tests {
     fn copy_only_mode_only_updates_clipboard() {
         let backend = MockBackend::default();
 
-        let result = insert_text_with_backend(&backend, "hello", InsertionMode::CopyOnly);
+        let result = insert_text_with_backend(
+            &backend,
+            "hello",
+            InsertionMode::CopyOnly,
+            InsertionOptions::default(),
+        );
 
         assert!(result.is_ok());
         assert_eq!(backend.call_order(), vec!["copy"]);
@@ -472,9 +722,14 @@ mod tests {
     fn auto_mode_prefers_direct_typing_for_short_text_with_focus() {
         let backend = MockBackend::default();
 
-        let result = insert_text_with_backend(&backend, "short text", InsertionMode::Auto);
+        let result = insert_text_with_backend(
+            &backend,
+            "short text",
+            InsertionMode::Auto,
+            InsertionOptions::default(),
+        );
 
-        assert!(result.is_ok());
+        assert_eq!(result, Ok(Some(InsertionStrategy::DirectTyping)));
         assert_eq!(backend.call_order(), vec!["focus_check", "direct_type"]);
     }
 
@@ -485,9 +740,14 @@ mod tests {
             ..Default::default()
         };
 
-        let result = insert_text_with_backend(&backend, "hello", InsertionMode::Auto);
+        let result = insert_text_with_backend(
+            &backend,
+            "hello",
+            InsertionMode::Auto,
+            InsertionOptions::default(),
+        );
 
-        assert!(result.is_ok());
+        assert_eq!(result, Ok(Some(InsertionStrategy::ClipboardPaste)));
         assert_eq!(
             backend.call_order(),
             vec![
@@ -496,7 +756,7 @@ mod tests {
                 "copy",
                 "paste",
                 "wait",
-                "copy"
+                "restore"
             ]
         );
         assert_eq!(
@@ -510,15 +770,44 @@ mod tests {
         let backend = MockBackend::default();
         let text = "a".repeat(DIRECT_TYPE_THRESHOLD_CHARS + 1);
 
-        let result = insert_text_with_backend(&backend, &text, InsertionMode::Auto);
+        let result = insert_text_with_backend(
+            &backend,
+            &text,
+            InsertionMode::Auto,
+            InsertionOptions::default(),
+        );
 
         assert!(result.is_ok());
         assert_eq!(
             backend.call_order(),
-            vec!["clipboard_read", "copy", "paste", "wait", "copy"]
+            vec!["clipboard_read", "copy", "paste", "wait", "restore"]
         );
     }
 
+    #[test]
+    fn type_out_pacing_types_long_text_without_focus_check() {
+        let backend = MockBackend {
+            focused_input: false,
+            ..MockBackend::default()
+        };
+        let text = "a".repeat(DIRECT_TYPE_THRESHOLD_CHARS + 1);
+
+        let result = insert_text_with_backend(
+            &backend,
+            &text,
+            InsertionMode::Auto,
+            InsertionOptions {
+                pacing: InsertionPacing::TypeOut {
+                    chars_per_second: 30,
+                },
+                ..InsertionOptions::default()
+            },
+        );
+
+        assert_eq!(result, Ok(Some(InsertionStrategy::DirectTyping)));
+        assert_eq!(backend.call_order(), vec!["direct_type"]);
+    }
+
     #[test]
     fn auto_mode_falls_back_to_clipboard_when_direct_typing_fails() {
         let backend = MockBackend {
@@ -526,7 +815,12 @@ mod tests {
             ..Default::default()
         };
 
-        let result = insert_text_with_backend(&backend, "hello", InsertionMode::Auto);
+        let result = insert_text_with_backend(
+            &backend,
+            "hello",
+            InsertionMode::Auto,
+            InsertionOptions::default(),
+        );
 
         assert!(result.is_ok());
         assert_eq!(
@@ -538,7 +832,7 @@ mod tests {
                 "copy",
                 "paste",
                 "wait",
-                "copy"
+                "restore"
             ]
         );
     }
@@ -551,7 +845,12 @@ mod tests {
             ..Default::default()
         };
 
-        let result = insert_text_with_backend(&backend, "hello", InsertionMode::Auto);
+        let result = insert_text_with_backend(
+            &backend,
+            "hello",
+            InsertionMode::Auto,
+            InsertionOptions::default(),
+        );
 
         assert!(result.is_err());
         assert_eq!(
@@ -571,7 +870,12 @@ mod tests {
             ..Default::default()
         };
 
-        let result = insert_text_with_backend(&backend, "hello", InsertionMode::Auto);
+        let result = insert_text_with_backend(
+            &backend,
+            "hello",
+            InsertionMode::Auto,
+            InsertionOptions::default(),
+        );
 
         assert!(result.is_ok());
         assert_eq!(
@@ -582,7 +886,7 @@ mod tests {
                 "copy",
                 "paste",
                 "wait",
-                "copy"
+                "restore"
             ]
         );
         assert_eq!(
@@ -599,7 +903,12 @@ mod tests {
             ..Default::default()
         };
 
-        let result = insert_text_with_backend(&backend, "hello", InsertionMode::Auto);
+        let result = insert_text_with_backend(
+            &backend,
+            "hello",
+            InsertionMode::Auto,
+            InsertionOptions::default(),
+        );
 
         assert!(result.is_ok());
         assert_eq!(
@@ -609,11 +918,135 @@ mod tests {
         assert_eq!(backend.clipboard_writes(), vec!["hello".to_string()]);
     }
 
+    #[test]
+    fn leaves_transcript_on_clipboard_when_preservation_is_off() {
+        let backend = MockBackend {
+            focused_input: false,
+            ..Default::default()
+        };
+
+        let result = insert_text_with_backend(
+            &backend,
+            "hello",
+            InsertionMode::Auto,
+            InsertionOptions {
+                clipboard: ClipboardRestore {
+                    enabled: false,
+                    delay: Duration::from_millis(500),
+                },
+                ..InsertionOptions::default()
+            },
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(
+            backend.call_order(),
+            vec!["focus_check", "copy", "paste", "wait"]
+        );
+        assert_eq!(backend.clipboard_writes(), vec!["hello".to_string()]);
+    }
+
+    fn text_field(value: &str) -> FocusedElement {
+        FocusedElement {
+            role: Some("AXTextArea".to_string()),
+            value: Some(value.to_string()),
+        }
+    }
+
+    #[test]
+    fn copies_instead_when_insertion_leaves_focused_field_unchanged() {
+        let backend = MockBackend {
+            focused_elements: RefCell::new(VecDeque::from([
+                text_field("Dear team,"),
+                text_field("Dear team,"),
+            ])),
+            ..Default::default()
+        };
+
+        let result = insert_text_with_backend(
+            &backend,
+            "hello",
+            InsertionMode::Auto,
+            InsertionOptions::default(),
+        );
+
+        assert_eq!(result, Ok(Some(InsertionStrategy::ClipboardFallback)));
+        assert_eq!(
+            backend.call_order(),
+            vec!["focus_check", "direct_type", "wait", "copy"]
+        );
+        assert_eq!(backend.clipboard_writes(), vec!["hello".to_string()]);
+    }
+
+    #[test]
+    fn verified_or_unreadable_insertions_are_left_alone() {
+        let backend = MockBackend {
+            focused_elements: RefCell::new(VecDeque::from([
+                text_field("Dear team,"),
+                text_field("Dear team, hello"),
+            ])),
+            ..Default::default()
+        };
+        let result = insert_text_with_backend(
+            &backend,
+            "hello",
+            InsertionMode::Auto,
+            InsertionOptions::default(),
+        );
+        assert_eq!(result, Ok(Some(InsertionStrategy::DirectTyping)));
+        assert!(backend.clipboard_writes().is_empty());
+
+        let secure_field = MockBackend {
+            focused_elements: RefCell::new(VecDeque::from([FocusedElement {
+                role: Some("AXTextField".to_string()),
+                value: None,
+            }])),
+            ..Default::default()
+        };
+        let result = insert_text_with_backend(
+            &secure_field,
+            "hello",
+            InsertionMode::Auto,
+            InsertionOptions::default(),
+        );
+        assert_eq!(result, Ok(Some(InsertionStrategy::DirectTyping)));
+        assert_eq!(
+            secure_field.call_order(),
+            vec!["focus_check", "direct_type"]
+        );
+    }
+
+    #[test]
+    fn non_editable_focus_skips_typing_and_copies() {
+        let backend = MockBackend {
+            focused_elements: RefCell::new(VecDeque::from([FocusedElement {
+                role: Some("AXButton".to_string()),
+                value: None,
+            }])),
+            ..Default::default()
+        };
+
+        let result = insert_text_with_backend(
+            &backend,
+            "hello",
+            InsertionMode::Auto,
+            InsertionOptions::default(),
+        );
+
+        assert_eq!(result, Ok(Some(InsertionStrategy::ClipboardFallback)));
+        assert_eq!(backend.call_order(), vec!["copy"]);
+    }
+
     #[test]
     fn empty_text_is_noop() {
         let backend = MockBackend::default();
 
-        let result = insert_text_with_backend(&backend, "", InsertionMode::Auto);
+        let result = insert_text_with_backend(
+            &backend,
+            "",
+            InsertionMode::Auto,
+            InsertionOptions::default(),
+        );
 
         assert!(result.is_ok());
         assert!(backend.call_order().is_empty());
@@ -622,7 +1055,7 @@ mod tests {
     #[test]
     fn utf16_chunking_preserves_non_bmp_characters() {
         let text = format!("{}{}{}", "a".repeat(UNICODE_CHUNK_SIZE - 1), "😀😀", "𐍈");
-        let chunks = utf16_chunks_preserving_char_boundaries(&text, UNICODE_CHUNK_SIZE);
+        let chunks = utf16_chunks_preserving_graphemes(&text, UNICODE_CHUNK_SIZE);
 
         assert!(!chunks.is_empty());
         assert!(chunks.iter().all(|chunk| chunk.len() <= UNICODE_CHUNK_SIZE));
@@ -632,10 +1065,21 @@ mod tests {
         assert_eq!(reconstructed, text);
     }
 
+    #[test]
+    fn utf16_chunking_keeps_combining_accents_with_their_letter() {
+        let chunks = utf16_chunks_preserving_graphemes("e\u{301}te\u{301}", 1);
+
+        let keystrokes = chunks
+            .into_iter()
+            .map(|chunk| String::from_utf16(&chunk).expect("valid UTF-16 chunk"))
+            .collect::<Vec<_>>();
+        assert_eq!(keystrokes, ["e\u{301}", "t", "e\u{301}"]);
+    }
+
     #[test]
     fn utf16_chunking_never_splits_surrogate_pairs() {
         let text = format!("{}{}", "a".repeat(UNICODE_CHUNK_SIZE - 1), "😀😀😀");
-        let chunks = utf16_chunks_preserving_char_boundaries(&text, UNICODE_CHUNK_SIZE);
+        let chunks = utf16_chunks_preserving_graphemes(&text, UNICODE_CHUNK_SIZE);
 
         assert!(chunks.iter().all(|chunk| {
             chunk
@@ -643,4 +1087,77 @@ mod tests {
                 .is_none_or(|unit| !(0xD800..=0xDBFF).contains(unit))
         }));
     }
+
+    #[test]
+    fn auto_insert_modes_decide_between_insert_copy_and_confirmation() {
+        let if_focused = AutoInsertMode::from_settings_value("if_focused");
+        assert_eq!(if_focused.decide(false, true), InsertionDecision::Insert);
+        assert_eq!(
+            if_focused.decide(false, false),
+            InsertionDecision::CopyToClipboard
+        );
+        assert_eq!(
+            AutoInsertMode::from_settings_value("ask").decide(false, true),
+            InsertionDecision::AwaitConfirmation
+        );
+        assert_eq!(
+            AutoInsertMode::from_settings_value("clipboard_only").decide(false, true),
+            InsertionDecision::CopyToClipboard
+        );
+        assert_eq!(
+            AutoInsertMode::from_settings_value("always").decide(true, true),
+            InsertionDecision::CopyToClipboard
+        );
+        assert_eq!(
+            AutoInsertMode::from_settings_value("unknown"),
+            AutoInsertMode::Always
+        );
+    }
+
+    #[test]
+    fn confirm_before_insert_overrides_auto_insert_mode() {
+        let mut settings = VoiceSettings {
+            auto_insert: "clipboard_only".to_string(),
+            ..VoiceSettings::default()
+        };
+        assert_eq!(
+            AutoInsertMode::from_settings(&settings),
+            AutoInsertMode::ClipboardOnly
+        );
+
+        settings.confirm_before_insert = true;
+        let mode = AutoInsertMode::from_settings(&settings);
+        assert_eq!(mode, AutoInsertMode::Ask);
+        assert_eq!(
+            mode.decide(false, true),
+            InsertionDecision::AwaitConfirmation
+        );
+        assert_eq!(mode.decide(true, true), InsertionDecision::CopyToClipboard);
+    }
+
+    #[test]
+    fn low_confidence_transcripts_await_review_when_enabled() {
+        let mut settings = VoiceSettings {
+            low_confidence_threshold_percent: 60,
+            ..VoiceSettings::default()
+        };
+        assert_eq!(
+            AutoInsertMode::for_transcript(&settings, Some(0.3)),
+            AutoInsertMode::Always
+        );
+
+        settings.low_confidence_review = true;
+        assert_eq!(
+            AutoInsertMode::for_transcript(&settings, Some(0.3)),
+            AutoInsertMode::Ask
+        );
+        assert_eq!(
+            AutoInsertMode::for_transcript(&settings, Some(0.9)),
+            AutoInsertMode::Always
+        );
+        assert_eq!(
+            AutoInsertMode::for_transcript(&settings, None),
+            AutoInsertMode::Always
+        );
+    }
 }
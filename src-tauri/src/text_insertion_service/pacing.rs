@@ -0,0 +1,219 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::{utf16_chunks_preserving_graphemes, UNICODE_CHUNK_SIZE};
+use crate::focused_app_watcher::FocusedApp;
+use crate::settings_store::{
+    VoiceSettings, INSERTION_PACING_CAUTIOUS, INSERTION_PACING_GENTLE, INSERTION_PACING_TYPE_OUT,
+};
+
+// Some apps (Google Docs, Citrix sessions) flag text that arrives faster than
+// anyone could type it. The slower presets send a few characters per keystroke
+// with jittered gaps and an occasional longer pause.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InsertionPacing {
+    #[default]
+    Instant,
+    Gentle,
+    Cautious,
+    // Types every character at a steady rate and never pastes, for terminals
+    // and remote desktops that mangle large pastes.
+    TypeOut {
+        chars_per_second: u32,
+    },
+}
+
+struct PacingProfile {
+    units_per_keystroke: usize,
+    keystroke_delay_ms: u64,
+    jitter_ms: u64,
+    pause_every_keystrokes: usize,
+    pause_ms: u64,
+}
+
+impl InsertionPacing {
+    pub fn from_settings_value(value: &str, typing_chars_per_second: u32) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            INSERTION_PACING_GENTLE => Self::Gentle,
+            INSERTION_PACING_CAUTIOUS => Self::Cautious,
+            INSERTION_PACING_TYPE_OUT => Self::TypeOut {
+                chars_per_second: typing_chars_per_second,
+            },
+            _ => Self::Instant,
+        }
+    }
+
+    pub fn types_out(self) -> bool {
+        matches!(self, Self::TypeOut { .. })
+    }
+
+    // Apps are matched by bundle id first, then by name.
+    pub fn for_app(settings: &VoiceSettings, app: Option<&FocusedApp>) -> Self {
+        app.and_then(|app| {
+            app.bundle_id
+                .as_ref()
+                .and_then(|bundle_id| settings.insertion_pacing.get(bundle_id))
+                .or_else(|| settings.insertion_pacing.get(&app.name))
+        })
+        .map_or(Self::Instant, |value| {
+            Self::from_settings_value(value, settings.typing_chars_per_second)
+        })
+    }
+
+    fn profile(self) -> PacingProfile {
+        match self {
+            Self::Instant => PacingProfile {
+                units_per_keystroke: UNICODE_CHUNK_SIZE,
+                keystroke_delay_ms: 0,
+                jitter_ms: 0,
+                pause_every_keystrokes: 0,
+                pause_ms: 0,
+            },
+            Self::Gentle => PacingProfile {
+                units_per_keystroke: 4,
+                keystroke_delay_ms: 15,
+                jitter_ms: 10,
+                pause_every_keystrokes: 40,
+                pause_ms: 150,
+            },
+            Self::Cautious => PacingProfile {
+                units_per_keystroke: 1,
+                keystroke_delay_ms: 40,
+                jitter_ms: 25,
+                pause_every_keystrokes: 25,
+                pause_ms: 450,
+            },
+            Self::TypeOut { chars_per_second } => PacingProfile {
+                units_per_keystroke: 1,
+                keystroke_delay_ms: 1_000 / u64::from(chars_per_second.max(1)),
+                jitter_ms: 0,
+                pause_every_keystrokes: 0,
+                pause_ms: 0,
+            },
+        }
+    }
+
+    // Splits `text` into keystrokes, each paired with the delay to wait after
+    // posting it.
+    pub(super) fn plan(self, text: &str) -> Vec<(Vec<u16>, Duration)> {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default();
+        self.plan_with_seed(text, seed)
+    }
+
+    fn plan_with_seed(self, text: &str, seed: u64) -> Vec<(Vec<u16>, Duration)> {
+        let profile = self.profile();
+        let mut jitter = Jitter::new(seed);
+        utf16_chunks_preserving_graphemes(text, profile.units_per_keystroke)
+            .into_iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                let mut delay_ms = profile.keystroke_delay_ms.saturating_sub(profile.jitter_ms)
+                    + jitter.next_below(profile.jitter_ms * 2 + 1);
+                if profile.pause_every_keystrokes > 0
+                    && (index + 1) % profile.pause_every_keystrokes == 0
+                {
+                    delay_ms += profile.pause_ms;
+                }
+                (chunk, Duration::from_millis(delay_ms))
+            })
+            .collect()
+    }
+}
+
+// xorshift64; only needs to look irregular, not be unpredictable.
+struct Jitter(u64);
+
+impl Jitter {
+    fn new(seed: u64) -> Self {
+        Self(seed | 1)
+    }
+
+    fn next_below(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % bound.max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, time::Duration};
+
+    use super::InsertionPacing;
+    use crate::{focused_app_watcher::FocusedApp, settings_store::VoiceSettings};
+
+    #[test]
+    fn paced_presets_split_keystrokes_with_jittered_delays_and_pauses() {
+        let text = "a".repeat(60);
+
+        let instant = InsertionPacing::Instant.plan_with_seed(&text, 7);
+        assert_eq!(instant.len(), 2);
+        assert!(instant.iter().all(|(_, delay)| delay.is_zero()));
+
+        let cautious =
+            InsertionPacing::from_settings_value(" Cautious ", 30).plan_with_seed(&text, 7);
+        assert_eq!(cautious.len(), 60);
+        assert!(cautious.iter().all(|(chunk, _)| chunk.len() == 1));
+        let delays = cautious.iter().map(|(_, delay)| *delay).collect::<Vec<_>>();
+        assert!(delays
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| (index + 1) % 25 != 0)
+            .all(
+                |(_, delay)| (Duration::from_millis(15)..=Duration::from_millis(65))
+                    .contains(delay)
+            ));
+        assert!(delays[24] >= Duration::from_millis(450));
+        assert!(delays.windows(2).any(|pair| pair[0] != pair[1]));
+
+        let typed =
+            InsertionPacing::from_settings_value("type_out", 20).plan_with_seed("héllo 🎙", 7);
+        assert_eq!(typed.len(), 7);
+        assert!(typed
+            .iter()
+            .all(|(_, delay)| *delay == Duration::from_millis(50)));
+        assert_eq!(typed.last().map(|(chunk, _)| chunk.len()), Some(2));
+
+        let settings = VoiceSettings {
+            insertion_pacing: BTreeMap::from([
+                ("com.google.Chrome".to_string(), "gentle".to_string()),
+                ("Citrix Viewer".to_string(), "cautious".to_string()),
+                ("com.apple.Terminal".to_string(), "type_out".to_string()),
+            ]),
+            typing_chars_per_second: 45,
+            ..VoiceSettings::default()
+        };
+        let app = |bundle_id: Option<&str>, name: &str| FocusedApp {
+            bundle_id: bundle_id.map(str::to_string),
+            name: name.to_string(),
+        };
+        assert_eq!(
+            InsertionPacing::for_app(&settings, Some(&app(Some("com.google.Chrome"), "Chrome"))),
+            InsertionPacing::Gentle
+        );
+        assert_eq!(
+            InsertionPacing::for_app(&settings, Some(&app(None, "Citrix Viewer"))),
+            InsertionPacing::Cautious
+        );
+        assert_eq!(
+            InsertionPacing::for_app(
+                &settings,
+                Some(&app(Some("com.apple.Terminal"), "Terminal"))
+            ),
+            InsertionPacing::TypeOut {
+                chars_per_second: 45
+            }
+        );
+        assert_eq!(
+            InsertionPacing::for_app(&settings, Some(&app(Some("com.apple.Notes"), "Notes"))),
+            InsertionPacing::Instant
+        );
+        assert_eq!(
+            InsertionPacing::for_app(&settings, None),
+            InsertionPacing::Instant
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::focused_app_watcher::FocusedApp;
+use crate::settings_store::{VoiceSettings, INSERTION_SUFFIX_NEWLINE, INSERTION_SUFFIX_SPACE};
+
+// Punctuation that belongs against the previous word, so a held-back space is
+// dropped when the next insertion starts with one of these.
+const ATTACHING_PUNCTUATION: &[char] = &[
+    '.', ',', ';', ':', '!', '?', ')', ']', '}', '%', '\u{2026}', '\'', '\u{2019}',
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InsertionSuffix {
+    #[default]
+    None,
+    Space,
+    Newline,
+}
+
+impl InsertionSuffix {
+    pub fn from_settings_value(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            INSERTION_SUFFIX_SPACE => Self::Space,
+            INSERTION_SUFFIX_NEWLINE => Self::Newline,
+            _ => Self::None,
+        }
+    }
+}
+
+// What is typed around a transcript, resolved from settings and the target
+// app's override.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InsertionSpacing {
+    pub prefix: String,
+    pub suffix: InsertionSuffix,
+    pub smart: bool,
+}
+
+impl InsertionSpacing {
+    // Apps are matched by bundle id first, then by name.
+    pub fn for_app(settings: &VoiceSettings, app: Option<&FocusedApp>) -> Self {
+        let overrides = app.and_then(|app| {
+            app.bundle_id
+                .as_ref()
+                .and_then(|bundle_id| settings.insertion_spacing_overrides.get(bundle_id))
+                .or_else(|| settings.insertion_spacing_overrides.get(&app.name))
+        });
+        let prefix = overrides
+            .and_then(|overrides| overrides.prefix.clone())
+            .unwrap_or_else(|| settings.insertion_prefix.clone());
+        let suffix = overrides
+            .and_then(|overrides| overrides.suffix.as_deref())
+            .unwrap_or(&settings.insertion_suffix);
+        let smart = overrides
+            .and_then(|overrides| overrides.smart_spacing)
+            .unwrap_or(settings.smart_spacing);
+        Self {
+            prefix,
+            suffix: InsertionSuffix::from_settings_value(suffix),
+            smart,
+        }
+    }
+
+    // Returns the text to type, and whether a trailing space is now held back
+    // for the next insertion. `space_pending` says whether the previous
+    // insertion into the same app held one back.
+    pub fn apply(&self, text: &str, space_pending: bool) -> (String, bool) {
+        let mut spaced = String::with_capacity(self.prefix.len() + text.len() + 2);
+        if space_pending && !text.starts_with(ATTACHING_PUNCTUATION) {
+            spaced.push(' ');
+        }
+        spaced.push_str(&self.prefix);
+        spaced.push_str(text);
+        match self.suffix {
+            InsertionSuffix::None => (spaced, false),
+            InsertionSuffix::Space if self.smart => (spaced, true),
+            InsertionSuffix::Space => {
+                spaced.push(' ');
+                (spaced, false)
+            }
+            InsertionSuffix::Newline => {
+                spaced.push('\n');
+                (spaced, false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{InsertionSpacing, InsertionSuffix};
+    use crate::{
+        focused_app_watcher::FocusedApp,
+        settings_store::{InsertionSpacingOverride, VoiceSettings},
+    };
+
+    #[test]
+    fn adds_affixes_and_holds_trailing_spaces_for_punctuation() {
+        let smart = InsertionSpacing {
+            suffix: InsertionSuffix::Space,
+            smart: true,
+            ..InsertionSpacing::default()
+        };
+        assert_eq!(
+            smart.apply("Hello there", false),
+            ("Hello there".to_string(), true)
+        );
+        assert_eq!(
+            smart.apply("how are you", true),
+            (" how are you".to_string(), true)
+        );
+        assert_eq!(smart.apply("?", true), ("?".to_string(), true));
+
+        let plain = InsertionSpacing {
+            prefix: "- ".to_string(),
+            suffix: InsertionSuffix::Space,
+            smart: false,
+        };
+        assert_eq!(plain.apply("milk", false), ("- milk ".to_string(), false));
+
+        let newline = InsertionSpacing {
+            suffix: InsertionSuffix::Newline,
+            smart: true,
+            ..InsertionSpacing::default()
+        };
+        assert_eq!(
+            newline.apply("Done.", true),
+            (" Done.\n".to_string(), false)
+        );
+    }
+
+    #[test]
+    fn app_overrides_replace_only_the_fields_they_set() {
+        let settings = VoiceSettings {
+            insertion_prefix: "> ".to_string(),
+            insertion_suffix: "space".to_string(),
+            insertion_spacing_overrides: BTreeMap::from([(
+                "com.apple.Terminal".to_string(),
+                InsertionSpacingOverride {
+                    suffix: Some("none".to_string()),
+                    ..InsertionSpacingOverride::default()
+                },
+            )]),
+            ..VoiceSettings::default()
+        };
+        let terminal = FocusedApp {
+            bundle_id: Some("com.apple.Terminal".to_string()),
+            name: "Terminal".to_string(),
+        };
+        assert_eq!(
+            InsertionSpacing::for_app(&settings, Some(&terminal)),
+            InsertionSpacing {
+                prefix: "> ".to_string(),
+                suffix: InsertionSuffix::None,
+                smart: true,
+            }
+        );
+        assert_eq!(
+            InsertionSpacing::for_app(&settings, None),
+            InsertionSpacing {
+                prefix: "> ".to_string(),
+                suffix: InsertionSuffix::Space,
+                smart: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::{ffi::c_char, ptr};
+
+use super::{
+    kCFAllocatorDefault, AXUIElementCopyAttributeValue, AXUIElementCreateSystemWide,
+    AXUIElementRef, Boolean, CFIndex, CFRelease, CFStringCreateWithCString, CFStringRef, CFTypeRef,
+    AX_SUCCESS, K_CF_STRING_ENCODING_UTF8,
+};
+
+type CFTypeID = usize;
+
+#[link(name = "CoreFoundation", kind = "framework")]
+unsafe extern "C" {
+    fn CFGetTypeID(cf: CFTypeRef) -> CFTypeID;
+    fn CFStringGetTypeID() -> CFTypeID;
+    fn CFStringGetLength(string: CFStringRef) -> CFIndex;
+    fn CFStringGetMaximumSizeForEncoding(length: CFIndex, encoding: u32) -> CFIndex;
+    fn CFStringGetCString(
+        string: CFStringRef,
+        buffer: *mut c_char,
+        buffer_size: CFIndex,
+        encoding: u32,
+    ) -> Boolean;
+}
+
+// Roles that can hold keyboard focus but never take typed text. Anything not
+// listed (including roles we cannot read) is given the benefit of the doubt.
+const NON_EDITABLE_ROLES: &[&str] = &[
+    "AXButton",
+    "AXCheckBox",
+    "AXRadioButton",
+    "AXPopUpButton",
+    "AXMenuButton",
+    "AXMenuItem",
+    "AXSlider",
+    "AXImage",
+    "AXLink",
+    "AXStaticText",
+    "AXList",
+    "AXTable",
+    "AXOutline",
+    "AXTabGroup",
+    "AXToolbar",
+];
+
+// The focused element's role and value as the accessibility API reports them.
+// Either is `None` when the app does not expose it, e.g. secure text fields
+// and most Electron apps hide their value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct FocusedElement {
+    pub role: Option<String>,
+    pub value: Option<String>,
+}
+
+impl FocusedElement {
+    pub fn is_non_editable(&self) -> bool {
+        self.role
+            .as_deref()
+            .is_some_and(|role| NON_EDITABLE_ROLES.contains(&role))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Verification {
+    Landed,
+    Missing,
+    Unknown,
+}
+
+// Only a readable value that did not change at all counts as a miss; editors
+// that autocorrect or reformat the insertion change the value without
+// containing the transcript verbatim.
+pub(super) fn verify_insertion(
+    before: &FocusedElement,
+    after: &FocusedElement,
+    text: &str,
+) -> Verification {
+    let (Some(before), Some(after)) = (&before.value, &after.value) else {
+        return Verification::Unknown;
+    };
+    if before == after {
+        Verification::Missing
+    } else if after.contains(text.trim()) {
+        Verification::Landed
+    } else {
+        Verification::Unknown
+    }
+}
+
+pub(super) fn read_focused_element() -> FocusedElement {
+    unsafe {
+        let system_wide = AXUIElementCreateSystemWide();
+        if system_wide.is_null() {
+            return FocusedElement::default();
+        }
+        let element = copy_attribute(system_wide, b"AXFocusedUIElement\0");
+        CFRelease(system_wide as CFTypeRef);
+        if element.is_null() {
+            return FocusedElement::default();
+        }
+
+        let focused = FocusedElement {
+            role: copy_string_attribute(element, b"AXRole\0"),
+            value: copy_string_attribute(element, b"AXValue\0"),
+        };
+        CFRelease(element);
+        focused
+    }
+}
+
+pub(super) unsafe fn copy_attribute(element: AXUIElementRef, attribute: &[u8]) -> CFTypeRef {
+    let attribute = CFStringCreateWithCString(
+        kCFAllocatorDefault,
+        attribute.as_ptr() as *const i8,
+        K_CF_STRING_ENCODING_UTF8,
+    );
+    if attribute.is_null() {
+        return ptr::null();
+    }
+    let mut value: CFTypeRef = ptr::null();
+    let status = AXUIElementCopyAttributeValue(element, attribute, &mut value);
+    CFRelease(attribute);
+    if status != AX_SUCCESS {
+        if !value.is_null() {
+            CFRelease(value);
+        }
+        return ptr::null();
+    }
+    value
+}
+
+unsafe fn copy_string_attribute(element: AXUIElementRef, attribute: &[u8]) -> Option<String> {
+    let value = copy_attribute(element, attribute);
+    if value.is_null() {
+        return None;
+    }
+    let text = (CFGetTypeID(value) == CFStringGetTypeID()).then(|| cf_string_to_string(value));
+    CFRelease(value);
+    text.flatten()
+}
+
+unsafe fn cf_string_to_string(string: CFStringRef) -> Option<String> {
+    let capacity =
+        CFStringGetMaximumSizeForEncoding(CFStringGetLength(string), K_CF_STRING_ENCODING_UTF8) + 1;
+    let mut buffer = vec![0 as c_char; usize::try_from(capacity).ok()?];
+    if CFStringGetCString(
+        string,
+        buffer.as_mut_ptr(),
+        capacity,
+        K_CF_STRING_ENCODING_UTF8,
+    ) == 0
+    {
+        return None;
+    }
+    let bytes: Vec<u8> = buffer
+        .into_iter()
+        .take_while(|byte| *byte != 0)
+        .map(|byte| byte as u8)
+        .collect();
+    String::from_utf8(bytes).ok()
+}
@@ -0,0 +1,287 @@
+use tracing::{debug, info, warn};
+
+use crate::audio_capture_service::pcm16_to_wav_bytes;
+use crate::transcription::{chunked::parse_pcm16_mono_wav, TranscriptionResult};
+
+// Short recordings gain almost nothing from stretching, so they upload as-is.
+const MIN_STRETCH_DURATION_SECS: f64 = 8.0;
+// Past roughly seven syllables per second providers start dropping words, so
+// fast talkers are never sped up beyond that.
+const MAX_STRETCHED_SYLLABLES_PER_SEC: f64 = 7.0;
+const FRAME_MS: u32 = 30;
+const SEARCH_TOLERANCE_MS: u32 = 10;
+// Waveform similarity is measured at about 4 kHz to keep the search cheap.
+const CORRELATION_RATE_HZ: u32 = 4_000;
+const ENVELOPE_FRAME_MS: u32 = 10;
+const ENVELOPE_SMOOTHING_FRAMES: usize = 4;
+const SYLLABLE_PEAK_RADIUS_FRAMES: usize = 6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StretchedWav {
+    pub wav_bytes: Vec<u8>,
+    pub factor: f64,
+}
+
+// Best-effort: returns `None` whenever the recording should upload unchanged.
+pub fn stretch_wav_for_upload(wav_bytes: &[u8], factor_percent: u32) -> Option<StretchedWav> {
+    let factor = f64::from(factor_percent) / 100.0;
+    if factor <= 1.0 {
+        return None;
+    }
+
+    let wav = match parse_pcm16_mono_wav(wav_bytes) {
+        Ok(wav) => wav,
+        Err(error) => {
+            warn!(%error, "skipping time-stretch for unparseable recording");
+            return None;
+        }
+    };
+    let duration_secs = wav.samples.len() as f64 / f64::from(wav.sample_rate_hz.max(1));
+    if duration_secs < MIN_STRETCH_DURATION_SECS {
+        debug!(duration_secs, "recording too short to time-stretch");
+        return None;
+    }
+
+    let syllables_per_sec = estimate_syllable_rate(&wav.samples, wav.sample_rate_hz);
+    if syllables_per_sec * factor > MAX_STRETCHED_SYLLABLES_PER_SEC {
+        info!(
+            syllables_per_sec,
+            factor, "speaking rate too fast to time-stretch safely; uploading original audio"
+        );
+        return None;
+    }
+
+    let stretched = wsola(&wav.samples, wav.sample_rate_hz, factor);
+    match pcm16_to_wav_bytes(&stretched, wav.sample_rate_hz, 1) {
+        Ok(wav_bytes) => {
+            info!(
+                factor,
+                syllables_per_sec,
+                original_secs = duration_secs,
+                stretched_secs = stretched.len() as f64 / f64::from(wav.sample_rate_hz),
+                "time-stretched recording for upload"
+            );
+            Some(StretchedWav { wav_bytes, factor })
+        }
+        Err(error) => {
+            warn!(%error, "failed to encode time-stretched recording; uploading original audio");
+            None
+        }
+    }
+}
+
+// Maps provider timestamps from the sped-up upload back onto the original
+// recording.
+pub fn restore_timeline(result: &mut TranscriptionResult, factor: f64) {
+    result.duration_secs = result.duration_secs.map(|secs| secs * factor);
+    for segment in &mut result.segments {
+        segment.start_secs *= factor;
+        segment.end_secs *= factor;
+    }
+    for word in &mut result.words {
+        word.start_secs *= factor;
+        word.end_secs *= factor;
+    }
+}
+
+// Waveform-similarity overlap-add: Hann-windowed frames are read from the input
+// every `factor * hop` samples and written every `hop` samples, with each read
+// nudged to the offset that best continues the previous frame so pitch is kept.
+fn wsola(samples: &[i16], sample_rate_hz: u32, factor: f64) -> Vec<i16> {
+    let frame_len = ((sample_rate_hz * FRAME_MS / 1000) as usize).max(8) & !1;
+    let synthesis_hop = frame_len / 2;
+    let analysis_hop = (synthesis_hop as f64 * factor).round() as usize;
+    let tolerance = (sample_rate_hz * SEARCH_TOLERANCE_MS / 1000) as usize;
+    let stride = (sample_rate_hz / CORRELATION_RATE_HZ).max(1) as usize;
+    let offset_step = (stride / 2).max(1);
+
+    if samples.len() < frame_len + tolerance * 2 + analysis_hop {
+        return samples.to_vec();
+    }
+
+    let window = (0..frame_len)
+        .map(|index| {
+            0.5 - 0.5 * (2.0 * std::f64::consts::PI * index as f64 / frame_len as f64).cos()
+        })
+        .collect::<Vec<_>>();
+    let expected_len = (samples.len() as f64 / factor) as usize + frame_len;
+    let mut output = vec![0.0f64; expected_len];
+    let mut previous_position = 0usize;
+    let mut frame_index = 0usize;
+
+    loop {
+        let nominal = frame_index * analysis_hop;
+        let output_position = frame_index * synthesis_hop;
+        if nominal + tolerance + frame_len > samples.len()
+            || output_position + frame_len > output.len()
+        {
+            break;
+        }
+
+        let natural = previous_position + synthesis_hop;
+        let position = if frame_index == 0 || natural + frame_len > samples.len() {
+            nominal
+        } else {
+            (nominal.saturating_sub(tolerance)..=nominal + tolerance)
+                .step_by(offset_step)
+                .map(|candidate| {
+                    let score = similarity(samples, natural, candidate, frame_len, stride);
+                    (candidate, score)
+                })
+                .max_by(|(_, left), (_, right)| left.total_cmp(right))
+                .map_or(nominal, |(candidate, _)| candidate)
+        };
+
+        for (offset, weight) in window.iter().enumerate() {
+            output[output_position + offset] += f64::from(samples[position + offset]) * weight;
+        }
+        previous_position = position;
+        frame_index += 1;
+    }
+
+    let written = (frame_index * synthesis_hop + synthesis_hop).min(output.len());
+    output[..written]
+        .iter()
+        .map(|sample| {
+            sample
+                .round()
+                .clamp(f64::from(i16::MIN), f64::from(i16::MAX)) as i16
+        })
+        .collect()
+}
+
+fn similarity(
+    samples: &[i16],
+    reference: usize,
+    candidate: usize,
+    frame_len: usize,
+    stride: usize,
+) -> f64 {
+    (0..frame_len)
+        .step_by(stride)
+        .map(|offset| {
+            f64::from(samples[reference + offset]) * f64::from(samples[candidate + offset])
+        })
+        .sum()
+}
+
+// Counts loudness peaks (roughly one per syllable nucleus) per second of
+// voiced audio, ignoring pauses.
+fn estimate_syllable_rate(samples: &[i16], sample_rate_hz: u32) -> f64 {
+    let frame_len = ((sample_rate_hz * ENVELOPE_FRAME_MS / 1000) as usize).max(1);
+    let energies = samples
+        .chunks(frame_len)
+        .map(|frame| {
+            let sum = frame
+                .iter()
+                .map(|sample| f64::from(*sample).powi(2))
+                .sum::<f64>();
+            (sum / frame.len() as f64).sqrt()
+        })
+        .collect::<Vec<_>>();
+    let envelope = (0..energies.len())
+        .map(|index| {
+            let start = index.saturating_sub(ENVELOPE_SMOOTHING_FRAMES / 2);
+            let end = (index + ENVELOPE_SMOOTHING_FRAMES / 2 + 1).min(energies.len());
+            energies[start..end].iter().sum::<f64>() / (end - start) as f64
+        })
+        .collect::<Vec<_>>();
+
+    let mut sorted = envelope.clone();
+    sorted.sort_by(f64::total_cmp);
+    let loud = sorted
+        .get(sorted.len().saturating_sub(1) * 95 / 100)
+        .copied()
+        .unwrap_or(0.0);
+    let voiced_threshold = loud * 0.15;
+    let voiced_frames = envelope
+        .iter()
+        .filter(|energy| **energy > voiced_threshold)
+        .count();
+    if voiced_frames == 0 || loud <= 0.0 {
+        return 0.0;
+    }
+
+    let peaks = (0..envelope.len())
+        .filter(|index| {
+            let energy = envelope[*index];
+            let start = index.saturating_sub(SYLLABLE_PEAK_RADIUS_FRAMES);
+            let end = (index + SYLLABLE_PEAK_RADIUS_FRAMES + 1).min(envelope.len());
+            energy > voiced_threshold
+                && envelope[start..*index].iter().all(|other| *other < energy)
+                && envelope[index + 1..end]
+                    .iter()
+                    .all(|other| *other <= energy)
+        })
+        .count();
+    let voiced_secs = (voiced_frames * frame_len) as f64 / f64::from(sample_rate_hz.max(1));
+    peaks as f64 / voiced_secs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate_syllable_rate, restore_timeline, wsola};
+    use crate::transcription::{TranscriptionResult, TranscriptionSegment};
+
+    const RATE: u32 = 16_000;
+
+    // A 200 Hz tone amplitude-modulated at `syllables_per_sec`.
+    fn speech_like(seconds: f64, syllables_per_sec: f64) -> Vec<i16> {
+        (0..(seconds * f64::from(RATE)) as usize)
+            .map(|index| {
+                let t = index as f64 / f64::from(RATE);
+                let envelope = (std::f64::consts::PI * syllables_per_sec * t).sin().abs();
+                ((2.0 * std::f64::consts::PI * 200.0 * t).sin() * envelope * 12_000.0) as i16
+            })
+            .collect()
+    }
+
+    fn dominant_period(samples: &[i16]) -> usize {
+        let window = &samples[samples.len() / 2..samples.len() / 2 + 2_000];
+        (40..160)
+            .max_by_key(|lag| {
+                window
+                    .iter()
+                    .zip(&window[*lag..])
+                    .map(|(left, right)| i64::from(*left) * i64::from(*right))
+                    .sum::<i64>()
+            })
+            .expect("lag range is not empty")
+    }
+
+    #[test]
+    fn shortens_audio_by_the_factor_without_shifting_pitch() {
+        let samples = speech_like(4.0, 4.0);
+
+        let stretched = wsola(&samples, RATE, 1.25);
+
+        let expected = samples.len() as f64 / 1.25;
+        assert!((stretched.len() as f64 - expected).abs() < f64::from(RATE) * 0.05);
+        assert_eq!(dominant_period(&samples), 80);
+        assert!((dominant_period(&stretched) as i64 - 80).abs() <= 1);
+    }
+
+    #[test]
+    fn estimates_speaking_rate_and_restores_timestamps() {
+        let slow = estimate_syllable_rate(&speech_like(6.0, 3.0), RATE);
+        let fast = estimate_syllable_rate(&speech_like(6.0, 7.0), RATE);
+        assert!((2.5..3.8).contains(&slow), "slow rate was {slow}");
+        assert!(fast > 6.0, "fast rate was {fast}");
+
+        let mut result = TranscriptionResult {
+            duration_secs: Some(8.0),
+            segments: vec![TranscriptionSegment {
+                start_secs: 2.0,
+                end_secs: 4.0,
+                text: "hello".to_string(),
+                speaker: None,
+                confidence: None,
+            }],
+            ..TranscriptionResult::default()
+        };
+        restore_timeline(&mut result, 1.5);
+        assert_eq!(result.duration_secs, Some(12.0));
+        assert_eq!(result.segments[0].start_secs, 3.0);
+        assert_eq!(result.segments[0].end_secs, 6.0);
+    }
+}
@@ -1,20 +1,26 @@
 use async_trait::async_trait;
 use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
 use bytes::Bytes;
-use reqwest::{multipart, Client, StatusCode};
+use reqwest::{multipart, StatusCode};
 use serde::Deserialize;
 use serde_json::Value;
 use std::time::Duration;
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
 
 use crate::{
+    audio_encoding::AudioEncoding,
     auth_store::{now_epoch_seconds, AuthMethod, AuthStore},
     oauth,
 };
 
 use super::{
-    normalize_transcript_text, TranscriptionError, TranscriptionOptions, TranscriptionProvider,
-    TranscriptionResult,
+    normalize_transcript_text,
+    retry::{
+        classify_status, classify_transport_error, parse_retry_after, report_retry,
+        seed_from_clock, RetryClass, RetryPolicy, TranscriptionRetryCallback,
+    },
+    run_cancellable, TranscriptionDeltaCallback, TranscriptionError, TranscriptionOptions,
+    TranscriptionProvider, TranscriptionResult,
 };
 
 const DEFAULT_CHATGPT_ENDPOINT: &str = "https://chatgpt.com/backend-api/transcribe";
@@ -27,6 +33,8 @@ const CODEX_BASE64_HEADER_VALUE: &str = "1";
 pub struct ChatGptTranscriptionConfig {
     pub endpoint: String,
     pub request_timeout_secs: u64,
+    pub retry_policy: RetryPolicy,
+    pub oauth_token_url: String,
 }
 
 impl Default for ChatGptTranscriptionConfig {
@@ -34,6 +42,8 @@ impl Default for ChatGptTranscriptionConfig {
         Self {
             endpoint: DEFAULT_CHATGPT_ENDPOINT.to_string(),
             request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+            retry_policy: RetryPolicy::default(),
+            oauth_token_url: oauth::TOKEN_URL.to_string(),
         }
     }
 }
@@ -50,9 +60,12 @@ impl ChatGptTranscriptionConfig {
             config.request_timeout_secs = timeout_secs.max(1);
         }
 
+        config.retry_policy = RetryPolicy::from_env("CHATGPT_TRANSCRIPTION");
+
         debug!(
             endpoint = %config.endpoint,
             request_timeout_secs = config.request_timeout_secs,
+            max_retries = config.retry_policy.max_retries,
             "loaded ChatGPT transcription config"
         );
 
@@ -62,9 +75,9 @@ impl ChatGptTranscriptionConfig {
 
 #[derive(Debug, Clone)]
 pub struct ChatGptTranscriptionProvider {
-    client: Client,
     config: ChatGptTranscriptionConfig,
     auth_store: AuthStore,
+    jitter_seed: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -82,9 +95,9 @@ impl ChatGptTranscriptionProvider {
         );
 
         Self {
-            client: build_client(&config),
             config,
             auth_store,
+            jitter_seed: seed_from_clock(),
         }
     }
 
@@ -92,7 +105,7 @@ impl ChatGptTranscriptionProvider {
         let method = self
             .auth_store
             .current_auth_method()
-            .map_err(TranscriptionError::Provider)?;
+            .map_err(|error| TranscriptionError::Provider(error.message))?;
 
         if method != AuthMethod::ChatgptOauth {
             return Err(TranscriptionError::Authentication(
@@ -103,7 +116,7 @@ impl ChatGptTranscriptionProvider {
         let Some(credentials) = self
             .auth_store
             .chatgpt_credentials()
-            .map_err(TranscriptionError::Provider)?
+            .map_err(|error| TranscriptionError::Provider(error.message))?
         else {
             return Err(TranscriptionError::Authentication(
                 "Missing ChatGPT OAuth credentials. Please login again.".to_string(),
@@ -112,30 +125,7 @@ impl ChatGptTranscriptionProvider {
 
         if credentials.expires_at <= now_epoch_seconds().saturating_add(60) {
             warn!("ChatGPT OAuth token expired or near expiry; refreshing");
-            let refreshed = oauth::refresh_access_token(&credentials.refresh_token)
-                .await
-                .map_err(TranscriptionError::Authentication)?;
-
-            let refreshed_refresh_token = refreshed
-                .refresh_token
-                .unwrap_or(credentials.refresh_token.clone());
-            let refreshed_account_id = refreshed
-                .account_id
-                .unwrap_or(credentials.account_id.clone());
-
-            self.auth_store
-                .update_chatgpt_tokens(
-                    &refreshed.access_token,
-                    &refreshed_refresh_token,
-                    refreshed.expires_at,
-                    &refreshed_account_id,
-                )
-                .map_err(TranscriptionError::Provider)?;
-
-            return Ok(ChatGptAuthContext {
-                access_token: refreshed.access_token,
-                account_id: refreshed_account_id,
-            });
+            return self.refreshed_auth_context(&credentials.access_token).await;
         }
 
         Ok(ChatGptAuthContext {
@@ -144,13 +134,33 @@ impl ChatGptTranscriptionProvider {
         })
     }
 
-    fn build_form(&self, audio_data: Vec<u8>) -> Result<multipart::Form, TranscriptionError> {
-        let encoded_audio = BASE64_STANDARD.encode(Bytes::from(audio_data));
+    async fn refreshed_auth_context(
+        &self,
+        stale_access_token: &str,
+    ) -> Result<ChatGptAuthContext, TranscriptionError> {
+        let refreshed = oauth::refresh_stored_tokens(
+            &self.auth_store,
+            &self.config.oauth_token_url,
+            stale_access_token,
+        )
+        .await
+        .map_err(TranscriptionError::Authentication)?;
+        Ok(ChatGptAuthContext {
+            access_token: refreshed.access_token,
+            account_id: refreshed.account_id,
+        })
+    }
+
+    fn build_form(
+        &self,
+        encoded_audio: Bytes,
+        file_name: &'static str,
+    ) -> Result<multipart::Form, TranscriptionError> {
         let audio_len = u64::try_from(encoded_audio.len())
             .map_err(|_| TranscriptionError::Provider("Audio upload is too large".to_string()))?;
 
-        let file_part = multipart::Part::stream_with_length(encoded_audio.into_bytes(), audio_len)
-            .file_name("audio.wav")
+        let file_part = multipart::Part::stream_with_length(encoded_audio, audio_len)
+            .file_name(file_name)
             .mime_str("application/octet-stream")
             .map_err(|error| {
                 TranscriptionError::Provider(format!("Unable to prepare audio upload: {error}"))
@@ -158,6 +168,19 @@ impl ChatGptTranscriptionProvider {
 
         Ok(multipart::Form::new().part("file", file_part))
     }
+
+    fn retry_delay(
+        &self,
+        attempt_index: u32,
+        class: RetryClass,
+        retry_after: Option<Duration>,
+    ) -> Duration {
+        // Only rate-limit responses carry a meaningful Retry-After.
+        let retry_after = retry_after.filter(|_| class == RetryClass::RateLimited);
+        self.config
+            .retry_policy
+            .delay(attempt_index, retry_after, self.jitter_seed)
+    }
 }
 
 #[async_trait]
@@ -173,29 +196,101 @@ impl TranscriptionProvider for ChatGptTranscriptionProvider {
     ) -> Result<TranscriptionResult, TranscriptionError> {
         let TranscriptionOptions {
             on_delta,
+            on_retry,
+            cancellation,
             language: _,
             prompt: _,
             context_hint: _,
+            decoding: _,
         } = options;
 
-        let auth = self.auth_context().await?;
-        let form = self.build_form(audio_data)?;
+        run_cancellable(
+            cancellation.as_ref(),
+            self.send_transcription(audio_data, on_delta, on_retry),
+        )
+        .await
+    }
+}
+
+impl ChatGptTranscriptionProvider {
+    async fn send_transcription(
+        &self,
+        audio_data: Vec<u8>,
+        on_delta: Option<TranscriptionDeltaCallback>,
+        on_retry: Option<TranscriptionRetryCallback>,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let mut auth = self.auth_context().await?;
+        let file_name = AudioEncoding::detect(&audio_data).file_name();
+        let encoded_audio = Bytes::from(BASE64_STANDARD.encode(audio_data));
+        let mut attempt_index = 0;
+        let mut refreshed_after_unauthorized = false;
 
         info!(endpoint = %self.config.endpoint, "starting ChatGPT transcription request");
-        let response = self
-            .client
-            .post(&self.config.endpoint)
-            .bearer_auth(auth.access_token)
-            .header(CHATGPT_ACCOUNT_HEADER, auth.account_id)
-            .header(CODEX_BASE64_HEADER, CODEX_BASE64_HEADER_VALUE)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(map_transport_error)?;
-
-        if !response.status().is_success() {
-            return Err(map_http_error(response).await);
-        }
+        let response = loop {
+            debug!(
+                attempt = attempt_index + 1,
+                "sending ChatGPT transcription request"
+            );
+            let form = self.build_form(encoded_audio.clone(), file_name)?;
+            let response = crate::http_client::shared_client()
+                .post(&self.config.endpoint)
+                .timeout(Duration::from_secs(self.config.request_timeout_secs.max(1)))
+                .bearer_auth(&auth.access_token)
+                .header(CHATGPT_ACCOUNT_HEADER, &auth.account_id)
+                .header(CODEX_BASE64_HEADER, CODEX_BASE64_HEADER_VALUE)
+                .multipart(form)
+                .send()
+                .await;
+
+            let (error, retry_class, retry_after) = match response {
+                Ok(response) if response.status().is_success() => break response,
+                // The token can be revoked or rotated elsewhere before it
+                // expires; refresh once and resend rather than failing.
+                Ok(response)
+                    if response.status() == StatusCode::UNAUTHORIZED
+                        && !refreshed_after_unauthorized =>
+                {
+                    warn!("ChatGPT rejected the access token; refreshing and retrying once");
+                    refreshed_after_unauthorized = true;
+                    auth = self.refreshed_auth_context(&auth.access_token).await?;
+                    continue;
+                }
+                Ok(response) => {
+                    let retry_class = classify_status(response.status());
+                    let retry_after = parse_retry_after(response.headers());
+                    (map_http_error(response).await, retry_class, retry_after)
+                }
+                Err(error) => {
+                    let retry_class = classify_transport_error(&error);
+                    (map_transport_error(error), retry_class, None)
+                }
+            };
+
+            if let Some(class) = retry_class
+                .filter(|class| self.config.retry_policy.should_retry(*class, attempt_index))
+            {
+                let delay = self.retry_delay(attempt_index, class, retry_after);
+                report_retry(
+                    on_retry.as_ref(),
+                    self.name(),
+                    &self.config.retry_policy,
+                    attempt_index,
+                    class,
+                    delay,
+                    &error,
+                );
+                tokio::time::sleep(delay).await;
+                attempt_index += 1;
+                continue;
+            }
+
+            error!(
+                attempt = attempt_index + 1,
+                error = %error,
+                "ChatGPT transcription request failed without retry"
+            );
+            return Err(error);
+        };
 
         let payload = response
             .json::<ChatGptTranscriptionResponse>()
@@ -214,8 +309,11 @@ impl TranscriptionProvider for ChatGptTranscriptionProvider {
         Ok(TranscriptionResult {
             text: normalized,
             language: None,
+            language_confidence: None,
             duration_secs: None,
             confidence: None,
+            segments: Vec::new(),
+            words: Vec::new(),
         })
     }
 }
@@ -316,13 +414,6 @@ fn read_u64_env(name: &str) -> Option<u64> {
         .and_then(|value| value.parse::<u64>().ok())
 }
 
-fn build_client(config: &ChatGptTranscriptionConfig) -> Client {
-    Client::builder()
-        .timeout(Duration::from_secs(config.request_timeout_secs.max(1)))
-        .build()
-        .expect("ChatGPT client construction should succeed")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -345,6 +436,13 @@ mod tests {
             ChatGptTranscriptionConfig {
                 endpoint: format!("{}/backend-api/transcribe", server.url()),
                 request_timeout_secs: 5,
+                retry_policy: RetryPolicy {
+                    max_retries: 2,
+                    initial_backoff_ms: 5,
+                    max_backoff_ms: 10,
+                    ..RetryPolicy::default()
+                },
+                oauth_token_url: format!("{}/oauth/token", server.url()),
             },
             auth_store,
         )
@@ -402,11 +500,20 @@ mod tests {
 
         let mock = server
             .mock("POST", "/backend-api/transcribe")
+            .expect(2)
             .with_status(401)
             .with_header("content-type", "application/json")
             .with_body(r#"{"error":{"message":"Token invalid"}}"#)
             .create_async()
             .await;
+        let refresh_mock = server
+            .mock("POST", "/oauth/token")
+            .expect(1)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"access_token":"still-bad-token","expires_in":3600}"#)
+            .create_async()
+            .await;
 
         let provider = provider_for_test(&server, auth_store);
         let error = provider
@@ -415,9 +522,137 @@ mod tests {
             .expect_err("request should fail");
 
         mock.assert_async().await;
+        refresh_mock.assert_async().await;
         assert_eq!(
             error,
             TranscriptionError::Authentication("Token invalid".to_string())
         );
     }
+
+    #[tokio::test]
+    async fn refreshes_tokens_and_retries_once_after_unauthorized() {
+        let mut server = Server::new_async().await;
+        let app_data_dir = temp_app_data_dir("auth-refresh");
+        let auth_store = AuthStore::new(app_data_dir);
+        auth_store
+            .save_chatgpt_login(
+                "revoked-token",
+                "refresh-token",
+                now_epoch_seconds().saturating_add(600),
+                "acct_123",
+            )
+            .expect("oauth credentials should persist");
+
+        let rejected_mock = server
+            .mock("POST", "/backend-api/transcribe")
+            .match_header("authorization", "Bearer revoked-token")
+            .expect(1)
+            .with_status(401)
+            .with_body(r#"{"error":{"message":"Token revoked"}}"#)
+            .create_async()
+            .await;
+        let refresh_mock = server
+            .mock("POST", "/oauth/token")
+            .match_body(Matcher::UrlEncoded(
+                "refresh_token".to_string(),
+                "refresh-token".to_string(),
+            ))
+            .expect(1)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"access_token":"fresh-token","refresh_token":"rotated-token","expires_in":3600}"#,
+            )
+            .create_async()
+            .await;
+        let accepted_mock = server
+            .mock("POST", "/backend-api/transcribe")
+            .match_header("authorization", "Bearer fresh-token")
+            .expect(1)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"text":"after refresh"}"#)
+            .create_async()
+            .await;
+
+        let provider = provider_for_test(&server, auth_store.clone());
+        let result = provider
+            .transcribe(vec![1, 2, 3], TranscriptionOptions::default())
+            .await
+            .expect("transcription should succeed after refreshing");
+
+        rejected_mock.assert_async().await;
+        refresh_mock.assert_async().await;
+        accepted_mock.assert_async().await;
+        assert_eq!(result.text, "after refresh");
+        let stored = auth_store
+            .chatgpt_credentials()
+            .expect("credentials should load")
+            .expect("credentials should exist");
+        assert_eq!(stored.access_token, "fresh-token");
+        assert_eq!(stored.refresh_token, "rotated-token");
+        assert_eq!(stored.account_id, "acct_123");
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_and_reports_each_attempt() {
+        let mut server = Server::new_async().await;
+        let app_data_dir = temp_app_data_dir("retry");
+        let auth_store = AuthStore::new(app_data_dir);
+        auth_store
+            .save_chatgpt_login(
+                "access-token",
+                "refresh-token",
+                now_epoch_seconds().saturating_add(600),
+                "acct_123",
+            )
+            .expect("oauth credentials should persist");
+
+        let unavailable_mock = server
+            .mock("POST", "/backend-api/transcribe")
+            .expect(2)
+            .with_status(503)
+            .with_body(r#"{"error":"Upstream unavailable"}"#)
+            .create_async()
+            .await;
+        let success_mock = server
+            .mock("POST", "/backend-api/transcribe")
+            .expect(1)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"text":"third time lucky"}"#)
+            .create_async()
+            .await;
+
+        let retries = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let captured = std::sync::Arc::clone(&retries);
+        let options = TranscriptionOptions {
+            on_retry: Some(std::sync::Arc::new(move |event| {
+                captured
+                    .lock()
+                    .expect("retry lock should not be poisoned")
+                    .push(event);
+            })),
+            ..TranscriptionOptions::default()
+        };
+
+        let provider = provider_for_test(&server, auth_store);
+        let result = provider
+            .transcribe(vec![1, 2, 3], options)
+            .await
+            .expect("transcription should succeed after retries");
+
+        unavailable_mock.assert_async().await;
+        success_mock.assert_async().await;
+        assert_eq!(result.text, "third time lucky");
+        let retries = retries.lock().expect("retry lock should not be poisoned");
+        assert_eq!(
+            retries
+                .iter()
+                .map(|event| (event.attempt, event.max_attempts, event.reason.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(2, 3, "server_error"), (3, 3, "server_error")]
+        );
+        assert!(retries[0].message.contains("Upstream unavailable"));
+    }
 }
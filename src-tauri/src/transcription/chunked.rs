@@ -0,0 +1,741 @@
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use futures_util::{stream, StreamExt};
+use tracing::{debug, info, warn};
+
+use crate::audio_capture_service::pcm16_to_wav_bytes;
+use crate::audio_encoding::{encode_pcm16, AudioEncoding};
+
+use super::{
+    normalize_transcript_text, TranscriptionError, TranscriptionOptions, TranscriptionProvider,
+    TranscriptionResult, TranscriptionSegment, TranscriptionWord,
+};
+
+const DEFAULT_CHUNK_SECS: u32 = 60;
+const DEFAULT_CHUNK_OVERLAP_SECS: u32 = 2;
+const DEFAULT_MAX_PARALLEL_REQUESTS: usize = 3;
+const MAX_STITCH_OVERLAP_WORDS: usize = 12;
+const WAV_HEADER_LEN: usize = 44;
+// Enough for the fmt chunk plus any metadata chunks ahead of `data`.
+const WAV_HEADER_SCAN_LEN: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkedTranscriptionConfig {
+    pub chunk_secs: u32,
+    pub overlap_secs: u32,
+    pub max_parallel_requests: usize,
+    pub audio_encoding: AudioEncoding,
+}
+
+impl Default for ChunkedTranscriptionConfig {
+    fn default() -> Self {
+        Self {
+            chunk_secs: DEFAULT_CHUNK_SECS,
+            overlap_secs: DEFAULT_CHUNK_OVERLAP_SECS,
+            max_parallel_requests: DEFAULT_MAX_PARALLEL_REQUESTS,
+            audio_encoding: AudioEncoding::Wav,
+        }
+    }
+}
+
+impl ChunkedTranscriptionConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Some(chunk_secs) = read_u64_env("VOICE_MEETING_CHUNK_SECS") {
+            config.chunk_secs = u32::try_from(chunk_secs).unwrap_or(u32::MAX).max(5);
+        }
+
+        if let Some(overlap_secs) = read_u64_env("VOICE_MEETING_CHUNK_OVERLAP_SECS") {
+            config.overlap_secs = u32::try_from(overlap_secs).unwrap_or(u32::MAX);
+        }
+
+        if let Some(max_parallel) = read_u64_env("VOICE_MEETING_MAX_PARALLEL_REQUESTS") {
+            config.max_parallel_requests = usize::try_from(max_parallel).unwrap_or(1).max(1);
+        }
+
+        if config.overlap_secs >= config.chunk_secs {
+            config.overlap_secs = config.chunk_secs / 4;
+        }
+
+        debug!(
+            chunk_secs = config.chunk_secs,
+            overlap_secs = config.overlap_secs,
+            max_parallel_requests = config.max_parallel_requests,
+            "loaded chunked transcription config"
+        );
+        config
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Pcm16Wav {
+    pub samples: Vec<i16>,
+    pub sample_rate_hz: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ChunkRange {
+    start_sample: usize,
+    end_sample: usize,
+}
+
+// The format and sample location of a WAV, read from its header alone so a
+// spooled recording never has to be loaded whole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WavHeader {
+    audio_format: u16,
+    channels: u16,
+    sample_rate_hz: u32,
+    block_align: u16,
+    bits_per_sample: u16,
+    data_offset: usize,
+    data_len: usize,
+}
+
+impl WavHeader {
+    fn check_pcm16_mono(&self) -> Result<(), String> {
+        if self.audio_format != 1 || self.channels != 1 || self.bits_per_sample != 16 {
+            return Err(format!(
+                "Unsupported WAV format (format {}, {} channels, {} bits); expected mono 16-bit PCM",
+                self.audio_format, self.channels, self.bits_per_sample
+            ));
+        }
+        Ok(())
+    }
+
+    // One frame holds a sample for every channel.
+    fn frame_count(&self) -> usize {
+        self.data_len / usize::from(self.block_align.max(1))
+    }
+
+    fn duration_secs(&self) -> Option<f64> {
+        if self.sample_rate_hz == 0 {
+            return None;
+        }
+        Some(self.frame_count() as f64 / f64::from(self.sample_rate_hz))
+    }
+}
+
+// A mono 16-bit WAV on disk, read a range of samples at a time.
+#[derive(Debug, Clone)]
+pub struct Pcm16WavFile {
+    path: PathBuf,
+    header: WavHeader,
+}
+
+impl Pcm16WavFile {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(read_error)?;
+        let total_len = file.metadata().map_err(read_error)?.len();
+        let mut header_bytes = Vec::new();
+        file.take(WAV_HEADER_SCAN_LEN)
+            .read_to_end(&mut header_bytes)
+            .map_err(read_error)?;
+        let header = parse_wav_header(&header_bytes, total_len)?;
+        header.check_pcm16_mono()?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            header,
+        })
+    }
+
+    pub fn sample_rate_hz(&self) -> u32 {
+        self.header.sample_rate_hz
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.header.frame_count()
+    }
+
+    pub fn duration_secs(&self) -> Option<f64> {
+        self.header.duration_secs()
+    }
+
+    // Opens the file per call so chunks can be read concurrently. The range
+    // is clamped to the samples the file holds.
+    pub fn read_samples(&self, start_sample: usize, end_sample: usize) -> Result<Vec<i16>, String> {
+        let end_sample = end_sample.min(self.sample_count());
+        let start_sample = start_sample.min(end_sample);
+        let mut file = File::open(&self.path).map_err(read_error)?;
+        file.seek(SeekFrom::Start(
+            (self.header.data_offset + start_sample * 2) as u64,
+        ))
+        .map_err(read_error)?;
+        let mut bytes = vec![0; (end_sample - start_sample) * 2];
+        file.read_exact(&mut bytes).map_err(read_error)?;
+        Ok(bytes
+            .chunks_exact(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+            .collect())
+    }
+}
+
+// Chunks are sliced from samples already in memory or read range by range
+// from a WAV on disk.
+enum ChunkSource<'a> {
+    Samples(&'a [i16]),
+    File(&'a Pcm16WavFile),
+}
+
+impl ChunkSource<'_> {
+    fn sample_count(&self) -> usize {
+        match self {
+            Self::Samples(samples) => samples.len(),
+            Self::File(file) => file.sample_count(),
+        }
+    }
+
+    fn samples(&self, range: ChunkRange) -> Result<Cow<'_, [i16]>, String> {
+        match self {
+            Self::Samples(samples) => Ok(Cow::Borrowed(
+                &samples[range.start_sample..range.end_sample],
+            )),
+            Self::File(file) => file
+                .read_samples(range.start_sample, range.end_sample)
+                .map(Cow::Owned),
+        }
+    }
+}
+
+// Only the header is parsed, so this is cheap even for long recordings.
+pub fn wav_duration_secs(wav_bytes: &[u8]) -> Option<f64> {
+    parse_wav_header(wav_bytes, wav_bytes.len() as u64)
+        .ok()?
+        .duration_secs()
+}
+
+pub async fn transcribe_chunked(
+    provider: &dyn TranscriptionProvider,
+    wav_bytes: &[u8],
+    options: TranscriptionOptions,
+    config: &ChunkedTranscriptionConfig,
+) -> Result<TranscriptionResult, TranscriptionError> {
+    let wav = parse_pcm16_mono_wav(wav_bytes).map_err(TranscriptionError::Provider)?;
+    transcribe_chunk_source(
+        provider,
+        ChunkSource::Samples(&wav.samples),
+        wav.sample_rate_hz,
+        options,
+        config,
+    )
+    .await
+}
+
+// Same as `transcribe_chunked`, but only one chunk of the file is in memory
+// per request in flight.
+pub async fn transcribe_chunked_file(
+    provider: &dyn TranscriptionProvider,
+    path: &Path,
+    options: TranscriptionOptions,
+    config: &ChunkedTranscriptionConfig,
+) -> Result<TranscriptionResult, TranscriptionError> {
+    let file = Pcm16WavFile::open(path).map_err(TranscriptionError::Provider)?;
+    transcribe_chunk_source(
+        provider,
+        ChunkSource::File(&file),
+        file.sample_rate_hz(),
+        options,
+        config,
+    )
+    .await
+}
+
+async fn transcribe_chunk_source(
+    provider: &dyn TranscriptionProvider,
+    source: ChunkSource<'_>,
+    sample_rate_hz: u32,
+    options: TranscriptionOptions,
+    config: &ChunkedTranscriptionConfig,
+) -> Result<TranscriptionResult, TranscriptionError> {
+    let sample_count = source.sample_count();
+    let ranges = plan_chunks(sample_count, sample_rate_hz, config);
+    info!(
+        provider = provider.name(),
+        chunk_count = ranges.len(),
+        chunk_secs = config.chunk_secs,
+        overlap_secs = config.overlap_secs,
+        "starting chunked transcription"
+    );
+
+    let on_delta = options.on_delta.clone();
+    let chunk_options = TranscriptionOptions {
+        on_delta: None,
+        ..options
+    };
+    let source = &source;
+    let mut chunk_results = stream::iter(ranges.iter().copied().enumerate())
+        .map(|(index, range)| {
+            let chunk_options = chunk_options.clone();
+            async move {
+                let chunk_samples = source
+                    .samples(range)
+                    .map_err(TranscriptionError::Provider)?;
+                let chunk_audio =
+                    encode_pcm16(&chunk_samples, sample_rate_hz, config.audio_encoding)
+                        .or_else(|error| {
+                            warn!(
+                                chunk_index = index,
+                                error = %error,
+                                "chunk encoding failed; uploading WAV"
+                            );
+                            pcm16_to_wav_bytes(&chunk_samples, sample_rate_hz, 1)
+                        })
+                        .map_err(TranscriptionError::Provider)?;
+                debug!(
+                    chunk_index = index,
+                    audio_bytes = chunk_audio.len(),
+                    "transcribing audio chunk"
+                );
+                provider
+                    .transcribe(chunk_audio, chunk_options)
+                    .await
+                    .map(|result| (range, result))
+            }
+        })
+        .buffered(config.max_parallel_requests.max(1));
+
+    // Provider speaker labels are only meaningful within one request, so they are
+    // dropped when the recording spans several chunks.
+    let keep_provider_speakers = ranges.len() == 1;
+    let mut text = String::new();
+    let mut segments = Vec::with_capacity(ranges.len());
+    let mut words = Vec::new();
+    let mut covered_until_secs = 0.0;
+    let mut words_covered_until_secs = 0.0;
+    let mut language = None;
+    let mut language_confidence = None;
+    let mut confidences = Vec::new();
+    while let Some(chunk_result) = chunk_results.next().await {
+        let (range, result) = chunk_result?;
+        let chunk_text = normalize_transcript_text(&result.text);
+        let appended = append_with_overlap(&mut text, &chunk_text);
+        if language.is_none() {
+            language = result.language;
+            language_confidence = result.language_confidence;
+        }
+        if let Some(confidence) = result.confidence {
+            confidences.push(confidence);
+        }
+        if appended.is_empty() {
+            continue;
+        }
+
+        if let Some(callback) = on_delta.as_ref() {
+            let delta = if text.len() == appended.len() {
+                appended.clone()
+            } else {
+                format!(" {appended}")
+            };
+            callback(delta);
+        }
+
+        let chunk_start_secs = range.start_sample as f64 / f64::from(sample_rate_hz);
+        let chunk_end_secs = range.end_sample as f64 / f64::from(sample_rate_hz);
+        for word in result.words {
+            let start_secs = chunk_start_secs + word.start_secs;
+            let end_secs = (chunk_start_secs + word.end_secs).min(chunk_end_secs);
+            if (start_secs + end_secs) / 2.0 < words_covered_until_secs {
+                continue;
+            }
+            words.push(TranscriptionWord {
+                start_secs,
+                end_secs,
+                text: word.text,
+                confidence: word.confidence,
+            });
+            words_covered_until_secs = end_secs;
+        }
+
+        if result.segments.is_empty() {
+            segments.push(TranscriptionSegment {
+                start_secs: chunk_start_secs,
+                end_secs: chunk_end_secs,
+                text: appended,
+                speaker: None,
+                confidence: result.confidence,
+            });
+            covered_until_secs = chunk_end_secs;
+            continue;
+        }
+
+        for segment in result.segments {
+            let start_secs = chunk_start_secs + segment.start_secs;
+            let end_secs = (chunk_start_secs + segment.end_secs).min(chunk_end_secs);
+            // Segments inside the overlap were already covered by the previous chunk.
+            if (start_secs + end_secs) / 2.0 < covered_until_secs {
+                continue;
+            }
+            segments.push(TranscriptionSegment {
+                start_secs,
+                end_secs,
+                text: normalize_transcript_text(&segment.text),
+                speaker: segment.speaker.filter(|_| keep_provider_speakers),
+                confidence: segment.confidence,
+            });
+            covered_until_secs = end_secs;
+        }
+    }
+
+    let confidence = if confidences.is_empty() {
+        None
+    } else {
+        Some(confidences.iter().sum::<f32>() / confidences.len() as f32)
+    };
+    info!(
+        provider = provider.name(),
+        segment_count = segments.len(),
+        transcript_chars = text.chars().count(),
+        "chunked transcription completed"
+    );
+
+    Ok(TranscriptionResult {
+        text,
+        language,
+        language_confidence,
+        duration_secs: Some(sample_count as f64 / f64::from(sample_rate_hz)),
+        confidence,
+        segments,
+        words,
+    })
+}
+
+pub(crate) fn parse_pcm16_mono_wav(wav_bytes: &[u8]) -> Result<Pcm16Wav, String> {
+    let header = parse_wav_header(wav_bytes, wav_bytes.len() as u64)?;
+    header.check_pcm16_mono()?;
+    let samples = wav_bytes[header.data_offset..header.data_offset + header.data_len]
+        .chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    Ok(Pcm16Wav {
+        samples,
+        sample_rate_hz: header.sample_rate_hz,
+    })
+}
+
+// `header_bytes` may be just the start of a file that is `total_len` long;
+// it has to reach past the `data` chunk's own header.
+fn parse_wav_header(header_bytes: &[u8], total_len: u64) -> Result<WavHeader, String> {
+    if header_bytes.len() < WAV_HEADER_LEN
+        || &header_bytes[0..4] != b"RIFF"
+        || &header_bytes[8..12] != b"WAVE"
+    {
+        return Err("Audio payload is not a WAV file".to_string());
+    }
+
+    let mut offset = 12;
+    let mut format: Option<(u16, u16, u32, u16, u16)> = None;
+    while offset + 8 <= header_bytes.len() {
+        let chunk_id = &header_bytes[offset..offset + 4];
+        let chunk_len = u32::from_le_bytes([
+            header_bytes[offset + 4],
+            header_bytes[offset + 5],
+            header_bytes[offset + 6],
+            header_bytes[offset + 7],
+        ]) as usize;
+        let body_start = offset + 8;
+
+        if chunk_id == b"fmt " {
+            let body_end = body_start.saturating_add(chunk_len).min(header_bytes.len());
+            let body = &header_bytes[body_start..body_end];
+            if body.len() >= 16 {
+                format = Some((
+                    u16::from_le_bytes([body[0], body[1]]),
+                    u16::from_le_bytes([body[2], body[3]]),
+                    u32::from_le_bytes([body[4], body[5], body[6], body[7]]),
+                    u16::from_le_bytes([body[12], body[13]]),
+                    u16::from_le_bytes([body[14], body[15]]),
+                ));
+            }
+        } else if chunk_id == b"data" {
+            let (audio_format, channels, sample_rate_hz, block_align, bits_per_sample) =
+                format.ok_or_else(|| "WAV data chunk appeared before fmt chunk".to_string())?;
+            let available =
+                usize::try_from(total_len.saturating_sub(body_start as u64)).unwrap_or(usize::MAX);
+            return Ok(WavHeader {
+                audio_format,
+                channels,
+                sample_rate_hz,
+                block_align,
+                bits_per_sample,
+                data_offset: body_start,
+                data_len: chunk_len.min(available),
+            });
+        }
+
+        offset = body_start.saturating_add(chunk_len + (chunk_len % 2));
+    }
+
+    Err("WAV file is missing a data chunk".to_string())
+}
+
+fn read_error(error: std::io::Error) -> String {
+    format!("Failed to read recorded audio: {error}")
+}
+
+fn plan_chunks(
+    sample_count: usize,
+    sample_rate_hz: u32,
+    config: &ChunkedTranscriptionConfig,
+) -> Vec<ChunkRange> {
+    let rate = sample_rate_hz as usize;
+    let chunk_len = (config.chunk_secs.max(1) as usize)
+        .saturating_mul(rate)
+        .max(1);
+    let overlap_len = (config.overlap_secs as usize)
+        .saturating_mul(rate)
+        .min(chunk_len / 2);
+    let step = chunk_len - overlap_len;
+
+    let mut ranges = Vec::new();
+    let mut start_sample = 0usize;
+    loop {
+        let end_sample = start_sample.saturating_add(chunk_len).min(sample_count);
+        ranges.push(ChunkRange {
+            start_sample,
+            end_sample,
+        });
+        if end_sample >= sample_count {
+            break;
+        }
+        start_sample += step;
+    }
+    ranges
+}
+
+// Chunks overlap by a few seconds so words on a boundary are not cut in half;
+// drop the longest run of words the next chunk repeats from the previous tail.
+fn append_with_overlap(text: &mut String, next: &str) -> String {
+    let next_words = next.split_whitespace().collect::<Vec<_>>();
+    if next_words.is_empty() {
+        return String::new();
+    }
+
+    let existing_words = text.split_whitespace().collect::<Vec<_>>();
+    let max_overlap = MAX_STITCH_OVERLAP_WORDS
+        .min(existing_words.len())
+        .min(next_words.len());
+    let overlap = (1..=max_overlap)
+        .rev()
+        .find(|&count| {
+            existing_words[existing_words.len() - count..]
+                .iter()
+                .zip(&next_words[..count])
+                .all(|(left, right)| words_match(left, right))
+        })
+        .unwrap_or(0);
+
+    let appended = next_words[overlap..].join(" ");
+    if !appended.is_empty() {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(&appended);
+    }
+    appended
+}
+
+fn words_match(left: &str, right: &str) -> bool {
+    let normalize = |word: &str| {
+        word.chars()
+            .filter(|ch| ch.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    normalize(left) == normalize(right)
+}
+
+fn read_u64_env(name: &str) -> Option<u64> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .and_then(|value| value.parse::<u64>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug)]
+    struct ScriptedProvider {
+        responses: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl TranscriptionProvider for ScriptedProvider {
+        fn name(&self) -> &'static str {
+            "scripted"
+        }
+
+        async fn transcribe(
+            &self,
+            audio_data: Vec<u8>,
+            _options: TranscriptionOptions,
+        ) -> Result<TranscriptionResult, TranscriptionError> {
+            let wav = parse_pcm16_mono_wav(&audio_data).expect("chunk should be valid wav");
+            let first_sample = wav.samples.first().copied().unwrap_or_default() as usize;
+            let text = self
+                .responses
+                .lock()
+                .expect("responses lock should not be poisoned")[first_sample]
+                .clone();
+            Ok(TranscriptionResult {
+                text,
+                language: Some("en".to_string()),
+                ..TranscriptionResult::default()
+            })
+        }
+    }
+
+    fn config(chunk_secs: u32, overlap_secs: u32) -> ChunkedTranscriptionConfig {
+        ChunkedTranscriptionConfig {
+            chunk_secs,
+            overlap_secs,
+            max_parallel_requests: 2,
+            audio_encoding: AudioEncoding::Wav,
+        }
+    }
+
+    #[test]
+    fn plans_overlapping_chunks_covering_all_samples() {
+        let ranges = plan_chunks(25, 1, &config(10, 2));
+
+        assert_eq!(
+            ranges,
+            vec![
+                ChunkRange {
+                    start_sample: 0,
+                    end_sample: 10
+                },
+                ChunkRange {
+                    start_sample: 8,
+                    end_sample: 18
+                },
+                ChunkRange {
+                    start_sample: 16,
+                    end_sample: 25
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn append_with_overlap_drops_repeated_boundary_words() {
+        let mut text = "we should ship the release on Friday".to_string();
+
+        let appended = append_with_overlap(&mut text, "on friday, and then celebrate");
+
+        assert_eq!(appended, "and then celebrate");
+        assert_eq!(
+            text,
+            "we should ship the release on Friday and then celebrate"
+        );
+    }
+
+    #[test]
+    fn parses_wav_written_by_capture_service() {
+        let wav_bytes = pcm16_to_wav_bytes(&[1, -2, 3], 16_000, 1).expect("wav should encode");
+
+        let parsed = parse_pcm16_mono_wav(&wav_bytes).expect("wav should parse");
+
+        assert_eq!(parsed.samples, vec![1, -2, 3]);
+        assert_eq!(parsed.sample_rate_hz, 16_000);
+        assert_eq!(wav_duration_secs(&wav_bytes), Some(3.0 / 16_000.0));
+        // Stereo frames are four bytes wide, going by the header's block align.
+        let stereo = pcm16_to_wav_bytes(&[0; 8], 4, 2).expect("wav should encode");
+        assert_eq!(wav_duration_secs(&stereo), Some(1.0));
+        assert!(parse_pcm16_mono_wav(b"not a wav").is_err());
+    }
+
+    #[tokio::test]
+    async fn transcribe_chunked_stitches_chunks_in_order_with_segments() {
+        // Each chunk starts with a sample whose value indexes the scripted response.
+        let mut samples = vec![0_i16; 26];
+        samples[8] = 1;
+        samples[16] = 2;
+        let wav_bytes = pcm16_to_wav_bytes(&samples, 1, 1).expect("wav should encode");
+        let provider = ScriptedProvider {
+            responses: Mutex::new(vec![
+                "hello there general".to_string(),
+                "general kenobi you are".to_string(),
+                "you are a bold one".to_string(),
+            ]),
+        };
+        let deltas = Arc::new(Mutex::new(Vec::new()));
+        let captured = Arc::clone(&deltas);
+        let options = TranscriptionOptions {
+            on_delta: Some(Arc::new(move |delta| {
+                captured
+                    .lock()
+                    .expect("delta lock should not be poisoned")
+                    .push(delta);
+            })),
+            ..TranscriptionOptions::default()
+        };
+
+        let result = transcribe_chunked(&provider, &wav_bytes, options, &config(10, 2))
+            .await
+            .expect("chunked transcription should succeed");
+
+        assert_eq!(result.text, "hello there general kenobi you are a bold one");
+        assert_eq!(result.segments.len(), 3);
+        assert_eq!(result.segments[1].text, "kenobi you are");
+        assert_eq!(result.segments[2].start_secs, 16.0);
+        assert_eq!(result.duration_secs, Some(26.0));
+        assert_eq!(
+            deltas
+                .lock()
+                .expect("delta lock should not be poisoned")
+                .concat(),
+            result.text
+        );
+    }
+
+    #[tokio::test]
+    async fn transcribe_chunked_file_reads_each_chunk_from_disk() {
+        let mut samples = vec![0_i16; 26];
+        samples[8] = 1;
+        samples[16] = 2;
+        let path = std::env::temp_dir().join(format!("voice-chunked-{}.wav", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            pcm16_to_wav_bytes(&samples, 1, 1).expect("wav should encode"),
+        )
+        .expect("fixture should write");
+        let provider = ScriptedProvider {
+            responses: Mutex::new(vec![
+                "hello there general".to_string(),
+                "general kenobi you are".to_string(),
+                "you are a bold one".to_string(),
+            ]),
+        };
+
+        assert_eq!(
+            Pcm16WavFile::open(&path)
+                .expect("wav should open")
+                .duration_secs(),
+            Some(26.0)
+        );
+        let result = transcribe_chunked_file(
+            &provider,
+            &path,
+            TranscriptionOptions::default(),
+            &config(10, 2),
+        )
+        .await
+        .expect("chunked transcription should succeed");
+
+        assert_eq!(result.text, "hello there general kenobi you are a bold one");
+        assert_eq!(result.segments[2].start_secs, 16.0);
+        assert_eq!(result.duration_secs, Some(26.0));
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,131 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub const RESPONSE_FORMAT_JSON: &str = "json";
+pub const RESPONSE_FORMAT_VERBOSE_JSON: &str = "verbose_json";
+const MAX_PERCENT: u32 = 100;
+const MAX_BEAM_SIZE: u32 = 16;
+
+// Power-user knobs for trading accuracy against hallucination. Unset fields
+// keep each provider's own defaults. Fractions are stored as percentages so
+// settings stay comparable.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct DecodingOptions {
+    // Sampling temperature; 0 is the most literal.
+    pub temperature_percent: Option<u32>,
+    // Segments the provider thinks are more likely than this to be silence
+    // are dropped from the transcript. Needs per-segment scores, so only
+    // `verbose_json` uploads honour it.
+    pub no_speech_threshold_percent: Option<u32>,
+    // `json` or `verbose_json`. Streaming and diarizing models always use
+    // their own format.
+    pub response_format: Option<String>,
+    // Only read by local whisper.cpp style providers; the hosted APIs choose
+    // their own search.
+    pub beam_size: Option<u32>,
+}
+
+impl DecodingOptions {
+    pub fn normalized(self) -> Result<Self, String> {
+        let temperature_percent = check_percent(self.temperature_percent, "temperature")?;
+        let no_speech_threshold_percent =
+            check_percent(self.no_speech_threshold_percent, "no-speech threshold")?;
+        let response_format = self
+            .response_format
+            .map(|format| format.trim().to_ascii_lowercase())
+            .filter(|format| !format.is_empty())
+            .map(|format| match format.as_str() {
+                RESPONSE_FORMAT_JSON | RESPONSE_FORMAT_VERBOSE_JSON => Ok(format),
+                _ => Err(format!("Unsupported response format `{format}`")),
+            })
+            .transpose()?;
+        let beam_size = self
+            .beam_size
+            .map(|beam_size| {
+                if (1..=MAX_BEAM_SIZE).contains(&beam_size) {
+                    Ok(beam_size)
+                } else {
+                    Err(format!("Beam size must be between 1 and {MAX_BEAM_SIZE}"))
+                }
+            })
+            .transpose()?;
+
+        Ok(Self {
+            temperature_percent,
+            no_speech_threshold_percent,
+            response_format,
+            beam_size,
+        })
+    }
+
+    pub fn temperature(&self) -> Option<f32> {
+        self.temperature_percent.map(percent_to_fraction)
+    }
+
+    pub fn no_speech_threshold(&self) -> Option<f32> {
+        self.no_speech_threshold_percent.map(percent_to_fraction)
+    }
+}
+
+fn check_percent(value: Option<u32>, name: &str) -> Result<Option<u32>, String> {
+    match value {
+        Some(value) if value > MAX_PERCENT => {
+            Err(format!("The {name} must be between 0 and {MAX_PERCENT}%"))
+        }
+        value => Ok(value),
+    }
+}
+
+fn percent_to_fraction(percent: u32) -> f32 {
+    percent as f32 / MAX_PERCENT as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DecodingOptions;
+
+    #[test]
+    fn validates_ranges_and_formats() {
+        let options = DecodingOptions {
+            temperature_percent: Some(20),
+            no_speech_threshold_percent: Some(60),
+            response_format: Some(" Verbose_JSON ".to_string()),
+            beam_size: Some(5),
+        }
+        .normalized()
+        .expect("options should be valid");
+        assert_eq!(options.response_format.as_deref(), Some("verbose_json"));
+        assert_eq!(options.temperature(), Some(0.2));
+        assert_eq!(options.no_speech_threshold(), Some(0.6));
+
+        let blank_format = DecodingOptions {
+            response_format: Some("  ".to_string()),
+            ..DecodingOptions::default()
+        }
+        .normalized()
+        .expect("blank format should be cleared");
+        assert_eq!(blank_format, DecodingOptions::default());
+
+        for invalid in [
+            DecodingOptions {
+                temperature_percent: Some(101),
+                ..DecodingOptions::default()
+            },
+            DecodingOptions {
+                no_speech_threshold_percent: Some(150),
+                ..DecodingOptions::default()
+            },
+            DecodingOptions {
+                response_format: Some("srt".to_string()),
+                ..DecodingOptions::default()
+            },
+            DecodingOptions {
+                beam_size: Some(0),
+                ..DecodingOptions::default()
+            },
+        ] {
+            assert!(invalid.normalized().is_err());
+        }
+    }
+}
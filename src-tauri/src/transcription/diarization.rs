@@ -0,0 +1,364 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use tracing::{debug, info};
+
+use super::TranscriptionSegment;
+
+const DEFAULT_MAX_SPEAKERS: usize = 4;
+const DEFAULT_DISTANCE_THRESHOLD: f32 = 1.5;
+const ANALYSIS_SAMPLE_RATE_HZ: u32 = 8_000;
+const FRAME_MS: u32 = 32;
+const MAX_FRAMES_PER_SEGMENT: usize = 120;
+const SILENCE_RMS_THRESHOLD: f32 = 300.0;
+const MIN_PITCH_HZ: f32 = 70.0;
+const MAX_PITCH_HZ: f32 = 400.0;
+const MIN_PITCH_CORRELATION: f32 = 0.3;
+// Rough per-feature scales so one unit of distance is a perceptible voice change:
+// a quarter octave of pitch, 0.05 zero-crossing rate, 0.1 high-frequency ratio.
+const PITCH_SCALE_OCTAVES: f32 = 0.25;
+const ZERO_CROSSING_SCALE: f32 = 0.05;
+const HIGH_FREQUENCY_SCALE: f32 = 0.1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiarizationConfig {
+    pub max_speakers: usize,
+    pub distance_threshold: f32,
+}
+
+impl Default for DiarizationConfig {
+    fn default() -> Self {
+        Self {
+            max_speakers: DEFAULT_MAX_SPEAKERS,
+            distance_threshold: DEFAULT_DISTANCE_THRESHOLD,
+        }
+    }
+}
+
+impl DiarizationConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Some(max_speakers) = read_u64_env("VOICE_DIARIZATION_MAX_SPEAKERS") {
+            config.max_speakers = usize::try_from(max_speakers).unwrap_or(1).max(1);
+        }
+
+        debug!(
+            max_speakers = config.max_speakers,
+            distance_threshold = config.distance_threshold,
+            "loaded diarization config"
+        );
+        config
+    }
+}
+
+pub fn speaker_label(index: usize) -> String {
+    format!("Speaker {}", index + 1)
+}
+
+pub fn has_speaker_labels(segments: &[TranscriptionSegment]) -> bool {
+    !segments.is_empty() && segments.iter().all(|segment| segment.speaker.is_some())
+}
+
+// Providers return opaque labels ("A", "spk_0", ...); map them to stable
+// "Speaker N" names in order of first appearance.
+pub fn normalize_speaker_labels(segments: &mut [TranscriptionSegment]) {
+    let mut labels = HashMap::<String, String>::new();
+    for segment in segments.iter_mut() {
+        let Some(raw_label) = segment.speaker.take() else {
+            continue;
+        };
+        let next_index = labels.len();
+        let label = labels
+            .entry(raw_label)
+            .or_insert_with(|| speaker_label(next_index))
+            .clone();
+        segment.speaker = Some(label);
+    }
+}
+
+// Lightweight local diarization: each segment gets a small voice embedding
+// (pitch, pitch spread, zero-crossing rate, high-frequency energy) and segments
+// are greedily clustered against running speaker centroids.
+pub fn assign_speakers(
+    samples: &[i16],
+    sample_rate_hz: u32,
+    segments: &mut [TranscriptionSegment],
+    config: &DiarizationConfig,
+) {
+    assign_speakers_with(sample_rate_hz, segments, config, |start, end| {
+        let start = start.min(samples.len());
+        Cow::Borrowed(&samples[start..end.clamp(start, samples.len())])
+    });
+}
+
+// For audio that is not in memory: `read_samples` is asked for each segment's
+// sample range in turn and may return fewer samples near the end.
+pub fn assign_speakers_with<'a>(
+    sample_rate_hz: u32,
+    segments: &mut [TranscriptionSegment],
+    config: &DiarizationConfig,
+    mut read_samples: impl FnMut(usize, usize) -> Cow<'a, [i16]>,
+) {
+    if segments.is_empty() || sample_rate_hz == 0 {
+        return;
+    }
+
+    let mut centroids: Vec<(VoiceEmbedding, usize)> = Vec::new();
+    let mut previous_speaker = 0;
+    for segment in segments.iter_mut() {
+        let start = seconds_to_sample(segment.start_secs, sample_rate_hz);
+        let end = seconds_to_sample(segment.end_secs, sample_rate_hz).max(start);
+        let speaker = match compute_embedding(&read_samples(start, end), sample_rate_hz) {
+            Some(embedding) => {
+                let nearest = centroids
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (centroid, _))| (index, centroid.distance(&embedding)))
+                    .min_by(|left, right| left.1.total_cmp(&right.1));
+                match nearest {
+                    Some((index, distance))
+                        if distance <= config.distance_threshold
+                            || centroids.len() >= config.max_speakers.max(1) =>
+                    {
+                        let (centroid, count) = &mut centroids[index];
+                        centroid.merge(&embedding, *count);
+                        *count += 1;
+                        index
+                    }
+                    _ => {
+                        centroids.push((embedding, 1));
+                        centroids.len() - 1
+                    }
+                }
+            }
+            // Silent or unvoiced segments stay with whoever spoke last.
+            None => previous_speaker,
+        };
+
+        previous_speaker = speaker;
+        segment.speaker = Some(speaker_label(speaker));
+    }
+
+    info!(
+        segment_count = segments.len(),
+        speaker_count = centroids.len().max(1),
+        "assigned local speaker labels"
+    );
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct VoiceEmbedding([f32; 4]);
+
+impl VoiceEmbedding {
+    fn distance(&self, other: &Self) -> f32 {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(left, right)| (left - right).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    fn merge(&mut self, other: &Self, existing_count: usize) {
+        let weight = existing_count as f32;
+        for (value, incoming) in self.0.iter_mut().zip(other.0.iter()) {
+            *value = (*value * weight + incoming) / (weight + 1.0);
+        }
+    }
+}
+
+fn compute_embedding(samples: &[i16], sample_rate_hz: u32) -> Option<VoiceEmbedding> {
+    let decimation = (sample_rate_hz / ANALYSIS_SAMPLE_RATE_HZ).max(1) as usize;
+    let analysis_rate_hz = sample_rate_hz as f32 / decimation as f32;
+    let decimated = samples
+        .iter()
+        .step_by(decimation)
+        .map(|sample| *sample as f32)
+        .collect::<Vec<_>>();
+    let frame_len = ((analysis_rate_hz * FRAME_MS as f32) / 1000.0) as usize;
+    if frame_len < 8 || decimated.len() < frame_len {
+        return None;
+    }
+
+    let frame_count = decimated.len() / frame_len;
+    let frame_step = frame_count.div_ceil(MAX_FRAMES_PER_SEGMENT).max(1);
+    let min_lag = (analysis_rate_hz / MAX_PITCH_HZ).floor().max(1.0) as usize;
+    let max_lag = ((analysis_rate_hz / MIN_PITCH_HZ).ceil() as usize).min(frame_len - 1);
+
+    let mut pitches = Vec::new();
+    let mut zero_crossing_total = 0.0;
+    let mut high_frequency_total = 0.0;
+    let mut voiced_frames = 0usize;
+    for frame_index in (0..frame_count).step_by(frame_step) {
+        let frame = &decimated[frame_index * frame_len..(frame_index + 1) * frame_len];
+        let energy = frame.iter().map(|value| value * value).sum::<f32>();
+        let rms = (energy / frame.len() as f32).sqrt();
+        if rms < SILENCE_RMS_THRESHOLD {
+            continue;
+        }
+
+        voiced_frames += 1;
+        let crossings = frame
+            .windows(2)
+            .filter(|pair| (pair[0] >= 0.0) != (pair[1] >= 0.0))
+            .count();
+        zero_crossing_total += crossings as f32 / frame.len() as f32;
+        let difference_energy = frame
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).powi(2))
+            .sum::<f32>();
+        high_frequency_total += (difference_energy / (4.0 * energy)).min(1.0);
+
+        if let Some(pitch_hz) = estimate_pitch_hz(frame, energy, min_lag, max_lag, analysis_rate_hz)
+        {
+            pitches.push(pitch_hz.log2());
+        }
+    }
+
+    if voiced_frames == 0 || pitches.is_empty() {
+        return None;
+    }
+
+    let pitch_mean = pitches.iter().sum::<f32>() / pitches.len() as f32;
+    let pitch_spread = (pitches
+        .iter()
+        .map(|pitch| (pitch - pitch_mean).powi(2))
+        .sum::<f32>()
+        / pitches.len() as f32)
+        .sqrt();
+
+    Some(VoiceEmbedding([
+        pitch_mean / PITCH_SCALE_OCTAVES,
+        pitch_spread / PITCH_SCALE_OCTAVES,
+        zero_crossing_total / voiced_frames as f32 / ZERO_CROSSING_SCALE,
+        high_frequency_total / voiced_frames as f32 / HIGH_FREQUENCY_SCALE,
+    ]))
+}
+
+fn estimate_pitch_hz(
+    frame: &[f32],
+    energy: f32,
+    min_lag: usize,
+    max_lag: usize,
+    sample_rate_hz: f32,
+) -> Option<f32> {
+    if energy <= 0.0 || min_lag >= max_lag {
+        return None;
+    }
+
+    let (best_lag, best_correlation) = (min_lag..=max_lag)
+        .map(|lag| {
+            let correlation = frame
+                .iter()
+                .zip(frame[lag..].iter())
+                .map(|(left, right)| left * right)
+                .sum::<f32>();
+            (lag, correlation / energy)
+        })
+        .max_by(|left, right| left.1.total_cmp(&right.1))?;
+
+    (best_correlation >= MIN_PITCH_CORRELATION).then(|| sample_rate_hz / best_lag as f32)
+}
+
+fn seconds_to_sample(seconds: f64, sample_rate_hz: u32) -> usize {
+    (seconds.max(0.0) * f64::from(sample_rate_hz)).round() as usize
+}
+
+fn read_u64_env(name: &str) -> Option<u64> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .and_then(|value| value.parse::<u64>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assign_speakers, normalize_speaker_labels, DiarizationConfig};
+    use crate::transcription::TranscriptionSegment;
+
+    const SAMPLE_RATE_HZ: u32 = 16_000;
+
+    fn tone(frequency_hz: f32, seconds: f32) -> Vec<i16> {
+        let sample_count = (SAMPLE_RATE_HZ as f32 * seconds) as usize;
+        (0..sample_count)
+            .map(|index| {
+                let phase = 2.0 * std::f32::consts::PI * frequency_hz * index as f32
+                    / SAMPLE_RATE_HZ as f32;
+                (phase.sin() * 8_000.0) as i16
+            })
+            .collect()
+    }
+
+    fn segment(start_secs: f64, end_secs: f64, speaker: Option<&str>) -> TranscriptionSegment {
+        TranscriptionSegment {
+            start_secs,
+            end_secs,
+            text: "words".to_string(),
+            speaker: speaker.map(str::to_string),
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn normalizes_provider_labels_in_order_of_appearance() {
+        let mut segments = vec![
+            segment(0.0, 1.0, Some("B")),
+            segment(1.0, 2.0, Some("A")),
+            segment(2.0, 3.0, None),
+            segment(3.0, 4.0, Some("B")),
+        ];
+
+        normalize_speaker_labels(&mut segments);
+
+        let labels = segments
+            .iter()
+            .map(|segment| segment.speaker.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                Some("Speaker 1"),
+                Some("Speaker 2"),
+                None,
+                Some("Speaker 1")
+            ]
+        );
+    }
+
+    #[test]
+    fn clusters_segments_with_distinct_voices() {
+        let mut samples = Vec::new();
+        samples.extend(tone(120.0, 1.0));
+        samples.extend(tone(260.0, 1.0));
+        samples.extend(tone(120.0, 1.0));
+        samples.extend(vec![0; SAMPLE_RATE_HZ as usize]);
+        let mut segments = vec![
+            segment(0.0, 1.0, None),
+            segment(1.0, 2.0, None),
+            segment(2.0, 3.0, None),
+            segment(3.0, 4.0, None),
+        ];
+
+        assign_speakers(
+            &samples,
+            SAMPLE_RATE_HZ,
+            &mut segments,
+            &DiarizationConfig::default(),
+        );
+
+        let labels = segments
+            .iter()
+            .map(|segment| segment.speaker.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                Some("Speaker 1"),
+                Some("Speaker 2"),
+                Some("Speaker 1"),
+                Some("Speaker 1")
+            ]
+        );
+    }
+}
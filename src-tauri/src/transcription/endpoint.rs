@@ -0,0 +1,163 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Url,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Points the OpenAI providers at any server that speaks the same API
+// (LocalAI, vLLM, faster-whisper-server). Unset fields keep the built-in or
+// environment defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ProviderConfig {
+    // e.g. `http://localhost:8000/v1`; the REST and realtime paths are
+    // appended to it.
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+    pub realtime_model: Option<String>,
+    pub extra_headers: BTreeMap<String, String>,
+}
+
+impl ProviderConfig {
+    pub fn normalized(self) -> Result<Self, String> {
+        let base_url = normalize_optional(self.base_url)
+            .map(|base_url| {
+                let url = Url::parse(&base_url)
+                    .map_err(|error| format!("Invalid provider base URL `{base_url}`: {error}"))?;
+                if !matches!(url.scheme(), "http" | "https") {
+                    return Err(format!(
+                        "Provider base URL `{base_url}` must start with http:// or https://"
+                    ));
+                }
+                Ok(base_url.trim_end_matches('/').to_string())
+            })
+            .transpose()?;
+        let extra_headers = self
+            .extra_headers
+            .into_iter()
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .filter(|(name, _)| !name.is_empty())
+            .collect::<BTreeMap<_, _>>();
+        header_map(&extra_headers)?;
+
+        Ok(Self {
+            base_url,
+            model: normalize_optional(self.model),
+            realtime_model: normalize_optional(self.realtime_model),
+            extra_headers,
+        })
+    }
+
+    pub fn transcription_endpoint(&self) -> Option<String> {
+        self.base_url
+            .as_ref()
+            .map(|base_url| format!("{base_url}/audio/transcriptions"))
+    }
+
+    pub fn realtime_endpoint(&self) -> Option<String> {
+        let base_url = self.base_url.as_deref()?;
+        let websocket_url = match base_url.split_once("://") {
+            Some(("https", rest)) => format!("wss://{rest}"),
+            Some(("http", rest)) => format!("ws://{rest}"),
+            _ => return None,
+        };
+        Some(format!("{websocket_url}/realtime"))
+    }
+
+    pub fn header_map(&self) -> Result<HeaderMap, String> {
+        header_map(&self.extra_headers)
+    }
+}
+
+// Held by the providers and swapped when settings change, so edits apply to
+// the next request without rebuilding the providers.
+#[derive(Debug, Clone, Default)]
+pub struct SharedProviderConfig(Arc<RwLock<ProviderConfig>>);
+
+impl SharedProviderConfig {
+    pub fn current(&self) -> ProviderConfig {
+        self.0
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set(&self, config: ProviderConfig) {
+        if let Ok(mut current) = self.0.write() {
+            *current = config;
+        }
+    }
+}
+
+fn header_map(headers: &BTreeMap<String, String>) -> Result<HeaderMap, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| format!("Invalid provider header name `{name}`"))?;
+            let header_value = HeaderValue::from_str(value)
+                .map_err(|_| format!("Invalid value for provider header `{name}`"))?;
+            Ok((header_name, header_value))
+        })
+        .collect()
+}
+
+fn normalize_optional(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::ProviderConfig;
+
+    #[test]
+    fn normalizes_and_derives_endpoints_from_base_url() {
+        let config = ProviderConfig {
+            base_url: Some(" http://localhost:8000/v1/ ".to_string()),
+            model: Some("  ".to_string()),
+            realtime_model: None,
+            extra_headers: BTreeMap::from([(" X-Api-Version ".to_string(), "2".to_string())]),
+        }
+        .normalized()
+        .expect("config should be valid");
+
+        assert_eq!(config.base_url.as_deref(), Some("http://localhost:8000/v1"));
+        assert_eq!(config.model, None);
+        assert_eq!(
+            config.transcription_endpoint().as_deref(),
+            Some("http://localhost:8000/v1/audio/transcriptions")
+        );
+        assert_eq!(
+            config.realtime_endpoint().as_deref(),
+            Some("ws://localhost:8000/v1/realtime")
+        );
+        assert!(config
+            .header_map()
+            .expect("headers")
+            .contains_key("x-api-version"));
+
+        assert_eq!(ProviderConfig::default().transcription_endpoint(), None);
+        assert!(ProviderConfig {
+            base_url: Some("ftp://example.com".to_string()),
+            ..ProviderConfig::default()
+        }
+        .normalized()
+        .is_err());
+        assert!(ProviderConfig {
+            extra_headers: BTreeMap::from([("Bad Header".to_string(), "x".to_string())]),
+            ..ProviderConfig::default()
+        }
+        .normalized()
+        .is_err());
+    }
+}
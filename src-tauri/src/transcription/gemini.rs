@@ -0,0 +1,601 @@
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{path::PathBuf, time::Duration};
+use tracing::{debug, error, info};
+
+#[cfg(not(test))]
+use crate::api_key_store::ApiKeyStore;
+use crate::{audio_encoding::AudioEncoding, settings_store::GEMINI_TRANSCRIPTION_PROVIDER};
+
+use super::{
+    endpoint::SharedProviderConfig,
+    models::{ModelListing, TranscriptionModel, MODEL_LIST_TIMEOUT},
+    normalize_transcript_text,
+    retry::{
+        classify_status, classify_transport_error, parse_retry_after, report_retry,
+        seed_from_clock, RetryPolicy,
+    },
+    TranscriptionError, TranscriptionOptions, TranscriptionProvider, TranscriptionResult,
+};
+
+const DEFAULT_GEMINI_API_BASE: &str = "https://generativelanguage.googleapis.com/v1beta";
+const DEFAULT_GEMINI_MODEL: &str = "gemini-2.0-flash";
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 180;
+const GEMINI_API_KEY_HEADER: &str = "x-goog-api-key";
+// Large enough that the whole catalog comes back in one page.
+const MODEL_LIST_PAGE_SIZE: u32 = 1000;
+const GENERATE_CONTENT_METHOD: &str = "generateContent";
+const TRANSCRIBE_INSTRUCTION: &str = "Transcribe this audio verbatim. Reply with only the transcript, without commentary, labels or quotation marks. If there is no speech, reply with nothing.";
+
+#[derive(Debug, Clone)]
+pub struct GeminiTranscriptionConfig {
+    pub api_key: Option<String>,
+    pub api_key_store_app_data_dir: Option<PathBuf>,
+    pub api_base: String,
+    pub model: String,
+    pub request_timeout_secs: u64,
+    pub retry_policy: RetryPolicy,
+    // Only the model is read; Gemini has no compatible servers to point at.
+    pub overrides: SharedProviderConfig,
+}
+
+impl Default for GeminiTranscriptionConfig {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            api_key_store_app_data_dir: None,
+            api_base: DEFAULT_GEMINI_API_BASE.to_string(),
+            model: DEFAULT_GEMINI_MODEL.to_string(),
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+            retry_policy: RetryPolicy::default(),
+            overrides: SharedProviderConfig::default(),
+        }
+    }
+}
+
+impl GeminiTranscriptionConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Some(model) = read_non_empty_env("GEMINI_TRANSCRIPTION_MODEL") {
+            config.model = model;
+        }
+
+        if let Some(api_base) = read_non_empty_env("GEMINI_API_BASE") {
+            config.api_base = api_base.trim_end_matches('/').to_string();
+        }
+
+        if let Some(timeout_secs) = read_u64_env("GEMINI_TRANSCRIPTION_TIMEOUT_SECS") {
+            config.request_timeout_secs = timeout_secs.max(1);
+        }
+
+        config.retry_policy = RetryPolicy::from_env("GEMINI_TRANSCRIPTION");
+
+        debug!(
+            api_base = %config.api_base,
+            model = %config.model,
+            request_timeout_secs = config.request_timeout_secs,
+            max_retries = config.retry_policy.max_retries,
+            "loaded Gemini transcription config"
+        );
+        config
+    }
+
+    fn endpoint(&self) -> String {
+        let model = self
+            .overrides
+            .current()
+            .model
+            .unwrap_or_else(|| self.model.clone());
+        format!("{}/models/{model}:generateContent", self.api_base)
+    }
+}
+
+// Gemini has no dedicated speech-to-text endpoint; the audio goes inline to
+// `generateContent` with an instruction to transcribe it.
+#[derive(Debug, Clone)]
+pub struct GeminiTranscriptionProvider {
+    config: GeminiTranscriptionConfig,
+    jitter_seed: u64,
+}
+
+impl GeminiTranscriptionProvider {
+    pub fn new(config: GeminiTranscriptionConfig) -> Self {
+        info!(
+            api_base = %config.api_base,
+            model = %config.model,
+            request_timeout_secs = config.request_timeout_secs,
+            "Gemini transcription provider initialized"
+        );
+        Self {
+            config,
+            jitter_seed: seed_from_clock(),
+        }
+    }
+
+    fn api_key(&self) -> Result<String, TranscriptionError> {
+        if let Some(explicit_key) = normalize_optional_string(self.config.api_key.clone()) {
+            return Ok(explicit_key);
+        }
+
+        #[cfg(not(test))]
+        {
+            if let Some(app_data_dir) = self.config.api_key_store_app_data_dir.clone() {
+                match ApiKeyStore::new(app_data_dir).get_api_key(self.name()) {
+                    Ok(Some(stored_key)) => return Ok(stored_key),
+                    Ok(None) => {}
+                    Err(error) => {
+                        return Err(TranscriptionError::Provider(format!(
+                            "Unable to read API key from local API key store: {error}",
+                        )));
+                    }
+                }
+            }
+        }
+
+        read_non_empty_env("GEMINI_API_KEY").ok_or(TranscriptionError::MissingApiKey)
+    }
+
+    fn build_body(&self, audio_data: &[u8], options: &TranscriptionOptions) -> Value {
+        let mut instruction = TRANSCRIBE_INSTRUCTION.to_string();
+        if let Some(language) = normalize_optional_string(options.language.clone()) {
+            instruction.push_str(&format!(" The speaker is using language `{language}`."));
+        }
+        // The vocabulary prompt and context hint read as spelling guidance,
+        // the same way the OpenAI prompt field treats them.
+        for context in [&options.prompt, &options.context_hint] {
+            if let Some(context) = normalize_optional_string(context.clone()) {
+                instruction.push_str(&format!(" Context for spelling: {context}"));
+            }
+        }
+
+        json!({
+            "contents": [{
+                "role": "user",
+                "parts": [
+                    { "text": instruction },
+                    {
+                        "inline_data": {
+                            "mime_type": AudioEncoding::detect(audio_data).mime_type(),
+                            "data": BASE64_STANDARD.encode(audio_data),
+                        }
+                    }
+                ]
+            }],
+            "generationConfig": {
+                "temperature": options.decoding.temperature().unwrap_or(0.0)
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for GeminiTranscriptionProvider {
+    fn name(&self) -> &'static str {
+        GEMINI_TRANSCRIPTION_PROVIDER
+    }
+
+    async fn transcribe(
+        &self,
+        audio_data: Vec<u8>,
+        options: TranscriptionOptions,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let api_key = self.api_key()?;
+        let body = self.build_body(&audio_data, &options);
+        let endpoint = self.config.endpoint();
+        let mut attempt_index = 0;
+
+        info!(
+            endpoint = %endpoint,
+            audio_bytes = audio_data.len(),
+            language = ?options.language,
+            "starting Gemini transcription request"
+        );
+        let response = loop {
+            debug!(
+                attempt = attempt_index + 1,
+                "sending Gemini transcription request"
+            );
+            let response = crate::http_client::shared_client()
+                .post(&endpoint)
+                .timeout(Duration::from_secs(self.config.request_timeout_secs.max(1)))
+                .header(GEMINI_API_KEY_HEADER, &api_key)
+                .json(&body)
+                .send()
+                .await;
+
+            let (error, retry_class, retry_after) = match response {
+                Ok(response) if response.status().is_success() => break response,
+                Ok(response) => {
+                    let retry_class = classify_status(response.status());
+                    let retry_after = parse_retry_after(response.headers());
+                    (map_http_error(response).await, retry_class, retry_after)
+                }
+                Err(error) => {
+                    let retry_class = classify_transport_error(&error);
+                    (map_transport_error(error), retry_class, None)
+                }
+            };
+
+            if let Some(class) = retry_class
+                .filter(|class| self.config.retry_policy.should_retry(*class, attempt_index))
+            {
+                let delay =
+                    self.config
+                        .retry_policy
+                        .delay(attempt_index, retry_after, self.jitter_seed);
+                report_retry(
+                    options.on_retry.as_ref(),
+                    self.name(),
+                    &self.config.retry_policy,
+                    attempt_index,
+                    class,
+                    delay,
+                    &error,
+                );
+                tokio::time::sleep(delay).await;
+                attempt_index += 1;
+                continue;
+            }
+
+            error!(
+                attempt = attempt_index + 1,
+                error = %error,
+                "Gemini transcription request failed without retry"
+            );
+            return Err(error);
+        };
+
+        let payload = response
+            .json::<GeminiGenerateResponse>()
+            .await
+            .map_err(|error| {
+                TranscriptionError::InvalidResponse(format!(
+                    "Unable to parse Gemini transcription response: {error}"
+                ))
+            })?;
+        let text = payload.text().ok_or_else(|| {
+            TranscriptionError::InvalidResponse(
+                "Gemini response did not contain a transcript".to_string(),
+            )
+        })?;
+
+        let normalized = normalize_transcript_text(&text);
+        if let Some(callback) = options.on_delta {
+            callback(normalized.clone());
+        }
+
+        Ok(TranscriptionResult {
+            text: normalized,
+            language: normalize_optional_string(options.language),
+            language_confidence: None,
+            duration_secs: None,
+            confidence: None,
+            segments: Vec::new(),
+            words: Vec::new(),
+        })
+    }
+
+    async fn list_models(&self, api_key: Option<&str>) -> Result<ModelListing, TranscriptionError> {
+        let api_key = match normalize_optional_string(api_key.map(str::to_string)) {
+            Some(api_key) => api_key,
+            None => self.api_key()?,
+        };
+        let endpoint = format!("{}/models", self.config.api_base);
+        debug!(endpoint = %endpoint, "listing Gemini models");
+
+        let response = crate::http_client::shared_client()
+            .get(&endpoint)
+            .query(&[("pageSize", MODEL_LIST_PAGE_SIZE)])
+            .timeout(MODEL_LIST_TIMEOUT)
+            .header(GEMINI_API_KEY_HEADER, &api_key)
+            .send()
+            .await
+            .map_err(map_transport_error)?;
+        if !response.status().is_success() {
+            return Err(map_http_error(response).await);
+        }
+        let payload = response.json::<GeminiModelList>().await.map_err(|error| {
+            TranscriptionError::InvalidResponse(format!(
+                "Unable to parse Gemini model list: {error}"
+            ))
+        })?;
+
+        // There is no streaming path for Gemini, so none are marked realtime.
+        let models = TranscriptionModel::sorted(
+            payload
+                .models
+                .into_iter()
+                .filter(GeminiModel::transcribes_audio)
+                .filter_map(|model| model.name.strip_prefix("models/").map(str::to_string))
+                .map(|id| TranscriptionModel {
+                    id,
+                    realtime: false,
+                })
+                .collect(),
+        );
+        info!(model_count = models.len(), "listed Gemini speech models");
+        Ok(ModelListing {
+            organization: None,
+            models,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiModelList {
+    #[serde(default)]
+    models: Vec<GeminiModel>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiModel {
+    name: String,
+    #[serde(default)]
+    supported_generation_methods: Vec<String>,
+}
+
+impl GeminiModel {
+    // Transcription goes through `generateContent`, which every Gemini model
+    // takes audio on; embedding, image and speech output models are skipped.
+    fn transcribes_audio(&self) -> bool {
+        let name = self.name.to_ascii_lowercase();
+        name.starts_with("models/gemini")
+            && !["embedding", "image", "tts"]
+                .iter()
+                .any(|marker| name.contains(marker))
+            && self
+                .supported_generation_methods
+                .iter()
+                .any(|method| method == GENERATE_CONTENT_METHOD)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiGenerateResponse {
+    #[serde(default)]
+    candidates: Vec<GeminiCandidate>,
+}
+
+impl GeminiGenerateResponse {
+    // A blocked or empty candidate comes back without content; silence comes
+    // back as a candidate with no text parts, which is a valid empty result.
+    fn text(self) -> Option<String> {
+        let content = self.candidates.into_iter().next()?.content?;
+        Some(
+            content
+                .parts
+                .into_iter()
+                .filter_map(|part| part.text)
+                .collect::<Vec<_>>()
+                .join(""),
+        )
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiCandidate {
+    #[serde(default)]
+    content: Option<GeminiContent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiContent {
+    #[serde(default)]
+    parts: Vec<GeminiPart>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiPart {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+fn map_transport_error(error: reqwest::Error) -> TranscriptionError {
+    if error.is_timeout() || error.is_connect() {
+        TranscriptionError::Network(error.to_string())
+    } else {
+        TranscriptionError::Provider(error.to_string())
+    }
+}
+
+async fn map_http_error(response: reqwest::Response) -> TranscriptionError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let error = serde_json::from_str::<Value>(&body)
+        .ok()
+        .and_then(|value| value.get("error").cloned());
+    let message = error
+        .as_ref()
+        .and_then(|error| error.get("message")?.as_str())
+        .and_then(|message| normalize_optional_string(Some(message.to_string())))
+        .unwrap_or_else(|| format!("Gemini request failed with status {}", status.as_u16()));
+    // Google reports a bad key as 400 INVALID_ARGUMENT rather than 401.
+    let invalid_key = error
+        .as_ref()
+        .map(|error| error.to_string().contains("API_KEY_INVALID"))
+        .unwrap_or(false);
+
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            TranscriptionError::Authentication(message)
+        }
+        StatusCode::BAD_REQUEST if invalid_key => TranscriptionError::Authentication(message),
+        StatusCode::TOO_MANY_REQUESTS => TranscriptionError::RateLimited(message),
+        StatusCode::REQUEST_TIMEOUT => TranscriptionError::Network(message),
+        _ if status.is_server_error() => TranscriptionError::Network(message),
+        _ => TranscriptionError::Provider(message),
+    }
+}
+
+fn normalize_optional_string(value: Option<String>) -> Option<String> {
+    value.and_then(|content| {
+        let trimmed = content.trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(trimmed.to_string())
+        }
+    })
+}
+
+fn read_non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name).ok().and_then(|value| {
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(trimmed.to_string())
+        }
+    })
+}
+
+fn read_u64_env(name: &str) -> Option<u64> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .and_then(|value| value.parse::<u64>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::endpoint::ProviderConfig;
+    use mockito::{Matcher, Server};
+
+    fn provider_for_test(server: &Server) -> GeminiTranscriptionProvider {
+        GeminiTranscriptionProvider::new(GeminiTranscriptionConfig {
+            api_key: Some("gm-test".to_string()),
+            api_key_store_app_data_dir: None,
+            api_base: format!("{}/v1beta", server.url()),
+            model: "gemini-test".to_string(),
+            request_timeout_secs: 5,
+            retry_policy: RetryPolicy {
+                max_retries: 1,
+                initial_backoff_ms: 5,
+                max_backoff_ms: 10,
+                ..RetryPolicy::default()
+            },
+            overrides: SharedProviderConfig::default(),
+        })
+    }
+
+    #[tokio::test]
+    async fn lists_gemini_models_that_can_transcribe() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/v1beta/models")
+            .match_query(Matcher::UrlEncoded("pageSize".to_string(), "1000".to_string()))
+            .match_header("x-goog-api-key", "gm-draft")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"models":[
+                    {"name":"models/gemini-2.0-flash","supportedGenerationMethods":["generateContent","countTokens"]},
+                    {"name":"models/text-embedding-004","supportedGenerationMethods":["embedContent"]},
+                    {"name":"models/gemini-2.5-flash-preview-tts","supportedGenerationMethods":["generateContent"]},
+                    {"name":"models/gemini-1.5-pro","supportedGenerationMethods":["generateContent"]}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let listing = provider_for_test(&server)
+            .list_models(Some("gm-draft"))
+            .await
+            .expect("model list should succeed");
+
+        mock.assert_async().await;
+        assert_eq!(listing.organization, None);
+        assert_eq!(
+            listing
+                .models
+                .iter()
+                .map(|model| model.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["gemini-1.5-pro", "gemini-2.0-flash"]
+        );
+        assert!(listing.models.iter().all(|model| !model.realtime));
+    }
+
+    #[tokio::test]
+    async fn sends_inline_audio_and_maps_invalid_key_errors() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1beta/models/gemini-test:generateContent")
+            .match_header("x-goog-api-key", "gm-test")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(r#""mime_type":"audio/wav""#.to_string()),
+                Matcher::Regex(r#""data":"UklGRg==""#.to_string()),
+                Matcher::Regex("language `de`".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"candidates":[{"content":{"parts":[{"text":" Hallo "},{"text":"Welt "}]}}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let provider = provider_for_test(&server);
+        let result = provider
+            .transcribe(
+                b"RIFF".to_vec(),
+                TranscriptionOptions {
+                    language: Some("de".to_string()),
+                    ..TranscriptionOptions::default()
+                },
+            )
+            .await
+            .expect("transcription should succeed");
+        mock.assert_async().await;
+        assert_eq!(result.text, "Hallo Welt");
+        assert_eq!(result.language.as_deref(), Some("de"));
+
+        server
+            .mock("POST", "/v1beta/models/gemini-test:generateContent")
+            .with_status(400)
+            .with_body(
+                r#"{"error":{"code":400,"message":"API key not valid.","status":"INVALID_ARGUMENT","details":[{"reason":"API_KEY_INVALID"}]}}"#,
+            )
+            .create_async()
+            .await;
+        let error = provider
+            .transcribe(b"RIFF".to_vec(), TranscriptionOptions::default())
+            .await
+            .expect_err("invalid key should fail");
+        assert_eq!(
+            error,
+            TranscriptionError::Authentication("API key not valid.".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn picked_model_replaces_the_configured_one() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1beta/models/gemini-2.5-flash:generateContent")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"candidates":[{"content":{"parts":[{"text":"Hello"}]}}]}"#)
+            .create_async()
+            .await;
+
+        let provider = provider_for_test(&server);
+        provider.config.overrides.set(ProviderConfig {
+            model: Some("gemini-2.5-flash".to_string()),
+            ..ProviderConfig::default()
+        });
+        let result = provider
+            .transcribe(b"RIFF".to_vec(), TranscriptionOptions::default())
+            .await
+            .expect("transcription should succeed");
+        mock.assert_async().await;
+        assert_eq!(result.text, "Hello");
+    }
+}
@@ -0,0 +1,307 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::translation::is_language;
+use crate::settings_store::{LanguageProfile, VoiceSettings};
+
+// Below this the detected language is worth a second look.
+pub const LOW_LANGUAGE_CONFIDENCE: f32 = 0.6;
+// Too few recognisable words to tell Latin-script languages apart.
+const MIN_STOPWORD_MATCHES: usize = 2;
+
+// Short, frequent words that rarely appear in the other listed languages.
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "you", "that", "it", "of", "to", "was", "with", "this", "have",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "und", "ist", "der", "die", "das", "nicht", "ich", "mit", "ein", "eine", "auch", "wir",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "les", "est", "et", "je", "pas", "une", "avec", "nous", "vous", "c'est", "mais",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "los", "las", "es", "y", "que", "una", "con", "pero", "por", "está", "muy",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "che", "è", "gli", "della", "non", "sono", "una", "con", "per", "anche", "questo",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "é", "não", "uma", "com", "para", "você", "muito", "isso", "também", "mas",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "niet", "ik", "met", "dat", "ook", "wij", "maar",
+        ],
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageUncertainEvent {
+    pub session_id: Option<u64>,
+    pub text: String,
+    pub detected_language: Option<String>,
+    pub confidence: Option<f32>,
+    // The user's other preferred languages, in order, to offer as chips.
+    pub alternatives: Vec<String>,
+}
+
+// A single preferred language is pinned; with several the provider has to
+// detect which one was spoken.
+pub fn language_hint(preferred_languages: &[String]) -> Option<String> {
+    match preferred_languages {
+        [language] => Some(language.clone()),
+        _ => None,
+    }
+}
+
+// For providers that do not report a language. Non-Latin scripts mostly
+// give the language away; Latin-script text is matched on stopwords.
+pub fn detect_language(text: &str) -> Option<(String, f32)> {
+    let mut letters = 0_usize;
+    let mut scripts = std::collections::HashMap::<&str, usize>::new();
+    for character in text.chars().filter(|character| character.is_alphabetic()) {
+        letters += 1;
+        if let Some(language) = script_language(character) {
+            *scripts.entry(language).or_default() += 1;
+        }
+    }
+    if letters == 0 {
+        return None;
+    }
+    if let Some((language, count)) = scripts.into_iter().max_by_key(|(_, count)| *count) {
+        let share = count as f32 / letters as f32;
+        if share > 0.5 {
+            return Some((language.to_string(), share));
+        }
+    }
+
+    let words = text
+        .split(|character: char| !(character.is_alphabetic() || character == '\''))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    let mut matches = STOPWORDS
+        .iter()
+        .map(|(language, stopwords)| {
+            let count = words
+                .iter()
+                .filter(|word| stopwords.contains(&word.as_str()))
+                .count();
+            (*language, count)
+        })
+        .collect::<Vec<_>>();
+    let total = matches.iter().map(|(_, count)| count).sum::<usize>();
+    matches.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    let (language, count) = matches.first().copied()?;
+    if count < MIN_STOPWORD_MATCHES {
+        return None;
+    }
+    Some((language.to_string(), count as f32 / total as f32))
+}
+
+fn script_language(character: char) -> Option<&'static str> {
+    match character as u32 {
+        0x3040..=0x30FF => Some("ja"),
+        0x4E00..=0x9FFF => Some("zh"),
+        0xAC00..=0xD7AF | 0x1100..=0x11FF => Some("ko"),
+        0x0400..=0x04FF => Some("ru"),
+        0x0600..=0x06FF => Some("ar"),
+        0x0370..=0x03FF => Some("el"),
+        0x0590..=0x05FF => Some("he"),
+        0x0900..=0x097F => Some("hi"),
+        0x0E00..=0x0E7F => Some("th"),
+        _ => None,
+    }
+}
+
+// Whisper reports full names whose first letters do not always match the
+// ISO code.
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("arabic", "ar"),
+    ("chinese", "zh"),
+    ("dutch", "nl"),
+    ("english", "en"),
+    ("french", "fr"),
+    ("german", "de"),
+    ("greek", "el"),
+    ("hebrew", "he"),
+    ("hindi", "hi"),
+    ("italian", "it"),
+    ("japanese", "ja"),
+    ("korean", "ko"),
+    ("polish", "pl"),
+    ("portuguese", "pt"),
+    ("russian", "ru"),
+    ("spanish", "es"),
+    ("swedish", "sv"),
+    ("thai", "th"),
+    ("turkish", "tr"),
+    ("ukrainian", "uk"),
+];
+
+fn language_code(language: &str) -> &str {
+    let language = language.trim();
+    LANGUAGE_NAMES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(language))
+        .map_or(language, |(_, code)| code)
+}
+
+// Providers answer with codes or names, settings hold whatever the user
+// typed, so either side may be the longer one.
+pub fn is_same_language(left: &str, right: &str) -> bool {
+    let (left, right) = (language_code(left), language_code(right));
+    is_language(Some(left), right) || is_language(Some(right), left)
+}
+
+pub fn language_profile<'a>(
+    settings: &'a VoiceSettings,
+    language: Option<&str>,
+) -> Option<&'a LanguageProfile> {
+    let language = language?;
+    settings
+        .language_profiles
+        .iter()
+        .find(|(profile_language, _)| is_same_language(profile_language, language))
+        .map(|(_, profile)| profile)
+}
+
+// With the language known only that language's words are sent; otherwise
+// every dictionary is, since the dictation may be in any of them.
+pub fn dictionary_hint(settings: &VoiceSettings, language: Option<&str>) -> Option<String> {
+    let profiles = match language {
+        Some(_) => language_profile(settings, language).into_iter().collect(),
+        None => settings.language_profiles.values().collect::<Vec<_>>(),
+    };
+    let mut words = Vec::<&str>::new();
+    for word in profiles
+        .into_iter()
+        .flat_map(|profile| profile.dictionary.iter())
+    {
+        if !words.contains(&word.as_str()) {
+            words.push(word);
+        }
+    }
+    (!words.is_empty()).then(|| format!("Vocabulary: {}.", words.join(", ")))
+}
+
+// The preferred languages to offer instead of the detected one, or `None`
+// when the detection can be trusted. Without at least two preferences the
+// language was pinned or there is nothing to suggest.
+pub fn uncertain_alternatives(
+    detected_language: Option<&str>,
+    confidence: Option<f32>,
+    preferred_languages: &[String],
+) -> Option<Vec<String>> {
+    if preferred_languages.len() < 2 {
+        return None;
+    }
+    let is_preferred = detected_language.is_some_and(|detected| {
+        preferred_languages
+            .iter()
+            .any(|preferred| is_same_language(detected, preferred))
+    });
+    let is_confident = confidence.is_none_or(|confidence| confidence >= LOW_LANGUAGE_CONFIDENCE);
+    if is_preferred && is_confident {
+        return None;
+    }
+    Some(
+        preferred_languages
+            .iter()
+            .filter(|preferred| {
+                !detected_language.is_some_and(|detected| is_same_language(detected, preferred))
+            })
+            .cloned()
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{detect_language, dictionary_hint, language_hint, uncertain_alternatives};
+    use crate::settings_store::{LanguageProfile, VoiceSettings};
+
+    #[test]
+    fn detects_script_and_stopword_languages() {
+        assert_eq!(
+            detect_language("Привет, как дела?").map(|(language, _)| language),
+            Some("ru".to_string())
+        );
+        let (language, confidence) =
+            detect_language("Ich glaube, das ist nicht so wichtig und wir machen weiter")
+                .expect("german should be detected");
+        assert_eq!(language, "de");
+        assert!(confidence > 0.5);
+        assert_eq!(detect_language("Okay"), None);
+        assert_eq!(detect_language("123 ..."), None);
+    }
+
+    #[test]
+    fn only_uncertain_or_unexpected_languages_offer_alternatives() {
+        let preferred = vec!["en".to_string(), "de".to_string(), "fr".to_string()];
+
+        assert_eq!(language_hint(&preferred[..1]), Some("en".to_string()));
+        assert_eq!(language_hint(&preferred), None);
+        assert_eq!(
+            uncertain_alternatives(Some("english"), Some(0.9), &preferred),
+            None
+        );
+        assert_eq!(
+            uncertain_alternatives(Some("german"), Some(0.3), &preferred),
+            Some(vec!["en".to_string(), "fr".to_string()])
+        );
+        assert_eq!(
+            uncertain_alternatives(Some("dutch"), None, &preferred),
+            Some(preferred.clone())
+        );
+        assert_eq!(uncertain_alternatives(None, None, &preferred[..1]), None);
+    }
+
+    #[test]
+    fn dictionaries_follow_the_known_language_or_all_apply() {
+        let profile = |words: &[&str]| LanguageProfile {
+            dictionary: words.iter().map(|word| word.to_string()).collect(),
+            transcription_style: None,
+        };
+        let settings = VoiceSettings {
+            language_profiles: BTreeMap::from([
+                ("de".to_string(), profile(&["Straßenbahn", "Kubernetes"])),
+                ("en".to_string(), profile(&["Kubernetes", "Figma"])),
+            ]),
+            ..VoiceSettings::default()
+        };
+
+        assert_eq!(
+            dictionary_hint(&settings, Some("german")).as_deref(),
+            Some("Vocabulary: Straßenbahn, Kubernetes.")
+        );
+        assert_eq!(
+            dictionary_hint(&settings, None).as_deref(),
+            Some("Vocabulary: Straßenbahn, Kubernetes, Figma.")
+        );
+        assert_eq!(dictionary_hint(&settings, Some("fr")), None);
+    }
+}
@@ -1,16 +1,28 @@
 pub mod chatgpt;
+pub mod chunked;
+pub mod decoding;
+pub mod diarization;
+pub mod endpoint;
+pub mod gemini;
+pub mod language;
+pub mod models;
 pub mod openai;
+pub mod prompt;
 pub mod realtime;
+pub mod retry;
+pub mod translation;
 
-use std::{fmt, sync::Arc};
+use std::{fmt, future::Future, path::Path, sync::Arc};
 
 use async_trait::async_trait;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 pub type TranscriptionDeltaCallback = Arc<dyn Fn(String) + Send + Sync + 'static>;
 
-#[derive(Clone, Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TranscriptionOptions {
     #[serde(default)]
@@ -19,8 +31,14 @@ pub struct TranscriptionOptions {
     pub prompt: Option<String>,
     #[serde(default)]
     pub context_hint: Option<String>,
+    #[serde(default)]
+    pub decoding: decoding::DecodingOptions,
     #[serde(skip, default)]
     pub on_delta: Option<TranscriptionDeltaCallback>,
+    #[serde(skip, default)]
+    pub on_retry: Option<retry::TranscriptionRetryCallback>,
+    #[serde(skip, default)]
+    pub cancellation: Option<CancellationToken>,
 }
 
 impl fmt::Debug for TranscriptionOptions {
@@ -29,7 +47,10 @@ impl fmt::Debug for TranscriptionOptions {
             .field("language", &self.language)
             .field("prompt", &self.prompt)
             .field("context_hint", &self.context_hint)
+            .field("decoding", &self.decoding)
             .field("on_delta", &self.on_delta.is_some())
+            .field("on_retry", &self.on_retry.is_some())
+            .field("cancellation", &self.cancellation.is_some())
             .finish()
     }
 }
@@ -40,10 +61,42 @@ pub struct TranscriptionResult {
     pub text: String,
     #[serde(default)]
     pub language: Option<String>,
+    // How sure the provider's language detection was, from 0 to 1. Unset when
+    // the language was pinned or the provider does not say.
+    #[serde(default)]
+    pub language_confidence: Option<f32>,
     #[serde(default)]
     pub duration_secs: Option<f64>,
     #[serde(default)]
     pub confidence: Option<f32>,
+    #[serde(default)]
+    pub segments: Vec<TranscriptionSegment>,
+    #[serde(default)]
+    pub words: Vec<TranscriptionWord>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionSegment {
+    pub start_secs: f64,
+    pub end_secs: f64,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    // How sure the provider was of this stretch, from 0 to 1; unset when it
+    // does not say.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionWord {
+    pub start_secs: f64,
+    pub end_secs: f64,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -51,9 +104,12 @@ pub enum TranscriptionError {
     MissingApiKey,
     Authentication(String),
     RateLimited(String),
+    // The account is out of credits; waiting will not help.
+    QuotaExceeded(String),
     Network(String),
     InvalidResponse(String),
     Provider(String),
+    Cancelled,
 }
 
 impl fmt::Display for TranscriptionError {
@@ -62,9 +118,11 @@ impl fmt::Display for TranscriptionError {
             Self::MissingApiKey => write!(f, "Missing transcription provider API key"),
             Self::Authentication(message) => write!(f, "Authentication failed: {message}"),
             Self::RateLimited(message) => write!(f, "Rate limited: {message}"),
+            Self::QuotaExceeded(message) => write!(f, "Quota exceeded: {message}"),
             Self::Network(message) => write!(f, "Network error: {message}"),
             Self::InvalidResponse(message) => write!(f, "Invalid provider response: {message}"),
             Self::Provider(message) => write!(f, "Transcription provider error: {message}"),
+            Self::Cancelled => write!(f, "Transcription was cancelled"),
         }
     }
 }
@@ -80,6 +138,31 @@ pub trait TranscriptionProvider: Send + Sync {
         audio_data: Vec<u8>,
         options: TranscriptionOptions,
     ) -> Result<TranscriptionResult, TranscriptionError>;
+
+    // Providers that can upload straight from disk override this so long
+    // recordings never have to be loaded into memory.
+    async fn transcribe_file(
+        &self,
+        path: &Path,
+        options: TranscriptionOptions,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let audio_data = tokio::fs::read(path).await.map_err(|error| {
+            TranscriptionError::Provider(format!("Failed to read recorded audio: {error}"))
+        })?;
+        self.transcribe(audio_data, options).await
+    }
+
+    // The speech models `api_key` can use, or the stored key when it is
+    // `None`; also how keys are validated.
+    async fn list_models(
+        &self,
+        _api_key: Option<&str>,
+    ) -> Result<models::ModelListing, TranscriptionError> {
+        Err(TranscriptionError::Provider(format!(
+            "{} does not list its models",
+            self.name()
+        )))
+    }
 }
 
 #[derive(Clone)]
@@ -121,18 +204,20 @@ impl TranscriptionOrchestrator {
             audio_bytes = audio_data.len(),
             "dispatching transcription request"
         );
-        let mut result = self
-            .active_provider
-            .transcribe(audio_data, options)
-            .await
-            .map_err(|error| {
-                error!(
-                    provider = self.active_provider.name(),
-                    error = %error,
-                    "transcription provider call failed"
-                );
-                error
-            })?;
+        let cancellation = options.cancellation.clone();
+        let mut result = run_cancellable(
+            cancellation.as_ref(),
+            self.active_provider.transcribe(audio_data, options),
+        )
+        .await
+        .map_err(|error| {
+            error!(
+                provider = self.active_provider.name(),
+                error = %error,
+                "transcription provider call failed"
+            );
+            error
+        })?;
         result.text = normalize_transcript_text(&result.text);
         info!(
             provider = self.active_provider.name(),
@@ -142,6 +227,114 @@ impl TranscriptionOrchestrator {
         );
         Ok(result)
     }
+
+    pub async fn transcribe_file(
+        &self,
+        path: &Path,
+        options: TranscriptionOptions,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        debug!(
+            provider = self.active_provider.name(),
+            path = %path.display(),
+            "dispatching file transcription request"
+        );
+        let cancellation = options.cancellation.clone();
+        let mut result = run_cancellable(
+            cancellation.as_ref(),
+            self.active_provider.transcribe_file(path, options),
+        )
+        .await
+        .map_err(|error| {
+            error!(
+                provider = self.active_provider.name(),
+                error = %error,
+                "transcription provider call failed"
+            );
+            error
+        })?;
+        result.text = normalize_transcript_text(&result.text);
+        info!(
+            provider = self.active_provider.name(),
+            transcript_chars = result.text.chars().count(),
+            language = ?result.language,
+            "transcription request completed"
+        );
+        Ok(result)
+    }
+
+    pub async fn list_models(
+        &self,
+        api_key: Option<&str>,
+    ) -> Result<models::ModelListing, TranscriptionError> {
+        debug!(
+            provider = self.active_provider.name(),
+            "listing provider models"
+        );
+        self.active_provider.list_models(api_key).await
+    }
+
+    pub async fn transcribe_chunked(
+        &self,
+        audio_data: &[u8],
+        options: TranscriptionOptions,
+        config: &chunked::ChunkedTranscriptionConfig,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let cancellation = options.cancellation.clone();
+        run_cancellable(
+            cancellation.as_ref(),
+            chunked::transcribe_chunked(self.active_provider.as_ref(), audio_data, options, config),
+        )
+        .await
+        .map_err(|error| {
+            error!(
+                provider = self.active_provider.name(),
+                error = %error,
+                "chunked transcription failed"
+            );
+            error
+        })
+    }
+
+    pub async fn transcribe_chunked_file(
+        &self,
+        path: &Path,
+        options: TranscriptionOptions,
+        config: &chunked::ChunkedTranscriptionConfig,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let cancellation = options.cancellation.clone();
+        run_cancellable(
+            cancellation.as_ref(),
+            chunked::transcribe_chunked_file(self.active_provider.as_ref(), path, options, config),
+        )
+        .await
+        .map_err(|error| {
+            error!(
+                provider = self.active_provider.name(),
+                error = %error,
+                "chunked transcription failed"
+            );
+            error
+        })
+    }
+}
+
+// Dropping the provider future drops its in-flight request, which is what
+// actually stops the upload and the quota it would burn.
+pub(crate) async fn run_cancellable<T>(
+    cancellation: Option<&CancellationToken>,
+    work: impl Future<Output = Result<T, TranscriptionError>>,
+) -> Result<T, TranscriptionError> {
+    let Some(cancellation) = cancellation else {
+        return work.await;
+    };
+    tokio::select! {
+        biased;
+        _ = cancellation.cancelled() => {
+            info!("transcription cancelled; dropping in-flight request");
+            Err(TranscriptionError::Cancelled)
+        }
+        result = work => result,
+    }
 }
 
 pub(crate) fn normalize_transcript_text(raw_text: &str) -> String {
@@ -179,8 +372,11 @@ mod tests {
             Ok(TranscriptionResult {
                 text: self.response_text.clone(),
                 language: Some("en".to_string()),
+                language_confidence: None,
                 duration_secs: Some(1.5),
                 confidence: Some(0.8),
+                segments: Vec::new(),
+                words: Vec::new(),
             })
         }
     }
@@ -236,4 +432,45 @@ mod tests {
             TranscriptionError::Provider("Audio payload is empty".to_string())
         );
     }
+
+    #[tokio::test]
+    async fn cancellation_aborts_pending_provider_work() {
+        let cancellation = CancellationToken::new();
+        let trigger = cancellation.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            trigger.cancel();
+        });
+
+        let error = run_cancellable(
+            Some(&cancellation),
+            std::future::pending::<Result<(), TranscriptionError>>(),
+        )
+        .await
+        .expect_err("pending work should be cancelled");
+        assert_eq!(error, TranscriptionError::Cancelled);
+
+        let provider = Arc::new(StubProvider {
+            captured_audio_len: Mutex::new(None),
+            response_text: "unused".to_string(),
+        });
+        let error = TranscriptionOrchestrator::new(provider.clone())
+            .transcribe(
+                vec![1, 2, 3],
+                TranscriptionOptions {
+                    cancellation: Some(cancellation),
+                    ..TranscriptionOptions::default()
+                },
+            )
+            .await
+            .expect_err("cancelled requests should not reach the provider");
+        assert_eq!(error, TranscriptionError::Cancelled);
+        assert_eq!(
+            *provider
+                .captured_audio_len
+                .lock()
+                .expect("stub provider lock should not be poisoned"),
+            None
+        );
+    }
 }
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::TranscriptionError;
+
+// Listing models is the cheapest authenticated request each provider has, so
+// it doubles as the API key check; it should answer well within this.
+pub const MODEL_LIST_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelListing {
+    // Only OpenAI reports which organization a key belongs to.
+    pub organization: Option<String>,
+    // Models that can transcribe a recording, sorted by id.
+    pub models: Vec<TranscriptionModel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionModel {
+    pub id: String,
+    // Whether realtime sessions accept it as their transcription model, so
+    // dictation can still stream text while recording.
+    pub realtime: bool,
+}
+
+impl TranscriptionModel {
+    pub fn sorted(mut models: Vec<Self>) -> Vec<Self> {
+        models.sort_by(|left, right| left.id.cmp(&right.id));
+        models.dedup_by(|left, right| left.id == right.id);
+        models
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyValidation {
+    pub provider: String,
+    // Unset when the provider could not give an answer, e.g. while offline;
+    // the key is then neither known good nor known bad.
+    pub valid: Option<bool>,
+    pub organization: Option<String>,
+    pub allowed_models: Vec<String>,
+    pub message: Option<String>,
+}
+
+impl ApiKeyValidation {
+    pub fn from_listing(provider: &str, listing: Result<ModelListing, TranscriptionError>) -> Self {
+        let (valid, listing, message) = match listing {
+            Ok(listing) => (Some(true), listing, None),
+            Err(error) => {
+                let rejected = matches!(
+                    error,
+                    TranscriptionError::Authentication(_) | TranscriptionError::MissingApiKey
+                );
+                (
+                    rejected.then_some(false),
+                    ModelListing::default(),
+                    Some(error.to_string()),
+                )
+            }
+        };
+        Self {
+            provider: provider.to_string(),
+            valid,
+            organization: listing.organization,
+            allowed_models: listing.models.into_iter().map(|model| model.id).collect(),
+            message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validation_only_rejects_keys_the_provider_refused() {
+        let accepted = ApiKeyValidation::from_listing(
+            "openai",
+            Ok(ModelListing {
+                organization: Some("org-voice".to_string()),
+                models: vec![TranscriptionModel {
+                    id: "whisper-1".to_string(),
+                    realtime: true,
+                }],
+            }),
+        );
+        assert_eq!(accepted.valid, Some(true));
+        assert_eq!(accepted.organization.as_deref(), Some("org-voice"));
+        assert_eq!(accepted.allowed_models, vec!["whisper-1".to_string()]);
+        assert_eq!(accepted.message, None);
+
+        let rejected = ApiKeyValidation::from_listing(
+            "openai",
+            Err(TranscriptionError::Authentication(
+                "Incorrect API key provided".to_string(),
+            )),
+        );
+        assert_eq!(rejected.valid, Some(false));
+        assert!(rejected.allowed_models.is_empty());
+
+        let offline = ApiKeyValidation::from_listing(
+            "gemini",
+            Err(TranscriptionError::Network("dns error".to_string())),
+        );
+        assert_eq!(offline.valid, None);
+        assert_eq!(offline.message.as_deref(), Some("Network error: dns error"));
+    }
+}
@@ -1,32 +1,41 @@
 use async_trait::async_trait;
 use bytes::Bytes;
-use reqwest::{
-    header::{HeaderMap, RETRY_AFTER},
-    multipart, Client, StatusCode,
-};
+use reqwest::{header::HeaderMap, multipart, StatusCode};
 use serde::Deserialize;
 use std::{
-    path::PathBuf,
-    time::{Duration, SystemTime, UNIX_EPOCH},
+    path::{Path, PathBuf},
+    time::Duration,
 };
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info};
 
 #[cfg(not(test))]
 use crate::api_key_store::ApiKeyStore;
+use crate::audio_encoding::AudioEncoding;
 
 use super::{
-    normalize_transcript_text, TranscriptionDeltaCallback, TranscriptionError,
-    TranscriptionOptions, TranscriptionProvider, TranscriptionResult,
+    chunked::{self, wav_duration_secs, ChunkedTranscriptionConfig, Pcm16WavFile},
+    decoding::{DecodingOptions, RESPONSE_FORMAT_VERBOSE_JSON},
+    diarization::normalize_speaker_labels,
+    endpoint::SharedProviderConfig,
+    models::{ModelListing, TranscriptionModel, MODEL_LIST_TIMEOUT},
+    normalize_transcript_text,
+    retry::{
+        classify_status, classify_transport_error, parse_retry_after, report_retry,
+        seed_from_clock, RetryClass, RetryPolicy,
+    },
+    TranscriptionDeltaCallback, TranscriptionError, TranscriptionOptions, TranscriptionProvider,
+    TranscriptionResult, TranscriptionSegment, TranscriptionWord,
 };
 
 const DEFAULT_OPENAI_ENDPOINT: &str = "https://api.openai.com/v1/audio/transcriptions";
 const DEFAULT_OPENAI_MODEL: &str = "gpt-4o-mini-transcribe";
 const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 180;
-const DEFAULT_MAX_RETRIES: u32 = 3;
-const DEFAULT_INITIAL_BACKOFF_MS: u64 = 500;
-const DEFAULT_MAX_BACKOFF_MS: u64 = 5_000;
+const DEFAULT_SEGMENT_SECS: u32 = 20;
+const SEGMENT_OVERLAP_SECS: u32 = 1;
 const STREAMING_TRANSCRIPT_DELTA_EVENT: &str = "transcript.text.delta";
 const STREAMING_TRANSCRIPT_DONE_EVENT: &str = "transcript.text.done";
+const TRANSCRIPTIONS_PATH: &str = "/audio/transcriptions";
+const OPENAI_ORGANIZATION_HEADER: &str = "openai-organization";
 
 #[derive(Debug, Clone)]
 pub struct OpenAiTranscriptionConfig {
@@ -35,9 +44,14 @@ pub struct OpenAiTranscriptionConfig {
     pub endpoint: String,
     pub model: String,
     pub request_timeout_secs: u64,
-    pub max_retries: u32,
-    pub retry_initial_backoff_ms: u64,
-    pub retry_max_backoff_ms: u64,
+    pub retry_policy: RetryPolicy,
+    // Models without a streaming response are sent in segments of this many
+    // seconds, one after another, so callers still see deltas. Zero sends the
+    // whole recording in one request.
+    pub segment_secs: u32,
+    // Settings-level endpoint, model and headers; these win over the fields
+    // above when set.
+    pub overrides: SharedProviderConfig,
 }
 
 impl Default for OpenAiTranscriptionConfig {
@@ -48,9 +62,9 @@ impl Default for OpenAiTranscriptionConfig {
             endpoint: DEFAULT_OPENAI_ENDPOINT.to_string(),
             model: DEFAULT_OPENAI_MODEL.to_string(),
             request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
-            max_retries: DEFAULT_MAX_RETRIES,
-            retry_initial_backoff_ms: DEFAULT_INITIAL_BACKOFF_MS,
-            retry_max_backoff_ms: DEFAULT_MAX_BACKOFF_MS,
+            retry_policy: RetryPolicy::default(),
+            segment_secs: DEFAULT_SEGMENT_SECS,
+            overrides: SharedProviderConfig::default(),
         }
     }
 }
@@ -71,31 +85,18 @@ impl OpenAiTranscriptionConfig {
             config.request_timeout_secs = timeout_secs.max(1);
         }
 
-        if let Some(max_retries) = read_u32_env("OPENAI_TRANSCRIPTION_MAX_RETRIES") {
-            config.max_retries = max_retries;
-        }
-
-        if let Some(initial_backoff_ms) =
-            read_u64_env("OPENAI_TRANSCRIPTION_RETRY_INITIAL_BACKOFF_MS")
-        {
-            config.retry_initial_backoff_ms = initial_backoff_ms.max(1);
+        if let Some(segment_secs) = read_u64_env("OPENAI_TRANSCRIPTION_SEGMENT_SECS") {
+            config.segment_secs = u32::try_from(segment_secs).unwrap_or(u32::MAX);
         }
 
-        if let Some(max_backoff_ms) = read_u64_env("OPENAI_TRANSCRIPTION_RETRY_MAX_BACKOFF_MS") {
-            config.retry_max_backoff_ms = max_backoff_ms.max(1);
-        }
-
-        if config.retry_initial_backoff_ms > config.retry_max_backoff_ms {
-            config.retry_initial_backoff_ms = config.retry_max_backoff_ms;
-        }
+        config.retry_policy = RetryPolicy::from_env("OPENAI_TRANSCRIPTION");
 
         debug!(
             endpoint = %config.endpoint,
             model = %config.model,
             request_timeout_secs = config.request_timeout_secs,
-            max_retries = config.max_retries,
-            retry_initial_backoff_ms = config.retry_initial_backoff_ms,
-            retry_max_backoff_ms = config.retry_max_backoff_ms,
+            max_retries = config.retry_policy.max_retries,
+            segment_secs = config.segment_secs,
             "loaded OpenAI transcription config"
         );
         config
@@ -104,7 +105,6 @@ impl OpenAiTranscriptionConfig {
 
 #[derive(Debug, Clone)]
 pub struct OpenAiTranscriptionProvider {
-    client: Client,
     config: OpenAiTranscriptionConfig,
     jitter_seed: u64,
 }
@@ -119,11 +119,10 @@ impl OpenAiTranscriptionProvider {
             endpoint = %config.endpoint,
             model = %config.model,
             request_timeout_secs = config.request_timeout_secs,
-            max_retries = config.max_retries,
+            max_retries = config.retry_policy.max_retries,
             "OpenAI transcription provider initialized"
         );
         Self {
-            client: build_client(&config),
             config,
             jitter_seed,
         }
@@ -151,7 +150,7 @@ impl OpenAiTranscriptionProvider {
                     Ok(None) => {}
                     Err(error) => {
                         if let Some(env_key) = read_non_empty_env("OPENAI_API_KEY") {
-                            warn!(
+                            tracing::warn!(
                                 error = %error,
                                 "falling back to OPENAI_API_KEY environment variable after API key file read failure"
                             );
@@ -171,55 +170,77 @@ impl OpenAiTranscriptionProvider {
             .ok_or(TranscriptionError::MissingApiKey)
     }
 
+    fn should_retry(&self, class: RetryClass, attempt_index: u32) -> bool {
+        self.config.retry_policy.should_retry(class, attempt_index)
+    }
+
     fn retry_delay(&self, attempt_index: u32, retry_after: Option<Duration>) -> Duration {
-        if let Some(delay) = retry_after {
-            return delay;
-        }
+        self.config
+            .retry_policy
+            .delay(attempt_index, retry_after, self.jitter_seed)
+    }
 
-        let growth_factor = 1_u64.checked_shl(attempt_index.min(20)).unwrap_or(u64::MAX);
-        let uncapped_ms = self
-            .config
-            .retry_initial_backoff_ms
-            .saturating_mul(growth_factor);
-        let capped_ms = uncapped_ms.min(self.config.retry_max_backoff_ms).max(1);
-
-        // Equal jitter: spread retries in [base/2, base] to reduce thundering herd while
-        // retaining monotonic growth.
-        let half_ms = capped_ms / 2;
-        let jitter_span_ms = capped_ms.saturating_sub(half_ms);
-        let jitter_offset = if jitter_span_ms == 0 {
-            0
-        } else {
-            self.pseudo_random(attempt_index) % (jitter_span_ms + 1)
-        };
+    fn model_supports_streaming(&self) -> bool {
+        let model = self.config.model.to_ascii_lowercase();
+        model.contains("transcribe") && !model.contains("diarize")
+    }
 
-        Duration::from_millis(half_ms.saturating_add(jitter_offset).max(1))
+    fn model_supports_diarization(&self) -> bool {
+        self.config.model.to_ascii_lowercase().contains("diarize")
     }
 
-    fn pseudo_random(&self, attempt_index: u32) -> u64 {
-        let mut state =
-            self.jitter_seed ^ (attempt_index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
-        state ^= state >> 12;
-        state ^= state << 25;
-        state ^= state >> 27;
-        state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    // Segmenting only applies when someone is listening for deltas and the
+    // model would otherwise answer in one piece. Diarized models keep whole
+    // requests so speaker labels stay consistent.
+    fn wants_segmented_deltas(&self, options: &TranscriptionOptions) -> bool {
+        if self.config.segment_secs == 0 || options.on_delta.is_none() {
+            return false;
+        }
+        let mut provider = self.clone();
+        if let Some(model) = self.config.overrides.current().model {
+            provider.config.model = model;
+        }
+        !provider.model_supports_streaming() && !provider.model_supports_diarization()
     }
 
-    fn model_supports_streaming(&self) -> bool {
-        self.config
-            .model
-            .to_ascii_lowercase()
-            .contains("transcribe")
+    // `duration_secs` is `None` for anything that is not a WAV.
+    fn segmented_config(
+        &self,
+        duration_secs: Option<f64>,
+        options: &TranscriptionOptions,
+    ) -> Option<ChunkedTranscriptionConfig> {
+        if !self.wants_segmented_deltas(options)
+            || duration_secs? <= f64::from(self.config.segment_secs)
+        {
+            return None;
+        }
+        Some(ChunkedTranscriptionConfig {
+            chunk_secs: self.config.segment_secs,
+            overlap_secs: SEGMENT_OVERLAP_SECS,
+            max_parallel_requests: 1,
+            audio_encoding: AudioEncoding::Wav,
+        })
     }
 
     fn build_form(
         &self,
-        audio_data: Bytes,
+        audio: &UploadAudio,
         language: Option<&str>,
         prompt: Option<&str>,
+        decoding: &DecodingOptions,
         stream: bool,
     ) -> Result<multipart::Form, TranscriptionError> {
-        let response_format = if stream { "text" } else { "verbose_json" };
+        let diarize = self.model_supports_diarization();
+        let response_format = if stream {
+            "text"
+        } else if diarize {
+            "diarized_json"
+        } else {
+            decoding
+                .response_format
+                .as_deref()
+                .unwrap_or(RESPONSE_FORMAT_VERBOSE_JSON)
+        };
         let mut form = multipart::Form::new()
             .text("model", self.config.model.clone())
             .text("response_format", response_format.to_string());
@@ -228,24 +249,28 @@ impl OpenAiTranscriptionProvider {
             form = form.text("stream", "true".to_string());
         }
 
+        if diarize {
+            form = form.text("chunking_strategy", "auto".to_string());
+        } else if response_format == "verbose_json" {
+            form = form
+                .text("timestamp_granularities[]", "segment".to_string())
+                .text("timestamp_granularities[]", "word".to_string());
+        }
+
         if let Some(language) = language {
             form = form.text("language", language.to_string());
         }
 
-        if let Some(prompt) = prompt {
-            form = form.text("prompt", prompt.to_string());
+        if let Some(temperature) = decoding.temperature() {
+            form = form.text("temperature", temperature.to_string());
         }
 
-        let audio_len = u64::try_from(audio_data.len())
-            .map_err(|_| TranscriptionError::Provider("Audio upload is too large".to_string()))?;
-
-        let file_part = multipart::Part::stream_with_length(audio_data, audio_len)
-            .file_name("audio.wav")
-            .mime_str("audio/wav")
-            .map_err(|error| {
-                TranscriptionError::Provider(format!("Unable to prepare audio upload: {error}"))
-            })?;
+        // Diarization models reject prompts.
+        if let Some(prompt) = prompt.filter(|_| !diarize) {
+            form = form.text("prompt", prompt.to_string());
+        }
 
+        let file_part = audio.to_part()?;
         Ok(form.part("file", file_part))
     }
 
@@ -296,8 +321,11 @@ impl OpenAiTranscriptionProvider {
         Ok(TranscriptionResult {
             text: normalize_transcript_text(&final_text),
             language: request_language,
+            language_confidence: None,
             duration_secs: None,
             confidence: None,
+            segments: Vec::new(),
+            words: Vec::new(),
         })
     }
 }
@@ -312,25 +340,164 @@ impl TranscriptionProvider for OpenAiTranscriptionProvider {
         &self,
         audio_data: Vec<u8>,
         options: TranscriptionOptions,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        // Segments are transcribed without a delta callback, so this never
+        // recurses past one level.
+        if let Some(config) = self.segmented_config(wav_duration_secs(&audio_data), &options) {
+            info!(
+                segment_secs = config.chunk_secs,
+                "transcribing in sequential segments for incremental deltas"
+            );
+            return chunked::transcribe_chunked(self, &audio_data, options, &config).await;
+        }
+        self.transcribe_upload(UploadAudio::Bytes(Bytes::from(audio_data)), options)
+            .await
+    }
+
+    async fn transcribe_file(
+        &self,
+        path: &Path,
+        options: TranscriptionOptions,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        // Spooled recordings are segmented straight from disk so a long one
+        // is never read into memory whole.
+        let duration_secs = if self.wants_segmented_deltas(&options) {
+            Pcm16WavFile::open(path)
+                .ok()
+                .and_then(|file| file.duration_secs())
+        } else {
+            None
+        };
+        if let Some(config) = self.segmented_config(duration_secs, &options) {
+            info!(
+                segment_secs = config.chunk_secs,
+                "transcribing spooled audio in sequential segments for incremental deltas"
+            );
+            return chunked::transcribe_chunked_file(self, path, options, &config).await;
+        }
+        let len = tokio::fs::metadata(path)
+            .await
+            .map_err(|error| {
+                TranscriptionError::Provider(format!("Failed to read recorded audio: {error}"))
+            })?
+            .len();
+        self.transcribe_upload(
+            UploadAudio::File {
+                path: path.to_path_buf(),
+                len,
+            },
+            options,
+        )
+        .await
+    }
+
+    async fn list_models(&self, api_key: Option<&str>) -> Result<ModelListing, TranscriptionError> {
+        let api_key = match normalize_optional_string(api_key.map(str::to_string)) {
+            Some(api_key) => api_key,
+            None => self.api_key()?,
+        };
+        let overrides = self.config.overrides.current();
+        let extra_headers = overrides
+            .header_map()
+            .map_err(TranscriptionError::Provider)?;
+        let endpoint = models_endpoint(
+            &overrides
+                .transcription_endpoint()
+                .unwrap_or_else(|| self.config.endpoint.clone()),
+        );
+        debug!(endpoint = %endpoint, "listing OpenAI models");
+
+        let response = crate::http_client::shared_client()
+            .get(&endpoint)
+            .timeout(MODEL_LIST_TIMEOUT)
+            .bearer_auth(&api_key)
+            .headers(extra_headers)
+            .send()
+            .await
+            .map_err(|error| map_transport_error(error).error)?;
+        if !response.status().is_success() {
+            return Err(map_http_error(response).await.error);
+        }
+        let organization = response
+            .headers()
+            .get(OPENAI_ORGANIZATION_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| normalize_optional_string(Some(value.to_string())));
+        let payload: OpenAiModelList = response
+            .json()
+            .await
+            .map_err(|error| TranscriptionError::InvalidResponse(error.to_string()))?;
+
+        let models = TranscriptionModel::sorted(
+            payload
+                .data
+                .into_iter()
+                .filter(|model| is_transcription_model(&model.id))
+                .map(|model| TranscriptionModel {
+                    realtime: supports_realtime_transcription(&model.id),
+                    id: model.id,
+                })
+                .collect(),
+        );
+        info!(
+            model_count = models.len(),
+            has_organization = organization.is_some(),
+            "listed OpenAI speech models"
+        );
+        Ok(ModelListing {
+            organization,
+            models,
+        })
+    }
+}
+
+impl OpenAiTranscriptionProvider {
+    async fn transcribe_upload(
+        &self,
+        audio: UploadAudio,
+        options: TranscriptionOptions,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let overrides = self.config.overrides.current();
+        let extra_headers = overrides
+            .header_map()
+            .map_err(TranscriptionError::Provider)?;
+        let mut provider = self.clone();
+        if let Some(endpoint) = overrides.transcription_endpoint() {
+            provider.config.endpoint = endpoint;
+        }
+        if let Some(model) = overrides.model {
+            provider.config.model = model;
+        }
+        provider.send_upload(audio, options, extra_headers).await
+    }
+
+    async fn send_upload(
+        &self,
+        audio: UploadAudio,
+        options: TranscriptionOptions,
+        extra_headers: HeaderMap,
     ) -> Result<TranscriptionResult, TranscriptionError> {
         let TranscriptionOptions {
             language,
             prompt,
             context_hint,
+            decoding,
             on_delta,
+            on_retry,
+            cancellation: _,
         } = options;
         let api_key = self.api_key()?;
         let request_language = normalize_optional_string(language);
         let request_prompt = build_prompt(prompt, context_hint);
         let request_language_for_payload = request_language.clone();
         let stream_response = self.model_supports_streaming();
-        let audio_data = Bytes::from(audio_data);
         let mut attempt_index = 0;
         info!(
             endpoint = %self.config.endpoint,
             model = %self.config.model,
             stream = stream_response,
-            audio_bytes = audio_data.len(),
+            audio_bytes = audio.len(),
+            streamed_from_file = audio.is_file(),
             language = ?request_language,
             has_prompt = request_prompt.is_some(),
             "starting OpenAI transcription request"
@@ -342,16 +509,18 @@ impl TranscriptionProvider for OpenAiTranscriptionProvider {
                 "sending OpenAI transcription request"
             );
             let form = self.build_form(
-                audio_data.clone(),
+                &audio,
                 request_language.as_deref(),
                 request_prompt.as_deref(),
+                &decoding,
                 stream_response,
             )?;
 
-            let response = self
-                .client
+            let response = crate::http_client::shared_client()
                 .post(&self.config.endpoint)
+                .timeout(Duration::from_secs(self.config.request_timeout_secs.max(1)))
                 .bearer_auth(&api_key)
+                .headers(extra_headers.clone())
                 .multipart(form)
                 .send()
                 .await;
@@ -360,13 +529,19 @@ impl TranscriptionProvider for OpenAiTranscriptionProvider {
                 Ok(response) => response,
                 Err(error) => {
                     let transport_error = map_transport_error(error);
-                    if transport_error.retryable && attempt_index < self.config.max_retries {
+                    if let Some(class) = transport_error
+                        .retry_class
+                        .filter(|class| self.should_retry(*class, attempt_index))
+                    {
                         let delay = self.retry_delay(attempt_index, None);
-                        warn!(
-                            attempt = attempt_index + 1,
-                            delay_ms = delay.as_millis() as u64,
-                            error = %transport_error.error,
-                            "retrying OpenAI transcription request after transport error"
+                        report_retry(
+                            on_retry.as_ref(),
+                            self.name(),
+                            &self.config.retry_policy,
+                            attempt_index,
+                            class,
+                            delay,
+                            &transport_error.error,
                         );
                         tokio::time::sleep(delay).await;
                         attempt_index += 1;
@@ -396,31 +571,48 @@ impl TranscriptionProvider for OpenAiTranscriptionProvider {
                         .await;
                 }
 
-                let response_payload: OpenAiTranscriptionResponse = response
+                let mut response_payload: OpenAiTranscriptionResponse = response
                     .json()
                     .await
                     .map_err(|error| TranscriptionError::InvalidResponse(error.to_string()))?;
+                if let Some(threshold) = decoding.no_speech_threshold() {
+                    drop_silent_segments(&mut response_payload, threshold);
+                }
+                let confidence = response_payload
+                    .confidence
+                    .or_else(|| derive_confidence_from_segments(&response_payload.segments));
+                let segments = transcription_segments(response_payload.segments);
+                let words = transcription_words(response_payload.words, &segments);
 
                 return Ok(TranscriptionResult {
                     text: normalize_transcript_text(&response_payload.text),
+                    language_confidence: response_payload
+                        .language_probability
+                        .filter(|_| response_payload.language.is_some()),
                     language: response_payload
                         .language
                         .or(request_language_for_payload.clone()),
                     duration_secs: response_payload.duration,
-                    confidence: response_payload
-                        .confidence
-                        .or_else(|| derive_confidence_from_segments(&response_payload.segments)),
+                    confidence,
+                    segments,
+                    words,
                 });
             }
 
             let http_error = map_http_error(response).await;
-            if http_error.retryable && attempt_index < self.config.max_retries {
+            if let Some(class) = http_error
+                .retry_class
+                .filter(|class| self.should_retry(*class, attempt_index))
+            {
                 let delay = self.retry_delay(attempt_index, http_error.retry_after);
-                warn!(
-                    attempt = attempt_index + 1,
-                    delay_ms = delay.as_millis() as u64,
-                    error = %http_error.error,
-                    "retrying OpenAI transcription request after HTTP error"
+                report_retry(
+                    on_retry.as_ref(),
+                    self.name(),
+                    &self.config.retry_policy,
+                    attempt_index,
+                    class,
+                    delay,
+                    &http_error.error,
                 );
                 tokio::time::sleep(delay).await;
                 attempt_index += 1;
@@ -437,6 +629,55 @@ impl TranscriptionProvider for OpenAiTranscriptionProvider {
     }
 }
 
+// Uploads either come from memory or are streamed from a spooled recording on
+// disk; the file is reopened for every retry attempt.
+#[derive(Debug)]
+enum UploadAudio {
+    Bytes(Bytes),
+    File { path: PathBuf, len: u64 },
+}
+
+impl UploadAudio {
+    fn len(&self) -> u64 {
+        match self {
+            Self::Bytes(bytes) => bytes.len() as u64,
+            Self::File { len, .. } => *len,
+        }
+    }
+
+    fn is_file(&self) -> bool {
+        matches!(self, Self::File { .. })
+    }
+
+    fn to_part(&self) -> Result<multipart::Part, TranscriptionError> {
+        let (part, encoding) = match self {
+            Self::Bytes(bytes) => (
+                multipart::Part::stream_with_length(bytes.clone(), bytes.len() as u64),
+                AudioEncoding::detect(bytes),
+            ),
+            Self::File { path, len } => {
+                let file = std::fs::File::open(path).map_err(|error| {
+                    TranscriptionError::Provider(format!(
+                        "Failed to open recorded audio for upload: {error}"
+                    ))
+                })?;
+                let body = reqwest::Body::from(tokio::fs::File::from_std(file));
+                // Spooled recordings are always WAV.
+                (
+                    multipart::Part::stream_with_length(body, *len),
+                    AudioEncoding::Wav,
+                )
+            }
+        };
+
+        part.file_name(encoding.file_name())
+            .mime_str(encoding.mime_type())
+            .map_err(|error| {
+                TranscriptionError::Provider(format!("Unable to prepare audio upload: {error}"))
+            })
+    }
+}
+
 #[derive(Debug, Default)]
 struct OpenAiStreamingState {
     current_event_name: Option<String>,
@@ -555,18 +796,52 @@ struct OpenAiTranscriptionResponse {
     text: String,
     #[serde(default)]
     language: Option<String>,
+    // Whisper-compatible servers such as faster-whisper report how sure
+    // their language detection was; OpenAI itself does not.
+    #[serde(default)]
+    language_probability: Option<f32>,
     #[serde(default)]
     duration: Option<f64>,
     #[serde(default)]
     confidence: Option<f32>,
     #[serde(default)]
     segments: Vec<OpenAiSegment>,
+    #[serde(default)]
+    words: Vec<OpenAiWord>,
 }
 
 #[derive(Debug, Deserialize)]
 struct OpenAiSegment {
     #[serde(default)]
     avg_logprob: Option<f32>,
+    #[serde(default)]
+    no_speech_prob: Option<f32>,
+    #[serde(default)]
+    start: Option<f64>,
+    #[serde(default)]
+    end: Option<f64>,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    speaker: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiWord {
+    word: String,
+    start: f64,
+    end: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiModelList {
+    #[serde(default)]
+    data: Vec<OpenAiModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiModel {
+    id: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -580,33 +855,146 @@ struct OpenAiErrorBody {
     message: Option<String>,
     #[serde(default, rename = "type")]
     kind: Option<String>,
+    #[serde(default)]
+    code: Option<String>,
+}
+
+// The model list sits next to the transcription endpoint, which also holds
+// for compatible servers configured through a base URL.
+fn models_endpoint(transcription_endpoint: &str) -> String {
+    let endpoint = transcription_endpoint.trim_end_matches('/');
+    let base_url = endpoint
+        .strip_suffix(TRANSCRIPTIONS_PATH)
+        .unwrap_or(endpoint);
+    format!("{base_url}/models")
+}
+
+// Whisper and the transcribe models take uploads; compatible servers tend to
+// keep `whisper` in the name. The realtime models only drive streaming
+// sessions, so they are not offered for transcription.
+fn is_transcription_model(model: &str) -> bool {
+    let model = model.to_ascii_lowercase();
+    ["whisper", "transcribe"]
+        .iter()
+        .any(|marker| model.contains(marker))
+}
+
+// Realtime sessions take `whisper-1` and the GPT transcribe models, except
+// the diarizing one.
+fn supports_realtime_transcription(model: &str) -> bool {
+    let model = model.to_ascii_lowercase();
+    model == "whisper-1"
+        || (model.starts_with("gpt-") && model.contains("transcribe") && !model.contains("diarize"))
 }
 
 fn derive_confidence_from_segments(segments: &[OpenAiSegment]) -> Option<f32> {
     let probabilities = segments
         .iter()
         .filter_map(|segment| segment.avg_logprob)
-        .map(|log_prob| (log_prob as f64).exp().clamp(0.0, 1.0))
+        .map(segment_confidence)
         .collect::<Vec<_>>();
 
     if probabilities.is_empty() {
         return None;
     }
 
-    let avg = probabilities.iter().sum::<f64>() / probabilities.len() as f64;
-    Some(avg as f32)
+    Some(probabilities.iter().sum::<f32>() / probabilities.len() as f32)
+}
+
+// The average log probability of a segment's tokens, as a probability.
+fn segment_confidence(avg_logprob: f32) -> f32 {
+    (avg_logprob as f64).exp().clamp(0.0, 1.0) as f32
+}
+
+// Whisper tends to invent text for stretches of silence. Segments it rates
+// as more likely silent than `threshold` are dropped, along with their words,
+// and the text is rebuilt from what is left.
+fn drop_silent_segments(response: &mut OpenAiTranscriptionResponse, threshold: f32) {
+    let (silent, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut response.segments)
+        .into_iter()
+        .partition(|segment| {
+            segment
+                .no_speech_prob
+                .is_some_and(|probability| probability > threshold)
+        });
+    response.segments = kept;
+    if silent.is_empty() {
+        return;
+    }
+    debug!(
+        dropped_segments = silent.len(),
+        threshold, "dropped segments above the no-speech threshold"
+    );
+    response.words.retain(|word| {
+        !silent.iter().any(|segment| {
+            segment
+                .start
+                .zip(segment.end)
+                .is_some_and(|(start, end)| word.start >= start && word.start < end)
+        })
+    });
+    response.text = response
+        .segments
+        .iter()
+        .filter_map(|segment| segment.text.as_deref())
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+}
+
+fn transcription_segments(segments: Vec<OpenAiSegment>) -> Vec<TranscriptionSegment> {
+    let mut converted = segments
+        .into_iter()
+        .filter_map(|segment| {
+            let text = normalize_optional_string(segment.text)?;
+            Some(TranscriptionSegment {
+                start_secs: segment.start?,
+                end_secs: segment.end?,
+                text,
+                speaker: normalize_optional_string(segment.speaker),
+                confidence: segment.avg_logprob.map(segment_confidence),
+            })
+        })
+        .collect::<Vec<_>>();
+    normalize_speaker_labels(&mut converted);
+    converted
+}
+
+// Words carry no scores of their own, so each takes the confidence of the
+// segment it starts in.
+fn transcription_words(
+    words: Vec<OpenAiWord>,
+    segments: &[TranscriptionSegment],
+) -> Vec<TranscriptionWord> {
+    words
+        .into_iter()
+        .filter_map(|word| {
+            let text = normalize_optional_string(Some(word.word))?;
+            let confidence = segments
+                .iter()
+                .find(|segment| word.start >= segment.start_secs && word.start < segment.end_secs)
+                .and_then(|segment| segment.confidence);
+            Some(TranscriptionWord {
+                start_secs: word.start,
+                end_secs: word.end,
+                text,
+                confidence,
+            })
+        })
+        .collect()
 }
 
 #[derive(Debug)]
 struct RetryableError {
     error: TranscriptionError,
-    retryable: bool,
+    retry_class: Option<RetryClass>,
     retry_after: Option<Duration>,
 }
 
 fn map_transport_error(error: reqwest::Error) -> RetryableError {
-    let retryable = error.is_timeout() || error.is_connect();
-    let mapped = if retryable {
+    let retry_class = classify_transport_error(&error);
+    let mapped = if retry_class.is_some() {
         TranscriptionError::Network(error.to_string())
     } else {
         TranscriptionError::Provider(error.to_string())
@@ -614,7 +1002,7 @@ fn map_transport_error(error: reqwest::Error) -> RetryableError {
 
     RetryableError {
         error: mapped,
-        retryable,
+        retry_class,
         retry_after: None,
     }
 }
@@ -629,6 +1017,7 @@ async fn map_http_error(response: reqwest::Response) -> RetryableError {
     let response_body = response.text().await.unwrap_or_default();
     let fallback_message = format!("OpenAI request failed with status {}", status.as_u16());
     let error_message = parse_openai_error_message(&response_body).unwrap_or(fallback_message);
+    let quota_exceeded = is_openai_quota_error(&response_body);
     debug!(
         status = status.as_u16(),
         retry_after_ms = retry_after.map(|d| d.as_millis() as u64),
@@ -639,6 +1028,9 @@ async fn map_http_error(response: reqwest::Response) -> RetryableError {
         StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
             TranscriptionError::Authentication(error_message)
         }
+        StatusCode::TOO_MANY_REQUESTS if quota_exceeded => {
+            TranscriptionError::QuotaExceeded(error_message)
+        }
         StatusCode::TOO_MANY_REQUESTS => TranscriptionError::RateLimited(error_message),
         StatusCode::REQUEST_TIMEOUT => TranscriptionError::Network(error_message),
         _ if status.is_server_error() => TranscriptionError::Network(error_message),
@@ -646,15 +1038,13 @@ async fn map_http_error(response: reqwest::Response) -> RetryableError {
     };
 
     RetryableError {
-        retryable: status == StatusCode::TOO_MANY_REQUESTS
-            || status == StatusCode::REQUEST_TIMEOUT
-            || status.is_server_error(),
+        retry_class: classify_status(status),
         error: mapped,
         retry_after,
     }
 }
 
-fn parse_openai_error_message(raw_body: &str) -> Option<String> {
+pub(super) fn parse_openai_error_message(raw_body: &str) -> Option<String> {
     let parsed = serde_json::from_str::<OpenAiErrorEnvelope>(raw_body).ok()?;
 
     if let Some(message) = normalize_optional_string(parsed.error.message) {
@@ -664,6 +1054,16 @@ fn parse_openai_error_message(raw_body: &str) -> Option<String> {
     normalize_optional_string(parsed.error.kind)
 }
 
+// OpenAI answers an empty balance with a 429 too, told apart by its error code.
+pub(super) fn is_openai_quota_error(raw_body: &str) -> bool {
+    serde_json::from_str::<OpenAiErrorEnvelope>(raw_body).is_ok_and(|parsed| {
+        [parsed.error.code, parsed.error.kind]
+            .iter()
+            .flatten()
+            .any(|code| code == "insufficient_quota")
+    })
+}
+
 fn normalize_optional_string(value: Option<String>) -> Option<String> {
     value.and_then(|content| {
         let trimmed = content.trim();
@@ -706,52 +1106,6 @@ fn read_u64_env(name: &str) -> Option<u64> {
         .and_then(|value| value.parse::<u64>().ok())
 }
 
-fn read_u32_env(name: &str) -> Option<u32> {
-    std::env::var(name)
-        .ok()
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
-        .and_then(|value| value.parse::<u32>().ok())
-}
-
-fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
-    let header_value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
-    if header_value.is_empty() {
-        return None;
-    }
-
-    if let Ok(seconds) = header_value.parse::<u64>() {
-        return Some(Duration::from_secs(seconds));
-    }
-
-    let retry_at = httpdate::parse_http_date(header_value).ok()?;
-    let now = SystemTime::now();
-    Some(
-        retry_at
-            .duration_since(now)
-            .unwrap_or(Duration::from_secs(0)),
-    )
-}
-
-fn build_client(config: &OpenAiTranscriptionConfig) -> Client {
-    let timeout = Duration::from_secs(config.request_timeout_secs.max(1));
-    debug!(
-        timeout_secs = timeout.as_secs(),
-        "building OpenAI HTTP client"
-    );
-    Client::builder()
-        .timeout(timeout)
-        .build()
-        .expect("OpenAI client construction should succeed")
-}
-
-fn seed_from_clock() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|duration| duration.as_nanos() as u64)
-        .unwrap_or(0xA5A5_A5A5_A5A5_A5A5)
-}
-
 #[cfg(test)]
 mod tests {
     use mockito::{Matcher, Server};
@@ -761,6 +1115,7 @@ mod tests {
     };
 
     use super::*;
+    use crate::transcription::endpoint::ProviderConfig;
 
     fn config_for_test(server: &Server, api_key: Option<&str>) -> OpenAiTranscriptionConfig {
         OpenAiTranscriptionConfig {
@@ -769,9 +1124,14 @@ mod tests {
             endpoint: format!("{}/v1/audio/transcriptions", server.url()),
             model: "whisper-1".to_string(),
             request_timeout_secs: 5,
-            max_retries: 3,
-            retry_initial_backoff_ms: 10,
-            retry_max_backoff_ms: 50,
+            retry_policy: RetryPolicy {
+                max_retries: 3,
+                initial_backoff_ms: 10,
+                max_backoff_ms: 50,
+                ..RetryPolicy::default()
+            },
+            segment_secs: 0,
+            overrides: SharedProviderConfig::default(),
         }
     }
 
@@ -827,6 +1187,286 @@ mod tests {
         assert!(result.confidence.is_some());
     }
 
+    #[tokio::test]
+    async fn lists_speech_models_and_organization_for_a_key() {
+        let mut server = Server::new_async().await;
+        let request_mock = server
+            .mock("GET", "/v1/models")
+            .match_header("authorization", "Bearer draft-key")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("openai-organization", "org-voice")
+            .with_body(
+                r#"{
+                    "data": [
+                        { "id": "gpt-4o" },
+                        { "id": "whisper-1" },
+                        { "id": "gpt-4o-mini-transcribe" },
+                        { "id": "gpt-4o-transcribe-diarize" },
+                        { "id": "gpt-realtime" }
+                    ]
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let provider = provider_for_test(&server, Some("stored-key"));
+        let listing = provider
+            .list_models(Some("draft-key"))
+            .await
+            .expect("model list should succeed");
+
+        request_mock.assert_async().await;
+        assert_eq!(listing.organization.as_deref(), Some("org-voice"));
+        assert_eq!(
+            listing
+                .models
+                .iter()
+                .map(|model| (model.id.as_str(), model.realtime))
+                .collect::<Vec<_>>(),
+            vec![
+                ("gpt-4o-mini-transcribe", true),
+                ("gpt-4o-transcribe-diarize", false),
+                ("whisper-1", true),
+            ]
+        );
+
+        server
+            .mock("GET", "/v1/models")
+            .with_status(401)
+            .with_body(r#"{"error":{"message":"Incorrect API key provided"}}"#)
+            .create_async()
+            .await;
+        let error = provider
+            .list_models(Some("bad-key"))
+            .await
+            .expect_err("a rejected key should fail");
+        assert_eq!(
+            error,
+            TranscriptionError::Authentication("Incorrect API key provided".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn settings_overrides_redirect_endpoint_model_and_headers() {
+        let mut server = Server::new_async().await;
+        let request_mock = server
+            .mock("POST", "/local/v1/audio/transcriptions")
+            .match_header("x-tenant", "voice")
+            .match_body(Matcher::Regex(
+                r#"name="model"\r\n\r\nSystran/faster-whisper-small"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"text": "hello from a local server"}"#)
+            .create_async()
+            .await;
+
+        let mut config = config_for_test(&server, Some("test-key"));
+        config.endpoint = "http://127.0.0.1:9/unreachable".to_string();
+        config.overrides.set(ProviderConfig {
+            base_url: Some(format!("{}/local/v1", server.url())),
+            model: Some("Systran/faster-whisper-small".to_string()),
+            realtime_model: None,
+            extra_headers: [("X-Tenant".to_string(), "voice".to_string())].into(),
+        });
+        let result = provider_with_config(config)
+            .transcribe(vec![1, 2, 3, 4], TranscriptionOptions::default())
+            .await
+            .expect("request should reach the overridden endpoint");
+
+        request_mock.assert_async().await;
+        assert_eq!(result.text, "hello from a local server");
+    }
+
+    #[tokio::test]
+    async fn requests_diarized_json_and_labels_speakers_for_diarize_models() {
+        let mut server = Server::new_async().await;
+        let request_mock = server
+            .mock("POST", "/v1/audio/transcriptions")
+            .match_body(Matcher::Regex(
+                r#"name="response_format"\r\n\r\ndiarized_json"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "text": "hi there hello",
+                    "segments": [
+                        { "speaker": "B", "start": 0.0, "end": 1.2, "text": "hi there" },
+                        { "speaker": "A", "start": 1.2, "end": 2.0, "text": " hello " }
+                    ]
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let mut config = config_for_test(&server, Some("test-key"));
+        config.model = "gpt-4o-transcribe-diarize".to_string();
+        let result = provider_with_config(config)
+            .transcribe(vec![1, 2, 3, 4], TranscriptionOptions::default())
+            .await
+            .expect("request should succeed");
+
+        request_mock.assert_async().await;
+        assert_eq!(
+            result.segments,
+            vec![
+                TranscriptionSegment {
+                    start_secs: 0.0,
+                    end_secs: 1.2,
+                    text: "hi there".to_string(),
+                    speaker: Some("Speaker 1".to_string()),
+                    confidence: None,
+                },
+                TranscriptionSegment {
+                    start_secs: 1.2,
+                    end_secs: 2.0,
+                    text: "hello".to_string(),
+                    speaker: Some("Speaker 2".to_string()),
+                    confidence: None,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_word_and_segment_timestamps_for_verbose_json_models() {
+        let mut server = Server::new_async().await;
+        let request_mock = server
+            .mock("POST", "/v1/audio/transcriptions")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(r#"name="timestamp_granularities\[\]"\r\n\r\nsegment"#.to_string()),
+                Matcher::Regex(r#"name="timestamp_granularities\[\]"\r\n\r\nword"#.to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "text": "hello world",
+                    "segments": [{ "start": 0.0, "end": 1.0, "text": "hello world", "avg_logprob": 0.0 }],
+                    "words": [
+                        { "word": "hello", "start": 0.0, "end": 0.4 },
+                        { "word": " world ", "start": 0.5, "end": 1.0 }
+                    ]
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let result = provider_for_test(&server, Some("test-key"))
+            .transcribe(vec![1, 2, 3, 4], TranscriptionOptions::default())
+            .await
+            .expect("request should succeed");
+
+        request_mock.assert_async().await;
+        assert_eq!(result.segments.len(), 1);
+        assert_eq!(result.segments[0].confidence, Some(1.0));
+        assert_eq!(
+            result.words,
+            vec![
+                TranscriptionWord {
+                    start_secs: 0.0,
+                    end_secs: 0.4,
+                    text: "hello".to_string(),
+                    confidence: Some(1.0),
+                },
+                TranscriptionWord {
+                    start_secs: 0.5,
+                    end_secs: 1.0,
+                    text: "world".to_string(),
+                    confidence: Some(1.0),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_detected_language_probability_apart_from_transcript_confidence() {
+        let mut server = Server::new_async().await;
+        let request_mock = server
+            .mock("POST", "/v1/audio/transcriptions")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "text": "hallo welt",
+                    "language": "german",
+                    "language_probability": 0.42,
+                    "segments": [{ "start": 0.0, "end": 1.0, "text": "hallo welt", "avg_logprob": 0.0 }]
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let result = provider_for_test(&server, Some("test-key"))
+            .transcribe(vec![1, 2, 3, 4], TranscriptionOptions::default())
+            .await
+            .expect("request should succeed");
+
+        request_mock.assert_async().await;
+        assert_eq!(result.language.as_deref(), Some("german"));
+        assert_eq!(result.language_confidence, Some(0.42));
+        assert_eq!(result.confidence, Some(1.0));
+    }
+
+    #[tokio::test]
+    async fn sends_decoding_options_and_drops_segments_above_no_speech_threshold() {
+        let mut server = Server::new_async().await;
+        let request_mock = server
+            .mock("POST", "/v1/audio/transcriptions")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(r#"name="temperature"\r\n\r\n0.2"#.to_string()),
+                Matcher::Regex(r#"name="response_format"\r\n\r\nverbose_json"#.to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "text": "Ship it today. Thanks for watching!",
+                    "segments": [
+                        { "start": 0.0, "end": 1.2, "text": " Ship it today.", "no_speech_prob": 0.05 },
+                        { "start": 1.2, "end": 4.0, "text": " Thanks for watching!", "no_speech_prob": 0.92 }
+                    ],
+                    "words": [
+                        { "word": "Ship", "start": 0.0, "end": 0.3 },
+                        { "word": "today.", "start": 0.7, "end": 1.2 },
+                        { "word": "Thanks", "start": 2.0, "end": 2.4 }
+                    ]
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let result = provider_for_test(&server, Some("test-key"))
+            .transcribe(
+                vec![1, 2, 3, 4],
+                TranscriptionOptions {
+                    decoding: DecodingOptions {
+                        temperature_percent: Some(20),
+                        no_speech_threshold_percent: Some(60),
+                        response_format: Some(RESPONSE_FORMAT_VERBOSE_JSON.to_string()),
+                        beam_size: None,
+                    },
+                    ..TranscriptionOptions::default()
+                },
+            )
+            .await
+            .expect("request should succeed");
+
+        request_mock.assert_async().await;
+        assert_eq!(result.text, "Ship it today.");
+        assert_eq!(result.segments.len(), 1);
+        assert_eq!(
+            result
+                .words
+                .iter()
+                .map(|word| word.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Ship", "today."]
+        );
+    }
+
     #[tokio::test]
     async fn streams_deltas_and_returns_done_payload_for_transcribe_models() {
         let mut server = Server::new_async().await;
@@ -884,6 +1524,61 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn sends_long_audio_in_sequential_segments_for_non_streaming_models() {
+        let mut server = Server::new_async().await;
+        let replies = ["first part", "second part", "third part"];
+        let request_count = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&request_count);
+        let segment_mock = server
+            .mock("POST", "/v1/audio/transcriptions")
+            .expect(3)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_request(move |_| {
+                let index = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                format!(r#"{{"text":"{}"}}"#, replies[index.min(2)]).into_bytes()
+            })
+            .create_async()
+            .await;
+
+        let mut config = config_for_test(&server, Some("test-key"));
+        config.segment_secs = 20;
+        let provider = provider_with_config(config);
+        let deltas = Arc::new(Mutex::new(Vec::<String>::new()));
+        let captured_deltas = Arc::clone(&deltas);
+        let options = TranscriptionOptions {
+            on_delta: Some(Arc::new(move |delta| {
+                captured_deltas
+                    .lock()
+                    .expect("delta lock should not be poisoned")
+                    .push(delta);
+            })),
+            ..TranscriptionOptions::default()
+        };
+        let wav = crate::audio_capture_service::pcm16_to_wav_bytes(&vec![0; 8_000 * 45], 8_000, 1)
+            .expect("wav should encode");
+
+        let result = provider
+            .transcribe(wav, options)
+            .await
+            .expect("segmented transcription should succeed");
+
+        segment_mock.assert_async().await;
+        assert_eq!(result.text, "first part second part third part");
+        assert_eq!(
+            deltas
+                .lock()
+                .expect("delta lock should not be poisoned")
+                .clone(),
+            vec![
+                "first part".to_string(),
+                " second part".to_string(),
+                " third part".to_string()
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn returns_authentication_error_for_unauthorized_response() {
         let mut server = Server::new_async().await;
@@ -908,6 +1603,32 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn returns_quota_error_for_insufficient_quota_response() {
+        let mut server = Server::new_async().await;
+        let request_mock = server
+            .mock("POST", "/v1/audio/transcriptions")
+            .with_status(429)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error":{"message":"Out of credits","code":"insufficient_quota"}}"#)
+            .create_async()
+            .await;
+
+        let mut config = config_for_test(&server, Some("test-key"));
+        config.retry_policy.max_retries = 0;
+        let provider = provider_with_config(config);
+        let error = provider
+            .transcribe(vec![1, 2, 3], TranscriptionOptions::default())
+            .await
+            .expect_err("request should fail");
+
+        request_mock.assert_async().await;
+        assert_eq!(
+            error,
+            TranscriptionError::QuotaExceeded("Out of credits".to_string())
+        );
+    }
+
     #[tokio::test]
     async fn retries_server_errors_then_returns_success() {
         let mut server = Server::new_async().await;
@@ -929,9 +1650,9 @@ mod tests {
             .await;
 
         let mut config = config_for_test(&server, Some("test-key"));
-        config.max_retries = 2;
-        config.retry_initial_backoff_ms = 80;
-        config.retry_max_backoff_ms = 80;
+        config.retry_policy.max_retries = 2;
+        config.retry_policy.initial_backoff_ms = 80;
+        config.retry_policy.max_backoff_ms = 80;
         let provider = provider_with_config(config);
 
         let started_at = Instant::now();
@@ -950,6 +1671,45 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn streams_spooled_file_uploads_and_reopens_them_on_retry() {
+        let mut server = Server::new_async().await;
+        let upload_body = Matcher::AllOf(vec![
+            Matcher::Regex(r#"filename="audio.wav""#.to_string()),
+            Matcher::Regex("spooled-recording-bytes".to_string()),
+        ]);
+        let server_error_mock = server
+            .mock("POST", "/v1/audio/transcriptions")
+            .match_body(upload_body.clone())
+            .expect(1)
+            .with_status(500)
+            .with_body(r#"{"error":{"message":"Service unavailable"}} "#)
+            .create_async()
+            .await;
+        let success_mock = server
+            .mock("POST", "/v1/audio/transcriptions")
+            .match_body(upload_body)
+            .expect(1)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"text":"from disk"}"#)
+            .create_async()
+            .await;
+
+        let path =
+            std::env::temp_dir().join(format!("voice-openai-upload-{}.wav", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"RIFF spooled-recording-bytes").expect("fixture should write");
+        let provider = provider_for_test(&server, Some("test-key"));
+        let result = provider
+            .transcribe_file(&path, TranscriptionOptions::default())
+            .await;
+        let _ = std::fs::remove_file(&path);
+
+        server_error_mock.assert_async().await;
+        success_mock.assert_async().await;
+        assert_eq!(result.expect("request should succeed").text, "from disk");
+    }
+
     #[tokio::test]
     async fn retries_rate_limited_responses_until_retry_limit() {
         let mut server = Server::new_async().await;
@@ -963,9 +1723,9 @@ mod tests {
             .await;
 
         let mut config = config_for_test(&server, Some("test-key"));
-        config.max_retries = 2;
-        config.retry_initial_backoff_ms = 80;
-        config.retry_max_backoff_ms = 80;
+        config.retry_policy.max_retries = 2;
+        config.retry_policy.initial_backoff_ms = 80;
+        config.retry_policy.max_backoff_ms = 80;
         let provider = provider_with_config(config);
 
         let started_at = Instant::now();
@@ -1008,9 +1768,9 @@ mod tests {
             .await;
 
         let mut config = config_for_test(&server, Some("test-key"));
-        config.max_retries = 1;
-        config.retry_initial_backoff_ms = 1;
-        config.retry_max_backoff_ms = 1;
+        config.retry_policy.max_retries = 1;
+        config.retry_policy.initial_backoff_ms = 1;
+        config.retry_policy.max_backoff_ms = 1;
         let provider = provider_with_config(config);
 
         let started_at = Instant::now();
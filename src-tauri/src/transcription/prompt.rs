@@ -0,0 +1,160 @@
+use super::language::{dictionary_hint, language_profile};
+use crate::settings_store::{
+    VoiceSettings, TRANSCRIPTION_STYLE_CASUAL, TRANSCRIPTION_STYLE_CLEAN,
+    TRANSCRIPTION_STYLE_CUSTOM, TRANSCRIPTION_STYLE_VERBATIM,
+};
+
+pub const CLEAN_TRANSCRIPTION_PROMPT: &str =
+    "Use proper punctuation, capitalization, and paragraph breaks. Write in complete sentences.";
+pub const CASUAL_TRANSCRIPTION_PROMPT: &str =
+    "Keep it casual and conversational. Use lowercase, minimal punctuation. Like texting a friend.";
+
+pub fn resolve_transcription_prompt(style: &str, custom_prompt: &str) -> Option<String> {
+    match style.trim().to_lowercase().as_str() {
+        TRANSCRIPTION_STYLE_CLEAN => Some(CLEAN_TRANSCRIPTION_PROMPT.to_string()),
+        TRANSCRIPTION_STYLE_CASUAL => Some(CASUAL_TRANSCRIPTION_PROMPT.to_string()),
+        TRANSCRIPTION_STYLE_VERBATIM => None,
+        TRANSCRIPTION_STYLE_CUSTOM => {
+            let trimmed = custom_prompt.trim();
+            if trimmed.is_empty() {
+                None
+            } else {
+                Some(trimmed.to_string())
+            }
+        }
+        _ => Some(CLEAN_TRANSCRIPTION_PROMPT.to_string()),
+    }
+}
+
+// The style bound to the dictation's language wins over the default one, and
+// the language dictionaries ride along as spelling hints.
+pub fn transcription_prompt(settings: &VoiceSettings, language: Option<&str>) -> Option<String> {
+    let style = transcription_profile(settings, language);
+    let prompt = resolve_transcription_prompt(style, &settings.custom_transcription_prompt);
+    match (prompt, dictionary_hint(settings, language)) {
+        (Some(prompt), Some(dictionary)) => Some(format!("{prompt}\n{dictionary}")),
+        (prompt, dictionary) => prompt.or(dictionary),
+    }
+}
+
+pub fn transcription_profile<'a>(settings: &'a VoiceSettings, language: Option<&str>) -> &'a str {
+    language_profile(settings, language)
+        .and_then(|profile| profile.transcription_style.as_deref())
+        .unwrap_or(&settings.transcription_style)
+}
+
+// Background on top of the style: the profile's context prompt, then the text
+// last inserted into the target app, which Whisper reads as what came just
+// before the recording.
+pub fn transcription_context_hint(
+    settings: &VoiceSettings,
+    language: Option<&str>,
+    recent_text: Option<String>,
+) -> Option<String> {
+    let context_prompt = settings
+        .context_prompts
+        .get(transcription_profile(settings, language))
+        .cloned();
+    match (context_prompt, recent_text) {
+        (Some(context_prompt), Some(recent_text)) => {
+            Some(format!("{context_prompt}\n{recent_text}"))
+        }
+        (context_prompt, recent_text) => context_prompt.or(recent_text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{resolve_transcription_prompt, transcription_context_hint};
+    use crate::settings_store::{
+        LanguageProfile, VoiceSettings, TRANSCRIPTION_STYLE_CASUAL, TRANSCRIPTION_STYLE_CLEAN,
+        TRANSCRIPTION_STYLE_VERBATIM,
+    };
+
+    #[test]
+    fn resolve_transcription_prompt_returns_clean_preset() {
+        let prompt = resolve_transcription_prompt("clean", "ignored");
+        assert_eq!(
+            prompt,
+            Some(
+                "Use proper punctuation, capitalization, and paragraph breaks. Write in complete sentences."
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn resolve_transcription_prompt_returns_casual_preset() {
+        let prompt = resolve_transcription_prompt("casual", "ignored");
+        assert_eq!(
+            prompt,
+            Some(
+                "Keep it casual and conversational. Use lowercase, minimal punctuation. Like texting a friend."
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn resolve_transcription_prompt_returns_none_for_verbatim_preset() {
+        let prompt = resolve_transcription_prompt("verbatim", "ignored");
+        assert_eq!(prompt, None);
+    }
+
+    #[test]
+    fn resolve_transcription_prompt_returns_custom_prompt_for_custom_style() {
+        let prompt = resolve_transcription_prompt("custom", "  Include ums and pauses.  ");
+        assert_eq!(prompt, Some("Include ums and pauses.".to_string()));
+    }
+
+    #[test]
+    fn context_hint_follows_the_profile_in_effect() {
+        let settings = VoiceSettings {
+            context_prompts: BTreeMap::from([
+                (
+                    TRANSCRIPTION_STYLE_CLEAN.to_string(),
+                    "Kubernetes, Helm".to_string(),
+                ),
+                (
+                    TRANSCRIPTION_STYLE_CASUAL.to_string(),
+                    "Dana, Priya".to_string(),
+                ),
+            ]),
+            language_profiles: BTreeMap::from([(
+                "de".to_string(),
+                LanguageProfile {
+                    transcription_style: Some(TRANSCRIPTION_STYLE_CASUAL.to_string()),
+                    ..LanguageProfile::default()
+                },
+            )]),
+            ..VoiceSettings::default()
+        };
+
+        assert_eq!(
+            transcription_context_hint(&settings, None, None),
+            Some("Kubernetes, Helm".to_string())
+        );
+        assert_eq!(
+            transcription_context_hint(&settings, Some("de"), Some("see you at".to_string())),
+            Some("Dana, Priya\nsee you at".to_string())
+        );
+
+        let verbatim = VoiceSettings {
+            transcription_style: TRANSCRIPTION_STYLE_VERBATIM.to_string(),
+            ..settings
+        };
+        assert_eq!(
+            transcription_context_hint(&verbatim, None, Some("see you at".to_string())),
+            Some("see you at".to_string())
+        );
+        assert_eq!(transcription_context_hint(&verbatim, None, None), None);
+    }
+
+    #[test]
+    fn resolve_transcription_prompt_returns_none_for_empty_custom_prompt() {
+        let prompt = resolve_transcription_prompt("custom", "   ");
+        assert_eq!(prompt, None);
+    }
+}
@@ -1,21 +1,25 @@
-use std::{path::PathBuf, time::Duration};
+use std::{collections::BTreeMap, path::PathBuf, time::Duration};
 
 use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
 use futures_util::{SinkExt, StreamExt};
 use reqwest::Url;
 use serde_json::{json, Value};
 use tokio::sync::{mpsc, oneshot};
-use tokio_tungstenite::{
-    connect_async,
-    tungstenite::{self, client::IntoClientRequest, http::HeaderValue, Message},
+use tokio_tungstenite::tungstenite::{
+    self,
+    client::IntoClientRequest,
+    http::{HeaderName, HeaderValue},
+    Message,
 };
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
 #[cfg(not(test))]
 use crate::api_key_store::ApiKeyStore;
 
 use super::{
-    normalize_transcript_text, TranscriptionError, TranscriptionOptions, TranscriptionResult,
+    endpoint::SharedProviderConfig, normalize_transcript_text, TranscriptionError,
+    TranscriptionOptions, TranscriptionResult,
 };
 
 const DEFAULT_OPENAI_REALTIME_ENDPOINT: &str = "wss://api.openai.com/v1/realtime";
@@ -51,6 +55,10 @@ pub struct OpenAiRealtimeTranscriptionConfig {
     pub realtime_model: String,
     pub transcription_model: String,
     pub commit_timeout_secs: u64,
+    pub extra_headers: BTreeMap<String, String>,
+    // Settings-level endpoint, models and headers; these win over the fields
+    // above when set.
+    pub overrides: SharedProviderConfig,
 }
 
 impl Default for OpenAiRealtimeTranscriptionConfig {
@@ -62,6 +70,8 @@ impl Default for OpenAiRealtimeTranscriptionConfig {
             realtime_model: DEFAULT_OPENAI_REALTIME_MODEL.to_string(),
             transcription_model: DEFAULT_OPENAI_TRANSCRIPTION_MODEL.to_string(),
             commit_timeout_secs: DEFAULT_COMMIT_TIMEOUT_SECS,
+            extra_headers: BTreeMap::new(),
+            overrides: SharedProviderConfig::default(),
         }
     }
 }
@@ -120,21 +130,39 @@ impl OpenAiRealtimeTranscriptionClient {
     }
 
     pub fn model_supports_realtime(&self) -> bool {
-        model_supports_realtime(&self.config.realtime_model)
+        model_supports_realtime(&self.effective_config().realtime_model)
     }
 
-    pub fn model(&self) -> &str {
-        &self.config.realtime_model
+    pub fn model(&self) -> String {
+        self.effective_config().realtime_model
+    }
+
+    fn effective_config(&self) -> OpenAiRealtimeTranscriptionConfig {
+        let overrides = self.config.overrides.current();
+        let mut config = self.config.clone();
+        if let Some(endpoint) = overrides.realtime_endpoint() {
+            config.endpoint = endpoint;
+        }
+        if let Some(realtime_model) = overrides.realtime_model {
+            config.realtime_model = realtime_model;
+        }
+        if let Some(model) = overrides.model {
+            config.transcription_model = model;
+        }
+        config.extra_headers = overrides.extra_headers;
+        config
     }
 
     pub fn begin_session(
         &self,
         options: TranscriptionOptions,
+        pin_detected_language: bool,
     ) -> Result<RealtimeTranscriptionSession, TranscriptionError> {
-        if !self.model_supports_realtime() {
+        let runtime_config = self.effective_config();
+        if !model_supports_realtime(&runtime_config.realtime_model) {
             return Err(TranscriptionError::Provider(format!(
                 "Configured model `{}` does not support realtime transcription",
-                self.config.realtime_model
+                runtime_config.realtime_model
             )));
         }
 
@@ -145,9 +173,15 @@ impl OpenAiRealtimeTranscriptionClient {
         let (result_tx, result_rx) =
             oneshot::channel::<Result<TranscriptionResult, TranscriptionError>>();
 
-        let runtime_config = self.config.clone();
-        tauri::async_runtime::spawn(async move {
-            let result = run_realtime_session(runtime_config, api_key, options, command_rx).await;
+        spawn(async move {
+            let result = run_realtime_session(
+                runtime_config,
+                api_key,
+                options,
+                pin_detected_language,
+                command_rx,
+            )
+            .await;
             match &result {
                 Ok(transcription) => info!(
                     transcript_chars = transcription.text.chars().count(),
@@ -235,12 +269,26 @@ impl RealtimeAudioSender {
         }
     }
 
+    pub fn pin_language(&self, language: &str) -> Result<(), TranscriptionError> {
+        let Some(language) = normalize_optional_string(Some(language.to_string())) else {
+            return Ok(());
+        };
+
+        self.command_tx
+            .try_send(RealtimeCommand::PinLanguage(language))
+            .map_err(|_| {
+                TranscriptionError::Network(
+                    "Realtime transcription session is no longer active".to_string(),
+                )
+            })
+    }
+
     pub fn close(&self) {
         match self.command_tx.try_send(RealtimeCommand::Close) {
             Ok(()) | Err(mpsc::error::TrySendError::Closed(_)) => {}
             Err(mpsc::error::TrySendError::Full(_)) => {
                 let command_tx = self.command_tx.clone();
-                tauri::async_runtime::spawn(async move {
+                spawn(async move {
                     let _ = command_tx.send(RealtimeCommand::Close).await;
                 });
             }
@@ -282,7 +330,21 @@ impl RealtimeTranscriptionSession {
         self.audio_sender.close();
     }
 
-    pub async fn commit_and_wait(self) -> Result<TranscriptionResult, TranscriptionError> {
+    // Cancelling closes the socket instead of waiting for the server to
+    // finish a transcript nobody will use.
+    pub async fn commit_and_wait(
+        self,
+        cancellation: Option<&CancellationToken>,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let audio_sender = self.audio_sender.clone();
+        let result = super::run_cancellable(cancellation, self.commit_and_wait_uncancelled()).await;
+        if matches!(result, Err(TranscriptionError::Cancelled)) {
+            audio_sender.close();
+        }
+        result
+    }
+
+    async fn commit_and_wait_uncancelled(self) -> Result<TranscriptionResult, TranscriptionError> {
         let RealtimeTranscriptionSession {
             audio_sender,
             result_rx,
@@ -325,6 +387,7 @@ struct AudioChunk {
 #[derive(Debug)]
 enum RealtimeCommand {
     Append(AudioChunk),
+    PinLanguage(String),
     Commit,
     Close,
 }
@@ -345,6 +408,7 @@ async fn run_realtime_session(
     config: OpenAiRealtimeTranscriptionConfig,
     api_key: String,
     options: TranscriptionOptions,
+    pin_detected_language: bool,
     mut command_rx: mpsc::Receiver<RealtimeCommand>,
 ) -> Result<TranscriptionResult, TranscriptionError> {
     let endpoint = resolve_realtime_endpoint(&config.endpoint)?;
@@ -366,6 +430,15 @@ async fn run_realtime_session(
         "OpenAI-Beta",
         HeaderValue::from_static(OPENAI_REALTIME_BETA_HEADER_VALUE),
     );
+    for (name, value) in &config.extra_headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+            TranscriptionError::Provider(format!("Invalid realtime header name `{name}`"))
+        })?;
+        let header_value = HeaderValue::from_str(value).map_err(|_| {
+            TranscriptionError::Provider(format!("Invalid value for realtime header `{name}`"))
+        })?;
+        request.headers_mut().insert(header_name, header_value);
+    }
 
     info!(
         endpoint = %endpoint,
@@ -374,33 +447,34 @@ async fn run_realtime_session(
         "connecting realtime transcription websocket"
     );
     let connect_timeout = Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS);
-    let (ws_stream, response) = tokio::time::timeout(connect_timeout, connect_async(request))
-        .await
-        .map_err(|_| {
-            let mapped = TranscriptionError::Network(format!(
-                "Timed out connecting realtime transcription websocket after {}s",
-                connect_timeout.as_secs()
-            ));
-            warn!(
-                endpoint = %endpoint,
-                realtime_model = %config.realtime_model,
-                transcription_model = %config.transcription_model,
-                error = %mapped,
-                "failed to connect realtime transcription websocket"
-            );
-            mapped
-        })?
-        .map_err(|error| {
-            let mapped = map_websocket_error(error);
-            warn!(
-                endpoint = %endpoint,
-                realtime_model = %config.realtime_model,
-                transcription_model = %config.transcription_model,
-                error = %mapped,
-                "failed to connect realtime transcription websocket"
-            );
-            mapped
-        })?;
+    let (ws_stream, response) =
+        tokio::time::timeout(connect_timeout, crate::proxy::connect_websocket(request))
+            .await
+            .map_err(|_| {
+                let mapped = TranscriptionError::Network(format!(
+                    "Timed out connecting realtime transcription websocket after {}s",
+                    connect_timeout.as_secs()
+                ));
+                warn!(
+                    endpoint = %endpoint,
+                    realtime_model = %config.realtime_model,
+                    transcription_model = %config.transcription_model,
+                    error = %mapped,
+                    "failed to connect realtime transcription websocket"
+                );
+                mapped
+            })?
+            .map_err(|error| {
+                let mapped = map_websocket_error(error);
+                warn!(
+                    endpoint = %endpoint,
+                    realtime_model = %config.realtime_model,
+                    transcription_model = %config.transcription_model,
+                    error = %mapped,
+                    "failed to connect realtime transcription websocket"
+                );
+                mapped
+            })?;
     info!(
         endpoint = %endpoint,
         status = %response.status(),
@@ -428,6 +502,9 @@ async fn run_realtime_session(
         })?;
 
     let request_language = normalize_optional_string(options.language.clone());
+    // A language from settings is already pinned by the initial session update.
+    let mut pinned_language = request_language.clone();
+    let mut detected_language: Option<String> = None;
     let on_delta = options.on_delta.clone();
     let mut transcript_from_deltas = String::new();
     let mut transcript_done: Option<String> = None;
@@ -469,6 +546,22 @@ async fn run_realtime_session(
                                 mapped
                             })?;
                     }
+                    RealtimeCommand::PinLanguage(language) => {
+                        if commit_sent || pinned_language.as_deref() == Some(language.as_str()) {
+                            continue;
+                        }
+                        let payload = build_language_pin_payload(&config, &options, &language);
+                        ws_writer
+                            .send(Message::Text(payload.to_string().into()))
+                            .await
+                            .map_err(|error| {
+                                let mapped = map_websocket_error(error);
+                                warn!(error = %mapped, "failed to send realtime language pin");
+                                mapped
+                            })?;
+                        info!(language = %language, "pinned realtime session language");
+                        pinned_language = Some(language);
+                    }
                     RealtimeCommand::Commit => {
                         if commit_sent {
                             continue;
@@ -514,6 +607,26 @@ async fn run_realtime_session(
                                 "Realtime websocket payload was not valid JSON: {error}",
                             ))
                         })?;
+                        if let Some(language) = extract_detected_language(&payload) {
+                            if pin_detected_language && pinned_language.is_none() && !commit_sent {
+                                let pin_payload =
+                                    build_language_pin_payload(&config, &options, &language);
+                                ws_writer
+                                    .send(Message::Text(pin_payload.to_string().into()))
+                                    .await
+                                    .map_err(|error| {
+                                        let mapped = map_websocket_error(error);
+                                        warn!(error = %mapped, "failed to send realtime language pin");
+                                        mapped
+                                    })?;
+                                info!(
+                                    language = %language,
+                                    "pinned realtime session language from first detected segment"
+                                );
+                                pinned_language = Some(language.clone());
+                            }
+                            detected_language.get_or_insert(language);
+                        }
                         match parse_server_event(&payload) {
                             ParsedServerEvent::SessionCreated => {
                                 debug!("realtime session created");
@@ -584,12 +697,27 @@ async fn run_realtime_session(
 
     Ok(TranscriptionResult {
         text: normalize_transcript_text(&final_text),
-        language: request_language,
+        language: pinned_language.or(detected_language),
+        language_confidence: None,
         duration_secs: None,
         confidence: None,
+        segments: Vec::new(),
+        words: Vec::new(),
     })
 }
 
+// The app runs sessions on Tauri's runtime, which is reachable from any
+// thread; embedders of the engine drive it from their own Tokio runtime.
+fn spawn<F>(task: F)
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    #[cfg(feature = "tauri")]
+    tauri::async_runtime::spawn(task);
+    #[cfg(not(feature = "tauri"))]
+    tokio::spawn(task);
+}
+
 fn resolve_realtime_endpoint(endpoint: &str) -> Result<String, TranscriptionError> {
     let mut url = Url::parse(endpoint).map_err(|error| {
         TranscriptionError::Provider(format!(
@@ -713,6 +841,29 @@ fn build_session_update_payload(
     })
 }
 
+// Re-sends the full transcription config because a session update replaces the
+// previous `input_audio_transcription` object rather than merging into it.
+fn build_language_pin_payload(
+    config: &OpenAiRealtimeTranscriptionConfig,
+    options: &TranscriptionOptions,
+    language: &str,
+) -> Value {
+    let pinned_options = TranscriptionOptions {
+        language: Some(language.to_string()),
+        on_delta: None,
+        ..options.clone()
+    };
+    build_session_update_payload(config, &pinned_options)
+}
+
+fn extract_detected_language(payload: &Value) -> Option<String> {
+    extract_first_text(
+        payload,
+        &["language", "/item/language", "/transcript/language"],
+    )
+    .map(|language| language.trim().to_lowercase())
+}
+
 fn build_prompt(prompt: Option<String>, context_hint: Option<String>) -> Option<String> {
     match (
         normalize_optional_string(prompt),
@@ -745,7 +896,11 @@ fn model_supports_realtime(model: &str) -> bool {
     normalized.contains("realtime")
 }
 
-fn resample_pcm16_linear(input: &[i16], input_rate_hz: u32, output_rate_hz: u32) -> Vec<i16> {
+pub(crate) fn resample_pcm16_linear(
+    input: &[i16],
+    input_rate_hz: u32,
+    output_rate_hz: u32,
+) -> Vec<i16> {
     if input.is_empty() {
         return Vec::new();
     }
@@ -895,6 +1050,46 @@ mod tests {
         assert_eq!(payload["session"]["turn_detection"], Value::Null);
     }
 
+    #[test]
+    fn build_language_pin_payload_keeps_model_and_prompt() {
+        let config = OpenAiRealtimeTranscriptionConfig::default();
+        let payload = build_language_pin_payload(
+            &config,
+            &TranscriptionOptions {
+                prompt: Some("Dictation".to_string()),
+                ..TranscriptionOptions::default()
+            },
+            "de",
+        );
+
+        assert_eq!(payload["type"], "transcription_session.update");
+        assert_eq!(
+            payload["session"]["input_audio_transcription"]["language"],
+            Value::String("de".to_string())
+        );
+        assert_eq!(
+            payload["session"]["input_audio_transcription"]["model"],
+            Value::String(config.transcription_model.clone())
+        );
+        assert_eq!(
+            payload["session"]["input_audio_transcription"]["prompt"],
+            Value::String("Dictation".to_string())
+        );
+    }
+
+    #[test]
+    fn extract_detected_language_reads_event_and_item_fields() {
+        assert_eq!(
+            extract_detected_language(&json!({ "type": "x", "language": "EN " })).as_deref(),
+            Some("en")
+        );
+        assert_eq!(
+            extract_detected_language(&json!({ "item": { "language": "es" } })).as_deref(),
+            Some("es")
+        );
+        assert_eq!(extract_detected_language(&json!({ "delta": "hola" })), None);
+    }
+
     #[test]
     fn resolve_realtime_endpoint_enforces_transcription_intent_and_strips_model() {
         let endpoint = "wss://api.openai.com/v1/realtime";
@@ -990,7 +1185,7 @@ mod tests {
         };
 
         let error = session
-            .commit_and_wait()
+            .commit_and_wait(None)
             .await
             .expect_err("commit should surface the session error");
         assert_eq!(error, expected_error);
@@ -1114,15 +1309,18 @@ mod tests {
         let delta_text_for_callback = Arc::clone(&delta_text);
         let client = OpenAiRealtimeTranscriptionClient::new(config);
         let session = client
-            .begin_session(TranscriptionOptions {
-                on_delta: Some(Arc::new(move |delta| {
-                    delta_text_for_callback
-                        .lock()
-                        .expect("delta lock should not be poisoned")
-                        .push_str(&delta);
-                })),
-                ..TranscriptionOptions::default()
-            })
+            .begin_session(
+                TranscriptionOptions {
+                    on_delta: Some(Arc::new(move |delta| {
+                        delta_text_for_callback
+                            .lock()
+                            .expect("delta lock should not be poisoned")
+                            .push_str(&delta);
+                    })),
+                    ..TranscriptionOptions::default()
+                },
+                false,
+            )
             .expect("session should start");
 
         let sender = session.audio_sender();
@@ -1132,7 +1330,7 @@ mod tests {
         assert_eq!(append_outcome, RealtimeAppendOutcome::Queued);
 
         let result = session
-            .commit_and_wait()
+            .commit_and_wait(None)
             .await
             .expect("session should return transcript");
         server_task
@@ -1162,4 +1360,91 @@ mod tests {
         assert!(request_uri.contains("intent=transcription"));
         assert!(!request_uri.contains("model="));
     }
+
+    #[tokio::test]
+    async fn pin_language_sends_session_update_before_commit() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let address = listener
+            .local_addr()
+            .expect("listener should have local address");
+
+        let server_task = tokio::spawn(async move {
+            let (stream, _) = listener
+                .accept()
+                .await
+                .expect("server should accept connection");
+            let ws_stream = tokio_tungstenite::accept_async(stream)
+                .await
+                .expect("server handshake should succeed");
+            let (mut write, mut read) = ws_stream.split();
+
+            let mut payloads = Vec::new();
+            while payloads.len() < 3 {
+                let message = read
+                    .next()
+                    .await
+                    .expect("client message should arrive")
+                    .expect("client frame should decode");
+                let text = message.into_text().expect("client message should be text");
+                payloads.push(
+                    serde_json::from_str::<Value>(text.as_ref()).expect("client JSON should parse"),
+                );
+            }
+
+            assert_eq!(payloads[0]["type"], "transcription_session.update");
+            assert_eq!(
+                payloads[0]["session"]["input_audio_transcription"]["language"],
+                Value::Null
+            );
+            assert_eq!(payloads[1]["type"], "transcription_session.update");
+            assert_eq!(
+                payloads[1]["session"]["input_audio_transcription"]["language"],
+                Value::String("fr".to_string())
+            );
+            assert_eq!(payloads[2]["type"], "input_audio_buffer.commit");
+
+            write
+                .send(Message::Text(
+                    json!({
+                        "type": "conversation.item.input_audio_transcription.completed",
+                        "transcript": "bonjour"
+                    })
+                    .to_string()
+                    .into(),
+                ))
+                .await
+                .expect("server should send completed transcript");
+        });
+
+        let config = OpenAiRealtimeTranscriptionConfig {
+            endpoint: format!("ws://{address}"),
+            api_key: Some("test-key".to_string()),
+            commit_timeout_secs: 5,
+            ..OpenAiRealtimeTranscriptionConfig::default()
+        };
+
+        let session = OpenAiRealtimeTranscriptionClient::new(config)
+            .begin_session(TranscriptionOptions::default(), true)
+            .expect("session should start");
+        let sender = session.audio_sender();
+        sender
+            .pin_language(" fr ")
+            .expect("language pin should be queued");
+        sender
+            .pin_language("fr")
+            .expect("repeated language pin should be queued");
+
+        let result = session
+            .commit_and_wait(None)
+            .await
+            .expect("session should return transcript");
+        server_task
+            .await
+            .expect("server task should finish without panic");
+
+        assert_eq!(result.text, "bonjour");
+        assert_eq!(result.language.as_deref(), Some("fr"));
+    }
 }
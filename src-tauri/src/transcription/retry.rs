@@ -0,0 +1,328 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::{
+    header::{HeaderMap, RETRY_AFTER},
+    StatusCode,
+};
+use schemars::JsonSchema;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use super::TranscriptionError;
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_INITIAL_BACKOFF_MS: u64 = 500;
+const DEFAULT_MAX_BACKOFF_MS: u64 = 5_000;
+
+pub type TranscriptionRetryCallback = Arc<dyn Fn(TranscriptionRetryEvent) + Send + Sync + 'static>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryClass {
+    RateLimited,
+    ServerError,
+    Timeout,
+    Network,
+}
+
+impl RetryClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RateLimited => "rate_limited",
+            Self::ServerError => "server_error",
+            Self::Timeout => "timeout",
+            Self::Network => "network",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "rate_limited" | "rate_limit" | "429" => Some(Self::RateLimited),
+            "server_error" | "5xx" => Some(Self::ServerError),
+            "timeout" => Some(Self::Timeout),
+            "network" => Some(Self::Network),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub retry_on: Vec<RetryClass>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff_ms: DEFAULT_INITIAL_BACKOFF_MS,
+            max_backoff_ms: DEFAULT_MAX_BACKOFF_MS,
+            retry_on: vec![
+                RetryClass::RateLimited,
+                RetryClass::ServerError,
+                RetryClass::Timeout,
+                RetryClass::Network,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    // Reads `<prefix>_MAX_RETRIES`, `<prefix>_RETRY_INITIAL_BACKOFF_MS`,
+    // `<prefix>_RETRY_MAX_BACKOFF_MS` and a comma-separated `<prefix>_RETRY_ON`.
+    pub fn from_env(prefix: &str) -> Self {
+        let mut policy = Self::default();
+
+        if let Some(max_retries) = read_u32_env(&format!("{prefix}_MAX_RETRIES")) {
+            policy.max_retries = max_retries;
+        }
+
+        if let Some(initial_backoff_ms) =
+            read_u64_env(&format!("{prefix}_RETRY_INITIAL_BACKOFF_MS"))
+        {
+            policy.initial_backoff_ms = initial_backoff_ms.max(1);
+        }
+
+        if let Some(max_backoff_ms) = read_u64_env(&format!("{prefix}_RETRY_MAX_BACKOFF_MS")) {
+            policy.max_backoff_ms = max_backoff_ms.max(1);
+        }
+
+        if policy.initial_backoff_ms > policy.max_backoff_ms {
+            policy.initial_backoff_ms = policy.max_backoff_ms;
+        }
+
+        if let Ok(retry_on) = std::env::var(format!("{prefix}_RETRY_ON")) {
+            policy.retry_on = parse_retry_on(&retry_on);
+        }
+
+        debug!(
+            prefix,
+            max_retries = policy.max_retries,
+            initial_backoff_ms = policy.initial_backoff_ms,
+            max_backoff_ms = policy.max_backoff_ms,
+            retry_on = ?policy.retry_on,
+            "loaded transcription retry policy"
+        );
+        policy
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_retries.saturating_add(1)
+    }
+
+    pub fn should_retry(&self, class: RetryClass, attempt_index: u32) -> bool {
+        attempt_index < self.max_retries && self.retry_on.contains(&class)
+    }
+
+    pub fn delay(
+        &self,
+        attempt_index: u32,
+        retry_after: Option<Duration>,
+        jitter_seed: u64,
+    ) -> Duration {
+        if let Some(delay) = retry_after {
+            return delay;
+        }
+
+        let growth_factor = 1_u64.checked_shl(attempt_index.min(20)).unwrap_or(u64::MAX);
+        let uncapped_ms = self.initial_backoff_ms.saturating_mul(growth_factor);
+        let capped_ms = uncapped_ms.min(self.max_backoff_ms).max(1);
+
+        // Equal jitter: spread retries in [base/2, base] to reduce thundering herd while
+        // retaining monotonic growth.
+        let half_ms = capped_ms / 2;
+        let jitter_span_ms = capped_ms.saturating_sub(half_ms);
+        let jitter_offset = if jitter_span_ms == 0 {
+            0
+        } else {
+            pseudo_random(jitter_seed, attempt_index) % (jitter_span_ms + 1)
+        };
+
+        Duration::from_millis(half_ms.saturating_add(jitter_offset).max(1))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionRetryEvent {
+    pub provider: String,
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub delay_ms: u64,
+    pub reason: String,
+    pub message: String,
+}
+
+// Logs the retry and tells the caller which attempt comes next, e.g. the
+// second of three.
+pub fn report_retry(
+    on_retry: Option<&TranscriptionRetryCallback>,
+    provider: &str,
+    policy: &RetryPolicy,
+    attempt_index: u32,
+    class: RetryClass,
+    delay: Duration,
+    error: &TranscriptionError,
+) {
+    let event = TranscriptionRetryEvent {
+        provider: provider.to_string(),
+        attempt: attempt_index + 2,
+        max_attempts: policy.max_attempts(),
+        delay_ms: delay.as_millis() as u64,
+        reason: class.as_str().to_string(),
+        message: error.to_string(),
+    };
+    warn!(
+        provider,
+        attempt = event.attempt,
+        max_attempts = event.max_attempts,
+        delay_ms = event.delay_ms,
+        reason = %event.reason,
+        error = %error,
+        "retrying transcription request"
+    );
+    if let Some(callback) = on_retry {
+        callback(event);
+    }
+}
+
+pub fn classify_status(status: StatusCode) -> Option<RetryClass> {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => Some(RetryClass::RateLimited),
+        StatusCode::REQUEST_TIMEOUT => Some(RetryClass::Timeout),
+        _ if status.is_server_error() => Some(RetryClass::ServerError),
+        _ => None,
+    }
+}
+
+pub fn classify_transport_error(error: &reqwest::Error) -> Option<RetryClass> {
+    if error.is_timeout() {
+        Some(RetryClass::Timeout)
+    } else if error.is_connect() {
+        Some(RetryClass::Network)
+    } else {
+        None
+    }
+}
+
+pub fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header_value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if header_value.is_empty() {
+        return None;
+    }
+
+    if let Ok(seconds) = header_value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let retry_at = httpdate::parse_http_date(header_value).ok()?;
+    let now = SystemTime::now();
+    Some(
+        retry_at
+            .duration_since(now)
+            .unwrap_or(Duration::from_secs(0)),
+    )
+}
+
+pub fn seed_from_clock() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0xA5A5_A5A5_A5A5_A5A5)
+}
+
+fn pseudo_random(seed: u64, attempt_index: u32) -> u64 {
+    let mut state = seed ^ (attempt_index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    state ^= state >> 12;
+    state ^= state << 25;
+    state ^= state >> 27;
+    state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+}
+
+fn parse_retry_on(value: &str) -> Vec<RetryClass> {
+    let mut classes = Vec::new();
+    for token in value
+        .split(',')
+        .map(|token| token.trim().to_ascii_lowercase())
+        .filter(|token| !token.is_empty() && token != "none")
+    {
+        match RetryClass::parse(&token) {
+            Some(class) if !classes.contains(&class) => classes.push(class),
+            Some(_) => {}
+            None => warn!(token = %token, "ignoring unknown transcription retry class"),
+        }
+    }
+    classes
+}
+
+fn read_u64_env(name: &str) -> Option<u64> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .and_then(|value| value.parse::<u64>().ok())
+}
+
+fn read_u32_env(name: &str) -> Option<u32> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .and_then(|value| value.parse::<u32>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use reqwest::StatusCode;
+
+    use super::{classify_status, parse_retry_on, RetryClass, RetryPolicy};
+
+    #[test]
+    fn retries_only_configured_classes_within_the_attempt_budget() {
+        let policy = RetryPolicy {
+            max_retries: 2,
+            retry_on: parse_retry_on("rate_limit, 5xx, bogus, rate_limited"),
+            ..RetryPolicy::default()
+        };
+
+        assert_eq!(
+            policy.retry_on,
+            vec![RetryClass::RateLimited, RetryClass::ServerError]
+        );
+        assert_eq!(policy.max_attempts(), 3);
+        assert!(policy.should_retry(RetryClass::ServerError, 1));
+        assert!(!policy.should_retry(RetryClass::ServerError, 2));
+        assert!(!policy.should_retry(RetryClass::Timeout, 0));
+        assert_eq!(
+            classify_status(StatusCode::BAD_GATEWAY),
+            Some(RetryClass::ServerError)
+        );
+        assert_eq!(classify_status(StatusCode::BAD_REQUEST), None);
+        assert!(parse_retry_on("none").is_empty());
+    }
+
+    #[test]
+    fn backoff_grows_up_to_the_cap_and_honors_retry_after() {
+        let policy = RetryPolicy {
+            initial_backoff_ms: 100,
+            max_backoff_ms: 300,
+            ..RetryPolicy::default()
+        };
+
+        let first = policy.delay(0, None, 7);
+        let capped = policy.delay(5, None, 7);
+        assert!((50..=100).contains(&(first.as_millis() as u64)));
+        assert!((150..=300).contains(&(capped.as_millis() as u64)));
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(2)), 7),
+            Duration::from_secs(2)
+        );
+    }
+}
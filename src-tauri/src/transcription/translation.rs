@@ -0,0 +1,315 @@
+use std::{path::PathBuf, time::Duration};
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+#[cfg(not(test))]
+use crate::api_key_store::ApiKeyStore;
+
+use super::{
+    openai::{is_openai_quota_error, parse_openai_error_message},
+    TranscriptionError,
+};
+
+const DEFAULT_TRANSLATION_ENDPOINT: &str = "https://api.openai.com/v1/chat/completions";
+const DEFAULT_TRANSLATION_MODEL: &str = "gpt-4o-mini";
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 60;
+pub const DEFAULT_TRANSLATION_TARGET_LANGUAGE: &str = "English";
+
+#[derive(Debug, Clone)]
+pub struct OpenAiTranslationConfig {
+    pub api_key: Option<String>,
+    pub api_key_store_app_data_dir: Option<PathBuf>,
+    pub endpoint: String,
+    pub model: String,
+    pub request_timeout_secs: u64,
+}
+
+impl Default for OpenAiTranslationConfig {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            api_key_store_app_data_dir: None,
+            endpoint: DEFAULT_TRANSLATION_ENDPOINT.to_string(),
+            model: DEFAULT_TRANSLATION_MODEL.to_string(),
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl OpenAiTranslationConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Some(model) = read_non_empty_env("OPENAI_TRANSLATION_MODEL") {
+            config.model = model;
+        }
+
+        if let Some(endpoint) = read_non_empty_env("OPENAI_TRANSLATION_ENDPOINT") {
+            config.endpoint = endpoint;
+        }
+
+        if let Some(timeout_secs) = read_non_empty_env("OPENAI_TRANSLATION_TIMEOUT_SECS")
+            .and_then(|value| value.parse::<u64>().ok())
+        {
+            config.request_timeout_secs = timeout_secs.max(1);
+        }
+
+        debug!(
+            endpoint = %config.endpoint,
+            model = %config.model,
+            request_timeout_secs = config.request_timeout_secs,
+            "loaded OpenAI translation config"
+        );
+        config
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ChatCompletionRequest<'a> {
+    model: &'a str,
+    temperature: f32,
+    messages: [ChatMessage<'a>; 2],
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    #[serde(default)]
+    choices: Vec<ChatCompletionChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChoice {
+    message: ChatCompletionMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+// Translates finished transcripts with a chat model; unlike the audio
+// translations endpoint this works with every transcription model.
+#[derive(Debug, Clone)]
+pub struct OpenAiTranslationClient {
+    config: OpenAiTranslationConfig,
+}
+
+impl OpenAiTranslationClient {
+    pub fn new(config: OpenAiTranslationConfig) -> Self {
+        info!(
+            endpoint = %config.endpoint,
+            model = %config.model,
+            "OpenAI translation client initialized"
+        );
+        Self { config }
+    }
+
+    pub async fn translate(
+        &self,
+        text: &str,
+        target_language: &str,
+    ) -> Result<String, TranscriptionError> {
+        let instructions = format!(
+            "Translate the user's dictated text into {target_language}. Keep the meaning, tone and formatting. Reply with the translation only."
+        );
+        debug!(
+            model = %self.config.model,
+            target_language,
+            text_chars = text.chars().count(),
+            "sending translation request"
+        );
+        self.complete(&instructions, text, "translation").await
+    }
+
+    // Runs a stored transcript through a style's instructions, the way a
+    // recording would have been shaped had the style been active.
+    pub async fn rewrite(
+        &self,
+        text: &str,
+        style_instructions: &str,
+    ) -> Result<String, TranscriptionError> {
+        let instructions = format!(
+            "Rewrite the user's dictated text following these instructions: {style_instructions}\nKeep the meaning and the language. Reply with the rewritten text only."
+        );
+        debug!(
+            model = %self.config.model,
+            text_chars = text.chars().count(),
+            "sending rewrite request"
+        );
+        self.complete(&instructions, text, "rewrite").await
+    }
+
+    async fn complete(
+        &self,
+        instructions: &str,
+        text: &str,
+        label: &str,
+    ) -> Result<String, TranscriptionError> {
+        let api_key = self.api_key()?;
+        let request = ChatCompletionRequest {
+            model: &self.config.model,
+            temperature: 0.0,
+            messages: [
+                ChatMessage {
+                    role: "system",
+                    content: instructions,
+                },
+                ChatMessage {
+                    role: "user",
+                    content: text,
+                },
+            ],
+        };
+
+        let response = crate::http_client::shared_client()
+            .post(&self.config.endpoint)
+            .bearer_auth(api_key)
+            .timeout(Duration::from_secs(self.config.request_timeout_secs))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|error| TranscriptionError::Network(error.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let message = parse_openai_error_message(&body).unwrap_or_else(|| {
+                format!("OpenAI {label} failed with status {}", status.as_u16())
+            });
+            return Err(match status {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    TranscriptionError::Authentication(message)
+                }
+                StatusCode::TOO_MANY_REQUESTS if is_openai_quota_error(&body) => {
+                    TranscriptionError::QuotaExceeded(message)
+                }
+                StatusCode::TOO_MANY_REQUESTS => TranscriptionError::RateLimited(message),
+                _ => TranscriptionError::Provider(message),
+            });
+        }
+
+        let parsed = response
+            .json::<ChatCompletionResponse>()
+            .await
+            .map_err(|error| TranscriptionError::InvalidResponse(error.to_string()))?;
+        parsed
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .map(|content| content.trim().to_string())
+            .filter(|content| !content.is_empty())
+            .ok_or_else(|| {
+                TranscriptionError::InvalidResponse(format!(
+                    "OpenAI {label} response did not contain any text"
+                ))
+            })
+    }
+
+    fn api_key(&self) -> Result<String, TranscriptionError> {
+        if let Some(explicit_key) = self
+            .config
+            .api_key
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        {
+            return Ok(explicit_key.to_string());
+        }
+
+        #[cfg(not(test))]
+        {
+            if let Some(app_data_dir) = self.config.api_key_store_app_data_dir.clone() {
+                match ApiKeyStore::new(app_data_dir).get_api_key("openai") {
+                    Ok(Some(stored_key)) => return Ok(stored_key),
+                    Ok(None) => {}
+                    Err(error) => {
+                        if let Some(env_key) = read_non_empty_env("OPENAI_API_KEY") {
+                            tracing::warn!(
+                                error = %error,
+                                "falling back to OPENAI_API_KEY after API key file read failure"
+                            );
+                            return Ok(env_key);
+                        }
+
+                        return Err(TranscriptionError::Provider(format!(
+                            "Unable to read API key from local API key store: {error}",
+                        )));
+                    }
+                }
+            }
+        }
+
+        read_non_empty_env("OPENAI_API_KEY").ok_or(TranscriptionError::MissingApiKey)
+    }
+}
+
+// Providers report either ISO codes or full names ("en", "english").
+pub fn is_language(detected: Option<&str>, target_language: &str) -> bool {
+    let Some(detected) = detected.map(str::trim).filter(|value| !value.is_empty()) else {
+        return false;
+    };
+    let target = target_language.trim();
+    detected.eq_ignore_ascii_case(target)
+        || (target.len() > 2 && detected.len() == 2 && target[..2].eq_ignore_ascii_case(detected))
+}
+
+fn read_non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name).ok().and_then(|value| {
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(trimmed.to_string())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Server};
+
+    use super::{is_language, OpenAiTranslationClient, OpenAiTranslationConfig};
+
+    #[tokio::test]
+    async fn translates_text_with_a_chat_completion() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/chat/completions")
+            .match_header("authorization", "Bearer test-key")
+            .match_body(Matcher::PartialJsonString(
+                r#"{"messages":[{"role":"system"},{"role":"user","content":"hola mundo"}]}"#
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(r#"{"choices":[{"message":{"content":" hello world \n"}}]}"#)
+            .create_async()
+            .await;
+        let client = OpenAiTranslationClient::new(OpenAiTranslationConfig {
+            api_key: Some("test-key".to_string()),
+            endpoint: format!("{}/v1/chat/completions", server.url()),
+            ..OpenAiTranslationConfig::default()
+        });
+
+        let translated = client
+            .translate("hola mundo", "English")
+            .await
+            .expect("translation should succeed");
+
+        mock.assert_async().await;
+        assert_eq!(translated, "hello world");
+        assert!(is_language(Some("en"), "English"));
+        assert!(is_language(Some("english"), "English"));
+        assert!(!is_language(Some("es"), "English"));
+        assert!(!is_language(None, "English"));
+    }
+}
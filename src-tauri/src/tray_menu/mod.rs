@@ -0,0 +1,408 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use tauri::{
+    image::Image,
+    menu::{CheckMenuItem, Menu, MenuItem, Submenu},
+    AppHandle, Manager, Wry,
+};
+use tracing::{debug, warn};
+
+use crate::{
+    audio_capture_service::MicrophoneInfo,
+    settings_store::{
+        VoiceSettings, VoiceSettingsUpdate, RECORDING_MODE_HOLD_TO_TALK, RECORDING_MODE_TOGGLE,
+        TRANSCRIPTION_STYLE_CASUAL, TRANSCRIPTION_STYLE_CLEAN, TRANSCRIPTION_STYLE_CUSTOM,
+        TRANSCRIPTION_STYLE_VERBATIM,
+    },
+};
+
+pub const TRAY_ID: &str = "voice-tray";
+pub const MENU_RECORDING_TIMER: &str = "recording_timer";
+pub const MENU_CANCEL_RECORDING: &str = "cancel_recording";
+pub const MENU_MICROPHONES: &str = "microphones";
+pub const MENU_RECORDING_MODES: &str = "recording_modes";
+pub const MENU_PROFILES: &str = "profiles";
+// Quick setting items carry their value after the prefix; an empty microphone
+// id stands for the system default.
+const MENU_MICROPHONE_PREFIX: &str = "microphone:";
+const MENU_RECORDING_MODE_PREFIX: &str = "recording_mode:";
+const MENU_PROFILE_PREFIX: &str = "profile:";
+const RECORDING_MODE_CHOICES: [(&str, &str); 2] = [
+    (RECORDING_MODE_HOLD_TO_TALK, "Hold to Talk"),
+    (RECORDING_MODE_TOGGLE, "Toggle"),
+];
+const PROFILE_CHOICES: [(&str, &str); 4] = [
+    (TRANSCRIPTION_STYLE_CLEAN, "Clean"),
+    (TRANSCRIPTION_STYLE_CASUAL, "Casual"),
+    (TRANSCRIPTION_STYLE_VERBATIM, "Verbatim"),
+    (TRANSCRIPTION_STYLE_CUSTOM, "Custom"),
+];
+const TIMER_TICK: Duration = Duration::from_secs(1);
+const RECORDING_ICON_SIZE: u32 = 32;
+const RECORDING_ICON_RADIUS: f32 = 11.0;
+
+// A settings change picked from the tray menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuickSetting {
+    Microphone(Option<String>),
+    RecordingMode(String),
+    Profile(String),
+}
+
+impl QuickSetting {
+    pub fn from_menu_id(menu_id: &str) -> Option<Self> {
+        if let Some(microphone_id) = menu_id.strip_prefix(MENU_MICROPHONE_PREFIX) {
+            return Some(Self::Microphone(
+                Some(microphone_id.to_string()).filter(|id| !id.is_empty()),
+            ));
+        }
+        if let Some(mode) = menu_id.strip_prefix(MENU_RECORDING_MODE_PREFIX) {
+            return Some(Self::RecordingMode(mode.to_string()));
+        }
+        menu_id
+            .strip_prefix(MENU_PROFILE_PREFIX)
+            .map(|profile| Self::Profile(profile.to_string()))
+    }
+
+    pub fn into_update(self) -> VoiceSettingsUpdate {
+        match self {
+            Self::Microphone(microphone_id) => VoiceSettingsUpdate {
+                microphone_id: Some(microphone_id),
+                ..VoiceSettingsUpdate::default()
+            },
+            Self::RecordingMode(mode) => VoiceSettingsUpdate {
+                recording_mode: Some(mode),
+                ..VoiceSettingsUpdate::default()
+            },
+            Self::Profile(profile) => VoiceSettingsUpdate {
+                transcription_style: Some(profile),
+                ..VoiceSettingsUpdate::default()
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MenuChoice {
+    id: String,
+    label: String,
+    checked: bool,
+}
+
+// Recording-only menu items are inserted at the top of the tray menu while
+// listening and removed again afterwards. The quick settings submenus are
+// rebuilt whenever settings change, and when the pointer reaches the tray
+// icon so newly plugged-in microphones show up.
+pub struct TrayMenuState {
+    menu: Menu<Wry>,
+    microphone_menu: Submenu<Wry>,
+    recording_mode_menu: Submenu<Wry>,
+    profile_menu: Submenu<Wry>,
+    recording_timer_item: MenuItem<Wry>,
+    cancel_recording_item: MenuItem<Wry>,
+    idle_icon: Image<'static>,
+    recording_items_visible: AtomicBool,
+    timer_generation: AtomicU64,
+}
+
+impl TrayMenuState {
+    pub fn new(
+        menu: Menu<Wry>,
+        microphone_menu: Submenu<Wry>,
+        recording_mode_menu: Submenu<Wry>,
+        profile_menu: Submenu<Wry>,
+        recording_timer_item: MenuItem<Wry>,
+        cancel_recording_item: MenuItem<Wry>,
+        idle_icon: Image<'static>,
+    ) -> Self {
+        Self {
+            menu,
+            microphone_menu,
+            recording_mode_menu,
+            profile_menu,
+            recording_timer_item,
+            cancel_recording_item,
+            idle_icon,
+            recording_items_visible: AtomicBool::new(false),
+            timer_generation: AtomicU64::new(0),
+        }
+    }
+
+    pub fn menu(&self) -> &Menu<Wry> {
+        &self.menu
+    }
+
+    pub fn idle_icon(&self) -> Image<'static> {
+        self.idle_icon.clone()
+    }
+
+    pub fn show_recording(&self, app: &AppHandle, listening_since: Instant) {
+        let generation = self.timer_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        self.set_tray_icon(app, recording_icon());
+        self.set_timer_text(listening_since.elapsed());
+
+        if !self.recording_items_visible.swap(true, Ordering::SeqCst) {
+            if let Err(error) = self.menu.insert(&self.recording_timer_item, 0) {
+                warn!(%error, "failed to add recording timer to tray menu");
+            }
+            if let Err(error) = self.menu.insert(&self.cancel_recording_item, 1) {
+                warn!(%error, "failed to add cancel item to tray menu");
+            }
+        }
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(TIMER_TICK).await;
+                let Some(tray_menu) = app.try_state::<TrayMenuState>() else {
+                    break;
+                };
+                if tray_menu.timer_generation.load(Ordering::SeqCst) != generation {
+                    break;
+                }
+                tray_menu.set_timer_text(listening_since.elapsed());
+            }
+            debug!(generation, "tray recording timer stopped");
+        });
+    }
+
+    pub fn show_idle(&self, app: &AppHandle) {
+        self.timer_generation.fetch_add(1, Ordering::SeqCst);
+        if !self.recording_items_visible.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        self.set_tray_icon(app, self.idle_icon());
+        for item in [&self.recording_timer_item, &self.cancel_recording_item] {
+            if let Err(error) = self.menu.remove(item) {
+                warn!(%error, "failed to remove recording item from tray menu");
+            }
+        }
+    }
+
+    pub fn refresh_quick_settings(
+        &self,
+        app: &AppHandle,
+        settings: &VoiceSettings,
+        microphones: &[MicrophoneInfo],
+    ) {
+        let selected_microphone = settings.microphone_id.as_deref();
+        for (submenu, choices) in [
+            (
+                &self.microphone_menu,
+                microphone_choices(microphones, selected_microphone),
+            ),
+            (
+                &self.recording_mode_menu,
+                fixed_choices(
+                    MENU_RECORDING_MODE_PREFIX,
+                    &RECORDING_MODE_CHOICES,
+                    &settings.recording_mode,
+                ),
+            ),
+            (
+                &self.profile_menu,
+                fixed_choices(
+                    MENU_PROFILE_PREFIX,
+                    &PROFILE_CHOICES,
+                    &settings.transcription_style,
+                ),
+            ),
+        ] {
+            if let Err(error) = replace_choices(app, submenu, &choices) {
+                warn!(%error, submenu = %submenu.id().as_ref(), "failed to refresh tray quick settings");
+            }
+        }
+    }
+
+    fn set_timer_text(&self, elapsed: Duration) {
+        let text = format!("Recording {}", format_elapsed(elapsed));
+        if let Err(error) = self.recording_timer_item.set_text(text) {
+            warn!(%error, "failed to update tray recording timer");
+        }
+    }
+
+    fn set_tray_icon(&self, app: &AppHandle, icon: Image<'static>) {
+        let Some(tray) = app.tray_by_id(TRAY_ID) else {
+            return;
+        };
+        if let Err(error) = tray.set_icon(Some(icon)) {
+            warn!(%error, "failed to swap tray icon");
+        }
+        if let Err(error) = tray.set_icon_as_template(true) {
+            warn!(%error, "failed to mark tray icon as template");
+        }
+    }
+}
+
+// Checked items are recreated rather than toggled in place, since the list of
+// microphones changes and macOS flips a check mark as soon as it is clicked.
+fn replace_choices(
+    app: &AppHandle,
+    submenu: &Submenu<Wry>,
+    choices: &[MenuChoice],
+) -> tauri::Result<()> {
+    while submenu.remove_at(0)?.is_some() {}
+    for choice in choices {
+        let item = CheckMenuItem::with_id(
+            app,
+            &choice.id,
+            &choice.label,
+            true,
+            choice.checked,
+            None::<&str>,
+        )?;
+        submenu.append(&item)?;
+    }
+    Ok(())
+}
+
+// The system default comes first. A chosen microphone that is unplugged stays
+// listed so it is clear why the default is being used instead.
+fn microphone_choices(microphones: &[MicrophoneInfo], selected: Option<&str>) -> Vec<MenuChoice> {
+    let mut choices = vec![MenuChoice {
+        id: MENU_MICROPHONE_PREFIX.to_string(),
+        label: "System Default".to_string(),
+        checked: selected.is_none(),
+    }];
+    choices.extend(microphones.iter().map(|microphone| MenuChoice {
+        id: format!("{MENU_MICROPHONE_PREFIX}{}", microphone.id),
+        label: microphone.name.clone(),
+        checked: selected == Some(microphone.id.as_str()),
+    }));
+    if let Some(selected) = selected {
+        if !microphones
+            .iter()
+            .any(|microphone| microphone.id == selected)
+        {
+            choices.push(MenuChoice {
+                id: format!("{MENU_MICROPHONE_PREFIX}{selected}"),
+                label: format!("{selected} (Not Connected)"),
+                checked: true,
+            });
+        }
+    }
+    choices
+}
+
+fn fixed_choices(prefix: &str, values: &[(&str, &str)], current: &str) -> Vec<MenuChoice> {
+    values
+        .iter()
+        .map(|(value, label)| MenuChoice {
+            id: format!("{prefix}{value}"),
+            label: label.to_string(),
+            checked: current.trim().eq_ignore_ascii_case(value),
+        })
+        .collect()
+}
+
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let total_secs = elapsed.as_secs();
+    let (hours, minutes, seconds) = (total_secs / 3600, total_secs / 60 % 60, total_secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+// A filled dot drawn at runtime; as a template image only its alpha matters,
+// so it follows the menu bar's light/dark appearance like the idle icon.
+fn recording_icon() -> Image<'static> {
+    let size = RECORDING_ICON_SIZE;
+    let center = size as f32 / 2.0;
+    let rgba = (0..size * size)
+        .flat_map(|index| {
+            let x = (index % size) as f32 + 0.5 - center;
+            let y = (index / size) as f32 + 0.5 - center;
+            let coverage = (RECORDING_ICON_RADIUS + 0.5 - x.hypot(y)).clamp(0.0, 1.0);
+            [0, 0, 0, (coverage * 255.0).round() as u8]
+        })
+        .collect::<Vec<_>>();
+    Image::new_owned(rgba, size, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn formats_elapsed_time_and_draws_recording_glyph() {
+        assert_eq!(format_elapsed(Duration::from_millis(7_900)), "0:07");
+        assert_eq!(format_elapsed(Duration::from_secs(62)), "1:02");
+        assert_eq!(format_elapsed(Duration::from_secs(3_723)), "1:02:03");
+
+        let icon = recording_icon();
+        assert_eq!(icon.width(), RECORDING_ICON_SIZE);
+        let alpha_at =
+            |x: u32, y: u32| icon.rgba()[((y * RECORDING_ICON_SIZE + x) * 4 + 3) as usize];
+        assert_eq!(alpha_at(16, 16), 255);
+        assert_eq!(alpha_at(0, 0), 0);
+    }
+
+    fn microphone(id: &str, name: &str) -> MicrophoneInfo {
+        MicrophoneInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            is_default: false,
+            sample_rate_hz: None,
+            channels: None,
+        }
+    }
+
+    #[test]
+    fn quick_setting_menu_ids_round_trip_to_settings_updates() {
+        let choices = microphone_choices(&[microphone("usb-1", "USB Mic")], Some("usb-1"));
+        let checked: Vec<_> = choices.iter().filter(|choice| choice.checked).collect();
+        assert_eq!(checked.len(), 1);
+        assert_eq!(
+            QuickSetting::from_menu_id(&checked[0].id),
+            Some(QuickSetting::Microphone(Some("usb-1".to_string())))
+        );
+        assert_eq!(
+            QuickSetting::from_menu_id(&choices[0].id),
+            Some(QuickSetting::Microphone(None))
+        );
+        assert_eq!(
+            QuickSetting::Microphone(None).into_update().microphone_id,
+            Some(None)
+        );
+
+        let modes = fixed_choices(
+            MENU_RECORDING_MODE_PREFIX,
+            &RECORDING_MODE_CHOICES,
+            RECORDING_MODE_TOGGLE,
+        );
+        let toggle = modes.iter().find(|choice| choice.checked).unwrap();
+        assert_eq!(
+            QuickSetting::from_menu_id(&toggle.id)
+                .unwrap()
+                .into_update()
+                .recording_mode
+                .as_deref(),
+            Some(RECORDING_MODE_TOGGLE)
+        );
+        assert_eq!(
+            QuickSetting::from_menu_id("profile:casual"),
+            Some(QuickSetting::Profile(
+                TRANSCRIPTION_STYLE_CASUAL.to_string()
+            ))
+        );
+        assert_eq!(QuickSetting::from_menu_id("show_window"), None);
+    }
+
+    #[test]
+    fn unplugged_microphone_stays_checked() {
+        let choices = microphone_choices(&[microphone("built-in", "MacBook Mic")], Some("usb-1"));
+        assert_eq!(choices.len(), 3);
+        assert!(!choices[0].checked);
+        assert!(!choices[1].checked);
+        assert_eq!(choices[2].label, "usb-1 (Not Connected)");
+        assert!(choices[2].checked);
+    }
+}
@@ -0,0 +1,115 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::PipelineErrorStage;
+use crate::{
+    app_error::ErrorCode,
+    auth_store::AuthMethod,
+    permission_service::{ACCESSIBILITY_SETTINGS_URL, MICROPHONE_SETTINGS_URL},
+};
+
+const OPENAI_API_KEYS_URL: &str = "https://platform.openai.com/api-keys";
+const OPENAI_BILLING_URL: &str = "https://platform.openai.com/settings/organization/billing";
+
+// The one-click fix the error screen offers alongside the message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorAction {
+    OpenMicrophoneSettings,
+    OpenAccessibilitySettings,
+    OpenApiKeySettings,
+    SignInWithChatgpt,
+    OpenBilling,
+    ChooseMicrophone,
+}
+
+impl ErrorAction {
+    pub fn help_url(self) -> Option<&'static str> {
+        match self {
+            Self::OpenMicrophoneSettings => Some(MICROPHONE_SETTINGS_URL),
+            Self::OpenAccessibilitySettings => Some(ACCESSIBILITY_SETTINGS_URL),
+            Self::OpenApiKeySettings => Some(OPENAI_API_KEYS_URL),
+            Self::OpenBilling => Some(OPENAI_BILLING_URL),
+            Self::SignInWithChatgpt | Self::ChooseMicrophone => None,
+        }
+    }
+}
+
+// The fix follows from where the pipeline failed and the error's code; a
+// missing credential points at whichever sign-in the user relies on. Errors
+// with no obvious fix get no action and render as text only.
+pub fn route_error(
+    stage: &PipelineErrorStage,
+    code: ErrorCode,
+    auth_method: AuthMethod,
+) -> Option<ErrorAction> {
+    match (code, stage) {
+        (ErrorCode::PermissionDenied, PipelineErrorStage::TextInsertion) => {
+            Some(ErrorAction::OpenAccessibilitySettings)
+        }
+        (ErrorCode::PermissionDenied, _) => Some(ErrorAction::OpenMicrophoneSettings),
+        (ErrorCode::Quota, _) => Some(ErrorAction::OpenBilling),
+        (ErrorCode::NoAuth, _) if auth_method == AuthMethod::ChatgptOauth => {
+            Some(ErrorAction::SignInWithChatgpt)
+        }
+        (ErrorCode::NoAuth, _) => Some(ErrorAction::OpenApiKeySettings),
+        (ErrorCode::NotFound, PipelineErrorStage::RecordingStart) => {
+            Some(ErrorAction::ChooseMicrophone)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{route_error, ErrorAction};
+    use crate::{app_error::ErrorCode, auth_store::AuthMethod, voice_pipeline::PipelineErrorStage};
+
+    #[test]
+    fn routes_known_failures_to_a_fix() {
+        let route = |stage, code| route_error(&stage, code, AuthMethod::ApiKey);
+
+        assert_eq!(
+            route(
+                PipelineErrorStage::RecordingStart,
+                ErrorCode::PermissionDenied
+            ),
+            Some(ErrorAction::OpenMicrophoneSettings)
+        );
+        assert_eq!(
+            route(
+                PipelineErrorStage::TextInsertion,
+                ErrorCode::PermissionDenied
+            ),
+            Some(ErrorAction::OpenAccessibilitySettings)
+        );
+        assert_eq!(
+            route(PipelineErrorStage::Transcription, ErrorCode::NoAuth),
+            Some(ErrorAction::OpenApiKeySettings)
+        );
+        assert_eq!(
+            route_error(
+                &PipelineErrorStage::Transcription,
+                ErrorCode::NoAuth,
+                AuthMethod::ChatgptOauth
+            ),
+            Some(ErrorAction::SignInWithChatgpt)
+        );
+        assert_eq!(
+            route(PipelineErrorStage::Transcription, ErrorCode::Quota),
+            Some(ErrorAction::OpenBilling)
+        );
+        assert_eq!(
+            route(PipelineErrorStage::RecordingStart, ErrorCode::NotFound),
+            Some(ErrorAction::ChooseMicrophone)
+        );
+        assert_eq!(
+            route(PipelineErrorStage::Transcription, ErrorCode::Network),
+            None
+        );
+        assert_eq!(
+            ErrorAction::OpenAccessibilitySettings.help_url(),
+            Some(crate::permission_service::ACCESSIBILITY_SETTINGS_URL)
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::silence_trim::SpeechActivity;
+
+// Whisper-family models were trained on subtitled video, so silence or room
+// tone tends to come back as one of these sign-offs.
+const KNOWN_SILENCE_HALLUCINATIONS: &[&str] = &[
+    "bye",
+    "like and subscribe",
+    "please subscribe",
+    "see you in the next video",
+    "see you next time",
+    "subscribe",
+    "subtitles by the amara org community",
+    "thank you",
+    "thank you for watching",
+    "thank you so much for watching",
+    "thanks for watching",
+    "you",
+];
+// Less than this above the threshold is a cough or a click, not speech.
+const MAX_SILENT_SPEECH_MS: u64 = 200;
+// A stock phrase is only trusted when there was enough speech to have said it.
+const MAX_SUSPICIOUS_SPEECH_RATIO: f64 = 0.05;
+
+// Why a transcript was taken for something the model made up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HallucinationReason {
+    // There was no speech at all, so any text is invented.
+    SilentAudio,
+    // A stock sign-off over audio that was almost entirely silence or noise.
+    StockPhrase,
+}
+
+pub fn detect_silence_hallucination(
+    transcript: &str,
+    activity: SpeechActivity,
+) -> Option<HallucinationReason> {
+    let normalized = normalize(transcript);
+    if normalized.is_empty() {
+        return None;
+    }
+    if activity.speech_ms <= MAX_SILENT_SPEECH_MS {
+        return Some(HallucinationReason::SilentAudio);
+    }
+    (activity.speech_ratio() < MAX_SUSPICIOUS_SPEECH_RATIO
+        && KNOWN_SILENCE_HALLUCINATIONS.contains(&normalized.as_str()))
+    .then_some(HallucinationReason::StockPhrase)
+}
+
+fn normalize(transcript: &str) -> String {
+    transcript
+        .split(|character: char| !character.is_alphanumeric() && character != '\'')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_silence_hallucination, HallucinationReason};
+    use crate::silence_trim::SpeechActivity;
+
+    fn activity(speech_ms: u64, total_ms: u64) -> SpeechActivity {
+        SpeechActivity {
+            speech_ms,
+            total_ms,
+        }
+    }
+
+    #[test]
+    fn suppresses_transcripts_of_silent_audio_and_stock_phrases_over_near_silence() {
+        assert_eq!(
+            detect_silence_hallucination("Thanks for watching!", activity(0, 5_000)),
+            Some(HallucinationReason::SilentAudio)
+        );
+        assert_eq!(
+            detect_silence_hallucination("Some sentence.", activity(150, 5_000)),
+            Some(HallucinationReason::SilentAudio)
+        );
+        assert_eq!(
+            detect_silence_hallucination("Thank you.", activity(400, 20_000)),
+            Some(HallucinationReason::StockPhrase)
+        );
+        assert_eq!(
+            detect_silence_hallucination("Thank you.", activity(600, 2_000)),
+            None
+        );
+        assert_eq!(
+            detect_silence_hallucination("Ship the release today.", activity(400, 20_000)),
+            None
+        );
+        assert_eq!(detect_silence_hallucination("", activity(0, 5_000)), None);
+    }
+}
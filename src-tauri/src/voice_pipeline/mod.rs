@@ -1,9 +1,24 @@
-use std::time::Duration;
+pub mod error_router;
+pub mod hallucination;
+pub mod plausibility;
+pub mod progress;
+pub mod queue;
+pub mod rest;
+
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use tracing::{debug, error, info, warn};
 
-use crate::status_notifier::AppStatus;
+use self::queue::QueueTicket;
+use crate::{
+    app_error::{AppError, ErrorCode},
+    audio_capture_service::RecordedWav,
+    status_notifier::AppStatus,
+    transcription::{
+        language::detect_language, TranscriptionResult, TranscriptionSegment, TranscriptionWord,
+    },
+};
 
 const DEFAULT_ERROR_RESET_DELAY_MS: u64 = 1_500;
 
@@ -13,6 +28,7 @@ pub enum PipelineErrorStage {
     RecordingStop,
     RecordingRuntime,
     Transcription,
+    TranscriptionTimeout,
     TextInsertion,
 }
 
@@ -23,6 +39,7 @@ impl PipelineErrorStage {
             Self::RecordingStop => "recording_stop",
             Self::RecordingRuntime => "recording_runtime",
             Self::Transcription => "transcription",
+            Self::TranscriptionTimeout => "transcription_timeout",
             Self::TextInsertion => "text_insertion",
         }
     }
@@ -31,31 +48,108 @@ impl PipelineErrorStage {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PipelineError {
     pub stage: PipelineErrorStage,
+    pub code: ErrorCode,
     pub message: String,
 }
 
+impl PipelineError {
+    pub fn new(stage: PipelineErrorStage, error: AppError) -> Self {
+        Self {
+            stage,
+            code: error.code,
+            message: error.message,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PipelineTranscript {
     pub text: String,
     pub duration_secs: Option<f64>,
     pub language: Option<String>,
+    // How sure the provider, or the fallback detector, was of `language`.
+    pub language_confidence: Option<f32>,
+    // How sure the provider was of the transcript as a whole, from 0 to 1.
+    pub confidence: Option<f32>,
     pub provider: String,
+    pub segments: Vec<TranscriptionSegment>,
+    pub words: Vec<TranscriptionWord>,
+    pub translation: Option<PipelineTranslation>,
+}
+
+impl PipelineTranscript {
+    pub fn from_transcription(transcription: TranscriptionResult, provider: String) -> Self {
+        Self {
+            text: transcription.text,
+            duration_secs: transcription.duration_secs,
+            language: transcription.language,
+            language_confidence: transcription.language_confidence,
+            confidence: transcription.confidence,
+            provider,
+            segments: transcription.segments,
+            words: transcription.words,
+            translation: None,
+        }
+    }
+
+    pub fn text_to_insert(&self) -> &str {
+        match &self.translation {
+            Some(translation) if translation.insert => &translation.text,
+            _ => &self.text,
+        }
+    }
+
+    // For providers that do not report a language. Only the language and its
+    // confidence change; `confidence` stays the provider's score for the
+    // words, which is all low-confidence review goes by.
+    pub fn detect_missing_language(&mut self) {
+        if self.language.is_some() {
+            return;
+        }
+        if let Some((language, confidence)) = detect_language(&self.text) {
+            self.language = Some(language);
+            self.language_confidence = Some(confidence);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineTranslation {
+    pub language: String,
+    pub text: String,
+    pub insert: bool,
 }
 
 #[async_trait]
 pub trait VoicePipelineDelegate: Send + Sync {
     fn set_status(&self, status: AppStatus);
-    fn emit_transcript(&self, transcript: &str);
+    fn emit_transcript(&self, transcript: &PipelineTranscript);
     fn emit_error(&self, error: &PipelineError);
     fn on_recording_started(&self, _success: bool) {}
     fn on_recording_stopped(&self, _success: bool) {}
-    fn start_recording(&self) -> Result<(), String>;
-    fn stop_recording(&self) -> Result<Vec<u8>, String>;
-    async fn transcribe(&self, wav_bytes: Vec<u8>) -> Result<PipelineTranscript, String>;
-    fn insert_text(&self, transcript: &str) -> Result<(), String>;
-    fn save_history_entry(&self, _transcript: &PipelineTranscript) -> Result<(), String> {
+    // Reported after each stage returns, whether or not it succeeded.
+    fn on_stage_finished(&self, _stage: PipelineErrorStage, _elapsed: Duration, _success: bool) {}
+    fn start_recording(&self) -> Result<(), AppError>;
+    fn stop_recording(&self) -> Result<RecordedWav, AppError>;
+    async fn transcribe(&self, wav: RecordedWav) -> Result<PipelineTranscript, AppError>;
+    async fn insert_text(&self, transcript: &str) -> Result<(), AppError>;
+    fn save_history_entry(&self, _transcript: &PipelineTranscript) -> Result<(), AppError> {
         Ok(())
     }
+    // Lets the delegate keep a suspicious transcript out of the focused app;
+    // it is still emitted and saved so the user can review or retry it.
+    fn should_hold_insertion(&self, _transcript: &PipelineTranscript) -> bool {
+        false
+    }
+    // Private sessions leave no trace beyond the insertion itself: no history
+    // entry, no usage stats, and no transcript details in the logs.
+    fn is_private(&self) -> bool {
+        false
+    }
+    // The watchdog limit for one transcription, retries included.
+    fn transcription_timeout(&self) -> Option<Duration> {
+        None
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -80,50 +174,127 @@ impl VoicePipeline {
 
     pub async fn handle_hotkey_started<D: VoicePipelineDelegate>(&self, delegate: &D) {
         info!("pipeline handling hotkey start");
-        match delegate.start_recording() {
+        let started_at = Instant::now();
+        let result = delegate.start_recording();
+        delegate.on_stage_finished(
+            PipelineErrorStage::RecordingStart,
+            started_at.elapsed(),
+            result.is_ok(),
+        );
+        match result {
             Ok(()) => {
                 info!("recording started successfully from hotkey");
                 delegate.on_recording_started(true);
                 delegate.set_status(AppStatus::Listening);
             }
-            Err(message) => {
-                error!(message = %message, "recording start failed from hotkey");
+            Err(error) => {
+                error!(%error, "recording start failed from hotkey");
                 delegate.on_recording_started(false);
-                self.handle_error(delegate, PipelineErrorStage::RecordingStart, message)
+                self.handle_error(delegate, PipelineErrorStage::RecordingStart, error)
                     .await;
             }
         }
     }
 
+    #[cfg(test)]
     pub async fn handle_hotkey_stopped<D: VoicePipelineDelegate>(&self, delegate: &D) {
+        if let Some(wav) = self.stop_recording_for_transcription(delegate).await {
+            self.transcribe_and_insert(delegate, wav, None).await;
+        }
+    }
+
+    // First half of a stop: ends the recording and returns the audio, or `None`
+    // when there is nothing left to transcribe.
+    pub async fn stop_recording_for_transcription<D: VoicePipelineDelegate>(
+        &self,
+        delegate: &D,
+    ) -> Option<RecordedWav> {
         info!("pipeline handling hotkey stop");
         delegate.set_status(AppStatus::Transcribing);
 
-        let wav_bytes = match delegate.stop_recording() {
-            Ok(wav_bytes) => {
+        let started_at = Instant::now();
+        let result = delegate.stop_recording();
+        delegate.on_stage_finished(
+            PipelineErrorStage::RecordingStop,
+            started_at.elapsed(),
+            result.is_ok(),
+        );
+        let wav = match result {
+            Ok(wav) => {
                 info!(
-                    audio_bytes = wav_bytes.len(),
+                    audio_bytes = wav.byte_len(),
                     "recording stopped successfully"
                 );
                 delegate.on_recording_stopped(true);
-                wav_bytes
+                wav
             }
-            Err(message) => {
-                error!(message = %message, "recording stop failed");
+            Err(error) => {
+                error!(%error, "recording stop failed");
                 delegate.on_recording_stopped(false);
-                self.handle_error(delegate, PipelineErrorStage::RecordingStop, message)
+                self.handle_error(delegate, PipelineErrorStage::RecordingStop, error)
                     .await;
-                return;
+                return None;
             }
         };
 
-        if wav_bytes.is_empty() {
+        if wav.is_empty() {
             info!("recording produced no audio; returning to idle");
             delegate.set_status(AppStatus::Idle);
-            return;
+            return None;
         }
 
-        let transcript = match delegate.transcribe(wav_bytes).await {
+        Some(wav)
+    }
+
+    // Second half of a stop. With a queue ticket, transcription runs right away
+    // but the transcript is only inserted once earlier sessions have finished.
+    pub async fn transcribe_and_insert<D: VoicePipelineDelegate>(
+        &self,
+        delegate: &D,
+        wav: RecordedWav,
+        mut ticket: Option<QueueTicket>,
+    ) {
+        let private = delegate.is_private();
+        let started_at = Instant::now();
+        let transcription = delegate.transcribe(wav);
+        let result = match delegate.transcription_timeout() {
+            Some(limit) => match tokio::time::timeout(limit, transcription).await {
+                Ok(result) => result,
+                Err(_) => {
+                    warn!(
+                        timeout_secs = limit.as_secs(),
+                        "transcription watchdog fired"
+                    );
+                    delegate.on_stage_finished(
+                        PipelineErrorStage::TranscriptionTimeout,
+                        started_at.elapsed(),
+                        false,
+                    );
+                    drop(ticket);
+                    self.handle_error(
+                        delegate,
+                        PipelineErrorStage::TranscriptionTimeout,
+                        AppError::new(ErrorCode::Timeout, timeout_message(limit)),
+                    )
+                    .await;
+                    return;
+                }
+            },
+            None => transcription.await,
+        };
+        delegate.on_stage_finished(
+            PipelineErrorStage::Transcription,
+            started_at.elapsed(),
+            result.is_ok(),
+        );
+        let transcript = match result {
+            Ok(transcript) if private => {
+                info!(
+                    provider = %transcript.provider,
+                    "transcription completed in pipeline for private session"
+                );
+                transcript
+            }
             Ok(transcript) => {
                 info!(
                     transcript_chars = transcript.text.chars().count(),
@@ -132,23 +303,45 @@ impl VoicePipeline {
                 );
                 transcript
             }
-            Err(message) => {
-                error!(message = %message, "pipeline transcription failed");
-                self.handle_error(delegate, PipelineErrorStage::Transcription, message)
+            Err(error) => {
+                error!(%error, "pipeline transcription failed");
+                drop(ticket);
+                self.handle_error(delegate, PipelineErrorStage::Transcription, error)
                     .await;
                 return;
             }
         };
 
-        delegate.emit_transcript(&transcript.text);
+        if let Some(ticket) = ticket.as_mut() {
+            ticket.wait_for_turn().await;
+        }
 
-        if let Err(message) = delegate.save_history_entry(&transcript) {
-            warn!(message = %message, "failed to persist transcript history entry");
+        delegate.emit_transcript(&transcript);
+
+        if private {
+            debug!("skipping transcript history for private session");
+        } else if let Err(error) = delegate.save_history_entry(&transcript) {
+            warn!(%error, "failed to persist transcript history entry");
+        }
+
+        if delegate.should_hold_insertion(&transcript) {
+            drop(ticket);
+            warn!("holding transcript back from insertion");
+            delegate.set_status(AppStatus::Idle);
+            return;
         }
 
-        if let Err(message) = delegate.insert_text(&transcript.text) {
-            error!(message = %message, "pipeline text insertion failed");
-            self.handle_error(delegate, PipelineErrorStage::TextInsertion, message)
+        let started_at = Instant::now();
+        let insertion_result = delegate.insert_text(transcript.text_to_insert()).await;
+        delegate.on_stage_finished(
+            PipelineErrorStage::TextInsertion,
+            started_at.elapsed(),
+            insertion_result.is_ok(),
+        );
+        drop(ticket);
+        if let Err(error) = insertion_result {
+            error!(%error, "pipeline text insertion failed");
+            self.handle_error(delegate, PipelineErrorStage::TextInsertion, error)
                 .await;
             return;
         }
@@ -162,19 +355,19 @@ impl VoicePipeline {
         &self,
         delegate: &D,
         stage: PipelineErrorStage,
-        message: String,
+        error: AppError,
     ) {
         debug!(stage = stage.as_str(), "handling pipeline stage error");
-        self.handle_error(delegate, stage, message).await;
+        self.handle_error(delegate, stage, error).await;
     }
 
     async fn handle_error<D: VoicePipelineDelegate>(
         &self,
         delegate: &D,
         stage: PipelineErrorStage,
-        message: String,
+        error: AppError,
     ) {
-        let error = PipelineError { stage, message };
+        let error = PipelineError::new(stage, error);
         error!(
             stage = error.stage.as_str(),
             message = %error.message,
@@ -192,6 +385,13 @@ impl VoicePipeline {
     }
 }
 
+fn timeout_message(limit: Duration) -> String {
+    format!(
+        "Transcription did not finish within {} seconds. Check your network connection and try again, or raise the transcription timeout in Settings.",
+        limit.as_secs()
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Mutex;
@@ -200,11 +400,15 @@ mod tests {
 
     #[derive(Debug)]
     struct MockDelegate {
-        start_result: Result<(), String>,
-        stop_result: Result<Vec<u8>, String>,
-        transcribe_result: Result<PipelineTranscript, String>,
-        insert_result: Result<(), String>,
-        save_history_result: Result<(), String>,
+        start_result: Result<(), AppError>,
+        stop_result: Result<RecordedWav, AppError>,
+        transcribe_result: Result<PipelineTranscript, AppError>,
+        insert_result: Result<(), AppError>,
+        save_history_result: Result<(), AppError>,
+        hold_insertion: bool,
+        private: bool,
+        transcribe_delay: Duration,
+        transcription_timeout: Option<Duration>,
         start_acknowledgements: Mutex<Vec<bool>>,
         stop_acknowledgements: Mutex<Vec<bool>>,
         statuses: Mutex<Vec<AppStatus>>,
@@ -212,21 +416,31 @@ mod tests {
         saved_history: Mutex<Vec<PipelineTranscript>>,
         errors: Mutex<Vec<PipelineError>>,
         call_order: Mutex<Vec<&'static str>>,
+        finished_stages: Mutex<Vec<(PipelineErrorStage, bool)>>,
     }
 
     impl Default for MockDelegate {
         fn default() -> Self {
             Self {
                 start_result: Ok(()),
-                stop_result: Ok(vec![1, 2, 3]),
+                stop_result: Ok(RecordedWav::Memory(vec![1, 2, 3])),
                 transcribe_result: Ok(PipelineTranscript {
                     text: "hello world".to_string(),
                     duration_secs: Some(2.4),
                     language: Some("en".to_string()),
+                    language_confidence: None,
+                    confidence: None,
                     provider: "openai".to_string(),
+                    segments: Vec::new(),
+                    words: Vec::new(),
+                    translation: None,
                 }),
                 insert_result: Ok(()),
                 save_history_result: Ok(()),
+                hold_insertion: false,
+                private: false,
+                transcribe_delay: Duration::ZERO,
+                transcription_timeout: None,
                 start_acknowledgements: Mutex::new(Vec::new()),
                 stop_acknowledgements: Mutex::new(Vec::new()),
                 statuses: Mutex::new(Vec::new()),
@@ -234,6 +448,7 @@ mod tests {
                 saved_history: Mutex::new(Vec::new()),
                 errors: Mutex::new(Vec::new()),
                 call_order: Mutex::new(Vec::new()),
+                finished_stages: Mutex::new(Vec::new()),
             }
         }
     }
@@ -274,6 +489,13 @@ mod tests {
                 .clone()
         }
 
+        fn finished_stages(&self) -> Vec<(PipelineErrorStage, bool)> {
+            self.finished_stages
+                .lock()
+                .expect("finished-stage lock should not be poisoned")
+                .clone()
+        }
+
         fn start_acknowledgements(&self) -> Vec<bool> {
             self.start_acknowledgements
                 .lock()
@@ -298,11 +520,11 @@ mod tests {
                 .push(status);
         }
 
-        fn emit_transcript(&self, transcript: &str) {
+        fn emit_transcript(&self, transcript: &PipelineTranscript) {
             self.transcripts
                 .lock()
                 .expect("transcript lock should not be poisoned")
-                .push(transcript.to_string());
+                .push(transcript.text_to_insert().to_string());
         }
 
         fn emit_error(&self, error: &PipelineError) {
@@ -326,7 +548,14 @@ mod tests {
                 .push(success);
         }
 
-        fn start_recording(&self) -> Result<(), String> {
+        fn on_stage_finished(&self, stage: PipelineErrorStage, _elapsed: Duration, success: bool) {
+            self.finished_stages
+                .lock()
+                .expect("finished-stage lock should not be poisoned")
+                .push((stage, success));
+        }
+
+        fn start_recording(&self) -> Result<(), AppError> {
             self.call_order
                 .lock()
                 .expect("call-order lock should not be poisoned")
@@ -334,7 +563,7 @@ mod tests {
             self.start_result.clone()
         }
 
-        fn stop_recording(&self) -> Result<Vec<u8>, String> {
+        fn stop_recording(&self) -> Result<RecordedWav, AppError> {
             self.call_order
                 .lock()
                 .expect("call-order lock should not be poisoned")
@@ -342,15 +571,18 @@ mod tests {
             self.stop_result.clone()
         }
 
-        async fn transcribe(&self, _wav_bytes: Vec<u8>) -> Result<PipelineTranscript, String> {
+        async fn transcribe(&self, _wav: RecordedWav) -> Result<PipelineTranscript, AppError> {
             self.call_order
                 .lock()
                 .expect("call-order lock should not be poisoned")
                 .push("transcribe");
+            if !self.transcribe_delay.is_zero() {
+                tokio::time::sleep(self.transcribe_delay).await;
+            }
             self.transcribe_result.clone()
         }
 
-        fn insert_text(&self, _transcript: &str) -> Result<(), String> {
+        async fn insert_text(&self, _transcript: &str) -> Result<(), AppError> {
             self.call_order
                 .lock()
                 .expect("call-order lock should not be poisoned")
@@ -358,7 +590,7 @@ mod tests {
             self.insert_result.clone()
         }
 
-        fn save_history_entry(&self, transcript: &PipelineTranscript) -> Result<(), String> {
+        fn save_history_entry(&self, transcript: &PipelineTranscript) -> Result<(), AppError> {
             self.call_order
                 .lock()
                 .expect("call-order lock should not be poisoned")
@@ -369,6 +601,18 @@ mod tests {
                 .push(transcript.clone());
             self.save_history_result.clone()
         }
+
+        fn should_hold_insertion(&self, _transcript: &PipelineTranscript) -> bool {
+            self.hold_insertion
+        }
+
+        fn is_private(&self) -> bool {
+            self.private
+        }
+
+        fn transcription_timeout(&self) -> Option<Duration> {
+            self.transcription_timeout
+        }
     }
 
     #[tokio::test]
@@ -389,7 +633,10 @@ mod tests {
     async fn hotkey_start_failure_sets_error_then_idle() {
         let pipeline = VoicePipeline::new(Duration::ZERO);
         let delegate = MockDelegate {
-            start_result: Err("microphone unavailable".to_string()),
+            start_result: Err(AppError::new(
+                ErrorCode::PermissionDenied,
+                "microphone unavailable",
+            )),
             ..MockDelegate::default()
         };
 
@@ -401,10 +648,10 @@ mod tests {
         assert_eq!(delegate.statuses(), vec![AppStatus::Error, AppStatus::Idle]);
         assert_eq!(
             delegate.errors(),
-            vec![PipelineError {
-                stage: PipelineErrorStage::RecordingStart,
-                message: "microphone unavailable".to_string(),
-            }]
+            vec![PipelineError::new(
+                PipelineErrorStage::RecordingStart,
+                AppError::new(ErrorCode::PermissionDenied, "microphone unavailable")
+            )]
         );
     }
 
@@ -426,6 +673,14 @@ mod tests {
         );
         assert!(delegate.start_acknowledgements().is_empty());
         assert_eq!(delegate.stop_acknowledgements(), vec![true]);
+        assert_eq!(
+            delegate.finished_stages(),
+            vec![
+                (PipelineErrorStage::RecordingStop, true),
+                (PipelineErrorStage::Transcription, true),
+                (PipelineErrorStage::TextInsertion, true),
+            ]
+        );
         assert_eq!(
             delegate.statuses(),
             vec![AppStatus::Transcribing, AppStatus::Idle]
@@ -437,17 +692,54 @@ mod tests {
                 text: "hello world".to_string(),
                 duration_secs: Some(2.4),
                 language: Some("en".to_string()),
+                language_confidence: None,
+                confidence: None,
                 provider: "openai".to_string(),
+                segments: Vec::new(),
+                words: Vec::new(),
+                translation: None,
             }]
         );
         assert!(delegate.errors().is_empty());
     }
 
+    #[tokio::test]
+    async fn translated_transcript_is_inserted_when_selected_and_both_are_saved() {
+        let pipeline = VoicePipeline::new(Duration::ZERO);
+        let translation = PipelineTranslation {
+            language: "English".to_string(),
+            text: "hello world".to_string(),
+            insert: true,
+        };
+        let delegate = MockDelegate {
+            transcribe_result: Ok(PipelineTranscript {
+                text: "hola mundo".to_string(),
+                duration_secs: Some(2.4),
+                language: Some("es".to_string()),
+                language_confidence: None,
+                confidence: None,
+                provider: "openai".to_string(),
+                segments: Vec::new(),
+                words: Vec::new(),
+                translation: Some(translation.clone()),
+            }),
+            ..MockDelegate::default()
+        };
+
+        pipeline.handle_hotkey_stopped(&delegate).await;
+
+        assert_eq!(delegate.transcripts(), vec!["hello world".to_string()]);
+        let saved = delegate.saved_history();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].text, "hola mundo");
+        assert_eq!(saved[0].translation, Some(translation));
+    }
+
     #[tokio::test]
     async fn hotkey_stop_with_empty_audio_skips_transcription_and_returns_to_idle() {
         let pipeline = VoicePipeline::new(Duration::ZERO);
         let delegate = MockDelegate {
-            stop_result: Ok(Vec::new()),
+            stop_result: Ok(RecordedWav::Memory(Vec::new())),
             ..MockDelegate::default()
         };
 
@@ -469,7 +761,7 @@ mod tests {
     async fn hotkey_stop_recording_failure_sets_error_then_idle() {
         let pipeline = VoicePipeline::new(Duration::ZERO);
         let delegate = MockDelegate {
-            stop_result: Err("recording not active".to_string()),
+            stop_result: Err(AppError::new(ErrorCode::Internal, "recording not active")),
             ..MockDelegate::default()
         };
 
@@ -484,10 +776,10 @@ mod tests {
         );
         assert_eq!(
             delegate.errors(),
-            vec![PipelineError {
-                stage: PipelineErrorStage::RecordingStop,
-                message: "recording not active".to_string(),
-            }]
+            vec![PipelineError::new(
+                PipelineErrorStage::RecordingStop,
+                AppError::new(ErrorCode::Internal, "recording not active")
+            )]
         );
         assert!(delegate.transcripts().is_empty());
         assert!(delegate.saved_history().is_empty());
@@ -497,7 +789,7 @@ mod tests {
     async fn hotkey_stop_transcription_failure_sets_error_then_idle() {
         let pipeline = VoicePipeline::new(Duration::ZERO);
         let delegate = MockDelegate {
-            transcribe_result: Err("provider unavailable".to_string()),
+            transcribe_result: Err(AppError::new(ErrorCode::Network, "provider unavailable")),
             ..MockDelegate::default()
         };
 
@@ -512,11 +804,45 @@ mod tests {
         );
         assert_eq!(
             delegate.errors(),
-            vec![PipelineError {
-                stage: PipelineErrorStage::Transcription,
-                message: "provider unavailable".to_string(),
-            }]
+            vec![PipelineError::new(
+                PipelineErrorStage::Transcription,
+                AppError::new(ErrorCode::Network, "provider unavailable")
+            )]
+        );
+        assert!(delegate.transcripts().is_empty());
+        assert!(delegate.saved_history().is_empty());
+    }
+
+    #[tokio::test]
+    async fn stalled_transcription_hits_watchdog_with_timeout_stage() {
+        let pipeline = VoicePipeline::new(Duration::ZERO);
+        let delegate = MockDelegate {
+            transcribe_delay: Duration::from_secs(60),
+            transcription_timeout: Some(Duration::from_millis(20)),
+            ..MockDelegate::default()
+        };
+
+        pipeline.handle_hotkey_stopped(&delegate).await;
+
+        assert_eq!(delegate.call_order(), vec!["stop_recording", "transcribe"]);
+        assert_eq!(
+            delegate.statuses(),
+            vec![AppStatus::Transcribing, AppStatus::Error, AppStatus::Idle]
+        );
+        let errors = delegate.errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].stage, PipelineErrorStage::TranscriptionTimeout);
+        assert_eq!(errors[0].code, ErrorCode::Timeout);
+        assert_eq!(
+            delegate.finished_stages(),
+            vec![
+                (PipelineErrorStage::RecordingStop, true),
+                (PipelineErrorStage::TranscriptionTimeout, false),
+            ]
         );
+        assert!(errors[0]
+            .message
+            .contains("transcription timeout in Settings"));
         assert!(delegate.transcripts().is_empty());
         assert!(delegate.saved_history().is_empty());
     }
@@ -525,7 +851,7 @@ mod tests {
     async fn hotkey_stop_history_persist_failure_does_not_fail_pipeline() {
         let pipeline = VoicePipeline::new(Duration::ZERO);
         let delegate = MockDelegate {
-            save_history_result: Err("disk full".to_string()),
+            save_history_result: Err(AppError::new(ErrorCode::Internal, "disk full")),
             ..MockDelegate::default()
         };
 
@@ -551,7 +877,10 @@ mod tests {
     async fn hotkey_stop_insertion_failure_emits_transcript_and_sets_error() {
         let pipeline = VoicePipeline::new(Duration::ZERO);
         let delegate = MockDelegate {
-            insert_result: Err("accessibility denied".to_string()),
+            insert_result: Err(AppError::new(
+                ErrorCode::PermissionDenied,
+                "accessibility denied",
+            )),
             ..MockDelegate::default()
         };
 
@@ -573,7 +902,12 @@ mod tests {
                 text: "hello world".to_string(),
                 duration_secs: Some(2.4),
                 language: Some("en".to_string()),
+                language_confidence: None,
+                confidence: None,
                 provider: "openai".to_string(),
+                segments: Vec::new(),
+                words: Vec::new(),
+                translation: None,
             }]
         );
         assert_eq!(
@@ -582,13 +916,92 @@ mod tests {
         );
         assert_eq!(
             delegate.errors(),
-            vec![PipelineError {
-                stage: PipelineErrorStage::TextInsertion,
-                message: "accessibility denied".to_string(),
-            }]
+            vec![PipelineError::new(
+                PipelineErrorStage::TextInsertion,
+                AppError::new(ErrorCode::PermissionDenied, "accessibility denied")
+            )]
         );
     }
 
+    #[tokio::test]
+    async fn private_session_inserts_without_saving_history() {
+        let pipeline = VoicePipeline::new(Duration::ZERO);
+        let delegate = MockDelegate {
+            private: true,
+            ..MockDelegate::default()
+        };
+
+        pipeline.handle_hotkey_stopped(&delegate).await;
+
+        assert_eq!(
+            delegate.call_order(),
+            vec!["stop_recording", "transcribe", "insert_text"]
+        );
+        assert!(delegate.saved_history().is_empty());
+        assert_eq!(
+            delegate.statuses(),
+            vec![AppStatus::Transcribing, AppStatus::Idle]
+        );
+    }
+
+    #[tokio::test]
+    async fn held_transcript_is_saved_but_not_inserted() {
+        let pipeline = VoicePipeline::new(Duration::ZERO);
+        let delegate = MockDelegate {
+            hold_insertion: true,
+            ..MockDelegate::default()
+        };
+
+        pipeline.handle_hotkey_stopped(&delegate).await;
+
+        assert_eq!(
+            delegate.call_order(),
+            vec!["stop_recording", "transcribe", "save_history_entry"]
+        );
+        assert_eq!(delegate.transcripts(), vec!["hello world".to_string()]);
+        assert_eq!(
+            delegate.statuses(),
+            vec![AppStatus::Transcribing, AppStatus::Idle]
+        );
+        assert!(delegate.errors().is_empty());
+    }
+
+    #[tokio::test]
+    async fn queued_session_waits_for_earlier_session_before_inserting() {
+        let pipeline = VoicePipeline::new(Duration::ZERO);
+        let queue = queue::SessionQueue::new(2);
+        let earlier = queue.try_reserve().expect("first slot should be free");
+        let later = queue.try_reserve().expect("second slot should be free");
+        let delegate = std::sync::Arc::new(MockDelegate::default());
+
+        let task = {
+            let pipeline = pipeline.clone();
+            let delegate = std::sync::Arc::clone(&delegate);
+            tokio::spawn(async move {
+                pipeline
+                    .transcribe_and_insert(
+                        delegate.as_ref(),
+                        RecordedWav::Memory(vec![1, 2, 3]),
+                        Some(later),
+                    )
+                    .await;
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(delegate.call_order(), vec!["transcribe"]);
+
+        drop(earlier);
+        task.await
+            .expect("queued pipeline task should finish cleanly");
+
+        assert_eq!(
+            delegate.call_order(),
+            vec!["transcribe", "save_history_entry", "insert_text"]
+        );
+        assert_eq!(delegate.statuses(), vec![AppStatus::Idle]);
+        assert_eq!(queue.in_flight(), 0);
+    }
+
     #[tokio::test]
     async fn handle_stage_error_uses_same_error_reset_policy() {
         let pipeline = VoicePipeline::new(Duration::ZERO);
@@ -598,17 +1011,57 @@ mod tests {
             .handle_stage_error(
                 &delegate,
                 PipelineErrorStage::Transcription,
-                "provider unavailable".to_string(),
+                AppError::new(ErrorCode::Network, "provider unavailable"),
             )
             .await;
 
         assert_eq!(delegate.statuses(), vec![AppStatus::Error, AppStatus::Idle]);
         assert_eq!(
             delegate.errors(),
-            vec![PipelineError {
-                stage: PipelineErrorStage::Transcription,
-                message: "provider unavailable".to_string(),
-            }]
+            vec![PipelineError::new(
+                PipelineErrorStage::Transcription,
+                AppError::new(ErrorCode::Network, "provider unavailable")
+            )]
+        );
+    }
+
+    #[test]
+    fn language_detection_leaves_transcript_confidence_to_review_routing() {
+        use crate::{settings_store::VoiceSettings, text_insertion_service::AutoInsertMode};
+
+        let mut transcript = PipelineTranscript {
+            text: "Ich glaube, das ist nicht so wichtig und wir machen weiter".to_string(),
+            duration_secs: None,
+            language: None,
+            language_confidence: None,
+            confidence: Some(0.3),
+            provider: "test".to_string(),
+            segments: Vec::new(),
+            words: Vec::new(),
+            translation: None,
+        };
+        transcript.detect_missing_language();
+
+        assert_eq!(transcript.language.as_deref(), Some("de"));
+        assert!(transcript
+            .language_confidence
+            .is_some_and(|confidence| confidence > 0.5));
+        assert_eq!(transcript.confidence, Some(0.3));
+
+        let settings = VoiceSettings {
+            low_confidence_review: true,
+            low_confidence_threshold_percent: 60,
+            ..VoiceSettings::default()
+        };
+        assert_eq!(
+            AutoInsertMode::for_transcript(&settings, transcript.confidence),
+            AutoInsertMode::Ask
+        );
+        transcript.confidence = Some(0.9);
+        transcript.language_confidence = Some(0.1);
+        assert_eq!(
+            AutoInsertMode::for_transcript(&settings, transcript.confidence),
+            AutoInsertMode::from_settings(&settings)
         );
     }
 }
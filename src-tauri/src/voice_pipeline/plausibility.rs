@@ -0,0 +1,60 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Below this, a handful of words is believable ("yes", "sounds good").
+const MIN_SPARSE_CHECK_SECS: f64 = 15.0;
+// Even slow, halting dictation stays well above this.
+const MIN_PLAUSIBLE_WORDS_PER_MINUTE: f64 = 10.0;
+const MIN_DENSE_CHECK_WORDS: u64 = 20;
+// Auctioneers peak around 250-300 words per minute; anything beyond this is
+// almost always a provider repeating itself.
+const MAX_PLAUSIBLE_WORDS_PER_MINUTE: f64 = 450.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImplausibleTranscriptReason {
+    TooFewWords,
+    TooManyWords,
+}
+
+// Catches providers that fail silently by returning a near-empty or runaway
+// transcript for a recording that clearly held something else.
+pub fn check_transcript_plausibility(
+    word_count: u64,
+    recording_secs: f64,
+) -> Option<ImplausibleTranscriptReason> {
+    if !recording_secs.is_finite() || recording_secs <= 0.0 {
+        return None;
+    }
+
+    let words_per_minute = word_count as f64 / (recording_secs / 60.0);
+    if recording_secs >= MIN_SPARSE_CHECK_SECS && words_per_minute < MIN_PLAUSIBLE_WORDS_PER_MINUTE
+    {
+        return Some(ImplausibleTranscriptReason::TooFewWords);
+    }
+    if word_count >= MIN_DENSE_CHECK_WORDS && words_per_minute > MAX_PLAUSIBLE_WORDS_PER_MINUTE {
+        return Some(ImplausibleTranscriptReason::TooManyWords);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_transcript_plausibility, ImplausibleTranscriptReason};
+
+    #[test]
+    fn flags_sparse_and_runaway_transcripts_only() {
+        assert_eq!(
+            check_transcript_plausibility(2, 60.0),
+            Some(ImplausibleTranscriptReason::TooFewWords)
+        );
+        assert_eq!(
+            check_transcript_plausibility(120, 10.0),
+            Some(ImplausibleTranscriptReason::TooManyWords)
+        );
+        assert_eq!(check_transcript_plausibility(2, 3.0), None);
+        assert_eq!(check_transcript_plausibility(150, 60.0), None);
+        assert_eq!(check_transcript_plausibility(15, 1.0), None);
+        assert_eq!(check_transcript_plausibility(0, f64::NAN), None);
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::word_count::WordCountStrategy;
+
+// The overlay only needs a few refreshes a second; deltas can arrive far faster.
+const MIN_EMIT_INTERVAL: Duration = Duration::from_millis(250);
+// Below this a single word reads as hundreds of words per minute.
+const MIN_RATE_ELAPSED: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DictationProgressEvent {
+    pub word_count: u64,
+    pub elapsed_ms: u64,
+    pub words_per_minute: f64,
+}
+
+// Accumulates realtime deltas for one dictation; deltas can split a word, so
+// words are counted over the whole transcript so far.
+#[derive(Debug)]
+pub struct DictationProgress {
+    transcript: String,
+    started_at: Instant,
+    last_emitted_at: Option<Instant>,
+    word_count: WordCountStrategy,
+}
+
+impl DictationProgress {
+    pub fn new(started_at: Instant, word_count: WordCountStrategy) -> Self {
+        Self {
+            transcript: String::new(),
+            started_at,
+            last_emitted_at: None,
+            word_count,
+        }
+    }
+
+    // Returns an event when enough time has passed since the last one.
+    pub fn push_delta(&mut self, delta: &str, now: Instant) -> Option<DictationProgressEvent> {
+        self.transcript.push_str(delta);
+        if self
+            .last_emitted_at
+            .is_some_and(|last| now.saturating_duration_since(last) < MIN_EMIT_INTERVAL)
+        {
+            return None;
+        }
+        self.last_emitted_at = Some(now);
+
+        let elapsed = now.saturating_duration_since(self.started_at);
+        let word_count = self.word_count.count(&self.transcript);
+        let words_per_minute = if elapsed < MIN_RATE_ELAPSED {
+            0.0
+        } else {
+            word_count as f64 / (elapsed.as_secs_f64() / 60.0)
+        };
+        Some(DictationProgressEvent {
+            word_count,
+            elapsed_ms: elapsed.as_millis() as u64,
+            words_per_minute,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::DictationProgress;
+    use crate::word_count::WordCountStrategy;
+
+    #[test]
+    fn counts_words_across_deltas_and_throttles_events() {
+        let started_at = Instant::now();
+        let mut progress = DictationProgress::new(started_at, WordCountStrategy::Segmented);
+
+        let first = progress
+            .push_delta("hel", started_at + Duration::from_millis(500))
+            .expect("first delta should emit");
+        assert_eq!(first.word_count, 1);
+        assert_eq!(first.words_per_minute, 0.0);
+
+        assert_eq!(
+            progress.push_delta("lo wor", started_at + Duration::from_millis(600)),
+            None
+        );
+
+        let later = progress
+            .push_delta("ld again ", started_at + Duration::from_secs(2))
+            .expect("delta after the interval should emit");
+        assert_eq!(later.word_count, 3);
+        assert_eq!(later.elapsed_ms, 2_000);
+        assert!((later.words_per_minute - 90.0).abs() < 1e-9);
+    }
+}
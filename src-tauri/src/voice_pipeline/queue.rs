@@ -0,0 +1,99 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::{oneshot, OwnedSemaphorePermit, Semaphore};
+use tracing::debug;
+
+// Keeps back-to-back dictations in the order they were recorded: each ticket
+// waits for the one before it to be released before inserting its transcript.
+// At most `capacity` sessions can be recording or transcribing at once.
+#[derive(Debug, Clone)]
+pub struct SessionQueue {
+    capacity: usize,
+    permits: Arc<Semaphore>,
+    tail: Arc<Mutex<Option<oneshot::Receiver<()>>>>,
+}
+
+impl SessionQueue {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            permits: Arc::new(Semaphore::new(capacity)),
+            tail: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn try_reserve(&self) -> Result<QueueTicket, String> {
+        let permit = Arc::clone(&self.permits).try_acquire_owned().map_err(|_| {
+            format!(
+                "Still processing {} earlier dictations; wait for one to finish",
+                self.capacity
+            )
+        })?;
+        let (done_tx, done_rx) = oneshot::channel();
+        let previous = self
+            .tail
+            .lock()
+            .map_err(|_| "Pipeline session queue is unavailable".to_string())?
+            .replace(done_rx);
+
+        debug!(in_flight = self.in_flight(), "reserved pipeline queue slot");
+        Ok(QueueTicket {
+            previous,
+            _done: done_tx,
+            _permit: permit,
+        })
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.capacity - self.permits.available_permits()
+    }
+}
+
+// Dropping the ticket frees its slot and lets the next session insert.
+#[derive(Debug)]
+pub struct QueueTicket {
+    previous: Option<oneshot::Receiver<()>>,
+    _done: oneshot::Sender<()>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl QueueTicket {
+    pub async fn wait_for_turn(&mut self) {
+        if let Some(previous) = self.previous.as_mut() {
+            debug!("waiting for earlier dictation to finish inserting");
+            // The sender is only ever dropped, so an error means "done".
+            let _ = previous.await;
+            self.previous = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::SessionQueue;
+
+    #[tokio::test]
+    async fn tickets_take_turns_in_reservation_order() {
+        let queue = SessionQueue::new(2);
+        let first = queue.try_reserve().expect("first slot should be free");
+        let mut second = queue.try_reserve().expect("second slot should be free");
+
+        assert!(queue.try_reserve().is_err());
+        assert!(
+            tokio::time::timeout(Duration::from_millis(20), second.wait_for_turn())
+                .await
+                .is_err()
+        );
+
+        drop(first);
+        tokio::time::timeout(Duration::from_millis(200), second.wait_for_turn())
+            .await
+            .expect("second ticket should get its turn once the first is released");
+        assert_eq!(queue.in_flight(), 1);
+        drop(second);
+        assert!(queue.try_reserve().is_ok());
+    }
+}
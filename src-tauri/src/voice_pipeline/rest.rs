@@ -0,0 +1,322 @@
+// The REST transcription path shared by the app's dictation pipeline and the
+// headless engine: everything from the recorded WAV to the history entry and
+// usage stats.
+use std::borrow::Cow;
+
+use tracing::{info, warn};
+
+use super::PipelineTranscript;
+use crate::{
+    app_error::{AppError, ErrorCode},
+    audio_capture_service::RecordedWav,
+    audio_encoding::{self, AudioEncoding},
+    auth_store::AuthMethod,
+    focused_app_watcher::FocusedApp,
+    history_store::{HistoryEntry, HistoryTranslation},
+    redaction::RedactionEngine,
+    resample,
+    settings_store::VoiceSettings,
+    silence_trim::{self, SilenceTrimConfig},
+    stats_store::StatsStore,
+    time_stretch,
+    transcription::{
+        chatgpt::ChatGptTranscriptionProvider,
+        chunked::{self, ChunkedTranscriptionConfig, Pcm16WavFile},
+        diarization::{self, DiarizationConfig},
+        prompt::{transcription_context_hint, transcription_prompt},
+        TranscriptionOptions, TranscriptionOrchestrator, TranscriptionProvider,
+        TranscriptionResult, TranscriptionSegment,
+    },
+    word_count::WordCountStrategy,
+};
+
+// The providers a REST request can go to, picked by the active credential.
+pub struct RestProviders<'a> {
+    pub api_key: &'a TranscriptionOrchestrator,
+    pub chatgpt: &'a ChatGptTranscriptionProvider,
+}
+
+#[derive(Debug)]
+pub struct RestTranscription {
+    // Timings are on the recording's timeline, before any trimming.
+    pub transcription: TranscriptionResult,
+    pub trimmed_silence_ms: u64,
+}
+
+pub fn rest_provider_name(auth_method: AuthMethod, transcription_provider: &str) -> &str {
+    match auth_method {
+        AuthMethod::ApiKey => transcription_provider,
+        AuthMethod::ChatgptOauth => "chatgpt-oauth",
+        AuthMethod::None => "none",
+    }
+}
+
+pub fn meeting_mode_duration_secs(settings: &VoiceSettings, wav: &RecordedWav) -> Option<f64> {
+    if !settings.meeting_mode {
+        return None;
+    }
+    wav.duration_secs()
+        .filter(|duration_secs| *duration_secs >= f64::from(settings.meeting_mode_threshold_secs))
+}
+
+// The prompt, context and decoding settings for a dictation; callers add
+// their own callbacks on top.
+pub fn transcription_options(
+    settings: &VoiceSettings,
+    language: Option<String>,
+    recent_text: Option<String>,
+) -> TranscriptionOptions {
+    TranscriptionOptions {
+        prompt: transcription_prompt(settings, language.as_deref()),
+        context_hint: transcription_context_hint(settings, language.as_deref(), recent_text),
+        language,
+        decoding: settings.decoding.clone(),
+        ..TranscriptionOptions::default()
+    }
+}
+
+pub async fn transcribe_with_rest(
+    settings: &VoiceSettings,
+    wav: RecordedWav,
+    auth_method: AuthMethod,
+    providers: RestProviders<'_>,
+    options: TranscriptionOptions,
+    meeting_mode_duration_secs: Option<f64>,
+) -> Result<RestTranscription, AppError> {
+    if auth_method == AuthMethod::None {
+        return Err(AppError::new(
+            ErrorCode::NoAuth,
+            "No authentication configured. Add an OpenAI API key or login with ChatGPT.",
+        ));
+    }
+    let provider_name = rest_provider_name(auth_method, &settings.transcription_provider);
+
+    // Spooled recordings are long enough that boundary silence hardly matters,
+    // so they stream from disk untrimmed.
+    let silence_trim_config = settings
+        .silence_trim_enabled
+        .then(|| SilenceTrimConfig::from_settings(settings));
+    let (wav, leading_trim_secs, trimmed_silence_ms) = match (wav, silence_trim_config) {
+        (RecordedWav::Memory(wav_bytes), Some(config)) => {
+            let trimmed = silence_trim::trim_wav_silence(wav_bytes, config);
+            let trimmed_silence_ms = trimmed.trimmed_ms();
+            (
+                RecordedWav::Memory(trimmed.wav_bytes),
+                trimmed.leading_ms as f64 / 1000.0,
+                trimmed_silence_ms,
+            )
+        }
+        (wav, _) => (wav, 0.0, 0),
+    };
+    // Resampling needs every sample in memory, so spooled recordings are
+    // loaded first; the smaller upload then goes out from memory.
+    let wav = match settings.target_sample_rate {
+        Some(target_sample_rate_hz) => {
+            let wav_bytes = wav.into_bytes()?;
+            RecordedWav::Memory(
+                resample::resample_wav_for_upload(&wav_bytes, target_sample_rate_hz)
+                    .unwrap_or(wav_bytes),
+            )
+        }
+        None => wav,
+    };
+
+    info!(
+        provider = %provider_name,
+        audio_bytes = wav.byte_len(),
+        spooled = matches!(wav, RecordedWav::Spooled(_)),
+        "starting REST transcription request"
+    );
+
+    let audio_encoding = AudioEncoding::from_settings_value(&settings.audio_encoding);
+    let transcription = if let Some(duration_secs) = meeting_mode_duration_secs {
+        let chunked_config = ChunkedTranscriptionConfig {
+            audio_encoding,
+            ..ChunkedTranscriptionConfig::from_env()
+        };
+        info!(
+            provider = %provider_name,
+            duration_secs,
+            chunk_secs = chunked_config.chunk_secs,
+            "using meeting mode chunked transcription"
+        );
+        // Spooled recordings are chunked by sample range from disk.
+        let chunked_transcription = match (&wav, auth_method) {
+            (RecordedWav::Memory(wav_bytes), AuthMethod::ApiKey) => {
+                providers
+                    .api_key
+                    .transcribe_chunked(wav_bytes, options, &chunked_config)
+                    .await
+            }
+            (RecordedWav::Spooled(spooled), AuthMethod::ApiKey) => {
+                providers
+                    .api_key
+                    .transcribe_chunked_file(spooled.path(), options, &chunked_config)
+                    .await
+            }
+            (RecordedWav::Memory(wav_bytes), AuthMethod::ChatgptOauth) => {
+                chunked::transcribe_chunked(providers.chatgpt, wav_bytes, options, &chunked_config)
+                    .await
+            }
+            (RecordedWav::Spooled(spooled), AuthMethod::ChatgptOauth) => {
+                chunked::transcribe_chunked_file(
+                    providers.chatgpt,
+                    spooled.path(),
+                    options,
+                    &chunked_config,
+                )
+                .await
+            }
+            (_, AuthMethod::None) => unreachable!("auth method none is handled above"),
+        };
+        chunked_transcription.map(|mut transcription| {
+            if settings.meeting_diarization {
+                diarize_meeting_segments(&wav, &mut transcription.segments);
+            }
+            transcription
+        })
+    } else {
+        match wav {
+            // Spooled recordings stream straight from disk unless they
+            // need re-encoding first.
+            RecordedWav::Spooled(spooled)
+                if audio_encoding == AudioEncoding::Wav && !settings.time_stretch_enabled =>
+            {
+                match auth_method {
+                    AuthMethod::ApiKey => {
+                        providers
+                            .api_key
+                            .transcribe_file(spooled.path(), options)
+                            .await
+                    }
+                    AuthMethod::ChatgptOauth => {
+                        providers
+                            .chatgpt
+                            .transcribe_file(spooled.path(), options)
+                            .await
+                    }
+                    AuthMethod::None => unreachable!("auth method none is handled above"),
+                }
+            }
+            wav => {
+                let mut wav_bytes = wav.into_bytes()?;
+                let mut stretch_factor = None;
+                if settings.time_stretch_enabled {
+                    if let Some(stretched) = time_stretch::stretch_wav_for_upload(
+                        &wav_bytes,
+                        settings.time_stretch_factor_percent,
+                    ) {
+                        wav_bytes = stretched.wav_bytes;
+                        stretch_factor = Some(stretched.factor);
+                    }
+                }
+                let upload_audio = audio_encoding::encode_wav_for_upload(wav_bytes, audio_encoding);
+                let transcription = match auth_method {
+                    AuthMethod::ApiKey => providers.api_key.transcribe(upload_audio, options).await,
+                    AuthMethod::ChatgptOauth => {
+                        providers.chatgpt.transcribe(upload_audio, options).await
+                    }
+                    AuthMethod::None => unreachable!("auth method none is handled above"),
+                };
+                transcription.map(|mut transcription| {
+                    if let Some(factor) = stretch_factor {
+                        time_stretch::restore_timeline(&mut transcription, factor);
+                    }
+                    transcription
+                })
+            }
+        }
+    };
+
+    let mut transcription = transcription?;
+    silence_trim::shift_timeline(&mut transcription, leading_trim_secs);
+    Ok(RestTranscription {
+        transcription,
+        trimmed_silence_ms,
+    })
+}
+
+fn diarize_meeting_segments(wav: &RecordedWav, segments: &mut [TranscriptionSegment]) {
+    if segments.is_empty() || diarization::has_speaker_labels(segments) {
+        return;
+    }
+
+    let config = DiarizationConfig::from_env();
+    match wav {
+        RecordedWav::Memory(wav_bytes) => match chunked::parse_pcm16_mono_wav(wav_bytes) {
+            Ok(wav) => {
+                diarization::assign_speakers(&wav.samples, wav.sample_rate_hz, segments, &config)
+            }
+            Err(error) => warn!(%error, "skipping diarization for unreadable meeting audio"),
+        },
+        // Only one segment's samples are read at a time.
+        RecordedWav::Spooled(spooled) => match Pcm16WavFile::open(spooled.path()) {
+            Ok(file) => diarization::assign_speakers_with(
+                file.sample_rate_hz(),
+                segments,
+                &config,
+                |start_sample, end_sample| {
+                    Cow::Owned(file.read_samples(start_sample, end_sample).unwrap_or_else(
+                        |error| {
+                            warn!(%error, "treating unreadable meeting audio as silence");
+                            Vec::new()
+                        },
+                    ))
+                },
+            ),
+            Err(error) => warn!(%error, "skipping diarization for unreadable meeting audio"),
+        },
+    }
+}
+
+// The transcript's detected language picks the strategy, falling back to the
+// language the user pinned in settings.
+pub fn count_words(settings: &VoiceSettings, text: &str, language: Option<&str>) -> u64 {
+    WordCountStrategy::from_settings(
+        &settings.word_count_mode,
+        language.or(settings.language.as_deref()),
+    )
+    .count(text)
+}
+
+// Redacted per the rules in effect; the warnings name rules that failed to
+// apply.
+pub fn history_entry(
+    transcript: &PipelineTranscript,
+    redaction: &RedactionEngine,
+) -> (HistoryEntry, Vec<String>) {
+    let entry = HistoryEntry::new(
+        transcript.text.clone(),
+        transcript.duration_secs,
+        transcript.language.clone(),
+        transcript.provider.clone(),
+    )
+    .with_segments(transcript.segments.clone())
+    .with_words(transcript.words.clone())
+    .with_confidence(transcript.confidence)
+    .with_translation(
+        transcript
+            .translation
+            .as_ref()
+            .map(|translation| HistoryTranslation {
+                language: translation.language.clone(),
+                text: translation.text.clone(),
+            }),
+    );
+    redaction.redact_history_entry(entry)
+}
+
+// Stats privacy mode keeps the dictation but not its word count.
+pub fn record_usage_stats(
+    stats_store: &StatsStore,
+    settings: &VoiceSettings,
+    transcript: &str,
+    language: Option<&str>,
+    recording_secs: f64,
+    trimmed_silence_ms: u64,
+    app: Option<&FocusedApp>,
+) -> Result<(), AppError> {
+    let word_count =
+        (!settings.stats_privacy_mode).then(|| count_words(settings, transcript, language));
+    stats_store.record_transcription(word_count, recording_secs, trimmed_silence_ms, app)
+}
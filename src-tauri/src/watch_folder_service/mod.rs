@@ -0,0 +1,259 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures_util::future::BoxFuture;
+use notify::{
+    event::{CreateKind, ModifyKind, RenameMode},
+    Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
+use tracing::{debug, info, warn};
+
+use crate::{audio_decoding::is_supported_audio_file, settings_store::WatchFolder};
+
+// A file counts as finished once its size has stopped changing for one
+// interval, so recorders and slow copies are not picked up half-written.
+const SETTLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub type WatchFolderHandler =
+    Arc<dyn Fn(PathBuf, WatchFolder) -> BoxFuture<'static, ()> + Send + Sync>;
+
+struct ActiveWatch {
+    folders: Vec<WatchFolder>,
+    _watcher: RecommendedWatcher,
+}
+
+// Watches the enabled folders (not their subfolders) for new audio files.
+#[derive(Default)]
+pub struct WatchFolderService {
+    active: Mutex<Option<ActiveWatch>>,
+    in_flight: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl WatchFolderService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Restarts the watcher only when the enabled folders actually changed.
+    pub fn sync(&self, folders: &[WatchFolder], handler: WatchFolderHandler) {
+        let folders: Vec<WatchFolder> = folders
+            .iter()
+            .filter(|folder| folder.enabled)
+            .cloned()
+            .collect();
+        let unchanged = self
+            .active
+            .lock()
+            .map(|active| {
+                active.as_ref().map(|active| &active.folders) == Some(&folders)
+                    || (active.is_none() && folders.is_empty())
+            })
+            .unwrap_or(false);
+        if unchanged {
+            return;
+        }
+
+        self.stop();
+        if !folders.is_empty() {
+            if let Err(error) = self.start(folders, handler) {
+                warn!(%error, "failed to start watch folder service");
+            }
+        }
+    }
+
+    pub fn start(
+        &self,
+        folders: Vec<WatchFolder>,
+        handler: WatchFolderHandler,
+    ) -> Result<(), String> {
+        let watched_folders = folders.clone();
+        let in_flight = Arc::clone(&self.in_flight);
+        let mut watcher =
+            notify::recommended_watcher(move |result: notify::Result<Event>| match result {
+                Ok(event) => {
+                    for path in new_audio_files(&event) {
+                        let Some(folder) = folder_for(&watched_folders, &path) else {
+                            continue;
+                        };
+                        queue_file(path, folder, &in_flight, handler.clone());
+                    }
+                }
+                Err(error) => warn!(%error, "watch folder event failed"),
+            })
+            .map_err(|error| format!("Failed to create folder watcher: {error}"))?;
+
+        for folder in &folders {
+            if let Err(error) = watcher.watch(Path::new(&folder.path), RecursiveMode::NonRecursive)
+            {
+                warn!(path = %folder.path, %error, "failed to watch folder");
+            }
+        }
+        info!(folder_count = folders.len(), "watch folder service started");
+        let mut active = self
+            .active
+            .lock()
+            .map_err(|_| "Watch folder lock poisoned".to_string())?;
+        *active = Some(ActiveWatch {
+            folders,
+            _watcher: watcher,
+        });
+        Ok(())
+    }
+
+    pub fn stop(&self) {
+        let Ok(mut active) = self.active.lock() else {
+            warn!("watch folder lock poisoned");
+            return;
+        };
+        if active.take().is_some() {
+            info!("watch folder service stopped");
+        }
+    }
+}
+
+// Files created in place or moved into the folder; hidden files are usually
+// partial downloads.
+fn new_audio_files(event: &Event) -> Vec<PathBuf> {
+    let arrived = matches!(
+        event.kind,
+        EventKind::Create(CreateKind::File | CreateKind::Any)
+            | EventKind::Modify(ModifyKind::Name(RenameMode::To | RenameMode::Any))
+    );
+    if !arrived {
+        return Vec::new();
+    }
+    event
+        .paths
+        .iter()
+        .filter(|path| {
+            let hidden = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_none_or(|name| name.starts_with('.'));
+            !hidden && is_supported_audio_file(path)
+        })
+        .cloned()
+        .collect()
+}
+
+fn folder_for(folders: &[WatchFolder], path: &Path) -> Option<WatchFolder> {
+    let parent = path.parent()?;
+    folders
+        .iter()
+        .find(|folder| Path::new(&folder.path) == parent)
+        .cloned()
+}
+
+// A sidecar transcript means the file was handled on an earlier run.
+pub fn transcript_path_for(audio_path: &Path) -> PathBuf {
+    audio_path.with_extension("txt")
+}
+
+fn queue_file(
+    path: PathBuf,
+    folder: WatchFolder,
+    in_flight: &Arc<Mutex<HashSet<PathBuf>>>,
+    handler: WatchFolderHandler,
+) {
+    if folder.write_text_file && transcript_path_for(&path).exists() {
+        debug!(path = %path.display(), "skipping watched file with an existing transcript");
+        return;
+    }
+    let Ok(mut queued) = in_flight.lock() else {
+        return;
+    };
+    if !queued.insert(path.clone()) {
+        return;
+    }
+    drop(queued);
+
+    let in_flight = Arc::clone(in_flight);
+    tauri::async_runtime::spawn(async move {
+        if wait_until_settled(&path).await {
+            info!(path = %path.display(), "transcribing file from watched folder");
+            handler(path.clone(), folder).await;
+        }
+        if let Ok(mut queued) = in_flight.lock() {
+            queued.remove(&path);
+        }
+    });
+}
+
+async fn wait_until_settled(path: &Path) -> bool {
+    let mut last_len = None;
+    loop {
+        tokio::time::sleep(SETTLE_POLL_INTERVAL).await;
+        let Ok(metadata) = fs::metadata(path) else {
+            debug!(path = %path.display(), "watched file disappeared before it settled");
+            return false;
+        };
+        let len = metadata.len();
+        if len > 0 && last_len == Some(len) {
+            return true;
+        }
+        last_len = Some(len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use notify::{
+        event::{CreateKind, DataChange, ModifyKind, RenameMode},
+        Event, EventKind,
+    };
+
+    use super::{folder_for, new_audio_files, transcript_path_for};
+    use crate::settings_store::WatchFolder;
+
+    fn event(kind: EventKind, paths: &[&str]) -> Event {
+        paths.iter().fold(Event::new(kind), |event, path| {
+            event.add_path(PathBuf::from(path))
+        })
+    }
+
+    #[test]
+    fn only_new_visible_audio_files_are_picked_up() {
+        assert_eq!(
+            new_audio_files(&event(
+                EventKind::Create(CreateKind::File),
+                &["/memos/a.m4a", "/memos/.b.mp3", "/memos/notes.txt"],
+            )),
+            vec![PathBuf::from("/memos/a.m4a")]
+        );
+        assert_eq!(
+            new_audio_files(&event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::To)),
+                &["/memos/c.wav"],
+            )),
+            vec![PathBuf::from("/memos/c.wav")]
+        );
+        assert!(new_audio_files(&event(
+            EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+            &["/memos/c.wav"],
+        ))
+        .is_empty());
+        assert_eq!(
+            transcript_path_for(Path::new("/memos/c.wav")),
+            PathBuf::from("/memos/c.txt")
+        );
+    }
+
+    #[test]
+    fn files_are_matched_to_their_direct_parent_folder() {
+        let folders = vec![WatchFolder {
+            path: "/memos".to_string(),
+            ..WatchFolder::default()
+        }];
+
+        assert!(folder_for(&folders, Path::new("/memos/a.wav")).is_some());
+        assert!(folder_for(&folders, Path::new("/memos/old/a.wav")).is_none());
+        assert!(folder_for(&folders, Path::new("/other/a.wav")).is_none());
+    }
+}
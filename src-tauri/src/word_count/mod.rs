@@ -0,0 +1,127 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+pub const WORD_COUNT_MODE_AUTO: &str = "auto";
+pub const WORD_COUNT_MODE_WHITESPACE: &str = "whitespace";
+
+// Languages written without spaces between words, where the usual unit of
+// length is the character rather than the word.
+const CHARACTER_COUNTED_LANGUAGES: &[&str] = &["zh", "ja", "yue"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordCountStrategy {
+    // The original behaviour, kept for users who want their old numbers.
+    Whitespace,
+    // UAX #29 word boundaries, with hyphenated compounds counted once.
+    Segmented,
+    // Each CJK character counts as one word; runs of other scripts are
+    // segmented as usual.
+    Characters,
+}
+
+impl WordCountStrategy {
+    pub fn from_settings(mode: &str, language: Option<&str>) -> Self {
+        if mode == WORD_COUNT_MODE_WHITESPACE {
+            return Self::Whitespace;
+        }
+        Self::for_language(language)
+    }
+
+    pub fn for_language(language: Option<&str>) -> Self {
+        let primary = language
+            .and_then(|language| language.split(['-', '_']).next())
+            .map(|language| language.trim().to_ascii_lowercase());
+        match primary {
+            Some(primary) if CHARACTER_COUNTED_LANGUAGES.contains(&primary.as_str()) => {
+                Self::Characters
+            }
+            _ => Self::Segmented,
+        }
+    }
+
+    pub fn count(self, text: &str) -> u64 {
+        match self {
+            Self::Whitespace => text.split_whitespace().count() as u64,
+            Self::Segmented => count_segmented(text, false),
+            Self::Characters => count_segmented(text, true),
+        }
+    }
+}
+
+fn count_segmented(text: &str, per_cjk_character: bool) -> u64 {
+    let mut count = 0;
+    let mut previous_was_word = false;
+    let mut joined_by_hyphen = false;
+    for segment in text.split_word_bounds() {
+        if is_hyphen(segment) {
+            joined_by_hyphen = previous_was_word;
+            previous_was_word = false;
+            continue;
+        }
+        if !segment.chars().any(char::is_alphanumeric) {
+            previous_was_word = false;
+            joined_by_hyphen = false;
+            continue;
+        }
+
+        let cjk_characters = if per_cjk_character {
+            segment
+                .chars()
+                .filter(|&character| is_cjk(character))
+                .count() as u64
+        } else {
+            0
+        };
+        let has_other = segment.chars().any(|character| {
+            character.is_alphanumeric() && !(per_cjk_character && is_cjk(character))
+        });
+        count += cjk_characters;
+        if has_other && !joined_by_hyphen {
+            count += 1;
+        }
+        previous_was_word = true;
+        joined_by_hyphen = false;
+    }
+    count
+}
+
+fn is_hyphen(segment: &str) -> bool {
+    matches!(segment, "-" | "\u{2010}" | "\u{2011}")
+}
+
+fn is_cjk(character: char) -> bool {
+    matches!(
+        character,
+        '\u{3040}'..='\u{30ff}'
+            | '\u{3400}'..='\u{4dbf}'
+            | '\u{4e00}'..='\u{9fff}'
+            | '\u{f900}'..='\u{faff}'
+            | '\u{ff66}'..='\u{ff9f}'
+            | '\u{20000}'..='\u{2ffff}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WordCountStrategy, WORD_COUNT_MODE_AUTO, WORD_COUNT_MODE_WHITESPACE};
+
+    #[test]
+    fn counts_by_language_aware_strategy() {
+        let segmented = WordCountStrategy::from_settings(WORD_COUNT_MODE_AUTO, Some("en"));
+        assert_eq!(segmented, WordCountStrategy::Segmented);
+        assert_eq!(segmented.count("A well-known  fact — really."), 4);
+        assert_eq!(segmented.count("Don't stop, it's 3.5 km"), 5);
+        assert_eq!(segmented.count(" - "), 0);
+
+        let characters = WordCountStrategy::from_settings(WORD_COUNT_MODE_AUTO, Some("zh-CN"));
+        assert_eq!(characters, WordCountStrategy::Characters);
+        assert_eq!(characters.count("我今天很忙"), 5);
+        assert_eq!(characters.count("今日はテスト OK"), 7);
+
+        let whitespace = WordCountStrategy::from_settings(WORD_COUNT_MODE_WHITESPACE, Some("zh"));
+        assert_eq!(whitespace.count("我今天很忙 — ok"), 3);
+        assert_eq!(
+            WordCountStrategy::from_settings(WORD_COUNT_MODE_AUTO, None),
+            WordCountStrategy::Segmented
+        );
+    }
+}
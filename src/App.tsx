@@ -27,6 +27,7 @@ import {
   TooltipTrigger,
 } from "@/components/ui/tooltip";
 import { cn } from "@/lib/utils";
+import { toErrorMessage, type ErrorCode } from "@/lib/appError";
 import { useDarkMode } from "@/hooks/use-dark-mode";
 import HistoryPanel from "./HistoryPanel";
 import Settings from "./Settings";
@@ -37,8 +38,32 @@ type AppView = "dashboard" | "history" | "settings";
 type OnboardingState = "loading" | "required" | "completed";
 type PermissionState = "not_determined" | "granted" | "denied";
 type PermissionType = "microphone" | "accessibility";
-type TranscriptReadyEvent = { text: string };
-type PipelineErrorEvent = { stage: string; message: string };
+type TranscriptWord = {
+  startSecs: number;
+  endSecs: number;
+  text: string;
+  confidence?: number | null;
+};
+type TranscriptReadyEvent = {
+  text: string;
+  confidence?: number | null;
+  words?: TranscriptWord[];
+};
+type PipelineErrorEvent = {
+  stage: string;
+  code: ErrorCode;
+  message: string;
+  recoverable: boolean;
+  hint: string | null;
+};
+type DeepLinkNavigation = { view: "history" | "settings"; historyId: string | null };
+type FileTranscriptionEvent = {
+  path: string;
+  state: "started" | "completed" | "failed";
+  entryId: string | null;
+  text: string | null;
+  error: string | null;
+};
 type PermissionSnapshot = {
   microphone: PermissionState;
   accessibility: PermissionState;
@@ -93,12 +118,6 @@ const PERMISSION_CARD_DISMISSED_KEY = "voice.permissionsOnboardingDismissed.v1";
 const INTEGER_FORMATTER = new Intl.NumberFormat();
 const DAY_LABEL_FORMATTER = new Intl.DateTimeFormat(undefined, { weekday: "short" });
 
-function toErrorMessage(error: unknown, fallbackMessage: string): string {
-  if (typeof error === "string" && error.trim()) return error;
-  if (error instanceof Error && error.message.trim()) return error.message;
-  return fallbackMessage;
-}
-
 function toIsoDateKey(date: Date): string {
   const year = date.getFullYear();
   const month = `${date.getMonth() + 1}`.padStart(2, "0");
@@ -299,12 +318,46 @@ function PermissionOnboardingCard({
 }
 
 /* ─── Dashboard View ────────────────────────────────── */
+const LOW_CONFIDENCE_WORD = 0.5;
+
+// Marks the words the provider was unsure of, walking the text in order so
+// punctuation the word list leaves out is kept.
+function highlightUncertainWords(text: string, words: TranscriptWord[]) {
+  const parts: React.ReactNode[] = [];
+  let cursor = 0;
+  for (const word of words) {
+    const needle = word.text.trim();
+    if (!needle) continue;
+    const start = text.indexOf(needle, cursor);
+    if (start === -1) continue;
+    const end = start + needle.length;
+    if (word.confidence != null && word.confidence < LOW_CONFIDENCE_WORD) {
+      parts.push(text.slice(cursor, start));
+      parts.push(
+        <mark
+          key={start}
+          className="rounded-sm bg-amber-500/20 px-0.5 text-foreground"
+          title={`${Math.round(word.confidence * 100)}% confident`}
+        >
+          {needle}
+        </mark>
+      );
+    } else {
+      parts.push(text.slice(cursor, end));
+    }
+    cursor = end;
+  }
+  parts.push(text.slice(cursor));
+  return parts;
+}
+
 type DashboardViewProps = {
   audioLevel: number;
   isRefreshingPermissions: boolean;
   isRefreshingUsageStats: boolean;
   isResettingUsageStats: boolean;
   lastTranscript: string;
+  lastTranscriptWords: TranscriptWord[];
   onDismissPermissions: () => void;
   onRefreshPermissions: () => void;
   onRefreshUsageStats: () => void;
@@ -326,6 +379,7 @@ function DashboardView({
   isRefreshingUsageStats,
   isResettingUsageStats,
   lastTranscript,
+  lastTranscriptWords,
   onDismissPermissions,
   onRefreshPermissions,
   onRefreshUsageStats,
@@ -427,7 +481,9 @@ function DashboardView({
               lastTranscript ? "text-foreground" : "text-muted-foreground italic"
             )}
           >
-            {lastTranscript || "No transcript captured yet."}
+            {lastTranscript
+              ? highlightUncertainWords(lastTranscript, lastTranscriptWords)
+              : "No transcript captured yet."}
           </p>
         </CardContent>
       </Card>
@@ -579,7 +635,9 @@ function App() {
   const [errorMessage, setErrorMessage] = useState("");
   const [audioLevel, setAudioLevel] = useState(0);
   const [lastTranscript, setLastTranscript] = useState("");
+  const [lastTranscriptWords, setLastTranscriptWords] = useState<TranscriptWord[]>([]);
   const [historyRefreshSignal, setHistoryRefreshSignal] = useState(0);
+  const [focusedHistoryId, setFocusedHistoryId] = useState<string | null>(null);
   const [backendSynced, setBackendSynced] = useState<boolean>(true);
   const [availableUpdate, setAvailableUpdate] = useState<Update | null>(null);
   const [isInstallingUpdate, setIsInstallingUpdate] = useState(false);
@@ -799,6 +857,7 @@ function App() {
           }),
           listen<TranscriptReadyEvent>("voice://transcript-ready", ({ payload }) => {
             setLastTranscript(payload.text ?? "");
+            setLastTranscriptWords(payload.words ?? []);
             if (activeViewRef.current === "history") {
               setHistoryRefreshSignal((current) => current + 1);
             }
@@ -807,10 +866,28 @@ function App() {
             }
           }),
           listen<PipelineErrorEvent>("voice://pipeline-error", ({ payload }) => {
-            setErrorMessage(payload.message || "An unexpected pipeline error occurred.");
+            const message = payload.message || "An unexpected pipeline error occurred.";
+            setErrorMessage(payload.hint ? `${message} ${payload.hint}` : message);
             statusRef.current = "error";
             setStatus("error");
           }),
+          listen<FileTranscriptionEvent>("voice://file-transcription", ({ payload }) => {
+            const fileName = payload.path.split("/").pop() || payload.path;
+            if (payload.state === "completed") {
+              setLastTranscript(payload.text ?? "");
+              setLastTranscriptWords([]);
+              setErrorMessage("");
+              if (activeViewRef.current === "history") {
+                setHistoryRefreshSignal((current) => current + 1);
+              }
+            } else if (payload.state === "failed") {
+              setErrorMessage(`Couldn't transcribe ${fileName}: ${payload.error ?? "unknown error"}`);
+            }
+          }),
+          listen<DeepLinkNavigation>("voice://deep-link-navigate", ({ payload }) => {
+            setActiveView(payload.view);
+            setFocusedHistoryId(payload.historyId);
+          }),
         ]);
 
         if (!isMounted) {
@@ -1005,6 +1082,7 @@ function App() {
                   isRefreshingUsageStats={isRefreshingUsageStats}
                   isResettingUsageStats={isResettingUsageStats}
                   lastTranscript={lastTranscript}
+                  lastTranscriptWords={lastTranscriptWords}
                   onDismissPermissions={dismissPermissionCard}
                   onRefreshPermissions={() => void refreshPermissions()}
                   onRefreshUsageStats={() => void refreshUsageStats()}
@@ -1021,7 +1099,10 @@ function App() {
                 />
               )}
               {activeView === "history" && (
-                <HistoryPanel refreshSignal={historyRefreshSignal} />
+                <HistoryPanel
+                  refreshSignal={historyRefreshSignal}
+                  focusEntryId={focusedHistoryId}
+                />
               )}
               {activeView === "settings" && (
                 <Settings />
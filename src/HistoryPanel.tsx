@@ -6,6 +6,8 @@ import { Button } from "@/components/ui/button";
 import { Badge } from "@/components/ui/badge";
 import { Alert, AlertDescription } from "@/components/ui/alert";
 import { ScrollArea } from "@/components/ui/scroll-area";
+import { cn } from "@/lib/utils";
+import { toErrorMessage } from "@/lib/appError";
 import {
   formatDuration,
   formatHistoryTimestamp,
@@ -19,17 +21,12 @@ const HISTORY_PAGE_SIZE = 25;
 type EntryAction = "copy" | "insert" | "delete";
 type ActiveEntryAction = { id: string; type: EntryAction } | null;
 
-function toErrorMessage(error: unknown, fallbackMessage: string): string {
-  if (typeof error === "string" && error.trim()) return error;
-  if (error instanceof Error && error.message.trim()) return error.message;
-  return fallbackMessage;
-}
-
 type HistoryPanelProps = {
   refreshSignal?: number;
+  focusEntryId?: string | null;
 };
 
-function HistoryPanel({ refreshSignal = 0 }: HistoryPanelProps) {
+function HistoryPanel({ refreshSignal = 0, focusEntryId = null }: HistoryPanelProps) {
   const [entries, setEntries] = useState<HistoryEntry[]>([]);
   const [offset, setOffset] = useState(0);
   const [hasMore, setHasMore] = useState(true);
@@ -80,6 +77,42 @@ function HistoryPanel({ refreshSignal = 0 }: HistoryPanelProps) {
     void refreshHistory();
   }, [refreshHistory, refreshSignal]);
 
+  // Deep links can point at an entry older than the loaded pages, so it is
+  // fetched on its own and pinned to the top.
+  useEffect(() => {
+    if (!focusEntryId) return;
+    let cancelled = false;
+
+    void (async () => {
+      try {
+        const entry = await invoke<HistoryEntry | null>("get_history_entry", { id: focusEntryId });
+        if (cancelled) return;
+        if (!entry) {
+          setActionError("That transcript is no longer in history.");
+          return;
+        }
+        setEntries((existingEntries) =>
+          existingEntries.some((existing) => existing.id === entry.id)
+            ? existingEntries
+            : [entry, ...existingEntries]
+        );
+        requestAnimationFrame(() => {
+          document
+            .getElementById(`history-entry-${entry.id}`)
+            ?.scrollIntoView({ block: "center", behavior: "smooth" });
+        });
+      } catch (error) {
+        if (!cancelled) {
+          setActionError(toErrorMessage(error, "Failed to open transcript."));
+        }
+      }
+    })();
+
+    return () => {
+      cancelled = true;
+    };
+  }, [focusEntryId]);
+
   const runEntryAction = useCallback(
     async (
       entryId: string,
@@ -240,7 +273,14 @@ function HistoryPanel({ refreshSignal = 0 }: HistoryPanelProps) {
             const entryActionsDisabled = entryActionActive || isClearingAll;
 
             return (
-              <Card key={entry.id} className="group transition-shadow hover:shadow-md">
+              <Card
+                key={entry.id}
+                id={`history-entry-${entry.id}`}
+                className={cn(
+                  "group transition-shadow hover:shadow-md",
+                  entry.id === focusEntryId && "ring-2 ring-primary"
+                )}
+              >
                 <CardContent className="space-y-2 py-3">
                   {/* Transcript text */}
                   <p className="line-clamp-3 text-sm leading-relaxed break-words">
@@ -261,6 +301,11 @@ function HistoryPanel({ refreshSignal = 0 }: HistoryPanelProps) {
                     <Badge variant="outline" className="text-[10px] px-1.5 py-0 font-normal tracking-wide">
                       {formatProvider(entry.provider)}
                     </Badge>
+                    {entry.source === "file" && (
+                      <Badge variant="outline" className="text-[10px] px-1.5 py-0 font-normal">
+                        File
+                      </Badge>
+                    )}
                   </div>
 
                   {/* Action buttons — show on hover */}
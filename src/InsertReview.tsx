@@ -0,0 +1,139 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { useEffect, useRef, useState, type KeyboardEvent } from "react";
+import { Button } from "@/components/ui/button";
+import { useDarkMode } from "@/hooks/use-dark-mode";
+
+type FocusedApp = {
+  bundleId: string | null;
+  name: string;
+};
+
+type PendingInsert = {
+  text: string;
+  originApp: FocusedApp | null;
+};
+
+type PendingInsertAction = "insert" | "copy" | "discard";
+
+const EVENT_INSERT_CONFIRMATION_REQUESTED = "voice://insert-confirmation-requested";
+const COMMAND_GET_PENDING_INSERT = "get_pending_insert";
+const COMMAND_RESOLVE_PENDING_INSERT = "resolve_pending_insert";
+
+function InsertReview() {
+  useDarkMode();
+  const [pending, setPending] = useState<PendingInsert | null>(null);
+  const [text, setText] = useState("");
+  const resolvingRef = useRef(false);
+  const textareaRef = useRef<HTMLTextAreaElement>(null);
+
+  useEffect(() => {
+    let isMounted = true;
+    let unlisten: UnlistenFn | null = null;
+
+    const applyPending = (next: PendingInsert | null) => {
+      setPending(next);
+      setText(next?.text ?? "");
+      resolvingRef.current = false;
+      window.setTimeout(() => textareaRef.current?.focus(), 0);
+    };
+
+    async function bindReviewEvents() {
+      try {
+        const initial = await invoke<PendingInsert | null>(COMMAND_GET_PENDING_INSERT);
+        if (isMounted) {
+          applyPending(initial);
+        }
+      } catch {
+        // The window stays empty until the next confirmation request.
+      }
+
+      try {
+        const dispose = await listen<PendingInsert>(
+          EVENT_INSERT_CONFIRMATION_REQUESTED,
+          ({ payload }) => applyPending(payload),
+        );
+        if (!isMounted) {
+          dispose();
+          return;
+        }
+        unlisten = dispose;
+      } catch {
+        // Without the listener the window only reflects the initial transcript.
+      }
+    }
+
+    void bindReviewEvents();
+
+    return () => {
+      isMounted = false;
+      unlisten?.();
+    };
+  }, []);
+
+  const resolve = (action: PendingInsertAction) => {
+    if (!pending || resolvingRef.current) {
+      return;
+    }
+
+    resolvingRef.current = true;
+    void invoke(COMMAND_RESOLVE_PENDING_INSERT, {
+      action,
+      text: action === "discard" ? null : text,
+    })
+      .then(() => setPending(null))
+      .finally(() => {
+        resolvingRef.current = false;
+      });
+  };
+
+  // Cmd+Enter inserts, Cmd+Shift+C copies and Escape discards; plain Cmd+C
+  // keeps copying the selection inside the editor.
+  const handleKeyDown = (event: KeyboardEvent) => {
+    const withModifier = event.metaKey || event.ctrlKey;
+    if (event.key === "Enter" && withModifier) {
+      event.preventDefault();
+      resolve("insert");
+    } else if (event.key.toLowerCase() === "c" && withModifier && event.shiftKey) {
+      event.preventDefault();
+      resolve("copy");
+    } else if (event.key === "Escape") {
+      event.preventDefault();
+      resolve("discard");
+    }
+  };
+
+  const target = pending?.originApp?.name;
+
+  return (
+    <main
+      className="flex h-screen flex-col gap-3 bg-background p-4 text-foreground"
+      onKeyDown={handleKeyDown}
+    >
+      <p className="text-xs text-muted-foreground">
+        {target ? `Review before inserting into ${target}` : "Review before inserting"}
+      </p>
+      <textarea
+        ref={textareaRef}
+        value={text}
+        onChange={(event) => setText(event.currentTarget.value)}
+        disabled={!pending}
+        spellCheck
+        className="min-h-0 flex-1 resize-none rounded-md border border-input bg-background px-3 py-2 text-sm text-foreground shadow-xs transition-colors focus-visible:outline-none focus-visible:ring-1 focus-visible:ring-ring"
+      />
+      <div className="flex items-center justify-end gap-2">
+        <Button variant="ghost" size="sm" disabled={!pending} onClick={() => resolve("discard")}>
+          Discard <kbd className="text-[10px] text-muted-foreground">Esc</kbd>
+        </Button>
+        <Button variant="outline" size="sm" disabled={!pending} onClick={() => resolve("copy")}>
+          Copy <kbd className="text-[10px] text-muted-foreground">⇧⌘C</kbd>
+        </Button>
+        <Button size="sm" disabled={!pending || !text.trim()} onClick={() => resolve("insert")}>
+          Insert <kbd className="text-[10px] opacity-70">⌘↩</kbd>
+        </Button>
+      </div>
+    </main>
+  );
+}
+
+export default InsertReview;
@@ -1,4 +1,4 @@
-import { useCallback, useEffect, useMemo, useState } from "react";
+import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { CheckCircle2, ChevronLeft, Circle, Loader2, Mic, Shield, Sparkles } from "lucide-react";
@@ -7,6 +7,7 @@ import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Alert, AlertDescription } from "@/components/ui/alert";
 import { cn } from "@/lib/utils";
+import { toErrorMessage, type ErrorCode } from "@/lib/appError";
 import {
   DEFAULT_HOTKEY_SHORTCUT,
   formatShortcutForDisplay,
@@ -26,6 +27,12 @@ import {
 } from "./onboardingUtils";
 
 type PermissionState = "not_determined" | "granted" | "denied";
+type PermissionType = "microphone" | "accessibility";
+type PermissionChangedEvent = {
+  permission: PermissionType;
+  previous: PermissionState;
+  state: PermissionState;
+};
 type PermissionSnapshot = {
   microphone: PermissionState;
   accessibility: PermissionState;
@@ -39,12 +46,22 @@ type VoiceSettings = {
   hotkey_shortcut: string;
   recording_mode: string;
 };
+type SettingsSnapshot = {
+  settings: VoiceSettings;
+  revision: number;
+};
 type VoiceSettingsUpdate = {
   hotkey_shortcut?: string;
   recording_mode?: RecordingMode;
 };
 type TranscriptReadyEvent = { text?: string };
-type PipelineErrorEvent = { stage: string; message: string };
+type PipelineErrorEvent = {
+  stage: string;
+  code: ErrorCode;
+  message: string;
+  recoverable: boolean;
+  hint: string | null;
+};
 
 type OnboardingProps = {
   onComplete: () => void;
@@ -70,12 +87,6 @@ const HOTKEY_PRESETS = [
 
 const CUSTOM_SHORTCUT_PRESET_VALUE = "__custom_shortcut__";
 
-function toErrorMessage(error: unknown, fallback: string): string {
-  if (typeof error === "string" && error.trim().length > 0) return error;
-  if (error instanceof Error && error.message.trim().length > 0) return error.message;
-  return fallback;
-}
-
 function splitShortcut(shortcut: string): string[] {
   return shortcut
     .split("+")
@@ -245,6 +256,8 @@ export default function Onboarding({ onComplete }: OnboardingProps) {
   const [isRecordingShortcut, setIsRecordingShortcut] = useState(false);
   const [errorMessage, setErrorMessage] = useState("");
   const [isLoadingInitialState, setIsLoadingInitialState] = useState(true);
+  // Sent back with the save so it cannot overwrite a change made elsewhere.
+  const settingsRevisionRef = useRef<number | null>(null);
   const [isRequestingMic, setIsRequestingMic] = useState(false);
   const [isOpeningAccessibilitySettings, setIsOpeningAccessibilitySettings] = useState(false);
   const [isStartingOauth, setIsStartingOauth] = useState(false);
@@ -311,12 +324,14 @@ export default function Onboarding({ onComplete }: OnboardingProps) {
   const loadInitialState = useCallback(async () => {
     setIsLoadingInitialState(true);
     try {
-      const [snapshot, apiKeyPresent, authStatus, settings] = await Promise.all([
+      const [snapshot, apiKeyPresent, authStatus, settingsSnapshot] = await Promise.all([
         invoke<PermissionSnapshot>("check_permissions"),
         invoke<boolean>("has_api_key", { provider: OPENAI_PROVIDER }),
         invoke<ChatGptAuthStatus | null>("get_auth_status"),
-        invoke<VoiceSettings>("get_settings"),
+        invoke<SettingsSnapshot>("get_settings_snapshot"),
       ]);
+      const { settings, revision } = settingsSnapshot;
+      settingsRevisionRef.current = revision;
 
       setPermissions(snapshot);
       setHasApiKey(apiKeyPresent);
@@ -338,10 +353,28 @@ export default function Onboarding({ onComplete }: OnboardingProps) {
   useEffect(() => {
     if (step !== 1 && step !== 2) return undefined;
     void refreshPermissionStatus();
-    const interval = window.setInterval(() => {
+
+    // The backend watches both permissions; granting the one this step asks
+    // for moves straight on to the next step.
+    const stepPermission: PermissionType = step === 1 ? "microphone" : "accessibility";
+    let isMounted = true;
+    let unlisten: UnlistenFn | undefined;
+    void listen<PermissionChangedEvent>("voice://permission-changed", ({ payload }) => {
       void refreshPermissionStatus();
-    }, 1200);
-    return () => window.clearInterval(interval);
+      if (payload.permission === stepPermission && payload.state === "granted") {
+        setStep((current) => (current === step ? step + 1 : current));
+      }
+    }).then((unlistenFn) => {
+      if (isMounted) {
+        unlisten = unlistenFn;
+      } else {
+        unlistenFn();
+      }
+    });
+    return () => {
+      isMounted = false;
+      unlisten?.();
+    };
   }, [refreshPermissionStatus, step]);
 
   useEffect(() => {
@@ -552,7 +585,11 @@ export default function Onboarding({ onComplete }: OnboardingProps) {
           hotkey_shortcut: hotkeyShortcut.trim() || DEFAULT_HOTKEY_SHORTCUT,
           recording_mode: recordingMode,
         } as VoiceSettingsUpdate,
+        expectedRevision: settingsRevisionRef.current,
       });
+      if (settingsRevisionRef.current !== null) {
+        settingsRevisionRef.current += 1;
+      }
 
       setHotkeyShortcut(updated.hotkey_shortcut || DEFAULT_HOTKEY_SHORTCUT);
       setRecordingMode(normalizeOnboardingRecordingMode(updated.recording_mode));
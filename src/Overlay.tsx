@@ -1,21 +1,36 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
-import { useEffect, useRef, useState } from "react";
-import { Square } from "lucide-react";
-import { formatElapsedLabel } from "./overlayUtils";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { useEffect, useRef, useState, type MouseEvent, type PointerEvent } from "react";
+import { X } from "lucide-react";
+import { formatElapsedLabel, formatOverlayStatusLabel, isOverlayDrag } from "./overlayUtils";
 import "./Overlay.css";
 
 type AppStatus = "idle" | "listening" | "transcribing" | "error";
 
+type RecordingStateChangedEvent = {
+  bindingName: string;
+  transition: "started" | "stopped";
+};
+
 const EVENT_STATUS_CHANGED = "voice://status-changed";
-const COMMAND_COMPLETE_RECORDING = "complete_recording";
+const EVENT_RECORDING_STATE_CHANGED = "voice://recording-state-changed";
+const COMMAND_STOP_RECORDING_FROM_OVERLAY = "stop_recording_from_overlay";
+const COMMAND_CANCEL_RECORDING_FROM_OVERLAY = "cancel_recording_from_overlay";
+const COMMAND_SAVE_OVERLAY_OFFSET = "save_overlay_offset";
+// Native drags report every intermediate move; the offset is saved once the
+// window has been still for this long.
+const DRAG_SETTLE_MS = 300;
 
 function Overlay() {
   const [status, setStatus] = useState<AppStatus>("idle");
   const [elapsedMs, setElapsedMs] = useState(0);
+  const [bindingName, setBindingName] = useState<string | null>(null);
   const statusRef = useRef<AppStatus>("idle");
   const startedAtRef = useRef<number | null>(null);
   const stopInFlightRef = useRef(false);
+  const pressStartRef = useRef<{ x: number; y: number } | null>(null);
+  const draggingRef = useRef(false);
 
   useEffect(() => {
     let isMounted = true;
@@ -49,6 +64,7 @@ function Overlay() {
 
       startedAtRef.current = null;
       setElapsedMs(0);
+      setBindingName(null);
     };
 
     async function bindOverlayEvents() {
@@ -69,6 +85,11 @@ function Overlay() {
           listen<AppStatus>(EVENT_STATUS_CHANGED, ({ payload }) => {
             applyStatus(payload);
           }),
+          // Names the binding that started the session, which stays the same
+          // through its transcribing phase.
+          listen<RecordingStateChangedEvent>(EVENT_RECORDING_STATE_CHANGED, ({ payload }) => {
+            setBindingName(payload.bindingName);
+          }),
         ]);
 
         if (!isMounted) {
@@ -90,6 +111,48 @@ function Overlay() {
     };
   }, []);
 
+  useEffect(() => {
+    let isMounted = true;
+    let unlisten: UnlistenFn | null = null;
+    let settleTimeout: number | null = null;
+
+    void getCurrentWindow()
+      .onMoved(() => {
+        if (!draggingRef.current) {
+          return;
+        }
+
+        if (settleTimeout !== null) {
+          window.clearTimeout(settleTimeout);
+        }
+        settleTimeout = window.setTimeout(() => {
+          settleTimeout = null;
+          draggingRef.current = false;
+          void invoke(COMMAND_SAVE_OVERLAY_OFFSET).catch(() => {
+            // The overlay returns to its previous spot next time it is shown.
+          });
+        }, DRAG_SETTLE_MS);
+      })
+      .then((dispose) => {
+        if (!isMounted) {
+          dispose();
+          return;
+        }
+        unlisten = dispose;
+      })
+      .catch(() => {
+        // Dragging still moves the overlay; it just is not remembered.
+      });
+
+    return () => {
+      isMounted = false;
+      if (settleTimeout !== null) {
+        window.clearTimeout(settleTimeout);
+      }
+      unlisten?.();
+    };
+  }, []);
+
   useEffect(() => {
     if (status !== "listening") {
       return;
@@ -111,7 +174,11 @@ function Overlay() {
   const isListening = status === "listening";
   const isTranscribing = status === "transcribing";
   const canStop = isListening;
-  const statusLabel = isListening ? "Listening..." : isTranscribing ? "Transcribing..." : "";
+  const canCancel = isListening || isTranscribing;
+  const statusLabel = formatOverlayStatusLabel(
+    isListening ? "Listening..." : isTranscribing ? "Transcribing..." : "",
+    bindingName,
+  );
 
   const handleStop = () => {
     if (!canStop || stopInFlightRef.current) {
@@ -119,31 +186,81 @@ function Overlay() {
     }
 
     stopInFlightRef.current = true;
-    void invoke(COMMAND_COMPLETE_RECORDING).finally(() => {
+    void invoke(COMMAND_STOP_RECORDING_FROM_OVERLAY).finally(() => {
       stopInFlightRef.current = false;
     });
   };
 
+  const handleCancel = (event: MouseEvent<HTMLButtonElement>) => {
+    event.stopPropagation();
+    if (!canCancel) {
+      return;
+    }
+
+    void invoke(COMMAND_CANCEL_RECORDING_FROM_OVERLAY);
+  };
+
+  // A press that moves past the dead zone hands off to a native window drag;
+  // one that does not is a click on the pill, which stops the recording.
+  const handlePointerDown = (event: PointerEvent<HTMLElement>) => {
+    if (event.button !== 0) {
+      return;
+    }
+
+    pressStartRef.current = { x: event.screenX, y: event.screenY };
+  };
+
+  const handlePointerMove = (event: PointerEvent<HTMLElement>) => {
+    const start = pressStartRef.current;
+    if (!start || (event.buttons & 1) === 0) {
+      return;
+    }
+
+    if (isOverlayDrag(start, { x: event.screenX, y: event.screenY })) {
+      pressStartRef.current = null;
+      draggingRef.current = true;
+      void getCurrentWindow()
+        .startDragging()
+        .catch(() => {
+          draggingRef.current = false;
+        });
+    }
+  };
+
+  const handlePillClick = () => {
+    if (pressStartRef.current === null) {
+      return;
+    }
+
+    pressStartRef.current = null;
+    handleStop();
+  };
+
   return (
     <main className="overlay-root">
       <section
         className={`overlay-pill ${isListening ? "active" : ""} ${
           isTranscribing ? "transcribing" : ""
         }`}
+        title={canStop ? "Click to stop, drag to move" : "Drag to move"}
+        onPointerDown={handlePointerDown}
+        onPointerMove={handlePointerMove}
+        onClick={handlePillClick}
       >
         <span className="recording-indicator" aria-hidden="true">
           <span className="recording-dot" />
         </span>
         <p className="overlay-transcript-text" aria-live="polite">{statusLabel}</p>
         <p className="overlay-elapsed">{isListening ? formatElapsedLabel(elapsedMs) : "..."}</p>
-        {canStop ? (
+        {canCancel ? (
           <button
             type="button"
-            className="overlay-stop-button"
-            onClick={handleStop}
-            aria-label="Stop recording"
+            className="overlay-cancel-button"
+            onPointerDown={(event) => event.stopPropagation()}
+            onClick={handleCancel}
+            aria-label="Cancel recording"
           >
-            <Square className="overlay-stop-icon" fill="currentColor" strokeWidth={0} aria-hidden="true" />
+            <X className="overlay-cancel-icon" strokeWidth={2.5} aria-hidden="true" />
           </button>
         ) : null}
       </section>
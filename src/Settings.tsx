@@ -16,6 +16,7 @@ import {
   SelectValue,
 } from "@/components/ui/select";
 import { cn } from "@/lib/utils";
+import { errorCode, toErrorMessage } from "@/lib/appError";
 
 import {
   createSettingsUpdatePayload,
@@ -35,10 +36,43 @@ type VoiceSettings = {
   language: string | null;
   transcription_style: string;
   custom_transcription_prompt: string;
+  transcription_models: Record<string, string>;
+  context_prompts: Record<string, string>;
+  formatting: Record<string, FormattingOptions>;
+  recent_text_context: boolean;
+  decoding: DecodingOptions;
   auto_insert: boolean;
+  low_confidence_review: boolean;
+  low_confidence_threshold_percent: number;
+  hallucination_filter: HallucinationFilter;
+  insertion_suffix: InsertionSuffix;
+  smart_spacing: boolean;
+  session_trace_enabled: boolean;
   launch_at_login: boolean;
 };
 
+type SettingsSnapshot = {
+  settings: VoiceSettings;
+  revision: number;
+};
+
+type HallucinationFilter = "discard" | "flag" | "off";
+
+type FormattingOptions = {
+  sentence_case: boolean;
+  numerals: boolean;
+  bullet_lists: boolean;
+  smart_quotes: boolean;
+};
+type InsertionSuffix = "none" | "space" | "newline";
+
+type DecodingOptions = {
+  temperature_percent: number | null;
+  no_speech_threshold_percent: number | null;
+  response_format: string | null;
+  beam_size: number | null;
+};
+
 type HotkeyConfig = {
   shortcut: string;
   mode: RecordingMode;
@@ -52,6 +86,19 @@ type MicrophoneInfo = {
   channels: number | null;
 };
 
+type ApiKeyValidation = {
+  provider: string;
+  valid: boolean | null;
+  organization: string | null;
+  allowedModels: string[];
+  message: string | null;
+};
+
+type TranscriptionModel = {
+  id: string;
+  realtime: boolean;
+};
+
 type SaveFeedback = {
   kind: "success" | "error";
   message: string;
@@ -75,18 +122,6 @@ type ChatGptAuthStatus = {
   expiresAt: number;
 };
 
-function toErrorMessage(error: unknown, fallback: string): string {
-  if (typeof error === "string") {
-    const trimmed = error.trim();
-    if (trimmed.length > 0) return trimmed;
-  }
-  if (error instanceof Error) {
-    const trimmed = error.message.trim();
-    if (trimmed.length > 0) return trimmed;
-  }
-  return fallback;
-}
-
 function formatMicrophoneLabel(device: MicrophoneInfo): string {
   const details: string[] = [];
   if (device.isDefault) details.push("Default");
@@ -96,6 +131,13 @@ function formatMicrophoneLabel(device: MicrophoneInfo): string {
   return `${device.name} (${details.join(", ")})`;
 }
 
+function parseOptionalNumber(value: string): number | null {
+  const trimmed = value.trim();
+  if (trimmed === "") return null;
+  const parsed = Number(trimmed);
+  return Number.isFinite(parsed) ? Math.round(parsed) : null;
+}
+
 function formatAuthMethodLabel(method: AuthMethod): string {
   if (method === "api_key") return "API Key";
   if (method === "chatgpt_oauth") return "ChatGPT OAuth";
@@ -110,6 +152,57 @@ const HOTKEY_PRESETS = [
 ] as const;
 
 const CUSTOM_SHORTCUT_PRESET_VALUE = "__custom_shortcut__";
+const DEFAULT_MODEL_VALUE = "__default_model__";
+const DEFAULT_RESPONSE_FORMAT_VALUE = "__default_response_format__";
+const EMPTY_DECODING_OPTIONS: DecodingOptions = {
+  temperature_percent: null,
+  no_speech_threshold_percent: null,
+  response_format: null,
+  beam_size: null,
+};
+
+const EMPTY_FORMATTING_OPTIONS: FormattingOptions = {
+  sentence_case: false,
+  numerals: false,
+  bullet_lists: false,
+  smart_quotes: false,
+};
+
+const FORMATTING_OPTIONS: ReadonlyArray<{
+  field: keyof FormattingOptions;
+  label: string;
+}> = [
+  { field: "sentence_case", label: "Capitalize sentences" },
+  { field: "numerals", label: "Numbers as digits" },
+  { field: "bullet_lists", label: "\"Dash\" starts a list item" },
+  { field: "smart_quotes", label: "Curly quotes" },
+];
+
+const HALLUCINATION_FILTER_OPTIONS: ReadonlyArray<{
+  value: HallucinationFilter;
+  label: string;
+}> = [
+  { value: "discard", label: "Discard" },
+  { value: "flag", label: "Ask First" },
+  { value: "off", label: "Off" },
+];
+
+const INSERTION_SUFFIX_OPTIONS: ReadonlyArray<{
+  value: InsertionSuffix;
+  label: string;
+}> = [
+  { value: "none", label: "Nothing" },
+  { value: "space", label: "Space" },
+  { value: "newline", label: "New Line" },
+];
+
+const RESPONSE_FORMAT_OPTIONS: ReadonlyArray<{
+  value: string;
+  label: string;
+}> = [
+  { value: "verbose_json", label: "Verbose JSON (timestamps)" },
+  { value: "json", label: "JSON (text only)" },
+];
 
 const RECORDING_MODE_OPTIONS: ReadonlyArray<{
   value: RecordingMode;
@@ -136,12 +229,15 @@ export default function Settings() {
   const [isStartingChatgptLogin, setIsStartingChatgptLogin] = useState(false);
   const [isLoggingOutChatgpt, setIsLoggingOutChatgpt] = useState(false);
   const [isRefreshingMics, setIsRefreshingMics] = useState(false);
+  const [isRefreshingModels, setIsRefreshingModels] = useState(false);
   const [isExportingLogs, setIsExportingLogs] = useState(false);
   const [feedback, setFeedback] = useState<SaveFeedback | null>(null);
   const [isSavingSettings, setIsSavingSettings] = useState(false);
   const initialLoadDone = useRef(false);
   const saveTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const skipNextDebouncedSaveRef = useRef(false);
+  // The revision `settings` were loaded at, sent with every save.
+  const settingsRevisionRef = useRef<number | null>(null);
 
   const [hotkeyShortcut, setHotkeyShortcut] = useState("");
   const [recordingMode, setRecordingMode] = useState<RecordingMode>("toggle");
@@ -152,6 +248,18 @@ export default function Settings() {
   const [customTranscriptionPrompt, setCustomTranscriptionPrompt] = useState("");
   const [autoInsert, setAutoInsert] = useState(true);
   const [launchAtLogin, setLaunchAtLogin] = useState(false);
+  const [transcriptionModels, setTranscriptionModels] = useState<Record<string, string>>({});
+  const [contextPrompts, setContextPrompts] = useState<Record<string, string>>({});
+  const [formatting, setFormatting] = useState<Record<string, FormattingOptions>>({});
+  const [recentTextContext, setRecentTextContext] = useState(false);
+  const [lowConfidenceReview, setLowConfidenceReview] = useState(false);
+  const [lowConfidenceThreshold, setLowConfidenceThreshold] = useState(50);
+  const [hallucinationFilter, setHallucinationFilter] = useState<HallucinationFilter>("discard");
+  const [insertionSuffix, setInsertionSuffix] = useState<InsertionSuffix>("none");
+  const [smartSpacing, setSmartSpacing] = useState(true);
+  const [sessionTraceEnabled, setSessionTraceEnabled] = useState(false);
+  const [decoding, setDecoding] = useState<DecodingOptions>(EMPTY_DECODING_OPTIONS);
+  const [availableModels, setAvailableModels] = useState<TranscriptionModel[]>([]);
   const [isRecordingShortcut, setIsRecordingShortcut] = useState(false);
 
   const [hasStoredApiKey, setHasStoredApiKey] = useState(false);
@@ -178,23 +286,49 @@ export default function Settings() {
     }
   }, []);
 
+  const loadTranscriptionModels = useCallback(async (showErrorFeedback: boolean) => {
+    try {
+      const models = await invoke<TranscriptionModel[]>("list_transcription_models", {
+        provider: OPENAI_PROVIDER,
+      });
+      setAvailableModels(models);
+    } catch (error) {
+      if (showErrorFeedback) {
+        setFeedback({ kind: "error", message: toErrorMessage(error, "Unable to load models.") });
+      }
+    }
+  }, []);
+
   const loadSettings = useCallback(async () => {
     setIsLoading(true);
     try {
-      const [settings, hotkeyConfig, hasOpenAiKey, authMethod, chatgptStatus] = await Promise.all([
-        invoke<VoiceSettings>("get_settings"),
-        invoke<HotkeyConfig>("get_hotkey_config"),
-        invoke<boolean>("has_api_key", { provider: OPENAI_PROVIDER }),
-        invoke<AuthMethod>("get_auth_method"),
-        invoke<ChatGptAuthStatus | null>("get_chatgpt_auth_status"),
-      ]);
-
+      const [{ settings, revision }, hotkeyConfig, hasOpenAiKey, authMethod, chatgptStatus] =
+        await Promise.all([
+          invoke<SettingsSnapshot>("get_settings_snapshot"),
+          invoke<HotkeyConfig>("get_hotkey_config"),
+          invoke<boolean>("has_api_key", { provider: OPENAI_PROVIDER }),
+          invoke<AuthMethod>("get_auth_method"),
+          invoke<ChatGptAuthStatus | null>("get_chatgpt_auth_status"),
+        ]);
+
+      settingsRevisionRef.current = revision;
       setHotkeyShortcut(hotkeyConfig.shortcut || settings.hotkey_shortcut);
       setRecordingMode(normalizeRecordingMode(hotkeyConfig.mode || settings.recording_mode));
       setMicrophoneId(settings.microphone_id ?? "");
       setLanguage(settings.language ?? "");
       setTranscriptionStyle(normalizeTranscriptionStyle(settings.transcription_style));
       setCustomTranscriptionPrompt(settings.custom_transcription_prompt ?? "");
+      setTranscriptionModels(settings.transcription_models ?? {});
+      setContextPrompts(settings.context_prompts ?? {});
+      setFormatting(settings.formatting ?? {});
+      setRecentTextContext(settings.recent_text_context);
+      setLowConfidenceReview(settings.low_confidence_review);
+      setLowConfidenceThreshold(settings.low_confidence_threshold_percent);
+      setHallucinationFilter(settings.hallucination_filter);
+      setInsertionSuffix(settings.insertion_suffix);
+      setSmartSpacing(settings.smart_spacing);
+      setSessionTraceEnabled(settings.session_trace_enabled);
+      setDecoding(settings.decoding ?? EMPTY_DECODING_OPTIONS);
       setAutoInsert(settings.auto_insert);
       setLaunchAtLogin(settings.launch_at_login);
       setHasStoredApiKey(hasOpenAiKey);
@@ -211,22 +345,54 @@ export default function Settings() {
       }
 
       await loadMicrophones(false);
+      if (hasOpenAiKey) {
+        await loadTranscriptionModels(false);
+      }
       initialLoadDone.current = true;
     } catch (error) {
       setFeedback({ kind: "error", message: toErrorMessage(error, "Unable to load settings.") });
     } finally {
       setIsLoading(false);
     }
-  }, [loadMicrophones]);
+  }, [loadMicrophones, loadTranscriptionModels]);
 
   useEffect(() => {
     void loadSettings();
   }, [loadSettings]);
 
+  // Saves are refused when settings changed elsewhere since they were loaded,
+  // e.g. from the tray, and the window reloads rather than overwrite them. A
+  // save that goes through moves the revision on by exactly one.
+  const saveSettings = useCallback(
+    async (update: Record<string, unknown>) => {
+      try {
+        const updatedSettings = await invoke<VoiceSettings>("apply_settings", {
+          update,
+          expectedRevision: settingsRevisionRef.current,
+        });
+        if (settingsRevisionRef.current !== null) {
+          settingsRevisionRef.current += 1;
+        }
+        return updatedSettings;
+      } catch (error) {
+        if (errorCode(error) === "conflict") {
+          void loadSettings();
+        }
+        throw error;
+      }
+    },
+    [loadSettings],
+  );
+
   const selectedMicrophoneExists = useMemo(
     () => microphoneId === "" || microphones.some((device) => device.id === microphoneId),
     [microphoneId, microphones]
   );
+  const selectedModelId = transcriptionModels[OPENAI_PROVIDER] ?? "";
+  const selectedModelExists = useMemo(
+    () => selectedModelId === "" || availableModels.some((model) => model.id === selectedModelId),
+    [availableModels, selectedModelId]
+  );
   const selectedShortcutPreset = useMemo(() => {
     const normalized = hotkeyShortcut.trim().toLowerCase();
     const matchingPreset = HOTKEY_PRESETS.find(
@@ -262,8 +428,8 @@ export default function Settings() {
   const applySettingsUpdate = useCallback(async (draft: SettingsDraft) => {
     setIsSavingSettings(true);
     try {
-      const updatedSettings = await invoke<VoiceSettings>("apply_settings", {
-        update: createSettingsUpdatePayload({
+      const updatedSettings = await saveSettings(
+        createSettingsUpdatePayload({
           hotkeyShortcut: normalizeShortcut(draft.hotkeyShortcut),
           recordingMode: draft.recordingMode,
           microphoneId: draft.microphoneId,
@@ -273,7 +439,7 @@ export default function Settings() {
           autoInsert: draft.autoInsert,
           launchAtLogin: draft.launchAtLogin,
         }),
-      });
+      );
       setHotkeyShortcut(updatedSettings.hotkey_shortcut);
       setRecordingMode(normalizeRecordingMode(updatedSettings.recording_mode));
       setMicrophoneId(updatedSettings.microphone_id ?? "");
@@ -288,7 +454,7 @@ export default function Settings() {
     } finally {
       setIsSavingSettings(false);
     }
-  }, []);
+  }, [saveSettings]);
 
   const handleMicrophoneChange = useCallback((value: string) => {
     const nextMicrophoneId = value === "__default__" ? "" : value;
@@ -404,6 +570,170 @@ export default function Settings() {
     setIsRefreshingMics(false);
   }
 
+  async function handleRefreshModels() {
+    setIsRefreshingModels(true);
+    await loadTranscriptionModels(true);
+    setIsRefreshingModels(false);
+  }
+
+  // Saved on its own so the picks for other providers are kept as they are.
+  async function handleTranscriptionModelChange(value: string) {
+    const nextModels = { ...transcriptionModels };
+    if (value === DEFAULT_MODEL_VALUE) {
+      delete nextModels[OPENAI_PROVIDER];
+    } else {
+      nextModels[OPENAI_PROVIDER] = value;
+    }
+    setTranscriptionModels(nextModels);
+
+    setIsSavingSettings(true);
+    try {
+      const updatedSettings = await saveSettings({ transcription_models: nextModels });
+      setTranscriptionModels(updatedSettings.transcription_models);
+      setFeedback({ kind: "success", message: "Transcription model saved." });
+    } catch (error) {
+      setFeedback({ kind: "error", message: toErrorMessage(error, "Unable to save transcription model.") });
+    } finally {
+      setIsSavingSettings(false);
+    }
+  }
+
+  // Context settings are saved on their own, outside the debounced draft.
+  async function applyContextUpdate(
+    update: Partial<Pick<VoiceSettings, "context_prompts" | "recent_text_context">>,
+  ) {
+    setIsSavingSettings(true);
+    try {
+      const updatedSettings = await saveSettings(update);
+      setContextPrompts(updatedSettings.context_prompts);
+      setRecentTextContext(updatedSettings.recent_text_context);
+      setFeedback({ kind: "success", message: "Settings saved." });
+    } catch (error) {
+      setFeedback({ kind: "error", message: toErrorMessage(error, "Unable to save settings.") });
+    } finally {
+      setIsSavingSettings(false);
+    }
+  }
+
+  function handleContextPromptBlur() {
+    void applyContextUpdate({ context_prompts: contextPrompts });
+  }
+
+  function handleRecentTextContextChange(enabled: boolean) {
+    setRecentTextContext(enabled);
+    void applyContextUpdate({ recent_text_context: enabled });
+  }
+
+  // Behavior settings are saved on their own, outside the debounced draft.
+  async function applyBehaviorUpdate(
+    update: Partial<
+      Pick<
+        VoiceSettings,
+        | "low_confidence_review"
+        | "hallucination_filter"
+        | "insertion_suffix"
+        | "smart_spacing"
+        | "session_trace_enabled"
+      >
+    >,
+  ) {
+    setIsSavingSettings(true);
+    try {
+      const updatedSettings = await saveSettings(update);
+      setLowConfidenceReview(updatedSettings.low_confidence_review);
+      setLowConfidenceThreshold(updatedSettings.low_confidence_threshold_percent);
+      setHallucinationFilter(updatedSettings.hallucination_filter);
+      setInsertionSuffix(updatedSettings.insertion_suffix);
+      setSmartSpacing(updatedSettings.smart_spacing);
+      setSessionTraceEnabled(updatedSettings.session_trace_enabled);
+      setFeedback({ kind: "success", message: "Settings saved." });
+    } catch (error) {
+      setFeedback({ kind: "error", message: toErrorMessage(error, "Unable to save settings.") });
+    } finally {
+      setIsSavingSettings(false);
+    }
+  }
+
+  function handleLowConfidenceReviewChange(enabled: boolean) {
+    setLowConfidenceReview(enabled);
+    void applyBehaviorUpdate({ low_confidence_review: enabled });
+  }
+
+  function handleHallucinationFilterChange(value: string) {
+    const nextFilter = value as HallucinationFilter;
+    setHallucinationFilter(nextFilter);
+    void applyBehaviorUpdate({ hallucination_filter: nextFilter });
+  }
+
+  function handleInsertionSuffixChange(value: string) {
+    const nextSuffix = value as InsertionSuffix;
+    setInsertionSuffix(nextSuffix);
+    void applyBehaviorUpdate({ insertion_suffix: nextSuffix });
+  }
+
+  function handleSmartSpacingChange(enabled: boolean) {
+    setSmartSpacing(enabled);
+    void applyBehaviorUpdate({ smart_spacing: enabled });
+  }
+
+  function handleSessionTraceChange(enabled: boolean) {
+    setSessionTraceEnabled(enabled);
+    void applyBehaviorUpdate({ session_trace_enabled: enabled });
+  }
+
+  // Formatting is saved as a whole for every profile, outside the debounced
+  // draft.
+  async function handleFormattingChange(field: keyof FormattingOptions, enabled: boolean) {
+    const nextFormatting = {
+      ...formatting,
+      [transcriptionStyle]: {
+        ...(formatting[transcriptionStyle] ?? EMPTY_FORMATTING_OPTIONS),
+        [field]: enabled,
+      },
+    };
+    setFormatting(nextFormatting);
+    setIsSavingSettings(true);
+    try {
+      const updatedSettings = await saveSettings({ formatting: nextFormatting });
+      setFormatting(updatedSettings.formatting);
+      setFeedback({ kind: "success", message: "Formatting saved." });
+    } catch (error) {
+      setFeedback({ kind: "error", message: toErrorMessage(error, "Unable to save formatting.") });
+    } finally {
+      setIsSavingSettings(false);
+    }
+  }
+
+  // Decoding options are saved as a whole, outside the debounced draft.
+  async function applyDecodingUpdate(nextDecoding: DecodingOptions) {
+    setIsSavingSettings(true);
+    try {
+      const updatedSettings = await saveSettings({ decoding: nextDecoding });
+      setDecoding(updatedSettings.decoding);
+      setFeedback({ kind: "success", message: "Decoding options saved." });
+    } catch (error) {
+      setFeedback({ kind: "error", message: toErrorMessage(error, "Unable to save decoding options.") });
+    } finally {
+      setIsSavingSettings(false);
+    }
+  }
+
+  function handleDecodingNumberChange(
+    field: "temperature_percent" | "no_speech_threshold_percent" | "beam_size",
+    value: string,
+  ) {
+    setDecoding((current) => ({ ...current, [field]: parseOptionalNumber(value) }));
+  }
+
+  function handleResponseFormatChange(value: string) {
+    const nextDecoding = {
+      ...decoding,
+      response_format: value === DEFAULT_RESPONSE_FORMAT_VALUE ? null : value,
+    };
+    setDecoding(nextDecoding);
+    void applyDecodingUpdate(nextDecoding);
+  }
+
   async function handleSelectAuthMethod(nextMethod: "api_key" | "chatgpt_oauth") {
     setSelectedAuthMethod(nextMethod);
     setIsSavingAuthMethod(true);
@@ -455,13 +785,18 @@ export default function Settings() {
 
     setIsSavingApiKey(true);
     try {
-      await invoke("set_api_key", { provider: OPENAI_PROVIDER, key });
+      const validation = await invoke<ApiKeyValidation | null>("set_api_key", {
+        provider: OPENAI_PROVIDER,
+        key,
+        validate: true,
+      });
       setHasStoredApiKey(true);
       setActiveAuthMethod("api_key");
       setSelectedAuthMethod("api_key");
       setApiKeyDraft("");
       setIsApiKeyDraftVisible(false);
-      setFeedback({ kind: "success", message: "OpenAI API key saved." });
+      setFeedback(apiKeySavedFeedback(validation));
+      void loadTranscriptionModels(false);
     } catch (error) {
       setFeedback({ kind: "error", message: toErrorMessage(error, "Unable to save API key.") });
     } finally {
@@ -469,6 +804,25 @@ export default function Settings() {
     }
   }
 
+  function apiKeySavedFeedback(validation: ApiKeyValidation | null): SaveFeedback {
+    if (validation?.valid === false) {
+      return {
+        kind: "error",
+        message: `OpenAI API key saved, but OpenAI rejected it: ${validation.message ?? "invalid key"}`,
+      };
+    }
+    if (validation?.valid === true && validation.organization) {
+      return {
+        kind: "success",
+        message: `OpenAI API key saved and verified for ${validation.organization}.`,
+      };
+    }
+    if (validation?.valid === true) {
+      return { kind: "success", message: "OpenAI API key saved and verified." };
+    }
+    return { kind: "success", message: "OpenAI API key saved." };
+  }
+
   async function handleClearApiKey() {
     if (!hasStoredApiKey) {
       setApiKeyDraft("");
@@ -480,6 +834,7 @@ export default function Settings() {
     try {
       await invoke("delete_api_key", { provider: OPENAI_PROVIDER });
       setHasStoredApiKey(false);
+      setAvailableModels([]);
       if (activeAuthMethod === "api_key") {
         setActiveAuthMethod("none");
       }
@@ -720,6 +1075,51 @@ export default function Settings() {
             </p>
           </div>
 
+          {/* Context Prompt */}
+          <div className="space-y-1.5">
+            <Label htmlFor="context-prompt" className="text-xs">
+              Context
+            </Label>
+            <textarea
+              id="context-prompt"
+              value={contextPrompts[transcriptionStyle] ?? ""}
+              onChange={(event) => {
+                const value = event.currentTarget.value;
+                setContextPrompts((current) => ({ ...current, [transcriptionStyle]: value }));
+              }}
+              onBlur={handleContextPromptBlur}
+              placeholder="Names, jargon, or topics you often mention..."
+              spellCheck={false}
+              rows={2}
+              className="min-h-[56px] w-full rounded-md border border-input bg-background px-3 py-2 text-xs text-foreground shadow-xs transition-colors placeholder:text-muted-foreground focus-visible:outline-none focus-visible:ring-1 focus-visible:ring-ring"
+            />
+            <p className="text-[11px] text-muted-foreground">
+              Sent with each dictation in this style so those words come out spelled right.
+            </p>
+          </div>
+
+          {/* Formatting */}
+          <div className="space-y-1.5">
+            <Label className="text-xs">Formatting</Label>
+            <div className="grid grid-cols-2 gap-2">
+              {FORMATTING_OPTIONS.map((option) => (
+                <div key={option.field} className="flex items-center justify-between gap-2">
+                  <Label htmlFor={`formatting-${option.field}`} className="text-[11px] font-normal">
+                    {option.label}
+                  </Label>
+                  <Switch
+                    id={`formatting-${option.field}`}
+                    checked={formatting[transcriptionStyle]?.[option.field] ?? false}
+                    onCheckedChange={(enabled) => void handleFormattingChange(option.field, enabled)}
+                  />
+                </div>
+              ))}
+            </div>
+            <p className="text-[11px] text-muted-foreground">
+              Applied to every transcript in this style, without sending it anywhere.
+            </p>
+          </div>
+
           {transcriptionStyle === "custom" && (
             <div className="space-y-1.5">
               <Label htmlFor="custom-transcription-prompt" className="text-xs">
@@ -764,6 +1164,128 @@ export default function Settings() {
 
           <Separator />
 
+          <div className="flex items-center justify-between">
+            <div className="space-y-0.5">
+              <Label htmlFor="insertion-suffix" className="text-xs font-medium">
+                After Inserting
+              </Label>
+              <p className="text-[11px] text-muted-foreground">
+                Typed after each transcript
+              </p>
+            </div>
+            <Select value={insertionSuffix} onValueChange={handleInsertionSuffixChange}>
+              <SelectTrigger id="insertion-suffix" className="h-8 w-32 text-xs">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {INSERTION_SUFFIX_OPTIONS.map((option) => (
+                  <SelectItem key={option.value} value={option.value}>
+                    {option.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+
+          <Separator />
+
+          <div className="flex items-center justify-between">
+            <div className="space-y-0.5">
+              <Label htmlFor="smart-spacing" className="text-xs font-medium">
+                Smart Spacing
+              </Label>
+              <p className="text-[11px] text-muted-foreground">
+                Skip the space when you dictate punctuation next
+              </p>
+            </div>
+            <Switch
+              id="smart-spacing"
+              checked={smartSpacing}
+              onCheckedChange={handleSmartSpacingChange}
+            />
+          </div>
+
+          <Separator />
+
+          <div className="flex items-center justify-between">
+            <div className="space-y-0.5">
+              <Label htmlFor="low-confidence-review" className="text-xs font-medium">
+                Review Unsure Transcripts
+              </Label>
+              <p className="text-[11px] text-muted-foreground">
+                Ask before inserting below {lowConfidenceThreshold}% confidence
+              </p>
+            </div>
+            <Switch
+              id="low-confidence-review"
+              checked={lowConfidenceReview}
+              onCheckedChange={handleLowConfidenceReviewChange}
+            />
+          </div>
+
+          <Separator />
+
+          <div className="flex items-center justify-between">
+            <div className="space-y-0.5">
+              <Label htmlFor="hallucination-filter" className="text-xs font-medium">
+                Silent Recordings
+              </Label>
+              <p className="text-[11px] text-muted-foreground">
+                Text made up from silence or background noise
+              </p>
+            </div>
+            <Select value={hallucinationFilter} onValueChange={handleHallucinationFilterChange}>
+              <SelectTrigger id="hallucination-filter" className="h-8 w-32 text-xs">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {HALLUCINATION_FILTER_OPTIONS.map((option) => (
+                  <SelectItem key={option.value} value={option.value}>
+                    {option.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+
+          <Separator />
+
+          <div className="flex items-center justify-between">
+            <div className="space-y-0.5">
+              <Label htmlFor="recent-text-context" className="text-xs font-medium">
+                Use Recent Text as Context
+              </Label>
+              <p className="text-[11px] text-muted-foreground">
+                Send the last words dictated into the same app
+              </p>
+            </div>
+            <Switch
+              id="recent-text-context"
+              checked={recentTextContext}
+              onCheckedChange={handleRecentTextContextChange}
+            />
+          </div>
+
+          <Separator />
+
+          <div className="flex items-center justify-between">
+            <div className="space-y-0.5">
+              <Label htmlFor="session-trace" className="text-xs font-medium">
+                Record Debug Traces
+              </Label>
+              <p className="text-[11px] text-muted-foreground">
+                Keep a timeline of each dictation, including its text, for bug reports
+              </p>
+            </div>
+            <Switch
+              id="session-trace"
+              checked={sessionTraceEnabled}
+              onCheckedChange={handleSessionTraceChange}
+            />
+          </div>
+
+          <Separator />
+
           <div className="flex items-center justify-between">
             <div className="space-y-0.5">
               <Label htmlFor="launch-login" className="text-xs font-medium">
@@ -782,6 +1304,102 @@ export default function Settings() {
         </CardContent>
       </Card>
 
+      {/* ── Decoding ── */}
+      <Card>
+        <CardContent className="space-y-4 py-4">
+          <p className="text-[11px] font-semibold uppercase tracking-wider text-muted-foreground">
+            Decoding
+          </p>
+
+          <div className="grid grid-cols-2 gap-3">
+            <div className="space-y-1.5">
+              <Label htmlFor="decoding-temperature" className="text-xs">
+                Temperature (%)
+              </Label>
+              <Input
+                id="decoding-temperature"
+                type="number"
+                min={0}
+                max={100}
+                value={decoding.temperature_percent ?? ""}
+                onChange={(event) =>
+                  handleDecodingNumberChange("temperature_percent", event.currentTarget.value)
+                }
+                onBlur={() => void applyDecodingUpdate(decoding)}
+                placeholder="Provider default"
+                className="h-8 text-xs"
+              />
+            </div>
+
+            <div className="space-y-1.5">
+              <Label htmlFor="decoding-no-speech" className="text-xs">
+                No-Speech Threshold (%)
+              </Label>
+              <Input
+                id="decoding-no-speech"
+                type="number"
+                min={0}
+                max={100}
+                value={decoding.no_speech_threshold_percent ?? ""}
+                onChange={(event) =>
+                  handleDecodingNumberChange("no_speech_threshold_percent", event.currentTarget.value)
+                }
+                onBlur={() => void applyDecodingUpdate(decoding)}
+                placeholder="Off"
+                className="h-8 text-xs"
+              />
+            </div>
+
+            <div className="space-y-1.5">
+              <Label htmlFor="decoding-response-format" className="text-xs">
+                Response Format
+              </Label>
+              <Select
+                value={decoding.response_format ?? DEFAULT_RESPONSE_FORMAT_VALUE}
+                onValueChange={handleResponseFormatChange}
+              >
+                <SelectTrigger id="decoding-response-format" className="h-8 text-xs">
+                  <SelectValue placeholder="Default" />
+                </SelectTrigger>
+                <SelectContent>
+                  <SelectItem value={DEFAULT_RESPONSE_FORMAT_VALUE}>Default</SelectItem>
+                  {RESPONSE_FORMAT_OPTIONS.map((option) => (
+                    <SelectItem key={option.value} value={option.value}>
+                      {option.label}
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+            </div>
+
+            <div className="space-y-1.5">
+              <Label htmlFor="decoding-beam-size" className="text-xs">
+                Beam Size
+              </Label>
+              <Input
+                id="decoding-beam-size"
+                type="number"
+                min={1}
+                max={16}
+                value={decoding.beam_size ?? ""}
+                onChange={(event) =>
+                  handleDecodingNumberChange("beam_size", event.currentTarget.value)
+                }
+                onBlur={() => void applyDecodingUpdate(decoding)}
+                placeholder="Provider default"
+                className="h-8 text-xs"
+              />
+            </div>
+          </div>
+
+          <p className="text-[11px] text-muted-foreground">
+            Lower temperatures stay closer to the audio. The no-speech threshold drops
+            stretches the provider thinks were silence, and needs the verbose format. Beam
+            size only applies to local models.
+          </p>
+        </CardContent>
+      </Card>
+
       {/* ── Authentication ── */}
       <Card>
         <CardContent className="space-y-3 py-4">
@@ -878,6 +1496,47 @@ export default function Settings() {
               <p className="text-[11px] text-muted-foreground">
                 {hasStoredApiKey ? "✓ API key set." : "No API key configured."}
               </p>
+
+              {hasStoredApiKey && (
+                <div className="space-y-1.5">
+                  <Label className="text-xs">Transcription Model</Label>
+                  <div className="flex gap-2">
+                    <Select
+                      value={selectedModelId || DEFAULT_MODEL_VALUE}
+                      onValueChange={(value) => void handleTranscriptionModelChange(value)}
+                    >
+                      <SelectTrigger className="h-8 flex-1 text-xs">
+                        <SelectValue placeholder="Default" />
+                      </SelectTrigger>
+                      <SelectContent>
+                        <SelectItem value={DEFAULT_MODEL_VALUE}>Default</SelectItem>
+                        {availableModels.map((model) => (
+                          <SelectItem key={model.id} value={model.id}>
+                            {model.realtime ? `${model.id} (Realtime)` : model.id}
+                          </SelectItem>
+                        ))}
+                        {!selectedModelExists && (
+                          <SelectItem value={selectedModelId}>
+                            Previously selected ({selectedModelId})
+                          </SelectItem>
+                        )}
+                      </SelectContent>
+                    </Select>
+                    <Button
+                      type="button"
+                      variant="outline"
+                      size="icon-sm"
+                      onClick={handleRefreshModels}
+                      disabled={isRefreshingModels}
+                    >
+                      <RefreshCw className={cn("size-3.5", isRefreshingModels && "animate-spin")} />
+                    </Button>
+                  </div>
+                  <p className="text-[11px] text-muted-foreground">
+                    Realtime models show text while you speak; the others transcribe once you stop.
+                  </p>
+                </div>
+              )}
             </>
           )}
 
@@ -5,6 +5,7 @@ export type HistoryEntry = {
   durationSecs?: number | null;
   language?: string | null;
   provider: string;
+  source?: "dictation" | "file";
 };
 
 const MINUTE_SECONDS = 60;
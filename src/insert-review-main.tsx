@@ -0,0 +1,5 @@
+import ReactDOM from "react-dom/client";
+import InsertReview from "./InsertReview";
+import "./index.css";
+
+ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(<InsertReview />);
@@ -0,0 +1,24 @@
+import { describe, expect, it } from "vitest";
+
+import { errorCode, toErrorMessage } from "./appError";
+
+describe("toErrorMessage", () => {
+  it("reads structured command errors", () => {
+    const error = {
+      code: "no_auth",
+      message: "Missing transcription provider API key",
+      recoverable: false,
+      hint: "Add an API key or sign in with ChatGPT in Settings.",
+    };
+
+    expect(toErrorMessage(error, "fallback")).toBe("Missing transcription provider API key");
+    expect(errorCode(error)).toBe("no_auth");
+  });
+
+  it("falls back for empty or unknown errors", () => {
+    expect(toErrorMessage(" boom ", "fallback")).toBe("boom");
+    expect(toErrorMessage(new Error(""), "fallback")).toBe("fallback");
+    expect(toErrorMessage({ reason: "nope" }, "fallback")).toBe("fallback");
+    expect(errorCode("network down")).toBeNull();
+  });
+});
@@ -0,0 +1,46 @@
+// Mirrors `AppError` in src-tauri/schemas/AppError.schema.json.
+export type ErrorCode =
+  | "no_auth"
+  | "permission_denied"
+  | "network"
+  | "quota"
+  | "rate_limited"
+  | "timeout"
+  | "not_found"
+  | "invalid_input"
+  | "conflict"
+  | "cancelled"
+  | "provider"
+  | "internal";
+
+export type AppError = {
+  code: ErrorCode;
+  message: string;
+  recoverable: boolean;
+  hint: string | null;
+};
+
+export function isAppError(error: unknown): error is AppError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as AppError).code === "string" &&
+    typeof (error as AppError).message === "string"
+  );
+}
+
+export function errorCode(error: unknown): ErrorCode | null {
+  return isAppError(error) ? error.code : null;
+}
+
+export function toErrorMessage(error: unknown, fallback: string): string {
+  const message = isAppError(error)
+    ? error.message
+    : error instanceof Error
+      ? error.message
+      : typeof error === "string"
+        ? error
+        : "";
+  const trimmed = message.trim();
+  return trimmed.length > 0 ? trimmed : fallback;
+}
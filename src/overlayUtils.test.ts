@@ -1,6 +1,12 @@
 import { describe, expect, it } from "vitest";
 
-import { clampAudioLevel, formatElapsedLabel, pushAudioLevelHistory } from "./overlayUtils";
+import {
+  clampAudioLevel,
+  formatElapsedLabel,
+  formatOverlayStatusLabel,
+  isOverlayDrag,
+  pushAudioLevelHistory,
+} from "./overlayUtils";
 
 describe("clampAudioLevel", () => {
   it("bounds levels to 0..1", () => {
@@ -46,3 +52,19 @@ describe("formatElapsedLabel", () => {
     expect(formatElapsedLabel(9 * 60_000 + 7_999)).toBe("09:07");
   });
 });
+
+describe("formatOverlayStatusLabel", () => {
+  it("prefixes the status with the session's binding", () => {
+    expect(formatOverlayStatusLabel("Listening...", "Meetings")).toBe("Meetings · Listening...");
+    expect(formatOverlayStatusLabel("Transcribing...", null)).toBe("Transcribing...");
+    expect(formatOverlayStatusLabel("Listening...", "  ")).toBe("Listening...");
+    expect(formatOverlayStatusLabel("", "Meetings")).toBe("");
+  });
+});
+
+describe("isOverlayDrag", () => {
+  it("treats small pointer movement as a click", () => {
+    expect(isOverlayDrag({ x: 10, y: 10 }, { x: 13, y: 12 })).toBe(false);
+    expect(isOverlayDrag({ x: 10, y: 10 }, { x: 10, y: 16 })).toBe(true);
+  });
+});
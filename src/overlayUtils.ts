@@ -35,3 +35,26 @@ export function formatElapsedLabel(elapsedMs: number): string {
 
   return `${minutes.toString().padStart(2, "0")}:${seconds.toString().padStart(2, "0")}`;
 }
+
+// Prefixes the status with the binding that started the session, e.g.
+// "Meetings · Listening...".
+export function formatOverlayStatusLabel(statusLabel: string, bindingName: string | null): string {
+  const name = bindingName?.trim();
+  if (!statusLabel || !name) {
+    return statusLabel;
+  }
+
+  return `${name} · ${statusLabel}`;
+}
+
+export const OVERLAY_DRAG_THRESHOLD_PX = 4;
+
+// A press only becomes a drag once the pointer leaves a small dead zone, so a
+// slightly shaky click still counts as a click.
+export function isOverlayDrag(
+  start: { x: number; y: number },
+  current: { x: number; y: number },
+  threshold = OVERLAY_DRAG_THRESHOLD_PX,
+): boolean {
+  return Math.hypot(current.x - start.x, current.y - start.y) > threshold;
+}
@@ -19,6 +19,7 @@ export default defineConfig(async () => ({
       input: {
         main: path.resolve(__dirname, "index.html"),
         overlay: path.resolve(__dirname, "overlay.html"),
+        insertReview: path.resolve(__dirname, "insert-review.html"),
       },
     },
   },